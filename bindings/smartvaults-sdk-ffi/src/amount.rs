@@ -28,10 +28,19 @@ impl Amount {
         }
     }
 
+    /// Send all the spendable funds (minus the fee)
     #[uniffi::constructor]
     pub fn max() -> Self {
         Self {
             inner: core::Amount::Max,
         }
     }
+
+    /// Send all the funds, keeping `reserve` SAT in the vault
+    #[uniffi::constructor]
+    pub fn max_with_reserve(reserve: u64) -> Self {
+        Self {
+            inner: core::Amount::MaxWithReserve(reserve),
+        }
+    }
 }
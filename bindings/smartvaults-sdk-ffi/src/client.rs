@@ -4,30 +4,40 @@
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_utility::thread;
-use nostr_ffi::{EventId, Keys, Metadata, NostrConnectURI, PublicKey};
+use nostr_ffi::{EventId, Keys, Metadata, NostrConnectURI, PublicKey, Timestamp};
 use nostr_sdk_ffi::profile::Profile;
 use nostr_sdk_ffi::Relay;
 use smartvaults_sdk::client;
 use smartvaults_sdk::core::bips::bip39::Mnemonic;
+use smartvaults_sdk::core::bitcoin::bip32::Fingerprint;
 use smartvaults_sdk::core::bitcoin::psbt::PartiallySignedTransaction;
 use smartvaults_sdk::core::bitcoin::{Address, Txid};
 use smartvaults_sdk::core::miniscript::Descriptor;
 use smartvaults_sdk::core::types::{FeeRate, Priority};
-use smartvaults_sdk::nostr::block_on;
+use smartvaults_sdk::nostr::{block_on, Url};
+use smartvaults_sdk::protocol::v1::SignerRequest;
+use smartvaults_sdk::types;
 use uniffi::Object;
 
 use crate::error::Result;
 use crate::{
-    AbortHandle, AddressIndex, Amount, Balance, CompletedProposal, Config, GetAddress, GetApproval,
-    GetCompletedProposal, GetPolicy, GetProposal, GetSharedSigner, GetSigner, GetTransaction,
-    KeyAgent, Message, Network, NostrConnectRequest, NostrConnectSession, OutPoint, Period,
-    PolicyTemplate, Seed, Signer, SignerOffering, Utxo, WordCount,
+    AbortHandle, AddressIndex, Amount, AppSettings, Balance, BulkApprovalResult, CompletedProposal,
+    CompromisedKey, Config, ConsistencyReport, DeviceRegistration, DiagnosticsReport, EventsImport,
+    GetAddress, GetApproval, GetCompletedProposal, GetDevice, GetPaymentRequest, GetPolicy,
+    GetProposal, GetProposalComment, GetQuorumStatus, GetRecurringPayment, GetSharedSigner,
+    GetSigner, GetSignerRequest, GetSignerUsage, GetTransaction, GetVaultSummary, KeyAgent,
+    MempoolSnapshot, Message, Network, NetworkCondition, NostrConnectRequest, NostrConnectSession,
+    OutPoint, Payee, PaymentMemo, Period, PolicyTemplate, RecoveryPath, RecoveryWatch,
+    RelayAdmission, RelayTerms, Seed, Signer, SignerOffering, SpendPreview, SpendRecipient,
+    SyncSchedule, TxOptions, Utxo, VelocityLimit, VelocityLimitStatus, Whitelist, WhitelistMode,
+    WordCount,
 };
 
 #[derive(Object)]
@@ -160,6 +170,21 @@ impl SmartVaults {
         )?)
     }
 
+    /// Check if an approval PIN is set on this device
+    pub fn has_approval_pin(&self) -> bool {
+        self.inner.has_approval_pin()
+    }
+
+    /// Set (or change) the device-local approval PIN
+    pub fn set_approval_pin(&self, password: String, pin: String) -> Result<()> {
+        Ok(self.inner.set_approval_pin(password, pin)?)
+    }
+
+    /// Remove the approval PIN
+    pub fn remove_approval_pin(&self, password: String) -> Result<()> {
+        Ok(self.inner.remove_approval_pin(password)?)
+    }
+
     /// Permanent delete the keychain
     pub fn wipe(&self, password: String) -> Result<()> {
         Ok(self.inner.wipe(password)?)
@@ -182,6 +207,47 @@ impl SmartVaults {
         block_on(async move { Ok(self.inner.force_full_timechain_sync().await?) })
     }
 
+    /// Sync the vaults now, also if the background sync is paused
+    pub fn sync_now(&self) -> Result<()> {
+        block_on(async move { Ok(self.inner.sync_now().await?) })
+    }
+
+    pub fn sync_schedule(&self) -> SyncSchedule {
+        block_on(async move { self.inner.sync_schedule().await.into() })
+    }
+
+    pub fn set_sync_schedule(&self, schedule: SyncSchedule) {
+        block_on(async move { self.inner.set_sync_schedule(schedule.into()).await })
+    }
+
+    /// Update the platform hints (network condition and battery saver) used by the background sync
+    pub fn set_platform_hints(&self, network: NetworkCondition, battery_saver: bool) {
+        block_on(async move {
+            self.inner
+                .set_platform_hints(network.into(), battery_saver)
+                .await
+        })
+    }
+
+    pub fn is_sync_paused(&self) -> bool {
+        block_on(async move { self.inner.is_sync_paused().await })
+    }
+
+    /// Reconcile the in-memory storage with the nostr database and report the inconsistencies
+    pub fn check_storage_consistency(&self) -> Result<ConsistencyReport> {
+        block_on(async move { Ok(self.inner.check_storage_consistency().await?.into()) })
+    }
+
+    /// Check the connectivity of the relays and of the timechain backends
+    pub fn run_diagnostics(&self) -> Result<DiagnosticsReport> {
+        block_on(async move { Ok(self.inner.run_diagnostics().await?.into()) })
+    }
+
+    /// Rebuild wallet, proposals and labels of a single vault from the local events
+    pub fn reset_vault_cache(&self, vault_id: Arc<EventId>) -> Result<()> {
+        block_on(async move { Ok(self.inner.reset_vault_cache(**vault_id).await?) })
+    }
+
     /// Delete all data from cache/database
     pub fn clear_cache(&self) -> Result<()> {
         block_on(async move { Ok(self.inner.clear_cache().await?) })
@@ -229,6 +295,42 @@ impl SmartVaults {
         })
     }
 
+    /// Get the terms of a relay (payment or admission requirements)
+    pub fn get_relay_terms(&self, url: String) -> Result<RelayTerms> {
+        block_on(async move {
+            let url = Url::parse(&url)?;
+            Ok(self.inner.get_relay_terms(url).await?.into())
+        })
+    }
+
+    /// Get the admission status of the relays (only the checked ones)
+    pub fn get_relays_admission(&self) -> Vec<RelayAdmission> {
+        block_on(async move {
+            self.inner
+                .get_relays_admission()
+                .await
+                .into_iter()
+                .map(|a| a.into())
+                .collect()
+        })
+    }
+
+    pub fn check_relay_admission(&self, url: String) -> Result<RelayAdmission> {
+        block_on(async move {
+            let url = Url::parse(&url)?;
+            let admission = self.inner.check_relay_admission(url.clone()).await?;
+            Ok((url, admission).into())
+        })
+    }
+
+    /// Mark a relay as admitted (i.e. after paying the admission fee)
+    pub fn mark_relay_admitted(&self, url: String) -> Result<()> {
+        block_on(async move {
+            let url = Url::parse(&url)?;
+            Ok(self.inner.mark_relay_admitted(url).await?)
+        })
+    }
+
     /// Shutdown client
     pub fn shutdown(&self) -> Result<()> {
         block_on(async move { Ok(self.inner.clone().shutdown().await?) })
@@ -280,6 +382,50 @@ impl SmartVaults {
         block_on(async move { Ok(self.inner.add_contact(**public_key).await?) })
     }
 
+    /// Import the contact list (kind 3) of another Nostr identity
+    ///
+    /// Return the number of new contacts.
+    pub fn import_contacts_from_public_key(&self, public_key: Arc<PublicKey>) -> Result<u64> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .import_contacts_from_public_key(**public_key)
+                .await? as u64)
+        })
+    }
+
+    /// Import the contacts exported by another Nostr client
+    ///
+    /// Return the number of new contacts.
+    pub fn import_contacts_from_file(&self, path: String) -> Result<u64> {
+        block_on(async move { Ok(self.inner.import_contacts_from_file(path).await? as u64) })
+    }
+
+    /// Import the events exported by another member or dumped from a relay (JSON array or an
+    /// event per line), i.e. to bootstrap a new device when the relays have pruned the old events
+    pub fn import_events_from_file(&self, path: String) -> Result<EventsImport> {
+        block_on(async move { Ok(self.inner.import_events_from_file(path).await?.into()) })
+    }
+
+    /// Export the events of a vault (an event per line), i.e. to archive them independently of
+    /// the relays retention
+    ///
+    /// If `decrypted` is set, the decrypted content of the events is written to it too (JSON).
+    /// Return the number of exported events.
+    pub fn export_vault_events(
+        &self,
+        policy_id: Arc<EventId>,
+        path: String,
+        decrypted: Option<String>,
+    ) -> Result<u64> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .export_vault_events(**policy_id, path, decrypted)
+                .await? as u64)
+        })
+    }
+
     /// Remove contact
     pub fn remove_contact(&self, public_key: Arc<PublicKey>) -> Result<()> {
         block_on(async move { Ok(self.inner.remove_contact(**public_key).await?) })
@@ -343,8 +489,18 @@ impl SmartVaults {
         })
     }
 
-    pub fn delete_signer_by_id(&self, signer_id: Arc<EventId>) -> Result<()> {
-        block_on(async move { Ok(self.inner.delete_signer_by_id(**signer_id).await?) })
+    /// Get the vaults that reference the signer keys and the approvals made with it
+    pub fn get_signer_usage(&self, signer_id: Arc<EventId>) -> Result<Arc<GetSignerUsage>> {
+        block_on(async move {
+            Ok(Arc::new(
+                self.inner.get_signer_usage(**signer_id).await?.into(),
+            ))
+        })
+    }
+
+    /// Delete signer (refused if used by a vault, unless `force` is `true`)
+    pub fn delete_signer_by_id(&self, signer_id: Arc<EventId>, force: bool) -> Result<()> {
+        block_on(async move { Ok(self.inner.delete_signer_by_id(**signer_id, force).await?) })
     }
 
     pub fn get_policies(&self) -> Result<Vec<Arc<GetPolicy>>> {
@@ -354,6 +510,152 @@ impl SmartVaults {
         })
     }
 
+    pub fn get_vault_summaries(&self) -> Result<Vec<Arc<GetVaultSummary>>> {
+        block_on(async move {
+            let summaries = self.inner.get_vault_summaries().await?;
+            Ok(summaries.into_iter().map(|s| Arc::new(s.into())).collect())
+        })
+    }
+
+    pub fn pin_vault(&self, policy_id: Arc<EventId>) -> Result<()> {
+        block_on(async move { Ok(self.inner.pin_vault(**policy_id).await?) })
+    }
+
+    pub fn unpin_vault(&self, policy_id: Arc<EventId>) -> Result<()> {
+        block_on(async move { Ok(self.inner.unpin_vault(**policy_id).await?) })
+    }
+
+    pub fn get_vault_relays(&self, policy_id: Arc<EventId>) -> Result<Vec<String>> {
+        block_on(async move {
+            let relays = self.inner.get_vault_relays(**policy_id).await?;
+            Ok(relays.into_iter().map(|url| url.to_string()).collect())
+        })
+    }
+
+    pub fn set_vault_relays(
+        &self,
+        policy_id: Arc<EventId>,
+        relays: Vec<String>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self.inner.set_vault_relays(**policy_id, relays).await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    pub fn get_vault_velocity_limits(&self, policy_id: Arc<EventId>) -> Result<Vec<VelocityLimit>> {
+        block_on(async move {
+            let limits = self.inner.get_vault_velocity_limits(**policy_id).await?;
+            Ok(limits.into_iter().map(|l| l.into()).collect())
+        })
+    }
+
+    pub fn set_vault_velocity_limits(
+        &self,
+        policy_id: Arc<EventId>,
+        limits: Vec<VelocityLimit>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let limits = limits.into_iter().map(|l| l.into()).collect();
+            let event_id = self
+                .inner
+                .set_vault_velocity_limits(**policy_id, limits)
+                .await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    /// Get the velocity limits of the vault with the amount spent in the current period
+    pub fn get_velocity_limits_status(
+        &self,
+        policy_id: Arc<EventId>,
+    ) -> Result<Vec<VelocityLimitStatus>> {
+        block_on(async move {
+            let list = self.inner.get_velocity_limits_status(**policy_id).await?;
+            Ok(list.into_iter().map(|s| s.into()).collect())
+        })
+    }
+
+    /// Get the velocity limits exceeded by a pending proposal
+    pub fn get_proposal_exceeded_velocity_limits(
+        &self,
+        proposal_id: Arc<EventId>,
+    ) -> Result<Vec<VelocityLimitStatus>> {
+        block_on(async move {
+            let list = self
+                .inner
+                .get_proposal_exceeded_velocity_limits(**proposal_id)
+                .await?;
+            Ok(list.into_iter().map(|s| s.into()).collect())
+        })
+    }
+
+    pub fn get_vault_whitelist(&self, policy_id: Arc<EventId>) -> Result<Whitelist> {
+        block_on(async move { Ok(self.inner.get_vault_whitelist(**policy_id).await?.into()) })
+    }
+
+    pub fn set_vault_whitelist_mode(
+        &self,
+        policy_id: Arc<EventId>,
+        mode: WhitelistMode,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self
+                .inner
+                .set_vault_whitelist_mode(**policy_id, mode.into())
+                .await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    /// Add an address or a descriptor to the whitelist of the vault (updating the label if
+    /// already whitelisted)
+    pub fn add_whitelisted_destination(
+        &self,
+        policy_id: Arc<EventId>,
+        destination: String,
+        label: Option<String>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self
+                .inner
+                .add_whitelisted_destination(**policy_id, destination, label)
+                .await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    pub fn remove_whitelisted_destination(
+        &self,
+        policy_id: Arc<EventId>,
+        destination: String,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self
+                .inner
+                .remove_whitelisted_destination(**policy_id, &destination)
+                .await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    /// Get the recipients of a pending proposal not in the whitelist of the vault
+    pub fn get_proposal_not_whitelisted_recipients(
+        &self,
+        proposal_id: Arc<EventId>,
+    ) -> Result<Vec<String>> {
+        block_on(async move {
+            let list = self
+                .inner
+                .get_proposal_not_whitelisted_recipients(**proposal_id)
+                .await?;
+            Ok(list
+                .into_iter()
+                .map(|a| a.assume_checked().to_string())
+                .collect())
+        })
+    }
+
     pub fn get_proposals(&self) -> Result<Vec<Arc<GetProposal>>> {
         block_on(async move {
             let proposals = self.inner.get_proposals().await?;
@@ -386,6 +688,52 @@ impl SmartVaults {
         })
     }
 
+    /// Comment a pending proposal (visible only to the vault members)
+    pub fn comment_proposal(
+        &self,
+        proposal_id: Arc<EventId>,
+        message: String,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let comment_id = self.inner.comment_proposal(**proposal_id, message).await?;
+            Ok(Arc::new(comment_id.into()))
+        })
+    }
+
+    pub fn get_proposal_comments(
+        &self,
+        proposal_id: Arc<EventId>,
+    ) -> Result<Vec<Arc<GetProposalComment>>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_proposal_comments(**proposal_id)
+                .await?
+                .into_iter()
+                .map(|c| Arc::new(c.into()))
+                .collect())
+        })
+    }
+
+    /// Get the signers still required by each spending path of a pending proposal
+    pub fn get_quorum_status(&self, proposal_id: Arc<EventId>) -> Result<Arc<GetQuorumStatus>> {
+        block_on(async move {
+            Ok(Arc::new(
+                self.inner.get_quorum_status(**proposal_id).await?.into(),
+            ))
+        })
+    }
+
+    /// Save the printable disaster-recovery kit of a vault
+    pub fn generate_recovery_kit(&self, vault_id: Arc<EventId>, path: String) -> Result<()> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .generate_recovery_kit(**vault_id, PathBuf::from(path))
+                .await?)
+        })
+    }
+
     pub fn get_completed_proposals(&self) -> Result<Vec<Arc<GetCompletedProposal>>> {
         block_on(async move {
             let completed_proposals = self.inner.get_completed_proposals().await?;
@@ -449,6 +797,9 @@ impl SmartVaults {
         })
     }
 
+    /// Make a spending proposal
+    ///
+    /// Use `Amount::max()` (or `Amount::max_with_reserve()`) to send all the funds, minus the fee.
     pub fn spend(
         &self,
         policy_id: Arc<EventId>,
@@ -483,23 +834,139 @@ impl SmartVaults {
         })
     }
 
-    pub fn self_transfer(
+    /// Build the spending transaction without creating the proposal
+    ///
+    /// Use `Amount::max()` to get the amount that would be sent when draining the vault.
+    pub fn preview_spend(
         &self,
-        from_policy_id: Arc<EventId>,
-        to_policy_id: Arc<EventId>,
+        policy_id: Arc<EventId>,
+        to_address: String,
         amount: Arc<Amount>,
         target_blocks: u8,
         utxos: Option<Vec<Arc<OutPoint>>>,
+        excluded_utxos: Vec<Arc<OutPoint>>,
+        avoid_address_reuse: bool,
+        policy_path: Option<HashMap<String, Vec<u64>>>,
+        skip_frozen_utxos: bool,
+    ) -> Result<SpendPreview> {
+        block_on(async move {
+            let to_address = Address::from_str(&to_address)?;
+            let options = types::SpendOptions {
+                utxos: utxos.map(|utxos| utxos.into_iter().map(|u| u.as_ref().into()).collect()),
+                excluded_utxos: excluded_utxos
+                    .into_iter()
+                    .map(|u| u.as_ref().into())
+                    .collect(),
+                avoid_address_reuse,
+                policy_path: policy_path.map(|pp| {
+                    pp.into_iter()
+                        .map(|(k, v)| (k, v.into_iter().map(|i| i as usize).collect()))
+                        .collect()
+                }),
+                skip_frozen_utxos,
+                expires_at: None,
+                memo: None,
+                op_return: None,
+                tx_options: Default::default(),
+            };
+            let preview = self
+                .inner
+                .preview_spend(
+                    **policy_id,
+                    to_address,
+                    **amount,
+                    FeeRate::Priority(Priority::Custom(target_blocks)),
+                    options,
+                )
+                .await?;
+            Ok(preview.into())
+        })
+    }
+
+    /// Make a spending proposal paying multiple recipients in a single transaction
+    ///
+    /// The proposal is deleted if not finalized by `expires_at`, if set.
+    /// The `memo` records are encrypted with the proposal.
+    /// The `op_return` data (max 80 bytes) is embedded in an OP_RETURN output.
+    /// The `tx_options` (nLockTime, RBF and nSequence) default to anti-fee-sniping and RBF.
+    pub fn spend_many(
+        &self,
+        policy_id: Arc<EventId>,
+        recipients: Vec<SpendRecipient>,
+        description: String,
+        target_blocks: u8,
+        utxos: Option<Vec<Arc<OutPoint>>>,
+        excluded_utxos: Vec<Arc<OutPoint>>,
+        avoid_address_reuse: bool,
         policy_path: Option<HashMap<String, Vec<u64>>>,
         skip_frozen_utxos: bool,
+        expires_at: Option<Arc<Timestamp>>,
+        memo: Option<PaymentMemo>,
+        op_return: Option<Vec<u8>>,
+        tx_options: Option<TxOptions>,
     ) -> Result<Arc<GetProposal>> {
         block_on(async move {
+            let mut list: Vec<types::SpendRecipient> = Vec::with_capacity(recipients.len());
+            for recipient in recipients.into_iter() {
+                list.push(types::SpendRecipient {
+                    address: Address::from_str(&recipient.address)?,
+                    amount: recipient.amount,
+                    label: recipient.label,
+                });
+            }
+            let options = types::SpendOptions {
+                utxos: utxos.map(|utxos| utxos.into_iter().map(|u| u.as_ref().into()).collect()),
+                excluded_utxos: excluded_utxos
+                    .into_iter()
+                    .map(|u| u.as_ref().into())
+                    .collect(),
+                avoid_address_reuse,
+                policy_path: policy_path.map(|pp| {
+                    pp.into_iter()
+                        .map(|(k, v)| (k, v.into_iter().map(|i| i as usize).collect()))
+                        .collect()
+                }),
+                skip_frozen_utxos,
+                expires_at: expires_at.map(|t| **t),
+                memo: memo.map(|m| m.try_into()).transpose()?,
+                op_return,
+                tx_options: tx_options.map(|o| o.into()).unwrap_or_default(),
+            };
             let proposal = self
                 .inner
-                .self_transfer(
-                    **from_policy_id,
-                    **to_policy_id,
+                .spend_many(
+                    **policy_id,
+                    list,
+                    description,
+                    FeeRate::Priority(Priority::Custom(target_blocks)),
+                    options,
+                )
+                .await?;
+            Ok(Arc::new(proposal.into()))
+        })
+    }
+
+    /// Replace a pending spending proposal (the approvers get the changes, see `GetProposal::diff`)
+    pub fn replace_proposal(
+        &self,
+        proposal_id: Arc<EventId>,
+        to_address: String,
+        amount: Arc<Amount>,
+        description: String,
+        target_blocks: u8,
+        utxos: Option<Vec<Arc<OutPoint>>>,
+        policy_path: Option<HashMap<String, Vec<u64>>>,
+        skip_frozen_utxos: bool,
+    ) -> Result<Arc<GetProposal>> {
+        block_on(async move {
+            let to_address = Address::from_str(&to_address)?;
+            let proposal = self
+                .inner
+                .replace_proposal(
+                    **proposal_id,
+                    to_address,
                     **amount,
+                    description,
                     FeeRate::Priority(Priority::Custom(target_blocks)),
                     utxos.map(|utxos| utxos.into_iter().map(|u| u.as_ref().into()).collect()),
                     policy_path.map(|pp| {
@@ -514,23 +981,160 @@ impl SmartVaults {
         })
     }
 
-    pub fn approve(&self, password: String, proposal_id: Arc<EventId>) -> Result<Arc<EventId>> {
-        block_on(async move {
-            let (approval_id, ..) = self.inner.approve(password, **proposal_id).await?;
-            Ok(Arc::new(approval_id.into()))
-        })
-    }
-
-    pub fn approve_with_signed_psbt(
+    /// Amend the amount, the description and the fee rate of a pending spending proposal
+    ///
+    /// The approvals of the original proposal must be given again.
+    pub fn amend_proposal(
         &self,
         proposal_id: Arc<EventId>,
-        signed_psbt: String,
-    ) -> Result<Arc<EventId>> {
-        block_on(async move {
+        amount: Option<Arc<Amount>>,
+        description: Option<String>,
+        target_blocks: u8,
+    ) -> Result<Arc<GetProposal>> {
+        block_on(async move {
+            let proposal = self
+                .inner
+                .amend_proposal(
+                    **proposal_id,
+                    amount.map(|a| **a),
+                    description,
+                    FeeRate::Priority(Priority::Custom(target_blocks)),
+                )
+                .await?;
+            Ok(Arc::new(proposal.into()))
+        })
+    }
+
+    /// Replace a pending spending proposal with the same one at a higher fee rate (sat/vByte)
+    pub fn bump_proposal_fee(
+        &self,
+        proposal_id: Arc<EventId>,
+        fee_rate: f32,
+    ) -> Result<Arc<GetProposal>> {
+        block_on(async move {
+            let proposal = self
+                .inner
+                .bump_proposal_fee(**proposal_id, FeeRate::Rate(fee_rate))
+                .await?;
+            Ok(Arc::new(proposal.into()))
+        })
+    }
+
+    pub fn self_transfer(
+        &self,
+        from_policy_id: Arc<EventId>,
+        to_policy_id: Arc<EventId>,
+        amount: Arc<Amount>,
+        target_blocks: u8,
+        utxos: Option<Vec<Arc<OutPoint>>>,
+        policy_path: Option<HashMap<String, Vec<u64>>>,
+        skip_frozen_utxos: bool,
+    ) -> Result<Arc<GetProposal>> {
+        block_on(async move {
+            let proposal = self
+                .inner
+                .self_transfer(
+                    **from_policy_id,
+                    **to_policy_id,
+                    **amount,
+                    FeeRate::Priority(Priority::Custom(target_blocks)),
+                    utxos.map(|utxos| utxos.into_iter().map(|u| u.as_ref().into()).collect()),
+                    policy_path.map(|pp| {
+                        pp.into_iter()
+                            .map(|(k, v)| (k, v.into_iter().map(|i| i as usize).collect()))
+                            .collect()
+                    }),
+                    skip_frozen_utxos,
+                )
+                .await?;
+            Ok(Arc::new(proposal.into()))
+        })
+    }
+
+    pub fn approve(
+        &self,
+        password: String,
+        pin: Option<String>,
+        proposal_id: Arc<EventId>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let (approval_id, ..) = self
+                .inner
+                .approve(password, pin.as_deref(), **proposal_id)
+                .await?;
+            Ok(Arc::new(approval_id.into()))
+        })
+    }
+
+    /// Approve a proposal exceeding the spending velocity limits of the vault
+    pub fn approve_with_velocity_override(
+        &self,
+        password: String,
+        pin: Option<String>,
+        proposal_id: Arc<EventId>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let (approval_id, ..) = self
+                .inner
+                .approve_with_velocity_override(password, pin.as_deref(), **proposal_id)
+                .await?;
+            Ok(Arc::new(approval_id.into()))
+        })
+    }
+
+    /// Approve a batch of proposals with a single unlock
+    pub fn approve_many(
+        &self,
+        proposal_ids: Vec<Arc<EventId>>,
+        password: String,
+        pin: Option<String>,
+    ) -> Result<Vec<BulkApprovalResult>> {
+        block_on(async move {
+            let proposal_ids = proposal_ids.into_iter().map(|id| **id).collect();
+            Ok(self
+                .inner
+                .approve_many(proposal_ids, password, pin.as_deref())
+                .await?
+                .into_iter()
+                .map(|r| r.into())
+                .collect())
+        })
+    }
+
+    pub fn approve_with_signed_psbt(
+        &self,
+        proposal_id: Arc<EventId>,
+        signed_psbt: String,
+        pin: Option<String>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let signed_psbt = PartiallySignedTransaction::from_str(&signed_psbt)?;
+            let (approval_id, ..) = self
+                .inner
+                .approve_with_signed_psbt(**proposal_id, signed_psbt, pin.as_deref())
+                .await?;
+            Ok(Arc::new(approval_id.into()))
+        })
+    }
+
+    /// Approve with the PSBT (base64) signed by an external (i.e. air-gap) signer
+    pub fn approve_with_external_signer(
+        &self,
+        proposal_id: Arc<EventId>,
+        signer_id: Arc<EventId>,
+        signed_psbt: String,
+        pin: Option<String>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
             let signed_psbt = PartiallySignedTransaction::from_str(&signed_psbt)?;
             let (approval_id, ..) = self
                 .inner
-                .approve_with_signed_psbt(**proposal_id, signed_psbt)
+                .approve_with_external_signer(
+                    **proposal_id,
+                    **signer_id,
+                    signed_psbt,
+                    pin.as_deref(),
+                )
                 .await?;
             Ok(Arc::new(approval_id.into()))
         })
@@ -666,6 +1270,68 @@ impl SmartVaults {
         })
     }
 
+    /// Ask a contact to share a signer with the specified purpose (i.e. `86` for taproot)
+    pub fn request_signer(
+        &self,
+        public_key: Arc<PublicKey>,
+        purpose: u32,
+        account: Option<u32>,
+        message: Option<String>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let request = SignerRequest::new(purpose, account, message);
+            Ok(Arc::new(
+                self.inner
+                    .request_signer(**public_key, request)
+                    .await?
+                    .into(),
+            ))
+        })
+    }
+
+    /// Get the signer requests received from other users and not answered yet
+    pub fn get_signer_requests(&self) -> Result<Vec<Arc<GetSignerRequest>>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_signer_requests()
+                .await?
+                .into_iter()
+                .map(|r| Arc::new(r.into()))
+                .collect())
+        })
+    }
+
+    /// Share a signer with the author of the request
+    pub fn answer_signer_request(
+        &self,
+        request_id: Arc<EventId>,
+        signer_id: Arc<EventId>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            Ok(Arc::new(
+                self.inner
+                    .answer_signer_request(**request_id, **signer_id)
+                    .await?
+                    .into(),
+            ))
+        })
+    }
+
+    /// Get the default shared signer of a contact (`None` if the contact hasn't shared any signer yet)
+    pub fn get_default_shared_signer(
+        &self,
+        public_key: Arc<PublicKey>,
+    ) -> Result<Option<Arc<GetSharedSigner>>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_default_shared_signer(**public_key)
+                .await?
+                .map(|s| Arc::new(s.into())))
+        })
+    }
+
     pub fn get_balance(&self, policy_id: Arc<EventId>) -> Option<Arc<Balance>> {
         block_on(async move {
             #[allow(deprecated)]
@@ -699,6 +1365,11 @@ impl SmartVaults {
         })
     }
 
+    /// Get the mempool fee histogram and the fee rates needed to confirm within 1, 6 and 12 blocks
+    pub fn get_mempool_snapshot(&self) -> Result<MempoolSnapshot> {
+        block_on(async move { Ok(self.inner.get_mempool_snapshot().await?.into()) })
+    }
+
     pub fn get_utxos(&self, policy_id: Arc<EventId>) -> Result<Vec<Arc<Utxo>>> {
         block_on(async move {
             Ok(self
@@ -711,6 +1382,97 @@ impl SmartVaults {
         })
     }
 
+    /// Get the timelocked paths of a vault that a signer of the user can satisfy alone
+    pub fn get_recovery_paths(&self, policy_id: Arc<EventId>) -> Result<Vec<RecoveryPath>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_recovery_paths(**policy_id)
+                .await?
+                .into_iter()
+                .map(|p| p.into())
+                .collect())
+        })
+    }
+
+    /// Make a proposal sweeping the vault funds with the recovery path of the signer
+    pub fn sweep_with_recovery_path(
+        &self,
+        policy_id: Arc<EventId>,
+        signer_id: Arc<EventId>,
+        to_address: String,
+        target_blocks: u8,
+    ) -> Result<Arc<GetProposal>> {
+        block_on(async move {
+            let to_address = Address::from_str(&to_address)?;
+            let proposal = self
+                .inner
+                .sweep_with_recovery_path(
+                    **policy_id,
+                    **signer_id,
+                    to_address,
+                    FeeRate::Priority(Priority::Custom(target_blocks)),
+                )
+                .await?;
+            Ok(Arc::new(proposal.into()))
+        })
+    }
+
+    /// Watch a vault for the maturity of its recovery paths
+    ///
+    /// If `sweep_address` is set, a sweep proposal is made automatically as soon as a path matures.
+    pub fn watch_recovery_paths(
+        &self,
+        policy_id: Arc<EventId>,
+        sweep_address: Option<String>,
+    ) -> Result<()> {
+        block_on(async move {
+            let sweep_address = match sweep_address {
+                Some(address) => Some(Address::from_str(&address)?),
+                None => None,
+            };
+            Ok(self
+                .inner
+                .watch_recovery_paths(**policy_id, sweep_address)
+                .await?)
+        })
+    }
+
+    pub fn unwatch_recovery_paths(&self, policy_id: Arc<EventId>) -> Result<()> {
+        block_on(async move { Ok(self.inner.unwatch_recovery_paths(**policy_id).await?) })
+    }
+
+    /// Dismiss the last automatic sweep of a watched vault, allowing a new sweep proposal
+    pub fn dismiss_recovery_sweep(&self, policy_id: Arc<EventId>) -> Result<()> {
+        block_on(async move { Ok(self.inner.dismiss_recovery_sweep(**policy_id).await?) })
+    }
+
+    pub fn get_recovery_watches(&self) -> Result<Vec<RecoveryWatch>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_recovery_watches()
+                .await?
+                .into_iter()
+                .map(|w| w.into())
+                .collect())
+        })
+    }
+
+    pub fn freeze_utxos(&self, policy_id: Arc<EventId>, utxos: Vec<Arc<OutPoint>>) -> Result<()> {
+        block_on(async move {
+            let utxos = utxos.into_iter().map(|u| u.as_ref().into());
+            Ok(self.inner.freeze_utxos(**policy_id, utxos).await?)
+        })
+    }
+
+    pub fn unfreeze_utxos(&self, policy_id: Arc<EventId>, utxos: Vec<Arc<OutPoint>>) -> Result<()> {
+        block_on(async move {
+            let utxos = utxos.into_iter().map(|u| u.as_ref().into());
+            Ok(self.inner.unfreeze_utxos(**policy_id, utxos).await?)
+        })
+    }
+
     pub fn get_total_balance(&self) -> Result<Arc<Balance>> {
         block_on(async move { Ok(Arc::new(self.inner.get_total_balance().await?.into())) })
     }
@@ -758,6 +1520,183 @@ impl SmartVaults {
         })
     }
 
+    pub fn get_vaults_without_shared_key(&self) -> Vec<Arc<EventId>> {
+        block_on(async move {
+            self.inner
+                .get_vaults_without_shared_key()
+                .await
+                .into_iter()
+                .map(|id| Arc::new(id.into()))
+                .collect()
+        })
+    }
+
+    pub fn request_shared_key(&self, policy_id: Arc<EventId>) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self.inner.request_shared_key(**policy_id).await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    pub fn device_id(&self) -> Result<String> {
+        block_on(async move { Ok(self.inner.device_id().await?) })
+    }
+
+    pub fn set_device_name(&self, name: Option<String>) -> Result<()> {
+        block_on(async move { Ok(self.inner.set_device_name(name).await?) })
+    }
+
+    pub fn get_devices(&self) -> Result<Vec<Arc<GetDevice>>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_devices()
+                .await?
+                .into_iter()
+                .map(|d| Arc::new(d.into()))
+                .collect())
+        })
+    }
+
+    pub fn revoke_device(&self, device_id: String) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self.inner.revoke_device(device_id).await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    /// Record that the vault was registered on a hardware signer
+    pub fn save_device_registration(
+        &self,
+        vault_id: Arc<EventId>,
+        fingerprint: String,
+        device: String,
+        proof: Option<String>,
+    ) -> Result<()> {
+        block_on(async move {
+            let fingerprint = Fingerprint::from_str(&fingerprint)?;
+            Ok(self
+                .inner
+                .save_device_registration(**vault_id, fingerprint, device, proof)
+                .await?)
+        })
+    }
+
+    pub fn get_device_registrations(
+        &self,
+        vault_id: Arc<EventId>,
+    ) -> Result<Vec<Arc<DeviceRegistration>>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_device_registrations(**vault_id)
+                .await?
+                .into_iter()
+                .map(|r| Arc::new(r.into()))
+                .collect())
+        })
+    }
+
+    pub fn delete_device_registration(
+        &self,
+        vault_id: Arc<EventId>,
+        fingerprint: String,
+    ) -> Result<()> {
+        block_on(async move {
+            let fingerprint = Fingerprint::from_str(&fingerprint)?;
+            Ok(self
+                .inner
+                .delete_device_registration(**vault_id, fingerprint)
+                .await?)
+        })
+    }
+
+    /// Mark a key (master fingerprint) as compromised
+    ///
+    /// If `share` is `true`, the key is synced across the user devices.
+    pub fn mark_key_compromised(
+        &self,
+        fingerprint: String,
+        reason: Option<String>,
+        share: bool,
+    ) -> Result<()> {
+        block_on(async move {
+            let fingerprint = Fingerprint::from_str(&fingerprint)?;
+            Ok(self
+                .inner
+                .mark_key_compromised(fingerprint, reason, share)
+                .await?)
+        })
+    }
+
+    pub fn unmark_key_compromised(&self, fingerprint: String) -> Result<()> {
+        block_on(async move {
+            let fingerprint = Fingerprint::from_str(&fingerprint)?;
+            Ok(self.inner.unmark_key_compromised(fingerprint).await?)
+        })
+    }
+
+    pub fn get_compromised_keys(&self) -> Result<Vec<CompromisedKey>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_compromised_keys()
+                .await?
+                .into_iter()
+                .map(|k| k.into())
+                .collect())
+        })
+    }
+
+    /// Get the compromised keys used by a descriptor or a policy (i.e. before creating a vault)
+    pub fn compromised_keys_in_descriptor(&self, descriptor: String) -> Result<Vec<String>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .compromised_keys_in_descriptor(descriptor)
+                .await?
+                .into_iter()
+                .map(|f| f.to_string())
+                .collect())
+        })
+    }
+
+    pub fn get_vault_compromised_keys(&self, vault_id: Arc<EventId>) -> Result<Vec<String>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_vault_compromised_keys(**vault_id)
+                .await?
+                .into_iter()
+                .map(|f| f.to_string())
+                .collect())
+        })
+    }
+
+    /// Get my hardware and air-gap signers used by the vault that still need to register it
+    pub fn get_unregistered_signers(&self, vault_id: Arc<EventId>) -> Result<Vec<Arc<GetSigner>>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_unregistered_signers(**vault_id)
+                .await?
+                .into_iter()
+                .map(|s| Arc::new(s.into()))
+                .collect())
+        })
+    }
+
+    pub fn get_app_settings(&self) -> Result<AppSettings> {
+        block_on(async move { Ok(self.inner.get_app_settings().await?.into()) })
+    }
+
+    pub fn save_app_settings(&self, settings: AppSettings) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self.inner.save_app_settings(settings.try_into()?).await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
     // TODO: add notifications methods
 
     pub fn new_nostr_connect_session(&self, uri: Arc<NostrConnectURI>) -> Result<()> {
@@ -872,6 +1811,150 @@ impl SmartVaults {
         self.add_contact(key_agent)
     }
 
+    /// Ask the other members of the vault to be paid (i.e. reimbursement)
+    pub fn create_payment_request(
+        &self,
+        policy_id: Arc<EventId>,
+        address: String,
+        amount: u64,
+        description: String,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let address = Address::from_str(&address)?;
+            Ok(Arc::new(
+                self.inner
+                    .create_payment_request(**policy_id, address, amount, description)
+                    .await?
+                    .into(),
+            ))
+        })
+    }
+
+    /// Get the payment requests published in the vaults, newest first
+    pub fn get_payment_requests(&self) -> Result<Vec<Arc<GetPaymentRequest>>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_payment_requests()
+                .await?
+                .into_iter()
+                .map(|r| Arc::new(r.into()))
+                .collect())
+        })
+    }
+
+    /// Schedule a payment repeated every `interval`, starting from `start` (default: now)
+    ///
+    /// Return the ID of the recurring payment.
+    pub fn create_recurring_payment(
+        &self,
+        policy_id: Arc<EventId>,
+        address: String,
+        amount: u64,
+        description: String,
+        interval: Duration,
+        start: Option<Arc<Timestamp>>,
+    ) -> Result<String> {
+        block_on(async move {
+            let address = Address::from_str(&address)?;
+            Ok(self
+                .inner
+                .create_recurring_payment(
+                    **policy_id,
+                    address,
+                    amount,
+                    description,
+                    interval,
+                    start.map(|t| **t),
+                )
+                .await?)
+        })
+    }
+
+    /// Get the recurring payments of the vaults, sorted by next due date
+    pub fn get_recurring_payments(&self) -> Result<Vec<Arc<GetRecurringPayment>>> {
+        block_on(async move {
+            Ok(self
+                .inner
+                .get_recurring_payments()
+                .await?
+                .into_iter()
+                .map(|p| Arc::new(p.into()))
+                .collect())
+        })
+    }
+
+    /// Cancel a recurring payment created by this account
+    pub fn delete_recurring_payment(&self, payment_id: String) -> Result<()> {
+        block_on(async move { Ok(self.inner.delete_recurring_payment(payment_id).await?) })
+    }
+
+    pub fn get_payees(&self) -> Result<Vec<Payee>> {
+        block_on(async move {
+            let payees = self.inner.get_payees().await?;
+            Ok(payees.payees.into_iter().map(|p| p.into()).collect())
+        })
+    }
+
+    /// Get the payees whose name starts with `prefix` (i.e. to autocomplete the recipient)
+    pub fn search_payees(&self, prefix: String) -> Result<Vec<Payee>> {
+        block_on(async move {
+            let payees = self.inner.search_payees(prefix).await?;
+            Ok(payees.into_iter().map(|p| p.into()).collect())
+        })
+    }
+
+    /// Add a payee (or replace the one with the same name)
+    ///
+    /// The destination can be an address, a descriptor or an xpub.
+    pub fn save_payee(
+        &self,
+        name: String,
+        destination: String,
+        memo: Option<String>,
+    ) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self.inner.save_payee(name, destination, memo).await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    pub fn remove_payee(&self, name: String) -> Result<Arc<EventId>> {
+        block_on(async move {
+            let event_id = self.inner.remove_payee(name).await?;
+            Ok(Arc::new(event_id.into()))
+        })
+    }
+
+    /// Get the address of the next payment to the payee, without using it
+    pub fn get_payee_address(&self, name: String) -> Result<String> {
+        block_on(async move { Ok(self.inner.get_payee_address(name).await?.to_string()) })
+    }
+
+    /// Get the address of the next payment to the payee (a fresh address is derived at each call
+    /// for the descriptors)
+    pub fn use_payee_address(&self, name: String) -> Result<String> {
+        block_on(async move { Ok(self.inner.use_payee_address(name).await?.to_string()) })
+    }
+
+    /// Create a spending proposal that pays the request
+    pub fn fulfill_payment_request(
+        &self,
+        request_id: Arc<EventId>,
+        target_blocks: u8,
+    ) -> Result<Arc<GetProposal>> {
+        block_on(async move {
+            let proposal = self
+                .inner
+                .fulfill_payment_request(
+                    **request_id,
+                    FeeRate::Priority(Priority::Custom(target_blocks)),
+                )
+                .await?;
+            Ok(Arc::new(proposal.into()))
+        })
+    }
+
     pub fn key_agent_payment(
         &self,
         policy_id: Arc<EventId>,
@@ -43,4 +43,31 @@ impl Config {
     pub fn block_explorer(&self) -> Result<String> {
         block_on(async move { Ok(self.inner.block_explorer().await?.to_string()) })
     }
+
+    pub fn add_esplora_endpoint(&self, url: String) -> Result<()> {
+        block_on(async move {
+            let url = Url::parse(&url)?;
+            self.inner.add_esplora_endpoint(url).await;
+            Ok(())
+        })
+    }
+
+    pub fn remove_esplora_endpoint(&self, url: String) -> Result<()> {
+        block_on(async move {
+            let url = Url::parse(&url)?;
+            self.inner.remove_esplora_endpoint(&url).await;
+            Ok(())
+        })
+    }
+
+    pub fn esplora_endpoints(&self) -> Vec<String> {
+        block_on(async move {
+            self.inner
+                .esplora_endpoints()
+                .await
+                .into_iter()
+                .map(|u| u.to_string())
+                .collect()
+        })
+    }
 }
@@ -0,0 +1,70 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::sync::Arc;
+
+use nostr_ffi::EventId;
+use smartvaults_sdk::types;
+use uniffi::Record;
+
+#[derive(Record)]
+pub struct ConsistencyReport {
+    /// Number of checked events
+    pub checked: u64,
+    /// Not processed events that were replayed
+    pub replayed: Vec<Arc<EventId>>,
+    /// Events that can't be processed
+    pub failed: Vec<Arc<EventId>>,
+    /// Items without a backing event (removed)
+    pub orphaned: Vec<Arc<EventId>>,
+    /// Events waiting for a shared key
+    pub pending: u64,
+}
+
+impl From<types::ConsistencyReport> for ConsistencyReport {
+    fn from(value: types::ConsistencyReport) -> Self {
+        Self {
+            checked: value.checked as u64,
+            replayed: value
+                .replayed
+                .into_iter()
+                .map(|id| Arc::new(id.into()))
+                .collect(),
+            failed: value
+                .failed
+                .into_iter()
+                .map(|id| Arc::new(id.into()))
+                .collect(),
+            orphaned: value
+                .orphaned
+                .into_iter()
+                .map(|id| Arc::new(id.into()))
+                .collect(),
+            pending: value.pending as u64,
+        }
+    }
+}
+
+/// Summary of the import of an events archive
+#[derive(Record)]
+pub struct EventsImport {
+    /// New events, handled as if received from a relay
+    pub imported: u64,
+    /// Events already in the database
+    pub duplicated: u64,
+    /// Events with an invalid ID or signature
+    pub invalid: u64,
+    /// Events not handled (i.e. the shared key of the vault is missing)
+    pub failed: u64,
+}
+
+impl From<types::EventsImport> for EventsImport {
+    fn from(value: types::EventsImport) -> Self {
+        Self {
+            imported: value.imported as u64,
+            duplicated: value.duplicated as u64,
+            invalid: value.invalid as u64,
+            failed: value.failed as u64,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::sync::Arc;
+
+use nostr_ffi::{EventId, Timestamp};
+use smartvaults_sdk::types;
+use uniffi::Object;
+
+#[derive(Object)]
+pub struct GetDevice {
+    inner: types::GetDevice,
+}
+
+impl From<types::GetDevice> for GetDevice {
+    fn from(inner: types::GetDevice) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl GetDevice {
+    pub fn device_id(&self) -> String {
+        self.inner.device_id.clone()
+    }
+
+    pub fn platform(&self) -> String {
+        self.inner.platform.clone()
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.inner.name.clone()
+    }
+
+    pub fn last_seen(&self) -> Arc<Timestamp> {
+        Arc::new(self.inner.last_seen.into())
+    }
+
+    pub fn revoked(&self) -> bool {
+        self.inner.revoked
+    }
+
+    pub fn current(&self) -> bool {
+        self.inner.current
+    }
+}
+
+#[derive(Object)]
+pub struct DeviceRegistration {
+    inner: types::DeviceRegistration,
+}
+
+impl From<types::DeviceRegistration> for DeviceRegistration {
+    fn from(inner: types::DeviceRegistration) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl DeviceRegistration {
+    pub fn vault_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.policy_id.into())
+    }
+
+    pub fn fingerprint(&self) -> String {
+        self.inner.fingerprint.to_string()
+    }
+
+    /// Device model (i.e. `ledger`, `coldcard`)
+    pub fn device(&self) -> String {
+        self.inner.device.clone()
+    }
+
+    /// Registration proof returned by the device (i.e. Ledger wallet policy HMAC)
+    pub fn proof(&self) -> Option<String> {
+        self.inner.proof.clone()
+    }
+
+    pub fn timestamp(&self) -> Arc<Timestamp> {
+        Arc::new(self.inner.timestamp.into())
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::sync::Arc;
+
+use nostr_ffi::Timestamp;
+use smartvaults_sdk::types;
+use uniffi::Record;
+
+#[derive(Record)]
+pub struct RelayDiagnostic {
+    pub url: String,
+    pub connected: bool,
+    /// NIP-42 authentication required (`None` if the relay information document is not available)
+    pub auth_required: Option<bool>,
+    /// Time to publish a test event and read it back (millis)
+    pub round_trip: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl From<types::RelayDiagnostic> for RelayDiagnostic {
+    fn from(value: types::RelayDiagnostic) -> Self {
+        Self {
+            url: value.url.to_string(),
+            connected: value.connected,
+            auth_required: value.auth_required,
+            round_trip: value.round_trip.map(|d| d.as_millis() as u64),
+            error: value.error,
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct BackendDiagnostic {
+    pub backend: String,
+    pub version: Option<String>,
+    pub block_height: Option<u32>,
+    /// Latency (millis)
+    pub latency: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl From<types::BackendDiagnostic> for BackendDiagnostic {
+    fn from(value: types::BackendDiagnostic) -> Self {
+        Self {
+            backend: value.backend,
+            version: value.version,
+            block_height: value.block_height,
+            latency: value.latency.map(|d| d.as_millis() as u64),
+            error: value.error,
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct DiagnosticsReport {
+    pub relays: Vec<RelayDiagnostic>,
+    pub backends: Vec<BackendDiagnostic>,
+    pub timestamp: Arc<Timestamp>,
+    /// All the relays and backends are working
+    pub ok: bool,
+}
+
+impl From<types::DiagnosticsReport> for DiagnosticsReport {
+    fn from(value: types::DiagnosticsReport) -> Self {
+        Self {
+            ok: value.is_ok(),
+            relays: value.relays.into_iter().map(|r| r.into()).collect(),
+            backends: value.backends.into_iter().map(|b| b.into()).collect(),
+            timestamp: Arc::new(value.timestamp.into()),
+        }
+    }
+}
@@ -4,14 +4,24 @@
 use std::fmt;
 
 use smartvaults_sdk::core::miniscript::descriptor::DescriptorKeyParseError;
+use smartvaults_sdk::ErrorKind;
 use uniffi::Error;
 
 pub type Result<T, E = SmartVaultsError> = std::result::Result<T, E>;
 
+/// Smart Vaults error
+///
+/// The variant is the error kind, `retryable` tells if the operation may succeed if retried later.
 #[derive(Debug, Error)]
-#[uniffi(flat_error)]
 pub enum SmartVaultsError {
-    Generic(String),
+    Keychain { message: String, retryable: bool },
+    Relay { message: String, retryable: bool },
+    Network { message: String, retryable: bool },
+    Storage { message: String, retryable: bool },
+    Wallet { message: String, retryable: bool },
+    Protocol { message: String, retryable: bool },
+    Policy { message: String, retryable: bool },
+    Generic { message: String, retryable: bool },
 }
 
 impl std::error::Error for SmartVaultsError {}
@@ -19,151 +29,226 @@ impl std::error::Error for SmartVaultsError {}
 impl fmt::Display for SmartVaultsError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Generic(e) => write!(f, "{e}"),
+            Self::Keychain { message, .. }
+            | Self::Relay { message, .. }
+            | Self::Network { message, .. }
+            | Self::Storage { message, .. }
+            | Self::Wallet { message, .. }
+            | Self::Protocol { message, .. }
+            | Self::Policy { message, .. }
+            | Self::Generic { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl SmartVaultsError {
+    pub(crate) fn keychain<T: ToString>(e: T) -> Self {
+        Self::Keychain {
+            message: e.to_string(),
+            retryable: false,
+        }
+    }
+
+    pub(crate) fn relay<T: ToString>(e: T) -> Self {
+        Self::Relay {
+            message: e.to_string(),
+            retryable: false,
+        }
+    }
+
+    pub(crate) fn storage<T: ToString>(e: T) -> Self {
+        Self::Storage {
+            message: e.to_string(),
+            retryable: false,
+        }
+    }
+
+    pub(crate) fn wallet<T: ToString>(e: T) -> Self {
+        Self::Wallet {
+            message: e.to_string(),
+            retryable: false,
+        }
+    }
+
+    pub(crate) fn protocol<T: ToString>(e: T) -> Self {
+        Self::Protocol {
+            message: e.to_string(),
+            retryable: false,
+        }
+    }
+
+    pub(crate) fn policy<T: ToString>(e: T) -> Self {
+        Self::Policy {
+            message: e.to_string(),
+            retryable: false,
+        }
+    }
+
+    pub(crate) fn generic<T: ToString>(e: T) -> Self {
+        Self::Generic {
+            message: e.to_string(),
+            retryable: false,
         }
     }
 }
 
 impl From<async_utility::thread::Error> for SmartVaultsError {
     fn from(e: async_utility::thread::Error) -> SmartVaultsError {
-        Self::Generic(e.to_string())
+        Self::generic(e)
     }
 }
 
 impl From<smartvaults_sdk::logger::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::logger::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::generic(e)
     }
 }
 
 impl From<std::io::Error> for SmartVaultsError {
     fn from(e: std::io::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::storage(e)
     }
 }
 
 impl From<smartvaults_sdk::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::Error) -> Self {
-        Self::Generic(e.to_string())
+        let message: String = e.to_string();
+        let retryable: bool = e.is_retryable();
+        match e.kind() {
+            ErrorKind::Keychain => Self::Keychain { message, retryable },
+            ErrorKind::Relay => Self::Relay { message, retryable },
+            ErrorKind::Network => Self::Network { message, retryable },
+            ErrorKind::Storage => Self::Storage { message, retryable },
+            ErrorKind::Wallet => Self::Wallet { message, retryable },
+            ErrorKind::Protocol => Self::Protocol { message, retryable },
+            ErrorKind::Policy => Self::Policy { message, retryable },
+            ErrorKind::Other => Self::Generic { message, retryable },
+        }
     }
 }
 
 impl From<smartvaults_sdk::config::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::config::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::storage(e)
     }
 }
 
 impl From<smartvaults_sdk::nostr::types::url::ParseError> for SmartVaultsError {
     fn from(e: smartvaults_sdk::nostr::types::url::ParseError) -> Self {
-        Self::Generic(e.to_string())
+        Self::relay(e)
     }
 }
 
 impl From<smartvaults_sdk::nostr::key::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::nostr::key::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::keychain(e)
     }
 }
 
 impl From<smartvaults_sdk::nostr::nips::nip19::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::nostr::nips::nip19::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::protocol(e)
     }
 }
 
 impl From<smartvaults_sdk::nostr::nips::nip46::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::nostr::nips::nip46::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::protocol(e)
     }
 }
 
 impl From<smartvaults_sdk::nostr::types::metadata::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::nostr::types::metadata::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::protocol(e)
     }
 }
 
 impl From<smartvaults_sdk::core::bitcoin::secp256k1::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::bitcoin::secp256k1::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::keychain(e)
+    }
+}
+
+impl From<smartvaults_sdk::core::descriptor::Error> for SmartVaultsError {
+    fn from(e: smartvaults_sdk::core::descriptor::Error) -> Self {
+        Self::policy(e)
     }
 }
 
 impl From<smartvaults_sdk::core::bitcoin::psbt::PsbtParseError> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::bitcoin::psbt::PsbtParseError) -> Self {
-        Self::Generic(e.to_string())
+        Self::wallet(e)
     }
 }
 
 impl From<smartvaults_sdk::nostr::event::id::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::nostr::event::id::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::protocol(e)
     }
 }
 
 impl From<smartvaults_sdk::core::bips::bip39::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::bips::bip39::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::keychain(e)
     }
 }
 
 impl From<smartvaults_sdk::core::bitcoin::address::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::bitcoin::address::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::wallet(e)
     }
 }
 
 impl From<smartvaults_sdk::core::bitcoin::hashes::hex::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::bitcoin::hashes::hex::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::generic(e)
     }
 }
 
 impl From<smartvaults_sdk::core::util::dir::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::util::dir::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::storage(e)
     }
 }
 
 impl From<smartvaults_sdk::core::policy::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::policy::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::policy(e)
     }
 }
 
 impl From<smartvaults_sdk::core::signer::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::signer::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::policy(e)
     }
 }
 
 impl From<DescriptorKeyParseError> for SmartVaultsError {
     fn from(e: DescriptorKeyParseError) -> SmartVaultsError {
-        Self::Generic(e.to_string())
+        Self::policy(e)
     }
 }
 
 impl From<smartvaults_sdk::core::bitcoin::absolute::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::bitcoin::absolute::Error) -> SmartVaultsError {
-        Self::Generic(e.to_string())
+        Self::policy(e)
     }
 }
 
 impl From<smartvaults_sdk::core::miniscript::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::core::miniscript::Error) -> Self {
-        Self::Generic(e.to_string())
+        Self::policy(e)
     }
 }
 
 impl From<nostr_ffi::NostrError> for SmartVaultsError {
     fn from(e: nostr_ffi::NostrError) -> SmartVaultsError {
-        Self::Generic(e.to_string())
+        Self::protocol(e)
     }
 }
 
 impl From<smartvaults_sdk::protocol::v1::key_agent::signer::Error> for SmartVaultsError {
     fn from(e: smartvaults_sdk::protocol::v1::key_agent::signer::Error) -> SmartVaultsError {
-        Self::Generic(e.to_string())
+        Self::protocol(e)
     }
 }
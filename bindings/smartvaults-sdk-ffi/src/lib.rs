@@ -14,15 +14,22 @@ mod amount;
 mod balance;
 mod client;
 mod config;
+mod consistency;
 mod descriptor;
+mod device;
+mod diagnostics;
 mod error;
 mod key_agent;
 mod message;
 mod network;
 mod nip46;
+mod payment_request;
 mod policy;
 mod proposal;
+mod relay;
+mod schedule;
 mod seed;
+mod settings;
 mod signer;
 mod transaction;
 
@@ -32,25 +39,39 @@ pub use self::amount::Amount;
 pub use self::balance::Balance;
 pub use self::client::{SmartVaults, SyncHandler};
 pub use self::config::Config;
+pub use self::consistency::{ConsistencyReport, EventsImport};
 pub use self::descriptor::Descriptor;
+pub use self::device::{DeviceRegistration, GetDevice};
+pub use self::diagnostics::{BackendDiagnostic, DiagnosticsReport, RelayDiagnostic};
 use self::error::Result;
 pub use self::error::SmartVaultsError;
 pub use self::key_agent::{DeviceType, KeyAgent, Price, SignerOffering, Temperature};
 pub use self::message::{EventHandled, Message};
 pub use self::network::Network;
 pub use self::nip46::{NostrConnectRequest, NostrConnectSession};
+pub use self::payment_request::{GetPaymentRequest, GetRecurringPayment, Payee};
 pub use self::policy::{
-    AbsoluteLockTime, DecayingTime, GetPolicy, Locktime, Policy, PolicyPath, PolicyPathSelector,
-    PolicyPathSigner, PolicyTemplate, PolicyTemplateType, RecoveryTemplate, RelativeLockTime,
+    AbsoluteLockTime, DecayingTime, GetPolicy, GetVaultSummary, Locktime, Policy, PolicyPath,
+    PolicyPathSelector, PolicyPathSigner, PolicyTemplate, PolicyTemplateType, RecoveryPath,
+    RecoveryTemplate, RecoveryWatch, RelativeLockTime, VaultSyncStatus, VelocityLimit,
+    VelocityLimitStatus, Whitelist, WhitelistMode, WhitelistedDestination,
 };
 pub use self::proposal::{
-    ApprovedProposal, CompletedProposal, GetApproval, GetCompletedProposal, GetProposal, Period,
-    Proposal,
+    ApprovedProposal, BulkApprovalResult, CompletedProposal, GetApproval, GetCompletedProposal,
+    GetProposal, GetProposalComment, GetQuorumStatus, PaymentMemo, Period, Proposal, ProposalDiff,
+    QuorumPath, Recipient, SpendPreview, SpendRecipient,
 };
+pub use self::relay::{RelayAdmission, RelayAdmissionStatus, RelayFee, RelayTerms};
+pub use self::schedule::{NetworkCondition, SyncSchedule};
 pub use self::seed::{Seed, WordCount};
-pub use self::signer::{GetSharedSigner, GetSigner, SharedSigner, Signer, SignerType};
+pub use self::settings::AppSettings;
+pub use self::signer::{
+    CompromisedKey, GetSharedSigner, GetSigner, GetSignerRequest, GetSignerUsage, SharedSigner,
+    Signer, SignerType,
+};
 pub use self::transaction::{
-    BlockTime, GetTransaction, OutPoint, Transaction, TransactionDetails, TxIn, TxOut, Utxo,
+    BlockTime, FeeHistogramEntry, GetTransaction, MempoolSnapshot, OutPoint, StuckTransaction,
+    StuckTxAction, Transaction, TransactionDetails, TxIn, TxOptions, TxOut, Utxo,
 };
 
 #[derive(Object)]
@@ -7,16 +7,23 @@ use nostr_ffi::{EventId, PublicKey};
 use smartvaults_sdk::{EventHandled as EventHandledSdk, Message as MessageSdk};
 use uniffi::Enum;
 
+use crate::StuckTransaction;
+
 #[derive(Enum)]
 pub enum EventHandled {
     SharedKey { event_id: Arc<EventId> },
+    SharedKeyRequest { request_id: Arc<EventId> },
     Policy { policy_id: Arc<EventId> },
     Proposal { proposal_id: Arc<EventId> },
     Approval { proposal_id: Arc<EventId> },
+    ProposalComment { proposal_id: Arc<EventId> },
     CompletedProposal { completed_proposal_id: Arc<EventId> },
     Signer { signer_id: Arc<EventId> },
     MySharedSigner { my_shared_signer_id: Arc<EventId> },
     SharedSigner { shared_signer_id: Arc<EventId> },
+    SignerRequest { request_id: Arc<EventId> },
+    PaymentRequest { request_id: Arc<EventId> },
+    RecurringPayment { policy_id: Arc<EventId> },
     Contacts,
     Metadata { public_key: Arc<PublicKey> },
     NostrConnectRequest { request_id: Arc<EventId> },
@@ -25,6 +32,12 @@ pub enum EventHandled {
     RelayList,
     KeyAgentSignerOffering,
     VerifiedKeyAgents,
+    Device,
+    AppSettings,
+    Payees,
+    Capabilities { public_key: Arc<PublicKey> },
+    Plugin { kind: u64, policy_id: Arc<EventId> },
+    VaultMetadata { policy_id: Arc<EventId> },
 }
 
 impl From<EventHandledSdk> for EventHandled {
@@ -33,6 +46,9 @@ impl From<EventHandledSdk> for EventHandled {
             EventHandledSdk::SharedKey(id) => Self::SharedKey {
                 event_id: Arc::new(id.into()),
             },
+            EventHandledSdk::SharedKeyRequest(id) => Self::SharedKeyRequest {
+                request_id: Arc::new(id.into()),
+            },
             EventHandledSdk::Policy(id) => Self::Policy {
                 policy_id: Arc::new(id.into()),
             },
@@ -42,6 +58,9 @@ impl From<EventHandledSdk> for EventHandled {
             EventHandledSdk::Approval { proposal_id } => Self::Approval {
                 proposal_id: Arc::new(proposal_id.into()),
             },
+            EventHandledSdk::ProposalComment { proposal_id } => Self::ProposalComment {
+                proposal_id: Arc::new(proposal_id.into()),
+            },
             EventHandledSdk::CompletedProposal(id) => Self::CompletedProposal {
                 completed_proposal_id: Arc::new(id.into()),
             },
@@ -54,6 +73,15 @@ impl From<EventHandledSdk> for EventHandled {
             EventHandledSdk::SharedSigner(id) => Self::SharedSigner {
                 shared_signer_id: Arc::new(id.into()),
             },
+            EventHandledSdk::SignerRequest(id) => Self::SignerRequest {
+                request_id: Arc::new(id.into()),
+            },
+            EventHandledSdk::PaymentRequest(id) => Self::PaymentRequest {
+                request_id: Arc::new(id.into()),
+            },
+            EventHandledSdk::RecurringPayment(id) => Self::RecurringPayment {
+                policy_id: Arc::new(id.into()),
+            },
             EventHandledSdk::Contacts => Self::Contacts,
             EventHandledSdk::Metadata(pk) => Self::Metadata {
                 public_key: Arc::new(pk.into()),
@@ -66,16 +94,62 @@ impl From<EventHandledSdk> for EventHandled {
             EventHandledSdk::RelayList => Self::RelayList,
             EventHandledSdk::KeyAgentSignerOffering => Self::KeyAgentSignerOffering,
             EventHandledSdk::VerifiedKeyAgents => Self::VerifiedKeyAgents,
+            EventHandledSdk::Device => Self::Device,
+            EventHandledSdk::AppSettings => Self::AppSettings,
+            EventHandledSdk::Payees => Self::Payees,
+            EventHandledSdk::Capabilities(pk) => Self::Capabilities {
+                public_key: Arc::new(pk.into()),
+            },
+            EventHandledSdk::Plugin { kind, vault_id } => Self::Plugin {
+                kind: kind.as_u64(),
+                policy_id: Arc::new(vault_id.into()),
+            },
+            EventHandledSdk::VaultMetadata(id) => Self::VaultMetadata {
+                policy_id: Arc::new(id.into()),
+            },
         }
     }
 }
 
 #[derive(Enum)]
 pub enum Message {
-    EventHandledMsg { event: EventHandled },
-    WalletSyncCompleted { policy_id: Arc<EventId> },
+    EventHandledMsg {
+        event: EventHandled,
+    },
+    WalletSyncCompleted {
+        policy_id: Arc<EventId>,
+    },
+    WalletSyncFailed {
+        policy_id: Arc<EventId>,
+        error: String,
+    },
     BlockHeightUpdated,
     MempoolFeesUpdated,
+    DeviceRevoked,
+    BalanceMismatch {
+        policy_id: Arc<EventId>,
+    },
+    UnconfirmedTx {
+        policy_id: Arc<EventId>,
+        txid: String,
+    },
+    RelayAdmissionChanged {
+        relay_url: String,
+    },
+    CompromisedKeys {
+        event_id: Arc<EventId>,
+        fingerprints: Vec<String>,
+    },
+    RecoveryPathMatured {
+        policy_id: Arc<EventId>,
+        signer_id: Arc<EventId>,
+        proposal_id: Option<Arc<EventId>>,
+    },
+    StuckTx {
+        policy_id: Arc<EventId>,
+        txid: String,
+        stuck: StuckTransaction,
+    },
 }
 
 impl From<MessageSdk> for Message {
@@ -87,8 +161,48 @@ impl From<MessageSdk> for Message {
             MessageSdk::WalletSyncCompleted(policy_id) => Self::WalletSyncCompleted {
                 policy_id: Arc::new(policy_id.into()),
             },
+            MessageSdk::WalletSyncFailed { policy_id, error } => Self::WalletSyncFailed {
+                policy_id: Arc::new(policy_id.into()),
+                error,
+            },
             MessageSdk::BlockHeightUpdated => Self::BlockHeightUpdated,
             MessageSdk::MempoolFeesUpdated(..) => Self::MempoolFeesUpdated,
+            MessageSdk::DeviceRevoked => Self::DeviceRevoked,
+            MessageSdk::BalanceMismatch(policy_id) => Self::BalanceMismatch {
+                policy_id: Arc::new(policy_id.into()),
+            },
+            MessageSdk::UnconfirmedTx { policy_id, txid } => Self::UnconfirmedTx {
+                policy_id: Arc::new(policy_id.into()),
+                txid: txid.to_string(),
+            },
+            MessageSdk::RelayAdmissionChanged(relay_url) => Self::RelayAdmissionChanged {
+                relay_url: relay_url.to_string(),
+            },
+            MessageSdk::CompromisedKeys {
+                event_id,
+                fingerprints,
+            } => Self::CompromisedKeys {
+                event_id: Arc::new(event_id.into()),
+                fingerprints: fingerprints.iter().map(|f| f.to_string()).collect(),
+            },
+            MessageSdk::RecoveryPathMatured {
+                policy_id,
+                signer_id,
+                proposal_id,
+            } => Self::RecoveryPathMatured {
+                policy_id: Arc::new(policy_id.into()),
+                signer_id: Arc::new(signer_id.into()),
+                proposal_id: proposal_id.map(|id| Arc::new(id.into())),
+            },
+            MessageSdk::StuckTx {
+                policy_id,
+                txid,
+                stuck,
+            } => Self::StuckTx {
+                policy_id: Arc::new(policy_id.into()),
+                txid: txid.to_string(),
+                stuck: stuck.into(),
+            },
         }
     }
 }
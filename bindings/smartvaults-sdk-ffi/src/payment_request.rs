@@ -0,0 +1,130 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use nostr_ffi::{EventId, PublicKey, Timestamp};
+use nostr_sdk_ffi::profile::Profile;
+use smartvaults_sdk::protocol::v1;
+use smartvaults_sdk::types;
+use uniffi::{Object, Record};
+
+#[derive(Object)]
+pub struct GetPaymentRequest {
+    inner: types::GetPaymentRequest,
+}
+
+impl From<types::GetPaymentRequest> for GetPaymentRequest {
+    fn from(inner: types::GetPaymentRequest) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl GetPaymentRequest {
+    pub fn request_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.request_id.into())
+    }
+
+    pub fn policy_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.policy_id.into())
+    }
+
+    pub fn user(&self) -> Arc<Profile> {
+        Arc::new(self.inner.user.clone().into())
+    }
+
+    pub fn address(&self) -> String {
+        self.inner.request.address().assume_checked().to_string()
+    }
+
+    /// Amount in SAT
+    pub fn amount(&self) -> u64 {
+        self.inner.request.amount()
+    }
+
+    pub fn description(&self) -> String {
+        self.inner.request.description()
+    }
+
+    /// A proposal of the vault already pays the request
+    pub fn fulfilled(&self) -> bool {
+        self.inner.fulfilled
+    }
+
+    pub fn timestamp(&self) -> Arc<Timestamp> {
+        Arc::new(self.inner.timestamp.into())
+    }
+}
+
+#[derive(Object)]
+pub struct GetRecurringPayment {
+    inner: types::GetRecurringPayment,
+}
+
+impl From<types::GetRecurringPayment> for GetRecurringPayment {
+    fn from(inner: types::GetRecurringPayment) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl GetRecurringPayment {
+    pub fn payment_id(&self) -> String {
+        self.inner.payment_id.clone()
+    }
+
+    pub fn policy_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.policy_id.into())
+    }
+
+    /// Member that created the payment (the one that creates the proposals)
+    pub fn owner(&self) -> Arc<PublicKey> {
+        Arc::new(self.inner.owner.into())
+    }
+
+    pub fn address(&self) -> String {
+        self.inner.payment.address().assume_checked().to_string()
+    }
+
+    /// Amount in SAT
+    pub fn amount(&self) -> u64 {
+        self.inner.payment.amount()
+    }
+
+    pub fn description(&self) -> String {
+        self.inner.payment.description()
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.inner.payment.interval()
+    }
+
+    pub fn next_due(&self) -> Arc<Timestamp> {
+        Arc::new(self.inner.next_due.into())
+    }
+}
+
+/// Saved payee
+#[derive(Record)]
+pub struct Payee {
+    pub name: String,
+    /// Address or descriptor
+    pub destination: String,
+    /// Default description of the proposals to this payee
+    pub memo: Option<String>,
+    /// A new address is derived for each payment
+    pub is_descriptor: bool,
+}
+
+impl From<v1::Payee> for Payee {
+    fn from(value: v1::Payee) -> Self {
+        Self {
+            name: value.name().to_string(),
+            destination: value.destination().to_string(),
+            memo: value.memo().map(String::from),
+            is_descriptor: value.is_descriptor(),
+        }
+    }
+}
@@ -4,9 +4,11 @@
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 
 use nostr_ffi::{EventId, Timestamp};
 use smartvaults_sdk::core::{policy, SelectableCondition};
+use smartvaults_sdk::protocol::v1;
 use smartvaults_sdk::protocol::v1::util::SerdeSer;
 use smartvaults_sdk::types;
 use uniffi::{Enum, Object, Record};
@@ -53,6 +55,18 @@ impl Policy {
         self.inner.as_descriptor().to_string()
     }
 
+    /// Short human-comparable fingerprint of the descriptor (8 words)
+    ///
+    /// Compare it with the other cosigners before depositing.
+    pub fn descriptor_fingerprint(&self) -> Result<String> {
+        Ok(self.inner.descriptor_fingerprint()?)
+    }
+
+    /// Coldcard registration file, to verify the addresses and co-sign offline
+    pub fn coldcard_config(&self) -> Result<String> {
+        Ok(self.inner.coldcard_config()?)
+    }
+
     pub fn satisfiable_item(&self) -> Result<String> {
         Ok(self.inner.satisfiable_item()?.as_json())
     }
@@ -131,6 +145,83 @@ impl GetPolicy {
     pub fn last_sync(&self) -> Arc<Timestamp> {
         Arc::new(self.inner.last_sync.into())
     }
+
+    /// Last event of the vault received from relays
+    pub fn last_event(&self) -> Option<Arc<Timestamp>> {
+        self.inner.last_event.map(|t| Arc::new(t.into()))
+    }
+
+    /// Never synced, or timechain sync or relay events too old
+    pub fn stale(&self) -> bool {
+        self.inner.stale
+    }
+
+    pub fn pinned(&self) -> bool {
+        self.inner.pinned
+    }
+}
+
+#[derive(Enum)]
+pub enum VaultSyncStatus {
+    NotLoaded,
+    NeverSynced,
+    Syncing,
+    Synced { last_sync: Arc<Timestamp> },
+}
+
+impl From<types::VaultSyncStatus> for VaultSyncStatus {
+    fn from(value: types::VaultSyncStatus) -> Self {
+        match value {
+            types::VaultSyncStatus::NotLoaded => Self::NotLoaded,
+            types::VaultSyncStatus::NeverSynced => Self::NeverSynced,
+            types::VaultSyncStatus::Syncing => Self::Syncing,
+            types::VaultSyncStatus::Synced(last_sync) => Self::Synced {
+                last_sync: Arc::new(last_sync.into()),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Object)]
+pub struct GetVaultSummary {
+    inner: types::GetVaultSummary,
+}
+
+impl From<types::GetVaultSummary> for GetVaultSummary {
+    fn from(inner: types::GetVaultSummary) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl GetVaultSummary {
+    pub fn policy_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.policy_id.into())
+    }
+
+    pub fn name(&self) -> String {
+        self.inner.name.clone()
+    }
+
+    pub fn balance(&self) -> Arc<Balance> {
+        Arc::new(self.inner.balance.clone().into())
+    }
+
+    pub fn pending_proposals(&self) -> u64 {
+        self.inner.pending_proposals as u64
+    }
+
+    pub fn last_activity(&self) -> Option<Arc<Timestamp>> {
+        self.inner.last_activity.map(|t| Arc::new(t.into()))
+    }
+
+    pub fn sync_status(&self) -> VaultSyncStatus {
+        self.inner.sync_status.into()
+    }
+
+    pub fn pinned(&self) -> bool {
+        self.inner.pinned
+    }
 }
 
 #[derive(Enum)]
@@ -197,3 +288,152 @@ impl From<policy::PolicyPathSelector> for PolicyPathSelector {
         }
     }
 }
+
+/// Max amount spent by a vault in a rolling period (or by a single proposal)
+#[derive(Record)]
+pub struct VelocityLimit {
+    /// Amount in SAT
+    pub amount: u64,
+    /// Period in seconds (`0` for a max amount per proposal)
+    pub period: u64,
+}
+
+impl From<VelocityLimit> for v1::VelocityLimit {
+    fn from(value: VelocityLimit) -> Self {
+        Self::new(value.amount, Duration::from_secs(value.period))
+    }
+}
+
+impl From<v1::VelocityLimit> for VelocityLimit {
+    fn from(value: v1::VelocityLimit) -> Self {
+        Self {
+            amount: value.amount(),
+            period: value.period().as_secs(),
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct VelocityLimitStatus {
+    pub limit: VelocityLimit,
+    /// Amount (SAT) sent in the last period, fees included
+    pub spent: u64,
+    /// Amount (SAT) that can still be spent in the current period
+    pub remaining: u64,
+}
+
+impl From<types::GetVelocityLimitStatus> for VelocityLimitStatus {
+    fn from(value: types::GetVelocityLimitStatus) -> Self {
+        Self {
+            remaining: value.remaining(),
+            limit: value.limit.into(),
+            spent: value.spent,
+        }
+    }
+}
+
+/// What to do with the proposals to destinations not in the whitelist
+#[derive(Enum)]
+pub enum WhitelistMode {
+    Disabled,
+    /// The approvers must be asked for an extra confirmation
+    Confirm,
+    /// The proposals are refused
+    Enforce,
+}
+
+impl From<WhitelistMode> for v1::WhitelistMode {
+    fn from(value: WhitelistMode) -> Self {
+        match value {
+            WhitelistMode::Disabled => Self::Disabled,
+            WhitelistMode::Confirm => Self::Confirm,
+            WhitelistMode::Enforce => Self::Enforce,
+        }
+    }
+}
+
+impl From<v1::WhitelistMode> for WhitelistMode {
+    fn from(value: v1::WhitelistMode) -> Self {
+        match value {
+            v1::WhitelistMode::Disabled => Self::Disabled,
+            v1::WhitelistMode::Confirm => Self::Confirm,
+            v1::WhitelistMode::Enforce => Self::Enforce,
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct WhitelistedDestination {
+    /// Address or descriptor
+    pub destination: String,
+    pub label: Option<String>,
+}
+
+impl From<v1::WhitelistedDestination> for WhitelistedDestination {
+    fn from(value: v1::WhitelistedDestination) -> Self {
+        Self {
+            destination: value.destination().to_string(),
+            label: value.label().map(String::from),
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct Whitelist {
+    pub mode: WhitelistMode,
+    pub destinations: Vec<WhitelistedDestination>,
+}
+
+impl From<v1::Whitelist> for Whitelist {
+    fn from(value: v1::Whitelist) -> Self {
+        Self {
+            mode: value.mode.into(),
+            destinations: value.destinations.into_iter().map(|d| d.into()).collect(),
+        }
+    }
+}
+
+/// Timelocked spending path of a vault that a signer of the user can satisfy alone
+#[derive(Record)]
+pub struct RecoveryPath {
+    pub signer_id: Arc<EventId>,
+    pub policy_path: HashMap<String, Vec<u64>>,
+    /// The timelock is matured: the vault funds can be swept with this path
+    pub matured: bool,
+}
+
+impl From<types::GetRecoveryPath> for RecoveryPath {
+    fn from(value: types::GetRecoveryPath) -> Self {
+        Self {
+            signer_id: Arc::new(value.signer_id.into()),
+            policy_path: value
+                .policy_path
+                .into_iter()
+                .map(|(k, v)| (k, v.into_iter().map(|i| i as u64).collect()))
+                .collect(),
+            matured: value.matured,
+        }
+    }
+}
+
+/// Vault watched for the maturity of its recovery paths
+#[derive(Record)]
+pub struct RecoveryWatch {
+    pub policy_id: Arc<EventId>,
+    /// Sweep the funds to this address as soon as a path matures
+    pub sweep_address: Option<String>,
+    /// Last automatic sweep proposal
+    pub proposal_id: Option<Arc<EventId>>,
+    pub timestamp: Arc<Timestamp>,
+}
+
+impl From<types::RecoveryWatch> for RecoveryWatch {
+    fn from(value: types::RecoveryWatch) -> Self {
+        Self {
+            policy_id: Arc::new(value.policy_id.into()),
+            sweep_address: value.sweep_address.map(|a| a.assume_checked().to_string()),
+            proposal_id: value.proposal_id.map(|id| Arc::new(id.into())),
+            timestamp: Arc::new(value.timestamp.into()),
+        }
+    }
+}
@@ -7,7 +7,7 @@ use nostr_ffi::{EventId, Timestamp};
 use nostr_sdk_ffi::profile::Profile;
 use smartvaults_sdk::core::proposal;
 use smartvaults_sdk::types;
-use uniffi::{Enum, Object};
+use uniffi::{Enum, Object, Record};
 
 #[derive(Clone, Enum)]
 pub enum ApprovedProposal {
@@ -32,6 +32,25 @@ impl From<proposal::ApprovedProposal> for ApprovedProposal {
     }
 }
 
+/// Approval result for a single proposal of a batch
+#[derive(Record)]
+pub struct BulkApprovalResult {
+    pub proposal_id: Arc<EventId>,
+    /// Approval event, if approved
+    pub approval_id: Option<Arc<EventId>>,
+    pub error: Option<String>,
+}
+
+impl From<types::BulkApprovalResult> for BulkApprovalResult {
+    fn from(value: types::BulkApprovalResult) -> Self {
+        Self {
+            proposal_id: Arc::new(value.proposal_id.into()),
+            approval_id: value.approval_id.map(|id| Arc::new(id.into())),
+            error: value.error,
+        }
+    }
+}
+
 #[derive(Object)]
 pub struct GetApproval {
     inner: types::GetApproval,
@@ -60,4 +79,49 @@ impl GetApproval {
     pub fn timestamp(&self) -> Arc<Timestamp> {
         Arc::new(self.inner.timestamp.into())
     }
+
+    /// Fingerprints of the signers used to approve
+    pub fn signers(&self) -> Vec<String> {
+        self.inner.signers.iter().map(|f| f.to_string()).collect()
+    }
+
+    /// `false` if expired or not matching the proposal transaction
+    pub fn valid(&self) -> bool {
+        self.inner.valid
+    }
+
+    /// `true` if the approval overrides the spending velocity limits of the vault
+    pub fn velocity_override(&self) -> bool {
+        self.inner.velocity_override
+    }
+}
+
+#[derive(Object)]
+pub struct GetProposalComment {
+    inner: types::GetProposalComment,
+}
+
+impl From<types::GetProposalComment> for GetProposalComment {
+    fn from(inner: types::GetProposalComment) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl GetProposalComment {
+    pub fn comment_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.comment_id.into())
+    }
+
+    pub fn user(&self) -> Arc<Profile> {
+        Arc::new(self.inner.user.clone().into())
+    }
+
+    pub fn message(&self) -> String {
+        self.inner.message.clone()
+    }
+
+    pub fn timestamp(&self) -> Arc<Timestamp> {
+        Arc::new(self.inner.timestamp.into())
+    }
 }
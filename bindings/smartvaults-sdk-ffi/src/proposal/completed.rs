@@ -8,13 +8,14 @@ use smartvaults_sdk::core::proposal;
 use smartvaults_sdk::types;
 use uniffi::{Enum, Object};
 
-use super::Period;
+use super::{PaymentMemo, Period};
 
 #[derive(Enum)]
 pub enum CompletedProposal {
     Spending {
         txid: String,
         description: String,
+        memo: Option<PaymentMemo>,
     },
     KeyAgentPayment {
         txid: String,
@@ -32,9 +33,14 @@ pub enum CompletedProposal {
 impl From<proposal::CompletedProposal> for CompletedProposal {
     fn from(value: proposal::CompletedProposal) -> Self {
         match value {
-            proposal::CompletedProposal::Spending { description, tx } => Self::Spending {
+            proposal::CompletedProposal::Spending {
+                description,
+                tx,
+                memo,
+            } => Self::Spending {
                 txid: tx.txid().to_string(),
                 description,
+                memo: memo.map(|m| m.into()),
             },
             proposal::CompletedProposal::KeyAgentPayment {
                 tx,
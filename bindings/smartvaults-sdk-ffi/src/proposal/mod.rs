@@ -2,18 +2,25 @@
 // Distributed under the MIT software license
 
 use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use nostr_ffi::{EventId, Timestamp};
+use smartvaults_sdk::core::bitcoin::hashes::sha256;
 use smartvaults_sdk::core::proposal;
 use smartvaults_sdk::types;
 use uniffi::{Enum, Object, Record};
 
+use crate::error::{Result, SmartVaultsError};
+use crate::OutPoint;
+
 mod approved;
 mod completed;
+mod quorum;
 
-pub use self::approved::{ApprovedProposal, GetApproval};
+pub use self::approved::{ApprovedProposal, BulkApprovalResult, GetApproval, GetProposalComment};
 pub use self::completed::{CompletedProposal, GetCompletedProposal};
+pub use self::quorum::{GetQuorumStatus, QuorumPath};
 
 #[derive(Record)]
 pub struct Period {
@@ -30,6 +37,156 @@ impl From<Period> for proposal::Period {
     }
 }
 
+#[derive(Record)]
+pub struct Recipient {
+    pub address: String,
+    pub amount: u64,
+}
+
+impl From<proposal::Recipient> for Recipient {
+    fn from(value: proposal::Recipient) -> Self {
+        Self {
+            address: value.address.assume_checked().to_string(),
+            amount: value.amount,
+        }
+    }
+}
+
+/// Payment records (i.e. travel rule data) encrypted with a spending proposal
+#[derive(Record)]
+pub struct PaymentMemo {
+    pub beneficiary_name: Option<String>,
+    /// External reference (i.e. the invoice number)
+    pub reference_id: Option<String>,
+    /// SHA256 of the invoice document (hex)
+    pub invoice_hash: Option<String>,
+}
+
+impl From<proposal::PaymentMemo> for PaymentMemo {
+    fn from(value: proposal::PaymentMemo) -> Self {
+        Self {
+            beneficiary_name: value.beneficiary_name,
+            reference_id: value.reference_id,
+            invoice_hash: value.invoice_hash.map(|h| h.to_string()),
+        }
+    }
+}
+
+impl TryFrom<PaymentMemo> for proposal::PaymentMemo {
+    type Error = SmartVaultsError;
+
+    fn try_from(value: PaymentMemo) -> Result<Self> {
+        Ok(Self {
+            beneficiary_name: value.beneficiary_name,
+            reference_id: value.reference_id,
+            invoice_hash: match value.invoice_hash {
+                Some(hash) => Some(sha256::Hash::from_str(&hash)?),
+                None => None,
+            },
+        })
+    }
+}
+
+/// Recipient of a batched spending proposal
+#[derive(Record)]
+pub struct SpendRecipient {
+    pub address: String,
+    /// Amount in SAT
+    pub amount: u64,
+    /// Address label, shared with the vault members
+    pub label: Option<String>,
+}
+
+/// Spending transaction built without creating the proposal
+#[derive(Record)]
+pub struct SpendPreview {
+    /// Amount paid to the recipient in SAT (the drained amount if sending all the funds)
+    pub amount: u64,
+    /// Fee in SAT
+    pub fee: u64,
+    /// Change in SAT
+    pub change: u64,
+    pub inputs: Vec<Arc<OutPoint>>,
+    /// Sum of the selected UTXOs in SAT
+    pub total_input: u64,
+    pub vsize: u64,
+    /// Estimated vsize of the signed transaction
+    pub estimated_vsize: u64,
+    /// Fee rate (sat/vB) of the signed transaction
+    pub effective_fee_rate: f64,
+    /// Policy path used (`None` if the policy has a single spending path)
+    pub policy_path: Option<HashMap<String, Vec<u64>>>,
+}
+
+impl From<types::SpendPreview> for SpendPreview {
+    fn from(value: types::SpendPreview) -> Self {
+        Self {
+            amount: value.amount(),
+            fee: value.fee,
+            change: value.change,
+            total_input: value.total_input(),
+            effective_fee_rate: value.effective_fee_rate(),
+            inputs: value
+                .inputs
+                .into_iter()
+                .map(|(outpoint, _)| Arc::new(outpoint.into()))
+                .collect(),
+            vsize: value.vsize as u64,
+            estimated_vsize: value.estimated_vsize as u64,
+            policy_path: value.policy_path.map(|path| {
+                path.into_iter()
+                    .map(|(k, v)| (k, v.into_iter().map(|n| n as u64).collect()))
+                    .collect()
+            }),
+        }
+    }
+}
+
+/// Changes from the replaced proposal
+#[derive(Record)]
+pub struct ProposalDiff {
+    /// Amount change (SAT)
+    pub amount: i64,
+    /// Fee change (SAT), if known
+    pub fee: Option<i64>,
+    pub added_recipients: Vec<Recipient>,
+    pub removed_recipients: Vec<Recipient>,
+    pub added_inputs: Vec<Arc<OutPoint>>,
+    pub removed_inputs: Vec<Arc<OutPoint>>,
+    /// New description, if changed
+    pub description: Option<String>,
+}
+
+impl From<proposal::ProposalDiff> for ProposalDiff {
+    fn from(value: proposal::ProposalDiff) -> Self {
+        Self {
+            amount: value.amount,
+            fee: value.fee,
+            added_recipients: value
+                .added_recipients
+                .into_iter()
+                .map(|r| r.into())
+                .collect(),
+            removed_recipients: value
+                .removed_recipients
+                .into_iter()
+                .map(|r| r.into())
+                .collect(),
+            added_inputs: value
+                .added_inputs
+                .into_iter()
+                .map(|o| Arc::new(o.into()))
+                .collect(),
+            removed_inputs: value
+                .removed_inputs
+                .into_iter()
+                .map(|o| Arc::new(o.into()))
+                .collect(),
+            description: value.description,
+        }
+    }
+}
+
 #[derive(Enum)]
 pub enum Proposal {
     Spending {
@@ -39,6 +196,9 @@ pub enum Proposal {
         description: String,
         psbt: String,
         policy_path: Option<HashMap<String, Vec<u64>>>,
+        memo: Option<PaymentMemo>,
+        /// Data embedded in the OP_RETURN output
+        op_return: Option<Vec<u8>>,
     },
     KeyAgentPayment {
         descriptor: String,
@@ -58,6 +218,7 @@ pub enum Proposal {
 
 impl From<proposal::Proposal> for Proposal {
     fn from(value: proposal::Proposal) -> Self {
+        let op_return: Option<Vec<u8>> = value.op_return_data();
         match value {
             proposal::Proposal::Spending {
                 descriptor,
@@ -66,6 +227,8 @@ impl From<proposal::Proposal> for Proposal {
                 description,
                 psbt,
                 policy_path,
+                memo,
+                ..
             } => Self::Spending {
                 descriptor: descriptor.to_string(),
                 to_address: to_address.assume_checked().to_string(),
@@ -77,6 +240,8 @@ impl From<proposal::Proposal> for Proposal {
                         .map(|(k, v)| (k.to_string(), v.into_iter().map(|x| x as u64).collect()))
                         .collect()
                 }),
+                memo: memo.map(|m| m.into()),
+                op_return,
             },
             proposal::Proposal::KeyAgentPayment {
                 descriptor,
@@ -143,4 +308,34 @@ impl GetProposal {
     pub fn is_signed(&self) -> bool {
         self.inner.signed
     }
+
+    /// Check if the PSBT doesn't match what the proposal declares
+    pub fn is_suspicious(&self) -> bool {
+        self.inner.is_suspicious()
+    }
+
+    /// Inconsistencies found validating the PSBT
+    pub fn issues(&self) -> Vec<String> {
+        self.inner.issues.iter().map(|i| i.to_string()).collect()
+    }
+
+    /// Check if the proposal supersedes an amended or replaced one
+    pub fn is_amended(&self) -> bool {
+        self.inner.is_amended()
+    }
+
+    /// Proposal replaced by this one
+    pub fn replaces(&self) -> Option<Arc<EventId>> {
+        self.inner.replaces.map(|id| Arc::new(id.into()))
+    }
+
+    /// Changes from the replaced proposal
+    pub fn diff(&self) -> Option<ProposalDiff> {
+        self.inner.diff.clone().map(|d| d.into())
+    }
+
+    /// The proposal is deleted if not finalized by this time
+    pub fn expires_at(&self) -> Option<Arc<Timestamp>> {
+        self.inner.expires_at.map(|t| Arc::new(t.into()))
+    }
 }
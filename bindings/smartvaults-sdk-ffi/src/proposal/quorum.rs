@@ -0,0 +1,88 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use nostr_ffi::EventId;
+use nostr_sdk_ffi::profile::Profile;
+use smartvaults_sdk::core::bitcoin::bip32::Fingerprint;
+use smartvaults_sdk::core::policy;
+use smartvaults_sdk::types;
+use uniffi::{Object, Record};
+
+/// Approval progress of a spending path
+#[derive(Record)]
+pub struct QuorumPath {
+    pub path: String,
+    /// Fingerprints of the signers involved in the path
+    pub signers: Vec<String>,
+    /// Fingerprints of the involved signers that already signed
+    pub signed: Vec<String>,
+    /// Fingerprints of the involved signers that not signed yet
+    pub missing: Vec<String>,
+    /// Signatures needed to satisfy the path (missing if not satisfiable only with signatures)
+    pub threshold: Option<u64>,
+    /// Signatures still needed to satisfy the path
+    pub required: Option<u64>,
+    pub timelock: bool,
+}
+
+impl From<policy::QuorumPath> for QuorumPath {
+    fn from(value: policy::QuorumPath) -> Self {
+        Self {
+            missing: value.missing().iter().map(|f| f.to_string()).collect(),
+            signers: value.signers.iter().map(|f| f.to_string()).collect(),
+            signed: value.signed.iter().map(|f| f.to_string()).collect(),
+            threshold: value.threshold.map(|t| t as u64),
+            required: value.required.map(|r| r as u64),
+            timelock: value.timelock,
+            path: value.path,
+        }
+    }
+}
+
+#[derive(Object)]
+pub struct GetQuorumStatus {
+    inner: types::GetQuorumStatus,
+}
+
+impl From<types::GetQuorumStatus> for GetQuorumStatus {
+    fn from(inner: types::GetQuorumStatus) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl GetQuorumStatus {
+    pub fn proposal_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.proposal_id.into())
+    }
+
+    pub fn policy_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.policy_id.into())
+    }
+
+    /// Number of valid approvals
+    pub fn approvals(&self) -> u64 {
+        self.inner.approvals as u64
+    }
+
+    /// Spending paths the proposal can be finalized with
+    pub fn paths(&self) -> Vec<QuorumPath> {
+        self.inner.paths.iter().cloned().map(|p| p.into()).collect()
+    }
+
+    /// Owner of the signer, if known
+    pub fn owner(&self, fingerprint: String) -> Option<Arc<Profile>> {
+        let fingerprint = Fingerprint::from_str(&fingerprint).ok()?;
+        self.inner
+            .owners
+            .get(&fingerprint)
+            .map(|owner| Arc::new(owner.clone().into()))
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.inner.ready
+    }
+}
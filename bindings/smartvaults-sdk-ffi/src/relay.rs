@@ -0,0 +1,100 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::sync::Arc;
+
+use nostr_ffi::Timestamp;
+use smartvaults_sdk::nostr::Url;
+use smartvaults_sdk::types;
+use uniffi::{Enum, Record};
+
+#[derive(Enum)]
+pub enum RelayAdmissionStatus {
+    Open,
+    PaymentRequired,
+    Admitted,
+    Rejected,
+}
+
+impl From<types::RelayAdmissionStatus> for RelayAdmissionStatus {
+    fn from(value: types::RelayAdmissionStatus) -> Self {
+        match value {
+            types::RelayAdmissionStatus::Open => Self::Open,
+            types::RelayAdmissionStatus::PaymentRequired => Self::PaymentRequired,
+            types::RelayAdmissionStatus::Admitted => Self::Admitted,
+            types::RelayAdmissionStatus::Rejected => Self::Rejected,
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct RelayAdmission {
+    pub relay_url: String,
+    pub status: RelayAdmissionStatus,
+    pub payments_url: Option<String>,
+    /// Last message from the relay (i.e. the rejection reason)
+    pub message: Option<String>,
+    pub timestamp: Arc<Timestamp>,
+}
+
+impl From<(Url, types::RelayAdmission)> for RelayAdmission {
+    fn from((url, admission): (Url, types::RelayAdmission)) -> Self {
+        Self {
+            relay_url: url.to_string(),
+            status: admission.status.into(),
+            payments_url: admission.payments_url,
+            message: admission.message,
+            timestamp: Arc::new(admission.timestamp.into()),
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct RelayFee {
+    pub amount: u64,
+    pub unit: String,
+    /// Subscription period (seconds)
+    pub period: Option<u64>,
+}
+
+impl From<types::RelayFee> for RelayFee {
+    fn from(value: types::RelayFee) -> Self {
+        Self {
+            amount: value.amount,
+            unit: value.unit,
+            period: value.period,
+        }
+    }
+}
+
+/// Terms of a relay (NIP-11)
+#[derive(Record)]
+pub struct RelayTerms {
+    pub name: Option<String>,
+    pub payment_required: bool,
+    pub auth_required: bool,
+    pub restricted_writes: bool,
+    pub payments_url: Option<String>,
+    pub admission_fees: Vec<RelayFee>,
+    pub subscription_fees: Vec<RelayFee>,
+    pub posting_policy: Option<String>,
+}
+
+impl From<types::RelayTerms> for RelayTerms {
+    fn from(value: types::RelayTerms) -> Self {
+        Self {
+            name: value.name,
+            payment_required: value.payment_required,
+            auth_required: value.auth_required,
+            restricted_writes: value.restricted_writes,
+            payments_url: value.payments_url,
+            admission_fees: value.admission_fees.into_iter().map(|f| f.into()).collect(),
+            subscription_fees: value
+                .subscription_fees
+                .into_iter()
+                .map(|f| f.into())
+                .collect(),
+            posting_policy: value.posting_policy,
+        }
+    }
+}
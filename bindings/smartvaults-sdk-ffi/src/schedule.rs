@@ -0,0 +1,54 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::time::Duration;
+
+use smartvaults_sdk::client;
+use uniffi::{Enum, Record};
+
+#[derive(Enum)]
+pub enum NetworkCondition {
+    Unmetered,
+    Metered,
+    Offline,
+}
+
+impl From<NetworkCondition> for client::NetworkCondition {
+    fn from(value: NetworkCondition) -> Self {
+        match value {
+            NetworkCondition::Unmetered => Self::Unmetered,
+            NetworkCondition::Metered => Self::Metered,
+            NetworkCondition::Offline => Self::Offline,
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct SyncSchedule {
+    /// Seconds between sync rounds on unmetered networks
+    pub unmetered_interval: u64,
+    /// Seconds between sync rounds on metered networks
+    pub metered_interval: u64,
+    /// Pause the background sync while the battery saver is enabled
+    pub pause_on_battery_saver: bool,
+}
+
+impl From<client::SyncSchedule> for SyncSchedule {
+    fn from(value: client::SyncSchedule) -> Self {
+        Self {
+            unmetered_interval: value.unmetered_interval.as_secs(),
+            metered_interval: value.metered_interval.as_secs(),
+            pause_on_battery_saver: value.pause_on_battery_saver,
+        }
+    }
+}
+
+impl From<SyncSchedule> for client::SyncSchedule {
+    fn from(value: SyncSchedule) -> Self {
+        Self {
+            unmetered_interval: Duration::from_secs(value.unmetered_interval),
+            metered_interval: Duration::from_secs(value.metered_interval),
+            pause_on_battery_saver: value.pause_on_battery_saver,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use nostr_ffi::EventId;
+use smartvaults_sdk::protocol::v1;
+use uniffi::Record;
+
+use crate::error::{Result, SmartVaultsError};
+
+#[derive(Record)]
+pub struct AppSettings {
+    pub hide_balances: bool,
+    pub pinned_vaults: Vec<Arc<EventId>>,
+    /// Other app-defined preferences (JSON values)
+    pub extra: HashMap<String, String>,
+}
+
+impl From<v1::AppSettings> for AppSettings {
+    fn from(value: v1::AppSettings) -> Self {
+        Self {
+            hide_balances: value.hide_balances,
+            pinned_vaults: value
+                .pinned_vaults
+                .into_iter()
+                .map(|id| Arc::new(id.into()))
+                .collect(),
+            extra: value
+                .extra
+                .into_iter()
+                .map(|(k, v)| (k, v.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<AppSettings> for v1::AppSettings {
+    type Error = SmartVaultsError;
+
+    fn try_from(value: AppSettings) -> Result<Self> {
+        let mut settings = Self {
+            hide_balances: value.hide_balances,
+            pinned_vaults: value.pinned_vaults.into_iter().map(|id| **id).collect(),
+            ..Default::default()
+        };
+        for (key, json) in value.extra.into_iter() {
+            let json = json
+                .parse()
+                .map_err(|e| SmartVaultsError::generic(format!("invalid `{key}` value: {e}")))?;
+            settings.extra.insert(key, json);
+        }
+        Ok(settings)
+    }
+}
@@ -1,14 +1,15 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
+use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use nostr_ffi::EventId;
+use nostr_ffi::{EventId, Timestamp};
 use nostr_sdk_ffi::profile::Profile;
 use smartvaults_sdk::core::signer;
 use smartvaults_sdk::types;
-use uniffi::{Enum, Object};
+use uniffi::{Enum, Object, Record};
 
 use crate::error::Result;
 use crate::Descriptor;
@@ -52,6 +53,97 @@ impl GetSigner {
     }
 }
 
+#[derive(Object)]
+pub struct GetSignerUsage {
+    inner: types::GetSignerUsage,
+}
+
+impl From<types::GetSignerUsage> for GetSignerUsage {
+    fn from(inner: types::GetSignerUsage) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl GetSignerUsage {
+    pub fn signer_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.signer_id.into())
+    }
+
+    /// Vaults whose descriptor contains the signer keys
+    pub fn vaults(&self) -> Vec<Arc<EventId>> {
+        self.inner
+            .vaults
+            .iter()
+            .map(|id| Arc::new((*id).into()))
+            .collect()
+    }
+
+    /// Approvals signed with the signer (approval ID -> proposal ID)
+    pub fn approvals(&self) -> HashMap<String, Arc<EventId>> {
+        self.inner
+            .approvals
+            .iter()
+            .map(|(approval_id, proposal_id)| {
+                (approval_id.to_hex(), Arc::new((*proposal_id).into()))
+            })
+            .collect()
+    }
+
+    pub fn is_used(&self) -> bool {
+        self.inner.is_used()
+    }
+}
+
+#[derive(Object)]
+pub struct GetSignerRequest {
+    inner: types::GetSignerRequest,
+}
+
+impl From<types::GetSignerRequest> for GetSignerRequest {
+    fn from(inner: types::GetSignerRequest) -> Self {
+        Self { inner }
+    }
+}
+
+#[uniffi::export]
+impl GetSignerRequest {
+    pub fn request_id(&self) -> Arc<EventId> {
+        Arc::new(self.inner.request_id.into())
+    }
+
+    pub fn user(&self) -> Arc<Profile> {
+        Arc::new(self.inner.user.clone().into())
+    }
+
+    /// BIP-43 purpose of the requested signer (i.e. `86` for taproot)
+    pub fn purpose(&self) -> u32 {
+        self.inner.request.purpose()
+    }
+
+    pub fn account(&self) -> Option<u32> {
+        self.inner.request.account()
+    }
+
+    pub fn message(&self) -> Option<String> {
+        self.inner.request.message()
+    }
+
+    /// Own signers matching the request
+    pub fn matching(&self) -> Vec<Arc<GetSigner>> {
+        self.inner
+            .matching
+            .iter()
+            .cloned()
+            .map(|s| Arc::new(s.into()))
+            .collect()
+    }
+
+    pub fn timestamp(&self) -> u64 {
+        self.inner.timestamp.as_u64()
+    }
+}
+
 #[derive(Object)]
 pub struct Signer {
     inner: signer::Signer,
@@ -141,3 +233,24 @@ impl SharedSigner {
         Ok(Arc::new(self.inner.descriptor_public_key()?.into()))
     }
 }
+
+/// Key (master fingerprint) marked as compromised
+#[derive(Record)]
+pub struct CompromisedKey {
+    pub fingerprint: String,
+    pub reason: Option<String>,
+    /// Synced across the user devices
+    pub shared: bool,
+    pub timestamp: Arc<Timestamp>,
+}
+
+impl From<types::CompromisedKey> for CompromisedKey {
+    fn from(value: types::CompromisedKey) -> Self {
+        Self {
+            fingerprint: value.fingerprint.to_string(),
+            reason: value.reason,
+            shared: value.shared,
+            timestamp: Arc::new(value.timestamp.into()),
+        }
+    }
+}
@@ -7,9 +7,10 @@ use nostr_ffi::{EventId, Timestamp};
 use smartvaults_sdk::core::bdk;
 use smartvaults_sdk::core::bdk::chain::ConfirmationTime;
 use smartvaults_sdk::core::bitcoin::{self, Address};
+use smartvaults_sdk::core::types::Priority;
 use smartvaults_sdk::manager::wallet;
 use smartvaults_sdk::types::{self, GetUtxo};
-use uniffi::{Object, Record};
+use uniffi::{Enum, Object, Record};
 
 use crate::error::Result;
 use crate::Network;
@@ -70,6 +71,16 @@ impl Utxo {
     pub fn label(&self) -> Option<String> {
         self.inner.label.clone()
     }
+
+    /// Frozen by a pending proposal or by the user
+    pub fn frozen(&self) -> bool {
+        self.inner.frozen
+    }
+
+    /// Frozen by the user
+    pub fn user_frozen(&self) -> bool {
+        self.inner.user_frozen
+    }
 }
 
 #[derive(Object)]
@@ -193,6 +204,32 @@ impl Transaction {
     }
 }
 
+/// Transaction-level options of a spending transaction
+#[derive(Record)]
+pub struct TxOptions {
+    /// Set the nLockTime to the current height, to discourage fee sniping
+    pub anti_fee_sniping: bool,
+    /// Signal replaceability (BIP125)
+    pub rbf: bool,
+    /// Custom nLockTime (consensus encoded)
+    pub locktime: Option<u32>,
+    /// Custom nSequence of the inputs (i.e. to satisfy an `older()` condition)
+    pub sequence: Option<u32>,
+}
+
+impl From<TxOptions> for smartvaults_sdk::core::TxOptions {
+    fn from(value: TxOptions) -> Self {
+        Self {
+            anti_fee_sniping: value.anti_fee_sniping,
+            rbf: value.rbf,
+            locktime: value
+                .locktime
+                .map(bitcoin::absolute::LockTime::from_consensus),
+            sequence: value.sequence.map(bitcoin::Sequence),
+        }
+    }
+}
+
 #[derive(Record)]
 pub struct Fee {
     /// Fee amount (SAT)
@@ -210,6 +247,90 @@ impl From<wallet::Fee> for Fee {
     }
 }
 
+#[derive(Enum)]
+pub enum StuckTxAction {
+    Rbf,
+    Cpfp,
+}
+
+impl From<types::StuckTxAction> for StuckTxAction {
+    fn from(value: types::StuckTxAction) -> Self {
+        match value {
+            types::StuckTxAction::Rbf => Self::Rbf,
+            types::StuckTxAction::Cpfp => Self::Cpfp,
+        }
+    }
+}
+
+#[derive(Record)]
+pub struct StuckTransaction {
+    /// Fee rate (sat/vByte)
+    pub fee_rate: f32,
+    /// Low priority mempool fee rate (sat/vByte)
+    pub mempool_min_fee_rate: f32,
+    /// Suggested actions to speed up the confirmation
+    pub actions: Vec<StuckTxAction>,
+}
+
+impl From<types::StuckTransaction> for StuckTransaction {
+    fn from(value: types::StuckTransaction) -> Self {
+        Self {
+            fee_rate: value.fee_rate.as_sat_per_vb(),
+            mempool_min_fee_rate: value.mempool_min_fee_rate.as_sat_per_vb(),
+            actions: value.actions.into_iter().map(|a| a.into()).collect(),
+        }
+    }
+}
+
+/// Pair of the mempool fee histogram
+#[derive(Record)]
+pub struct FeeHistogramEntry {
+    /// Fee rate (sat/vByte)
+    pub fee_rate: f64,
+    /// Size (vBytes) of the transactions paying at least `fee_rate` and less than the fee rate
+    /// of the previous entry
+    pub vsize: u64,
+}
+
+#[derive(Record)]
+pub struct MempoolSnapshot {
+    /// Highest fee rate first
+    pub fee_histogram: Vec<FeeHistogramEntry>,
+    /// Total size of the mempool (vBytes)
+    pub vsize: u64,
+    /// Fee rate (sat/vByte) needed to confirm within 1 block
+    pub high: f32,
+    /// Fee rate (sat/vByte) needed to confirm within 6 blocks
+    pub medium: f32,
+    /// Fee rate (sat/vByte) needed to confirm within 12 blocks
+    pub low: f32,
+    pub timestamp: Arc<Timestamp>,
+}
+
+impl From<types::MempoolSnapshot> for MempoolSnapshot {
+    fn from(value: types::MempoolSnapshot) -> Self {
+        let target = |priority: Priority| -> f32 {
+            value
+                .targets
+                .get(&priority)
+                .map(|rate| rate.as_sat_per_vb())
+                .unwrap_or(1.0)
+        };
+        Self {
+            high: target(Priority::High),
+            medium: target(Priority::Medium),
+            low: target(Priority::Low),
+            fee_histogram: value
+                .fee_histogram
+                .into_iter()
+                .map(|(fee_rate, vsize)| FeeHistogramEntry { fee_rate, vsize })
+                .collect(),
+            vsize: value.vsize,
+            timestamp: Arc::new(value.timestamp.into()),
+        }
+    }
+}
+
 #[derive(Object)]
 pub struct TransactionDetails {
     inner: wallet::TransactionDetails,
@@ -286,4 +407,14 @@ impl GetTransaction {
     pub fn block_explorer(&self) -> Option<String> {
         self.inner.block_explorer.clone()
     }
+
+    /// The confirmation was verified against the SPV header chain
+    pub fn is_confirmation_verified(&self) -> bool {
+        self.inner.is_confirmation_verified
+    }
+
+    /// Unconfirmed transaction paying less than the current mempool minimum
+    pub fn stuck(&self) -> Option<StuckTransaction> {
+        self.inner.stuck.clone().map(|s| s.into())
+    }
 }
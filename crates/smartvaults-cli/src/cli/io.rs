@@ -25,6 +25,17 @@ pub fn get_confirmation_password() -> Result<String> {
     Ok(Password::new().with_prompt("Confirm password").interact()?)
 }
 
+pub fn get_pin() -> Result<String> {
+    Ok(Password::new().with_prompt("Approval PIN").interact()?)
+}
+
+pub fn get_new_pin() -> Result<String> {
+    Ok(Password::new()
+        .with_prompt("New approval PIN")
+        .with_confirmation("Confirm approval PIN", "PINs not match")
+        .interact()?)
+}
+
 pub fn ask<S>(prompt: S) -> Result<bool>
 where
     S: Into<String> + std::marker::Copy,
@@ -6,21 +6,26 @@
 use std::net::SocketAddr;
 use std::path::PathBuf;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use smartvaults_sdk::config::{CrossCheckBackend, FeeProvider};
 use smartvaults_sdk::core::bips::bip32::Fingerprint;
 use smartvaults_sdk::core::bitcoin::address::NetworkUnchecked;
-use smartvaults_sdk::core::bitcoin::Address;
+use smartvaults_sdk::core::bitcoin::hashes::sha256;
+use smartvaults_sdk::core::bitcoin::{Address, OutPoint};
 use smartvaults_sdk::core::miniscript::{Descriptor, DescriptorPublicKey};
 use smartvaults_sdk::nostr::prelude::NostrConnectURI;
 use smartvaults_sdk::nostr::{EventId, PublicKey, Url};
-use smartvaults_sdk::protocol::v1::{BasisPoints, DeviceType, LabelData, Price, Temperature};
+use smartvaults_sdk::protocol::v1::{
+    BasisPoints, Currency, DeviceType, LabelData, Price, Temperature, WhitelistMode,
+};
 
 pub mod batch;
 pub mod io;
 pub mod parser;
 mod types;
 
-use self::types::{CliNetwork, CliWordCount};
+pub use self::types::ProofFormat;
+use self::types::{CliNetwork, CliRecipient, CliVelocityLimit, CliWordCount, Payout};
 
 #[derive(Debug, Parser)]
 #[clap(author, version, about, long_about)]
@@ -73,6 +78,38 @@ pub enum CliCommand {
     },
     /// List keychains
     List,
+    /// Verify an exported Proof Of Reserve (no keychain required)
+    #[command(arg_required_else_help = true)]
+    VerifyProof {
+        /// Proof file
+        #[arg(required = true)]
+        path: PathBuf,
+        /// Message (required if the file contains only the PSBT)
+        #[arg(long)]
+        message: Option<String>,
+    },
+    /// Export keychains, databases and config to an encrypted bundle (to migrate to another device)
+    #[command(arg_required_else_help = true)]
+    ExportBundle {
+        /// Output file
+        #[arg(required = true)]
+        path: PathBuf,
+        /// Include the keychains
+        #[arg(long)]
+        keychains: bool,
+    },
+    /// Import a bundle exported from another device
+    #[command(arg_required_else_help = true)]
+    ImportBundle {
+        /// Bundle file
+        #[arg(required = true)]
+        path: PathBuf,
+    },
+    /// Combine, decode and finalize PSBTs exchanged offline (no keychain required)
+    Psbt {
+        #[command(subcommand)]
+        command: PsbtCommand,
+    },
     /// Config
     Config {
         #[command(subcommand)]
@@ -80,6 +117,37 @@ pub enum CliCommand {
     },
 }
 
+#[derive(Debug, Subcommand)]
+pub enum PsbtCommand {
+    /// Combine the PSBTs of the same transaction (i.e. signed by different signers)
+    #[command(arg_required_else_help = true)]
+    Combine {
+        /// PSBT files (binary or base64)
+        #[arg(required = true, num_args = 2..)]
+        paths: Vec<PathBuf>,
+        /// Output file (print the base64 PSBT if not set)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Decode a PSBT
+    #[command(arg_required_else_help = true)]
+    Decode {
+        /// PSBT file (binary or base64)
+        #[arg(required = true)]
+        path: PathBuf,
+    },
+    /// Finalize a fully signed PSBT and print the raw transaction
+    #[command(arg_required_else_help = true)]
+    Finalize {
+        /// PSBT file (binary or base64)
+        #[arg(required = true)]
+        path: PathBuf,
+        /// Output file (print the transaction hex if not set)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommand {
     /// View config
@@ -96,6 +164,27 @@ pub enum ConfigCommand {
         /// Block explorer
         #[clap(long)]
         block_explorer: Option<Url>,
+        /// Esplora endpoint used to broadcast transactions (can be repeated)
+        #[clap(long)]
+        esplora_endpoint: Vec<Url>,
+        /// Additional electrum server used to broadcast transactions (can be repeated)
+        #[clap(long)]
+        broadcast_electrum_server: Vec<String>,
+        /// Bitcoin P2P node used to broadcast transactions
+        #[clap(long)]
+        broadcast_peer: Option<SocketAddr>,
+        /// Max number of inputs of a spending proposal
+        #[clap(long)]
+        max_proposal_inputs: Option<usize>,
+        /// Fee estimation provider (`electrum`, `mempool:<url>`, `core:<url>` or `static:<high>,<medium>,<low>`)
+        #[clap(long)]
+        fee_provider: Option<FeeProvider>,
+        /// Backend used to cross-check the vault balances (`electrum:<endpoint>` or `esplora:<url>`)
+        #[clap(long)]
+        cross_check_backend: Option<CrossCheckBackend>,
+        /// Max number of vaults synced at the same time
+        #[clap(long)]
+        max_concurrent_syncs: Option<usize>,
     },
 
     /// Unset
@@ -110,6 +199,30 @@ pub enum ConfigCommand {
         /// Block explorer
         #[clap(long)]
         block_explorer: bool,
+        /// Remove an esplora endpoint
+        #[clap(long)]
+        esplora_endpoint: Vec<Url>,
+        /// Remove all the esplora endpoints
+        #[clap(long)]
+        esplora_endpoints: bool,
+        /// Remove an additional electrum server used to broadcast transactions
+        #[clap(long)]
+        broadcast_electrum_server: Vec<String>,
+        /// Remove all the additional electrum servers used to broadcast transactions
+        #[clap(long)]
+        broadcast_electrum_servers: bool,
+        /// Disable the broadcast through the P2P node
+        #[clap(long)]
+        broadcast_peer: bool,
+        /// Reset the fee estimation provider to electrum
+        #[clap(long)]
+        fee_provider: bool,
+        /// Disable the balance cross-check
+        #[clap(long)]
+        cross_check_backend: bool,
+        /// Reset the max number of vaults synced at the same time to default
+        #[clap(long)]
+        max_concurrent_syncs: bool,
     },
 }
 
@@ -124,6 +237,10 @@ pub enum SettingCommand {
     },
     /// Change keychain password
     ChangePassword,
+    /// Set the approval PIN of this device
+    SetApprovalPin,
+    /// Remove the approval PIN of this device
+    RemoveApprovalPin,
 }
 
 #[derive(Debug, Parser)]
@@ -132,6 +249,7 @@ pub enum Command {
     /// Inspect bitcoin and nostr keys
     Inspect,
     /// Create a spending proposal
+    #[command(allow_missing_positional = true)]
     Spend {
         /// Policy id
         #[arg(required = true)]
@@ -139,6 +257,91 @@ pub enum Command {
         /// To address
         #[arg(required = true)]
         to_address: Address<NetworkUnchecked>,
+        /// Amount in sat (or in fiat, i.e. `12.50`, if `--currency` is set)
+        #[arg(required_unless_present = "all")]
+        amount: Option<String>,
+        /// Description
+        #[arg(required = true)]
+        description: String,
+        /// Taget blocks
+        #[clap(short, long, default_value_t = 6)]
+        target_blocks: u8,
+        /// Fiat currency of the amount (i.e. USD), converted at the current price
+        #[arg(long)]
+        currency: Option<Currency>,
+        /// Additional recipient (`<address>=<amount>[=<label>]`) of a batched spending
+        #[arg(long = "to", conflicts_with = "currency")]
+        recipients: Vec<CliRecipient>,
+        /// Send all the spendable funds (minus fee), without the amount
+        #[arg(long, conflicts_with_all = ["amount", "currency", "recipients"])]
+        all: bool,
+        /// Delete the proposal if not finalized within the hours
+        #[arg(long, value_name = "HOURS", conflicts_with_all = ["currency", "all"])]
+        expires_in: Option<u64>,
+        /// Beneficiary name (i.e. travel rule records), encrypted with the proposal
+        #[arg(long, conflicts_with_all = ["currency", "all"])]
+        beneficiary: Option<String>,
+        /// Payment reference (i.e. the invoice number), encrypted with the proposal
+        #[arg(long, conflicts_with_all = ["currency", "all"])]
+        reference: Option<String>,
+        /// SHA256 of the invoice document, encrypted with the proposal
+        #[arg(long, value_name = "HASH", conflicts_with_all = ["currency", "all"])]
+        invoice_hash: Option<sha256::Hash>,
+        /// UTXO to keep out of the payment (i.e. a doxxed coin), without freezing it
+        #[arg(
+            long = "exclude-utxo",
+            value_name = "OUTPOINT",
+            conflicts_with_all = ["currency", "all"]
+        )]
+        excluded_utxos: Vec<OutPoint>,
+        /// Don't spend the UTXOs sharing the address with other UTXOs
+        #[arg(long, conflicts_with_all = ["currency", "all"])]
+        avoid_address_reuse: bool,
+        /// Data (hex, max 80 bytes) embedded in an OP_RETURN output (i.e. an invoice hash)
+        #[arg(long, value_name = "HEX", conflicts_with_all = ["currency", "all"])]
+        op_return: Option<String>,
+        /// Don't signal replaceability (RBF)
+        #[arg(long, conflicts_with_all = ["currency", "all", "sequence"])]
+        no_rbf: bool,
+        /// Don't set the nLockTime to the current height (anti-fee-sniping)
+        #[arg(long, conflicts_with_all = ["currency", "all", "locktime"])]
+        no_anti_fee_sniping: bool,
+        /// Custom nLockTime (block height or UNIX timestamp)
+        #[arg(long, conflicts_with_all = ["currency", "all"])]
+        locktime: Option<u32>,
+        /// Custom nSequence of the inputs (i.e. to satisfy the `older()` of a recovery path)
+        #[arg(long, conflicts_with_all = ["currency", "all"])]
+        sequence: Option<u32>,
+    },
+    /// Create a spending proposal (send all funds)
+    SpendAll {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// To address
+        #[arg(required = true)]
+        to_address: Address<NetworkUnchecked>,
+        /// Description
+        #[arg(required = true)]
+        description: String,
+        /// Taget blocks
+        #[clap(short, long, default_value_t = 6)]
+        target_blocks: u8,
+        /// Split in multiple proposals if the UTXOs exceed the max number of inputs
+        #[arg(long)]
+        split: bool,
+        /// Keep a reserve in the vault (sat), i.e. for future fee bumps
+        #[arg(long, conflicts_with = "split")]
+        reserve: Option<u64>,
+    },
+    /// Replace a pending spending proposal (the approvers see what changed)
+    Replace {
+        /// Proposal id
+        #[arg(required = true)]
+        proposal_id: EventId,
+        /// To address
+        #[arg(required = true)]
+        to_address: Address<NetworkUnchecked>,
         /// Amount in sat
         #[arg(required = true)]
         amount: u64,
@@ -149,17 +352,119 @@ pub enum Command {
         #[clap(short, long, default_value_t = 6)]
         target_blocks: u8,
     },
-    /// Create a spending proposal (send all funds)
-    SpendAll {
+    /// Amend a pending spending proposal (the approvals must be given again)
+    Amend {
+        /// Proposal id
+        #[arg(required = true)]
+        proposal_id: EventId,
+        /// New amount in sat
+        #[arg(long)]
+        amount: Option<u64>,
+        /// New description
+        #[arg(long)]
+        description: Option<String>,
+        /// Taget blocks
+        #[clap(short, long, default_value_t = 6)]
+        target_blocks: u8,
+    },
+    /// Replace a pending spending proposal with the same one at a higher fee rate
+    BumpFee {
+        /// Proposal id
+        #[arg(required = true)]
+        proposal_id: EventId,
+        /// Fee rate (sat/vByte)
+        #[arg(required = true)]
+        fee_rate: f32,
+    },
+    /// Sweep the vault funds with a matured recovery (timelocked) path of a signer
+    Recover {
         /// Policy id
         #[arg(required = true)]
         policy_id: EventId,
+        /// Signer id
+        #[arg(required = true)]
+        signer_id: EventId,
         /// To address
         #[arg(required = true)]
         to_address: Address<NetworkUnchecked>,
+        /// Taget blocks
+        #[clap(short, long, default_value_t = 6)]
+        target_blocks: u8,
+    },
+    /// Watch a vault for the maturity of its recovery paths (notify or sweep automatically)
+    WatchRecovery {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Sweep the funds to this address as soon as a path matures
+        #[arg(long)]
+        sweep_to: Option<Address<NetworkUnchecked>>,
+        /// Stop watching the vault
+        #[arg(long, conflicts_with = "sweep_to")]
+        stop: bool,
+    },
+    /// Create a proposal that pays each member their share, based on contributions
+    SettleUp {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Amount in sat to split among members
+        #[arg(required = true)]
+        amount: u64,
+        /// Member payout address (`<public-key>=<address>`, can be repeated)
+        #[arg(long, required = true)]
+        payout: Vec<Payout>,
         /// Description
+        #[arg(long, default_value = "Settle up")]
+        description: String,
+        /// Taget blocks
+        #[clap(short, long, default_value_t = 6)]
+        target_blocks: u8,
+        /// Only print the computed shares, without create the proposal
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Ask the other members of the vault to be paid (i.e. reimbursement)
+    RequestPayment {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Address
+        #[arg(required = true)]
+        address: Address<NetworkUnchecked>,
+        /// Amount in sat
+        #[arg(required = true)]
+        amount: u64,
+        /// Reason
         #[arg(required = true)]
         description: String,
+    },
+    /// Schedule a recurring payment (a spending proposal is created at each due date)
+    SchedulePayment {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Address
+        #[arg(required = true)]
+        address: Address<NetworkUnchecked>,
+        /// Amount in sat
+        #[arg(required = true)]
+        amount: u64,
+        /// Description
+        #[arg(required = true)]
+        description: String,
+        /// Days between the payments
+        #[arg(long, value_name = "DAYS", default_value_t = 30)]
+        every: u64,
+        /// First due date as UNIX timestamp (default: now)
+        #[arg(long)]
+        start: Option<u64>,
+    },
+    /// Create a spending proposal that pays a payment request
+    FulfillRequest {
+        /// Payment request id
+        #[arg(required = true)]
+        request_id: EventId,
         /// Taget blocks
         #[clap(short, long, default_value_t = 6)]
         target_blocks: u8,
@@ -169,12 +474,46 @@ pub enum Command {
         /// Proposal id
         #[arg(required = true)]
         proposal_id: EventId,
+        /// Approve also if exceeding the spending velocity limits of the vault
+        #[arg(long)]
+        override_velocity: bool,
+        /// Approve with an external (i.e. air-gap) signer: export the PSBT, import the signed one
+        #[arg(long, value_name = "SIGNER_ID", conflicts_with = "override_velocity")]
+        signer: Option<EventId>,
+        /// Write the PSBT to sign to file (print the base64 PSBT if not set)
+        #[arg(long, value_name = "PATH", requires = "signer")]
+        export: Option<PathBuf>,
+        /// Signed PSBT file (binary or base64), instead of waiting for it
+        #[arg(long, value_name = "PATH", requires = "signer")]
+        signed: Option<PathBuf>,
+    },
+    /// Automatically approve the proposals of a vault matching the rules (testnet only)
+    #[cfg(feature = "auto-approver")]
+    AutoApprove {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Max amount in sat
+        #[arg(long)]
+        max_amount: Option<u64>,
+        /// Allowed recipient (repeatable, any if not set)
+        #[arg(long = "address")]
+        addresses: Vec<Address<NetworkUnchecked>>,
+        /// Approve also the proof of reserve proposals
+        #[arg(long)]
+        proof_of_reserve: bool,
     },
     /// Finalize proposal
     Finalize {
         /// Proposal id
         #[arg(required = true)]
         proposal_id: EventId,
+        /// Don't broadcast the transaction: print the raw transaction instead
+        #[arg(long)]
+        no_broadcast: bool,
+        /// Don't broadcast the transaction: write the raw transaction to file instead
+        #[arg(long)]
+        export_tx: Option<PathBuf>,
     },
     /// Proof of Reserve commands
     #[command(arg_required_else_help = true)]
@@ -194,6 +533,12 @@ pub enum Command {
         #[command(subcommand)]
         command: KeyAgentCommand,
     },
+    /// Saved payees commands
+    #[command(arg_required_else_help = true)]
+    Payee {
+        #[command(subcommand)]
+        command: PayeeCommand,
+    },
     /// Add
     #[command(arg_required_else_help = true)]
     Add {
@@ -218,6 +563,12 @@ pub enum Command {
         #[command(subcommand)]
         command: ShareCommand,
     },
+    /// Export
+    #[command(arg_required_else_help = true)]
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
     /// Delete
     #[command(arg_required_else_help = true)]
     Delete {
@@ -230,12 +581,48 @@ pub enum Command {
         #[command(subcommand)]
         command: SettingCommand,
     },
-    /// Rebroadcast all events to connected relays
-    Rebroadcast,
+    /// Cross-check the balance of vaults against the backend set in the config
+    CrossCheck {
+        /// Policy id
+        #[arg(required_unless_present_any = ["all", "vaults"])]
+        policy_id: Option<EventId>,
+        #[command(flatten)]
+        filter: VaultFilter,
+    },
+    /// Sync vaults with the timechain (all the vaults, without summary, if none is selected)
+    Sync {
+        #[command(flatten)]
+        filter: VaultFilter,
+    },
+    /// Rebroadcast events to connected relays (all the events if no vault is selected)
+    Rebroadcast {
+        #[command(flatten)]
+        filter: VaultFilter,
+    },
+    /// Check the connectivity of the relays and of the timechain backends
+    Doctor,
     /// Exit
     Exit,
 }
 
+/// Vaults selection of the bulk commands
+#[derive(Debug, Clone, Args)]
+pub struct VaultFilter {
+    /// Select all the vaults
+    #[arg(long, conflicts_with = "vaults")]
+    pub all: bool,
+    /// Select a vault (can be repeated)
+    #[arg(long = "vault", value_name = "VAULT_ID")]
+    pub vaults: Vec<EventId>,
+}
+
+impl VaultFilter {
+    /// Check if no vault is selected
+    pub fn is_empty(&self) -> bool {
+        !self.all && self.vaults.is_empty()
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ProofCommand {
     /// New Proof Of Reserve
@@ -253,6 +640,27 @@ pub enum ProofCommand {
         #[arg(required = true)]
         proposal_id: EventId,
     },
+    /// Export Proof Of Reserve, verifiable with external tools (i.e. bitcoin-core, bdk-cli)
+    Export {
+        /// Proposal id
+        #[arg(required = true)]
+        proposal_id: EventId,
+        /// Format
+        #[arg(long, value_enum, default_value_t = ProofFormat::Txt)]
+        format: ProofFormat,
+        /// Output file (print to stdout if not set)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Verify an exported Proof Of Reserve without using the vault wallet
+    VerifyExternal {
+        /// Proof file
+        #[arg(required = true)]
+        path: PathBuf,
+        /// Message (required if the file contains only the PSBT)
+        #[arg(long)]
+        message: Option<String>,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -332,6 +740,43 @@ pub enum KeyAgentCommand {
     ListSigners,
 }
 
+#[derive(Debug, Subcommand)]
+pub enum PayeeCommand {
+    /// Save a payee (or edit the one with the same name)
+    Add {
+        /// Name
+        #[arg(required = true)]
+        name: String,
+        /// Address, descriptor or xpub (a new address is derived for each payment)
+        #[arg(required = true)]
+        destination: String,
+        /// Default description of the proposals
+        #[arg(long)]
+        memo: Option<String>,
+    },
+    /// List payees
+    List {
+        /// Only the payees whose name starts with the prefix
+        #[arg(long)]
+        prefix: Option<String>,
+    },
+    /// Remove payee
+    Remove {
+        /// Name
+        #[arg(required = true)]
+        name: String,
+    },
+    /// Get the address of the next payment to the payee
+    Address {
+        /// Name
+        #[arg(required = true)]
+        name: String,
+        /// Mark the address as used (the next call derives a new one)
+        #[arg(long = "use")]
+        mark_used: bool,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum AddCommand {
     /// Add relay
@@ -348,6 +793,46 @@ pub enum AddCommand {
         #[arg(required = true)]
         public_key: PublicKey,
     },
+    /// Import the contacts of another Nostr identity or exported by another client
+    Contacts {
+        /// Public key of the identity to import the contact list (kind 3) from
+        #[arg(long, required_unless_present = "file", conflicts_with = "file")]
+        from: Option<PublicKey>,
+        /// Exported contact list (kind 3 event, JSON array or a public key per line)
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Import the events exported by another member or dumped from a relay (i.e. to bootstrap a
+    /// new device when the relays have pruned the old events)
+    Events {
+        /// Events archive (JSON array or an event per line)
+        #[arg(required = true)]
+        path: PathBuf,
+    },
+    /// Add an address or a descriptor to the whitelist of a vault
+    WhitelistedDestination {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Address or descriptor
+        #[arg(required = true)]
+        destination: String,
+        /// Label
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Mark a key as compromised (the vaults and shared signers using it are reported)
+    CompromisedKey {
+        /// Master fingerprint
+        #[arg(required = true)]
+        fingerprint: Fingerprint,
+        /// Reason
+        #[arg(long)]
+        reason: Option<String>,
+        /// Sync the key across my devices
+        #[arg(long)]
+        share: bool,
+    },
     /// Add policy
     Policy {
         /// Policy name
@@ -385,6 +870,51 @@ pub enum AddCommand {
     },
 }
 
+#[derive(Debug, Subcommand)]
+pub enum ExportCommand {
+    /// Export the Coldcard registration file of a vault (to verify addresses and co-sign offline)
+    ColdcardConfig {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Output file (print to stdout if not set)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Export the printable disaster-recovery kit of a vault (to recover the funds without Smart
+    /// Vaults)
+    RecoveryKit {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Output file (print to stdout if not set)
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Export the events of a vault (an event per line), i.e. to archive them independently of
+    /// the relays retention
+    Events {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Output file
+        #[arg(required = true)]
+        output: PathBuf,
+        /// Also write the decrypted content of the events (JSON) to this file
+        #[arg(long)]
+        decrypted: Option<PathBuf>,
+    },
+    /// Export the backup of vaults (`<VAULT_ID>.json` files)
+    #[command(arg_required_else_help = true)]
+    Backup {
+        #[command(flatten)]
+        filter: VaultFilter,
+        /// Output directory
+        #[arg(long, default_value = ".")]
+        output_dir: PathBuf,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum GetCommand {
     /// Get contacts list
@@ -412,16 +942,88 @@ pub enum GetCommand {
         #[arg(required = true)]
         proposal_id: EventId,
     },
+    /// Get approvals of a proposal
+    Approvals {
+        /// Proposal id
+        #[arg(required = true)]
+        proposal_id: EventId,
+    },
+    /// Get approval status (quorum progress) of a proposal
+    Status {
+        /// Proposal id
+        #[arg(required = true)]
+        proposal_id: EventId,
+    },
+    /// Get the signers still required by each spending path of a proposal
+    Quorum {
+        /// Proposal id
+        #[arg(required = true)]
+        proposal_id: EventId,
+    },
+    /// Get payment requests
+    PaymentRequests,
+    /// Get recurring payments
+    RecurringPayments,
     /// Get signers
     Signers,
+    /// Get vaults and approvals that depend on a signer
+    SignerUsage {
+        /// Signer id
+        #[arg(required = true)]
+        signer_id: EventId,
+    },
     /// Get relays
     Relays,
+    /// Get the terms of a relay (payment or admission requirements)
+    RelayTerms {
+        /// Url
+        #[arg(required = true)]
+        url: Url,
+    },
+    /// Get relays pinned to a vault
+    VaultRelays {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+    },
+    /// Get the spending velocity limits of a vault and the amount spent in the current periods
+    VelocityLimits {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+    },
+    /// Get the whitelist of the destinations of a vault
+    Whitelist {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+    },
+    /// Get keys marked as compromised
+    CompromisedKeys,
+    /// Get the recovery (timelocked) paths of a vault that a signer can satisfy alone
+    RecoveryPaths {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+    },
     /// Get addresses
     Addresses {
         /// Policy id
         #[arg(required = true)]
         policy_id: EventId,
     },
+    /// Get deposits breakdown by member
+    Contributions {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+    },
+    /// Get the client capabilities of the cosigners
+    Capabilities {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+    },
 }
 
 #[derive(Debug, Subcommand)]
@@ -452,6 +1054,41 @@ pub enum SetCommand {
         /// Label
         #[arg(required = true)]
         text: String,
+        /// Claim the deposit as own contribution to the vault
+        #[arg(long)]
+        mine: bool,
+    },
+    /// Mark a relay as admitted (i.e. after paying the admission fee)
+    RelayAdmitted {
+        /// Url
+        #[arg(required = true)]
+        url: Url,
+    },
+    /// Pin relays to a vault (its events are always published to and fetched from them)
+    VaultRelays {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Relay urls (none to unpin all)
+        relays: Vec<Url>,
+    },
+    /// Set the spending velocity limits of a vault (i.e. `100000000/7d` or `10000000/proposal`,
+    /// none to remove all)
+    VelocityLimits {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Limits (`<amount>/<days>d`, `<amount>/<hours>h` or `<amount>/proposal`)
+        limits: Vec<CliVelocityLimit>,
+    },
+    /// Set what to do with the proposals to destinations not in the whitelist of a vault
+    WhitelistMode {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Mode (`disabled`, `confirm` or `enforce`)
+        #[arg(required = true)]
+        mode: WhitelistMode,
     },
 }
 
@@ -502,6 +1139,9 @@ pub enum DeleteCommand {
         /// Signer id
         #[arg(required = true)]
         signer_id: EventId,
+        /// Delete also if used by a vault
+        #[arg(long)]
+        force: bool,
     },
     /// Revoke shared signer by id
     SharedSigner {
@@ -509,6 +1149,31 @@ pub enum DeleteCommand {
         #[arg(required = true)]
         shared_signer_id: EventId,
     },
+    /// Cancel recurring payment by id
+    RecurringPayment {
+        /// Recurring payment id
+        #[arg(required = true)]
+        payment_id: String,
+    },
     /// Clear cache
-    Cache,
+    Cache {
+        /// Reset only the cache of this vault
+        #[arg(long)]
+        vault_id: Option<EventId>,
+    },
+    /// Remove an address or a descriptor from the whitelist of a vault
+    WhitelistedDestination {
+        /// Policy id
+        #[arg(required = true)]
+        policy_id: EventId,
+        /// Address or descriptor
+        #[arg(required = true)]
+        destination: String,
+    },
+    /// Unmark a compromised key
+    CompromisedKey {
+        /// Master fingerprint
+        #[arg(required = true)]
+        fingerprint: Fingerprint,
+    },
 }
@@ -1,9 +1,16 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
+use std::str::FromStr;
+use std::time::Duration;
+
 use clap::ValueEnum;
-use smartvaults_sdk::core::bitcoin::Network;
+use smartvaults_sdk::core::bitcoin::address::NetworkUnchecked;
+use smartvaults_sdk::core::bitcoin::{Address, Network};
 use smartvaults_sdk::core::types::WordCount;
+use smartvaults_sdk::nostr::PublicKey;
+use smartvaults_sdk::protocol::v1::VelocityLimit;
+use smartvaults_sdk::types::SpendRecipient;
 
 #[derive(Debug, Clone, ValueEnum)]
 pub enum CliNetwork {
@@ -43,3 +50,109 @@ impl From<CliWordCount> for WordCount {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ProofFormat {
+    /// Self-contained package (network, message, descriptor and PSBT)
+    Txt,
+    /// Base64 PSBT only (the message must be shared separately)
+    Psbt,
+}
+
+/// Member payout address (format: `<public-key>=<address>`)
+#[derive(Debug, Clone)]
+pub struct Payout {
+    pub public_key: PublicKey,
+    pub address: Address<NetworkUnchecked>,
+}
+
+impl FromStr for Payout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (public_key, address) = s
+            .split_once('=')
+            .ok_or_else(|| String::from("expected <public-key>=<address>"))?;
+        Ok(Self {
+            public_key: PublicKey::from_str(public_key).map_err(|e| e.to_string())?,
+            address: Address::from_str(address).map_err(|e| e.to_string())?,
+        })
+    }
+}
+
+/// Additional spending recipient (format: `<address>=<amount>[=<label>]`)
+#[derive(Debug, Clone)]
+pub struct CliRecipient {
+    pub address: Address<NetworkUnchecked>,
+    /// Amount in SAT
+    pub amount: u64,
+    pub label: Option<String>,
+}
+
+impl FromStr for CliRecipient {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut split = s.splitn(3, '=');
+        let (address, amount) = match (split.next(), split.next()) {
+            (Some(address), Some(amount)) => (address, amount),
+            _ => return Err(String::from("expected <address>=<amount>[=<label>]")),
+        };
+        Ok(Self {
+            address: Address::from_str(address).map_err(|e| e.to_string())?,
+            amount: u64::from_str(amount).map_err(|e| e.to_string())?,
+            label: split.next().map(String::from),
+        })
+    }
+}
+
+impl From<CliRecipient> for SpendRecipient {
+    fn from(value: CliRecipient) -> Self {
+        Self {
+            address: value.address,
+            amount: value.amount,
+            label: value.label,
+        }
+    }
+}
+
+/// Spending velocity limit (format: `<amount>/<days>d` or `<amount>/<hours>h`, i.e. `100000000/7d`,
+/// or `<amount>/proposal` for a max amount per proposal)
+#[derive(Debug, Clone, Copy)]
+pub struct CliVelocityLimit(VelocityLimit);
+
+impl FromStr for CliVelocityLimit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (amount, period) = s.split_once('/').ok_or_else(|| {
+            String::from("expected <amount>/<days>d, <amount>/<hours>h or <amount>/proposal")
+        })?;
+        let amount: u64 = u64::from_str(amount).map_err(|e| e.to_string())?;
+        if period == "proposal" {
+            return Ok(Self(VelocityLimit::per_proposal(amount)));
+        }
+        let hours: u64 = match (period.strip_suffix('d'), period.strip_suffix('h')) {
+            (Some(days), _) => u64::from_str(days).map_err(|e| e.to_string())? * 24,
+            (_, Some(hours)) => u64::from_str(hours).map_err(|e| e.to_string())?,
+            _ => {
+                return Err(String::from(
+                    "period must end with `d` (days) or `h` (hours)",
+                ))
+            }
+        };
+        if hours == 0 {
+            return Err(String::from("period must be greater than zero"));
+        }
+        Ok(Self(VelocityLimit::new(
+            amount,
+            Duration::from_secs(hours * 60 * 60),
+        )))
+    }
+}
+
+impl From<CliVelocityLimit> for VelocityLimit {
+    fn from(value: CliVelocityLimit) -> Self {
+        value.0
+    }
+}
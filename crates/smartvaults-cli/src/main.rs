@@ -5,23 +5,32 @@
 
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 
 use clap::Parser;
-use cli::{AddCommand, ConfigCommand, ConnectCommand, KeyAgentCommand, SetCommand};
+use cli::{AddCommand, ConfigCommand, ConnectCommand, KeyAgentCommand, PsbtCommand, SetCommand};
 use rustyline::error::ReadlineError;
 use rustyline::DefaultEditor;
-use smartvaults_sdk::config::Config;
+#[cfg(feature = "auto-approver")]
+use smartvaults_sdk::client::AutoApproverRules;
+use smartvaults_sdk::config::{Config, FeeProvider};
 use smartvaults_sdk::core::bips::bip39::Mnemonic;
-use smartvaults_sdk::core::bitcoin::Network;
-use smartvaults_sdk::core::signer::Signer;
+use smartvaults_sdk::core::bitcoin::absolute::LockTime;
+use smartvaults_sdk::core::bitcoin::consensus::encode::serialize_hex;
+use smartvaults_sdk::core::bitcoin::hashes::hex::FromHex;
+use smartvaults_sdk::core::bitcoin::psbt::PartiallySignedTransaction;
+use smartvaults_sdk::core::bitcoin::{Network, Sequence};
+use smartvaults_sdk::core::sandbox;
+use smartvaults_sdk::core::signer::{Signer, SignerType};
 use smartvaults_sdk::core::types::Priority;
-use smartvaults_sdk::core::{Amount, CompletedProposal, FeeRate, Keychain, Result};
-use smartvaults_sdk::nostr::{EventId, Metadata};
-use smartvaults_sdk::protocol::v1::{Label, SignerOffering};
-use smartvaults_sdk::types::{GetPolicy, GetProposal};
+use smartvaults_sdk::core::{
+    Amount, CompletedProposal, FeeRate, Keychain, PaymentMemo, Result, TxOptions,
+};
+use smartvaults_sdk::nostr::{EventId, Metadata, Timestamp};
+use smartvaults_sdk::protocol::v1::{Label, Payee, SignerOffering};
+use smartvaults_sdk::types::{GetPolicy, GetProposal, SpendOptions, SpendRecipient};
 use smartvaults_sdk::util::format;
 use smartvaults_sdk::{logger, SmartVaults};
 
@@ -30,8 +39,8 @@ mod util;
 
 use crate::cli::batch::BatchCommand;
 use crate::cli::{
-    io, Cli, CliCommand, Command, DeleteCommand, GetCommand, ProofCommand, SettingCommand,
-    ShareCommand,
+    io, Cli, CliCommand, Command, DeleteCommand, ExportCommand, GetCommand, PayeeCommand,
+    ProofCommand, ProofFormat, SettingCommand, ShareCommand, VaultFilter,
 };
 
 fn base_path() -> Result<PathBuf> {
@@ -202,6 +211,67 @@ async fn run() -> Result<()> {
             }
             Ok(())
         }
+        CliCommand::VerifyProof { path, message } => {
+            util::verify_external_proof(path, message, network)
+        }
+        CliCommand::ExportBundle { path, keychains } => {
+            let password: String = io::get_new_password()?;
+            if password != io::get_confirmation_password()? {
+                return Err(smartvaults_sdk::Error::PasswordNotMatch.into());
+            }
+            let manifest =
+                SmartVaults::export_state_bundle(base_path, network, &path, password, keychains)?;
+            println!(
+                "Exported {} files to {}",
+                manifest.entries.len(),
+                path.display()
+            );
+            Ok(())
+        }
+        CliCommand::ImportBundle { path } => {
+            let password: String = io::get_password()?;
+            let manifest = SmartVaults::import_state_bundle(base_path, network, path, password)?;
+            for entry in manifest.entries.iter() {
+                println!("- {} ({} bytes)", entry.path, entry.size);
+            }
+            println!("Imported {} files", manifest.entries.len());
+            Ok(())
+        }
+        CliCommand::Psbt { command } => match command {
+            PsbtCommand::Combine { paths, output } => {
+                let psbts: Vec<PartiallySignedTransaction> = paths
+                    .into_iter()
+                    .map(util::read_psbt)
+                    .collect::<Result<_>>()?;
+                let psbt: PartiallySignedTransaction = sandbox::combine_psbts(psbts)?;
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, psbt.to_string())?;
+                        println!("Combined PSBT saved to {}", path.display());
+                    }
+                    None => println!("{psbt}"),
+                }
+                Ok(())
+            }
+            PsbtCommand::Decode { path } => {
+                let psbt: PartiallySignedTransaction = util::read_psbt(path)?;
+                util::print_psbt(&psbt, network);
+                Ok(())
+            }
+            PsbtCommand::Finalize { path, output } => {
+                let psbt: PartiallySignedTransaction = util::read_psbt(path)?;
+                let tx = sandbox::finalize_psbt(psbt)?;
+                let hex: String = serialize_hex(&tx);
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, hex)?;
+                        println!("Transaction {} saved to {}", tx.txid(), path.display());
+                    }
+                    None => println!("{hex}"),
+                }
+                Ok(())
+            }
+        },
         CliCommand::Config { command } => match command {
             ConfigCommand::View => {
                 let config = Config::try_from_file(base_path, network)?;
@@ -212,6 +282,13 @@ async fn run() -> Result<()> {
                 electrum_server,
                 proxy,
                 block_explorer,
+                esplora_endpoint,
+                broadcast_electrum_server,
+                broadcast_peer,
+                max_proposal_inputs,
+                fee_provider,
+                cross_check_backend,
+                max_concurrent_syncs,
             } => {
                 let config = Config::try_from_file(base_path, network)?;
 
@@ -227,6 +304,34 @@ async fn run() -> Result<()> {
                     config.set_block_explorer(Some(block_explorer)).await;
                 }
 
+                for url in esplora_endpoint.into_iter() {
+                    config.add_esplora_endpoint(url).await;
+                }
+
+                for endpoint in broadcast_electrum_server.into_iter() {
+                    config.add_broadcast_electrum_endpoint(endpoint).await?;
+                }
+
+                if let Some(peer) = broadcast_peer {
+                    config.set_broadcast_peer(Some(peer)).await;
+                }
+
+                if let Some(max_inputs) = max_proposal_inputs {
+                    config.set_max_proposal_inputs(max_inputs).await;
+                }
+
+                if let Some(fee_provider) = fee_provider {
+                    config.set_fee_provider(fee_provider).await;
+                }
+
+                if let Some(backend) = cross_check_backend {
+                    config.set_cross_check_backend(Some(backend)).await;
+                }
+
+                if let Some(max_concurrent) = max_concurrent_syncs {
+                    config.set_max_concurrent_syncs(Some(max_concurrent)).await;
+                }
+
                 config.save().await?;
 
                 Ok(())
@@ -235,6 +340,14 @@ async fn run() -> Result<()> {
                 electrum_server,
                 proxy,
                 block_explorer,
+                esplora_endpoint,
+                esplora_endpoints,
+                broadcast_electrum_server,
+                broadcast_electrum_servers,
+                broadcast_peer,
+                fee_provider,
+                cross_check_backend,
+                max_concurrent_syncs,
             } => {
                 let config = Config::try_from_file(base_path, network)?;
 
@@ -250,6 +363,38 @@ async fn run() -> Result<()> {
                     config.set_block_explorer(None).await;
                 }
 
+                for url in esplora_endpoint.iter() {
+                    config.remove_esplora_endpoint(url).await;
+                }
+
+                if esplora_endpoints {
+                    config.clear_esplora_endpoints().await;
+                }
+
+                for endpoint in broadcast_electrum_server.iter() {
+                    config.remove_broadcast_electrum_endpoint(endpoint).await?;
+                }
+
+                if broadcast_electrum_servers {
+                    config.clear_broadcast_electrum_endpoints().await;
+                }
+
+                if broadcast_peer {
+                    config.set_broadcast_peer(None).await;
+                }
+
+                if fee_provider {
+                    config.set_fee_provider(FeeProvider::default()).await;
+                }
+
+                if cross_check_backend {
+                    config.set_cross_check_backend(None).await;
+                }
+
+                if max_concurrent_syncs {
+                    config.set_max_concurrent_syncs(None).await;
+                }
+
                 config.save().await?;
 
                 Ok(())
@@ -258,6 +403,23 @@ async fn run() -> Result<()> {
     }
 }
 
+/// Vaults selected with `--all` or `--vault`
+async fn selected_vaults(client: &SmartVaults, filter: VaultFilter) -> Result<Vec<GetPolicy>> {
+    if filter.is_empty() {
+        return Err("no vault selected: use `--all` or `--vault <VAULT_ID>`".into());
+    }
+
+    if filter.all {
+        return Ok(client.get_policies().await?);
+    }
+
+    let mut vaults: Vec<GetPolicy> = Vec::with_capacity(filter.vaults.len());
+    for vault_id in filter.vaults.into_iter() {
+        vaults.push(client.get_policy_by_id(vault_id).await?);
+    }
+    Ok(vaults)
+}
+
 async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
     match command {
         Command::Inspect => {
@@ -271,10 +433,143 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
             amount,
             description,
             target_blocks,
+            currency,
+            recipients,
+            all,
+            expires_in,
+            beneficiary,
+            reference,
+            invoice_hash,
+            excluded_utxos,
+            avoid_address_reuse,
+            op_return,
+            no_rbf,
+            no_anti_fee_sniping,
+            locktime,
+            sequence,
         } => {
-            let GetProposal { proposal_id, .. } = client
-                .spend(
-                    policy_id,
+            for cosigner in client.get_incompatible_cosigners(policy_id, false).await? {
+                eprintln!(
+                    "Warning: cosigner {} may not be able to parse the proposal ({})",
+                    cosigner.public_key, cosigner.compatibility
+                );
+            }
+            let fee_rate = FeeRate::Priority(Priority::Custom(target_blocks));
+            let amount: String = amount.unwrap_or_default();
+            let expires_at: Option<Timestamp> =
+                expires_in.map(|hours| Timestamp::now() + Duration::from_secs(hours * 60 * 60));
+            let memo = PaymentMemo {
+                beneficiary_name: beneficiary,
+                reference_id: reference,
+                invoice_hash,
+            };
+            let memo: Option<PaymentMemo> = Some(memo).filter(|m| !m.is_empty());
+            let op_return: Option<Vec<u8>> =
+                op_return.map(|hex| Vec::from_hex(&hex)).transpose()?;
+            let tx_options = TxOptions {
+                anti_fee_sniping: !no_anti_fee_sniping,
+                rbf: !no_rbf,
+                locktime: locktime.map(LockTime::from_consensus),
+                sequence: sequence.map(Sequence),
+            };
+            let GetProposal {
+                proposal_id,
+                proposal,
+                ..
+            } = match currency {
+                None if all => {
+                    client
+                        .spend(
+                            policy_id,
+                            to_address,
+                            Amount::Max,
+                            description,
+                            fee_rate,
+                            None,
+                            None,
+                            false,
+                        )
+                        .await?
+                }
+                None if !recipients.is_empty()
+                    || expires_at.is_some()
+                    || memo.is_some()
+                    || !excluded_utxos.is_empty()
+                    || avoid_address_reuse
+                    || op_return.is_some()
+                    || tx_options != TxOptions::default() =>
+                {
+                    let mut list: Vec<SpendRecipient> = Vec::with_capacity(recipients.len() + 1);
+                    list.push(SpendRecipient {
+                        address: to_address,
+                        amount: u64::from_str(&amount)?,
+                        label: None,
+                    });
+                    list.extend(recipients.into_iter().map(SpendRecipient::from));
+                    client
+                        .spend_many(
+                            policy_id,
+                            list,
+                            description,
+                            fee_rate,
+                            SpendOptions {
+                                excluded_utxos,
+                                avoid_address_reuse,
+                                expires_at,
+                                memo,
+                                op_return,
+                                tx_options,
+                                ..Default::default()
+                            },
+                        )
+                        .await?
+                }
+                Some(currency) => {
+                    client
+                        .spend_fiat(
+                            policy_id,
+                            to_address,
+                            f64::from_str(&amount)?,
+                            currency,
+                            description,
+                            fee_rate,
+                            None,
+                            None,
+                            false,
+                        )
+                        .await?
+                }
+                None => {
+                    client
+                        .spend(
+                            policy_id,
+                            to_address,
+                            Amount::Custom(u64::from_str(&amount)?),
+                            description,
+                            fee_rate,
+                            None,
+                            None,
+                            false,
+                        )
+                        .await?
+                }
+            };
+            if currency.is_some() {
+                println!("Description: {}", proposal.description());
+            }
+            println!("Spending proposal {proposal_id} sent");
+            Ok(())
+        }
+        Command::Replace {
+            proposal_id,
+            to_address,
+            amount,
+            description,
+            target_blocks,
+        } => {
+            let proposal = client
+                .replace_proposal(
+                    proposal_id,
                     to_address,
                     Amount::Custom(amount),
                     description,
@@ -284,77 +579,465 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                     false,
                 )
                 .await?;
-            println!("Spending proposal {proposal_id} sent");
+            println!(
+                "Proposal {proposal_id} replaced by {}",
+                proposal.proposal_id
+            );
+            if let Some(diff) = proposal.diff {
+                util::print_proposal_diff(diff);
+            }
             Ok(())
         }
-        Command::SpendAll {
+        Command::Amend {
+            proposal_id,
+            amount,
+            description,
+            target_blocks,
+        } => {
+            let proposal = client
+                .amend_proposal(
+                    proposal_id,
+                    amount.map(Amount::Custom),
+                    description,
+                    FeeRate::Priority(Priority::Custom(target_blocks)),
+                )
+                .await?;
+            println!("Proposal {proposal_id} amended by {}", proposal.proposal_id);
+            if let Some(diff) = proposal.diff {
+                util::print_proposal_diff(diff);
+            }
+            Ok(())
+        }
+        Command::BumpFee {
+            proposal_id,
+            fee_rate,
+        } => {
+            let proposal = client
+                .bump_proposal_fee(proposal_id, FeeRate::Rate(fee_rate))
+                .await?;
+            println!(
+                "Proposal {proposal_id} replaced by {}",
+                proposal.proposal_id
+            );
+            if let Some(diff) = proposal.diff {
+                util::print_proposal_diff(diff);
+            }
+            Ok(())
+        }
+        Command::Recover {
             policy_id,
+            signer_id,
             to_address,
-            description,
             target_blocks,
         } => {
             let GetProposal { proposal_id, .. } = client
-                .spend(
+                .sweep_with_recovery_path(
                     policy_id,
+                    signer_id,
                     to_address,
-                    Amount::Max,
+                    FeeRate::Priority(Priority::Custom(target_blocks)),
+                )
+                .await?;
+            println!("Recovery sweep proposal {proposal_id} sent");
+            Ok(())
+        }
+        Command::WatchRecovery {
+            policy_id,
+            sweep_to,
+            stop,
+        } => {
+            if stop {
+                client.unwatch_recovery_paths(policy_id).await?;
+                println!("Vault {policy_id} no longer watched");
+            } else {
+                client.watch_recovery_paths(policy_id, sweep_to).await?;
+                println!("Vault {policy_id} watched for matured recovery paths");
+            }
+            Ok(())
+        }
+        Command::RequestPayment {
+            policy_id,
+            address,
+            amount,
+            description,
+        } => {
+            let request_id = client
+                .create_payment_request(policy_id, address, amount, description)
+                .await?;
+            println!("Payment request {request_id} sent");
+            Ok(())
+        }
+        Command::SchedulePayment {
+            policy_id,
+            address,
+            amount,
+            description,
+            every,
+            start,
+        } => {
+            let payment_id = client
+                .create_recurring_payment(
+                    policy_id,
+                    address,
+                    amount,
                     description,
+                    Duration::from_secs(every * 60 * 60 * 24),
+                    start.map(Timestamp::from),
+                )
+                .await?;
+            println!("Recurring payment {payment_id} scheduled");
+            Ok(())
+        }
+        Command::FulfillRequest {
+            request_id,
+            target_blocks,
+        } => {
+            let GetProposal { proposal_id, .. } = client
+                .fulfill_payment_request(
+                    request_id,
                     FeeRate::Priority(Priority::Custom(target_blocks)),
-                    None,
-                    None,
-                    false,
                 )
                 .await?;
             println!("Spending proposal {proposal_id} sent");
             Ok(())
         }
-        Command::Approve { proposal_id } => {
+        Command::SpendAll {
+            policy_id,
+            to_address,
+            description,
+            target_blocks,
+            split,
+            reserve,
+        } => {
+            let fee_rate = FeeRate::Priority(Priority::Custom(target_blocks));
+            if split {
+                let proposals: Vec<GetProposal> = client
+                    .spend_split(policy_id, to_address, description, fee_rate, None)
+                    .await?;
+                for GetProposal { proposal_id, .. } in proposals.iter() {
+                    println!("Spending proposal {proposal_id} sent");
+                }
+                println!("Spending split in {} proposals", proposals.len());
+            } else {
+                let GetProposal { proposal_id, .. } = client
+                    .spend(
+                        policy_id,
+                        to_address,
+                        match reserve {
+                            Some(reserve) => Amount::MaxWithReserve(reserve),
+                            None => Amount::Max,
+                        },
+                        description,
+                        fee_rate,
+                        None,
+                        None,
+                        false,
+                    )
+                    .await?;
+                println!("Spending proposal {proposal_id} sent");
+            }
+            Ok(())
+        }
+        Command::SettleUp {
+            policy_id,
+            amount,
+            payout,
+            description,
+            target_blocks,
+            dry_run,
+        } => {
+            let shares = client.compute_settlement(policy_id, amount).await?;
+            for (public_key, share) in shares.iter() {
+                println!("- {public_key}: {} sat", format::number(*share));
+            }
+
+            if !dry_run {
+                let payouts = payout
+                    .into_iter()
+                    .map(|p| (p.public_key, p.address))
+                    .collect();
+                let GetProposal { proposal_id, .. } = client
+                    .settle_up(
+                        policy_id,
+                        amount,
+                        payouts,
+                        description,
+                        FeeRate::Priority(Priority::Custom(target_blocks)),
+                        None,
+                    )
+                    .await?;
+                println!("Settlement proposal {proposal_id} sent");
+            }
+
+            Ok(())
+        }
+        Command::Approve {
+            proposal_id,
+            override_velocity,
+            signer,
+            export,
+            signed,
+        } => {
+            for status in client
+                .get_proposal_exceeded_velocity_limits(proposal_id)
+                .await?
+                .into_iter()
+            {
+                eprintln!(
+                    "Warning: velocity limit exceeded ({}, {} sat remaining)",
+                    status.limit,
+                    status.remaining()
+                );
+            }
+            let not_whitelisted = client
+                .get_proposal_not_whitelisted_recipients(proposal_id)
+                .await?;
+            if !not_whitelisted.is_empty() {
+                for address in not_whitelisted.into_iter() {
+                    eprintln!(
+                        "Warning: destination not whitelisted: {}",
+                        address.assume_checked()
+                    );
+                }
+                if !io::ask("Approve anyway?")? {
+                    return Ok(());
+                }
+            }
+            if let Some(signer_id) = signer {
+                let signer: Signer = client.get_signer_by_id(signer_id).await?;
+                if signer.signer_type() == SignerType::Seed {
+                    return Err("seed signers approve without `--signer`".into());
+                }
+                let signed_psbt: PartiallySignedTransaction = match signed {
+                    Some(path) => util::read_psbt(path)?,
+                    None => {
+                        let GetProposal { proposal, .. } =
+                            client.get_proposal_by_id(proposal_id).await?;
+                        let psbt: PartiallySignedTransaction = proposal.psbt();
+                        match export {
+                            Some(path) => {
+                                std::fs::write(&path, psbt.to_string())?;
+                                eprintln!("PSBT saved to {}", path.display());
+                            }
+                            None => println!("{psbt}"),
+                        }
+                        eprintln!(
+                            "Sign the PSBT with {} and enter the signed PSBT path (or base64):",
+                            signer.name()
+                        );
+                        let mut line = String::new();
+                        std::io::stdin().read_line(&mut line)?;
+                        let line: &str = line.trim();
+                        if Path::new(line).is_file() {
+                            util::read_psbt(line)?
+                        } else {
+                            PartiallySignedTransaction::from_str(line)?
+                        }
+                    }
+                };
+                let pin: Option<String> = if client.has_approval_pin() {
+                    Some(io::get_pin()?)
+                } else {
+                    None
+                };
+                let (event_id, _) = client
+                    .approve_with_external_signer(
+                        proposal_id,
+                        signer_id,
+                        signed_psbt,
+                        pin.as_deref(),
+                    )
+                    .await?;
+                println!("Proposal {proposal_id} approved: {event_id}");
+                return Ok(());
+            }
             let password: String = io::get_password()?;
-            let (event_id, _) = client.approve(password, proposal_id).await?;
+            let pin: Option<String> = if client.has_approval_pin() {
+                Some(io::get_pin()?)
+            } else {
+                None
+            };
+            let (event_id, _) = if override_velocity {
+                client
+                    .approve_with_velocity_override(password, pin.as_deref(), proposal_id)
+                    .await?
+            } else {
+                client
+                    .approve(password, pin.as_deref(), proposal_id)
+                    .await?
+            };
             println!("Proposal {proposal_id} approved: {event_id}");
             Ok(())
         }
-        Command::Finalize { proposal_id } => {
-            let completed_proposal: CompletedProposal = client.finalize(proposal_id).await?;
+        #[cfg(feature = "auto-approver")]
+        Command::AutoApprove {
+            policy_id,
+            max_amount,
+            addresses,
+            proof_of_reserve,
+        } => {
+            let password: String = io::get_password()?;
+            let pin: Option<String> = if client.has_approval_pin() {
+                Some(io::get_pin()?)
+            } else {
+                None
+            };
+            let rules = AutoApproverRules {
+                max_amount,
+                addresses: addresses.into_iter().collect(),
+                proof_of_reserve,
+            };
+            client
+                .auto_approve(policy_id, password, pin.as_deref(), rules)
+                .await?;
+            println!("Auto-approver of {policy_id} started (stopped when the session is closed)");
+            Ok(())
+        }
+        Command::Finalize {
+            proposal_id,
+            no_broadcast,
+            export_tx,
+        } => {
+            let broadcast: bool = !no_broadcast && export_tx.is_none();
+            let (completed_proposal, broadcast_results) =
+                client.finalize_with_opts(proposal_id, broadcast).await?;
 
             match completed_proposal {
+                CompletedProposal::Spending { tx, .. } if !broadcast => {
+                    let txid = tx.txid();
+                    let raw: String = serialize_hex(&tx);
+
+                    match export_tx {
+                        Some(path) => {
+                            std::fs::write(&path, raw)?;
+                            println!("Transaction {txid} exported to {}", path.display());
+                        }
+                        None => println!("{raw}"),
+                    }
+
+                    println!("\nThe transaction was NOT broadcasted: broadcast it manually.");
+                }
                 CompletedProposal::Spending { tx, .. } => {
                     let txid = tx.txid();
 
                     println!("Transaction {txid} broadcasted");
 
-                    match client.network() {
-                        Network::Bitcoin => {
-                            println!("\nExplorer: https://blockstream.info/tx/{txid} \n")
-                        }
-                        Network::Testnet => {
-                            println!("\nExplorer: https://blockstream.info/testnet/tx/{txid} \n")
-                        }
-                        _ => (),
-                    };
+                    for result in broadcast_results.iter() {
+                        println!("- {}: {}", result.backend, result.status);
+                    }
+
+                    if let Ok(url) = client.config().block_explorer().await {
+                        let url: &str = url.as_str().trim_end_matches('/');
+                        println!("\nExplorer: {url}/tx/{txid} \n");
+                    }
                 }
                 CompletedProposal::KeyAgentPayment { tx, .. } => {
                     let txid = tx.txid();
 
                     println!("Key agent payment broadcasted: {txid}");
 
-                    match client.network() {
-                        Network::Bitcoin => {
-                            println!("\nExplorer: https://blockstream.info/tx/{txid} \n")
-                        }
-                        Network::Testnet => {
-                            println!("\nExplorer: https://blockstream.info/testnet/tx/{txid} \n")
-                        }
-                        _ => (),
-                    };
+                    if let Ok(url) = client.config().block_explorer().await {
+                        let url: &str = url.as_str().trim_end_matches('/');
+                        println!("\nExplorer: {url}/tx/{txid} \n");
+                    }
                 }
                 CompletedProposal::ProofOfReserve { .. } => println!("Proof of Reserve finalized"),
             };
 
             Ok(())
         }
-        Command::Rebroadcast => {
-            client.rebroadcast_all_events().await?;
+        Command::CrossCheck {
+            policy_id: None,
+            filter,
+        } => {
+            let mut results = Vec::new();
+            for vault in selected_vaults(client, filter).await?.into_iter() {
+                let res = client
+                    .cross_check_balance(vault.policy_id)
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|check| {
+                        if check.is_consistent() {
+                            Ok(String::from("balances match"))
+                        } else {
+                            Err(format!(
+                                "{} missing and {} unknown UTXOs",
+                                check.missing.len(),
+                                check.unknown.len()
+                            ))
+                        }
+                    });
+                results.push((vault, res));
+            }
+            util::print_bulk_summary(results);
+            Ok(())
+        }
+        Command::CrossCheck {
+            policy_id: Some(policy_id),
+            ..
+        } => {
+            let check = client.cross_check_balance(policy_id).await?;
+            println!("Backend: {}", check.backend);
+            println!("Local balance: {} sat", format::number(check.local));
+            println!("Backend balance: {} sat", format::number(check.remote));
+            if check.is_consistent() {
+                println!("The balances match");
+            } else {
+                for outpoint in check.missing.iter() {
+                    println!("Missing UTXO: {outpoint}");
+                }
+                for outpoint in check.unknown.iter() {
+                    println!("Unknown UTXO: {outpoint}");
+                }
+                println!(
+                    "\nWARNING: the electrum server may be hiding UTXOs (or it's out of sync)"
+                );
+            }
+            Ok(())
+        }
+        Command::Doctor => {
+            let report = client.run_diagnostics().await?;
+            util::print_diagnostics(&report);
+            if !report.is_ok() {
+                println!("\nSome relays or backends are not working: check the errors above");
+            }
+            Ok(())
+        }
+        Command::Sync { filter } => {
+            if filter.is_empty() {
+                client.sync_now().await?;
+                println!("Vaults synced");
+                return Ok(());
+            }
+
+            let mut results = Vec::new();
+            for vault in selected_vaults(client, filter).await?.into_iter() {
+                let res = client
+                    .sync_vault(vault.policy_id)
+                    .await
+                    .map(|_| String::from("synced"))
+                    .map_err(|e| e.to_string());
+                results.push((vault, res));
+            }
+            util::print_bulk_summary(results);
+            Ok(())
+        }
+        Command::Rebroadcast { filter } => {
+            if filter.is_empty() {
+                client.rebroadcast_all_events().await?;
+                return Ok(());
+            }
+
+            let mut results = Vec::new();
+            for vault in selected_vaults(client, filter).await?.into_iter() {
+                let res = client
+                    .rebroadcast_vault_events(vault.policy_id)
+                    .await
+                    .map(|count| format!("{count} events rebroadcasted"))
+                    .map_err(|e| e.to_string());
+                results.push((vault, res));
+            }
+            util::print_bulk_summary(results);
             Ok(())
         }
         Command::Proof { command } => match command {
@@ -371,6 +1054,31 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 );
                 Ok(())
             }
+            ProofCommand::Export {
+                proposal_id,
+                format,
+                output,
+            } => {
+                let package = client.export_proof_by_id(proposal_id).await?;
+                let content: String = match format {
+                    ProofFormat::Txt => package.to_string(),
+                    ProofFormat::Psbt => package.psbt.to_string(),
+                };
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, content)?;
+                        println!("Proof of Reserve exported to {}", path.display());
+                    }
+                    None => println!("{content}"),
+                }
+                if let ProofFormat::Psbt = format {
+                    println!("Message: {}", package.message);
+                }
+                Ok(())
+            }
+            ProofCommand::VerifyExternal { path, message } => {
+                util::verify_external_proof(path, message, client.network())
+            }
         },
         Command::Connect { command } => match command {
             ConnectCommand::New { uri } => {
@@ -454,6 +1162,37 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 Ok(())
             }
         },
+        Command::Payee { command } => match command {
+            PayeeCommand::Add {
+                name,
+                destination,
+                memo,
+            } => {
+                client.save_payee(name, destination, memo).await?;
+                Ok(())
+            }
+            PayeeCommand::List { prefix } => {
+                let payees: Vec<Payee> = match prefix {
+                    Some(prefix) => client.search_payees(prefix).await?,
+                    None => client.get_payees().await?.payees,
+                };
+                util::print_payees(payees);
+                Ok(())
+            }
+            PayeeCommand::Remove { name } => {
+                client.remove_payee(name).await?;
+                Ok(())
+            }
+            PayeeCommand::Address { name, mark_used } => {
+                let address = if mark_used {
+                    client.use_payee_address(name).await?
+                } else {
+                    client.get_payee_address(name).await?
+                };
+                println!("{address}");
+                Ok(())
+            }
+        },
         Command::Add { command } => match command {
             AddCommand::Relay { url, proxy } => {
                 client.add_relay(url, proxy).await?;
@@ -463,12 +1202,62 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 client.add_contact(public_key).await?;
                 Ok(())
             }
+            AddCommand::Contacts { from, file } => {
+                let imported: usize = match (from, file) {
+                    (Some(public_key), _) => {
+                        client.import_contacts_from_public_key(public_key).await?
+                    }
+                    (None, Some(path)) => client.import_contacts_from_file(path).await?,
+                    (None, None) => 0,
+                };
+                println!("{imported} new contacts imported");
+                Ok(())
+            }
+            AddCommand::Events { path } => {
+                let summary = client.import_events_from_file(path).await?;
+                println!(
+                    "{} events imported, {} already known, {} invalid, {} not handled",
+                    summary.imported, summary.duplicated, summary.invalid, summary.failed
+                );
+                Ok(())
+            }
+            AddCommand::WhitelistedDestination {
+                policy_id,
+                destination,
+                label,
+            } => {
+                let event_id = client
+                    .add_whitelisted_destination(policy_id, destination, label)
+                    .await?;
+                println!("Whitelist saved at event {event_id}");
+                Ok(())
+            }
+            AddCommand::CompromisedKey {
+                fingerprint,
+                reason,
+                share,
+            } => {
+                client
+                    .mark_key_compromised(fingerprint, reason, share)
+                    .await?;
+                println!("Key {fingerprint} marked as compromised");
+                Ok(())
+            }
             AddCommand::Policy {
                 name,
                 description,
                 descriptor,
                 nostr_pubkeys,
             } => {
+                let compromised = client.compromised_keys_in_descriptor(&descriptor).await?;
+                if !compromised.is_empty() {
+                    for fingerprint in compromised.iter() {
+                        println!("Key {fingerprint} is marked as compromised");
+                    }
+                    if !io::ask("Save the policy anyway?")? {
+                        return Ok(());
+                    }
+                }
                 let policy_id = client
                     .save_policy(name, description, descriptor, nostr_pubkeys)
                     .await?;
@@ -545,14 +1334,45 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 util::print_proposal(proposal);
                 Ok(())
             }
+            GetCommand::Approvals { proposal_id } => {
+                let approvals = client.get_approvals_by_proposal_id(proposal_id).await?;
+                util::print_approvals(approvals);
+                Ok(())
+            }
+            GetCommand::Status { proposal_id } => {
+                let status = client.get_proposal_status(proposal_id).await?;
+                util::print_proposal_status(status);
+                Ok(())
+            }
+            GetCommand::Quorum { proposal_id } => {
+                let status = client.get_quorum_status(proposal_id).await?;
+                util::print_quorum_status(status);
+                Ok(())
+            }
             GetCommand::Signers => {
                 let signers = client.get_signers().await;
                 util::print_signers(signers);
                 Ok(())
             }
+            GetCommand::PaymentRequests => {
+                let requests = client.get_payment_requests().await?;
+                util::print_payment_requests(requests);
+                Ok(())
+            }
+            GetCommand::RecurringPayments => {
+                let payments = client.get_recurring_payments().await?;
+                util::print_recurring_payments(payments);
+                Ok(())
+            }
+            GetCommand::SignerUsage { signer_id } => {
+                let usage = client.get_signer_usage(signer_id).await?;
+                util::print_signer_usage(usage);
+                Ok(())
+            }
             GetCommand::Relays => {
                 let relays = client.relays().await;
-                util::print_relays(relays).await;
+                let admission = client.get_relays_admission().await;
+                util::print_relays(relays, admission).await;
                 Ok(())
             }
             GetCommand::Addresses { policy_id } => {
@@ -561,6 +1381,80 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 util::print_addresses(addresses, balances);
                 Ok(())
             }
+            GetCommand::Contributions { policy_id } => {
+                let contributions = client.get_member_contributions(policy_id).await?;
+                util::print_contributions(contributions);
+                Ok(())
+            }
+            GetCommand::Capabilities { policy_id } => {
+                let cosigners = client.get_cosigners_capabilities(policy_id).await?;
+                util::print_cosigners_capabilities(cosigners);
+                Ok(())
+            }
+            GetCommand::RelayTerms { url } => {
+                let terms = client.get_relay_terms(url.clone()).await?;
+                let admission = client.get_relays_admission().await.remove(&url);
+                util::print_relay_terms(url, terms, admission);
+                Ok(())
+            }
+            GetCommand::VaultRelays { policy_id } => {
+                let relays = client.get_vault_relays(policy_id).await?;
+                if relays.is_empty() {
+                    println!("No relays pinned");
+                }
+                for url in relays.into_iter() {
+                    println!("{url}");
+                }
+                Ok(())
+            }
+            GetCommand::VelocityLimits { policy_id } => {
+                let limits = client.get_velocity_limits_status(policy_id).await?;
+                if limits.is_empty() {
+                    println!("No velocity limits");
+                }
+                for status in limits.into_iter() {
+                    println!(
+                        "{}: spent {} sat, {} sat remaining",
+                        status.limit,
+                        format::number(status.spent),
+                        format::number(status.remaining())
+                    );
+                }
+                Ok(())
+            }
+            GetCommand::Whitelist { policy_id } => {
+                let whitelist = client.get_vault_whitelist(policy_id).await?;
+                println!("Mode: {}", whitelist.mode);
+                for destination in whitelist.destinations.iter() {
+                    match destination.label() {
+                        Some(label) => println!("{} ({label})", destination.destination()),
+                        None => println!("{}", destination.destination()),
+                    }
+                }
+                Ok(())
+            }
+            GetCommand::RecoveryPaths { policy_id } => {
+                for path in client.get_recovery_paths(policy_id).await?.into_iter() {
+                    println!(
+                        "Signer {} - {} - {:?}",
+                        path.signer_id,
+                        if path.matured { "matured" } else { "locked" },
+                        path.policy_path
+                    );
+                }
+                Ok(())
+            }
+            GetCommand::CompromisedKeys => {
+                for key in client.get_compromised_keys().await?.into_iter() {
+                    println!(
+                        "{} - {}{}",
+                        key.fingerprint,
+                        key.reason.as_deref().unwrap_or("-"),
+                        if key.shared { " (shared)" } else { "" }
+                    );
+                }
+                Ok(())
+            }
         },
         Command::Set { command } => match command {
             SetCommand::Metadata {
@@ -586,12 +1480,44 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 policy_id,
                 data,
                 text,
+                mine,
             } => {
-                let label = Label::new(data, text);
-                let event_id = client.save_label(policy_id, label).await?;
+                let event_id = if mine {
+                    client.claim_deposit(policy_id, data, text).await?
+                } else {
+                    let label = Label::new(data, text);
+                    client.save_label(policy_id, label).await?
+                };
                 println!("Label saved at event {event_id}");
                 Ok(())
             }
+            SetCommand::RelayAdmitted { url } => {
+                client.mark_relay_admitted(url.clone()).await?;
+                println!("Relay {url} marked as admitted");
+                Ok(())
+            }
+            SetCommand::VaultRelays { policy_id, relays } => {
+                let event_id = client
+                    .set_vault_relays(policy_id, relays.iter().map(|url| url.as_str()))
+                    .await?;
+                println!("Vault relays saved at event {event_id}");
+                Ok(())
+            }
+            SetCommand::VelocityLimits { policy_id, limits } => {
+                let event_id = client
+                    .set_vault_velocity_limits(
+                        policy_id,
+                        limits.into_iter().map(|l| l.into()).collect(),
+                    )
+                    .await?;
+                println!("Velocity limits saved at event {event_id}");
+                Ok(())
+            }
+            SetCommand::WhitelistMode { policy_id, mode } => {
+                let event_id = client.set_vault_whitelist_mode(policy_id, mode).await?;
+                println!("Whitelist saved at event {event_id}");
+                Ok(())
+            }
         },
         Command::Share { command } => match command {
             ShareCommand::Signer {
@@ -608,6 +1534,56 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 Ok(())
             }
         },
+        Command::Export { command } => match command {
+            ExportCommand::ColdcardConfig { policy_id, output } => {
+                let policy: GetPolicy = client.get_policy_by_id(policy_id).await?;
+                let config: String = policy.coldcard_config()?;
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, config)?;
+                        println!("Coldcard config exported to {}", path.display());
+                    }
+                    None => println!("{config}"),
+                }
+                Ok(())
+            }
+            ExportCommand::RecoveryKit { policy_id, output } => {
+                match output {
+                    Some(path) => {
+                        client.generate_recovery_kit(policy_id, &path).await?;
+                        println!("Recovery kit exported to {}", path.display());
+                    }
+                    None => println!("{}", client.recovery_kit(policy_id).await?),
+                }
+                Ok(())
+            }
+            ExportCommand::Events {
+                policy_id,
+                output,
+                decrypted,
+            } => {
+                let count: usize = client
+                    .export_vault_events(policy_id, &output, decrypted.as_ref())
+                    .await?;
+                println!("{count} events exported to {}", output.display());
+                Ok(())
+            }
+            ExportCommand::Backup { filter, output_dir } => {
+                std::fs::create_dir_all(&output_dir)?;
+                let mut results = Vec::new();
+                for vault in selected_vaults(client, filter).await?.into_iter() {
+                    let path: PathBuf = output_dir.join(format!("{}.json", vault.policy_id));
+                    let res = client
+                        .save_policy_backup(vault.policy_id, &path)
+                        .await
+                        .map(|_| format!("exported to {}", path.display()))
+                        .map_err(|e| e.to_string());
+                    results.push((vault, res));
+                }
+                util::print_bulk_summary(results);
+                Ok(())
+            }
+        },
         Command::Delete { command } => match command {
             DeleteCommand::Relay { url } => {
                 client.remove_relay(url).await?;
@@ -630,13 +1606,34 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 client.revoke_approval(approval_id).await?;
                 Ok(())
             }
-            DeleteCommand::Signer { signer_id } => {
-                Ok(client.delete_signer_by_id(signer_id).await?)
+            DeleteCommand::Signer { signer_id, force } => {
+                Ok(client.delete_signer_by_id(signer_id, force).await?)
             }
             DeleteCommand::SharedSigner { shared_signer_id } => {
                 Ok(client.revoke_shared_signer(shared_signer_id).await?)
             }
-            DeleteCommand::Cache => Ok(client.clear_cache().await?),
+            DeleteCommand::RecurringPayment { payment_id } => {
+                Ok(client.delete_recurring_payment(payment_id).await?)
+            }
+            DeleteCommand::Cache { vault_id } => match vault_id {
+                Some(vault_id) => Ok(client.reset_vault_cache(vault_id).await?),
+                None => Ok(client.clear_cache().await?),
+            },
+            DeleteCommand::WhitelistedDestination {
+                policy_id,
+                destination,
+            } => {
+                let event_id = client
+                    .remove_whitelisted_destination(policy_id, &destination)
+                    .await?;
+                println!("Whitelist saved at event {event_id}");
+                Ok(())
+            }
+            DeleteCommand::CompromisedKey { fingerprint } => {
+                client.unmark_key_compromised(fingerprint).await?;
+                println!("Key {fingerprint} unmarked");
+                Ok(())
+            }
         },
         Command::Setting { command } => match command {
             SettingCommand::Rename { new_name } => Ok(client.rename(new_name)?),
@@ -645,6 +1642,19 @@ async fn handle_command(command: Command, client: &SmartVaults) -> Result<()> {
                 io::get_new_password,
                 io::get_confirmation_password,
             )?),
+            SettingCommand::SetApprovalPin => {
+                let password: String = io::get_password()?;
+                let pin: String = io::get_new_pin()?;
+                client.set_approval_pin(password, pin)?;
+                println!("Approval PIN set");
+                Ok(())
+            }
+            SettingCommand::RemoveApprovalPin => {
+                let password: String = io::get_password()?;
+                client.remove_approval_pin(password)?;
+                println!("Approval PIN removed");
+                Ok(())
+            }
         },
         Command::Exit => std::process::exit(0x01),
     }
@@ -2,6 +2,10 @@
 // Distributed under the MIT software license
 
 use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
 
 use owo_colors::colors::css::Lime;
 use owo_colors::colors::xterm::{BlazeOrange, BrightElectricViolet, Pistachio};
@@ -11,15 +15,21 @@ use prettytable::{row, Table};
 use smartvaults_sdk::core::bdk::chain::ConfirmationTime;
 use smartvaults_sdk::core::bdk::descriptor::policy::{PkOrF, SatisfiableItem};
 use smartvaults_sdk::core::bips::bip32::Bip32;
-use smartvaults_sdk::core::bitcoin::bip32::ExtendedPubKey;
-use smartvaults_sdk::core::bitcoin::{Network, ScriptBuf};
-use smartvaults_sdk::core::proposal::{CompletedProposal, Proposal};
+use smartvaults_sdk::core::bitcoin::bip32::{ExtendedPubKey, Fingerprint};
+use smartvaults_sdk::core::bitcoin::psbt::PartiallySignedTransaction;
+use smartvaults_sdk::core::bitcoin::{Address, Network, ScriptBuf};
+use smartvaults_sdk::core::proposal::{CompletedProposal, Proposal, ProposalDiff};
+use smartvaults_sdk::core::reserves::ProofPackage;
+use smartvaults_sdk::core::util::{psbt_involved_fingerprints, psbt_signers};
 use smartvaults_sdk::core::{Keychain, Purpose, Result, SECP256K1};
 use smartvaults_sdk::nostr::prelude::{FromMnemonic, NostrConnectURI, ToBech32};
 use smartvaults_sdk::nostr::{EventId, Keys, Profile, PublicKey, Relay, Timestamp, Url};
+use smartvaults_sdk::protocol::v1::Payee;
 use smartvaults_sdk::types::{
-    GetAddress, GetCompletedProposal, GetPolicy, GetProposal, GetSigner, GetSignerOffering,
-    GetTransaction, GetUtxo, NostrConnectRequest,
+    DiagnosticsReport, GetAddress, GetApproval, GetCompletedProposal, GetCosignerCapabilities,
+    GetMemberContributions, GetPaymentRequest, GetPolicy, GetProposal, GetProposalStatus,
+    GetQuorumStatus, GetRecurringPayment, GetSigner, GetSignerOffering, GetSignerUsage,
+    GetTransaction, GetUtxo, NostrConnectRequest, RelayAdmission, RelayFee, RelayTerms,
 };
 use smartvaults_sdk::util::{self, format};
 use termtree::Tree;
@@ -65,6 +75,114 @@ pub fn print_secrets(keychain: Keychain, network: Network) -> Result<()> {
     Ok(())
 }
 
+/// Read a PSBT file, binary or base64 encoded
+pub fn read_psbt<P>(path: P) -> Result<PartiallySignedTransaction>
+where
+    P: AsRef<Path>,
+{
+    let content: Vec<u8> = fs::read(path)?;
+    match PartiallySignedTransaction::deserialize(&content) {
+        Ok(psbt) => Ok(psbt),
+        Err(_) => Ok(PartiallySignedTransaction::from_str(
+            String::from_utf8(content)?.trim(),
+        )?),
+    }
+}
+
+pub fn print_psbt(psbt: &PartiallySignedTransaction, network: Network) {
+    let join = |list: BTreeSet<Fingerprint>| -> String {
+        if list.is_empty() {
+            String::from("-")
+        } else {
+            list.iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+
+    let tx = &psbt.unsigned_tx;
+
+    println!();
+    println!("- Txid: {}", tx.txid());
+    println!("- Version: {}", tx.version);
+    println!("- Lock time: {}", tx.lock_time);
+    println!("- Signers: {}", join(psbt_involved_fingerprints(psbt)));
+    println!("- Signed by: {}", join(psbt_signers(psbt)));
+
+    let mut total_in: Option<u64> = Some(0);
+    println!("- Inputs:");
+    for (txin, input) in tx.input.iter().zip(psbt.inputs.iter()) {
+        let outpoint = txin.previous_output;
+        let value: Option<u64> = match (&input.witness_utxo, &input.non_witness_utxo) {
+            (Some(txout), _) => Some(txout.value),
+            (None, Some(tx)) => tx.output.get(outpoint.vout as usize).map(|o| o.value),
+            (None, None) => None,
+        };
+        total_in = total_in.zip(value).map(|(total, value)| total + value);
+        let finalized: bool =
+            input.final_script_witness.is_some() || input.final_script_sig.is_some();
+        println!(
+            "  - {outpoint}: {}{}",
+            value
+                .map(|v| format!("{} sat", format::number(v)))
+                .unwrap_or_else(|| String::from("unknown amount")),
+            if finalized { " (finalized)" } else { "" }
+        );
+    }
+
+    let total_out: u64 = tx.output.iter().map(|o| o.value).sum();
+    println!("- Outputs:");
+    for txout in tx.output.iter() {
+        let destination: String = match Address::from_script(&txout.script_pubkey, network) {
+            Ok(address) => address.to_string(),
+            Err(_) => format!("{:x}", txout.script_pubkey),
+        };
+        println!("  - {destination}: {} sat", format::number(txout.value));
+    }
+
+    if let Some(fee) = total_in.and_then(|total_in| total_in.checked_sub(total_out)) {
+        println!("- Fee: {} sat", format::number(fee));
+    }
+    println!();
+}
+
+/// Verify a proof of reserve exported with `proof export`
+pub fn verify_external_proof<P>(path: P, message: Option<String>, network: Network) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let content: String = fs::read_to_string(path)?;
+    let package: ProofPackage = match message {
+        Some(message) => ProofPackage::new(
+            network,
+            message,
+            None,
+            PartiallySignedTransaction::from_str(content.trim())?,
+        ),
+        None => ProofPackage::from_str(&content)?,
+    };
+
+    let amount: u64 = package.verify()?;
+
+    println!();
+    println!("- Network: {}", package.network);
+    println!("- Message: {}", package.message);
+    if let Some(descriptor) = &package.descriptor {
+        println!("- Descriptor: {descriptor}");
+    }
+    println!("- Amount: {} sat", format::number(amount));
+    println!();
+    println!("Valid Proof - Check that the following outpoints are still unspent");
+    println!("(i.e. `bitcoin-cli gettxout <txid> <vout>`):");
+    for outpoint in package.outpoints().into_iter() {
+        println!("- {outpoint}");
+    }
+    println!();
+
+    Ok(())
+}
+
 pub fn print_contacts(contacts: BTreeSet<Profile>) {
     let mut table = Table::new();
 
@@ -102,6 +220,9 @@ pub fn print_policy(
     println!("- ID: {policy_id}");
     println!("- Name: {}", policy.name());
     println!("- Description: {}", policy.description());
+    if let Ok(fingerprint) = policy.descriptor_fingerprint() {
+        println!("- Fingerprint: {fingerprint}");
+    }
 
     let mut tree: Tree<String> = Tree::new("- Descriptor".to_string());
     tree.push(add_node(&item));
@@ -160,7 +281,13 @@ pub fn print_txs(txs: BTreeSet<GetTransaction>, limit: usize) {
         "Date/Time"
     ]);
 
-    for (index, GetTransaction { tx, label, .. }) in txs.into_iter().take(limit).enumerate() {
+    for (
+        index,
+        GetTransaction {
+            tx, label, stuck, ..
+        },
+    ) in txs.into_iter().take(limit).enumerate()
+    {
         let (total, positive): (u64, bool) = {
             let received: i64 = tx.received as i64;
             let sent: i64 = tx.sent as i64;
@@ -182,7 +309,15 @@ pub fn print_txs(txs: BTreeSet<GetTransaction>, limit: usize) {
             match tx.confirmation_time {
                 ConfirmationTime::Confirmed { time, .. } =>
                     Timestamp::from(time).to_human_datetime(),
-                ConfirmationTime::Unconfirmed { .. } => String::from("Pending"),
+                ConfirmationTime::Unconfirmed { .. } => match stuck {
+                    Some(stuck) if !stuck.actions.is_empty() => {
+                        let actions: Vec<String> =
+                            stuck.actions.iter().map(|a| a.to_string()).collect();
+                        format!("Pending (stuck, try: {})", actions.join("/"))
+                    }
+                    Some(..) => String::from("Pending (stuck)"),
+                    None => String::from("Pending"),
+                },
             }
         ]);
     }
@@ -208,6 +343,7 @@ pub fn print_utxos(utxos: Vec<GetUtxo>, limit: usize) {
             utxo,
             label,
             frozen,
+            ..
         },
     ) in utxos.into_iter().take(limit).enumerate()
     {
@@ -315,48 +451,133 @@ fn add_node(item: &SatisfiableItem) -> Tree<String> {
 pub fn print_policies(policies: Vec<GetPolicy>) {
     let mut table = Table::new();
 
-    table.set_titles(row!["#", "ID", "Name", "Description"]);
+    table.set_titles(row!["#", "ID", "Name", "Description", "Last sync"]);
 
     for (
         index,
         GetPolicy {
-            policy_id, policy, ..
+            policy_id,
+            policy,
+            last_sync,
+            stale,
+            ..
         },
     ) in policies.into_iter().enumerate()
     {
+        let mut last_sync: String = if last_sync == Timestamp::from(0) {
+            String::from("never")
+        } else {
+            last_sync.to_human_datetime()
+        };
+        if stale {
+            last_sync.push_str(" (stale)");
+        }
         table.add_row(row![
             index + 1,
             policy_id,
             policy.name(),
-            policy.description()
+            policy.description(),
+            last_sync
         ]);
     }
 
     table.printstd();
 }
 
+/// Print the per-vault result of a bulk operation
+pub fn print_bulk_summary(results: Vec<(GetPolicy, std::result::Result<String, String>)>) {
+    let mut table = Table::new();
+
+    table.set_titles(row!["#", "ID", "Name", "Result"]);
+
+    let total: usize = results.len();
+    let mut succeeded: usize = 0;
+    for (
+        index,
+        (
+            GetPolicy {
+                policy_id, policy, ..
+            },
+            res,
+        ),
+    ) in results.into_iter().enumerate()
+    {
+        let res: String = match res {
+            Ok(msg) => {
+                succeeded += 1;
+                msg
+            }
+            Err(e) => format!("FAILED: {e}"),
+        };
+        table.add_row(row![index + 1, policy_id, policy.name(), res]);
+    }
+
+    table.printstd();
+    println!("{succeeded} of {total} vaults succeeded");
+}
+
 pub fn print_proposal(proposal: GetProposal) {
     let GetProposal {
         proposal_id,
         policy_id,
         proposal,
         signed,
+        issues,
+        replaces,
+        diff,
+        expires_at,
         ..
     } = proposal;
     println!();
     println!("- Proposal id: {proposal_id}");
     println!("- Policy id: {policy_id}");
+    if let Some(expires_at) = expires_at {
+        println!("- Expires at: {}", expires_at.to_human_datetime());
+    }
+    if let Some(replaces) = replaces {
+        println!("- Replaces: {replaces}");
+        match diff {
+            Some(diff) => print_proposal_diff(diff),
+            None => println!("  (replaced proposal not available, review it from scratch)"),
+        }
+    }
+    if !issues.is_empty() {
+        println!("- WARNING: suspicious proposal");
+        for issue in issues.into_iter() {
+            println!("  - {issue}");
+        }
+    }
+    let op_return: Option<Vec<u8>> = proposal.op_return_data();
     match proposal {
         Proposal::Spending {
             to_address,
             amount,
             description,
+            recipients,
+            memo,
             ..
         } => {
             println!("- Type: spending");
             println!("- Description: {description}");
-            println!("- To address: {}", to_address.assume_checked());
+            if recipients.is_empty() {
+                println!("- To address: {}", to_address.assume_checked());
+            } else {
+                println!("- Recipients:");
+                for recipient in recipients.into_iter() {
+                    println!(
+                        "  - {}: {} sat",
+                        recipient.address.assume_checked(),
+                        format::number(recipient.amount)
+                    );
+                }
+            }
             println!("- Amount: {amount}");
+            if let Some(data) = &op_return {
+                println!("- OP_RETURN: {}", format::data(data));
+            }
+            if let Some(memo) = memo {
+                println!("- Memo: {memo}");
+            }
             println!("- Signed: {signed}");
         }
         Proposal::KeyAgentPayment {
@@ -379,6 +600,42 @@ pub fn print_proposal(proposal: GetProposal) {
     println!();
 }
 
+pub fn print_proposal_diff(diff: ProposalDiff) {
+    if diff.is_empty() {
+        println!("  No changes");
+        return;
+    }
+    if diff.amount != 0 {
+        println!("  - Amount: {:+} sat", diff.amount);
+    }
+    if let Some(fee) = diff.fee.filter(|fee| *fee != 0) {
+        println!("  - Fee: {fee:+} sat");
+    }
+    for recipient in diff.removed_recipients.into_iter() {
+        println!(
+            "  - Removed recipient: {} ({} sat)",
+            recipient.address.assume_checked(),
+            format::number(recipient.amount)
+        );
+    }
+    for recipient in diff.added_recipients.into_iter() {
+        println!(
+            "  - New recipient: {} ({} sat)",
+            recipient.address.assume_checked(),
+            format::number(recipient.amount)
+        );
+    }
+    for input in diff.removed_inputs.into_iter() {
+        println!("  - Removed input: {input}");
+    }
+    for input in diff.added_inputs.into_iter() {
+        println!("  - New input: {input}");
+    }
+    if let Some(description) = diff.description {
+        println!("  - Description: {description}");
+    }
+}
+
 pub fn print_proposals(proposals: Vec<GetProposal>) {
     let mut table = Table::new();
 
@@ -400,6 +657,7 @@ pub fn print_proposals(proposals: Vec<GetProposal>) {
             policy_id,
             proposal,
             signed,
+            replaces,
             ..
         },
     ) in proposals.into_iter().enumerate()
@@ -415,7 +673,11 @@ pub fn print_proposals(proposals: Vec<GetProposal>) {
                     index + 1,
                     proposal_id,
                     util::cut_event_id(policy_id),
-                    "spending",
+                    if replaces.is_some() {
+                        "spending (amended)"
+                    } else {
+                        "spending"
+                    },
                     description,
                     to_address.assume_checked(),
                     format!("{} sat", format::number(amount)),
@@ -457,10 +719,118 @@ pub fn print_proposals(proposals: Vec<GetProposal>) {
     table.printstd();
 }
 
+pub fn print_approvals(approvals: Vec<GetApproval>) {
+    let mut table = Table::new();
+
+    table.set_titles(row!["#", "ID", "User", "Signers", "Timestamp", "Valid"]);
+
+    for (index, approval) in approvals.into_iter().enumerate() {
+        let signers: Vec<String> = approval.signers.iter().map(|f| f.to_string()).collect();
+        table.add_row(row![
+            index + 1,
+            util::cut_event_id(approval.approval_id),
+            approval.user.name(),
+            if signers.is_empty() {
+                String::from("-")
+            } else {
+                signers.join(", ")
+            },
+            approval.timestamp.to_human_datetime(),
+            approval.valid
+        ]);
+    }
+
+    table.printstd();
+}
+
+pub fn print_proposal_status(status: GetProposalStatus) {
+    let join = |list: &BTreeSet<Fingerprint>| -> String {
+        if list.is_empty() {
+            String::from("-")
+        } else {
+            list.iter()
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+    };
+
+    println!();
+    println!("- Proposal id: {}", status.proposal_id);
+    println!("- Policy id: {}", status.policy_id);
+    println!("- Valid approvals: {}", status.approvals);
+    println!("- Signed by: {}", join(&status.signed));
+    println!("- Missing signers: {}", join(&status.missing));
+    if status.ready {
+        println!("- Status: {}", "ready to be finalized".fg::<Lime>());
+    } else {
+        println!("- Status: {}", "waiting for approvals".fg::<BlazeOrange>());
+    }
+    println!();
+}
+
+pub fn print_quorum_status(status: GetQuorumStatus) {
+    let name = |fingerprint: &Fingerprint| -> String {
+        match status.owners.get(fingerprint) {
+            Some(owner) => format!("{} ({fingerprint})", owner.name()),
+            None => fingerprint.to_string(),
+        }
+    };
+
+    let mut table = Table::new();
+
+    table.set_titles(row![
+        "#",
+        "Path",
+        "Signed",
+        "Required",
+        "Waiting on",
+        "Timelock"
+    ]);
+
+    for (index, path) in status.paths.iter().enumerate() {
+        let missing: Vec<String> = path.missing().iter().map(name).collect();
+        table.add_row(row![
+            index + 1,
+            path.path,
+            format!(
+                "{} of {}",
+                path.signed.len(),
+                path.threshold
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| String::from("-"))
+            ),
+            path.required
+                .map(|r| r.to_string())
+                .unwrap_or_else(|| String::from("-")),
+            if path.is_satisfied() || missing.is_empty() {
+                String::from("-")
+            } else {
+                missing.join(", ")
+            },
+            path.timelock
+        ]);
+    }
+
+    table.printstd();
+
+    if status.ready {
+        println!("{}", "Ready to be finalized".fg::<Lime>());
+    }
+}
+
 pub fn print_completed_proposals(proposals: Vec<GetCompletedProposal>) {
     let mut table = Table::new();
 
-    table.set_titles(row!["#", "ID", "Policy ID", "Type", "Txid", "Description"]);
+    table.set_titles(row![
+        "#",
+        "ID",
+        "Policy ID",
+        "Type",
+        "Txid",
+        "Description",
+        "Memo"
+    ]);
 
     for (
         index,
@@ -474,7 +844,9 @@ pub fn print_completed_proposals(proposals: Vec<GetCompletedProposal>) {
     {
         match proposal {
             CompletedProposal::Spending {
-                tx, description, ..
+                tx,
+                description,
+                memo,
             } => {
                 table.add_row(row![
                     index + 1,
@@ -483,6 +855,8 @@ pub fn print_completed_proposals(proposals: Vec<GetCompletedProposal>) {
                     "spending",
                     tx.txid(),
                     description,
+                    memo.map(|m| m.to_string())
+                        .unwrap_or_else(|| String::from("-")),
                 ]);
             }
             CompletedProposal::KeyAgentPayment {
@@ -495,6 +869,7 @@ pub fn print_completed_proposals(proposals: Vec<GetCompletedProposal>) {
                     "key-agent-payment",
                     tx.txid(),
                     description,
+                    "-",
                 ]);
             }
             CompletedProposal::ProofOfReserve { message, .. } => {
@@ -505,6 +880,7 @@ pub fn print_completed_proposals(proposals: Vec<GetCompletedProposal>) {
                     "proof-of-reserve",
                     "-",
                     message,
+                    "-",
                 ]);
             }
         }
@@ -531,7 +907,119 @@ pub fn print_signers(signers: Vec<GetSigner>) {
     table.printstd();
 }
 
-pub async fn print_relays(relays: BTreeMap<Url, Relay>) {
+pub fn print_payment_requests(requests: Vec<GetPaymentRequest>) {
+    let mut table = Table::new();
+
+    table.set_titles(row![
+        "#",
+        "ID",
+        "Policy ID",
+        "User",
+        "Reason",
+        "Address",
+        "Amount",
+        "Fulfilled",
+        "Date/Time"
+    ]);
+
+    for (index, request) in requests.into_iter().enumerate() {
+        table.add_row(row![
+            index + 1,
+            request.request_id,
+            util::cut_event_id(request.policy_id),
+            request.user.name(),
+            request.request.description(),
+            request.request.address().assume_checked(),
+            format!("{} sat", format::number(request.request.amount())),
+            request.fulfilled,
+            request.timestamp.to_human_datetime()
+        ]);
+    }
+
+    table.printstd();
+}
+
+pub fn print_recurring_payments(payments: Vec<GetRecurringPayment>) {
+    let mut table = Table::new();
+
+    table.set_titles(row![
+        "#",
+        "ID",
+        "Policy ID",
+        "Description",
+        "Address",
+        "Amount",
+        "Every",
+        "Next due"
+    ]);
+
+    for (
+        index,
+        GetRecurringPayment {
+            payment_id,
+            policy_id,
+            payment,
+            next_due,
+            ..
+        },
+    ) in payments.into_iter().enumerate()
+    {
+        table.add_row(row![
+            index + 1,
+            payment_id,
+            util::cut_event_id(policy_id),
+            payment.description(),
+            payment.address().assume_checked(),
+            format!("{} sat", format::number(payment.amount())),
+            format!("{} days", payment.interval().as_secs() / (60 * 60 * 24)),
+            next_due.to_human_datetime()
+        ]);
+    }
+
+    table.printstd();
+}
+
+pub fn print_payees(payees: Vec<Payee>) {
+    let mut table = Table::new();
+
+    table.set_titles(row!["#", "Name", "Destination", "Next index", "Memo"]);
+
+    for (index, payee) in payees.into_iter().enumerate() {
+        table.add_row(row![
+            index + 1,
+            payee.name(),
+            payee.destination(),
+            if payee.is_descriptor() {
+                payee.index().to_string()
+            } else {
+                String::from("-")
+            },
+            payee.memo().unwrap_or("-")
+        ]);
+    }
+
+    table.printstd();
+}
+
+pub fn print_signer_usage(usage: GetSignerUsage) {
+    println!();
+    println!("- Signer id: {}", usage.signer_id);
+    println!("- Used by {} vaults", usage.vaults.len());
+    for vault_id in usage.vaults.iter() {
+        println!("    {vault_id}");
+    }
+    println!("- Approved {} proposals", usage.approvals.len());
+    for (approval_id, proposal_id) in usage.approvals.iter() {
+        println!(
+            "    {} (approval {})",
+            proposal_id,
+            util::cut_event_id(*approval_id)
+        );
+    }
+    println!();
+}
+
+pub async fn print_relays(relays: BTreeMap<Url, Relay>, admission: HashMap<Url, RelayAdmission>) {
     let mut table = Table::new();
 
     table.set_titles(row![
@@ -544,7 +1032,8 @@ pub async fn print_relays(relays: BTreeMap<Url, Relay>) {
         "Received (bytes)",
         "Queue",
         "Latency",
-        "Connected at"
+        "Connected at",
+        "Admission"
     ]);
 
     for (index, (url, relay)) in relays.into_iter().enumerate() {
@@ -566,6 +1055,10 @@ pub async fn print_relays(relays: BTreeMap<Url, Relay>) {
                 String::from("-")
             } else {
                 stats.connected_at().to_human_datetime()
+            },
+            match admission.get(&url) {
+                Some(admission) => admission.status.to_string(),
+                None => String::from("-"),
             }
         ]);
     }
@@ -573,6 +1066,105 @@ pub async fn print_relays(relays: BTreeMap<Url, Relay>) {
     table.printstd();
 }
 
+fn format_fees(fees: &[RelayFee]) -> String {
+    if fees.is_empty() {
+        return String::from("-");
+    }
+
+    fees.iter()
+        .map(|fee| match fee.period {
+            Some(period) => format!("{} {} every {} days", fee.amount, fee.unit, period / 86400),
+            None => format!("{} {}", fee.amount, fee.unit),
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+pub fn print_relay_terms(url: Url, terms: RelayTerms, admission: Option<RelayAdmission>) {
+    let mut table = Table::new();
+
+    table.add_row(row!["Url", url]);
+    table.add_row(row![
+        "Name",
+        terms.name.unwrap_or_else(|| String::from("-"))
+    ]);
+    table.add_row(row!["Payment required", terms.payment_required]);
+    table.add_row(row!["Auth required", terms.auth_required]);
+    table.add_row(row!["Restricted writes", terms.restricted_writes]);
+    table.add_row(row!["Admission fees", format_fees(&terms.admission_fees)]);
+    table.add_row(row![
+        "Subscription fees",
+        format_fees(&terms.subscription_fees)
+    ]);
+    table.add_row(row![
+        "Payments url",
+        terms.payments_url.unwrap_or_else(|| String::from("-"))
+    ]);
+    table.add_row(row![
+        "Posting policy",
+        terms.posting_policy.unwrap_or_else(|| String::from("-"))
+    ]);
+    if let Some(admission) = admission {
+        table.add_row(row!["Admission", admission.status]);
+        if let Some(message) = admission.message {
+            table.add_row(row!["Last message", message]);
+        }
+    }
+
+    table.printstd();
+}
+
+pub fn print_diagnostics(report: &DiagnosticsReport) {
+    let format_duration = |duration: Option<Duration>| match duration {
+        Some(duration) => format!("{} ms", duration.as_millis()),
+        None => String::from("-"),
+    };
+
+    let mut table = Table::new();
+    table.set_titles(row![
+        "Relay",
+        "Connected",
+        "Auth required",
+        "Round-trip",
+        "Error"
+    ]);
+    for relay in report.relays.iter() {
+        table.add_row(row![
+            relay.url,
+            relay.connected,
+            match relay.auth_required {
+                Some(auth_required) => auth_required.to_string(),
+                None => String::from("-"),
+            },
+            format_duration(relay.round_trip),
+            relay.error.as_deref().unwrap_or("-")
+        ]);
+    }
+    table.printstd();
+
+    let mut table = Table::new();
+    table.set_titles(row![
+        "Backend",
+        "Version",
+        "Block height",
+        "Latency",
+        "Error"
+    ]);
+    for backend in report.backends.iter() {
+        table.add_row(row![
+            backend.backend,
+            backend.version.as_deref().unwrap_or("-"),
+            match backend.block_height {
+                Some(height) => format::number(height as u64),
+                None => String::from("-"),
+            },
+            format_duration(backend.latency),
+            backend.error.as_deref().unwrap_or("-")
+        ]);
+    }
+    table.printstd();
+}
+
 pub fn print_addresses(addresses: Vec<GetAddress>, balances: HashMap<ScriptBuf, u64>) {
     let mut table = Table::new();
 
@@ -598,6 +1190,89 @@ pub fn print_addresses(addresses: Vec<GetAddress>, balances: HashMap<ScriptBuf,
     table.printstd();
 }
 
+pub fn print_contributions(contributions: GetMemberContributions) {
+    let total: u64 = contributions.total();
+    let share = |amount: u64| -> String {
+        if total > 0 {
+            format!("{:.2}%", amount as f64 / total as f64 * 100.0)
+        } else {
+            String::from("-")
+        }
+    };
+
+    let mut table = Table::new();
+
+    table.set_titles(row!["#", "Member", "Deposits", "Amount", "Share"]);
+
+    for (index, member) in contributions.members.into_iter().enumerate() {
+        table.add_row(row![
+            index + 1,
+            member.user.name(),
+            member.deposits.len(),
+            format!("{} sat", format::number(member.amount)),
+            share(member.amount)
+        ]);
+    }
+
+    if contributions.unattributed > 0 {
+        table.add_row(row![
+            "-",
+            "Unattributed",
+            contributions.unattributed_deposits.len(),
+            format!("{} sat", format::number(contributions.unattributed)),
+            share(contributions.unattributed)
+        ]);
+    }
+
+    table.printstd();
+
+    println!("Total deposited: {} sat", format::number(total));
+}
+
+pub fn print_cosigners_capabilities(cosigners: Vec<GetCosignerCapabilities>) {
+    let mut table = Table::new();
+
+    table.set_titles(row![
+        "#",
+        "Public key",
+        "Client",
+        "Protocol versions",
+        "Compatibility",
+        "Updated at"
+    ]);
+
+    for (index, cosigner) in cosigners.into_iter().enumerate() {
+        let (client, versions) = match &cosigner.capabilities {
+            Some(capabilities) => (
+                capabilities
+                    .client
+                    .clone()
+                    .unwrap_or_else(|| String::from("-")),
+                capabilities
+                    .protocol_versions
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", "),
+            ),
+            None => (String::from("-"), String::from("-")),
+        };
+        table.add_row(row![
+            index + 1,
+            util::cut_public_key(cosigner.public_key),
+            client,
+            versions,
+            cosigner.compatibility,
+            cosigner
+                .timestamp
+                .map(|t| t.to_human_datetime())
+                .unwrap_or_else(|| String::from("-")),
+        ]);
+    }
+
+    table.printstd();
+}
+
 pub fn print_sessions(sessions: Vec<(NostrConnectURI, Timestamp)>) {
     let mut table = Table::new();
 
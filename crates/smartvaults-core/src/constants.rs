@@ -3,3 +3,6 @@
 
 // Derivation paths
 pub const SMARTVAULTS_ACCOUNT_INDEX: u32 = 784923;
+
+/// Max size of the data embedded in an `OP_RETURN` output (standardness limit)
+pub const MAX_OP_RETURN_SIZE: usize = 80;
@@ -0,0 +1,378 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Descriptor utilities
+//!
+//! Checksum verification, normalization and diffing, to detect tampered descriptors when
+//! importing a vault. Export of the registration file for air-gapped signers.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use keechain_core::bdk::miniscript::descriptor::checksum::desc_checksum;
+use keechain_core::bdk::miniscript::descriptor::{Descriptor, DescriptorPublicKey, DescriptorType};
+use keechain_core::bips::bip39::Language;
+use keechain_core::bitcoin::hashes::sha256::Hash as Sha256Hash;
+use keechain_core::bitcoin::hashes::Hash;
+
+/// Number of words of the descriptor fingerprint (88 bits)
+pub const FINGERPRINT_WORDS: usize = 8;
+/// Max length of the wallet name on Coldcard
+const COLDCARD_NAME_MAX_LEN: usize = 20;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Miniscript(#[from] keechain_core::bdk::miniscript::Error),
+    #[error("missing checksum")]
+    MissingChecksum,
+    #[error("invalid checksum: expected {expected}, found {found}")]
+    InvalidChecksum { expected: String, found: String },
+}
+
+/// Differences between two descriptors
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DescriptorDiff {
+    /// Keys only in the second descriptor
+    pub added_keys: BTreeSet<String>,
+    /// Keys only in the first descriptor
+    pub removed_keys: BTreeSet<String>,
+    /// Different descriptor type (i.e. `wsh` and `tr`)
+    pub type_changed: bool,
+    /// Same keys and type but different script (i.e. threshold or timelocks)
+    pub script_changed: bool,
+}
+
+impl DescriptorDiff {
+    /// Check if the descriptors are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.added_keys.is_empty()
+            && self.removed_keys.is_empty()
+            && !self.type_changed
+            && !self.script_changed
+    }
+}
+
+/// Split the descriptor and its checksum (if any)
+fn split_checksum(descriptor: &str) -> (&str, Option<&str>) {
+    match descriptor.trim().rsplit_once('#') {
+        Some((desc, checksum)) => (desc, Some(checksum)),
+        None => (descriptor.trim(), None),
+    }
+}
+
+/// Compute the checksum of the descriptor (an existing checksum is ignored)
+pub fn checksum<S>(descriptor: S) -> Result<String, Error>
+where
+    S: AsRef<str>,
+{
+    let (desc, _) = split_checksum(descriptor.as_ref());
+    Ok(desc_checksum(desc)?)
+}
+
+/// Add (or replace) the checksum of the descriptor
+pub fn with_checksum<S>(descriptor: S) -> Result<String, Error>
+where
+    S: AsRef<str>,
+{
+    let (desc, _) = split_checksum(descriptor.as_ref());
+    Ok(format!("{desc}#{}", desc_checksum(desc)?))
+}
+
+/// Verify the checksum of the descriptor
+///
+/// Return [`Error::MissingChecksum`] if the descriptor has no checksum.
+pub fn verify_checksum<S>(descriptor: S) -> Result<(), Error>
+where
+    S: AsRef<str>,
+{
+    let (desc, found) = split_checksum(descriptor.as_ref());
+    let found: &str = found.ok_or(Error::MissingChecksum)?;
+    let expected: String = desc_checksum(desc)?;
+    if expected == found {
+        Ok(())
+    } else {
+        Err(Error::InvalidChecksum {
+            expected,
+            found: found.to_string(),
+        })
+    }
+}
+
+/// Sort the keys of the `sortedmulti` and `sortedmulti_a` fragments
+fn sort_multi_keys(descriptor: &str) -> String {
+    let mut output = String::with_capacity(descriptor.len());
+    let mut rest: &str = descriptor;
+    while let Some(pos) = rest.find("sortedmulti") {
+        let (before, fragment) = rest.split_at(pos);
+        output.push_str(before);
+        match (fragment.find('('), fragment.find(')')) {
+            (Some(open), Some(close)) if open < close => {
+                output.push_str(&fragment[..=open]);
+                let mut args: Vec<&str> = fragment[open + 1..close].split(',').collect();
+                if args.len() > 1 {
+                    args[1..].sort_unstable();
+                }
+                output.push_str(&args.join(","));
+                rest = &fragment[close..];
+            }
+            _ => {
+                output.push_str(fragment);
+                rest = "";
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Normalize the descriptor
+///
+/// The keys of `sortedmulti` fragments are sorted and the descriptor is re-formatted in the
+/// canonical form (i.e. `'` for hardened derivation steps), with checksum.
+pub fn normalize<S>(descriptor: S) -> Result<String, Error>
+where
+    S: AsRef<str>,
+{
+    let descriptor: Descriptor<DescriptorPublicKey> = parse(descriptor.as_ref())?;
+    Ok(descriptor.to_string())
+}
+
+fn parse(descriptor: &str) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+    let (desc, checksum) = split_checksum(descriptor);
+    if checksum.is_some() {
+        verify_checksum(descriptor)?;
+    }
+    let desc: String = desc.chars().filter(|c| !c.is_whitespace()).collect();
+    let first = Descriptor::<DescriptorPublicKey>::from_str(&sort_multi_keys(&desc))?;
+    // Re-sort after the canonical formatting of the keys
+    Ok(Descriptor::from_str(&sort_multi_keys(
+        split_checksum(&first.to_string()).0,
+    ))?)
+}
+
+fn keys(descriptor: &Descriptor<DescriptorPublicKey>) -> BTreeSet<String> {
+    let mut keys: BTreeSet<String> = BTreeSet::new();
+    descriptor.for_each_key(|key| {
+        keys.insert(key.to_string());
+        true
+    });
+    keys
+}
+
+/// Compute a short human-comparable fingerprint of the descriptor
+///
+/// The SHA256 of the normalized descriptor is encoded as [`FINGERPRINT_WORDS`] BIP39 words, so
+/// cosigners can check (i.e. over a phone call) that they see the same wallet.
+pub fn fingerprint<S>(descriptor: S) -> Result<String, Error>
+where
+    S: AsRef<str>,
+{
+    let normalized: String = normalize(descriptor)?;
+    let hash: [u8; 32] = Sha256Hash::hash(normalized.as_bytes()).to_byte_array();
+    Ok(encode_words(&hash))
+}
+
+/// Encode the first `FINGERPRINT_WORDS * 11` bits as BIP39 words
+fn encode_words(bytes: &[u8; 32]) -> String {
+    let word_list: &[&str; 2048] = Language::English.word_list();
+    let mut words: Vec<&str> = Vec::with_capacity(FINGERPRINT_WORDS);
+    for i in 0..FINGERPRINT_WORDS {
+        let mut index: usize = 0;
+        for bit in i * 11..(i + 1) * 11 {
+            let set: bool = bytes[bit / 8] & (1 << (7 - (bit % 8))) != 0;
+            index = (index << 1) | set as usize;
+        }
+        words.push(word_list[index]);
+    }
+    words.join(" ")
+}
+
+/// Compare two descriptors
+pub fn diff<A, B>(a: A, b: B) -> Result<DescriptorDiff, Error>
+where
+    A: AsRef<str>,
+    B: AsRef<str>,
+{
+    let a: Descriptor<DescriptorPublicKey> = parse(a.as_ref())?;
+    let b: Descriptor<DescriptorPublicKey> = parse(b.as_ref())?;
+
+    let keys_a: BTreeSet<String> = keys(&a);
+    let keys_b: BTreeSet<String> = keys(&b);
+    let added_keys: BTreeSet<String> = keys_b.difference(&keys_a).cloned().collect();
+    let removed_keys: BTreeSet<String> = keys_a.difference(&keys_b).cloned().collect();
+
+    let type_a: DescriptorType = a.desc_type();
+    let type_b: DescriptorType = b.desc_type();
+    let type_changed: bool = type_a != type_b;
+
+    Ok(DescriptorDiff {
+        script_changed: added_keys.is_empty() && removed_keys.is_empty() && !type_changed && a != b,
+        added_keys,
+        removed_keys,
+        type_changed,
+    })
+}
+
+/// Threshold of the multisig, if the descriptor contains a single `multi` fragment
+fn multi_threshold(descriptor: &str) -> Option<usize> {
+    if descriptor.matches("multi").count() != 1 {
+        return None;
+    }
+    let (_, fragment) = descriptor.split_once("multi")?;
+    let (_, args) = fragment.split_once('(')?;
+    let (threshold, _) = args.split_once(',')?;
+    threshold.trim().parse().ok()
+}
+
+/// Wallet name accepted by Coldcard (ASCII, max 20 chars)
+fn coldcard_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .take(COLDCARD_NAME_MAX_LEN)
+        .collect();
+    let name: &str = name.trim();
+    if name.is_empty() {
+        String::from("Smart Vaults")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Build the Coldcard registration file of the vault
+///
+/// The file lists the name, the address format, the threshold (if the policy is a plain multisig)
+/// and the derivation and xpub of every cosigner, followed by the full descriptor. Once imported,
+/// the device can verify the receive addresses and co-sign the PSBTs offline.
+pub fn coldcard_config<N, S>(name: N, descriptor: S) -> Result<String, Error>
+where
+    N: AsRef<str>,
+    S: AsRef<str>,
+{
+    let descriptor: Descriptor<DescriptorPublicKey> = parse(descriptor.as_ref())?;
+
+    let format: &str = match descriptor.desc_type() {
+        DescriptorType::Tr => "P2TR",
+        DescriptorType::Wsh | DescriptorType::WshSortedMulti => "P2WSH",
+        DescriptorType::ShWsh | DescriptorType::ShWshSortedMulti => "P2SH-P2WSH",
+        DescriptorType::Wpkh => "P2WPKH",
+        DescriptorType::ShWpkh => "P2SH-P2WPKH",
+        _ => "P2SH",
+    };
+
+    let mut cosigners: Vec<String> = Vec::new();
+    descriptor.for_each_key(|key| {
+        let (origin, xpub) = match key {
+            DescriptorPublicKey::XPub(xkey) => (&xkey.origin, xkey.xkey),
+            DescriptorPublicKey::MultiXPub(xkey) => (&xkey.origin, xkey.xkey),
+            DescriptorPublicKey::Single(..) => return true,
+        };
+        let (fingerprint, path) = match origin {
+            Some((fingerprint, path)) => (*fingerprint, path.to_string()),
+            None => (xpub.fingerprint(), String::from("m")),
+        };
+        let cosigner: String = format!(
+            "Derivation: {path}\n{}: {xpub}",
+            fingerprint.to_string().to_uppercase()
+        );
+        if !cosigners.contains(&cosigner) {
+            cosigners.push(cosigner);
+        }
+        true
+    });
+
+    let descriptor: String = descriptor.to_string();
+    let mut config = String::from("# Coldcard setup file (created by Smart Vaults)\n#\n");
+    config.push_str(&format!("Name: {}\n", coldcard_name(name.as_ref())));
+    if let Some(threshold) = multi_threshold(&descriptor) {
+        config.push_str(&format!("Policy: {threshold} of {}\n", cosigners.len()));
+    }
+    config.push_str(&format!("Format: {format}\n"));
+    for cosigner in cosigners.into_iter() {
+        config.push_str(&format!("\n{cosigner}\n"));
+    }
+    config.push_str(&format!("\nDescriptor: {descriptor}\n"));
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTOR: &str = "tr([e157a520/86'/1'/784923']tpubDCCYFYCyDkxo1xAzDpoFNdtGcjD5BPLZbEJswjJmwqp67Weqd2C7fg6Jy1SBjgn3wYnKyUtoYKXG4VdQczjqb6FJnqHe3NmFdgy8vNBSty4/0/*,pk([7356e457/86'/1'/784923']tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d/0/*))#m9nhr276";
+    const XPUB_A: &str = "xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB/1/0/*";
+    const XPUB_B: &str = "xpub69H7F5d8KSRgmmdJg2KhpAK8SR3DjMwAdkxj3ZuxV27CprR9LgpeyGmXUbC6wb7ERfvrnKZjXoUmmDznezpbZb7ap6r1D3tgFxHmwMkQTPH/0/0/*";
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(DESCRIPTOR).unwrap(), "m9nhr276");
+        assert!(verify_checksum(DESCRIPTOR).is_ok());
+
+        let tampered = DESCRIPTOR.replace("/0/*))", "/1/*))");
+        assert!(matches!(
+            verify_checksum(tampered),
+            Err(Error::InvalidChecksum { .. })
+        ));
+
+        let (desc, _) = split_checksum(DESCRIPTOR);
+        assert!(matches!(verify_checksum(desc), Err(Error::MissingChecksum)));
+        assert_eq!(with_checksum(desc).unwrap(), DESCRIPTOR);
+    }
+
+    #[test]
+    fn test_normalize_sortedmulti() {
+        let a = format!("wsh(sortedmulti(1,{XPUB_A},{XPUB_B}))");
+        let b = format!("wsh(sortedmulti(1,{XPUB_B},{XPUB_A}))");
+        assert_eq!(normalize(&a).unwrap(), normalize(b).unwrap());
+        assert!(diff(&a, normalize(&a).unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let a = format!("wsh(sortedmulti(1,{XPUB_A},{XPUB_B}))");
+        let b = format!("wsh(sortedmulti(1,{XPUB_B},{XPUB_A}))");
+        let fingerprint_a = fingerprint(&a).unwrap();
+        assert_eq!(fingerprint_a.split(' ').count(), FINGERPRINT_WORDS);
+        assert_eq!(fingerprint_a, fingerprint(b).unwrap());
+        assert_ne!(fingerprint_a, fingerprint(DESCRIPTOR).unwrap());
+
+        assert_eq!(
+            encode_words(&[0u8; 32]),
+            vec!["abandon"; FINGERPRINT_WORDS].join(" ")
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let a = format!("wsh(multi(1,{XPUB_A},{XPUB_B}))");
+        let b = format!("wsh(multi(2,{XPUB_A},{XPUB_B}))");
+        let d = diff(&a, &b).unwrap();
+        assert!(d.script_changed);
+        assert!(d.added_keys.is_empty());
+
+        let c = format!("sh(wsh(multi(1,{XPUB_A},{XPUB_B})))");
+        assert!(diff(&a, c).unwrap().type_changed);
+
+        let d = diff(&a, DESCRIPTOR).unwrap();
+        assert_eq!(d.removed_keys.len(), 2);
+        assert_eq!(d.added_keys.len(), 2);
+    }
+
+    #[test]
+    fn test_coldcard_config() {
+        let config = coldcard_config("My vault!", DESCRIPTOR).unwrap();
+        assert!(config.contains("Name: My vault\n"));
+        assert!(config.contains("Format: P2TR\n"));
+        assert!(!config.contains("Policy:"));
+        assert!(config.contains("Derivation: m/86'/1'/784923'\nE157A520: tpubDCCYFYCy"));
+        assert!(config.contains("Derivation: m/86'/1'/784923'\n7356E457: tpubDCvLwbJP"));
+        assert!(config.contains(&format!("Descriptor: {DESCRIPTOR}\n")));
+
+        let multisig = format!("wsh(sortedmulti(2,{XPUB_A},{XPUB_B}))");
+        let config = coldcard_config("", multisig).unwrap();
+        assert!(config.contains("Name: Smart Vaults\n"));
+        assert!(config.contains("Policy: 2 of 2\n"));
+        assert!(config.contains("Format: P2WSH\n"));
+    }
+}
@@ -13,21 +13,25 @@ pub use keechain_core::*;
 use once_cell::sync::Lazy;
 
 pub mod constants;
+pub mod descriptor;
 pub mod policy;
 pub mod proposal;
 #[cfg(feature = "reserves")]
 pub mod reserves;
+pub mod sandbox;
 pub mod signer;
 pub mod types;
 pub mod util;
 
 pub use self::policy::{
     AbsoluteLockTime, DecayingTime, Locktime, Policy, PolicyTemplate, PolicyTemplateType,
-    RecoveryTemplate, SelectableCondition, Sequence,
+    QuorumPath, RecoveryTemplate, SelectableCondition, Sequence,
+};
+pub use self::proposal::{
+    ApprovedProposal, CompletedProposal, PaymentMemo, Proposal, ProposalDiff, Recipient,
 };
-pub use self::proposal::{ApprovedProposal, CompletedProposal, Proposal};
 pub use self::signer::{SharedSigner, Signer, SignerType};
-pub use self::types::{Amount, FeeRate, Priority};
+pub use self::types::{Amount, FeeRate, Priority, TxOptions};
 
 pub static SECP256K1: Lazy<Secp256k1<All>> = Lazy::new(|| {
     let mut ctx = Secp256k1::new();
@@ -167,6 +171,7 @@ mod tests {
             None,
             None,
             None,
+            TxOptions::default(),
         )?;
 
         let approved_a: ApprovedProposal = proposal.approve(&seed_a, Vec::new(), NETWORK)?;
@@ -180,6 +185,57 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_proposal_diff() -> Result<()> {
+        let mnemonic_a: Mnemonic = Mnemonic::from_str(MNEMONIC_A)?;
+        let seed_a: Seed = Seed::from_mnemonic(mnemonic_a);
+        let desc_a: DescriptorPublicKey =
+            seed_a.to_descriptor(Purpose::BIP86, Some(7291640), false, NETWORK, &SECP256K1)?;
+
+        let mnemonic_b: Mnemonic = Mnemonic::from_str(MNEMONIC_B)?;
+        let seed_b: Seed = Seed::from_mnemonic(mnemonic_b);
+        let desc_b: DescriptorPublicKey =
+            seed_b.to_descriptor(Purpose::BIP86, Some(7291640), false, NETWORK, &SECP256K1)?;
+
+        let template = PolicyTemplate::multisig(2, vec![desc_a, desc_b]);
+        let policy: Policy = Policy::from_template("Name", "Description", template, NETWORK)?;
+        let descriptor: String = policy.as_descriptor().to_string();
+
+        let mut wallet = get_funded_wallet(&descriptor).unwrap();
+        let address = Address::from_str("mohjSavDdQYHRYXcS3uS6ttaHP8amyvX78")?;
+        let previous: Proposal = policy.spend(
+            &mut wallet,
+            address.clone(),
+            Amount::Custom(1120),
+            "Testing",
+            FeeRate::from_sat_per_vb(1.0),
+            None,
+            None,
+            None,
+            TxOptions::default(),
+        )?;
+        assert!(previous.diff(&previous).is_empty());
+
+        let proposal: Proposal = policy.spend(
+            &mut wallet,
+            address,
+            Amount::Custom(2000),
+            "Testing (edited)",
+            FeeRate::from_sat_per_vb(2.0),
+            None,
+            None,
+            None,
+            TxOptions::default(),
+        )?;
+        let diff: ProposalDiff = proposal.diff(&previous);
+        assert_eq!(diff.amount, 880);
+        assert!(diff.fee.unwrap() > 0);
+        assert!(!diff.destination_changed());
+        assert_eq!(diff.description.as_deref(), Some("Testing (edited)"));
+
+        Ok(())
+    }
+
     #[test]
     #[cfg(feature = "reserves")]
     fn test_proof_of_reserve() -> Result<()> {
@@ -258,6 +314,7 @@ mod tests {
             None,
             None,
             None,
+            TxOptions::default(),
         )?;
 
         let approved_a: ApprovedProposal = proposal.approve(&seed_a, Vec::new(), NETWORK)?;
@@ -293,6 +350,7 @@ mod tests {
                 None,
                 None,
                 None,
+                TxOptions::default(),
             )
             .unwrap();
 
@@ -4,20 +4,22 @@
 use core::cmp::Ordering;
 use core::hash::{Hash, Hasher};
 use core::str::FromStr;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 use bdk::chain::{ConfirmationTime, PersistBackend};
 use bdk::descriptor::policy::{BuildSatisfaction, PkOrF, SatisfiableItem};
 use bdk::descriptor::{ExtractPolicy, IntoWalletDescriptor, Policy as SpendingPolicy};
 use bdk::signer::SignersContainer;
+use bdk::wallet::error::CreateTxError;
 use bdk::wallet::tx_builder::AddUtxoError;
-use bdk::wallet::ChangeSet;
+use bdk::wallet::{AddressIndex, ChangeSet};
 use bdk::{FeeRate, KeychainKind, LocalOutput, Wallet};
 use keechain_core::bitcoin::absolute::{self, Height, Time};
 use keechain_core::bitcoin::address::NetworkUnchecked;
 use keechain_core::bitcoin::bip32::Fingerprint;
 #[cfg(feature = "reserves")]
 use keechain_core::bitcoin::psbt::PartiallySignedTransaction;
+use keechain_core::bitcoin::script::PushBytesBuf;
 use keechain_core::bitcoin::{Address, Network, OutPoint};
 use keechain_core::miniscript::descriptor::DescriptorType;
 use keechain_core::miniscript::policy::Concrete;
@@ -33,11 +35,13 @@ pub use self::template::{
     AbsoluteLockTime, DecayingTime, Locktime, PolicyTemplate, PolicyTemplateType, RecoveryTemplate,
     Sequence,
 };
-use crate::proposal::Proposal;
+use crate::constants::MAX_OP_RETURN_SIZE;
+use crate::descriptor;
+use crate::proposal::{Proposal, Recipient};
 #[cfg(feature = "reserves")]
 use crate::reserves::ProofOfReserves;
 use crate::util::{search_network_for_descriptor, Unspendable};
-use crate::{Amount, Signer, SECP256K1};
+use crate::{Amount, Signer, TxOptions, SECP256K1};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -76,6 +80,10 @@ pub enum Error {
     AbsoluteTimelockNotSatisfied,
     #[error("Relative timelock not satisfied")]
     RelativeTimelockNotSatisfied,
+    #[error("no recipients")]
+    NoRecipients,
+    #[error("OP_RETURN data too large: {0} bytes (max {max})", max = MAX_OP_RETURN_SIZE)]
+    OpReturnTooLarge(usize),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -86,6 +94,35 @@ pub struct SelectableCondition {
     pub sub_paths: Vec<String>,
 }
 
+/// Approval progress of a spending path
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QuorumPath {
+    /// Policy path ID (see [`SelectableCondition`])
+    pub path: String,
+    /// Signers involved in the path
+    pub signers: BTreeSet<Fingerprint>,
+    /// Involved signers that already signed
+    pub signed: BTreeSet<Fingerprint>,
+    /// Signatures needed to satisfy the path (`None` if not satisfiable only with signatures)
+    pub threshold: Option<usize>,
+    /// Signatures still needed to satisfy the path
+    pub required: Option<usize>,
+    /// The path is spendable only after a timelock
+    pub timelock: bool,
+}
+
+impl QuorumPath {
+    /// Involved signers that not signed yet
+    pub fn missing(&self) -> BTreeSet<Fingerprint> {
+        self.signers.difference(&self.signed).copied().collect()
+    }
+
+    /// Check if the collected signatures satisfy the path (timelocks excluded)
+    pub fn is_satisfied(&self) -> bool {
+        self.required == Some(0)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum PolicyPathSelector {
@@ -311,6 +348,26 @@ impl Policy {
         self.network
     }
 
+    /// Set the network of the policy
+    ///
+    /// The network found when deserializing can't distinguish testnet from regtest (same extended key version).
+    pub fn with_network(self, network: Network) -> Result<Self, Error> {
+        if self.network == network {
+            return Ok(self);
+        }
+        Self::new(self.name, self.description, self.descriptor, network)
+    }
+
+    /// Short human-comparable fingerprint of the descriptor (see [`descriptor::fingerprint`])
+    pub fn descriptor_fingerprint(&self) -> Result<String, descriptor::Error> {
+        descriptor::fingerprint(self.descriptor.to_string())
+    }
+
+    /// Coldcard registration file (see [`descriptor::coldcard_config`])
+    pub fn coldcard_config(&self) -> Result<String, descriptor::Error> {
+        descriptor::coldcard_config(&self.name, self.descriptor.to_string())
+    }
+
     /// Check if [`Policy`] has an `absolute` or `relative` timelock
     #[inline]
     pub fn has_timelock(&self) -> bool {
@@ -415,6 +472,38 @@ impl Policy {
         Ok(check(item, None, &path))
     }
 
+    /// Get the approval progress of each spending path, given the signers that already `signed`
+    ///
+    /// Paths without known signers (i.e. the unspendable taproot internal key) are skipped.
+    pub fn quorum_paths(&self, signed: &BTreeSet<Fingerprint>) -> Result<Vec<QuorumPath>, Error> {
+        let item: &SatisfiableItem = self.satisfiable_item()?;
+        let paths: Vec<(String, &SatisfiableItem)> = match item {
+            SatisfiableItem::Thresh { items, threshold } if *threshold == 1 => {
+                items.iter().map(|i| (i.id.clone(), &i.item)).collect()
+            }
+            _ => vec![(item.id(), item)],
+        };
+
+        let none: BTreeSet<Fingerprint> = BTreeSet::new();
+        Ok(paths
+            .into_iter()
+            .filter_map(|(path, item)| {
+                let signers: BTreeSet<Fingerprint> = satisfiable_item_fingerprints(item);
+                if signers.is_empty() {
+                    return None;
+                }
+                Some(QuorumPath {
+                    path,
+                    signed: signers.intersection(signed).copied().collect(),
+                    signers,
+                    threshold: satisfiable_item_required_signatures(item, &none),
+                    required: satisfiable_item_required_signatures(item, signed),
+                    timelock: satisfiable_item_has_timelock(item),
+                })
+            })
+            .collect())
+    }
+
     /// Check if a [Fingerprint] is involved in the [Policy]
     pub fn is_fingerprint_involved(&self, fingerprint: &Fingerprint) -> Result<bool, Error> {
         let item: &SatisfiableItem = self.satisfiable_item()?;
@@ -574,6 +663,47 @@ impl Policy {
         }
     }
 
+    /// Get the `policy path` of a timelocked branch that the [Signer] can satisfy alone (i.e. the
+    /// recovery branch of a decaying or social recovery vault)
+    ///
+    /// The maturity of the timelock is not checked.
+    pub fn timelocked_policy_path(
+        &self,
+        signer: &Signer,
+    ) -> Result<Option<BTreeMap<String, Vec<usize>>>, Error> {
+        let path: BTreeMap<String, Vec<usize>> = match self.get_policy_path_from_signer(signer)? {
+            Some(PolicyPathSelector::Complete { path }) => path,
+            _ => return Ok(None),
+        };
+
+        for SelectableCondition {
+            path: condition,
+            sub_paths,
+            ..
+        } in self
+            .selectable_conditions()?
+            .unwrap_or_default()
+            .into_iter()
+        {
+            if let Some(idxs) = path.get(&condition) {
+                for sub_path in idxs.iter().filter_map(|idx| sub_paths.get(*idx)) {
+                    // Skip the sub-paths further selected (check only the innermost branches)
+                    if path.contains_key(sub_path) {
+                        continue;
+                    }
+
+                    if let Some(item) = self.satisfiable_item_by_path(sub_path)? {
+                        if satisfiable_item_has_timelock(&item) {
+                            return Ok(Some(path));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
     pub fn get_policy_paths_from_signers<I>(&self, my_signers: I) -> Result<PolicyPath, Error>
     where
         I: Iterator<Item = Signer>,
@@ -724,27 +854,44 @@ impl Policy {
                 utxos,
                 frozen_utxos,
                 policy_path,
+                TxOptions::default(),
             )
             .ok()?;
         let psbt = proposal.psbt();
         Some(psbt.unsigned_tx.vsize())
     }
 
-    pub fn spend<D, S>(
+    /// Build spending PSBT
+    ///
+    /// [`Amount::Max`] drains the wallet to the output address.
+    /// [`Amount::MaxWithReserve`] does the same, but sends the reserve to a vault address
+    /// (the fee is calculated including the reserve output).
+    /// If `data` is set, an `OP_RETURN` output embedding it is added.
+    fn build_spending_psbt<D>(
         &self,
         wallet: &mut Wallet<D>,
-        address: Address<NetworkUnchecked>,
-        amount: Amount,
-        description: S,
+        outputs: &[(Address<NetworkUnchecked>, Amount)],
         fee_rate: FeeRate,
         utxos: Option<Vec<OutPoint>>,
         frozen_utxos: Option<Vec<OutPoint>>,
         policy_path: Option<BTreeMap<String, Vec<usize>>>,
-    ) -> Result<Proposal, Error>
+        data: Option<Vec<u8>>,
+        tx_options: TxOptions,
+    ) -> Result<PartiallySignedTransaction, Error>
     where
         D: PersistBackend<ChangeSet>,
-        S: Into<String>,
     {
+        // Check data size (standardness limit of the OP_RETURN outputs)
+        let data: Option<PushBytesBuf> = match data {
+            Some(data) if data.len() > MAX_OP_RETURN_SIZE => {
+                return Err(Error::OpReturnTooLarge(data.len()))
+            }
+            Some(data) => {
+                Some(PushBytesBuf::try_from(data).map_err(|e| Error::BdkCreateTx(e.to_string()))?)
+            }
+            None => None,
+        };
+
         let wallet_utxos: HashMap<OutPoint, LocalOutput> = wallet
             .list_unspent()
             .map(|utxo| (utxo.outpoint, utxo))
@@ -775,44 +922,101 @@ impl Policy {
             }
         }
 
+        // Vault address that receives the reserve (if any)
+        let reserve_script = if outputs
+            .iter()
+            .any(|(_, amount)| matches!(amount, Amount::MaxWithReserve(..)))
+        {
+            let info = wallet
+                .try_get_internal_address(AddressIndex::LastUnused)
+                .map_err(|e| Error::BdkCreateTx(format!("{e:?}")))?;
+            Some(info.address.script_pubkey())
+        } else {
+            None
+        };
+
+        if let Some(utxos) = &utxos {
+            if utxos.is_empty() {
+                return Err(Error::NoUtxosSelected);
+            }
+        }
+
+        // Without an explicit nLockTime, the current height is used (anti-fee-sniping).
+        // Without anti-fee-sniping, the nLockTime is zero unless the policy path requires an
+        // absolute timelock (`after()`): the build is retried with the required one.
+        let mut locktime: Option<absolute::LockTime> = match tx_options.locktime {
+            Some(locktime) => Some(locktime),
+            None if !tx_options.anti_fee_sniping => Some(absolute::LockTime::ZERO),
+            None => None,
+        };
+
         // Build the PSBT
-        let psbt = {
+        let psbt = loop {
             let mut builder = wallet.build_tx();
 
-            if let Some(frozen_utxos) = frozen_utxos {
-                for unspendable in frozen_utxos.into_iter() {
-                    builder.add_unspendable(unspendable);
+            if let Some(frozen_utxos) = &frozen_utxos {
+                for unspendable in frozen_utxos.iter() {
+                    builder.add_unspendable(*unspendable);
                 }
             }
 
-            if let Some(utxos) = utxos {
-                if utxos.is_empty() {
-                    return Err(Error::NoUtxosSelected);
-                }
+            if let Some(utxos) = &utxos {
                 builder.manually_selected_only();
-                builder.add_utxos(&utxos)?;
+                builder.add_utxos(utxos)?;
             }
 
-            if let Some(path) = policy_path.clone() {
-                builder.policy_path(path, KeychainKind::External);
+            if let Some(path) = &policy_path {
+                builder.policy_path(path.clone(), KeychainKind::External);
             }
 
             // TODO: add custom coin selection alorithm (to exclude UTXOs with timelock enabled)
-            builder
-                .fee_rate(fee_rate)
-                .enable_rbf()
-                .current_height(current_height);
-            match amount {
-                Amount::Max => builder
-                    .drain_wallet()
-                    .drain_to(address.payload.script_pubkey()),
-                Amount::Custom(amount) => {
-                    builder.add_recipient(address.payload.script_pubkey(), amount)
+            builder.fee_rate(fee_rate).current_height(current_height);
+
+            match tx_options.sequence {
+                Some(sequence) => {
+                    builder.enable_rbf_with_sequence(sequence);
                 }
+                None if tx_options.rbf => {
+                    builder.enable_rbf();
+                }
+                None => (),
             };
-            builder
-                .finish()
-                .map_err(|e| Error::BdkCreateTx(format!("{e:?}")))?
+
+            if let Some(locktime) = locktime {
+                builder.nlocktime(locktime);
+            }
+
+            for (address, amount) in outputs.iter() {
+                match amount {
+                    Amount::Max => builder
+                        .drain_wallet()
+                        .drain_to(address.payload.script_pubkey()),
+                    Amount::MaxWithReserve(reserve) => {
+                        if let Some(script) = &reserve_script {
+                            builder.add_recipient(script.clone(), *reserve);
+                        }
+                        builder
+                            .drain_wallet()
+                            .drain_to(address.payload.script_pubkey())
+                    }
+                    Amount::Custom(amount) => {
+                        builder.add_recipient(address.payload.script_pubkey(), *amount)
+                    }
+                };
+            }
+            if let Some(data) = &data {
+                builder.add_data(data);
+            }
+            match builder.finish() {
+                Ok(psbt) => break psbt,
+                Err(CreateTxError::LockTime { required, .. })
+                    if tx_options.locktime.is_none()
+                        && locktime == Some(absolute::LockTime::ZERO) =>
+                {
+                    locktime = Some(required);
+                }
+                Err(e) => return Err(Error::BdkCreateTx(format!("{e:?}"))),
+            }
         };
 
         if self.has_timelock() {
@@ -845,8 +1049,38 @@ impl Policy {
             }
         }
 
+        Ok(psbt)
+    }
+
+    pub fn spend<D, S>(
+        &self,
+        wallet: &mut Wallet<D>,
+        address: Address<NetworkUnchecked>,
+        amount: Amount,
+        description: S,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        frozen_utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        tx_options: TxOptions,
+    ) -> Result<Proposal, Error>
+    where
+        D: PersistBackend<ChangeSet>,
+        S: Into<String>,
+    {
+        let psbt: PartiallySignedTransaction = self.build_spending_psbt(
+            wallet,
+            &[(address.clone(), amount)],
+            fee_rate,
+            utxos,
+            frozen_utxos,
+            policy_path.clone(),
+            None,
+            tx_options,
+        )?;
+
         let amount: u64 = match amount {
-            Amount::Max => {
+            Amount::Max | Amount::MaxWithReserve(..) => {
                 let fee: u64 = psbt.fee()?.to_sat();
                 let (sent, received) = wallet.sent_and_received(&psbt.unsigned_tx);
                 sent.saturating_sub(received).saturating_sub(fee)
@@ -864,6 +1098,54 @@ impl Policy {
         ))
     }
 
+    /// Spend to multiple recipients in a single transaction
+    ///
+    /// If `data` is set, an `OP_RETURN` output embedding it (max [`MAX_OP_RETURN_SIZE`] bytes) is
+    /// added (i.e. an invoice hash).
+    pub fn spend_many<D, S>(
+        &self,
+        wallet: &mut Wallet<D>,
+        recipients: Vec<Recipient>,
+        description: S,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        frozen_utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        data: Option<Vec<u8>>,
+        tx_options: TxOptions,
+    ) -> Result<Proposal, Error>
+    where
+        D: PersistBackend<ChangeSet>,
+        S: Into<String>,
+    {
+        if recipients.is_empty() {
+            return Err(Error::NoRecipients);
+        }
+
+        let outputs: Vec<(Address<NetworkUnchecked>, Amount)> = recipients
+            .iter()
+            .map(|r| (r.address.clone(), Amount::Custom(r.amount)))
+            .collect();
+        let psbt: PartiallySignedTransaction = self.build_spending_psbt(
+            wallet,
+            &outputs,
+            fee_rate,
+            utxos,
+            frozen_utxos,
+            policy_path.clone(),
+            data,
+            tx_options,
+        )?;
+
+        Ok(Proposal::batch_spending(
+            self.descriptor.clone(),
+            recipients,
+            description,
+            psbt,
+            policy_path,
+        ))
+    }
+
     #[cfg(feature = "reserves")]
     pub fn proof_of_reserve<D, S>(
         &self,
@@ -893,6 +1175,76 @@ impl Policy {
     }
 }
 
+/// Get the fingerprints of the keys of [SatisfiableItem]
+fn satisfiable_item_fingerprints(item: &SatisfiableItem) -> BTreeSet<Fingerprint> {
+    match item {
+        SatisfiableItem::EcdsaSignature(PkOrF::Fingerprint(f))
+        | SatisfiableItem::SchnorrSignature(PkOrF::Fingerprint(f)) => BTreeSet::from([*f]),
+        SatisfiableItem::Multisig { keys, .. } => keys
+            .iter()
+            .filter_map(|key| match key {
+                PkOrF::Fingerprint(f) => Some(*f),
+                _ => None,
+            })
+            .collect(),
+        SatisfiableItem::Thresh { items, .. } => items
+            .iter()
+            .flat_map(|x| satisfiable_item_fingerprints(&x.item))
+            .collect(),
+        _ => BTreeSet::new(),
+    }
+}
+
+/// Get the signatures, in addition to the `signed` ones, needed to satisfy [SatisfiableItem]
+///
+/// Return `None` if not satisfiable only with signatures (i.e. hash preimages).
+fn satisfiable_item_required_signatures(
+    item: &SatisfiableItem,
+    signed: &BTreeSet<Fingerprint>,
+) -> Option<usize> {
+    match item {
+        SatisfiableItem::EcdsaSignature(key) | SatisfiableItem::SchnorrSignature(key) => {
+            match key {
+                PkOrF::Fingerprint(f) if signed.contains(f) => Some(0),
+                _ => Some(1),
+            }
+        }
+        SatisfiableItem::Multisig { keys, threshold } => {
+            let count: usize = keys
+                .iter()
+                .filter(|key| matches!(key, PkOrF::Fingerprint(f) if signed.contains(f)))
+                .count();
+            Some(threshold.saturating_sub(count))
+        }
+        SatisfiableItem::Thresh { items, threshold } => {
+            let mut required: Vec<usize> = items
+                .iter()
+                .filter_map(|x| satisfiable_item_required_signatures(&x.item, signed))
+                .collect();
+            if required.len() < *threshold {
+                return None;
+            }
+            required.sort_unstable();
+            Some(required.into_iter().take(*threshold).sum())
+        }
+        SatisfiableItem::AbsoluteTimelock { .. } | SatisfiableItem::RelativeTimelock { .. } => {
+            Some(0)
+        }
+        _ => None,
+    }
+}
+
+/// Check if [SatisfiableItem] contains a timelock
+fn satisfiable_item_has_timelock(item: &SatisfiableItem) -> bool {
+    match item {
+        SatisfiableItem::AbsoluteTimelock { .. } | SatisfiableItem::RelativeTimelock { .. } => true,
+        SatisfiableItem::Thresh { items, .. } => {
+            items.iter().any(|x| satisfiable_item_has_timelock(&x.item))
+        }
+        _ => false,
+    }
+}
+
 /// Check if [SatisfiableItem] contains [Fingerprint]
 fn satisfiable_item_contains_fingerprint(
     item: &SatisfiableItem,
@@ -985,6 +1337,15 @@ mod tests {
         assert!(Policy::from_descriptor("", "", descriptor, Network::Bitcoin).is_err())
     }
 
+    #[test]
+    fn test_policy_with_network() {
+        let descriptor = "tr([9bf4354b/86'/1'/784923']tpubDCT8uwnkZj7woaY71Xr5hU7Wvjr7B1BXJEpwMzzDLd1H6HLnKTiaLPtt6ZfEizDMwdQ8PT8JCmKbB4ESVXTkCzv51oxhJhX5FLBvkeN9nJ3/0/*,pk([7356e457/86'/1'/784923']tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d/0/*))#rs0udsfg";
+        let policy = Policy::from_descriptor("", "", descriptor, Network::Testnet).unwrap();
+        let policy = policy.with_network(Network::Regtest).unwrap();
+        assert_eq!(policy.network(), Network::Regtest);
+        assert!(policy.with_network(Network::Bitcoin).is_err());
+    }
+
     #[test]
     fn selectable_conditions() {
         let policy = Policy::from_descriptor("", "", COMPLEX_DESCRIPTOR, NETWORK).unwrap();
@@ -1127,6 +1488,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_timelocked_policy_path() {
+        let policy = Policy::from_descriptor("", "", COMPLEX_DESCRIPTOR, NETWORK).unwrap();
+
+        // Internal key path
+        let mnemonic = Mnemonic::from_str(
+            "possible suffer flavor boring essay zoo collect stairs day cabbage wasp tackle",
+        )
+        .unwrap();
+        let seed = Seed::from_mnemonic(mnemonic);
+        let signer = smartvaults_signer(seed, NETWORK).unwrap();
+        assert_eq!(policy.timelocked_policy_path(&signer).unwrap(), None);
+
+        // Key + older(52000)
+        let mnemonic = Mnemonic::from_str(
+            "vicious climb harsh insane yard aspect frequent already tackle fetch ask throw",
+        )
+        .unwrap();
+        let seed = Seed::from_mnemonic(mnemonic);
+        let signer = smartvaults_signer(seed, NETWORK).unwrap();
+        let mut path: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        path.insert(String::from("fx0z8u06"), vec![0]);
+        path.insert(String::from("y46gds64"), vec![1]);
+        assert_eq!(policy.timelocked_policy_path(&signer).unwrap(), Some(path));
+
+        // Not satisfiable alone
+        let mnemonic = Mnemonic::from_str(
+            "involve camp enter man minimum milk minimum news hockey divert window mind",
+        )
+        .unwrap();
+        let seed = Seed::from_mnemonic(mnemonic);
+        let signer = smartvaults_signer(seed, NETWORK).unwrap();
+        assert_eq!(policy.timelocked_policy_path(&signer).unwrap(), None);
+    }
+
     #[test]
     fn test_get_policy_path_from_signers() {
         // Signer 1
@@ -1218,6 +1614,31 @@ mod tests {
         assert!(!policy.is_fingerprint_involved(&fingerprint).unwrap());
     }
 
+    #[test]
+    fn test_quorum_paths() {
+        let policy = Policy::from_descriptor("", "", COMPLEX_DESCRIPTOR, NETWORK).unwrap();
+
+        let signed = BTreeSet::from([Fingerprint::from_str("f3ab64d8").unwrap()]);
+        let paths = policy.quorum_paths(&signed).unwrap();
+        assert_eq!(paths.len(), 2);
+
+        // Key path
+        let key_path = &paths[0];
+        assert_eq!(key_path.threshold, Some(1));
+        assert_eq!(key_path.required, Some(1));
+        assert!(!key_path.timelock);
+
+        // Script path: f3ab64d8 AND (f57a6b99 after the timelock OR 2 of 2)
+        let script_path = &paths[1];
+        assert_eq!(script_path.signers.len(), 4);
+        assert_eq!(script_path.signed, signed);
+        assert_eq!(script_path.threshold, Some(2));
+        assert_eq!(script_path.required, Some(1));
+        assert_eq!(script_path.missing().len(), 3);
+        assert!(script_path.timelock);
+        assert!(!script_path.is_satisfied());
+    }
+
     #[test]
     fn test_policy_template_match() {
         let singlesig = DescriptorPublicKey::from_str("[7356e457/86'/1'/784923']tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d/0/*").unwrap();
@@ -1,11 +1,14 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
+use std::collections::BTreeSet;
+
+use keechain_core::bitcoin::bip32::Fingerprint;
 use keechain_core::bitcoin::psbt::PartiallySignedTransaction;
 use serde::{Deserialize, Serialize};
 
 use super::ProposalType;
-use crate::util::{deserialize_psbt, serialize_psbt};
+use crate::util::{self, deserialize_psbt, serialize_psbt};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ApprovedProposal {
@@ -60,4 +63,13 @@ impl ApprovedProposal {
             Self::KeyAgentPayment { psbt } => psbt.clone(),
         }
     }
+
+    /// Fingerprints of the signers that approved the proposal
+    pub fn signers(&self) -> BTreeSet<Fingerprint> {
+        match self {
+            Self::Spending { psbt }
+            | Self::ProofOfReserve { psbt }
+            | Self::KeyAgentPayment { psbt } => util::psbt_signers(psbt),
+        }
+    }
 }
@@ -6,7 +6,7 @@ use keechain_core::bitcoin::Transaction;
 use keechain_core::miniscript::Descriptor;
 use serde::{Deserialize, Serialize};
 
-use super::{Period, ProposalType};
+use super::{PaymentMemo, Period, ProposalType};
 use crate::util::{deserialize_psbt, serialize_psbt};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -14,6 +14,8 @@ pub enum CompletedProposal {
     Spending {
         tx: Transaction,
         description: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memo: Option<PaymentMemo>,
     },
     ProofOfReserve {
         message: String,
@@ -40,9 +42,18 @@ impl CompletedProposal {
         Self::Spending {
             tx,
             description: description.into(),
+            memo: None,
         }
     }
 
+    /// Attach the payment records (ignored if not a spending)
+    pub fn with_memo(mut self, memo: Option<PaymentMemo>) -> Self {
+        if let Self::Spending { memo: m, .. } = &mut self {
+            *m = memo.filter(|memo| !memo.is_empty());
+        }
+        self
+    }
+
     pub fn proof_of_reserve<S>(
         message: S,
         descriptor: Descriptor<String>,
@@ -91,6 +102,14 @@ impl CompletedProposal {
         }
     }
 
+    /// Get the payment records of a spending
+    pub fn memo(&self) -> Option<PaymentMemo> {
+        match self {
+            Self::Spending { memo, .. } => memo.clone(),
+            _ => None,
+        }
+    }
+
     pub fn desc(&self) -> String {
         match self {
             Self::Spending { description, .. } => description.clone(),
@@ -0,0 +1,116 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Changes between a proposal and the one it replaces
+
+use std::collections::BTreeSet;
+
+use keechain_core::bitcoin::OutPoint;
+
+use super::{Proposal, Recipient};
+
+/// What changed from the replaced proposal
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProposalDiff {
+    /// Amount change (SAT)
+    pub amount: i64,
+    /// Fee change (SAT), if the fee of both the proposals is known
+    pub fee: Option<i64>,
+    /// New recipients (or recipients with a different amount)
+    pub added_recipients: Vec<Recipient>,
+    /// Recipients no longer paid (or paid a different amount)
+    pub removed_recipients: Vec<Recipient>,
+    pub added_inputs: Vec<OutPoint>,
+    pub removed_inputs: Vec<OutPoint>,
+    /// New description, if changed
+    pub description: Option<String>,
+}
+
+impl ProposalDiff {
+    /// Check if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.amount == 0
+            && self.fee.unwrap_or_default() == 0
+            && self.added_recipients.is_empty()
+            && self.removed_recipients.is_empty()
+            && !self.inputs_changed()
+            && self.description.is_none()
+    }
+
+    /// Check if the destination addresses changed (not only the amounts)
+    pub fn destination_changed(&self) -> bool {
+        let contains = |list: &[Recipient], recipient: &Recipient| {
+            list.iter().any(|r| r.address == recipient.address)
+        };
+        self.added_recipients
+            .iter()
+            .any(|r| !contains(&self.removed_recipients, r))
+            || self
+                .removed_recipients
+                .iter()
+                .any(|r| !contains(&self.added_recipients, r))
+    }
+
+    /// Check if the inputs changed
+    pub fn inputs_changed(&self) -> bool {
+        !self.added_inputs.is_empty() || !self.removed_inputs.is_empty()
+    }
+}
+
+impl Proposal {
+    /// Total amount sent (0 for proof of reserve)
+    fn amount(&self) -> u64 {
+        match self {
+            Self::Spending { .. } => self.recipients().iter().map(|r| r.amount).sum(),
+            Self::KeyAgentPayment { amount, .. } => *amount,
+            Self::ProofOfReserve { .. } => 0,
+        }
+    }
+
+    fn fee(&self) -> Option<u64> {
+        self.psbt().fee().ok().map(|fee| fee.to_sat())
+    }
+
+    fn inputs(&self) -> BTreeSet<OutPoint> {
+        self.psbt()
+            .unsigned_tx
+            .input
+            .iter()
+            .map(|txin| txin.previous_output)
+            .collect()
+    }
+
+    /// Compute the changes from the `previous` proposal
+    pub fn diff(&self, previous: &Proposal) -> ProposalDiff {
+        let recipients: Vec<Recipient> = self.recipients();
+        let previous_recipients: Vec<Recipient> = previous.recipients();
+        let inputs: BTreeSet<OutPoint> = self.inputs();
+        let previous_inputs: BTreeSet<OutPoint> = previous.inputs();
+        let description: String = self.description();
+
+        ProposalDiff {
+            amount: self.amount() as i64 - previous.amount() as i64,
+            fee: match (self.fee(), previous.fee()) {
+                (Some(fee), Some(previous)) => Some(fee as i64 - previous as i64),
+                _ => None,
+            },
+            added_recipients: recipients
+                .iter()
+                .filter(|r| !previous_recipients.contains(r))
+                .cloned()
+                .collect(),
+            removed_recipients: previous_recipients
+                .iter()
+                .filter(|r| !recipients.contains(r))
+                .cloned()
+                .collect(),
+            added_inputs: inputs.difference(&previous_inputs).copied().collect(),
+            removed_inputs: previous_inputs.difference(&inputs).copied().collect(),
+            description: if description != previous.description() {
+                Some(description)
+            } else {
+                None
+            },
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Structured records of a spending (i.e. travel rule data)
+
+use core::fmt;
+
+use keechain_core::bitcoin::hashes::sha256;
+use serde::{Deserialize, Serialize};
+
+/// Records attached to a spending proposal, encrypted with the proposal for the vault members
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PaymentMemo {
+    /// Name of the beneficiary
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beneficiary_name: Option<String>,
+    /// External reference (i.e. the invoice number)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference_id: Option<String>,
+    /// Hash of the invoice document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub invoice_hash: Option<sha256::Hash>,
+}
+
+impl fmt::Display for PaymentMemo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields: Vec<String> = Vec::new();
+        if let Some(name) = &self.beneficiary_name {
+            fields.push(format!("beneficiary: {name}"));
+        }
+        if let Some(reference_id) = &self.reference_id {
+            fields.push(format!("reference: {reference_id}"));
+        }
+        if let Some(hash) = &self.invoice_hash {
+            fields.push(format!("invoice hash: {hash}"));
+        }
+        write!(f, "{}", fields.join(", "))
+    }
+}
+
+impl PaymentMemo {
+    /// Check if no record is set
+    pub fn is_empty(&self) -> bool {
+        self.beneficiary_name.is_none()
+            && self.reference_id.is_none()
+            && self.invoice_hash.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_payment_memo() {
+        let memo: PaymentMemo = serde_json::from_str(r#"{"reference_id":"INV-42"}"#).unwrap();
+        assert!(!memo.is_empty());
+        assert_eq!(memo.to_string(), "reference: INV-42");
+
+        let memo = PaymentMemo {
+            beneficiary_name: Some(String::from("ACME Ltd")),
+            invoice_hash: Some(
+                sha256::Hash::from_str(
+                    "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                )
+                .unwrap(),
+            ),
+            ..Default::default()
+        };
+        let json: String = serde_json::to_string(&memo).unwrap();
+        assert!(!json.contains("reference_id"));
+        assert_eq!(serde_json::from_str::<PaymentMemo>(&json).unwrap(), memo);
+
+        assert!(PaymentMemo::default().is_empty());
+    }
+}
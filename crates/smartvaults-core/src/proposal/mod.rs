@@ -2,12 +2,13 @@
 // Distributed under the MIT software license
 
 use std::cmp::Ordering;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 
 use keechain_core::bdk::signer::{SignerError, SignerWrapper};
 use keechain_core::bdk::{SignOptions, Wallet};
 use keechain_core::bitcoin::address::NetworkUnchecked;
+use keechain_core::bitcoin::bip32::Fingerprint;
 use keechain_core::bitcoin::psbt::{
     Error as PsbtError, PartiallySignedTransaction, PsbtParseError,
 };
@@ -20,10 +21,14 @@ use serde::{Deserialize, Serialize};
 
 mod approved;
 mod completed;
+mod diff;
+mod memo;
 
 pub use self::approved::ApprovedProposal;
 pub use self::completed::CompletedProposal;
-use crate::util::{deserialize_psbt, serialize_psbt};
+pub use self::diff::ProposalDiff;
+pub use self::memo::PaymentMemo;
+use crate::util::{self, deserialize_psbt, serialize_psbt};
 use crate::SECP256K1;
 
 #[derive(Debug, thiserror::Error)]
@@ -75,11 +80,21 @@ pub struct Period {
     pub to: u64,
 }
 
+/// Spending recipient
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Recipient {
+    pub address: Address<NetworkUnchecked>,
+    /// Amount in SAT
+    pub amount: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Proposal {
     Spending {
         descriptor: Descriptor<String>,
+        /// First recipient address (see `recipients` for batched spending)
         to_address: Address<NetworkUnchecked>,
+        /// Total amount sent to recipients
         amount: u64,
         description: String,
         #[serde(
@@ -88,6 +103,11 @@ pub enum Proposal {
         )]
         psbt: PartiallySignedTransaction,
         policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        /// All the recipients of a batched spending (empty if single recipient)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        recipients: Vec<Recipient>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memo: Option<PaymentMemo>,
     },
     ProofOfReserve {
         descriptor: Descriptor<String>,
@@ -145,6 +165,38 @@ impl Proposal {
             description: description.into(),
             psbt,
             policy_path,
+            recipients: Vec::new(),
+            memo: None,
+        }
+    }
+
+    /// Spending to multiple recipients
+    ///
+    /// Panics if `recipients` is empty.
+    pub fn batch_spending<S>(
+        descriptor: Descriptor<String>,
+        recipients: Vec<Recipient>,
+        description: S,
+        psbt: PartiallySignedTransaction,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        if recipients.len() == 1 {
+            let Recipient { address, amount } = recipients[0].clone();
+            return Self::spending(descriptor, address, amount, description, psbt, policy_path);
+        }
+
+        Self::Spending {
+            descriptor,
+            to_address: recipients[0].address.clone(),
+            amount: recipients.iter().map(|r| r.amount).sum(),
+            description: description.into(),
+            psbt,
+            policy_path,
+            recipients,
+            memo: None,
         }
     }
 
@@ -186,6 +238,14 @@ impl Proposal {
         }
     }
 
+    /// Attach the payment records (ignored if not a spending proposal)
+    pub fn with_memo(mut self, memo: Option<PaymentMemo>) -> Self {
+        if let Self::Spending { memo: m, .. } = &mut self {
+            *m = memo.filter(|memo| !memo.is_empty());
+        }
+        self
+    }
+
     pub fn get_type(&self) -> ProposalType {
         match self {
             Self::Spending { .. } => ProposalType::Spending,
@@ -194,6 +254,14 @@ impl Proposal {
         }
     }
 
+    /// Get the payment records of a spending proposal
+    pub fn memo(&self) -> Option<PaymentMemo> {
+        match self {
+            Self::Spending { memo, .. } => memo.clone(),
+            _ => None,
+        }
+    }
+
     pub fn descriptor(&self) -> Descriptor<String> {
         match self {
             Self::Spending { descriptor, .. } => descriptor.clone(),
@@ -210,6 +278,30 @@ impl Proposal {
         }
     }
 
+    /// Get spending recipients
+    ///
+    /// Return an empty list if not a spending proposal.
+    pub fn recipients(&self) -> Vec<Recipient> {
+        match self {
+            Self::Spending {
+                to_address,
+                amount,
+                recipients,
+                ..
+            } => {
+                if recipients.is_empty() {
+                    vec![Recipient {
+                        address: to_address.clone(),
+                        amount: *amount,
+                    }]
+                } else {
+                    recipients.clone()
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
     pub fn psbt(&self) -> PartiallySignedTransaction {
         match self {
             Self::Spending { psbt, .. } => psbt.clone(),
@@ -218,6 +310,19 @@ impl Proposal {
         }
     }
 
+    /// Data embedded in the `OP_RETURN` output of the spending transaction (i.e. an invoice hash)
+    pub fn op_return_data(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::Spending { psbt, .. } => util::op_return_data(&psbt.unsigned_tx),
+            _ => None,
+        }
+    }
+
+    /// Fingerprints of the signers that can approve the proposal
+    pub fn involved_signers(&self) -> BTreeSet<Fingerprint> {
+        util::psbt_involved_fingerprints(&self.psbt())
+    }
+
     pub fn approve(
         &self,
         seed: &Seed,
@@ -306,14 +411,16 @@ impl Proposal {
 
         // Finalize the proposal
         match self {
-            Self::Spending { description, .. } => {
+            Self::Spending {
+                description, memo, ..
+            } => {
                 base_psbt
                     .finalize_mut(&SECP256K1)
                     .map_err(Error::ImpossibleToFinalizePsbt)?;
-                Ok(CompletedProposal::spending(
-                    base_psbt.extract_tx(),
-                    description,
-                ))
+                Ok(
+                    CompletedProposal::spending(base_psbt.extract_tx(), description)
+                        .with_memo(memo.clone()),
+                )
             }
             Self::KeyAgentPayment {
                 signer_descriptor,
@@ -1,6 +1,9 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
+use core::fmt;
+use core::str::FromStr;
+
 use keechain_core::bdk::chain::{ConfirmationTime, PersistBackend};
 use keechain_core::bdk::wallet::tx_builder::{AddForeignUtxoError, TxOrdering};
 use keechain_core::bdk::wallet::{ChangeSet, Wallet};
@@ -59,6 +62,9 @@ pub enum ProofError {
     BdkAddForeignUtxo(#[from] AddForeignUtxoError),
     #[error("{0}")]
     BdkCreateTx(String),
+    /// Invalid proof package
+    #[error("invalid proof package: {0}")]
+    InvalidPackage(String),
 }
 
 /// The API for proof of reserves
@@ -170,6 +176,134 @@ where
     }
 }
 
+/// Self-contained Proof of Reserve, verifiable without a Smart Vaults wallet
+///
+/// The text format is a list of `key: value` lines (`network`, `message`, `descriptor` and `psbt`).
+/// The PSBT is base64 encoded, so it can be inspected with `bitcoin-cli decodepsbt` or `bdk-cli`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProofPackage {
+    pub network: Network,
+    pub message: String,
+    pub descriptor: Option<String>,
+    pub psbt: PartiallySignedTransaction,
+}
+
+impl ProofPackage {
+    pub fn new<S>(
+        network: Network,
+        message: S,
+        descriptor: Option<String>,
+        psbt: PartiallySignedTransaction,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            network,
+            message: message.into(),
+            descriptor,
+            psbt,
+        }
+    }
+
+    /// Outpoints of the proof, excluding the challenge input
+    ///
+    /// The verifier must check that they are still unspent (i.e. with `bitcoin-cli gettxout`).
+    pub fn outpoints(&self) -> Vec<OutPoint> {
+        self.psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .skip(1)
+            .map(|txin| txin.previous_output)
+            .collect()
+    }
+
+    /// Verify the proof using the previous outputs embedded in the PSBT
+    ///
+    /// This doesn't check if the outpoints are still unspent: see [`ProofPackage::outpoints`].
+    /// Returns the amount of the proof.
+    pub fn verify(&self) -> Result<u64, ProofError> {
+        let mut outpoints: Vec<(OutPoint, TxOut)> = Vec::new();
+        for (i, (txin, input)) in self
+            .psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .zip(self.psbt.inputs.iter())
+            .enumerate()
+            .skip(1)
+        {
+            let outpoint: OutPoint = txin.previous_output;
+            let txout: TxOut = match (&input.witness_utxo, &input.non_witness_utxo) {
+                (Some(txout), _) => txout.clone(),
+                (None, Some(tx)) => tx
+                    .output
+                    .get(outpoint.vout as usize)
+                    .cloned()
+                    .ok_or(ProofError::OutpointNotFound(i))?,
+                (None, None) => return Err(ProofError::OutpointNotFound(i)),
+            };
+            outpoints.push((outpoint, txout));
+        }
+        verify_proof(&self.psbt, self.message.clone(), outpoints, self.network)
+    }
+}
+
+impl fmt::Display for ProofPackage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "network: {}", self.network)?;
+        writeln!(f, "message: {}", self.message)?;
+        if let Some(descriptor) = &self.descriptor {
+            writeln!(f, "descriptor: {descriptor}")?;
+        }
+        writeln!(f, "psbt: {}", self.psbt)
+    }
+}
+
+impl FromStr for ProofPackage {
+    type Err = ProofError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut network: Option<Network> = None;
+        let mut message: Option<String> = None;
+        let mut descriptor: Option<String> = None;
+        let mut psbt: Option<PartiallySignedTransaction> = None;
+
+        for line in s.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let (key, value) = line
+                .split_once(": ")
+                .ok_or_else(|| ProofError::InvalidPackage(format!("invalid line: {line}")))?;
+            match key {
+                "network" => {
+                    network = Some(
+                        Network::from_str(value)
+                            .map_err(|e| ProofError::InvalidPackage(e.to_string()))?,
+                    )
+                }
+                "message" => message = Some(value.to_string()),
+                "descriptor" => descriptor = Some(value.to_string()),
+                "psbt" => {
+                    psbt = Some(
+                        PartiallySignedTransaction::from_str(value)
+                            .map_err(|e| ProofError::InvalidPackage(e.to_string()))?,
+                    )
+                }
+                _ => (),
+            }
+        }
+
+        Ok(Self {
+            network: network
+                .ok_or_else(|| ProofError::InvalidPackage(String::from("missing network")))?,
+            message: message
+                .ok_or_else(|| ProofError::InvalidPackage(String::from("missing message")))?,
+            descriptor,
+            psbt: psbt.ok_or_else(|| ProofError::InvalidPackage(String::from("missing psbt")))?,
+        })
+    }
+}
+
 /// Make sure this is a proof, and not a spendable transaction.
 /// Make sure the proof is valid.
 /// Currently proofs can only be validated against the tip of the chain.
@@ -404,6 +538,21 @@ mod tests {
     // assert_eq!(spendable, 50_000);
     // }
 
+    #[test]
+    fn test_package_text() {
+        let psbt = get_signed_proof();
+        let package = ProofPackage::new(
+            Network::Testnet,
+            MESSAGE,
+            Some(DESCRIPTOR.to_string()),
+            psbt.clone(),
+        );
+        let parsed = ProofPackage::from_str(&package.to_string()).unwrap();
+        assert_eq!(parsed, package);
+        assert_eq!(parsed.outpoints().len(), psbt.unsigned_tx.input.len() - 1);
+        assert!(ProofPackage::from_str("network: testnet").is_err());
+    }
+
     #[test]
     #[should_panic(expected = "ChallengeInputMismatch")]
     fn wrong_message() {
@@ -0,0 +1,163 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Descriptor-only API
+//!
+//! Pure descriptor, policy and PSBT operations: no network, no storage and no wallet database
+//! (i.e. to combine and finalize PSBTs exchanged offline).
+//! Allow to reuse the policy engine (i.e. in other Rust projects) without the SDK.
+
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use keechain_core::bitcoin::bip32::{ChildNumber, DerivationPath};
+use keechain_core::bitcoin::psbt::{Error as PsbtError, Input, PartiallySignedTransaction};
+use keechain_core::bitcoin::{Network, OutPoint, ScriptBuf, Transaction, TxOut};
+use keechain_core::miniscript::descriptor::ConversionError;
+use keechain_core::miniscript::psbt::PsbtExt;
+use keechain_core::miniscript::{Descriptor, DescriptorPublicKey};
+
+use crate::policy::{self, Policy, PolicyTemplate};
+use crate::SECP256K1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Policy(#[from] policy::Error),
+    #[error(transparent)]
+    Miniscript(#[from] keechain_core::miniscript::Error),
+    #[error(transparent)]
+    Conversion(#[from] ConversionError),
+    #[error(transparent)]
+    Psbt(#[from] PsbtError),
+    #[error("no PSBTs to combine")]
+    NoPsbts,
+    #[error("impossible to finalize PSBT: {0}")]
+    ImpossibleToFinalize(String),
+    #[error("input {0} without previous output")]
+    MissingInputUtxo(OutPoint),
+    #[error("input {0} not spendable by the descriptor")]
+    ForeignInput(OutPoint),
+}
+
+/// Compile the policy template to a (taproot) descriptor
+pub fn compile_template(
+    template: PolicyTemplate,
+    network: Network,
+) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+    let policy: Policy = Policy::from_template("", "", template, network)?;
+    Ok(Descriptor::from_str(&policy.as_descriptor().to_string())?)
+}
+
+/// Max weight (WU) of the witness needed to spend an output of the descriptor, through the
+/// most expensive spending path
+pub fn satisfaction_weight(descriptor: &Descriptor<DescriptorPublicKey>) -> Result<usize, Error> {
+    Ok(descriptor.max_weight_to_satisfy()?)
+}
+
+/// Derivation indexes declared by the key origins of the PSBT input
+fn derivation_indexes(input: &Input) -> BTreeSet<u32> {
+    input
+        .bip32_derivation
+        .values()
+        .map(|(_, path)| path)
+        .chain(input.tap_key_origins.values().map(|(_, (_, path))| path))
+        .filter_map(|path: &DerivationPath| match path.into_iter().last() {
+            Some(ChildNumber::Normal { index }) => Some(*index),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check that all the inputs of the PSBT are outputs of the descriptor
+///
+/// The derivation index of each input is taken from its key origins.
+pub fn validate_psbt(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    psbt: &PartiallySignedTransaction,
+) -> Result<(), Error> {
+    for (txin, input) in psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()) {
+        let outpoint: OutPoint = txin.previous_output;
+        let prevout: TxOut = match (&input.witness_utxo, &input.non_witness_utxo) {
+            (Some(txout), _) => txout.clone(),
+            (None, Some(tx)) => tx
+                .output
+                .get(outpoint.vout as usize)
+                .cloned()
+                .ok_or(Error::MissingInputUtxo(outpoint))?,
+            (None, None) => return Err(Error::MissingInputUtxo(outpoint)),
+        };
+
+        let indexes: BTreeSet<u32> = if descriptor.has_wildcard() {
+            derivation_indexes(input)
+        } else {
+            BTreeSet::from([0])
+        };
+
+        let mut found: bool = false;
+        for index in indexes.into_iter() {
+            let script: ScriptBuf = descriptor.at_derivation_index(index)?.script_pubkey();
+            if script == prevout.script_pubkey {
+                found = true;
+                break;
+            }
+        }
+
+        if !found {
+            return Err(Error::ForeignInput(outpoint));
+        }
+    }
+
+    Ok(())
+}
+
+/// Combine the PSBTs of the same transaction (i.e. signed by different signers)
+pub fn combine_psbts<I>(psbts: I) -> Result<PartiallySignedTransaction, Error>
+where
+    I: IntoIterator<Item = PartiallySignedTransaction>,
+{
+    let mut psbts = psbts.into_iter();
+    let mut base: PartiallySignedTransaction = psbts.next().ok_or(Error::NoPsbts)?;
+    for psbt in psbts {
+        base.combine(psbt)?;
+    }
+    Ok(base)
+}
+
+/// Finalize the PSBT and extract the signed transaction
+pub fn finalize_psbt(mut psbt: PartiallySignedTransaction) -> Result<Transaction, Error> {
+    psbt.finalize_mut(&SECP256K1).map_err(|errors| {
+        Error::ImpossibleToFinalize(
+            errors
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    })?;
+    Ok(psbt.extract_tx())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESC_A: &str = "[7356e457/86'/1'/784923']tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d/0/*";
+    const DESC_B: &str = "[4eb5d5a1/86'/1'/784923']tpubDCLskGdzStPPo1auRQygJUfbmLMwujWr7fmekdUMD7gqSpwEcRso4CfiP5GkRqfXFYkfqTujyvuehb7inymMhBJFdbJqFyHsHVRuwLKCSe9/0/*";
+
+    #[test]
+    fn test_compile_template() {
+        let desc_a = DescriptorPublicKey::from_str(DESC_A).unwrap();
+        let desc_b = DescriptorPublicKey::from_str(DESC_B).unwrap();
+
+        let single =
+            compile_template(PolicyTemplate::singlesig(desc_a.clone()), Network::Testnet).unwrap();
+        let multisig = compile_template(
+            PolicyTemplate::multisig(2, vec![desc_a, desc_b]),
+            Network::Testnet,
+        )
+        .unwrap();
+
+        assert!(satisfaction_weight(&multisig).unwrap() > satisfaction_weight(&single).unwrap());
+    }
+}
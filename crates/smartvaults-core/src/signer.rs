@@ -4,9 +4,10 @@
 use core::fmt;
 
 use bdk::descriptor::IntoWalletDescriptor;
-use bdk::miniscript::descriptor::Tr;
+use bdk::miniscript::descriptor::{DescriptorXKey, Tr, Wildcard};
 use keechain_core::bips::bip32::{self, Bip32, Fingerprint};
 use keechain_core::bips::bip48::ScriptType;
+use keechain_core::bitcoin::bip32::{ChildNumber, DerivationPath, ExtendedPubKey};
 use keechain_core::bitcoin::Network;
 use keechain_core::crypto::hash;
 use keechain_core::descriptors::{self, ToDescriptor};
@@ -35,6 +36,8 @@ pub enum Error {
     Coldcard(#[from] keechain_core::export::coldcard::Error),
     #[error("must be a taproot descriptor")]
     NotTaprootDescriptor,
+    #[error("descriptor key must be an extended public key with `/0/*` derivation")]
+    UnsupportedDescriptorKey,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -205,6 +208,47 @@ impl Signer {
         self.t
     }
 
+    fn descriptor_xkey(&self) -> Result<DescriptorXKey<ExtendedPubKey>, Error> {
+        match self.descriptor_public_key()? {
+            DescriptorPublicKey::XPub(xkey) => Ok(xkey),
+            _ => Err(Error::UnsupportedDescriptorKey),
+        }
+    }
+
+    /// Purpose of the key derivation path, if known
+    pub fn purpose(&self) -> Option<Purpose> {
+        let xkey = self.descriptor_xkey().ok()?;
+        let (_, path) = xkey.origin?;
+        match path.as_ref() {
+            [ChildNumber::Hardened { index: 86 }, _, _] => Some(Purpose::BIP86),
+            [ChildNumber::Hardened { index: 48 }, _, _, ChildNumber::Hardened { index: 3 }] => {
+                Some(Purpose::BIP48 {
+                    script: ScriptType::P2TR,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Account extended public key, with key origin (i.e. `[fingerprint/86'/0'/0']xpub...`)
+    pub fn xpub(&self) -> Result<String, Error> {
+        let mut xkey = self.descriptor_xkey()?;
+        xkey.derivation_path = DerivationPath::master();
+        xkey.wildcard = Wildcard::None;
+        Ok(DescriptorPublicKey::XPub(xkey).to_string())
+    }
+
+    /// Change descriptor (`/1/*` derivation of the account key)
+    pub fn change_descriptor(&self) -> Result<Descriptor<DescriptorPublicKey>, Error> {
+        let mut xkey = self.descriptor_xkey()?;
+        if xkey.derivation_path.as_ref() != [ChildNumber::Normal { index: 0 }] {
+            return Err(Error::UnsupportedDescriptorKey);
+        }
+        xkey.derivation_path = DerivationPath::from(vec![ChildNumber::Normal { index: 1 }]);
+        let descriptor = Tr::new(DescriptorPublicKey::XPub(xkey), None)?;
+        Ok(Descriptor::Tr(descriptor))
+    }
+
     /// Generate deterministic identifier
     pub fn generate_identifier(&self, network: Network) -> String {
         let unhashed: String = format!("{}:{}", network.magic(), self.fingerprint);
@@ -4,6 +4,8 @@
 use core::fmt;
 use std::str::FromStr;
 
+use keechain_core::bitcoin::{absolute, Sequence};
+
 pub use keechain_core::types::*;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -89,6 +91,8 @@ impl fmt::Display for FeeRate {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Amount {
     Max,
+    /// Send all the funds, keeping a reserve (SAT) in the vault (i.e. for future fee bumps)
+    MaxWithReserve(u64),
     Custom(u64),
 }
 
@@ -98,7 +102,10 @@ impl FromStr for Amount {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "max" => Ok(Amount::Max),
-            _ => Ok(Amount::Custom(s.parse()?)),
+            s => match s.strip_prefix("max:") {
+                Some(reserve) => Ok(Amount::MaxWithReserve(reserve.parse()?)),
+                None => Ok(Amount::Custom(s.parse()?)),
+            },
         }
     }
 }
@@ -109,6 +116,11 @@ impl Amount {
         Self::Max
     }
 
+    #[inline]
+    pub fn max_with_reserve(reserve: u64) -> Self {
+        Self::MaxWithReserve(reserve)
+    }
+
     #[inline]
     pub fn from_sat(sat: u64) -> Self {
         Self::Custom(sat)
@@ -121,6 +133,35 @@ impl Amount {
     }
 }
 
+/// Transaction-level options of a spending transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxOptions {
+    /// Set the nLockTime to the current height, to discourage fee sniping
+    ///
+    /// If disabled, the nLockTime is zero or, if the policy path requires an absolute timelock
+    /// (`after()`), the required one.
+    pub anti_fee_sniping: bool,
+    /// Signal replaceability (BIP125)
+    pub rbf: bool,
+    /// Custom nLockTime (i.e. to satisfy an `after()` condition), instead of the current height
+    pub locktime: Option<absolute::LockTime>,
+    /// Custom nSequence of the inputs (i.e. to satisfy an `older()` condition of a recovery path)
+    ///
+    /// Must signal replaceability (lower than `0xfffffffe`).
+    pub sequence: Option<Sequence>,
+}
+
+impl Default for TxOptions {
+    fn default() -> Self {
+        Self {
+            anti_fee_sniping: true,
+            rbf: true,
+            locktime: None,
+            sequence: None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -151,5 +192,8 @@ mod test {
         assert_eq!(Amount::Max, amount);
         let amount: Amount = Amount::from_str("11535").unwrap();
         assert_eq!(Amount::Custom(11535), amount);
+        let amount: Amount = Amount::from_str("max:5000").unwrap();
+        assert_eq!(Amount::MaxWithReserve(5000), amount);
+        assert!(Amount::from_str("max:").is_err());
     }
 }
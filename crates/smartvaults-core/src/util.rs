@@ -1,15 +1,18 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
+use std::collections::BTreeSet;
 use std::str::FromStr;
 
 use ::serde::{Deserialize, Deserializer, Serializer};
 use keechain_core::bdk::descriptor::{DescriptorError, IntoWalletDescriptor};
 use keechain_core::bdk::keys::KeyError;
 use keechain_core::bdk::miniscript::Descriptor;
+use keechain_core::bitcoin::bip32::Fingerprint;
 use keechain_core::bitcoin::psbt::PartiallySignedTransaction;
+use keechain_core::bitcoin::script::Instruction;
 use keechain_core::bitcoin::secp256k1::rand::rngs::OsRng;
-use keechain_core::bitcoin::Network;
+use keechain_core::bitcoin::{Network, Transaction};
 use keechain_core::secp256k1::{Secp256k1, Signing, XOnlyPublicKey};
 pub use keechain_core::util::*;
 
@@ -53,6 +56,60 @@ where
     PartiallySignedTransaction::from_str(&psbt).map_err(::serde::de::Error::custom)
 }
 
+/// Get the fingerprints of the keys that signed the PSBT
+pub fn psbt_signers(psbt: &PartiallySignedTransaction) -> BTreeSet<Fingerprint> {
+    let mut signers: BTreeSet<Fingerprint> = BTreeSet::new();
+    for input in psbt.inputs.iter() {
+        for public_key in input.partial_sigs.keys() {
+            if let Some((fingerprint, ..)) = input.bip32_derivation.get(&public_key.inner) {
+                signers.insert(*fingerprint);
+            }
+        }
+
+        for (public_key, ..) in input.tap_script_sigs.keys() {
+            if let Some((.., (fingerprint, ..))) = input.tap_key_origins.get(public_key) {
+                signers.insert(*fingerprint);
+            }
+        }
+
+        if input.tap_key_sig.is_some() {
+            if let Some((.., (fingerprint, ..))) = input
+                .tap_internal_key
+                .and_then(|internal_key| input.tap_key_origins.get(&internal_key))
+            {
+                signers.insert(*fingerprint);
+            }
+        }
+    }
+    signers
+}
+
+/// Get the fingerprints of the keys that can sign the PSBT
+pub fn psbt_involved_fingerprints(psbt: &PartiallySignedTransaction) -> BTreeSet<Fingerprint> {
+    let mut fingerprints: BTreeSet<Fingerprint> = BTreeSet::new();
+    for input in psbt.inputs.iter() {
+        fingerprints.extend(input.bip32_derivation.values().map(|(f, ..)| *f));
+        fingerprints.extend(input.tap_key_origins.values().map(|(.., (f, ..))| *f));
+    }
+    fingerprints
+}
+
+/// Get the data embedded in the first `OP_RETURN` output of the transaction
+pub fn op_return_data(tx: &Transaction) -> Option<Vec<u8>> {
+    let txout = tx.output.iter().find(|o| o.script_pubkey.is_op_return())?;
+    Some(
+        txout
+            .script_pubkey
+            .instructions()
+            .filter_map(|i| match i {
+                Ok(Instruction::PushBytes(bytes)) => Some(bytes.as_bytes().to_vec()),
+                _ => None,
+            })
+            .flatten()
+            .collect(),
+    )
+}
+
 /// Search the [`Network`] of the descriptor
 #[tracing::instrument(skip_all, level = "trace")]
 pub fn search_network_for_descriptor(desc: &Descriptor<String>) -> Option<Network> {
@@ -95,6 +152,32 @@ mod tests {
         let network = search_network_for_descriptor(&desc);
         assert_eq!(network, Some(Network::Bitcoin));
     }
+
+    #[test]
+    fn test_op_return_data() {
+        use keechain_core::bitcoin::absolute::LockTime;
+        use keechain_core::bitcoin::script::PushBytesBuf;
+        use keechain_core::bitcoin::{ScriptBuf, TxOut};
+
+        let data: Vec<u8> = b"invoice-42".to_vec();
+        let mut tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: Vec::new(),
+            output: vec![TxOut {
+                value: 1_000,
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        assert_eq!(op_return_data(&tx), None);
+
+        let push = PushBytesBuf::try_from(data.clone()).unwrap();
+        tx.output.push(TxOut {
+            value: 0,
+            script_pubkey: ScriptBuf::new_op_return(&push),
+        });
+        assert_eq!(op_return_data(&tx), Some(data));
+    }
 }
 
 #[cfg(bench)]
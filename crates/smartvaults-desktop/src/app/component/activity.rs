@@ -1,13 +1,13 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 
-use iced::widget::{Column, Row, Space};
-use iced::{Alignment, Length};
+use iced::widget::{Checkbox, Column, Container, Row, Space};
+use iced::{Alignment, Element, Length};
 use smartvaults_sdk::core::bdk::chain::ConfirmationTime;
 use smartvaults_sdk::core::proposal::Proposal;
-use smartvaults_sdk::nostr::Timestamp;
+use smartvaults_sdk::nostr::{EventId, Timestamp};
 use smartvaults_sdk::types::{GetCompletedProposal, GetProposal, GetTransaction};
 use smartvaults_sdk::util;
 
@@ -15,13 +15,16 @@ use crate::app::{Context, Message, Stage};
 use crate::component::{
     rule, Amount, AmountSign, Badge, BadgeStyle, Button, ButtonStyle, Icon, Text,
 };
-use crate::theme::color::{GREEN, YELLOW};
+use crate::theme::color::{GREEN, RED, YELLOW};
 use crate::theme::icon::{BROWSER, CHECK, CLIPBOARD, FULLSCREEN, HOURGLASS};
 
+type Selection = (HashSet<EventId>, fn(EventId, bool) -> Message);
+
 pub struct Activity {
     proposals: Vec<GetProposal>,
     txs: BTreeSet<GetTransaction>,
     hide_policy_id: bool,
+    selection: Option<Selection>,
 }
 
 impl Activity {
@@ -30,9 +33,42 @@ impl Activity {
             proposals,
             txs,
             hide_policy_id: false,
+            selection: None,
+        }
+    }
+
+    /// Show a checkbox to select the proposals to approve
+    pub fn selectable(
+        self,
+        selected: HashSet<EventId>,
+        on_select: fn(EventId, bool) -> Message,
+    ) -> Self {
+        Self {
+            selection: Some((selected, on_select)),
+            ..self
         }
     }
 
+    fn select_box(
+        selection: &Option<Selection>,
+        proposal_id: EventId,
+        signed: bool,
+    ) -> Container<'static, Message> {
+        let content: Element<'static, Message> = match selection {
+            Some((selected, on_select)) if !signed => {
+                let on_select = *on_select;
+                Checkbox::new("", selected.contains(&proposal_id), move |value| {
+                    on_select(proposal_id, value)
+                })
+                .into()
+            }
+            _ => Space::with_width(Length::Fixed(70.0)).into(),
+        };
+        Container::new(content)
+            .width(Length::Fixed(70.0))
+            .center_x()
+    }
+
     pub fn hide_policy_id(self) -> Self {
         Self {
             hide_policy_id: true,
@@ -93,6 +129,9 @@ impl Activity {
                 proposal,
                 signed,
                 timestamp,
+                issues,
+                replaces,
+                ..
             } in self.proposals.into_iter()
             {
                 let row = match proposal {
@@ -106,7 +145,7 @@ impl Activity {
                         description,
                         ..
                     } => Row::new()
-                        .push(Space::with_width(Length::Fixed(70.0)))
+                        .push(Self::select_box(&self.selection, proposal_id, signed))
                         .push(if self.hide_policy_id {
                             Text::new("").view()
                         } else {
@@ -128,7 +167,9 @@ impl Activity {
                             Row::new()
                                 .push(
                                     Badge::new(
-                                        Text::new(if signed {
+                                        Text::new(if !issues.is_empty() {
+                                            "Suspicious"
+                                        } else if signed {
                                             "To broadcast"
                                         } else {
                                             "To approve"
@@ -137,7 +178,9 @@ impl Activity {
                                         .extra_light()
                                         .view(),
                                     )
-                                    .style(if signed {
+                                    .style(if !issues.is_empty() {
+                                        BadgeStyle::Danger
+                                    } else if signed {
                                         BadgeStyle::Warning
                                     } else {
                                         BadgeStyle::Info
@@ -155,7 +198,20 @@ impl Activity {
                                 .view()
                                 .width(Length::Fill),
                         )
-                        .push(Text::new(description).width(Length::FillPortion(2)).view())
+                        .push(
+                            Row::new()
+                                .push(Text::new(description).view())
+                                .push(if replaces.is_some() {
+                                    Badge::new(Text::new("Amended").small().extra_light().view())
+                                        .style(BadgeStyle::Default)
+                                        .into()
+                                } else {
+                                    Text::new("").view()
+                                })
+                                .spacing(10)
+                                .align_items(Alignment::Center)
+                                .width(Length::FillPortion(2)),
+                        )
                         .push(Space::with_width(Length::Fixed(40.0)))
                         .push(Space::with_width(Length::Fixed(40.0)))
                         .push(
@@ -169,7 +225,7 @@ impl Activity {
                         .align_items(Alignment::Center)
                         .width(Length::Fill),
                     Proposal::ProofOfReserve { message, .. } => Row::new()
-                        .push(Space::with_width(Length::Fixed(70.0)))
+                        .push(Self::select_box(&self.selection, proposal_id, signed))
                         .push(if self.hide_policy_id {
                             Text::new("").view()
                         } else {
@@ -225,14 +281,34 @@ impl Activity {
                 tx,
                 label,
                 block_explorer,
+                stuck,
+                ..
             } in self.txs.into_iter()
             {
                 let status = if tx.confirmation_time.is_confirmed() {
                     Icon::new(CHECK).color(GREEN)
+                } else if stuck.is_some() {
+                    Icon::new(HOURGLASS).color(RED)
                 } else {
                     Icon::new(HOURGLASS).color(YELLOW)
                 };
 
+                let badge = match stuck {
+                    Some(stuck) => {
+                        let text: String = if stuck.actions.is_empty() {
+                            String::from("Stuck")
+                        } else {
+                            let actions: Vec<String> =
+                                stuck.actions.iter().map(|a| a.to_string()).collect();
+                            format!("Stuck: {}", actions.join("/"))
+                        };
+                        Badge::new(Text::new(text).small().extra_light().view())
+                            .style(BadgeStyle::Warning)
+                    }
+                    None => Badge::new(Text::new("Completed").small().extra_light().view())
+                        .style(BadgeStyle::Success),
+                };
+
                 let total: i64 = tx.total();
 
                 let row = Row::new()
@@ -261,11 +337,7 @@ impl Activity {
                     )
                     .push(
                         Row::new()
-                            .push(
-                                Badge::new(Text::new("Completed").small().extra_light().view())
-                                    .style(BadgeStyle::Success)
-                                    .width(Length::Fixed(125.0)),
-                            )
+                            .push(badge.width(Length::Fixed(125.0)))
                             .width(Length::Fixed(170.0)),
                     )
                     .push(
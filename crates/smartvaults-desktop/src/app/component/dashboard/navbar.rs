@@ -8,7 +8,8 @@ use smartvaults_sdk::util::format;
 use crate::app::component::breadcrumb::Breadcrumb;
 use crate::app::{Context, Message, Stage};
 use crate::component::{rule, Button, ButtonStyle, Icon, Text};
-use crate::theme::icon::{BOX, EYE, EYE_SLASH, FINGERPRINT, PERSON_CIRCLE};
+use crate::theme::color::GREEN;
+use crate::theme::icon::{ARROW_UP, BOX, EYE, EYE_SLASH, FINGERPRINT, PERSON_CIRCLE};
 
 #[derive(Clone, Default)]
 pub struct Navbar;
@@ -19,7 +20,7 @@ impl Navbar {
     }
 
     pub fn view<'a>(&self, ctx: &Context) -> Row<'a, Message> {
-        Row::new()
+        let mut row = Row::new()
             .push(
                 Row::new()
                     .push(Breadcrumb::new(ctx.breadcrumb.clone()).view())
@@ -60,25 +61,39 @@ impl Navbar {
                     .push(rule::vertical())
                     .height(Length::Fixed(40.0)),
             )
-            .push(
-                Button::new()
-                    .icon(if ctx.hide_balances { EYE_SLASH } else { EYE })
-                    .on_press(Message::ToggleHideBalances)
-                    .style(ButtonStyle::Transparent { text_color: None })
-                    .width(Length::Fixed(40.0))
-                    .view(),
-            )
-            .push(
-                Button::new()
-                    .icon(PERSON_CIRCLE)
-                    .style(ButtonStyle::Transparent { text_color: None })
-                    .on_press(Message::View(Stage::Profile))
-                    .width(Length::Fixed(40.0))
-                    .view(),
-            )
             .spacing(10)
             .padding(5)
             .height(Length::Fixed(60.0))
-            .align_items(Alignment::Center)
+            .align_items(Alignment::Center);
+
+        if let Some(release) = &ctx.release {
+            row = row.push(
+                Button::new()
+                    .icon(ARROW_UP)
+                    .text(format!("v{}", release.version.trim_start_matches('v')))
+                    .style(ButtonStyle::Transparent {
+                        text_color: Some(GREEN),
+                    })
+                    .on_press(Message::View(Stage::Update))
+                    .view(),
+            );
+        }
+
+        row.push(
+            Button::new()
+                .icon(if ctx.hide_balances { EYE_SLASH } else { EYE })
+                .on_press(Message::ToggleHideBalances)
+                .style(ButtonStyle::Transparent { text_color: None })
+                .width(Length::Fixed(40.0))
+                .view(),
+        )
+        .push(
+            Button::new()
+                .icon(PERSON_CIRCLE)
+                .style(ButtonStyle::Transparent { text_color: None })
+                .on_press(Message::View(Stage::Profile))
+                .width(Length::Fixed(40.0))
+                .view(),
+        )
     }
 }
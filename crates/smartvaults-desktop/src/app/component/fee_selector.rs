@@ -2,20 +2,66 @@
 // Distributed under the MIT software license
 
 use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
 
-use iced::widget::{component, Column, Component, Radio, Row};
-use iced::{Alignment, Element, Length, Renderer};
+use iced::widget::{
+    component, container, Column, Component, Container, PickList, Radio, Row, Space,
+};
+use iced::{theme, Alignment, Background, BorderRadius, Element, Length, Renderer, Theme};
 use smartvaults_sdk::core::bdk::FeeRate as BdkFeeRate;
 use smartvaults_sdk::core::{FeeRate, Priority};
+use smartvaults_sdk::types::MempoolSnapshot;
 
 use crate::app::Message;
 use crate::component::{Button, ButtonStyle, NumericInput, Text};
+use crate::theme::color::{GREY, TRANSPARENT};
+
+const FEE_CHART_HEIGHT: f32 = 60.0;
+/// Histogram entries shown in the fee chart (highest fee rate first)
+const FEE_CHART_MAX_BARS: usize = 40;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub enum InternalStage {
     #[default]
     TargetBlocks,
     FeeRate,
+    ConfirmBy,
+}
+
+/// Deadline of the "confirm by" fee suggestion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline(pub Duration);
+
+impl Deadline {
+    const ALL: [Self; 7] = [
+        Self(Duration::from_secs(30 * 60)),
+        Self(Duration::from_secs(60 * 60)),
+        Self(Duration::from_secs(3 * 60 * 60)),
+        Self(Duration::from_secs(6 * 60 * 60)),
+        Self(Duration::from_secs(12 * 60 * 60)),
+        Self(Duration::from_secs(24 * 60 * 60)),
+        Self(Duration::from_secs(7 * 24 * 60 * 60)),
+    ];
+}
+
+impl Default for Deadline {
+    fn default() -> Self {
+        Self(Duration::from_secs(60 * 60))
+    }
+}
+
+impl fmt::Display for Deadline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let minutes: u64 = self.0.as_secs() / 60;
+        if minutes < 60 {
+            write!(f, "{minutes} minutes")
+        } else if minutes < 24 * 60 {
+            write!(f, "{} hour/s", minutes / 60)
+        } else {
+            write!(f, "{} day/s", minutes / (24 * 60))
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -23,6 +69,7 @@ pub enum Event {
     FeeRateChanged(FeeRate),
     CustomTargetBlockChanged(Option<u8>),
     CustomRateChanged(Option<f32>),
+    DeadlineChanged(Deadline),
     SetInternalStage(InternalStage),
 }
 
@@ -34,7 +81,10 @@ pub struct FeeSelector {
     max_width: Option<f32>,
     on_change: Box<dyn Fn(FeeRate) -> Message>,
     current_mempool_fees: BTreeMap<Priority, BdkFeeRate>,
+    mempool_snapshot: Option<MempoolSnapshot>,
     estimated_tx_vsize: Option<usize>,
+    deadline: Option<Deadline>,
+    on_deadline: Option<Box<dyn Fn(Deadline) -> Message>>,
 }
 
 impl FeeSelector {
@@ -58,7 +108,10 @@ impl FeeSelector {
             max_width: None,
             on_change: Box::new(on_change),
             current_mempool_fees: BTreeMap::new(),
+            mempool_snapshot: None,
             estimated_tx_vsize: None,
+            deadline: None,
+            on_deadline: None,
         }
     }
 
@@ -74,10 +127,30 @@ impl FeeSelector {
         self
     }
 
+    /// Render the fee histogram of the mempool as a live fee chart
+    pub fn mempool_snapshot(mut self, snapshot: Option<MempoolSnapshot>) -> Self {
+        self.mempool_snapshot = snapshot;
+        self
+    }
+
     pub fn estimate_tx_vsize(mut self, vsize: Option<usize>) -> Self {
         self.estimated_tx_vsize = vsize;
         self
     }
+
+    /// Enable the "confirm by" stage: the fee rate is suggested for the selected deadline by the caller
+    pub fn confirm_by(
+        mut self,
+        deadline: Option<Deadline>,
+        on_deadline: impl Fn(Deadline) -> Message + 'static,
+    ) -> Self {
+        if deadline.is_some() {
+            self.stage = InternalStage::ConfirmBy;
+        }
+        self.deadline = deadline;
+        self.on_deadline = Some(Box::new(on_deadline));
+        self
+    }
 }
 
 impl Component<Message, Renderer> for FeeSelector {
@@ -97,53 +170,78 @@ impl Component<Message, Renderer> for FeeSelector {
                 Some(rate) => Some((self.on_change)(FeeRate::Rate(rate))),
                 None => Some((self.on_change)(FeeRate::min_relay_fee())),
             },
+            Event::DeadlineChanged(deadline) => self.on_deadline.as_ref().map(|f| f(deadline)),
             Event::SetInternalStage(stage) => {
                 self.stage = stage;
                 match stage {
                     InternalStage::TargetBlocks => Some((self.on_change)(FeeRate::default())),
                     InternalStage::FeeRate => Some((self.on_change)(FeeRate::min_relay_fee())),
+                    InternalStage::ConfirmBy => self
+                        .on_deadline
+                        .as_ref()
+                        .map(|f| f(self.deadline.unwrap_or_default())),
                 }
             }
         }
     }
 
     fn view(&self, _state: &Self::State) -> Element<Event, Renderer> {
-        let mut content = Column::new()
-            .push(Text::new("Priority & arrival time").view())
+        let mut stages = Row::new()
             .push(
-                Row::new()
-                    .push(
-                        Button::new()
-                            .style(if let InternalStage::TargetBlocks = self.stage {
-                                ButtonStyle::Primary
-                            } else {
-                                ButtonStyle::Bordered
-                            })
-                            .text("Target blocks")
-                            .width(Length::Fill)
-                            .on_press(Event::SetInternalStage(InternalStage::TargetBlocks))
-                            .view(),
-                    )
-                    .push(
-                        Button::new()
-                            .style(if let InternalStage::FeeRate = self.stage {
-                                ButtonStyle::Primary
-                            } else {
-                                ButtonStyle::Bordered
-                            })
-                            .text("Fee rate")
-                            .width(Length::Fill)
-                            .on_press(Event::SetInternalStage(InternalStage::FeeRate))
-                            .view(),
-                    )
-                    .spacing(5),
+                Button::new()
+                    .style(if let InternalStage::TargetBlocks = self.stage {
+                        ButtonStyle::Primary
+                    } else {
+                        ButtonStyle::Bordered
+                    })
+                    .text("Target blocks")
+                    .width(Length::Fill)
+                    .on_press(Event::SetInternalStage(InternalStage::TargetBlocks))
+                    .view(),
+            )
+            .push(
+                Button::new()
+                    .style(if let InternalStage::FeeRate = self.stage {
+                        ButtonStyle::Primary
+                    } else {
+                        ButtonStyle::Bordered
+                    })
+                    .text("Fee rate")
+                    .width(Length::Fill)
+                    .on_press(Event::SetInternalStage(InternalStage::FeeRate))
+                    .view(),
             )
+            .spacing(5);
+
+        if self.on_deadline.is_some() {
+            stages = stages.push(
+                Button::new()
+                    .style(if let InternalStage::ConfirmBy = self.stage {
+                        ButtonStyle::Primary
+                    } else {
+                        ButtonStyle::Bordered
+                    })
+                    .text("Confirm by")
+                    .width(Length::Fill)
+                    .on_press(Event::SetInternalStage(InternalStage::ConfirmBy))
+                    .view(),
+            );
+        }
+
+        let mut content = Column::new()
+            .push(Text::new("Priority & arrival time").view())
+            .push(stages)
             .push(match self.stage {
                 InternalStage::TargetBlocks => self.view_target_blocks(),
                 InternalStage::FeeRate => self.view_fee_rate(),
+                InternalStage::ConfirmBy => self.view_confirm_by(),
             })
             .spacing(10);
 
+        if let Some(snapshot) = &self.mempool_snapshot {
+            content = content.push(self.view_fee_chart(snapshot));
+        }
+
         if let Some(max_width) = self.max_width {
             content = content.max_width(max_width);
         }
@@ -308,6 +406,83 @@ impl FeeSelector {
             .spacing(10)
     }
 
+    fn view_confirm_by<'a>(&self) -> Column<'a, Event> {
+        let mut content = Column::new()
+            .push(
+                PickList::new(
+                    Deadline::ALL.to_vec(),
+                    Some(self.deadline.unwrap_or_default()),
+                    Event::DeadlineChanged,
+                )
+                .width(Length::Fill)
+                .padding(10),
+            )
+            .spacing(10);
+
+        if let FeeRate::Rate(rate) = self.fee_rate {
+            let mut row = Row::new()
+                .push(
+                    Text::new(format!("Suggested: {rate:.2} sat/vB"))
+                        .small()
+                        .extra_light()
+                        .view(),
+                )
+                .spacing(5);
+            if let Some(vsize) = self.estimated_tx_vsize {
+                row = row.push(
+                    Text::new(format!(" | {:.0} sat", rate * vsize as f32))
+                        .small()
+                        .extra_light()
+                        .view(),
+                );
+            }
+            content = content.push(row);
+        }
+
+        content
+    }
+
+    /// Bars of the histogram entries, by size: the entries that would be included in the next
+    /// block are highlighted
+    fn view_fee_chart<'a>(&self, snapshot: &MempoolSnapshot) -> Column<'a, Event> {
+        let next_block: f64 = snapshot
+            .targets
+            .get(&Priority::High)
+            .map(|rate| rate.as_sat_per_vb() as f64)
+            .unwrap_or(1.0);
+        let entries = snapshot.fee_histogram.iter().take(FEE_CHART_MAX_BARS);
+        let max_vsize: u64 = entries.clone().map(|(_, vsize)| *vsize).max().unwrap_or(1);
+
+        let bars = entries.fold(Row::new(), |row, (rate, vsize)| {
+            let height: f32 = *vsize as f32 / max_vsize.max(1) as f32 * FEE_CHART_HEIGHT;
+            row.push(
+                Container::new(Space::with_height(Length::Fixed(height.max(1.0))))
+                    .width(Length::Fill)
+                    .style(FeeBarStyle {
+                        next_block: *rate >= next_block,
+                    }),
+            )
+        });
+
+        Column::new()
+            .push(Text::new("Mempool").view())
+            .push(
+                bars.spacing(2)
+                    .align_items(Alignment::End)
+                    .height(Length::Fixed(FEE_CHART_HEIGHT)),
+            )
+            .push(
+                Text::new(format!(
+                    "{:.2} MvB | Next block: {next_block:.2} sat/vB",
+                    snapshot.vsize as f64 / 1_000_000.0
+                ))
+                .small()
+                .extra_light()
+                .view(),
+            )
+            .spacing(5)
+    }
+
     fn view_fee_rate<'a>(&self) -> Column<'a, Event> {
         Column::new()
             .push(
@@ -324,3 +499,33 @@ impl<'a> From<FeeSelector> for Element<'a, Message, Renderer> {
         component(numeric_input)
     }
 }
+
+#[derive(Clone, Copy)]
+struct FeeBarStyle {
+    next_block: bool,
+}
+
+impl container::StyleSheet for FeeBarStyle {
+    type Style = Theme;
+
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        let palette = style.palette();
+        container::Appearance {
+            text_color: None,
+            background: Some(Background::Color(if self.next_block {
+                palette.primary
+            } else {
+                GREY
+            })),
+            border_radius: BorderRadius::from(2.0),
+            border_width: 0.0,
+            border_color: TRANSPARENT,
+        }
+    }
+}
+
+impl From<FeeBarStyle> for theme::Container {
+    fn from(style: FeeBarStyle) -> Self {
+        theme::Container::Custom(Box::new(style))
+    }
+}
@@ -18,7 +18,7 @@ mod utxo_selector;
 pub use self::activity::{Activity, CompletedProposalsList};
 pub use self::balances::Balances;
 pub use self::dashboard::Dashboard;
-pub use self::fee_selector::FeeSelector;
+pub use self::fee_selector::{Deadline, FeeSelector};
 pub use self::policy_tree::PolicyTree;
 pub use self::utxo_selector::UtxoSelector;
 
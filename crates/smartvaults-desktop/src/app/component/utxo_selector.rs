@@ -89,6 +89,7 @@ impl Component<Message, Renderer> for UtxoSelector {
             utxo,
             label,
             frozen,
+            ..
         } in self.utxos.iter()
         {
             let LocalOutput {
@@ -9,9 +9,9 @@ use smartvaults_sdk::core::bitcoin::Txid;
 use smartvaults_sdk::core::policy::Policy;
 use smartvaults_sdk::core::signer::Signer;
 use smartvaults_sdk::core::Priority;
-use smartvaults_sdk::nostr::{EventId, Url};
-use smartvaults_sdk::protocol::v1::SignerOffering;
-use smartvaults_sdk::types::{GetPolicy, GetSigner};
+use smartvaults_sdk::nostr::{EventId, PublicKey, Url};
+use smartvaults_sdk::protocol::v1::{Release, SignerOffering};
+use smartvaults_sdk::types::{GetPolicy, GetSigner, MempoolSnapshot};
 use smartvaults_sdk::{util, SmartVaults};
 
 pub const AVAILABLE_MODES: [Mode; 2] = [Mode::User, Mode::KeyAgent];
@@ -21,11 +21,15 @@ pub enum Stage {
     Dashboard,
     Vaults,
     AddVault,
-    VaultBuilder,
+    VaultBuilder(Option<PublicKey>),
     RestoreVault,
     Vault(EventId),
     PolicyTree(EventId),
+    VaultMembers(EventId),
+    VaultWhitelist(EventId),
+    VaultUtxos(EventId),
     Spend(Option<GetPolicy>),
+    BatchSpend(Option<GetPolicy>),
     Receive(Option<GetPolicy>),
     SelfTransfer,
     NewProof(Option<GetPolicy>),
@@ -51,14 +55,19 @@ pub enum Stage {
     EditProfile,
     Settings,
     Config,
+    Devices,
+    Doctor,
+    Update,
     Relays,
     Relay(Url),
     AddRelay,
     ChangePassword,
+    ApprovalPin,
     RecoveryKeys,
     WipeKeys,
     NostrConnect,
     AddNostrConnectSession,
+    NostrConnectProfiles,
 }
 
 impl fmt::Display for Stage {
@@ -67,11 +76,15 @@ impl fmt::Display for Stage {
             Self::Dashboard => write!(f, "Dashboard"),
             Self::Vaults => write!(f, "Vaults"),
             Self::AddVault => write!(f, "Add vault"),
-            Self::VaultBuilder => write!(f, "Builder"),
+            Self::VaultBuilder(_) => write!(f, "Builder"),
             Self::RestoreVault => write!(f, "Restore vault"),
             Self::PolicyTree(_) => write!(f, "Tree"),
+            Self::VaultMembers(_) => write!(f, "Members"),
+            Self::VaultWhitelist(_) => write!(f, "Whitelist"),
+            Self::VaultUtxos(_) => write!(f, "UTXOs"),
             Self::Vault(id) => write!(f, "Vault #{}", util::cut_event_id(*id)),
             Self::Spend(_) => write!(f, "Spend"),
+            Self::BatchSpend(_) => write!(f, "Batch payout"),
             Self::Receive(_) => write!(f, "Receive"),
             Self::SelfTransfer => write!(f, "Self transfer"),
             Self::NewProof(_) => write!(f, "New Proof"),
@@ -97,14 +110,19 @@ impl fmt::Display for Stage {
             Self::EditProfile => write!(f, "Edit profile"),
             Self::Settings => write!(f, "Settings"),
             Self::Config => write!(f, "Config"),
+            Self::Devices => write!(f, "Devices"),
+            Self::Doctor => write!(f, "Connection doctor"),
+            Self::Update => write!(f, "Updates"),
             Self::Relays => write!(f, "Relays"),
             Self::Relay(..) => write!(f, "Relay"),
             Self::AddRelay => write!(f, "Add relay"),
             Self::ChangePassword => write!(f, "Change password"),
+            Self::ApprovalPin => write!(f, "Approval PIN"),
             Self::RecoveryKeys => write!(f, "Recovery Keys"),
             Self::WipeKeys => write!(f, "Wipe Keys"),
             Self::NostrConnect => write!(f, "Connect"),
             Self::AddNostrConnectSession => write!(f, "Add session"),
+            Self::NostrConnectProfiles => write!(f, "Permission profiles"),
         }
     }
 }
@@ -166,6 +184,10 @@ pub struct Context {
     pub breadcrumb: Vec<Stage>,
     pub mode: Mode,
     pub current_fees: BTreeMap<Priority, FeeRate>,
+    /// Fee histogram of the mempool, refreshed with the mempool fees
+    pub mempool_snapshot: Option<MempoolSnapshot>,
+    /// Newer release available
+    pub release: Option<Release>,
 }
 
 impl Context {
@@ -177,6 +199,8 @@ impl Context {
             breadcrumb: vec![stage],
             mode: Mode::default(),
             current_fees: BTreeMap::new(),
+            mempool_snapshot: None,
+            release: None,
         }
     }
 
@@ -1,6 +1,8 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
+use smartvaults_sdk::protocol::v1::Release;
+use smartvaults_sdk::types::MempoolSnapshot;
 use smartvaults_sdk::Message as SdkMessage;
 
 use super::context::Mode;
@@ -8,13 +10,15 @@ use super::context::Mode;
 use super::screen::{
     ActivityMessage, AddAirGapSignerMessage, AddColdcardSignerMessage, AddContactMessage,
     AddNostrConnectSessionMessage, AddRelayMessage, AddSignerMessage, AddVaultMessage,
-    AddressesMessage, ChangePasswordMessage, CompletedProposalMessage, ConfigMessage,
-    ConnectMessage, ContactsMessage, DashboardMessage, EditProfileMessage,
-    EditSignerOfferingMessage, HistoryMessage, KeyAgentsMessage, NewProofMessage, PoliciesMessage,
+    AddressesMessage, ApprovalPinMessage, BatchSpendMessage, ChangePasswordMessage,
+    CompletedProposalMessage, ConfigMessage, ConnectMessage, ContactsMessage, DashboardMessage,
+    DevicesMessage, DoctorMessage, EditProfileMessage, EditSignerOfferingMessage, HistoryMessage,
+    KeyAgentsMessage, NewProofMessage, NostrConnectProfilesMessage, PoliciesMessage,
     PolicyBuilderMessage, PolicyTreeMessage, ProfileMessage, ProposalMessage, ReceiveMessage,
     RecoveryKeysMessage, RelayMessage, RelaysMessage, RestoreVaultMessage, RevokeAllSignersMessage,
     SelfTransferMessage, SettingsMessage, ShareSignerMessage, SignerMessage, SignersMessage,
-    SpendMessage, TransactionMessage, VaultMessage, WipeKeysMessage,
+    SpendMessage, TransactionMessage, UpdateMessage, VaultMembersMessage, VaultMessage,
+    VaultUtxosMessage, VaultWhitelistMessage, WipeKeysMessage,
 };
 use super::Stage;
 
@@ -28,7 +32,11 @@ pub enum Message {
     RestorePolicy(RestoreVaultMessage),
     Policy(VaultMessage),
     PolicyTree(PolicyTreeMessage),
+    VaultMembers(VaultMembersMessage),
+    VaultWhitelist(VaultWhitelistMessage),
+    VaultUtxos(VaultUtxosMessage),
     Spend(SpendMessage),
+    BatchSpend(BatchSpendMessage),
     Receive(ReceiveMessage),
     SelfTransfer(SelfTransferMessage),
     NewProof(NewProofMessage),
@@ -54,18 +62,26 @@ pub enum Message {
     EditProfile(EditProfileMessage),
     Settings(SettingsMessage),
     Config(ConfigMessage),
+    Devices(DevicesMessage),
+    Doctor(DoctorMessage),
+    Update(UpdateMessage),
     Relays(RelaysMessage),
     Relay(RelayMessage),
     AddRelay(AddRelayMessage),
     ChangePassword(ChangePasswordMessage),
+    ApprovalPin(ApprovalPinMessage),
     RecoveryKeys(RecoveryKeysMessage),
     WipeKeys(WipeKeysMessage),
     Connect(ConnectMessage),
     AddNostrConnectSession(AddNostrConnectSessionMessage),
+    NostrConnectProfiles(NostrConnectProfilesMessage),
     Clipboard(String),
     OpenInBrowser(String),
     ChangeMode(Mode),
     ToggleHideBalances,
+    SetHideBalances(bool),
+    SetRelease(Option<Release>),
+    SetMempoolSnapshot(MempoolSnapshot),
     Lock,
     Sync(SdkMessage),
     Tick,
@@ -3,11 +3,13 @@
 
 use iced::{clipboard, Command, Element, Subscription};
 use smartvaults_sdk::core::bitcoin::Network;
-use smartvaults_sdk::{Message as SdkMessage, SmartVaults};
+use smartvaults_sdk::protocol::v1::constants::DESKTOP_RELEASE_IDENTIFIER;
+use smartvaults_sdk::{EventHandled, Message as SdkMessage, SmartVaults};
 
 mod component;
 mod context;
 mod message;
+pub mod print;
 pub mod screen;
 mod sync;
 
@@ -17,14 +19,17 @@ pub use self::message::Message;
 use self::screen::{
     ActivityState, AddAirGapSignerState, AddColdcardSignerState, AddContactState,
     AddNostrConnectSessionState, AddRelayState, AddSignerState, AddVaultState, AddressesState,
-    ChangePasswordState, CompletedProposalState, ConfigState, ConnectState, ContactsState,
-    DashboardState, EditProfileState, EditSignerOfferingState, HistoryState, KeyAgentsState,
-    NewProofState, PoliciesState, PolicyBuilderState, PolicyTreeState, ProfileState, ProposalState,
-    ReceiveState, RecoveryKeysState, RelayState, RelaysState, RestoreVaultState,
+    ApprovalPinState, BatchSpendState, ChangePasswordState, CompletedProposalState, ConfigState,
+    ConnectState, ContactsState, DashboardState, DevicesState, DoctorState, EditProfileState,
+    EditSignerOfferingState, HistoryState, KeyAgentsState, NewProofState,
+    NostrConnectProfilesState, PoliciesState, PolicyBuilderState, PolicyTreeState, ProfileState,
+    ProposalState, ReceiveState, RecoveryKeysState, RelayState, RelaysState, RestoreVaultState,
     RevokeAllSignersState, SelfTransferState, SettingsState, ShareSignerState, SignerState,
-    SignersState, SpendState, TransactionState, VaultState, WipeKeysState,
+    SignersState, SpendState, TransactionState, UpdateState, VaultMembersState, VaultState,
+    VaultUtxosState, VaultWhitelistState, WipeKeysState,
 };
 use self::sync::SmartVaultsSync;
+use crate::constants::APP_VERSION;
 use crate::theme::Theme;
 
 pub trait State {
@@ -48,11 +53,15 @@ pub fn new_state(ctx: &Context) -> Box<dyn State> {
         Stage::Dashboard => DashboardState::new().into(),
         Stage::Vaults => PoliciesState::new().into(),
         Stage::AddVault => AddVaultState::new().into(),
-        Stage::VaultBuilder => PolicyBuilderState::new().into(),
+        Stage::VaultBuilder(contact) => PolicyBuilderState::new(*contact).into(),
         Stage::RestoreVault => RestoreVaultState::new().into(),
         Stage::Vault(policy_id) => VaultState::new(*policy_id).into(),
         Stage::PolicyTree(policy_id) => PolicyTreeState::new(*policy_id).into(),
+        Stage::VaultMembers(vault_id) => VaultMembersState::new(*vault_id).into(),
+        Stage::VaultWhitelist(vault_id) => VaultWhitelistState::new(*vault_id).into(),
+        Stage::VaultUtxos(vault_id) => VaultUtxosState::new(*vault_id).into(),
         Stage::Spend(policy) => SpendState::new(policy.clone()).into(),
+        Stage::BatchSpend(policy) => BatchSpendState::new(policy.clone()).into(),
         Stage::Receive(policy) => ReceiveState::new(policy.clone()).into(),
         Stage::SelfTransfer => SelfTransferState::new().into(),
         Stage::NewProof(policy) => NewProofState::new(policy.clone()).into(),
@@ -82,12 +91,17 @@ pub fn new_state(ctx: &Context) -> Box<dyn State> {
         Stage::Relays => RelaysState::new().into(),
         Stage::Relay(url) => RelayState::new(url.clone()).into(),
         Stage::Config => ConfigState::new().into(),
+        Stage::Devices => DevicesState::new().into(),
+        Stage::Doctor => DoctorState::new().into(),
+        Stage::Update => UpdateState::new().into(),
         Stage::AddRelay => AddRelayState::new().into(),
         Stage::ChangePassword => ChangePasswordState::new().into(),
+        Stage::ApprovalPin => ApprovalPinState::new().into(),
         Stage::RecoveryKeys => RecoveryKeysState::new().into(),
         Stage::WipeKeys => WipeKeysState::new().into(),
         Stage::NostrConnect => ConnectState::new().into(),
         Stage::AddNostrConnectSession => AddNostrConnectSessionState::new().into(),
+        Stage::NostrConnectProfiles => NostrConnectProfilesState::new().into(),
     }
 }
 
@@ -127,6 +141,55 @@ impl App {
         Subscription::batch(vec![sync, self.state.subscription()])
     }
 
+    /// Load the settings synced from the other devices
+    pub fn load_app_settings(&self) -> Command<Message> {
+        let client = self.ctx.client.clone();
+        Command::perform(
+            async move { client.get_app_settings().await },
+            |res| match res {
+                Ok(settings) => Message::SetHideBalances(settings.hide_balances),
+                Err(e) => {
+                    tracing::error!("Impossible to get app settings: {e}");
+                    Message::Tick
+                }
+            },
+        )
+    }
+
+    /// Refresh the fee histogram of the mempool (fee chart of the fee selector)
+    pub fn load_mempool_snapshot(&self) -> Command<Message> {
+        let client = self.ctx.client.clone();
+        Command::perform(
+            async move { client.get_mempool_snapshot().await },
+            |res| match res {
+                Ok(snapshot) => Message::SetMempoolSnapshot(snapshot),
+                Err(e) => {
+                    tracing::warn!("Impossible to get mempool snapshot: {e}");
+                    Message::Tick
+                }
+            },
+        )
+    }
+
+    /// Check if a newer release is available
+    pub fn check_for_updates(&self) -> Command<Message> {
+        let client = self.ctx.client.clone();
+        Command::perform(
+            async move {
+                client
+                    .check_for_updates(DESKTOP_RELEASE_IDENTIFIER, APP_VERSION, None)
+                    .await
+            },
+            |res| match res {
+                Ok(release) => Message::SetRelease(release),
+                Err(e) => {
+                    tracing::warn!("Impossible to check for updates: {e}");
+                    Message::Tick
+                }
+            },
+        )
+    }
+
     pub fn update(&mut self, message: Message) -> Command<Message> {
         match message {
             Message::View(stage) => {
@@ -141,7 +204,11 @@ impl App {
             Message::Sync(msg) => match msg {
                 SdkMessage::MempoolFeesUpdated(fees) => {
                     self.ctx.current_fees = fees;
-                    Command::none()
+                    self.load_mempool_snapshot()
+                }
+                SdkMessage::DeviceRevoked => Command::perform(async {}, |_| Message::Lock),
+                SdkMessage::EventHandled(EventHandled::AppSettings) => {
+                    Command::batch(vec![self.load_app_settings(), self.state.load(&self.ctx)])
                 }
                 _ => self.state.load(&self.ctx),
             },
@@ -158,6 +225,35 @@ impl App {
             }
             Message::ToggleHideBalances => {
                 self.ctx.toggle_hide_balances();
+                let client = self.ctx.client.clone();
+                let hide_balances: bool = self.ctx.hide_balances;
+                Command::perform(
+                    async move {
+                        let mut settings = client.get_app_settings().await?;
+                        settings.hide_balances = hide_balances;
+                        client.save_app_settings(settings).await
+                    },
+                    |res| {
+                        if let Err(e) = res {
+                            tracing::error!("Impossible to save app settings: {e}");
+                        }
+                        Message::Tick
+                    },
+                )
+            }
+            Message::SetHideBalances(hide) => {
+                self.ctx.hide_balances = hide;
+                Command::none()
+            }
+            Message::SetRelease(release) => {
+                if let Some(release) = &release {
+                    tracing::info!("New release available: {}", release.version);
+                }
+                self.ctx.release = release;
+                Command::none()
+            }
+            Message::SetMempoolSnapshot(snapshot) => {
+                self.ctx.mempool_snapshot = Some(snapshot);
                 Command::none()
             }
             _ => self.state.update(&mut self.ctx, message),
@@ -0,0 +1,150 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Printable documents (recovery kit, vault report and receive-address sheet)
+//!
+//! The documents are rendered as HTML and opened in the default browser, that show the print
+//! dialog (where the document can also be saved as PDF).
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+use qrcode::render::svg;
+use qrcode::QrCode;
+use smartvaults_sdk::core::bdk::chain::ConfirmationTime;
+use smartvaults_sdk::nostr::Timestamp;
+use smartvaults_sdk::types::{GetAddress, GetPolicy, GetTransaction, RecoveryKit};
+use smartvaults_sdk::util::{self, format};
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }
+pre, .mono { font-family: monospace; white-space: pre-wrap; word-break: break-all; }
+table { border-collapse: collapse; width: 100%; }
+td, th { border: 1px solid #999; padding: 4px 8px; text-align: left; }
+.sheet { display: flex; flex-wrap: wrap; gap: 1em; }
+.card { border: 1px dashed #999; padding: 1em; width: 45%; page-break-inside: avoid; }";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// QR code as SVG (`None` if the data is too long to be encoded)
+fn qr_code(data: &str, size: u32) -> Option<String> {
+    let code = QrCode::new(data).ok()?;
+    Some(
+        code.render::<svg::Color>()
+            .min_dimensions(size, size)
+            .build(),
+    )
+}
+
+/// Write the document to the temp dir and open it in the default browser, to print it
+pub fn open(name: &str, title: &str, body: String) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir: PathBuf = std::env::temp_dir().join("smartvaults-print");
+    fs::create_dir_all(&dir)?;
+    let path: PathBuf = dir.join(format!("{name}.html"));
+
+    let html: String = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{STYLE}</style>\n</head>\n\
+         <body onload=\"window.print()\">\n{body}</body>\n</html>\n",
+        escape(title)
+    );
+    fs::write(&path, html)?;
+
+    webbrowser::open(&path.display().to_string())?;
+    Ok(path)
+}
+
+/// Recovery kit, with the QR code of the descriptor
+pub fn recovery_kit(kit: &RecoveryKit) -> String {
+    let mut body: String = format!("<pre>{}</pre>\n", escape(&kit.to_string()));
+    if let Some(qr) = qr_code(&kit.descriptor, 300) {
+        body.push_str("<h3>Descriptor</h3>\n");
+        body.push_str(&qr);
+    }
+    body
+}
+
+/// Vault summary: balance and transactions
+pub fn vault_report(policy: &GetPolicy, txs: &BTreeSet<GetTransaction>) -> String {
+    let mut body: String = String::new();
+    let balance = &policy.balance;
+    let _ = writeln!(body, "<h1>{}</h1>", escape(&policy.policy.name()));
+    let _ = writeln!(body, "<p>{}</p>", escape(&policy.policy.description()));
+    let _ = writeln!(
+        body,
+        "<p>Vault ID: <span class=\"mono\">{}</span><br>Fingerprint: {}<br>Generated: {}</p>",
+        policy.policy_id,
+        policy
+            .policy
+            .descriptor_fingerprint()
+            .unwrap_or_else(|_| String::from("Unavailable")),
+        Timestamp::now().to_human_datetime()
+    );
+
+    body.push_str("<h2>Balance</h2>\n<table>\n");
+    for (name, amount) in [
+        ("Confirmed", balance.confirmed),
+        ("Pending (own)", balance.trusted_pending),
+        ("Pending (incoming)", balance.untrusted_pending),
+        ("Immature", balance.immature),
+        ("Total", balance.total()),
+    ] {
+        let _ = writeln!(
+            body,
+            "<tr><th>{name}</th><td>{} sat</td></tr>",
+            format::number(amount)
+        );
+    }
+    body.push_str("</table>\n");
+
+    body.push_str("<h2>Transactions</h2>\n<table>\n");
+    body.push_str("<tr><th>Date</th><th>Txid</th><th>Amount</th><th>Label</th></tr>\n");
+    for GetTransaction { tx, label, .. } in txs.iter() {
+        let date: String = match tx.confirmation_time {
+            ConfirmationTime::Confirmed { time, .. } => Timestamp::from(time).to_human_datetime(),
+            ConfirmationTime::Unconfirmed { .. } => String::from("Pending"),
+        };
+        let total: i64 = tx.total();
+        let _ = writeln!(
+            body,
+            "<tr><td>{date}</td><td class=\"mono\">{}</td><td>{}{} sat</td><td>{}</td></tr>",
+            tx.txid(),
+            if total < 0 { "-" } else { "+" },
+            format::number(total.unsigned_abs()),
+            escape(label.as_deref().unwrap_or_default())
+        );
+    }
+    body.push_str("</table>\n");
+
+    body
+}
+
+/// Sheet of receive addresses, each with its QR code (BIP21 URI)
+pub fn address_sheet(policy: &GetPolicy, addresses: &[GetAddress]) -> String {
+    let mut body: String = format!(
+        "<h1>{}</h1>\n<p>Receive addresses. Don't reuse an address once funded.</p>\n",
+        escape(&policy.policy.name())
+    );
+    body.push_str("<div class=\"sheet\">\n");
+    for GetAddress { address, label } in addresses.iter() {
+        let address = address.clone().assume_checked();
+        let uri: String = util::bip21_uri(&address, None, label.as_deref());
+        let _ = writeln!(
+            body,
+            "<div class=\"card\">{}<p class=\"mono\">{address}</p>{}</div>",
+            qr_code(&uri, 200).unwrap_or_default(),
+            label
+                .as_ref()
+                .map(|l| format!("<p>{}</p>", escape(l)))
+                .unwrap_or_default()
+        );
+    }
+    body.push_str("</div>\n");
+    body
+}
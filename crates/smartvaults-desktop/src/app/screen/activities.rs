@@ -1,20 +1,36 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 
-use iced::widget::{Column, Space};
+use iced::widget::{Column, Row, Space};
 use iced::{Alignment, Command, Element, Length};
-use smartvaults_sdk::types::{GetProposal, GetTransaction};
+use smartvaults_sdk::core::{FeeRate, Priority};
+use smartvaults_sdk::nostr::EventId;
+use smartvaults_sdk::types::{BulkApprovalResult, GetPaymentRequest, GetProposal, GetTransaction};
+use smartvaults_sdk::util::{self, format};
 
 use crate::app::component::{Activity, Dashboard};
-use crate::app::{Context, Message, State};
-use crate::component::{Button, ButtonStyle, Text};
-use crate::theme::icon::RELOAD;
+use crate::app::{Context, Message, Stage, State};
+use crate::component::{rule, Button, ButtonStyle, Text, TextInput};
+use crate::theme::color::{DARK_RED, GREEN};
+use crate::theme::icon::{CHECK, RELOAD};
 
 #[derive(Debug, Clone)]
 pub enum ActivityMessage {
-    Load(Vec<GetProposal>, BTreeSet<GetTransaction>),
+    Load(
+        Vec<GetProposal>,
+        BTreeSet<GetTransaction>,
+        Vec<GetPaymentRequest>,
+    ),
+    FulfillPaymentRequest(EventId),
+    ToggleSelectMode,
+    Select(EventId, bool),
+    PasswordChanged(String),
+    PinChanged(String),
+    ApproveSelected,
+    Approved(Vec<BulkApprovalResult>),
+    ErrorChanged(Option<String>),
     Reload,
 }
 
@@ -24,6 +40,13 @@ pub struct ActivityState {
     loaded: bool,
     proposals: Vec<GetProposal>,
     txs: BTreeSet<GetTransaction>,
+    payment_requests: Vec<GetPaymentRequest>,
+    select_mode: bool,
+    selected: HashSet<EventId>,
+    password: String,
+    pin: Option<String>,
+    approval_results: Vec<BulkApprovalResult>,
+    error: Option<String>,
 }
 
 impl ActivityState {
@@ -44,9 +67,18 @@ impl State for ActivityState {
             async move {
                 let proposals = client.get_proposals().await.unwrap();
                 let txs = client.get_all_transactions().await.unwrap();
-                (proposals, txs)
+                let payment_requests = client
+                    .get_payment_requests()
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|r| !r.fulfilled)
+                    .collect();
+                (proposals, txs, payment_requests)
+            },
+            |(proposals, txs, payment_requests)| {
+                ActivityMessage::Load(proposals, txs, payment_requests).into()
             },
-            |(proposals, txs)| ActivityMessage::Load(proposals, txs).into(),
         )
     }
 
@@ -57,13 +89,94 @@ impl State for ActivityState {
 
         if let Message::Activity(msg) = message {
             match msg {
-                ActivityMessage::Load(proposals, txs) => {
+                ActivityMessage::Load(proposals, txs, payment_requests) => {
                     self.proposals = proposals;
                     self.txs = txs;
+                    self.payment_requests = payment_requests;
                     self.loading = false;
                     self.loaded = true;
                     Command::none()
                 }
+                ActivityMessage::FulfillPaymentRequest(request_id) => {
+                    self.loading = true;
+                    self.error = None;
+                    let client = ctx.client.clone();
+                    Command::perform(
+                        async move {
+                            client
+                                .fulfill_payment_request(
+                                    request_id,
+                                    FeeRate::Priority(Priority::Medium),
+                                )
+                                .await
+                        },
+                        |res| match res {
+                            Ok(proposal) => Message::View(Stage::Proposal(proposal.proposal_id)),
+                            Err(e) => ActivityMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    )
+                }
+                ActivityMessage::ToggleSelectMode => {
+                    self.select_mode = !self.select_mode;
+                    self.selected.clear();
+                    self.password.clear();
+                    self.pin = if self.select_mode && ctx.client.has_approval_pin() {
+                        Some(String::new())
+                    } else {
+                        None
+                    };
+                    self.approval_results.clear();
+                    self.error = None;
+                    Command::none()
+                }
+                ActivityMessage::Select(proposal_id, selected) => {
+                    if selected {
+                        self.selected.insert(proposal_id);
+                    } else {
+                        self.selected.remove(&proposal_id);
+                    }
+                    Command::none()
+                }
+                ActivityMessage::PasswordChanged(password) => {
+                    self.password = password;
+                    Command::none()
+                }
+                ActivityMessage::PinChanged(pin) => {
+                    self.pin = Some(pin);
+                    Command::none()
+                }
+                ActivityMessage::ApproveSelected => {
+                    self.loading = true;
+                    self.error = None;
+                    let client = ctx.client.clone();
+                    let proposal_ids: Vec<EventId> = self.selected.iter().copied().collect();
+                    let password: String = self.password.clone();
+                    let pin: Option<String> = self.pin.clone();
+                    Command::perform(
+                        async move {
+                            client
+                                .approve_many(proposal_ids, password, pin.as_deref())
+                                .await
+                        },
+                        |res| match res {
+                            Ok(results) => ActivityMessage::Approved(results).into(),
+                            Err(e) => ActivityMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    )
+                }
+                ActivityMessage::Approved(results) => {
+                    self.select_mode = false;
+                    self.selected.clear();
+                    self.password.clear();
+                    self.pin = None;
+                    self.approval_results = results;
+                    self.load(ctx)
+                }
+                ActivityMessage::ErrorChanged(error) => {
+                    self.loading = false;
+                    self.error = error;
+                    Command::none()
+                }
                 ActivityMessage::Reload => self.load(ctx),
             }
         } else {
@@ -76,7 +189,60 @@ impl State for ActivityState {
         let mut center_y = true;
 
         if self.loaded {
-            if self.proposals.is_empty() {
+            if !self.payment_requests.is_empty() {
+                center_y = false;
+                content = content
+                    .push(Text::new("Payment requests").bold().big().view())
+                    .push(rule::horizontal_bold());
+
+                for request in self.payment_requests.iter() {
+                    let row = Row::new()
+                        .push(
+                            Text::new(util::cut_event_id(request.policy_id))
+                                .width(Length::Fixed(115.0))
+                                .view(),
+                        )
+                        .push(
+                            Text::new(request.user.name())
+                                .width(Length::Fixed(200.0))
+                                .view(),
+                        )
+                        .push(
+                            Text::new(request.request.description())
+                                .width(Length::Fill)
+                                .view(),
+                        )
+                        .push(
+                            Text::new(format!("{} sat", format::number(request.request.amount())))
+                                .width(Length::Fixed(150.0))
+                                .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .style(ButtonStyle::Bordered)
+                                .text("Create proposal")
+                                .on_press(
+                                    ActivityMessage::FulfillPaymentRequest(request.request_id)
+                                        .into(),
+                                )
+                                .loading(self.loading)
+                                .width(Length::Fixed(180.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill);
+                    content = content.push(row).push(rule::horizontal());
+                }
+
+                if let Some(error) = &self.error {
+                    content = content.push(Text::new(error).color(DARK_RED).view());
+                }
+
+                content = content.push(Space::with_height(Length::Fixed(20.0)));
+            }
+
+            if self.proposals.is_empty() && self.payment_requests.is_empty() {
                 content = content
                     .push(Text::new("No proposals").view())
                     .push(Space::with_height(Length::Fixed(15.0)))
@@ -90,10 +256,20 @@ impl State for ActivityState {
                             .view(),
                     )
                     .align_items(Alignment::Center);
-            } else {
+            } else if !self.proposals.is_empty() {
                 center_y = false;
-                content =
-                    content.push(Activity::new(self.proposals.clone(), self.txs.clone()).view(ctx));
+                content = content.push(self.view_bulk_approval());
+
+                let activity = Activity::new(self.proposals.clone(), self.txs.clone());
+                content = content.push(if self.select_mode {
+                    activity
+                        .selectable(self.selected.clone(), |proposal_id, selected| {
+                            ActivityMessage::Select(proposal_id, selected).into()
+                        })
+                        .view(ctx)
+                } else {
+                    activity.view(ctx)
+                });
             }
         }
 
@@ -103,6 +279,98 @@ impl State for ActivityState {
     }
 }
 
+impl ActivityState {
+    fn view_bulk_approval<'a>(&self) -> Column<'a, Message> {
+        let mut content = Column::new().spacing(10);
+
+        if !self.approval_results.is_empty() {
+            let approved: usize = self
+                .approval_results
+                .iter()
+                .filter(|r| r.is_success())
+                .count();
+            content = content.push(
+                Text::new(format!(
+                    "{approved} of {} proposals approved",
+                    self.approval_results.len()
+                ))
+                .color(GREEN)
+                .view(),
+            );
+            for result in self.approval_results.iter() {
+                if let Some(error) = &result.error {
+                    content = content.push(
+                        Text::new(format!(
+                            "Proposal #{}: {error}",
+                            util::cut_event_id(result.proposal_id)
+                        ))
+                        .color(DARK_RED)
+                        .view(),
+                    );
+                }
+            }
+        }
+
+        let mut row = Row::new()
+            .push(
+                Button::new()
+                    .style(ButtonStyle::Bordered)
+                    .icon(CHECK)
+                    .text(if self.select_mode {
+                        "Cancel"
+                    } else {
+                        "Approve multiple"
+                    })
+                    .on_press(ActivityMessage::ToggleSelectMode.into())
+                    .width(Length::Fixed(200.0))
+                    .view(),
+            )
+            .spacing(10)
+            .align_items(Alignment::End);
+
+        if self.select_mode {
+            row = row.push(
+                TextInput::with_label("Password", &self.password)
+                    .password()
+                    .placeholder("Password")
+                    .on_input(|p| ActivityMessage::PasswordChanged(p).into())
+                    .view()
+                    .width(Length::Fixed(250.0)),
+            );
+
+            if let Some(pin) = &self.pin {
+                row = row.push(
+                    TextInput::with_label("Approval PIN", pin)
+                        .password()
+                        .placeholder("Approval PIN")
+                        .on_input(|p| ActivityMessage::PinChanged(p).into())
+                        .view()
+                        .width(Length::Fixed(150.0)),
+                );
+            }
+
+            let mut approve_btn = Button::new()
+                .text(format!("Approve {} selected", self.selected.len()))
+                .loading(self.loading)
+                .width(Length::Fixed(250.0));
+            if !self.selected.is_empty() && !self.password.is_empty() {
+                approve_btn = approve_btn.on_press(ActivityMessage::ApproveSelected.into());
+            }
+            row = row.push(approve_btn.view());
+        }
+
+        content = content.push(row);
+
+        if self.select_mode {
+            if let Some(error) = &self.error {
+                content = content.push(Text::new(error).color(DARK_RED).view());
+            }
+        }
+
+        content
+    }
+}
+
 impl From<ActivityState> for Box<dyn State> {
     fn from(s: ActivityState) -> Box<dyn State> {
         Box::new(s)
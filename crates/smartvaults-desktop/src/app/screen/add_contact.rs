@@ -3,11 +3,12 @@
 
 use iced::widget::{Column, Row, Space};
 use iced::{Alignment, Command, Element, Length};
+use rfd::FileDialog;
 use smartvaults_sdk::nostr::Keys;
 
 use crate::app::component::Dashboard;
 use crate::app::{Context, Message, Stage, State};
-use crate::component::{Button, Text, TextInput};
+use crate::component::{Button, ButtonStyle, Text, TextInput};
 use crate::theme::color::DARK_RED;
 
 #[derive(Debug, Clone)]
@@ -15,6 +16,8 @@ pub enum AddContactMessage {
     PublicKeyChanged(String),
     ErrorChanged(Option<String>),
     SaveContact,
+    ImportContacts,
+    ImportContactsFromFile,
 }
 
 #[derive(Debug, Default)]
@@ -61,6 +64,48 @@ impl State for AddContactState {
                         Err(e) => self.error = Some(e.to_string()),
                     }
                 }
+                AddContactMessage::ImportContacts => {
+                    let client = ctx.client.clone();
+                    match Keys::parse(&self.public_key) {
+                        Ok(keys) => {
+                            self.loading = true;
+                            return Command::perform(
+                                async move {
+                                    client
+                                        .import_contacts_from_public_key(keys.public_key())
+                                        .await
+                                },
+                                |res| match res {
+                                    Ok(_) => Message::View(Stage::Contacts),
+                                    Err(e) => {
+                                        AddContactMessage::ErrorChanged(Some(e.to_string())).into()
+                                    }
+                                },
+                            );
+                        }
+                        Err(e) => self.error = Some(e.to_string()),
+                    }
+                }
+                AddContactMessage::ImportContactsFromFile => {
+                    let path = FileDialog::new()
+                        .set_title("Import contacts")
+                        .add_filter("Contact list", &["json", "txt"])
+                        .pick_file();
+
+                    if let Some(path) = path {
+                        let client = ctx.client.clone();
+                        self.loading = true;
+                        return Command::perform(
+                            async move { client.import_contacts_from_file(path).await },
+                            |res| match res {
+                                Ok(_) => Message::View(Stage::Contacts),
+                                Err(e) => {
+                                    AddContactMessage::ErrorChanged(Some(e.to_string())).into()
+                                }
+                            },
+                        );
+                    }
+                }
             }
         }
 
@@ -98,6 +143,24 @@ impl State for AddContactState {
                     .width(Length::Fill)
                     .view(),
             )
+            .push(
+                Button::new()
+                    .style(ButtonStyle::Bordered)
+                    .text("Import contacts of this public key")
+                    .on_press(AddContactMessage::ImportContacts.into())
+                    .loading(self.loading || self.public_key.is_empty())
+                    .width(Length::Fill)
+                    .view(),
+            )
+            .push(
+                Button::new()
+                    .style(ButtonStyle::Bordered)
+                    .text("Import contacts from file")
+                    .on_press(AddContactMessage::ImportContactsFromFile.into())
+                    .loading(self.loading)
+                    .width(Length::Fill)
+                    .view(),
+            )
             .align_items(Alignment::Center)
             .spacing(10)
             .padding(20)
@@ -0,0 +1,417 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::fs;
+
+use iced::alignment::Horizontal;
+use iced::widget::{Column, Container, PickList, Row, Space};
+use iced::{Alignment, Command, Element, Length};
+use rfd::FileDialog;
+use smartvaults_sdk::core::FeeRate;
+use smartvaults_sdk::nostr::EventId;
+use smartvaults_sdk::types::{GetPolicy, GetProposal, SpendOptions, SpendPreview, SpendRecipient};
+use smartvaults_sdk::util::{self, format};
+
+use crate::app::component::{Dashboard, FeeSelector, PolicyPickList};
+use crate::app::{Context, Message, Stage, State};
+use crate::component::{rule, Button, ButtonStyle, Text, TextInput};
+use crate::theme::color::DARK_RED;
+
+#[derive(Debug, Clone)]
+pub enum BatchSpendMessage {
+    LoadPolicies(Vec<PolicyPickList>),
+    PolicySelectd(PolicyPickList),
+    ImportCsv,
+    RecipientsLoaded(String, Vec<SpendRecipient>),
+    DescriptionChanged(String),
+    FeeRateChanged(FeeRate),
+    PreviewChanged(Result<SpendPreview, String>),
+    ErrorChanged(Option<String>),
+    SendProposal,
+}
+
+#[derive(Debug)]
+pub struct BatchSpendState {
+    policy: Option<PolicyPickList>,
+    policies: Vec<PolicyPickList>,
+    file_name: Option<String>,
+    recipients: Vec<SpendRecipient>,
+    description: String,
+    fee_rate: FeeRate,
+    preview: Option<Result<SpendPreview, String>>,
+    loading: bool,
+    loaded: bool,
+    error: Option<String>,
+}
+
+impl BatchSpendState {
+    pub fn new(policy: Option<GetPolicy>) -> Self {
+        Self {
+            policy: policy.map(|p| p.into()),
+            policies: Vec::new(),
+            file_name: None,
+            recipients: Vec::new(),
+            description: String::new(),
+            fee_rate: FeeRate::default(),
+            preview: None,
+            loading: false,
+            loaded: false,
+            error: None,
+        }
+    }
+
+    fn total_amount(&self) -> u64 {
+        self.recipients.iter().map(|r| r.amount).sum()
+    }
+
+    fn preview_spend(&mut self, ctx: &mut Context) -> Command<Message> {
+        self.preview = None;
+        match &self.policy {
+            Some(policy) if !self.recipients.is_empty() => {
+                let client = ctx.client.clone();
+                let policy_id: EventId = policy.policy_id;
+                let recipients = self.recipients.clone();
+                let fee_rate = self.fee_rate;
+                Command::perform(
+                    async move {
+                        client
+                            .preview_spend_many(
+                                policy_id,
+                                recipients,
+                                fee_rate,
+                                SpendOptions::default(),
+                            )
+                            .await
+                            .map_err(|e| e.to_string())
+                    },
+                    |res| BatchSpendMessage::PreviewChanged(res).into(),
+                )
+            }
+            _ => Command::none(),
+        }
+    }
+}
+
+impl State for BatchSpendState {
+    fn title(&self) -> String {
+        String::from("Batch payout")
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.loading = true;
+        let client = ctx.client.clone();
+        Command::perform(
+            async move {
+                client
+                    .get_policies()
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|p| p.into())
+                    .collect()
+            },
+            |p| BatchSpendMessage::LoadPolicies(p).into(),
+        )
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if let Message::BatchSpend(msg) = message {
+            match msg {
+                BatchSpendMessage::LoadPolicies(policies) => {
+                    self.policies = policies;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                BatchSpendMessage::PolicySelectd(policy) => {
+                    self.policy = Some(policy);
+                    return self.preview_spend(ctx);
+                }
+                BatchSpendMessage::ImportCsv => {
+                    let path = FileDialog::new()
+                        .set_title("Import recipients")
+                        .add_filter("CSV", &["csv", "txt"])
+                        .pick_file();
+
+                    if let Some(path) = path {
+                        let network = ctx.client.network();
+                        return Command::perform(
+                            async move {
+                                let content: String = fs::read_to_string(&path)?;
+                                let recipients = util::csv::parse_recipients(&content, network)?;
+                                let file_name: String = path
+                                    .file_name()
+                                    .map(|n| n.to_string_lossy().to_string())
+                                    .unwrap_or_default();
+                                Ok::<_, Box<dyn std::error::Error + Send + Sync>>((
+                                    file_name, recipients,
+                                ))
+                            },
+                            |res| match res {
+                                Ok((file_name, recipients)) => {
+                                    BatchSpendMessage::RecipientsLoaded(file_name, recipients)
+                                        .into()
+                                }
+                                Err(e) => {
+                                    BatchSpendMessage::ErrorChanged(Some(e.to_string())).into()
+                                }
+                            },
+                        );
+                    }
+                }
+                BatchSpendMessage::RecipientsLoaded(file_name, recipients) => {
+                    self.file_name = Some(file_name);
+                    self.recipients = recipients;
+                    self.error = None;
+                    return self.preview_spend(ctx);
+                }
+                BatchSpendMessage::DescriptionChanged(description) => {
+                    self.description = description
+                }
+                BatchSpendMessage::FeeRateChanged(fee_rate) => {
+                    self.fee_rate = fee_rate;
+                    return self.preview_spend(ctx);
+                }
+                BatchSpendMessage::PreviewChanged(preview) => self.preview = Some(preview),
+                BatchSpendMessage::ErrorChanged(error) => {
+                    self.loading = false;
+                    self.error = error;
+                }
+                BatchSpendMessage::SendProposal => match &self.policy {
+                    Some(policy) => {
+                        if self.recipients.is_empty() {
+                            self.error = Some(String::from("Import the recipients first"));
+                            return Command::none();
+                        }
+
+                        let client = ctx.client.clone();
+                        let policy_id: EventId = policy.policy_id;
+                        let recipients = self.recipients.clone();
+                        let description = self.description.clone();
+                        let fee_rate = self.fee_rate;
+                        self.loading = true;
+                        return Command::perform(
+                            async move {
+                                client
+                                    .spend_many(
+                                        policy_id,
+                                        recipients,
+                                        description,
+                                        fee_rate,
+                                        SpendOptions::default(),
+                                    )
+                                    .await
+                            },
+                            |res| match res {
+                                Ok(GetProposal { proposal_id, .. }) => {
+                                    Message::View(Stage::Proposal(proposal_id))
+                                }
+                                Err(e) => {
+                                    BatchSpendMessage::ErrorChanged(Some(e.to_string())).into()
+                                }
+                            },
+                        );
+                    }
+                    None => self.error = Some(String::from("You must select a vault")),
+                },
+            }
+        }
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let mut content = Column::new();
+
+        if self.loaded {
+            let policy_pick_list = Column::new()
+                .push(Text::new("Vault").view())
+                .push(
+                    PickList::new(self.policies.clone(), self.policy.clone(), |policy| {
+                        BatchSpendMessage::PolicySelectd(policy).into()
+                    })
+                    .width(Length::Fill)
+                    .padding(10)
+                    .placeholder(if self.policies.is_empty() {
+                        "No vault availabe"
+                    } else {
+                        "Select a vault"
+                    }),
+                )
+                .spacing(5);
+
+            let import = Column::new()
+                .push(
+                    Button::new()
+                        .style(ButtonStyle::Bordered)
+                        .text("Import CSV")
+                        .on_press(BatchSpendMessage::ImportCsv.into())
+                        .width(Length::Fill)
+                        .view(),
+                )
+                .push(
+                    Text::new(match &self.file_name {
+                        Some(file_name) => file_name.clone(),
+                        None => String::from("Rows: address,amount[,label] (amount in SAT or BTC)"),
+                    })
+                    .extra_light()
+                    .small()
+                    .view(),
+                )
+                .spacing(5);
+
+            let totals = Text::new(format!(
+                "{} recipient/s - Total: {} sat",
+                self.recipients.len(),
+                format::number(self.total_amount())
+            ))
+            .width(Length::Fill)
+            .view();
+
+            let preview = match &self.preview {
+                Some(Ok(preview)) => Text::new(format!(
+                    "Fee: {} sat (≈ {:.1} sat/vB) - Change: {} sat - {} input/s - Total spent: {} sat",
+                    format::number(preview.fee),
+                    preview.effective_fee_rate(),
+                    format::number(preview.change),
+                    preview.inputs.len(),
+                    format::number(self.total_amount().saturating_add(preview.fee))
+                ))
+                .extra_light()
+                .small()
+                .width(Length::Fill)
+                .view(),
+                Some(Err(e)) => Text::new(e)
+                    .color(DARK_RED)
+                    .small()
+                    .width(Length::Fill)
+                    .view(),
+                None => Text::new("").view(),
+            };
+
+            let description = TextInput::with_label("Description", &self.description)
+                .on_input(|s| BatchSpendMessage::DescriptionChanged(s).into())
+                .placeholder("Description")
+                .view();
+
+            let details = Column::new()
+                .push(policy_pick_list)
+                .push(import)
+                .push(totals)
+                .push(preview)
+                .push(description)
+                .spacing(10)
+                .max_width(400);
+
+            let mut recipients = Column::new()
+                .push(
+                    Row::new()
+                        .push(Text::new("Address").bold().width(Length::Fill).view())
+                        .push(
+                            Text::new("Amount")
+                                .bold()
+                                .horizontal_alignment(Horizontal::Right)
+                                .width(Length::Fixed(150.0))
+                                .view(),
+                        )
+                        .push(Text::new("Label").bold().width(Length::Fixed(200.0)).view())
+                        .spacing(10),
+                )
+                .push(rule::horizontal_bold())
+                .spacing(10);
+
+            for recipient in self.recipients.iter() {
+                recipients = recipients
+                    .push(
+                        Row::new()
+                            .push(
+                                Text::new(recipient.address.clone().assume_checked().to_string())
+                                    .width(Length::Fill)
+                                    .view(),
+                            )
+                            .push(
+                                Text::new(format!("{} sat", format::number(recipient.amount)))
+                                    .horizontal_alignment(Horizontal::Right)
+                                    .width(Length::Fixed(150.0))
+                                    .view(),
+                            )
+                            .push(
+                                Text::new(recipient.label.clone().unwrap_or_default())
+                                    .width(Length::Fixed(200.0))
+                                    .view(),
+                            )
+                            .spacing(10),
+                    )
+                    .push(rule::horizontal());
+            }
+
+            let error = if let Some(error) = &self.error {
+                Row::new().push(Text::new(error).color(DARK_RED).view())
+            } else {
+                Row::new()
+            };
+
+            let ready: bool = self.policy.is_some()
+                && !self.recipients.is_empty()
+                && matches!(self.preview, Some(Ok(..)));
+
+            let mut send_btn = Button::new()
+                .text("Create proposal")
+                .loading(self.loading)
+                .width(Length::Fixed(400.0));
+            if ready {
+                send_btn = send_btn.on_press(BatchSpendMessage::SendProposal.into());
+            }
+
+            content = content
+                .push(
+                    Row::new()
+                        .push(details)
+                        .push(rule::vertical())
+                        .push(
+                            FeeSelector::new(self.fee_rate, |f| {
+                                BatchSpendMessage::FeeRateChanged(f).into()
+                            })
+                            .current_mempool_fees(ctx.current_fees.clone())
+                            .mempool_snapshot(ctx.mempool_snapshot.clone())
+                            .estimate_tx_vsize(match &self.preview {
+                                Some(Ok(preview)) => Some(preview.estimated_vsize),
+                                _ => None,
+                            })
+                            .max_width(400.0),
+                        )
+                        .spacing(25)
+                        .height(Length::Fixed(375.0)),
+                )
+                .push(Space::with_height(Length::Fixed(5.0)))
+                .push(error)
+                .push(send_btn.view())
+                .push(Space::with_height(Length::Fixed(10.0)))
+                .push(recipients.max_width(850));
+        }
+
+        let content = Container::new(
+            content
+                .align_items(Alignment::Center)
+                .spacing(10)
+                .padding(20),
+        )
+        .width(Length::Fill)
+        .center_x();
+
+        Dashboard::new()
+            .loaded(self.loaded)
+            .view(ctx, content, true, false)
+    }
+}
+
+impl From<BatchSpendState> for Box<dyn State> {
+    fn from(s: BatchSpendState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<BatchSpendMessage> for Message {
+    fn from(msg: BatchSpendMessage) -> Self {
+        Self::BatchSpend(msg)
+    }
+}
@@ -227,7 +227,9 @@ impl State for CompletedProposalState {
 
                     match completed_proposal {
                         CompletedProposal::Spending {
-                            tx, description, ..
+                            tx,
+                            description,
+                            memo,
                         } => {
                             let txid = tx.txid();
                             content = content
@@ -241,6 +243,10 @@ impl State for CompletedProposalState {
                                         .view(),
                                 )
                                 .push(Text::new(format!("Description: {description}")).view());
+
+                            if let Some(memo) = memo {
+                                content = content.push(Text::new(format!("Memo: {memo}")).view());
+                            }
                         }
                         CompletedProposal::KeyAgentPayment {
                             tx, description, ..
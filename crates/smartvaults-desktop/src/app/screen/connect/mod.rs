@@ -1,31 +1,48 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
-use iced::widget::{Column, Row, Space};
+use iced::widget::{Column, PickList, Row, Space};
 use iced::{Alignment, Command, Element, Length};
 use smartvaults_sdk::nostr::nips::nip46::NostrConnectURI;
 use smartvaults_sdk::nostr::{EventId, PublicKey, Timestamp};
-use smartvaults_sdk::types::NostrConnectRequest;
+use smartvaults_sdk::types::{NostrConnectRequest, NostrConnectRequestDetail};
 use smartvaults_sdk::util;
 
 pub mod add_session;
+pub mod profiles;
 
 use crate::app::component::Dashboard;
 use crate::app::{Context, Message, Stage, State};
 use crate::component::{rule, Button, ButtonStyle, Text};
 use crate::theme::color::RED;
-use crate::theme::icon::{CHECK, FULLSCREEN, PLUS, RELOAD, STOP, STOPWATCH, TRASH};
+use crate::theme::icon::{CHECK, FULLSCREEN, LOCK, PLUS, RELOAD, STOP, STOPWATCH, TRASH};
 
 type Sessions = Vec<(NostrConnectURI, Timestamp)>;
 type Requests = Vec<NostrConnectRequest>;
 type Authorizations = BTreeMap<PublicKey, Timestamp>;
+type SessionProfiles = HashMap<PublicKey, String>;
+
+const NO_PROFILE: &str = "No restrictions";
 
 #[derive(Debug, Clone)]
 pub enum ConnectMessage {
-    Load((Sessions, Requests, Requests, Authorizations)),
+    Load(
+        (
+            Sessions,
+            Requests,
+            Requests,
+            Authorizations,
+            Vec<String>,
+            SessionProfiles,
+        ),
+    ),
+    SetSessionProfile(PublicKey, String),
+    ShowRequest(EventId),
+    RequestDetailLoaded(NostrConnectRequestDetail),
+    CloseRequest,
     ApproveRequest(EventId),
     DeleteRequest(EventId),
     DisconnectSession(PublicKey),
@@ -43,6 +60,9 @@ pub struct ConnectState {
     pending_requests: Requests,
     approved_requests: Requests,
     authorizations: Authorizations,
+    profiles: Vec<String>,
+    session_profiles: SessionProfiles,
+    detail: Option<NostrConnectRequestDetail>,
     error: Option<String>,
 }
 
@@ -66,11 +86,23 @@ impl State for ConnectState {
                 let pending_requests = client.get_nostr_connect_requests(false).await.unwrap();
                 let approved_requests = client.get_nostr_connect_requests(true).await.unwrap();
                 let authorizations = client.get_nostr_connect_pre_authorizations().await;
+                let mut profiles: Vec<String> = vec![NO_PROFILE.to_string()];
+                profiles.extend(
+                    client
+                        .get_nostr_connect_profiles()
+                        .await
+                        .unwrap()
+                        .into_iter()
+                        .map(|p| p.name),
+                );
+                let session_profiles = client.get_nostr_connect_sessions_profiles().await.unwrap();
                 (
                     sessions,
                     pending_requests,
                     approved_requests,
                     authorizations,
+                    profiles,
+                    session_profiles,
                 )
             },
             |c| ConnectMessage::Load(c).into(),
@@ -89,17 +121,59 @@ impl State for ConnectState {
                     pending_requests,
                     approved_requests,
                     authorizations,
+                    profiles,
+                    session_profiles,
                 )) => {
                     self.sessions = sessions;
                     self.pending_requests = pending_requests;
                     self.approved_requests = approved_requests;
                     self.authorizations = authorizations;
+                    self.profiles = profiles;
+                    self.session_profiles = session_profiles;
                     self.loading = false;
                     self.loaded = true;
                     Command::none()
                 }
+                ConnectMessage::SetSessionProfile(app_public_key, profile) => {
+                    let client = ctx.client.clone();
+                    let profile: Option<String> = if profile == NO_PROFILE {
+                        None
+                    } else {
+                        Some(profile)
+                    };
+                    Command::perform(
+                        async move {
+                            client
+                                .set_nostr_connect_session_profile(app_public_key, profile)
+                                .await
+                        },
+                        |res| match res {
+                            Ok(_) => ConnectMessage::Reload.into(),
+                            Err(e) => ConnectMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    )
+                }
+                ConnectMessage::ShowRequest(id) => {
+                    let client = ctx.client.clone();
+                    Command::perform(
+                        async move { client.get_nostr_connect_request_detail(id).await },
+                        |res| match res {
+                            Ok(detail) => ConnectMessage::RequestDetailLoaded(detail).into(),
+                            Err(e) => ConnectMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    )
+                }
+                ConnectMessage::RequestDetailLoaded(detail) => {
+                    self.detail = Some(detail);
+                    Command::none()
+                }
+                ConnectMessage::CloseRequest => {
+                    self.detail = None;
+                    Command::none()
+                }
                 ConnectMessage::ApproveRequest(id) => {
                     self.loading = true;
+                    self.detail = None;
                     let client = ctx.client.clone();
                     Command::perform(
                         async move { client.approve_nostr_connect_request(id).await },
@@ -111,6 +185,7 @@ impl State for ConnectState {
                 }
                 ConnectMessage::DeleteRequest(id) => {
                     self.loading = true;
+                    self.detail = None;
                     let client = ctx.client.clone();
                     Command::perform(
                         async move { client.reject_nostr_connect_request(id).await },
@@ -239,7 +314,21 @@ impl State for ConnectState {
                                     .width(Length::Fill)
                                     .view(),
                             )
-                            .push(Space::with_width(Length::Fixed(40.0)))
+                            .push(
+                                Text::new("Profile")
+                                    .bold()
+                                    .big()
+                                    .width(Length::Fixed(200.0))
+                                    .view(),
+                            )
+                            .push(
+                                Button::new()
+                                    .icon(LOCK)
+                                    .width(Length::Fixed(40.0))
+                                    .style(ButtonStyle::Bordered)
+                                    .on_press(Message::View(Stage::NostrConnectProfiles))
+                                    .view(),
+                            )
                             .push(
                                 Button::new()
                                     .icon(PLUS)
@@ -264,6 +353,7 @@ impl State for ConnectState {
                     .push(rule::horizontal_bold());
 
                 for (uri, timestamp) in self.sessions.iter() {
+                    let app_public_key: PublicKey = uri.public_key;
                     let row = Row::new()
                         .push(
                             Text::new(util::cut_public_key(uri.public_key))
@@ -293,6 +383,23 @@ impl State for ConnectState {
                             .width(Length::Fill)
                             .view(),
                         )
+                        .push(
+                            PickList::new(
+                                self.profiles.clone(),
+                                Some(
+                                    self.session_profiles
+                                        .get(&uri.public_key)
+                                        .cloned()
+                                        .unwrap_or_else(|| NO_PROFILE.to_string()),
+                                ),
+                                move |profile| {
+                                    ConnectMessage::SetSessionProfile(app_public_key, profile)
+                                        .into()
+                                },
+                            )
+                            .width(Length::Fixed(200.0))
+                            .padding(10),
+                        )
                         .push(
                             if self.authorizations.get(&uri.public_key).is_some() {
                                 Button::new()
@@ -369,6 +476,7 @@ impl State for ConnectState {
                                         .width(Length::Fill)
                                         .view(),
                                 )
+                                .push(Space::with_width(Length::Fixed(40.0)))
                                 .push(Space::with_width(Length::Fixed(120.0)))
                                 .push(Space::with_width(Length::Fixed(40.0)))
                                 .spacing(10)
@@ -396,6 +504,16 @@ impl State for ConnectState {
                                         .width(Length::Fill)
                                         .view(),
                                 )
+                                .push(
+                                    Button::new()
+                                        .icon(FULLSCREEN)
+                                        .on_press(
+                                            ConnectMessage::ShowRequest(request.event_id).into(),
+                                        )
+                                        .style(ButtonStyle::Bordered)
+                                        .width(Length::Fixed(40.0))
+                                        .view(),
+                                )
                                 .push(
                                     Button::new()
                                         .icon(CHECK)
@@ -426,6 +544,12 @@ impl State for ConnectState {
                     }
                 }
 
+                if let Some(detail) = &self.detail {
+                    content = content
+                        .push(Space::with_height(Length::Fixed(20.0)))
+                        .push(view_request_detail(detail, self.loading));
+                }
+
                 // Approved Requests
 
                 if !self.approved_requests.is_empty() {
@@ -497,6 +621,96 @@ impl State for ConnectState {
     }
 }
 
+fn view_request_detail(
+    detail: &NostrConnectRequestDetail,
+    loading: bool,
+) -> Element<'static, Message> {
+    let mut content = Column::new()
+        .push(
+            Row::new()
+                .push(
+                    Text::new(format!("Request #{}", util::cut_event_id(detail.event_id)))
+                        .big()
+                        .bold()
+                        .width(Length::Fill)
+                        .view(),
+                )
+                .push(
+                    Button::new()
+                        .text("Close")
+                        .style(ButtonStyle::Bordered)
+                        .on_press(ConnectMessage::CloseRequest.into())
+                        .width(Length::Fixed(120.0))
+                        .view(),
+                )
+                .align_items(Alignment::Center),
+        )
+        .push(rule::horizontal_bold())
+        .push(Text::new(format!("Method: {}", detail.method)).view())
+        .push(
+            Text::new(format!(
+                "App public key: {}",
+                util::cut_public_key(detail.app_public_key)
+            ))
+            .view(),
+        )
+        .push(
+            Text::new(format!(
+                "Requested at: {}",
+                detail.timestamp.to_human_datetime()
+            ))
+            .view(),
+        )
+        .spacing(10);
+
+    if let Some(kind) = detail.kind() {
+        content = content.push(Text::new(format!("Event kind: {}", kind.as_u64())).view());
+    }
+
+    if let Some(preview) = detail.content_preview(500) {
+        content = content
+            .push(Text::new("Event content").bold().view())
+            .push(Text::new(preview).extra_light().view());
+    } else if !detail.params.is_empty() {
+        content = content.push(Text::new("Params").bold().view());
+        for param in detail.params.iter() {
+            content = content.push(Text::new(param).extra_light().view());
+        }
+    }
+
+    content = content
+        .push(Text::new("Raw payload").bold().view())
+        .push(Text::new(&detail.raw).small().extra_light().view());
+
+    if !detail.approved {
+        content = content.push(
+            Row::new()
+                .push(
+                    Button::new()
+                        .icon(CHECK)
+                        .text("Approve")
+                        .on_press(ConnectMessage::ApproveRequest(detail.event_id).into())
+                        .loading(loading)
+                        .width(Length::Fixed(200.0))
+                        .view(),
+                )
+                .push(
+                    Button::new()
+                        .icon(TRASH)
+                        .text("Reject")
+                        .on_press(ConnectMessage::DeleteRequest(detail.event_id).into())
+                        .loading(loading)
+                        .style(ButtonStyle::BorderedDanger)
+                        .width(Length::Fixed(200.0))
+                        .view(),
+                )
+                .spacing(10),
+        );
+    }
+
+    content.into()
+}
+
 impl From<ConnectState> for Box<dyn State> {
     fn from(s: ConnectState) -> Box<dyn State> {
         Box::new(s)
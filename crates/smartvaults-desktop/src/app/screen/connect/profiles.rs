@@ -0,0 +1,288 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::BTreeSet;
+
+use iced::widget::{Column, Row, Space};
+use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::types::NostrConnectProfile;
+
+use crate::app::component::Dashboard;
+use crate::app::{Context, Message, State};
+use crate::component::{rule, Button, ButtonStyle, Text, TextInput};
+use crate::theme::color::DARK_RED;
+use crate::theme::icon::{PENCIL, RELOAD, TRASH};
+
+#[derive(Debug, Clone)]
+pub enum NostrConnectProfilesMessage {
+    Load(Vec<NostrConnectProfile>),
+    Edit(NostrConnectProfile),
+    NameChanged(String),
+    MethodsChanged(String),
+    KindsChanged(String),
+    Save,
+    Delete(String),
+    ErrorChanged(Option<String>),
+    Reload,
+}
+
+#[derive(Debug, Default)]
+pub struct NostrConnectProfilesState {
+    profiles: Vec<NostrConnectProfile>,
+    name: String,
+    methods: String,
+    kinds: String,
+    loading: bool,
+    loaded: bool,
+    error: Option<String>,
+}
+
+impl NostrConnectProfilesState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn profile(&self) -> Result<NostrConnectProfile, String> {
+        let name: String = self.name.trim().to_string();
+        if name.is_empty() {
+            return Err(String::from("Name required"));
+        }
+
+        let methods: BTreeSet<String> = self
+            .methods
+            .split(',')
+            .map(|m| m.trim())
+            .filter(|m| !m.is_empty())
+            .map(String::from)
+            .collect();
+        if methods.is_empty() {
+            return Err(String::from("At least one method required"));
+        }
+
+        let mut kinds: BTreeSet<u64> = BTreeSet::new();
+        for kind in self
+            .kinds
+            .split(',')
+            .map(|k| k.trim())
+            .filter(|k| !k.is_empty())
+        {
+            kinds.insert(
+                kind.parse::<u64>()
+                    .map_err(|_| format!("Invalid kind: {kind}"))?,
+            );
+        }
+
+        Ok(NostrConnectProfile {
+            name,
+            methods,
+            kinds,
+        })
+    }
+}
+
+impl State for NostrConnectProfilesState {
+    fn title(&self) -> String {
+        String::from("Permission profiles")
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.loading = true;
+        let client = ctx.client.clone();
+        Command::perform(
+            async move { client.get_nostr_connect_profiles().await },
+            |res| match res {
+                Ok(profiles) => NostrConnectProfilesMessage::Load(profiles).into(),
+                Err(e) => NostrConnectProfilesMessage::ErrorChanged(Some(e.to_string())).into(),
+            },
+        )
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if !self.loaded && !self.loading {
+            return self.load(ctx);
+        }
+
+        if let Message::NostrConnectProfiles(msg) = message {
+            match msg {
+                NostrConnectProfilesMessage::Load(profiles) => {
+                    self.profiles = profiles;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                NostrConnectProfilesMessage::Edit(profile) => {
+                    let kinds: Vec<String> = profile.kinds.iter().map(|k| k.to_string()).collect();
+                    self.name = profile.name;
+                    self.methods = profile.methods.into_iter().collect::<Vec<_>>().join(", ");
+                    self.kinds = kinds.join(", ");
+                }
+                NostrConnectProfilesMessage::NameChanged(name) => self.name = name,
+                NostrConnectProfilesMessage::MethodsChanged(methods) => self.methods = methods,
+                NostrConnectProfilesMessage::KindsChanged(kinds) => self.kinds = kinds,
+                NostrConnectProfilesMessage::Save => match self.profile() {
+                    Ok(profile) => {
+                        self.loading = true;
+                        let client = ctx.client.clone();
+                        return Command::perform(
+                            async move { client.save_nostr_connect_profile(profile).await },
+                            |res| match res {
+                                Ok(_) => NostrConnectProfilesMessage::Reload.into(),
+                                Err(e) => {
+                                    NostrConnectProfilesMessage::ErrorChanged(Some(e.to_string()))
+                                        .into()
+                                }
+                            },
+                        );
+                    }
+                    Err(e) => self.error = Some(e),
+                },
+                NostrConnectProfilesMessage::Delete(name) => {
+                    self.loading = true;
+                    let client = ctx.client.clone();
+                    return Command::perform(
+                        async move { client.delete_nostr_connect_profile(name).await },
+                        |res| match res {
+                            Ok(_) => NostrConnectProfilesMessage::Reload.into(),
+                            Err(e) => {
+                                NostrConnectProfilesMessage::ErrorChanged(Some(e.to_string()))
+                                    .into()
+                            }
+                        },
+                    );
+                }
+                NostrConnectProfilesMessage::ErrorChanged(e) => {
+                    self.error = e;
+                    self.loading = false;
+                }
+                NostrConnectProfilesMessage::Reload => {
+                    self.error = None;
+                    return self.load(ctx);
+                }
+            }
+        }
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let mut content = Column::new().spacing(10).padding(20);
+
+        if self.loaded {
+            content = content
+                .push(
+                    Row::new()
+                        .push(Text::new("Name").bold().big().width(Length::Fill).view())
+                        .push(Text::new("Methods").bold().big().width(Length::Fill).view())
+                        .push(Text::new("Kinds").bold().big().width(Length::Fill).view())
+                        .push(Space::with_width(Length::Fixed(40.0)))
+                        .push(
+                            Button::new()
+                                .icon(RELOAD)
+                                .style(ButtonStyle::Bordered)
+                                .on_press(NostrConnectProfilesMessage::Reload.into())
+                                .loading(self.loading)
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal_bold());
+
+            for profile in self.profiles.iter() {
+                let methods: Vec<&str> = profile.methods.iter().map(|m| m.as_str()).collect();
+                let kinds: String = if profile.kinds.is_empty() {
+                    String::from("Any")
+                } else {
+                    let kinds: Vec<String> = profile.kinds.iter().map(|k| k.to_string()).collect();
+                    kinds.join(", ")
+                };
+                let row = Row::new()
+                    .push(Text::new(&profile.name).width(Length::Fill).view())
+                    .push(Text::new(methods.join(", ")).width(Length::Fill).view())
+                    .push(Text::new(kinds).width(Length::Fill).view())
+                    .push(
+                        Button::new()
+                            .icon(PENCIL)
+                            .style(ButtonStyle::Bordered)
+                            .on_press(NostrConnectProfilesMessage::Edit(profile.clone()).into())
+                            .width(Length::Fixed(40.0))
+                            .view(),
+                    )
+                    .push(
+                        Button::new()
+                            .icon(TRASH)
+                            .style(ButtonStyle::BorderedDanger)
+                            .on_press(
+                                NostrConnectProfilesMessage::Delete(profile.name.clone()).into(),
+                            )
+                            .loading(self.loading)
+                            .width(Length::Fixed(40.0))
+                            .view(),
+                    )
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .width(Length::Fill);
+                content = content.push(row).push(rule::horizontal());
+            }
+
+            let form = Column::new()
+                .push(Text::new("Add or edit profile").big().bold().view())
+                .push(
+                    TextInput::with_label("Name", &self.name)
+                        .on_input(|s| NostrConnectProfilesMessage::NameChanged(s).into())
+                        .placeholder("social app")
+                        .view(),
+                )
+                .push(
+                    TextInput::with_label("Methods (comma separated)", &self.methods)
+                        .on_input(|s| NostrConnectProfilesMessage::MethodsChanged(s).into())
+                        .placeholder("sign_event, nip04_encrypt, nip04_decrypt")
+                        .view(),
+                )
+                .push(
+                    TextInput::with_label(
+                        "Kinds allowed for sign_event (comma separated, empty for any)",
+                        &self.kinds,
+                    )
+                    .on_input(|s| NostrConnectProfilesMessage::KindsChanged(s).into())
+                    .placeholder("0, 1, 7")
+                    .view(),
+                )
+                .push(
+                    Button::new()
+                        .text("Save profile")
+                        .on_press(NostrConnectProfilesMessage::Save.into())
+                        .loading(self.loading)
+                        .width(Length::Fixed(250.0))
+                        .view(),
+                )
+                .spacing(10)
+                .max_width(600);
+
+            content = content
+                .push(Space::with_height(Length::Fixed(20.0)))
+                .push(form);
+
+            if let Some(error) = &self.error {
+                content = content.push(Text::new(error).color(DARK_RED).view());
+            }
+        }
+
+        Dashboard::new()
+            .loaded(self.loaded)
+            .view(ctx, content, true, false)
+    }
+}
+
+impl From<NostrConnectProfilesState> for Box<dyn State> {
+    fn from(s: NostrConnectProfilesState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<NostrConnectProfilesMessage> for Message {
+    fn from(msg: NostrConnectProfilesMessage) -> Self {
+        Self::NostrConnectProfiles(msg)
+    }
+}
@@ -11,7 +11,7 @@ use smartvaults_sdk::util;
 use crate::app::component::Dashboard;
 use crate::app::{Context, Message, Stage, State};
 use crate::component::{rule, Button, ButtonStyle, Text};
-use crate::theme::icon::{CLIPBOARD, PLUS, RELOAD, TRASH};
+use crate::theme::icon::{CLIPBOARD, PLUS, RELOAD, TRASH, VAULT};
 
 #[derive(Debug, Clone)]
 pub enum ContactsMessage {
@@ -186,6 +186,14 @@ impl State for ContactsState {
                                 .width(Length::Fill)
                                 .view(),
                         )
+                        .push(
+                            Button::new()
+                                .style(ButtonStyle::Bordered)
+                                .icon(VAULT)
+                                .on_press(Message::View(Stage::VaultBuilder(Some(public_key))))
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
                         .push(
                             Button::new()
                                 .style(ButtonStyle::Bordered)
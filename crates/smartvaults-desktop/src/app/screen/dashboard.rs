@@ -3,20 +3,29 @@
 
 use std::collections::BTreeSet;
 
-use iced::widget::{Column, Space};
-use iced::{Command, Element, Length};
+use iced::widget::{Column, Row, Space};
+use iced::{Alignment, Command, Element, Length};
 use smartvaults_sdk::core::bdk::wallet::Balance;
-use smartvaults_sdk::types::{GetProposal, GetTransaction};
+use smartvaults_sdk::types::{GetProposal, GetTransaction, GetVaultSummary, VaultSyncStatus};
 
 use crate::app::component::{Activity, Balances, Dashboard};
 use crate::app::{Context, Message, Stage, State};
-use crate::component::Text;
+use crate::component::{Amount, Button, ButtonStyle, Card, Icon, Text};
+use crate::theme::color::YELLOW;
+use crate::theme::icon::{FULLSCREEN, STAR_FILL};
+
+const CARDS_PER_ROW: usize = 3;
 
 #[derive(Debug, Clone)]
 pub enum DashboardMessage {
     Send,
     Deposit,
-    Load(Balance, Vec<GetProposal>, BTreeSet<GetTransaction>),
+    Load(
+        Balance,
+        Vec<GetVaultSummary>,
+        Vec<GetProposal>,
+        BTreeSet<GetTransaction>,
+    ),
     Reload,
 }
 
@@ -25,6 +34,7 @@ pub struct DashboardState {
     loading: bool,
     loaded: bool,
     balance: Balance,
+    vaults: Vec<GetVaultSummary>,
     proposals: Vec<GetProposal>,
     transactions: BTreeSet<GetTransaction>,
 }
@@ -46,12 +56,15 @@ impl State for DashboardState {
         Command::perform(
             async move {
                 let balance = client.get_total_balance().await.unwrap();
+                let vaults = client.get_vault_summaries().await.unwrap();
                 let txs = client.get_all_transactions().await.unwrap();
                 let proposals = client.get_proposals().await.unwrap();
 
-                (balance, proposals, txs)
+                (balance, vaults, proposals, txs)
+            },
+            |(balance, vaults, proposals, txs)| {
+                DashboardMessage::Load(balance, vaults, proposals, txs).into()
             },
-            |(balance, proposals, txs)| DashboardMessage::Load(balance, proposals, txs).into(),
         )
     }
 
@@ -68,8 +81,9 @@ impl State for DashboardState {
                 DashboardMessage::Deposit => {
                     return Command::perform(async {}, |_| Message::View(Stage::Receive(None)))
                 }
-                DashboardMessage::Load(balance, proposals, txs) => {
+                DashboardMessage::Load(balance, vaults, proposals, txs) => {
                     self.balance = balance;
+                    self.vaults = vaults;
                     self.proposals = proposals;
                     self.transactions = txs;
                     self.loading = false;
@@ -102,7 +116,25 @@ impl State for DashboardState {
                         .on_deposit(DashboardMessage::Deposit.into())
                         .view(),
                 )
-                .push(Space::with_height(Length::Fixed(20.0)))
+                .push(Space::with_height(Length::Fixed(20.0)));
+
+            if !self.vaults.is_empty() {
+                content = content
+                    .push(Text::new("Vaults").bold().big().view())
+                    .push(Space::with_height(Length::Fixed(10.0)));
+
+                for chunk in self.vaults.chunks(CARDS_PER_ROW) {
+                    let mut row = Row::new().spacing(10);
+                    for summary in chunk.iter() {
+                        row = row.push(vault_card(summary, ctx.hide_balances));
+                    }
+                    content = content.push(row);
+                }
+
+                content = content.push(Space::with_height(Length::Fixed(20.0)));
+            }
+
+            content = content
                 .push(Text::new("Activity").bold().big().view())
                 .push(Space::with_height(Length::Fixed(10.0)))
                 .push(Activity::new(self.proposals.clone(), self.transactions.clone()).view(ctx));
@@ -114,6 +146,55 @@ impl State for DashboardState {
     }
 }
 
+fn vault_card(summary: &GetVaultSummary, hide_balances: bool) -> Element<'static, Message> {
+    let mut head = Row::new().spacing(5).align_items(Alignment::Center);
+    if summary.pinned {
+        head = head.push(Icon::new(STAR_FILL).color(YELLOW));
+    }
+    head = head.push(Text::new(&summary.name).bold().view());
+
+    let sync_status: String = match summary.sync_status {
+        VaultSyncStatus::NotLoaded => String::from("Loading..."),
+        VaultSyncStatus::NeverSynced | VaultSyncStatus::Syncing => String::from("Syncing..."),
+        VaultSyncStatus::Synced(last_sync) => {
+            format!("Synced: {}", last_sync.to_human_datetime())
+        }
+    };
+    let last_activity: String = match summary.last_activity {
+        Some(timestamp) => format!("Last activity: {}", timestamp.to_human_datetime()),
+        None => String::from("No activity"),
+    };
+
+    let body = Column::new()
+        .spacing(5)
+        .push(
+            Amount::new(summary.balance.total())
+                .bold()
+                .hidden(hide_balances)
+                .view(),
+        )
+        .push(
+            Text::new(format!("Pending proposals: {}", summary.pending_proposals))
+                .small()
+                .view(),
+        )
+        .push(Text::new(last_activity).small().extra_light().view())
+        .push(Text::new(sync_status).small().extra_light().view());
+
+    Card::new(head, body)
+        .foot(
+            Button::new()
+                .style(ButtonStyle::Bordered)
+                .icon(FULLSCREEN)
+                .text("Open")
+                .on_press(Message::View(Stage::Vault(summary.policy_id)))
+                .width(Length::Fill)
+                .view(),
+        )
+        .max_width(250.0)
+        .view()
+}
+
 impl From<DashboardState> for Box<dyn State> {
     fn from(s: DashboardState) -> Box<dyn State> {
         Box::new(s)
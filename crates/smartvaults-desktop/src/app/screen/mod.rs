@@ -8,6 +8,7 @@ mod activities;
 mod add_coldcard_signer;
 mod add_signer;
 mod addresses;
+mod batch_spend;
 mod completed_proposal;
 mod connect;
 mod contacts;
@@ -37,8 +38,10 @@ pub use self::add_coldcard_signer::{AddColdcardSignerMessage, AddColdcardSignerS
 pub use self::add_contact::{AddContactMessage, AddContactState};
 pub use self::add_signer::{AddSignerMessage, AddSignerState};
 pub use self::addresses::{AddressesMessage, AddressesState};
+pub use self::batch_spend::{BatchSpendMessage, BatchSpendState};
 pub use self::completed_proposal::{CompletedProposalMessage, CompletedProposalState};
 pub use self::connect::add_session::{AddNostrConnectSessionMessage, AddNostrConnectSessionState};
+pub use self::connect::profiles::{NostrConnectProfilesMessage, NostrConnectProfilesState};
 pub use self::connect::{ConnectMessage, ConnectState};
 pub use self::contacts::{ContactsMessage, ContactsState};
 pub use self::dashboard::{DashboardMessage, DashboardState};
@@ -53,11 +56,15 @@ pub use self::receive::{ReceiveMessage, ReceiveState};
 pub use self::revoke_all_signers::{RevokeAllSignersMessage, RevokeAllSignersState};
 pub use self::self_transfer::{SelfTransferMessage, SelfTransferState};
 pub use self::settings::add_relay::{AddRelayMessage, AddRelayState};
+pub use self::settings::approval_pin::{ApprovalPinMessage, ApprovalPinState};
 pub use self::settings::change_password::{ChangePasswordMessage, ChangePasswordState};
 pub use self::settings::config::{ConfigMessage, ConfigState};
+pub use self::settings::devices::{DevicesMessage, DevicesState};
+pub use self::settings::doctor::{DoctorMessage, DoctorState};
 pub use self::settings::recovery_keys::{RecoveryKeysMessage, RecoveryKeysState};
 pub use self::settings::relay::{RelayMessage, RelayState};
 pub use self::settings::relays::{RelaysMessage, RelaysState};
+pub use self::settings::update::{UpdateMessage, UpdateState};
 pub use self::settings::wipe_keys::{WipeKeysMessage, WipeKeysState};
 pub use self::settings::{SettingsMessage, SettingsState};
 pub use self::share_signer::{ShareSignerMessage, ShareSignerState};
@@ -67,7 +74,10 @@ pub use self::spend::{SpendMessage, SpendState};
 pub use self::transaction::{TransactionMessage, TransactionState};
 pub use self::vault::add::{AddVaultMessage, AddVaultState};
 pub use self::vault::builder::{PolicyBuilderMessage, PolicyBuilderState};
+pub use self::vault::members::{VaultMembersMessage, VaultMembersState};
 pub use self::vault::restore::{RestoreVaultMessage, RestoreVaultState};
 pub use self::vault::tree::{PolicyTreeMessage, PolicyTreeState};
+pub use self::vault::utxos::{VaultUtxosMessage, VaultUtxosState};
 pub use self::vault::vaults::{PoliciesMessage, PoliciesState};
+pub use self::vault::whitelist::{VaultWhitelistMessage, VaultWhitelistState};
 pub use self::vault::{VaultMessage, VaultState};
@@ -4,13 +4,18 @@
 use iced::widget::{Column, Row, Space};
 use iced::{Alignment, Command, Element, Length};
 use rfd::FileDialog;
+use smartvaults_sdk::core::bitcoin::address::NetworkUnchecked;
 use smartvaults_sdk::core::bitcoin::psbt::PartiallySignedTransaction;
-use smartvaults_sdk::core::proposal::Proposal;
+use smartvaults_sdk::core::bitcoin::Address;
+use smartvaults_sdk::core::proposal::{Proposal, ProposalDiff};
 use smartvaults_sdk::core::signer::{Signer, SignerType};
 use smartvaults_sdk::core::{CompletedProposal, PsbtUtility};
-use smartvaults_sdk::nostr::{EventId, PublicKey};
-use smartvaults_sdk::types::{GetApproval, GetProposal};
-use smartvaults_sdk::util;
+use smartvaults_sdk::nostr::{EventId, PublicKey, Timestamp};
+use smartvaults_sdk::types::{
+    GetApproval, GetProposal, GetProposalComment, GetQuorumStatus, GetVelocityLimitStatus,
+    ProposalIssue,
+};
+use smartvaults_sdk::util::{self, format};
 
 use crate::app::component::Dashboard;
 use crate::app::{Context, Message, Stage, State};
@@ -27,9 +32,19 @@ pub enum ProposalMessage {
         Vec<GetApproval>,
         Option<Signer>,
         PublicKey,
+        bool,
+        Vec<ProposalIssue>,
+        Option<EventId>,
+        Option<ProposalDiff>,
+        Option<Timestamp>,
+        Vec<GetProposalComment>,
+        Option<GetQuorumStatus>,
+        Vec<GetVelocityLimitStatus>,
+        Vec<Address<NetworkUnchecked>>,
     ),
     Approve,
     ApproveWithSeed(String),
+    MarkRegistered,
     Finalize,
     Signed(bool),
     Reload,
@@ -37,13 +52,19 @@ pub enum ProposalMessage {
     RevokeApproval(EventId),
     SetModal(Option<ModalType>),
     PasswordChanged(String),
+    PinChanged(String),
     Delete,
+    CommentChanged(String),
+    SendComment,
+    CommentSent,
     ErrorChanged(Option<String>),
 }
 
 #[derive(Debug, Clone)]
 pub enum ModalType {
     Approve,
+    /// Ask the approval PIN before selecting the signed PSBT
+    ApproveWithPsbt,
     Delete,
 }
 
@@ -58,8 +79,19 @@ pub struct ProposalState {
     policy_id: Option<EventId>,
     my_public_key: Option<PublicKey>,
     password: String,
+    pin: Option<String>,
     approved_proposals: Vec<GetApproval>,
     signer: Option<Signer>,
+    registration_required: bool,
+    issues: Vec<ProposalIssue>,
+    replaces: Option<EventId>,
+    diff: Option<ProposalDiff>,
+    expires_at: Option<Timestamp>,
+    comments: Vec<GetProposalComment>,
+    comment: String,
+    quorum: Option<GetQuorumStatus>,
+    exceeded_limits: Vec<GetVelocityLimitStatus>,
+    not_whitelisted: Vec<Address<NetworkUnchecked>>,
     error: Option<String>,
 }
 
@@ -75,8 +107,19 @@ impl ProposalState {
             policy_id: None,
             my_public_key: None,
             password: String::new(),
+            pin: None,
             approved_proposals: Vec::new(),
             signer: None,
+            registration_required: false,
+            issues: Vec::new(),
+            replaces: None,
+            diff: None,
+            expires_at: None,
+            comments: Vec::new(),
+            comment: String::new(),
+            quorum: None,
+            exceeded_limits: Vec::new(),
+            not_whitelisted: Vec::new(),
             error: None,
         }
     }
@@ -101,6 +144,10 @@ impl State for ProposalState {
                     policy_id,
                     proposal,
                     signed,
+                    issues,
+                    replaces,
+                    diff,
+                    expires_at,
                     ..
                 } = client.get_proposal_by_id(proposal_id).await.ok()?;
                 let signer = client
@@ -111,6 +158,26 @@ impl State for ProposalState {
                     .get_approvals_by_proposal_id(proposal_id)
                     .await
                     .unwrap_or_default();
+                let registration_required: bool = match &signer {
+                    Some(signer) => client
+                        .is_device_registration_required(policy_id, signer)
+                        .await
+                        .unwrap_or_default(),
+                    None => false,
+                };
+                let comments = client
+                    .get_proposal_comments(proposal_id)
+                    .await
+                    .unwrap_or_default();
+                let quorum = client.get_quorum_status(proposal_id).await.ok();
+                let exceeded_limits = client
+                    .get_proposal_exceeded_velocity_limits(proposal_id)
+                    .await
+                    .unwrap_or_default();
+                let not_whitelisted = client
+                    .get_proposal_not_whitelisted_recipients(proposal_id)
+                    .await
+                    .unwrap_or_default();
                 let keys = client.keys();
 
                 Some((
@@ -120,15 +187,52 @@ impl State for ProposalState {
                     approvals,
                     signer,
                     keys.public_key(),
+                    registration_required,
+                    issues,
+                    replaces,
+                    diff,
+                    expires_at,
+                    comments,
+                    quorum,
+                    exceeded_limits,
+                    not_whitelisted,
                 ))
             },
             |res| match res {
-                Some((proposal, signed, policy_id, approvals, signer, pk)) => {
-                    ProposalMessage::LoadProposal(
-                        proposal, signed, policy_id, approvals, signer, pk,
-                    )
-                    .into()
-                }
+                Some((
+                    proposal,
+                    signed,
+                    policy_id,
+                    approvals,
+                    signer,
+                    pk,
+                    registration_required,
+                    issues,
+                    replaces,
+                    diff,
+                    expires_at,
+                    comments,
+                    quorum,
+                    exceeded_limits,
+                    not_whitelisted,
+                )) => ProposalMessage::LoadProposal(
+                    proposal,
+                    signed,
+                    policy_id,
+                    approvals,
+                    signer,
+                    pk,
+                    registration_required,
+                    issues,
+                    replaces,
+                    diff,
+                    expires_at,
+                    comments,
+                    quorum,
+                    exceeded_limits,
+                    not_whitelisted,
+                )
+                .into(),
                 None => Message::View(Stage::Dashboard),
             },
         )
@@ -148,6 +252,15 @@ impl State for ProposalState {
                     approvals,
                     signer,
                     pk,
+                    registration_required,
+                    issues,
+                    replaces,
+                    diff,
+                    expires_at,
+                    comments,
+                    quorum,
+                    exceeded_limits,
+                    not_whitelisted,
                 ) => {
                     self.proposal = Some(proposal);
                     self.policy_id = Some(policy_id);
@@ -155,6 +268,15 @@ impl State for ProposalState {
                     self.signed = signed;
                     self.approved_proposals = approvals;
                     self.signer = signer;
+                    self.registration_required = registration_required;
+                    self.issues = issues;
+                    self.replaces = replaces;
+                    self.diff = diff;
+                    self.expires_at = expires_at;
+                    self.comments = comments;
+                    self.quorum = quorum;
+                    self.exceeded_limits = exceeded_limits;
+                    self.not_whitelisted = not_whitelisted;
                     self.loading = false;
                     self.loaded = true;
                 }
@@ -172,10 +294,20 @@ impl State for ProposalState {
                                     ProposalMessage::SetModal(Some(ModalType::Approve)).into()
                                 });
                             }
+                            SignerType::AirGap
+                                if self.modal.is_none() && ctx.client.has_approval_pin() =>
+                            {
+                                return Command::perform(async {}, |_| {
+                                    ProposalMessage::SetModal(Some(ModalType::ApproveWithPsbt))
+                                        .into()
+                                });
+                            }
                             SignerType::Hardware | SignerType::AirGap => {
+                                self.modal = None;
                                 self.loading = true;
                                 let client = ctx.client.clone();
                                 let proposal_id = self.proposal_id;
+                                let pin: Option<String> = self.pin.take();
                                 return Command::perform(
                                     async move {
                                         match signer.signer_type() {
@@ -196,6 +328,7 @@ impl State for ProposalState {
                                                         .approve_with_signed_psbt(
                                                             proposal_id,
                                                             signed_psbt,
+                                                            pin.as_deref(),
                                                         )
                                                         .await?;
                                                 }
@@ -221,14 +354,51 @@ impl State for ProposalState {
                         }
                     };
                 }
+                ProposalMessage::MarkRegistered => {
+                    if let (Some(policy_id), Some(signer)) = (self.policy_id, self.signer.clone()) {
+                        self.loading = true;
+                        let client = ctx.client.clone();
+                        return Command::perform(
+                            async move {
+                                client
+                                    .save_device_registration(
+                                        policy_id,
+                                        signer.fingerprint(),
+                                        signer.name(),
+                                        None,
+                                    )
+                                    .await
+                            },
+                            |res| match res {
+                                Ok(_) => ProposalMessage::Reload.into(),
+                                Err(e) => ProposalMessage::ErrorChanged(Some(e.to_string())).into(),
+                            },
+                        );
+                    }
+                }
                 ProposalMessage::ApproveWithSeed(password) => {
                     self.modal = None;
                     self.password.clear();
                     self.loading = true;
                     let client = ctx.client.clone();
                     let proposal_id = self.proposal_id;
+                    let pin: Option<String> = self.pin.take();
+                    // Exceeding the spending limits requires an explicit override (see modal)
+                    let velocity_override: bool = !self.exceeded_limits.is_empty();
                     return Command::perform(
-                        async move { client.approve(password, proposal_id).await },
+                        async move {
+                            if velocity_override {
+                                client
+                                    .approve_with_velocity_override(
+                                        password,
+                                        pin.as_deref(),
+                                        proposal_id,
+                                    )
+                                    .await
+                            } else {
+                                client.approve(password, pin.as_deref(), proposal_id).await
+                            }
+                        },
                         |res| match res {
                             Ok(_) => ProposalMessage::Reload.into(),
                             Err(e) => ProposalMessage::ErrorChanged(Some(e.to_string())).into(),
@@ -307,10 +477,41 @@ impl State for ProposalState {
                     );
                 }
                 ProposalMessage::SetModal(modal) => {
+                    self.pin = match modal {
+                        Some(ModalType::Approve | ModalType::ApproveWithPsbt)
+                            if ctx.client.has_approval_pin() =>
+                        {
+                            Some(String::new())
+                        }
+                        _ => None,
+                    };
                     self.modal = modal;
                     self.password.clear();
                 }
                 ProposalMessage::PasswordChanged(password) => self.password = password,
+                ProposalMessage::PinChanged(pin) => self.pin = Some(pin),
+                ProposalMessage::CommentChanged(comment) => self.comment = comment,
+                ProposalMessage::SendComment => {
+                    let message: String = self.comment.trim().to_string();
+                    if message.is_empty() {
+                        return Command::none();
+                    }
+                    self.loading = true;
+                    let client = ctx.client.clone();
+                    let proposal_id = self.proposal_id;
+                    return Command::perform(
+                        async move { client.comment_proposal(proposal_id, message).await },
+                        |res| match res {
+                            Ok(_) => ProposalMessage::CommentSent.into(),
+                            Err(e) => ProposalMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    );
+                }
+                ProposalMessage::CommentSent => {
+                    self.comment.clear();
+                    self.loading = false;
+                    return self.load(ctx);
+                }
                 ProposalMessage::Delete => {
                     self.loading = true;
                     let client = ctx.client.clone();
@@ -359,6 +560,7 @@ impl State for ProposalState {
                             amount,
                             description,
                             psbt,
+                            memo,
                             ..
                         } => {
                             left_content = left_content
@@ -401,6 +603,17 @@ impl State for ProposalState {
                                     .push(Text::new(format!("Description: {description}")).view());
                             }
 
+                            if let Some(data) = proposal.op_return_data() {
+                                left_content = left_content.push(
+                                    Text::new(format!("OP_RETURN: {}", format::data(&data))).view(),
+                                );
+                            }
+
+                            if let Some(memo) = memo {
+                                left_content =
+                                    left_content.push(Text::new(format!("Memo: {memo}")).view());
+                            }
+
                             "Broadcast"
                         }
                         Proposal::KeyAgentPayment {
@@ -488,10 +701,12 @@ impl State for ProposalState {
                                 (approve_btn, finalize_btn)
                             }
                             None => {
-                                let approve_btn = Button::new()
-                                    .text("Approve")
-                                    .on_press(ProposalMessage::Approve.into())
-                                    .loading(self.loading);
+                                let mut approve_btn =
+                                    Button::new().text("Approve").loading(self.loading);
+                                if !self.registration_required {
+                                    approve_btn =
+                                        approve_btn.on_press(ProposalMessage::Approve.into());
+                                }
                                 let finalize_btn = Button::new()
                                     .style(ButtonStyle::Bordered)
                                     .text(finalize_btn_text);
@@ -525,6 +740,110 @@ impl State for ProposalState {
                         .loading(self.loading)
                         .view();
 
+                    if !self.issues.is_empty() {
+                        left_content = left_content.push(Space::with_height(10.0)).push(
+                            Text::new("Suspicious proposal: the PSBT doesn't match what the proposal declares")
+                                .color(RED)
+                                .bold()
+                                .view(),
+                        );
+                        for issue in self.issues.iter() {
+                            left_content = left_content
+                                .push(Text::new(format!("- {issue}")).color(RED).view());
+                        }
+                    }
+
+                    if !self.exceeded_limits.is_empty() {
+                        left_content = left_content.push(Space::with_height(10.0)).push(
+                            Text::new("Spending limits exceeded: approving overrides the limits of the vault")
+                                .color(RED)
+                                .bold()
+                                .view(),
+                        );
+                        for status in self.exceeded_limits.iter() {
+                            left_content = left_content.push(
+                                Text::new(format!(
+                                    "- {} ({} sat remaining)",
+                                    status.limit,
+                                    format::number(status.remaining())
+                                ))
+                                .color(RED)
+                                .view(),
+                            );
+                        }
+                    }
+
+                    if !self.not_whitelisted.is_empty() {
+                        left_content = left_content.push(Space::with_height(10.0)).push(
+                            Text::new("Destinations not whitelisted: the approval requires an extra confirmation")
+                                .color(YELLOW)
+                                .bold()
+                                .view(),
+                        );
+                        for address in self.not_whitelisted.iter() {
+                            left_content = left_content.push(
+                                Text::new(format!("- {}", address.clone().assume_checked()))
+                                    .color(YELLOW)
+                                    .view(),
+                            );
+                        }
+                    }
+
+                    if let Some(expires_at) = self.expires_at {
+                        left_content = left_content.push(Space::with_height(10.0)).push(
+                            Text::new(format!(
+                                "Expires on {}: deleted if not finalized by then",
+                                expires_at.to_human_datetime()
+                            ))
+                            .color(YELLOW)
+                            .view(),
+                        );
+                    }
+
+                    if let Some(replaces) = self.replaces {
+                        left_content = left_content.push(Space::with_height(10.0)).push(
+                            Text::new(format!(
+                                "Replaces proposal #{}",
+                                util::cut_event_id(replaces)
+                            ))
+                            .color(YELLOW)
+                            .bold()
+                            .view(),
+                        );
+                        match &self.diff {
+                            Some(diff) => {
+                                for change in diff_lines(diff).into_iter() {
+                                    left_content =
+                                        left_content.push(Text::new(format!("- {change}")).view());
+                                }
+                            }
+                            None => {
+                                left_content = left_content.push(
+                                    Text::new("The replaced proposal is not available: review the proposal from scratch")
+                                        .view(),
+                                );
+                            }
+                        }
+                    }
+
+                    if self.registration_required {
+                        left_content = left_content
+                            .push(Space::with_height(10.0))
+                            .push(
+                                Text::new("The vault is not registered on this signer: register it on the device before approving")
+                                    .color(YELLOW)
+                                    .view(),
+                            )
+                            .push(
+                                Button::new()
+                                    .style(ButtonStyle::Bordered)
+                                    .text("I've registered the vault on the device")
+                                    .on_press(ProposalMessage::MarkRegistered.into())
+                                    .loading(self.loading)
+                                    .view(),
+                            );
+                    }
+
                     left_content = left_content
                         .push(Space::with_height(10.0))
                         .push(
@@ -542,6 +861,10 @@ impl State for ProposalState {
                         left_content = left_content.push(Text::new(error).color(RED).view());
                     };
 
+                    if let Some(quorum) = &self.quorum {
+                        left_content = left_content.push(self.view_quorum(quorum));
+                    }
+
                     if !self.approved_proposals.is_empty() {
                         left_content = left_content
                             .push(Text::new("Approvals").bold().big().view())
@@ -615,9 +938,11 @@ impl State for ProposalState {
                         }
                     }
 
-                    content = content.push(
-                        Row::new().spacing(20).push(left_content), //.push(self.view_chat()),
-                    );
+                    left_content = left_content
+                        .push(Space::with_height(20.0))
+                        .push(self.view_comments());
+
+                    content = content.push(Row::new().spacing(20).push(left_content));
                 }
             }
         };
@@ -633,7 +958,14 @@ impl State for ProposalState {
                 match modal {
                     ModalType::Approve => Card::new(
                         Text::new("Approve proposal").view(),
-                        Text::new("Do you really want approve this proposal?").view(),
+                        Text::new(if !self.exceeded_limits.is_empty() {
+                            "The proposal exceeds the spending limits of the vault: do you really want to override them and approve it?"
+                        } else if !self.not_whitelisted.is_empty() {
+                            "The proposal pays destinations not in the whitelist of the vault: do you really want to approve it?"
+                        } else {
+                            "Do you really want approve this proposal?"
+                        })
+                        .view(),
                     )
                     .foot(
                         Column::new()
@@ -647,6 +979,15 @@ impl State for ProposalState {
                                     .on_input(|p| ProposalMessage::PasswordChanged(p).into())
                                     .view(),
                             )
+                            .push(if let Some(pin) = &self.pin {
+                                TextInput::with_label("Approval PIN", pin)
+                                    .password()
+                                    .placeholder("Approval PIN")
+                                    .on_input(|p| ProposalMessage::PinChanged(p).into())
+                                    .view()
+                            } else {
+                                Column::new()
+                            })
                             .push(
                                 Row::new()
                                     .spacing(10)
@@ -674,6 +1015,47 @@ impl State for ProposalState {
                                     ),
                             ),
                     ),
+                    ModalType::ApproveWithPsbt => Card::new(
+                        Text::new("Approve proposal").view(),
+                        Text::new("Enter the approval PIN, then select the signed PSBT").view(),
+                    )
+                    .foot(
+                        Column::new()
+                            .width(Length::Fill)
+                            .spacing(10)
+                            .padding(5)
+                            .push(
+                                TextInput::with_label(
+                                    "Approval PIN",
+                                    self.pin.as_deref().unwrap_or_default(),
+                                )
+                                .password()
+                                .placeholder("Approval PIN")
+                                .on_input(|p| ProposalMessage::PinChanged(p).into())
+                                .view(),
+                            )
+                            .push(
+                                Row::new()
+                                    .spacing(10)
+                                    .width(Length::Fill)
+                                    .push(
+                                        Button::new()
+                                            .text("Approve")
+                                            .width(Length::Fill)
+                                            .on_press(ProposalMessage::Approve.into())
+                                            .loading(self.loading)
+                                            .view(),
+                                    )
+                                    .push(
+                                        Button::new()
+                                            .style(ButtonStyle::Bordered)
+                                            .text("Close")
+                                            .width(Length::Fill)
+                                            .on_press(ProposalMessage::SetModal(None).into())
+                                            .view(),
+                                    ),
+                            ),
+                    ),
                     ModalType::Delete => Card::new(
                         Text::new("Delete proposal").view(),
                         Text::new("Do you want really delete this proposal?").view(),
@@ -713,35 +1095,153 @@ impl State for ProposalState {
     }
 }
 
-// impl ProposalState {
-// fn view_chat<'a>(&self) -> Container<'a, Message> {
-// let chat = Column::new().spacing(10);
-// let content = Column::new()
-// .push(Text::new("Chat").bold().bigger().view())
-// .push(rule::horizontal())
-// .push(Scrollable::new(chat).height(Length::Fill))
-// .push(rule::horizontal())
-// .push(
-// Row::new()
-// .spacing(10)
-// .push(
-// TextInput::new("")
-// .placeholder("Message")
-// .view()
-// .width(Length::Fill),
-// )
-// .push(Button::new().text("Send").width(Length::Fixed(80.0)).view())
-// .width(Length::Fill),
-// )
-// .max_width(450.0)
-// .spacing(10)
-// .padding(15)
-// .height(Length::Shrink);
-// Container::new(content)
-// .style(CardStyle::Primary)
-// .height(Length::Shrink)
-// }
-// }
+impl ProposalState {
+    fn view_quorum(&self, quorum: &GetQuorumStatus) -> Column<'static, Message> {
+        let mut content = Column::new()
+            .spacing(10)
+            .push(Text::new("Quorum").bold().big().view())
+            .push(rule::horizontal_bold());
+
+        for path in quorum.paths.iter() {
+            let threshold: String = path
+                .threshold
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| String::from("-"));
+            let mut status: String = format!("{} of {threshold}", path.signed.len());
+            if !path.is_satisfied() {
+                let waiting_on: Vec<String> = path
+                    .missing()
+                    .iter()
+                    .map(|fingerprint| match quorum.owners.get(fingerprint) {
+                        Some(owner) => owner.name(),
+                        None => fingerprint.to_string(),
+                    })
+                    .collect();
+                status.push_str(&format!(" - waiting on {}", waiting_on.join(", ")));
+            }
+            if path.timelock {
+                status.push_str(" (timelocked)");
+            }
+
+            content = content.push(
+                Row::new()
+                    .push(
+                        Text::new(format!("Path {}", path.path))
+                            .width(Length::Fixed(115.0))
+                            .view(),
+                    )
+                    .push(
+                        Text::new(status)
+                            .color(if path.is_satisfied() { GREEN } else { YELLOW })
+                            .width(Length::Fill)
+                            .view(),
+                    )
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            );
+        }
+
+        content.push(Space::with_height(20.0))
+    }
+
+    fn view_comments(&self) -> Column<'static, Message> {
+        let mut comments = Column::new()
+            .spacing(10)
+            .push(Text::new("Comments").bold().big().view())
+            .push(rule::horizontal_bold());
+
+        if self.comments.is_empty() {
+            comments = comments.push(Text::new("No comments").extra_light().view());
+        }
+
+        for GetProposalComment {
+            user,
+            message,
+            timestamp,
+            ..
+        } in self.comments.iter()
+        {
+            comments = comments
+                .push(
+                    Row::new()
+                        .push(Text::new(user.name()).bold().view())
+                        .push(
+                            Text::new(timestamp.to_human_datetime())
+                                .extra_light()
+                                .small()
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center),
+                )
+                .push(Text::new(message).view())
+                .push(rule::horizontal());
+        }
+
+        comments.push(
+            Row::new()
+                .push(
+                    TextInput::new(&self.comment)
+                        .placeholder("Write a comment")
+                        .on_input(|c| ProposalMessage::CommentChanged(c).into())
+                        .on_submit(ProposalMessage::SendComment.into())
+                        .view()
+                        .width(Length::Fill),
+                )
+                .push(
+                    Button::new()
+                        .text("Send")
+                        .width(Length::Fixed(80.0))
+                        .on_press(ProposalMessage::SendComment.into())
+                        .loading(self.loading)
+                        .view(),
+                )
+                .spacing(10)
+                .align_items(Alignment::End)
+                .width(Length::Fill),
+        )
+    }
+}
+
+/// Human readable changes from the replaced proposal
+fn diff_lines(diff: &ProposalDiff) -> Vec<String> {
+    if diff.is_empty() {
+        return vec![String::from("No changes")];
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    if diff.amount != 0 {
+        lines.push(format!("Amount: {:+} sat", diff.amount));
+    }
+    if let Some(fee) = diff.fee.filter(|fee| *fee != 0) {
+        lines.push(format!("Fee: {fee:+} sat"));
+    }
+    for recipient in diff.removed_recipients.iter() {
+        lines.push(format!(
+            "Removed recipient: {} ({} sat)",
+            recipient.address.clone().assume_checked(),
+            util::format::number(recipient.amount)
+        ));
+    }
+    for recipient in diff.added_recipients.iter() {
+        lines.push(format!(
+            "New recipient: {} ({} sat)",
+            recipient.address.clone().assume_checked(),
+            util::format::number(recipient.amount)
+        ));
+    }
+    if diff.inputs_changed() {
+        lines.push(format!(
+            "Inputs: {} added, {} removed",
+            diff.added_inputs.len(),
+            diff.removed_inputs.len()
+        ));
+    }
+    if let Some(description) = &diff.description {
+        lines.push(format!("Description: {description}"));
+    }
+    lines
+}
 
 impl From<ProposalState> for Box<dyn State> {
     fn from(s: ProposalState) -> Box<dyn State> {
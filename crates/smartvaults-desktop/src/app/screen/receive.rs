@@ -7,11 +7,16 @@ use iced::{Alignment, Command, Element, Length};
 use smartvaults_sdk::nostr::EventId;
 use smartvaults_sdk::protocol::v1::Label;
 use smartvaults_sdk::types::{GetAddress, GetPolicy};
+use smartvaults_sdk::util::{self, format};
 
 use crate::app::component::{Dashboard, PolicyPickList};
-use crate::app::{Context, Message, State};
-use crate::component::{Button, ButtonStyle, Text, TextInput};
-use crate::theme::icon::CLIPBOARD;
+use crate::app::{print, Context, Message, State};
+use crate::component::{Button, ButtonStyle, NumericInput, Text, TextInput};
+use crate::theme::color::GREEN;
+use crate::theme::icon::{CLIPBOARD, PRINTER};
+
+/// Unused addresses printed in the receive-address sheet
+const ADDRESS_SHEET_SIZE: usize = 10;
 
 #[derive(Debug, Clone)]
 pub enum ReceiveMessage {
@@ -20,7 +25,12 @@ pub enum ReceiveMessage {
     PolicySelectd(PolicyPickList),
     AddressChanged(GetAddress),
     LabelChanged(String),
+    AmountChanged(Option<u64>),
     SaveLabel(Label),
+    CopyUri,
+    PrintAddressSheet,
+    PaymentReceived(u64),
+    NewRequest,
     ErrorChanged(Option<String>),
     Reload,
 }
@@ -32,6 +42,8 @@ pub struct ReceiveState {
     qr_code: Option<qr_code::State>,
     address: Option<GetAddress>,
     label: String,
+    amount: Option<u64>,
+    received: Option<u64>,
     loading: bool,
     loaded: bool,
     allow_reload: bool,
@@ -46,12 +58,49 @@ impl ReceiveState {
             qr_code: None,
             address: None,
             label: String::new(),
+            amount: None,
+            received: None,
             loading: false,
             loaded: false,
             allow_reload: false,
             error: None,
         }
     }
+
+    /// BIP21 URI of the current request
+    fn uri(&self) -> Option<String> {
+        let address = self.address.as_ref()?.address.clone().assume_checked();
+        Some(util::bip21_uri(&address, self.amount, Some(&self.label)))
+    }
+
+    fn update_qr_code(&mut self) {
+        self.qr_code = self.uri().and_then(|uri| qr_code::State::new(uri).ok());
+    }
+
+    /// Check if the requested address received a payment
+    fn watch_payment(&self, ctx: &Context) -> Command<Message> {
+        if let (Some(policy), Some(address), None) =
+            (self.policy.as_ref(), self.address.as_ref(), self.received)
+        {
+            let client = ctx.client.clone();
+            let policy_id = policy.policy_id;
+            let script = address.address.clone().assume_checked().script_pubkey();
+            return Command::perform(
+                async move {
+                    let balances = client.get_addresses_balances(policy_id).await?;
+                    Ok::<u64, smartvaults_sdk::Error>(
+                        balances.get(&script).copied().unwrap_or_default(),
+                    )
+                },
+                |res| match res {
+                    Ok(amount) if amount > 0 => ReceiveMessage::PaymentReceived(amount).into(),
+                    Ok(_) => Message::Tick,
+                    Err(e) => ReceiveMessage::ErrorChanged(Some(e.to_string())).into(),
+                },
+            );
+        }
+        Command::none()
+    }
 }
 
 impl State for ReceiveState {
@@ -61,7 +110,7 @@ impl State for ReceiveState {
 
     fn load(&mut self, ctx: &Context) -> Command<Message> {
         if self.loaded && !self.allow_reload {
-            return Command::none();
+            return self.watch_payment(ctx);
         }
 
         self.loading = true;
@@ -113,15 +162,20 @@ impl State for ReceiveState {
                     });
                 }
                 ReceiveMessage::AddressChanged(value) => {
+                    self.loading = false;
                     self.label = value.label.clone().unwrap_or_default();
                     self.address = Some(value);
-                    if let Some(address) = self.address.clone() {
-                        let address = address.address.clone();
-                        self.qr_code =
-                            qr_code::State::new(address.assume_checked().to_qr_uri()).ok();
-                    }
+                    self.update_qr_code();
+                    return self.watch_payment(ctx);
+                }
+                ReceiveMessage::LabelChanged(label) => {
+                    self.label = label;
+                    self.update_qr_code();
+                }
+                ReceiveMessage::AmountChanged(amount) => {
+                    self.amount = amount;
+                    self.update_qr_code();
                 }
-                ReceiveMessage::LabelChanged(label) => self.label = label,
                 ReceiveMessage::SaveLabel(label) => {
                     let client = ctx.client.clone();
                     if let Some(policy) = self.policy.as_ref() {
@@ -138,6 +192,69 @@ impl State for ReceiveState {
                         self.error = Some(String::from("Policy not selected"));
                     }
                 }
+                ReceiveMessage::CopyUri => {
+                    if let (Some(address), Some(uri)) = (self.address.as_ref(), self.uri()) {
+                        let copy = Command::perform(async {}, move |_| Message::Clipboard(uri));
+
+                        // Auto-label the address with the label of the request
+                        let saved: String = address.label.clone().unwrap_or_default();
+                        if !self.label.is_empty() && self.label != saved {
+                            let label = Label::address(address.address.clone(), self.label.clone());
+                            return Command::batch([
+                                copy,
+                                Command::perform(async {}, move |_| {
+                                    ReceiveMessage::SaveLabel(label).into()
+                                }),
+                            ]);
+                        }
+
+                        return copy;
+                    }
+                }
+                ReceiveMessage::PrintAddressSheet => {
+                    if let Some(policy) = self.policy.as_ref() {
+                        let client = ctx.client.clone();
+                        let policy_id = policy.policy_id;
+                        return Command::perform(
+                            async move {
+                                let policy = client.get_policy_by_id(policy_id).await?;
+                                let last_unused = client.get_last_unused_address(policy_id).await?;
+                                let addresses: Vec<GetAddress> = client
+                                    .get_addresses(policy_id)
+                                    .await?
+                                    .into_iter()
+                                    .skip_while(|a| a.address != last_unused.address)
+                                    .take(ADDRESS_SHEET_SIZE)
+                                    .collect();
+                                print::open(
+                                    &format!("addresses-{}", util::cut_event_id(policy_id)),
+                                    &policy.policy.name(),
+                                    print::address_sheet(&policy, &addresses),
+                                )?;
+                                Ok::<(), Box<dyn std::error::Error>>(())
+                            },
+                            |res| match res {
+                                Ok(_) => ReceiveMessage::ErrorChanged(None).into(),
+                                Err(e) => ReceiveMessage::ErrorChanged(Some(e.to_string())).into(),
+                            },
+                        );
+                    }
+                }
+                ReceiveMessage::PaymentReceived(amount) => self.received = Some(amount),
+                ReceiveMessage::NewRequest => {
+                    self.received = None;
+                    self.amount = None;
+                    self.label.clear();
+                    self.address = None;
+                    self.qr_code = None;
+                    if let Some(policy) = self.policy.as_ref() {
+                        self.loading = true;
+                        let policy_id = policy.policy_id;
+                        return Command::perform(async {}, move |_| {
+                            ReceiveMessage::LoadAddress(policy_id).into()
+                        });
+                    }
+                }
                 ReceiveMessage::ErrorChanged(error) => {
                     self.loading = false;
                     self.error = error;
@@ -161,9 +278,11 @@ impl State for ReceiveState {
                     Column::new()
                         .push(Text::new("Receive").big().bold().view())
                         .push(
-                            Text::new("Use the address below to fund your wallet.")
-                                .extra_light()
-                                .view(),
+                            Text::new(
+                                "Use the address or the payment request below to fund your wallet.",
+                            )
+                            .extra_light()
+                            .view(),
                         )
                         .spacing(10)
                         .width(Length::Fill),
@@ -220,8 +339,36 @@ impl State for ReceiveState {
                             )
                             .view(),
                     )
+                    .push(
+                        NumericInput::new("Amount (sat)", self.amount)
+                            .on_input(|a| ReceiveMessage::AmountChanged(a).into())
+                            .placeholder("Amount (optional)"),
+                    )
                     .push(Space::with_height(Length::Fixed(20.0)));
 
+                if let Some(received) = self.received {
+                    content = content
+                        .push(
+                            Text::new(format!(
+                                "Payment received: {} sat",
+                                format::number(received)
+                            ))
+                            .bold()
+                            .big()
+                            .color(GREEN)
+                            .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .text("New request")
+                                .width(Length::Fill)
+                                .on_press(ReceiveMessage::NewRequest.into())
+                                .loading(self.loading)
+                                .view(),
+                        )
+                        .push(Space::with_height(Length::Fixed(10.0)));
+                }
+
                 if let Some(qr_code) = self.qr_code.as_ref() {
                     content = content
                         .push(QRCode::new(qr_code).cell_size(5))
@@ -245,10 +392,28 @@ impl State for ReceiveState {
                         Button::new()
                             .style(ButtonStyle::Bordered)
                             .icon(CLIPBOARD)
-                            .text("Copy")
+                            .text("Copy address")
                             .width(Length::Fill)
                             .on_press(Message::Clipboard(address.to_string()))
                             .view(),
+                    )
+                    .push(
+                        Button::new()
+                            .icon(CLIPBOARD)
+                            .text("Copy payment request")
+                            .width(Length::Fill)
+                            .on_press(ReceiveMessage::CopyUri.into())
+                            .view(),
+                    )
+                    .push(
+                        Button::new()
+                            .style(ButtonStyle::Bordered)
+                            .icon(PRINTER)
+                            .text("Print address sheet")
+                            .width(Length::Fill)
+                            .on_press(ReceiveMessage::PrintAddressSheet.into())
+                            .loading(self.loading)
+                            .view(),
                     );
             }
 
@@ -384,6 +384,7 @@ impl State for SelfTransferState {
                                     SelfTransferMessage::FeeRateChanged(f).into()
                                 })
                                 .current_mempool_fees(ctx.current_fees.clone())
+                                .mempool_snapshot(ctx.mempool_snapshot.clone())
                                 .max_width(400.0),
                             )
                             .spacing(25)
@@ -0,0 +1,178 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use iced::widget::{Column, Row};
+use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::pin::{MAX_PIN_LEN, MIN_PIN_LEN};
+
+use crate::app::component::Dashboard;
+use crate::app::{Context, Message, Stage, State};
+use crate::component::{Button, ButtonStyle, Text, TextInput};
+use crate::theme::color::DARK_RED;
+
+#[derive(Debug, Clone)]
+pub enum ApprovalPinMessage {
+    PasswordChanged(String),
+    PinChanged(String),
+    ConfirmPinChanged(String),
+    ErrorChanged(Option<String>),
+    Save,
+    Remove,
+}
+
+#[derive(Debug, Default)]
+pub struct ApprovalPinState {
+    password: String,
+    pin: String,
+    confirm_pin: String,
+    is_set: bool,
+    loading: bool,
+    error: Option<String>,
+}
+
+impl ApprovalPinState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl State for ApprovalPinState {
+    fn title(&self) -> String {
+        String::from("Approval PIN")
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.is_set = ctx.client.has_approval_pin();
+        Command::none()
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if let Message::ApprovalPin(msg) = message {
+            match msg {
+                ApprovalPinMessage::PasswordChanged(password) => self.password = password,
+                ApprovalPinMessage::PinChanged(pin) => self.pin = pin,
+                ApprovalPinMessage::ConfirmPinChanged(confirm_pin) => {
+                    self.confirm_pin = confirm_pin
+                }
+                ApprovalPinMessage::ErrorChanged(e) => {
+                    self.loading = false;
+                    self.error = e;
+                }
+                ApprovalPinMessage::Save => {
+                    if self.pin != self.confirm_pin {
+                        self.error = Some(String::from("PINs not match"));
+                        return Command::none();
+                    }
+
+                    let client = ctx.client.clone();
+                    let password = self.password.clone();
+                    let pin = self.pin.clone();
+                    self.loading = true;
+                    return Command::perform(
+                        async move { client.set_approval_pin(password, pin) },
+                        |res| match res {
+                            Ok(_) => Message::View(Stage::Settings),
+                            Err(e) => ApprovalPinMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    );
+                }
+                ApprovalPinMessage::Remove => {
+                    let client = ctx.client.clone();
+                    let password = self.password.clone();
+                    self.loading = true;
+                    return Command::perform(
+                        async move { client.remove_approval_pin(password) },
+                        |res| match res {
+                            Ok(_) => Message::View(Stage::Settings),
+                            Err(e) => ApprovalPinMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    );
+                }
+            }
+        };
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let password = TextInput::with_label("Password", &self.password)
+            .on_input(|s| ApprovalPinMessage::PasswordChanged(s).into())
+            .placeholder("Password")
+            .password()
+            .on_submit(ApprovalPinMessage::Save.into())
+            .view();
+
+        let pin = TextInput::with_label("New PIN", &self.pin)
+            .on_input(|s| ApprovalPinMessage::PinChanged(s).into())
+            .placeholder(format!("{MIN_PIN_LEN} to {MAX_PIN_LEN} digits"))
+            .password()
+            .on_submit(ApprovalPinMessage::Save.into())
+            .view();
+
+        let confirm_pin = TextInput::with_label("Confirm PIN", &self.confirm_pin)
+            .on_input(|s| ApprovalPinMessage::ConfirmPinChanged(s).into())
+            .placeholder("Confirm PIN")
+            .password()
+            .on_submit(ApprovalPinMessage::Save.into())
+            .view();
+
+        let save_btn = Button::new()
+            .text("Save")
+            .on_press(ApprovalPinMessage::Save.into())
+            .loading(self.loading)
+            .width(Length::Fill);
+
+        let content = Column::new()
+            .push(
+                Column::new()
+                    .push(Text::new("Approval PIN").big().bold().view())
+                    .push(
+                        Text::new("Required to approve proposals on this device")
+                            .extra_light()
+                            .view(),
+                    )
+                    .spacing(10)
+                    .width(Length::Fill),
+            )
+            .push(password)
+            .push(pin)
+            .push(confirm_pin)
+            .push(if let Some(error) = &self.error {
+                Row::new().push(Text::new(error).color(DARK_RED).view())
+            } else {
+                Row::new()
+            })
+            .push(save_btn.view())
+            .push(if self.is_set {
+                Row::new().push(
+                    Button::new()
+                        .style(ButtonStyle::BorderedDanger)
+                        .text("Remove PIN")
+                        .on_press(ApprovalPinMessage::Remove.into())
+                        .loading(self.loading)
+                        .width(Length::Fill)
+                        .view(),
+                )
+            } else {
+                Row::new()
+            })
+            .align_items(Alignment::Center)
+            .spacing(10)
+            .padding(20)
+            .max_width(400);
+
+        Dashboard::new().view(ctx, content, true, true)
+    }
+}
+
+impl From<ApprovalPinState> for Box<dyn State> {
+    fn from(s: ApprovalPinState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<ApprovalPinMessage> for Message {
+    fn from(msg: ApprovalPinMessage) -> Self {
+        Self::ApprovalPin(msg)
+    }
+}
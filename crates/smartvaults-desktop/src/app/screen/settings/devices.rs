@@ -0,0 +1,186 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use iced::alignment::Horizontal;
+use iced::widget::{Column, Row};
+use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::types::GetDevice;
+
+use crate::app::component::Dashboard;
+use crate::app::{Context, Message, State};
+use crate::component::{rule, Button, ButtonStyle, Text};
+use crate::theme::color::DARK_RED;
+use crate::theme::icon::{RELOAD, TRASH};
+
+#[derive(Debug, Clone)]
+pub enum DevicesMessage {
+    LoadDevices(Vec<GetDevice>),
+    RevokeDevice(String),
+    ErrorChanged(Option<String>),
+    Reload,
+}
+
+#[derive(Debug, Default)]
+pub struct DevicesState {
+    loading: bool,
+    loaded: bool,
+    devices: Vec<GetDevice>,
+    error: Option<String>,
+}
+
+impl DevicesState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl State for DevicesState {
+    fn title(&self) -> String {
+        String::from("Devices")
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.loading = true;
+        let client = ctx.client.clone();
+        Command::perform(async move { client.get_devices().await }, |res| match res {
+            Ok(devices) => DevicesMessage::LoadDevices(devices).into(),
+            Err(e) => DevicesMessage::ErrorChanged(Some(e.to_string())).into(),
+        })
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if !self.loaded && !self.loading {
+            return self.load(ctx);
+        }
+
+        if let Message::Devices(msg) = message {
+            match msg {
+                DevicesMessage::LoadDevices(devices) => {
+                    self.devices = devices;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                DevicesMessage::RevokeDevice(device_id) => {
+                    self.loading = true;
+                    let client = ctx.client.clone();
+                    return Command::perform(
+                        async move { client.revoke_device(device_id).await },
+                        |res| match res {
+                            Ok(_) => DevicesMessage::Reload.into(),
+                            Err(e) => DevicesMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    );
+                }
+                DevicesMessage::ErrorChanged(e) => {
+                    self.error = e;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                DevicesMessage::Reload => return self.load(ctx),
+            }
+        }
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let mut content = Column::new().spacing(10).padding(20);
+
+        if self.loaded {
+            content = content
+                .push(
+                    Row::new()
+                        .push(Text::new("Device").bold().big().width(Length::Fill).view())
+                        .push(
+                            Text::new("Platform")
+                                .bold()
+                                .big()
+                                .width(Length::Fixed(200.0))
+                                .view(),
+                        )
+                        .push(
+                            Text::new("Last seen")
+                                .bold()
+                                .big()
+                                .horizontal_alignment(Horizontal::Center)
+                                .width(Length::Fixed(200.0))
+                                .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .icon(RELOAD)
+                                .style(ButtonStyle::Bordered)
+                                .on_press(DevicesMessage::Reload.into())
+                                .loading(self.loading)
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal_bold());
+
+            for device in self.devices.iter() {
+                let mut name: String = device.name.clone().unwrap_or_else(|| {
+                    format!("#{}", device.device_id.chars().take(8).collect::<String>())
+                });
+                if device.current {
+                    name.push_str(" (this device)");
+                }
+                if device.revoked {
+                    name.push_str(" (revoked)");
+                }
+
+                let mut revoke_btn = Button::new()
+                    .icon(TRASH)
+                    .loading(self.loading)
+                    .style(ButtonStyle::BorderedDanger)
+                    .width(Length::Fixed(40.0));
+                if !device.current && !device.revoked {
+                    revoke_btn = revoke_btn
+                        .on_press(DevicesMessage::RevokeDevice(device.device_id.clone()).into());
+                }
+
+                let row = Row::new()
+                    .push(Text::new(name).width(Length::Fill).view())
+                    .push(
+                        Text::new(&device.platform)
+                            .width(Length::Fixed(200.0))
+                            .view(),
+                    )
+                    .push(
+                        Text::new(device.last_seen.to_human_datetime())
+                            .horizontal_alignment(Horizontal::Center)
+                            .width(Length::Fixed(200.0))
+                            .view(),
+                    )
+                    .push(revoke_btn.view())
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .width(Length::Fill);
+                content = content.push(row).push(rule::horizontal());
+            }
+
+            if let Some(error) = &self.error {
+                content = content.push(Text::new(error).color(DARK_RED).view());
+            }
+        }
+
+        Dashboard::new()
+            .loaded(self.loaded)
+            .view(ctx, content, true, false)
+    }
+}
+
+impl From<DevicesState> for Box<dyn State> {
+    fn from(s: DevicesState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<DevicesMessage> for Message {
+    fn from(msg: DevicesMessage) -> Self {
+        Self::Devices(msg)
+    }
+}
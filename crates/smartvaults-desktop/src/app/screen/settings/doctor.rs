@@ -0,0 +1,276 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::time::Duration;
+
+use iced::widget::{Column, Row};
+use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::types::DiagnosticsReport;
+
+use crate::app::component::Dashboard;
+use crate::app::{Context, Message, State};
+use crate::component::{rule, Button, ButtonStyle, Text};
+use crate::theme::color::{DARK_RED, GREEN, RED};
+use crate::theme::icon::RELOAD;
+
+fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!("{} ms", duration.as_millis()),
+        None => String::from("-"),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum DoctorMessage {
+    LoadReport(DiagnosticsReport),
+    ErrorChanged(Option<String>),
+    Reload,
+}
+
+#[derive(Debug, Default)]
+pub struct DoctorState {
+    loading: bool,
+    loaded: bool,
+    report: Option<DiagnosticsReport>,
+    error: Option<String>,
+}
+
+impl DoctorState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl State for DoctorState {
+    fn title(&self) -> String {
+        String::from("Connection doctor")
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.loading = true;
+        let client = ctx.client.clone();
+        Command::perform(
+            async move { client.run_diagnostics().await },
+            |res| match res {
+                Ok(report) => DoctorMessage::LoadReport(report).into(),
+                Err(e) => DoctorMessage::ErrorChanged(Some(e.to_string())).into(),
+            },
+        )
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if !self.loaded && !self.loading {
+            return self.load(ctx);
+        }
+
+        if let Message::Doctor(msg) = message {
+            match msg {
+                DoctorMessage::LoadReport(report) => {
+                    self.report = Some(report);
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                DoctorMessage::ErrorChanged(e) => {
+                    self.error = e;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                DoctorMessage::Reload => {
+                    self.error = None;
+                    return self.load(ctx);
+                }
+            }
+        }
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let mut content = Column::new().spacing(10).padding(20);
+
+        if self.loaded {
+            let summary = match &self.report {
+                Some(report) if report.is_ok() => Text::new("All the checks passed").color(GREEN),
+                Some(..) => Text::new("Some checks failed").color(RED),
+                None => Text::new("Diagnostics not available"),
+            };
+            content = content
+                .push(
+                    Row::new()
+                        .push(summary.bold().big().width(Length::Fill).view())
+                        .push(
+                            Button::new()
+                                .icon(RELOAD)
+                                .style(ButtonStyle::Bordered)
+                                .on_press(DoctorMessage::Reload.into())
+                                .loading(self.loading)
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal_bold());
+
+            if let Some(report) = &self.report {
+                content = content
+                    .push(
+                        Row::new()
+                            .push(Text::new("Relay").bold().width(Length::Fill).view())
+                            .push(
+                                Text::new("Connected")
+                                    .bold()
+                                    .width(Length::Fixed(100.0))
+                                    .view(),
+                            )
+                            .push(Text::new("Auth").bold().width(Length::Fixed(80.0)).view())
+                            .push(
+                                Text::new("Round-trip")
+                                    .bold()
+                                    .width(Length::Fixed(120.0))
+                                    .view(),
+                            )
+                            .spacing(10)
+                            .align_items(Alignment::Center)
+                            .width(Length::Fill),
+                    )
+                    .push(rule::horizontal());
+
+                if report.relays.is_empty() {
+                    content = content.push(Text::new("No relays").view());
+                }
+
+                for relay in report.relays.iter() {
+                    let auth: &str = match relay.auth_required {
+                        Some(true) => "Required",
+                        Some(false) => "No",
+                        None => "-",
+                    };
+                    let row = Row::new()
+                        .push(
+                            Text::new(relay.url.to_string())
+                                .color(if relay.is_ok() { GREEN } else { RED })
+                                .width(Length::Fill)
+                                .view(),
+                        )
+                        .push(
+                            Text::new(if relay.connected { "Yes" } else { "No" })
+                                .width(Length::Fixed(100.0))
+                                .view(),
+                        )
+                        .push(Text::new(auth).width(Length::Fixed(80.0)).view())
+                        .push(
+                            Text::new(format_duration(relay.round_trip))
+                                .width(Length::Fixed(120.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill);
+                    content = content.push(row);
+                    if let Some(error) = &relay.error {
+                        content = content.push(Text::new(error).color(RED).small().view());
+                    }
+                    content = content.push(rule::horizontal());
+                }
+
+                content = content
+                    .push(Row::new().height(Length::Fixed(20.0)))
+                    .push(
+                        Row::new()
+                            .push(Text::new("Backend").bold().width(Length::Fill).view())
+                            .push(
+                                Text::new("Version")
+                                    .bold()
+                                    .width(Length::Fixed(180.0))
+                                    .view(),
+                            )
+                            .push(
+                                Text::new("Height")
+                                    .bold()
+                                    .width(Length::Fixed(100.0))
+                                    .view(),
+                            )
+                            .push(
+                                Text::new("Latency")
+                                    .bold()
+                                    .width(Length::Fixed(120.0))
+                                    .view(),
+                            )
+                            .spacing(10)
+                            .align_items(Alignment::Center)
+                            .width(Length::Fill),
+                    )
+                    .push(rule::horizontal());
+
+                for backend in report.backends.iter() {
+                    let row = Row::new()
+                        .push(
+                            Text::new(&backend.backend)
+                                .color(if backend.is_ok() { GREEN } else { RED })
+                                .width(Length::Fill)
+                                .view(),
+                        )
+                        .push(
+                            Text::new(backend.version.as_deref().unwrap_or("-"))
+                                .width(Length::Fixed(180.0))
+                                .view(),
+                        )
+                        .push(
+                            Text::new(
+                                backend
+                                    .block_height
+                                    .map(|h| h.to_string())
+                                    .unwrap_or_else(|| String::from("-")),
+                            )
+                            .width(Length::Fixed(100.0))
+                            .view(),
+                        )
+                        .push(
+                            Text::new(format_duration(backend.latency))
+                                .width(Length::Fixed(120.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill);
+                    content = content.push(row);
+                    if let Some(error) = &backend.error {
+                        content = content.push(Text::new(error).color(RED).small().view());
+                    }
+                    content = content.push(rule::horizontal());
+                }
+
+                content = content.push(
+                    Text::new(format!(
+                        "Last check: {}",
+                        report.timestamp.to_human_datetime()
+                    ))
+                    .extra_light()
+                    .view(),
+                );
+            }
+
+            if let Some(error) = &self.error {
+                content = content.push(Text::new(error).color(DARK_RED).view());
+            }
+        }
+
+        Dashboard::new()
+            .loaded(self.loaded)
+            .view(ctx, content, true, false)
+    }
+}
+
+impl From<DoctorState> for Box<dyn State> {
+    fn from(s: DoctorState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<DoctorMessage> for Message {
+    fn from(msg: DoctorMessage) -> Self {
+        Self::Doctor(msg)
+    }
+}
@@ -3,18 +3,27 @@
 
 use iced::widget::{Column, Row};
 use iced::{Command, Element, Length};
+use rfd::FileDialog;
+use smartvaults_sdk::types::EventsImport;
 
 use crate::app::component::Dashboard;
 use crate::app::{Context, Message, Stage, State};
 use crate::component::{Button, ButtonStyle, Card, Modal, Text};
-use crate::theme::icon::{BROADCAST_PIN, KEY, NETWORK, SETTING, TRASH};
+use crate::theme::color::DARK_RED;
+use crate::theme::icon::{
+    ARROW_DOWN, ARROW_UP, BROADCAST_PIN, KEY, LINK, NETWORK, SETTING, TOOLS, TRASH,
+};
 
 pub mod add_relay;
+pub mod approval_pin;
 pub mod change_password;
 pub mod config;
+pub mod devices;
+pub mod doctor;
 pub mod recovery_keys;
 pub mod relay;
 pub mod relays;
+pub mod update;
 pub mod wipe_keys;
 
 #[derive(Debug, Clone)]
@@ -24,11 +33,15 @@ pub enum SettingsMessage {
     CloseModal,
     ClearCache,
     ForceFullTimechainSync,
+    ImportEvents,
+    EventsImported(Result<EventsImport, String>),
 }
 
 #[derive(Debug, Default)]
 pub struct SettingsState {
     show_modal: bool,
+    importing: bool,
+    import_result: Option<Result<EventsImport, String>>,
 }
 
 impl SettingsState {
@@ -68,6 +81,28 @@ impl State for SettingsState {
                         move |_| Message::View(Stage::Dashboard),
                     );
                 }
+                SettingsMessage::ImportEvents => {
+                    let path = FileDialog::new()
+                        .set_title("Import events")
+                        .add_filter("JSON", &["json", "jsonl"])
+                        .pick_file();
+
+                    if let Some(path) = path {
+                        self.importing = true;
+                        let client = ctx.client.clone();
+                        return Command::perform(
+                            async move { client.import_events_from_file(path).await },
+                            |res| {
+                                SettingsMessage::EventsImported(res.map_err(|e| e.to_string()))
+                                    .into()
+                            },
+                        );
+                    }
+                }
+                SettingsMessage::EventsImported(res) => {
+                    self.importing = false;
+                    self.import_result = Some(res);
+                }
             }
         }
 
@@ -84,6 +119,14 @@ impl State for SettingsState {
                     .width(Length::Fill)
                     .view(),
             )
+            .push(
+                Button::new()
+                    .text("Approval PIN")
+                    .icon(KEY)
+                    .on_press(Message::View(Stage::ApprovalPin))
+                    .width(Length::Fill)
+                    .view(),
+            )
             .push(
                 Button::new()
                     .text("Config")
@@ -108,6 +151,30 @@ impl State for SettingsState {
                     .width(Length::Fill)
                     .view(),
             )
+            .push(
+                Button::new()
+                    .text("Connection doctor")
+                    .icon(TOOLS)
+                    .on_press(Message::View(Stage::Doctor))
+                    .width(Length::Fill)
+                    .view(),
+            )
+            .push(
+                Button::new()
+                    .text("Devices")
+                    .icon(LINK)
+                    .on_press(Message::View(Stage::Devices))
+                    .width(Length::Fill)
+                    .view(),
+            )
+            .push(
+                Button::new()
+                    .text("Updates")
+                    .icon(ARROW_UP)
+                    .on_press(Message::View(Stage::Update))
+                    .width(Length::Fill)
+                    .view(),
+            )
             .push(
                 Button::new()
                     .text("Rebroadcast all events")
@@ -116,6 +183,26 @@ impl State for SettingsState {
                     .width(Length::Fill)
                     .view(),
             )
+            .push(
+                Button::new()
+                    .text("Import events (JSON or JSONL archive)")
+                    .icon(ARROW_DOWN)
+                    .on_press(SettingsMessage::ImportEvents.into())
+                    .loading(self.importing)
+                    .width(Length::Fill)
+                    .view(),
+            )
+            .push(match &self.import_result {
+                Some(Ok(summary)) => Text::new(format!(
+                    "{} events imported, {} already known, {} invalid, {} not handled",
+                    summary.imported, summary.duplicated, summary.invalid, summary.failed
+                ))
+                .extra_light()
+                .small()
+                .view(),
+                Some(Err(e)) => Text::new(e).color(DARK_RED).small().view(),
+                None => Text::new("").view(),
+            })
             .push(
                 Button::new()
                     .text("Force full timechain sync")
@@ -7,11 +7,12 @@ use iced::alignment::Horizontal;
 use iced::widget::{Column, Row};
 use iced::{time, Alignment, Command, Element, Length, Subscription};
 use smartvaults_sdk::nostr::{RelayStatus, Url};
+use smartvaults_sdk::types::RelayAdmissionStatus;
 
 use crate::app::component::Dashboard;
 use crate::app::{Context, Message, Stage, State};
 use crate::component::{rule, Button, ButtonStyle, Circle, Text};
-use crate::theme::color::{GREEN, GREY, NEUTRAL, RED, YELLOW};
+use crate::theme::color::{GREEN, GREY, NEUTRAL, ORANGE, RED, YELLOW};
 use crate::theme::icon::{FULLSCREEN, PLUS, RELOAD, TRASH};
 
 #[derive(Debug, Clone)]
@@ -19,6 +20,7 @@ pub struct Relay {
     url: Url,
     status: RelayStatus,
     queue: usize,
+    admission: Option<RelayAdmissionStatus>,
 }
 
 #[derive(Debug, Clone)]
@@ -59,9 +61,11 @@ impl State for RelaysState {
         let client = ctx.client.clone();
         Command::perform(
             async move {
+                let admission = client.get_relays_admission().await;
                 let mut relays = Vec::new();
                 for (url, relay) in client.relays().await.into_iter() {
                     relays.push(Relay {
+                        admission: admission.get(&url).map(|a| a.status),
                         url,
                         status: relay.status().await,
                         queue: relay.queue(),
@@ -155,7 +159,13 @@ impl State for RelaysState {
                 )
                 .push(rule::horizontal_bold());
 
-            for Relay { url, status, queue } in self.relays.iter() {
+            for Relay {
+                url,
+                status,
+                queue,
+                admission,
+            } in self.relays.iter()
+            {
                 let status = match status {
                     RelayStatus::Initialized | RelayStatus::Pending => Circle::new(7.0).color(GREY),
                     RelayStatus::Connecting => Circle::new(7.0).color(YELLOW),
@@ -165,8 +175,27 @@ impl State for RelaysState {
                     RelayStatus::Terminated => continue,
                 };
 
+                let mut url_col = Column::new()
+                    .push(Text::new(url.to_string()).view())
+                    .width(Length::Fill);
+                match admission {
+                    Some(RelayAdmissionStatus::PaymentRequired) => {
+                        url_col = url_col.push(
+                            Text::new("Payment required to publish")
+                                .small()
+                                .color(ORANGE)
+                                .view(),
+                        );
+                    }
+                    Some(RelayAdmissionStatus::Rejected) => {
+                        url_col =
+                            url_col.push(Text::new("Rejecting events").small().color(RED).view());
+                    }
+                    _ => (),
+                }
+
                 let row = Row::new()
-                    .push(Text::new(url.to_string()).width(Length::Fill).view())
+                    .push(url_col)
                     .push(
                         Column::new()
                             .push(status)
@@ -0,0 +1,238 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::path::PathBuf;
+
+use iced::widget::{Column, Row, Space};
+use iced::{Alignment, Command, Element, Length};
+use rfd::FileDialog;
+use smartvaults_sdk::protocol::v1::constants::DESKTOP_RELEASE_IDENTIFIER;
+use smartvaults_sdk::protocol::v1::{Release, ReleaseAsset};
+
+use crate::app::component::Dashboard;
+use crate::app::{Context, Message, State};
+use crate::component::{rule, Button, ButtonStyle, Text};
+use crate::constants::APP_VERSION;
+use crate::theme::color::{DARK_RED, GREEN};
+use crate::theme::icon::{ARROW_DOWN, RELOAD};
+
+/// Installer platform of this build (i.e. `linux-x86_64`)
+pub fn current_platform() -> String {
+    format!("{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+#[derive(Debug, Clone)]
+pub enum UpdateMessage {
+    Load(Option<Release>),
+    Download(ReleaseAsset),
+    Downloaded(PathBuf),
+    ErrorChanged(Option<String>),
+    Reload,
+}
+
+#[derive(Debug, Default)]
+pub struct UpdateState {
+    loading: bool,
+    loaded: bool,
+    release: Option<Release>,
+    downloaded: Option<PathBuf>,
+    error: Option<String>,
+}
+
+impl UpdateState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl State for UpdateState {
+    fn title(&self) -> String {
+        String::from("Updates")
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.loading = true;
+        let client = ctx.client.clone();
+        Command::perform(
+            async move {
+                client
+                    .check_for_updates(DESKTOP_RELEASE_IDENTIFIER, APP_VERSION, None)
+                    .await
+            },
+            |res| match res {
+                Ok(release) => UpdateMessage::Load(release).into(),
+                Err(e) => UpdateMessage::ErrorChanged(Some(e.to_string())).into(),
+            },
+        )
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if !self.loaded && !self.loading {
+            return self.load(ctx);
+        }
+
+        if let Message::Update(msg) = message {
+            match msg {
+                UpdateMessage::Load(release) => {
+                    ctx.release = release.clone();
+                    self.release = release;
+                    self.error = None;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                UpdateMessage::Download(asset) => {
+                    if let Some(dir) = FileDialog::new().pick_folder() {
+                        self.loading = true;
+                        let client = ctx.client.clone();
+                        return Command::perform(
+                            async move { client.download_release_asset(&asset, dir).await },
+                            |res| match res {
+                                Ok(path) => UpdateMessage::Downloaded(path).into(),
+                                Err(e) => UpdateMessage::ErrorChanged(Some(e.to_string())).into(),
+                            },
+                        );
+                    }
+                }
+                UpdateMessage::Downloaded(path) => {
+                    self.downloaded = Some(path);
+                    self.error = None;
+                    self.loading = false;
+                }
+                UpdateMessage::ErrorChanged(e) => {
+                    self.error = e;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                UpdateMessage::Reload => return self.load(ctx),
+            }
+        }
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let mut content = Column::new().spacing(10).padding(20);
+
+        if self.loaded {
+            content = content
+                .push(
+                    Row::new()
+                        .push(
+                            Text::new(format!("Current version: {APP_VERSION}"))
+                                .bold()
+                                .big()
+                                .width(Length::Fill)
+                                .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .icon(RELOAD)
+                                .style(ButtonStyle::Bordered)
+                                .on_press(UpdateMessage::Reload.into())
+                                .loading(self.loading)
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal_bold());
+
+            match &self.release {
+                Some(release) => {
+                    content = content.push(
+                        Text::new(format!("New version available: {}", release.version))
+                            .bold()
+                            .view(),
+                    );
+
+                    if release.is_incompatible_with(APP_VERSION) {
+                        content = content.push(
+                            Text::new(
+                                "This version is no longer compatible with the latest one: \
+                                update to keep signing with the other vault members.",
+                            )
+                            .color(DARK_RED)
+                            .view(),
+                        );
+                    }
+
+                    if !release.changelog.is_empty() {
+                        content = content
+                            .push(Space::with_height(Length::Fixed(10.0)))
+                            .push(Text::new("Changelog").bold().view())
+                            .push(Text::new(&release.changelog).view());
+                    }
+
+                    if !release.assets.is_empty() {
+                        content = content
+                            .push(Space::with_height(Length::Fixed(10.0)))
+                            .push(Text::new("Downloads").bold().view())
+                            .push(rule::horizontal());
+
+                        let platform: String = current_platform();
+                        for asset in release.assets.iter() {
+                            let style = if asset.platform == platform {
+                                ButtonStyle::Primary
+                            } else {
+                                ButtonStyle::Bordered
+                            };
+                            content = content.push(
+                                Row::new()
+                                    .push(Text::new(&asset.platform).width(Length::Fill).view())
+                                    .push(
+                                        Button::new()
+                                            .icon(ARROW_DOWN)
+                                            .text("Download")
+                                            .style(style)
+                                            .on_press(UpdateMessage::Download(asset.clone()).into())
+                                            .loading(self.loading)
+                                            .width(Length::Fixed(200.0))
+                                            .view(),
+                                    )
+                                    .spacing(10)
+                                    .align_items(Alignment::Center)
+                                    .width(Length::Fill),
+                            );
+                        }
+                    }
+                }
+                None => {
+                    content = content.push(Text::new("Smart Vaults is up to date").view());
+                }
+            }
+
+            if let Some(path) = &self.downloaded {
+                content = content.push(
+                    Text::new(format!(
+                        "Installer verified and saved to {}",
+                        path.display()
+                    ))
+                    .color(GREEN)
+                    .view(),
+                );
+            }
+
+            if let Some(error) = &self.error {
+                content = content.push(Text::new(error).color(DARK_RED).view());
+            }
+        }
+
+        Dashboard::new()
+            .loaded(self.loaded)
+            .view(ctx, content, true, false)
+    }
+}
+
+impl From<UpdateState> for Box<dyn State> {
+    fn from(s: UpdateState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<UpdateMessage> for Message {
+    fn from(msg: UpdateMessage) -> Self {
+        Self::Update(msg)
+    }
+}
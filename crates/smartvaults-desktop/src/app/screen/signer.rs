@@ -3,22 +3,39 @@
 
 use std::collections::BTreeMap;
 
+use iced::widget::qr_code::{self, QRCode};
 use iced::widget::{Column, Row, Space};
 use iced::{Alignment, Command, Element, Length};
 use smartvaults_sdk::core::signer::Signer;
+use smartvaults_sdk::core::Purpose;
 use smartvaults_sdk::nostr::{EventId, Profile};
-use smartvaults_sdk::util;
+use smartvaults_sdk::types::{GetSignerDescriptors, GetSignerUsage};
+use smartvaults_sdk::{util, Error};
 
 use crate::app::component::Dashboard;
 use crate::app::{Context, Message, Stage, State};
 use crate::component::{rule, Button, ButtonStyle, Text};
 use crate::theme::color::RED;
-use crate::theme::icon::TRASH;
+use crate::theme::icon::{CLIPBOARD, QRCODE, TRASH, VAULT};
+
+fn purpose_name(purpose: Option<&Purpose>) -> &'static str {
+    match purpose {
+        Some(Purpose::BIP86) => "BIP86 (single-sig taproot)",
+        Some(Purpose::BIP48 { .. }) => "BIP48 (multi-sig)",
+        _ => "Unknown",
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum SignerMessage {
-    LoadMySharedSigners(BTreeMap<EventId, Profile>),
-    Delete,
+    Load(
+        GetSignerDescriptors,
+        GetSignerUsage,
+        BTreeMap<EventId, Profile>,
+    ),
+    ShowQrCode(String),
+    Delete(bool),
+    SignerInUse(Vec<EventId>),
     RevokeSharedSigner(EventId),
     Reload,
     ErrorChanged(Option<String>),
@@ -30,7 +47,11 @@ pub struct SignerState {
     loaded: bool,
     signer_id: EventId,
     signer: Signer,
+    descriptors: Option<GetSignerDescriptors>,
+    usage: Option<GetSignerUsage>,
     my_shared_signers: BTreeMap<EventId, Profile>,
+    qr_code: Option<(String, qr_code::State)>,
+    used_by: Vec<EventId>,
     error: Option<String>,
 }
 
@@ -41,7 +62,11 @@ impl SignerState {
             loaded: false,
             signer_id,
             signer,
+            descriptors: None,
+            usage: None,
             my_shared_signers: BTreeMap::new(),
+            qr_code: None,
+            used_by: Vec::new(),
             error: None,
         }
     }
@@ -58,12 +83,17 @@ impl State for SignerState {
         let signer_id = self.signer_id;
         Command::perform(
             async move {
-                client
-                    .get_my_shared_signers_by_signer_id(signer_id)
-                    .await
-                    .unwrap()
+                let descriptors = client.get_signer_descriptors(signer_id).await?;
+                let usage = client.get_signer_usage(signer_id).await?;
+                let signers = client.get_my_shared_signers_by_signer_id(signer_id).await?;
+                Ok::<_, Error>((descriptors, usage, signers))
+            },
+            |res| match res {
+                Ok((descriptors, usage, signers)) => {
+                    SignerMessage::Load(descriptors, usage, signers).into()
+                }
+                Err(e) => SignerMessage::ErrorChanged(Some(e.to_string())).into(),
             },
-            |signers| SignerMessage::LoadMySharedSigners(signers).into(),
         )
     }
 
@@ -74,27 +104,53 @@ impl State for SignerState {
 
         if let Message::Signer(msg) = message {
             match msg {
-                SignerMessage::LoadMySharedSigners(signers) => {
+                SignerMessage::Load(descriptors, usage, signers) => {
+                    self.descriptors = Some(descriptors);
+                    self.usage = Some(usage);
                     self.my_shared_signers = signers;
                     self.loading = false;
                     self.loaded = true;
                 }
+                SignerMessage::ShowQrCode(data) => {
+                    self.qr_code = match self.qr_code.take() {
+                        Some((current, _)) if current == data => None,
+                        _ => match qr_code::State::new(&data) {
+                            Ok(state) => Some((data, state)),
+                            Err(e) => {
+                                self.error = Some(format!("Impossible to generate QR code: {e}"));
+                                None
+                            }
+                        },
+                    };
+                }
                 SignerMessage::ErrorChanged(error) => {
                     self.loading = false;
+                    self.loaded = true;
                     self.error = error;
                 }
-                SignerMessage::Delete => {
+                SignerMessage::Delete(force) => {
                     self.loading = true;
                     let client = ctx.client.clone();
                     let signer_id = self.signer_id;
                     return Command::perform(
-                        async move { client.delete_signer_by_id(signer_id).await },
+                        async move { client.delete_signer_by_id(signer_id, force).await },
                         |res| match res {
                             Ok(_) => Message::View(Stage::Signers),
+                            Err(Error::SignerInUse(vaults)) => {
+                                SignerMessage::SignerInUse(vaults).into()
+                            }
                             Err(e) => SignerMessage::ErrorChanged(Some(e.to_string())).into(),
                         },
                     );
                 }
+                SignerMessage::SignerInUse(vaults) => {
+                    self.loading = false;
+                    self.error = Some(format!(
+                        "This signer is used by {} vault/s: deleting it may make them unspendable",
+                        vaults.len()
+                    ));
+                    self.used_by = vaults;
+                }
                 SignerMessage::RevokeSharedSigner(shared_signer_id) => {
                     self.loading = true;
                     let client = ctx.client.clone();
@@ -128,7 +184,6 @@ impl State for SignerState {
                 .push(Text::new(format!("Name: {}", self.signer.name())).view())
                 .push(Text::new(format!("Type: {}", self.signer.signer_type())).view())
                 .push(Text::new(format!("Fingerprint: {}", self.signer.fingerprint())).view())
-                .push(Text::new(format!("Descriptor: {}", self.signer.descriptor())).view())
                 .push(Space::with_height(10.0))
                 .push(
                     Row::new()
@@ -137,7 +192,7 @@ impl State for SignerState {
                                 .style(ButtonStyle::Danger)
                                 .icon(TRASH)
                                 .text("Delete")
-                                .on_press(SignerMessage::Delete.into())
+                                .on_press(SignerMessage::Delete(false).into())
                                 .loading(self.loading)
                                 .view(),
                         )
@@ -149,6 +204,126 @@ impl State for SignerState {
                 content = content.push(Text::new(error).color(RED).view());
             };
 
+            if let Some(descriptors) = &self.descriptors {
+                let mut keys: Vec<(&str, String)> = vec![
+                    ("Extended public key", descriptors.xpub.clone()),
+                    ("Receive descriptor", descriptors.external.to_string()),
+                ];
+                if let Some(internal) = &descriptors.internal {
+                    keys.push(("Change descriptor", internal.to_string()));
+                }
+
+                content = content
+                    .push(
+                        Text::new(format!(
+                            "Keys - {}",
+                            purpose_name(descriptors.purpose.as_ref())
+                        ))
+                        .bold()
+                        .big()
+                        .view(),
+                    )
+                    .push(rule::horizontal_bold());
+
+                for (label, value) in keys.into_iter() {
+                    let row = Row::new()
+                        .push(
+                            Column::new()
+                                .push(Text::new(label).bold().view())
+                                .push(Text::new(&value).extra_light().view())
+                                .spacing(5)
+                                .width(Length::Fill),
+                        )
+                        .push(
+                            Button::new()
+                                .style(ButtonStyle::Bordered)
+                                .icon(CLIPBOARD)
+                                .on_press(Message::Clipboard(value.clone()))
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .style(ButtonStyle::Bordered)
+                                .icon(QRCODE)
+                                .on_press(SignerMessage::ShowQrCode(value).into())
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill);
+                    content = content.push(row).push(rule::horizontal());
+                }
+
+                if let Some((_, qr_code)) = &self.qr_code {
+                    content = content
+                        .push(QRCode::new(qr_code).cell_size(3))
+                        .push(Space::with_height(Length::Fixed(10.0)));
+                }
+
+                content = content.push(Space::with_height(20.0));
+            }
+
+            if let Some(usage) = &self.usage {
+                content = content
+                    .push(Text::new("Used by").bold().big().view())
+                    .push(
+                        Text::new(format!(
+                            "{} vault/s, {} approval/s signed",
+                            usage.vaults.len(),
+                            usage.approvals.len()
+                        ))
+                        .extra_light()
+                        .view(),
+                    )
+                    .push(rule::horizontal_bold());
+
+                if usage.vaults.is_empty() {
+                    content = content.push(Text::new("No vaults").extra_light().view());
+                }
+
+                for vault_id in usage.vaults.iter() {
+                    let row = Row::new()
+                        .push(
+                            Text::new(format!("Vault #{}", util::cut_event_id(*vault_id)))
+                                .width(Length::Fill)
+                                .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .style(ButtonStyle::Bordered)
+                                .icon(VAULT)
+                                .on_press(Message::View(Stage::Vault(*vault_id)))
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill);
+                    content = content.push(row).push(rule::horizontal());
+                }
+
+                content = content.push(Space::with_height(20.0));
+            }
+
+            if !self.used_by.is_empty() {
+                for vault_id in self.used_by.iter() {
+                    content = content.push(
+                        Text::new(format!("- Vault #{}", util::cut_event_id(*vault_id))).view(),
+                    );
+                }
+                content = content.push(
+                    Button::new()
+                        .style(ButtonStyle::BorderedDanger)
+                        .icon(TRASH)
+                        .text("Delete anyway")
+                        .on_press(SignerMessage::Delete(true).into())
+                        .loading(self.loading)
+                        .view(),
+                );
+            }
+
             if !self.my_shared_signers.is_empty() {
                 content = content
                     .push(Text::new("My Shared Signers").bold().big().view())
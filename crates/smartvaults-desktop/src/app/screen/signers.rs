@@ -4,7 +4,8 @@
 use iced::widget::{Column, Row, Space};
 use iced::{Alignment, Command, Element, Length};
 use smartvaults_sdk::core::Signer;
-use smartvaults_sdk::types::{GetSharedSigner, GetSigner, GetSignerOffering};
+use smartvaults_sdk::nostr::EventId;
+use smartvaults_sdk::types::{GetSharedSigner, GetSigner, GetSignerOffering, GetSignerRequest};
 use smartvaults_sdk::util;
 
 use crate::app::component::Dashboard;
@@ -19,8 +20,13 @@ pub enum SignersMessage {
         signers: Vec<GetSigner>,
         shared_signers: Vec<GetSharedSigner>,
         signer_offerings: Vec<GetSignerOffering>,
+        requests: Vec<GetSignerRequest>,
     },
     DeleteSignerOffering(Signer),
+    AnswerRequest {
+        request_id: EventId,
+        signer_id: EventId,
+    },
     Reload,
 }
 
@@ -31,6 +37,7 @@ pub struct SignersState {
     signers: Vec<GetSigner>,
     shared_signers: Vec<GetSharedSigner>,
     signer_offerings: Vec<GetSignerOffering>,
+    requests: Vec<GetSignerRequest>,
 }
 
 impl SignersState {
@@ -59,13 +66,18 @@ impl State for SignersState {
                     Mode::User => Vec::new(),
                     Mode::KeyAgent => client.my_signer_offerings().await.unwrap(),
                 };
-                (signers, shared_signers, signer_offerings)
+                let requests = match mode {
+                    Mode::User => client.get_signer_requests().await.unwrap_or_default(),
+                    Mode::KeyAgent => Vec::new(),
+                };
+                (signers, shared_signers, signer_offerings, requests)
             },
-            |(signers, shared_signers, signer_offerings)| {
+            |(signers, shared_signers, signer_offerings, requests)| {
                 SignersMessage::Load {
                     signers,
                     shared_signers,
                     signer_offerings,
+                    requests,
                 }
                 .into()
             },
@@ -83,10 +95,12 @@ impl State for SignersState {
                     signers,
                     shared_signers,
                     signer_offerings,
+                    requests,
                 } => {
                     self.signers = signers;
                     self.shared_signers = shared_signers;
                     self.signer_offerings = signer_offerings;
+                    self.requests = requests;
                     self.loading = false;
                     self.loaded = true;
                     Command::none()
@@ -105,6 +119,22 @@ impl State for SignersState {
                         },
                     )
                 }
+                SignersMessage::AnswerRequest {
+                    request_id,
+                    signer_id,
+                } => {
+                    let client = ctx.client.clone();
+                    self.loading = true;
+                    Command::perform(
+                        async move { client.answer_signer_request(request_id, signer_id).await },
+                        |res| {
+                            if let Err(e) = res {
+                                tracing::error!("Impossible to answer signer request: {e}");
+                            }
+                            SignersMessage::Reload.into()
+                        },
+                    )
+                }
                 SignersMessage::Reload => self.load(ctx),
             }
         } else {
@@ -161,6 +191,64 @@ impl State for SignersState {
                     .loading(self.loading)
                     .view();
 
+                // Signer requests
+
+                if !self.requests.is_empty() {
+                    content = content
+                        .push(Text::new("Signer requests").big().bold().view())
+                        .push(rule::horizontal_bold());
+
+                    for GetSignerRequest {
+                        request_id,
+                        user,
+                        request,
+                        matching,
+                        ..
+                    } in self.requests.iter()
+                    {
+                        let mut description: String = format!(
+                            "{} asked for a signer (purpose {})",
+                            user.name(),
+                            request.purpose()
+                        );
+                        if let Some(message) = request.message() {
+                            description.push_str(&format!(": {message}"));
+                        }
+
+                        let mut row = Row::new()
+                            .push(Text::new(description).width(Length::Fill).view())
+                            .spacing(10)
+                            .align_items(Alignment::Center)
+                            .width(Length::Fill);
+
+                        if matching.is_empty() {
+                            row = row.push(Text::new("No matching signers").extra_light().view());
+                        }
+
+                        for GetSigner { signer_id, signer } in matching.iter() {
+                            row = row.push(
+                                Button::new()
+                                    .style(ButtonStyle::Bordered)
+                                    .icon(SHARE)
+                                    .text(format!("Share {}", signer.name()))
+                                    .on_press(
+                                        SignersMessage::AnswerRequest {
+                                            request_id: *request_id,
+                                            signer_id: *signer_id,
+                                        }
+                                        .into(),
+                                    )
+                                    .loading(self.loading)
+                                    .view(),
+                            );
+                        }
+
+                        content = content.push(row).push(rule::horizontal());
+                    }
+
+                    content = content.push(Space::with_height(Length::Fixed(40.0)));
+                }
+
                 // My Signers
 
                 content = content
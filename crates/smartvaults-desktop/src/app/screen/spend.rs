@@ -6,21 +6,29 @@ use std::str::FromStr;
 
 use iced::widget::{Checkbox, Column, Container, PickList, Row, Space};
 use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::constants::SUPPORTED_FIAT_CURRENCIES;
 use smartvaults_sdk::core::bdk::descriptor::policy::SatisfiableItem;
 use smartvaults_sdk::core::bitcoin::address::NetworkUnchecked;
 use smartvaults_sdk::core::bitcoin::{Address, OutPoint};
-use smartvaults_sdk::core::{Amount, FeeRate, SelectableCondition};
+use smartvaults_sdk::core::{Amount, FeeRate, SelectableCondition, TxOptions};
 use smartvaults_sdk::nostr::EventId;
-use smartvaults_sdk::types::{GetPolicy, GetProposal, GetUtxo};
+use smartvaults_sdk::protocol::v1::{Currency, Payees};
+use smartvaults_sdk::types::{
+    BtcPrice, FeeSuggestion, GetPolicy, GetProposal, GetUtxo, SpendOptions, SpendPreview,
+};
 use smartvaults_sdk::util::format;
 
-use crate::app::component::{Dashboard, FeeSelector, PolicyPickList, PolicyTree, UtxoSelector};
+use crate::app::component::{
+    Dashboard, Deadline, FeeSelector, PolicyPickList, PolicyTree, UtxoSelector,
+};
 use crate::app::{Context, Message, Stage, State};
 use crate::component::{
     rule, Amount as AmountComponent, Button, ButtonStyle, NumericInput, Text, TextInput,
 };
 use crate::theme::color::{DARK_RED, RED};
 
+const SAT_UNIT: &str = "SAT";
+
 #[derive(Debug, Clone, Copy, Default)]
 pub enum InternalStageBuild {
     #[default]
@@ -47,10 +55,18 @@ pub enum SpendMessage {
     PolicySelectd(PolicyPickList),
     LoadPolicy(EventId),
     AddressChanged(String),
+    LoadPayees(Payees),
+    PayeeSelected(String),
     AmountChanged(Option<u64>),
+    UnitSelected(String),
+    FiatAmountChanged(String),
+    PriceLoaded(Result<BtcPrice, String>),
     SendAllBtnPressed,
+    ReserveChanged(Option<u64>),
     DescriptionChanged(String),
     FeeRateChanged(FeeRate),
+    DeadlineChanged(Deadline),
+    FeeSuggested(Result<FeeSuggestion, String>),
     PolicyLoaded(
         Vec<GetUtxo>,
         SatisfiableItem,
@@ -58,7 +74,7 @@ pub enum SpendMessage {
     ),
     SelectedUtxosChanged(HashSet<OutPoint>),
     SetSkipFrozenUtxos(bool),
-    EstimatedTxVSize(Option<usize>),
+    PreviewChanged(Option<SpendPreview>),
     ToggleCondition(String, usize),
     ErrorChanged(Option<String>),
     SetInternalStage(InternalStage),
@@ -70,17 +86,28 @@ pub struct SpendState {
     policy: Option<PolicyPickList>,
     policies: Vec<PolicyPickList>,
     to_address: String,
+    payees: Payees,
+    /// Payee of the address, to derive a new address for the next payment
+    payee: Option<String>,
     amount: Option<u64>,
+    /// Currency of the amount (sat if `None`)
+    currency: Option<Currency>,
+    fiat_amount: String,
+    price: Option<BtcPrice>,
     send_all: bool,
+    /// Amount kept in the vault when sending all
+    reserve: Option<u64>,
     description: String,
     fee_rate: FeeRate,
+    /// Deadline of the "confirm by" fee suggestion
+    deadline: Option<Deadline>,
     utxos: Vec<GetUtxo>,
     selected_utxos: HashSet<OutPoint>,
     skip_frozen_utxos: bool,
     policy_path: Option<BTreeMap<String, Vec<usize>>>,
     satisfiable_item: Option<SatisfiableItem>,
     selectable_conditions: Option<Vec<SelectableCondition>>,
-    estimated_tx_vsize: Option<usize>,
+    preview: Option<SpendPreview>,
     stage: InternalStage,
     loading: bool,
     loaded: bool,
@@ -93,17 +120,24 @@ impl SpendState {
             policy: policy.map(|p| p.into()),
             policies: Vec::new(),
             to_address: String::new(),
+            payees: Payees::default(),
+            payee: None,
             amount: None,
+            currency: None,
+            fiat_amount: String::new(),
+            price: None,
             send_all: false,
+            reserve: None,
             description: String::new(),
             fee_rate: FeeRate::default(),
+            deadline: None,
             utxos: Vec::new(),
             selected_utxos: HashSet::new(),
             skip_frozen_utxos: false,
             policy_path: None,
             satisfiable_item: None,
             selectable_conditions: None,
-            estimated_tx_vsize: None,
+            preview: None,
             stage: InternalStage::default(),
             loading: false,
             loaded: false,
@@ -111,7 +145,33 @@ impl SpendState {
         }
     }
 
-    fn _estimate_tx_vsize(
+    fn max_amount(&self) -> Amount {
+        match self.reserve {
+            Some(reserve) if reserve > 0 => Amount::MaxWithReserve(reserve),
+            _ => Amount::Max,
+        }
+    }
+
+    fn unit(&self) -> String {
+        match self.currency {
+            Some(currency) => currency.to_string(),
+            None => String::from(SAT_UNIT),
+        }
+    }
+
+    /// Convert the fiat amount at the last loaded price
+    fn update_fiat_amount(&mut self) {
+        if self.currency.is_some() {
+            self.amount = match (self.fiat_amount.parse::<f64>(), &self.price) {
+                (Ok(fiat_amount), Some(price)) if fiat_amount > 0.0 => {
+                    Some(price.to_sat(fiat_amount))
+                }
+                _ => None,
+            };
+        }
+    }
+
+    fn _preview_spend(
         &self,
         ctx: &mut Context,
         policy_id: EventId,
@@ -120,39 +180,42 @@ impl SpendState {
     ) -> Command<Message> {
         let client = ctx.client.clone();
         let selected_utxos: Vec<OutPoint> = self.selected_utxos.iter().cloned().collect();
-        let policy_path = self.policy_path.clone();
-        let skip_frozen_utxos: bool = self.skip_frozen_utxos;
+        let fee_rate = self.fee_rate;
+        let options = SpendOptions {
+            utxos: if selected_utxos.is_empty() {
+                None
+            } else {
+                Some(selected_utxos)
+            },
+            excluded_utxos: Vec::new(),
+            avoid_address_reuse: false,
+            policy_path: self.policy_path.clone(),
+            skip_frozen_utxos: self.skip_frozen_utxos,
+            expires_at: None,
+            memo: None,
+            op_return: None,
+            tx_options: TxOptions::default(),
+        };
         Command::perform(
             async move {
                 client
-                    .estimate_tx_vsize(
-                        policy_id,
-                        address,
-                        amount,
-                        if selected_utxos.is_empty() {
-                            None
-                        } else {
-                            Some(selected_utxos)
-                        },
-                        policy_path,
-                        skip_frozen_utxos,
-                    )
+                    .preview_spend(policy_id, address, amount, fee_rate, options)
                     .await
-                    .ok()?
+                    .ok()
             },
-            |res| SpendMessage::EstimatedTxVSize(res).into(),
+            |res| SpendMessage::PreviewChanged(res).into(),
         )
     }
 
-    fn estimate_tx_vsize(&self, ctx: &mut Context) -> Command<Message> {
+    fn preview_spend(&self, ctx: &mut Context) -> Command<Message> {
         match &self.policy {
             Some(pp) => match Address::from_str(&self.to_address) {
                 Ok(address) => {
                     if self.send_all {
-                        self._estimate_tx_vsize(ctx, pp.policy_id, address, Amount::Max)
+                        self._preview_spend(ctx, pp.policy_id, address, self.max_amount())
                     } else {
                         match self.amount {
-                            Some(amount) => self._estimate_tx_vsize(
+                            Some(amount) => self._preview_spend(
                                 ctx,
                                 pp.policy_id,
                                 address,
@@ -183,25 +246,61 @@ impl SpendState {
         let selected_utxos: Vec<OutPoint> = self.selected_utxos.iter().cloned().collect();
         let policy_path = self.policy_path.clone();
         let skip_frozen_utxos: bool = self.skip_frozen_utxos;
+        let payee: Option<String> = self.payee.clone();
+
+        let utxos = if selected_utxos.is_empty() {
+            None
+        } else {
+            Some(selected_utxos)
+        };
+        // The fiat amount is converted again at the current price when the proposal is created
+        let fiat: Option<(f64, Currency)> = match (self.currency, amount) {
+            (Some(currency), Amount::Custom(_)) => self
+                .fiat_amount
+                .parse::<f64>()
+                .ok()
+                .map(|fiat_amount| (fiat_amount, currency)),
+            _ => None,
+        };
 
         Command::perform(
             async move {
-                let GetProposal { proposal_id, .. } = client
-                    .spend(
-                        policy_id,
-                        to_address,
-                        amount,
-                        description,
-                        fee_rate,
-                        if selected_utxos.is_empty() {
-                            None
-                        } else {
-                            Some(selected_utxos)
-                        },
-                        policy_path,
-                        skip_frozen_utxos,
-                    )
-                    .await?;
+                let GetProposal { proposal_id, .. } = match fiat {
+                    Some((fiat_amount, currency)) => {
+                        client
+                            .spend_fiat(
+                                policy_id,
+                                to_address,
+                                fiat_amount,
+                                currency,
+                                description,
+                                fee_rate,
+                                utxos,
+                                policy_path,
+                                skip_frozen_utxos,
+                            )
+                            .await?
+                    }
+                    None => {
+                        client
+                            .spend(
+                                policy_id,
+                                to_address,
+                                amount,
+                                description,
+                                fee_rate,
+                                utxos,
+                                policy_path,
+                                skip_frozen_utxos,
+                            )
+                            .await?
+                    }
+                };
+                if let Some(payee) = payee {
+                    if let Err(e) = client.use_payee_address(payee).await {
+                        tracing::warn!("Impossible to update payee: {e}");
+                    }
+                }
                 Ok::<EventId, Box<dyn std::error::Error>>(proposal_id)
             },
             |res| match res {
@@ -224,7 +323,7 @@ impl State for SpendState {
 
         self.loading = true;
         let client = ctx.client.clone();
-        Command::perform(
+        let policies = Command::perform(
             async move {
                 client
                     .get_policies()
@@ -235,7 +334,13 @@ impl State for SpendState {
                     .collect()
             },
             |p| SpendMessage::LoadPolicies(p).into(),
-        )
+        );
+        let client = ctx.client.clone();
+        let payees = Command::perform(async move { client.get_payees().await }, |res| match res {
+            Ok(payees) => SpendMessage::LoadPayees(payees).into(),
+            Err(e) => SpendMessage::ErrorChanged(Some(e.to_string())).into(),
+        });
+        Command::batch(vec![policies, payees])
     }
 
     fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
@@ -295,15 +400,15 @@ impl State for SpendState {
                     self.utxos = utxos;
                     self.satisfiable_item = Some(item);
                     self.selectable_conditions = conditions;
-                    return self.estimate_tx_vsize(ctx);
+                    return self.preview_spend(ctx);
                 }
                 SpendMessage::SelectedUtxosChanged(s) => {
                     self.selected_utxos = s;
-                    return self.estimate_tx_vsize(ctx);
+                    return self.preview_spend(ctx);
                 }
                 SpendMessage::SetSkipFrozenUtxos(val) => {
                     self.skip_frozen_utxos = val;
-                    return self.estimate_tx_vsize(ctx);
+                    return self.preview_spend(ctx);
                 }
                 SpendMessage::ToggleCondition(id, index) => match self.policy_path.as_mut() {
                     Some(policy_path) => match policy_path.get_mut(&id) {
@@ -330,19 +435,90 @@ impl State for SpendState {
                 },
                 SpendMessage::AddressChanged(value) => {
                     self.to_address = value;
-                    return self.estimate_tx_vsize(ctx);
+                    self.payee = None;
+                    return self.preview_spend(ctx);
+                }
+                SpendMessage::LoadPayees(payees) => self.payees = payees,
+                SpendMessage::PayeeSelected(name) => {
+                    if let Some(payee) = self.payees.get(&name) {
+                        match payee.address(ctx.client.network()) {
+                            Ok(address) => {
+                                self.to_address = address.to_string();
+                                if self.description.is_empty() {
+                                    self.description = payee.memo().unwrap_or_default().to_string();
+                                }
+                                self.payee = Some(name);
+                                return self.preview_spend(ctx);
+                            }
+                            Err(e) => self.error = Some(e.to_string()),
+                        }
+                    }
                 }
                 SpendMessage::AmountChanged(value) => {
                     self.amount = value;
-                    return self.estimate_tx_vsize(ctx);
+                    return self.preview_spend(ctx);
                 }
+                SpendMessage::UnitSelected(unit) => {
+                    self.currency = Currency::from_str(&unit).ok();
+                    self.price = None;
+                    self.amount = None;
+                    self.fiat_amount = String::new();
+                    if let Some(currency) = self.currency {
+                        let client = ctx.client.clone();
+                        return Command::perform(
+                            async move { client.get_btc_price(currency).await },
+                            |res| SpendMessage::PriceLoaded(res.map_err(|e| e.to_string())).into(),
+                        );
+                    }
+                }
+                SpendMessage::FiatAmountChanged(value) => {
+                    self.fiat_amount = value;
+                    self.update_fiat_amount();
+                    return self.preview_spend(ctx);
+                }
+                SpendMessage::PriceLoaded(res) => match res {
+                    Ok(price) => {
+                        if self.currency == Some(price.currency) {
+                            self.price = Some(price);
+                            self.update_fiat_amount();
+                            self.error = None;
+                            return self.preview_spend(ctx);
+                        }
+                    }
+                    Err(e) => self.error = Some(e),
+                },
                 SpendMessage::SendAllBtnPressed => {
                     self.send_all = !self.send_all;
-                    return self.estimate_tx_vsize(ctx);
+                    return self.preview_spend(ctx);
+                }
+                SpendMessage::ReserveChanged(value) => {
+                    self.reserve = value;
+                    return self.preview_spend(ctx);
                 }
                 SpendMessage::DescriptionChanged(value) => self.description = value,
-                SpendMessage::FeeRateChanged(fee_rate) => self.fee_rate = fee_rate,
-                SpendMessage::EstimatedTxVSize(vsize) => self.estimated_tx_vsize = vsize,
+                SpendMessage::FeeRateChanged(fee_rate) => {
+                    self.fee_rate = fee_rate;
+                    self.deadline = None;
+                    return self.preview_spend(ctx);
+                }
+                SpendMessage::DeadlineChanged(deadline) => {
+                    self.deadline = Some(deadline);
+                    let client = ctx.client.clone();
+                    return Command::perform(
+                        async move { client.suggest_fee(deadline.0).await },
+                        |res| SpendMessage::FeeSuggested(res.map_err(|e| e.to_string())).into(),
+                    );
+                }
+                SpendMessage::FeeSuggested(res) => match res {
+                    Ok(suggestion) => {
+                        if self.deadline.is_some() {
+                            self.fee_rate = FeeRate::Rate(suggestion.fee_rate.as_sat_per_vb());
+                            return self.preview_spend(ctx);
+                        }
+                    }
+                    Err(e) => self.error = Some(format!("Impossible to suggest fee: {e}")),
+                },
+                SpendMessage::PreviewChanged(preview) => self.preview = preview,
                 SpendMessage::ErrorChanged(error) => {
                     self.loading = false;
                     self.error = error;
@@ -376,7 +552,8 @@ impl State for SpendState {
                         match Address::from_str(&self.to_address) {
                             Ok(to_address) => {
                                 if self.send_all {
-                                    return self.spend(ctx, policy_id, to_address, Amount::Max);
+                                    let amount: Amount = self.max_amount();
+                                    return self.spend(ctx, policy_id, to_address, amount);
                                 } else {
                                     match self.amount {
                                         Some(amount) => {
@@ -536,14 +713,37 @@ impl SpendState {
             )
             .spacing(5);
 
-        let address = Column::new()
-            .push(
-                TextInput::new(&self.to_address)
-                    .label("Address")
-                    .on_input(|s| SpendMessage::AddressChanged(s).into())
-                    .placeholder("Address")
+        let mut address = Column::new().push(
+            TextInput::new(&self.to_address)
+                .label("Address")
+                .on_input(|s| SpendMessage::AddressChanged(s).into())
+                .placeholder("Address or payee name")
+                .view(),
+        );
+
+        // Payees autocomplete
+        if self.payee.is_none() && !self.to_address.is_empty() {
+            let mut suggestions = Row::new().spacing(5);
+            for payee in self.payees.search(&self.to_address).into_iter().take(3) {
+                suggestions = suggestions.push(
+                    Button::new()
+                        .text(payee.name())
+                        .style(ButtonStyle::Bordered)
+                        .on_press(SpendMessage::PayeeSelected(payee.name().to_string()).into())
+                        .view(),
+                );
+            }
+            address = address.push(suggestions);
+        } else if let Some(payee) = &self.payee {
+            address = address.push(
+                Text::new(format!("Payee: {payee}"))
+                    .extra_light()
+                    .small()
                     .view(),
-            )
+            );
+        }
+
+        let address = address
             .push(
                 Text::new("Transfer to other policy")
                     .extra_light()
@@ -562,25 +762,72 @@ impl SpendState {
             .view();
 
         let amount = if self.send_all {
-            TextInput::with_label("Amount (sat)", "Send all")
-                .button(send_all_btn)
-                .view()
+            Column::new()
+                .push(
+                    TextInput::new(match &self.preview {
+                        Some(preview) => {
+                            format!("Send all (≈ {} sat)", format::number(preview.amount()))
+                        }
+                        None => String::from("Send all"),
+                    })
+                    .label("Amount (sat)")
+                    .button(send_all_btn)
+                    .view(),
+                )
+                .push(
+                    NumericInput::new("Keep in the vault (sat)", self.reserve)
+                        .on_input(|s| SpendMessage::ReserveChanged(s).into())
+                        .placeholder("Reserve for future fee bumps (optional)"),
+                )
+                .spacing(10)
         } else {
-            Column::new().push(
-                Row::new()
-                    .push(
-                        Column::new()
-                            .push(
-                                NumericInput::new("Amount (sat)", self.amount)
-                                    .on_input(|s| SpendMessage::AmountChanged(s).into())
-                                    .placeholder("Amount"),
-                            )
-                            .width(Length::Fill),
-                    )
-                    .push(send_all_btn)
-                    .align_items(Alignment::End)
-                    .spacing(5),
-            )
+            let units: Vec<String> = std::iter::once(String::from(SAT_UNIT))
+                .chain(SUPPORTED_FIAT_CURRENCIES.iter().map(|c| c.to_string()))
+                .collect();
+            let unit_pick_list = PickList::new(units, Some(self.unit()), |unit| {
+                SpendMessage::UnitSelected(unit).into()
+            })
+            .width(Length::Fixed(90.0))
+            .padding(10);
+
+            let input: Element<Message> = match self.currency {
+                Some(currency) => TextInput::new(&self.fiat_amount)
+                    .label(format!("Amount ({currency})"))
+                    .on_input(|s| SpendMessage::FiatAmountChanged(s).into())
+                    .placeholder("Amount")
+                    .view()
+                    .into(),
+                None => NumericInput::new("Amount (sat)", self.amount)
+                    .on_input(|s| SpendMessage::AmountChanged(s).into())
+                    .placeholder("Amount")
+                    .into(),
+            };
+
+            let mut amount = Column::new()
+                .push(
+                    Row::new()
+                        .push(Column::new().push(input).width(Length::Fill))
+                        .push(unit_pick_list)
+                        .push(send_all_btn)
+                        .align_items(Alignment::End)
+                        .spacing(5),
+                )
+                .spacing(5);
+
+            if let Some(currency) = self.currency {
+                let conversion: String = match (&self.price, self.amount) {
+                    (Some(price), Some(amount)) => format!(
+                        "≈ {} sat (1 BTC = {:.2} {currency})",
+                        format::number(amount),
+                        price.price
+                    ),
+                    (Some(price), None) => format!("1 BTC = {:.2} {currency}", price.price),
+                    (None, _) => String::from("Loading price..."),
+                };
+                amount = amount.push(Text::new(conversion).extra_light().small().view());
+            }
+
+            amount
         };
 
         let your_balance = if let Some(policy) = &self.policy {
@@ -596,6 +843,21 @@ impl SpendState {
             Text::new("").view()
         };
 
+        let preview = match &self.preview {
+            Some(preview) => Text::new(format!(
+                "Fee: {} sat (≈ {:.1} sat/vB) - Change: {} sat - {} input/s",
+                format::number(preview.fee),
+                preview.effective_fee_rate(),
+                format::number(preview.change),
+                preview.inputs.len()
+            ))
+            .extra_light()
+            .small()
+            .width(Length::Fill)
+            .view(),
+            None => Text::new("").view(),
+        };
+
         let description = TextInput::with_label("Description", &self.description)
             .on_input(|s| SpendMessage::DescriptionChanged(s).into())
             .placeholder("Description")
@@ -606,6 +868,7 @@ impl SpendState {
             .push(address)
             .push(amount)
             .push(your_balance)
+            .push(preview)
             .push(description)
             .spacing(10)
             .max_width(400);
@@ -617,7 +880,9 @@ impl SpendState {
                 .push(
                     FeeSelector::new(self.fee_rate, |f| SpendMessage::FeeRateChanged(f).into())
                         .current_mempool_fees(ctx.current_fees.clone())
-                        .estimate_tx_vsize(self.estimated_tx_vsize)
+                        .mempool_snapshot(ctx.mempool_snapshot.clone())
+                        .estimate_tx_vsize(self.preview.as_ref().map(|p| p.estimated_vsize))
+                        .confirm_by(self.deadline, |d| SpendMessage::DeadlineChanged(d).into())
                         .max_width(400.0),
                 )
                 .spacing(25)
@@ -748,7 +1013,25 @@ impl SpendState {
         let amount = Column::new()
             .push(Row::new().push(Text::new("Amount").bold().view()))
             .push(if self.send_all {
-                Row::new().push(Text::new("Send all").view())
+                match self.reserve {
+                    Some(reserve) if reserve > 0 => Row::new().push(
+                        Text::new(format!(
+                            "Send all, keeping {} sat in the vault",
+                            format::number(reserve)
+                        ))
+                        .view(),
+                    ),
+                    _ => Row::new().push(Text::new("Send all").view()),
+                }
+            } else if let Some(currency) = self.currency {
+                Row::new().push(
+                    Text::new(format!(
+                        "{} {currency} (≈ {} sat, converted at the current price when sent)",
+                        self.fiat_amount,
+                        format::number(self.amount.unwrap_or_default())
+                    ))
+                    .view(),
+                )
             } else {
                 AmountComponent::new(self.amount.unwrap_or_default())
                     .bigger()
@@ -774,6 +1057,27 @@ impl SpendState {
             .spacing(5)
             .width(Length::Fill);
 
+        let fees = match &self.preview {
+            Some(preview) => Column::new()
+                .push(Row::new().push(Text::new("Fee").bold().view()))
+                .push(
+                    Row::new().push(
+                        Text::new(format!(
+                            "{} sat, ≈ {:.1} sat/vB ({} input/s for {} sat, change {} sat)",
+                            format::number(preview.fee),
+                            preview.effective_fee_rate(),
+                            preview.inputs.len(),
+                            format::number(preview.total_input()),
+                            format::number(preview.change)
+                        ))
+                        .view(),
+                    ),
+                )
+                .spacing(5)
+                .width(Length::Fill),
+            None => Column::new(),
+        };
+
         let error = if let Some(error) = &self.error {
             Row::new().push(Text::new(error).color(DARK_RED).view())
         } else {
@@ -810,6 +1114,7 @@ impl SpendState {
             .push(amount)
             .push(description)
             .push(priority)
+            .push(fees)
             .push(error)
             .push(Space::with_height(Length::Fixed(15.0)))
             .push(send_proposal_btn)
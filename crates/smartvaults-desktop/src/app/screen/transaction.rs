@@ -83,7 +83,13 @@ impl State for TransactionState {
     fn view(&self, ctx: &Context) -> Element<Message> {
         let mut content = Column::new().spacing(20).padding(20);
 
-        if let Some(GetTransaction { tx, label, .. }) = &self.tx {
+        if let Some(GetTransaction {
+            tx,
+            label,
+            is_confirmation_verified,
+            ..
+        }) = &self.tx
+        {
             let (total, positive): (u64, bool) = {
                 let received: i64 = tx.received as i64;
                 let sent: i64 = tx.sent as i64;
@@ -165,7 +171,11 @@ impl State for TransactionState {
                 ConfirmationTime::Confirmed { height, time } => {
                     let confirmations: u32 = ctx.client.block_height().saturating_sub(height) + 1;
                     (
-                        format::number(height as u64),
+                        if *is_confirmation_verified {
+                            format!("{} (SPV verified)", format::number(height as u64))
+                        } else {
+                            format::number(height as u64)
+                        },
                         Timestamp::from(time).to_human_datetime(),
                         format::number(confirmations as u64),
                     )
@@ -5,6 +5,7 @@ use std::collections::{BTreeSet, HashSet};
 
 use iced::widget::{Column, Row, Space};
 use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::core::bitcoin::bip32::Fingerprint;
 use smartvaults_sdk::nostr::{Profile, PublicKey};
 use smartvaults_sdk::util;
 
@@ -24,6 +25,7 @@ pub enum AddVaultMessage {
     RemovePublicKey(PublicKey),
     SelectPublicKeys(bool),
     ErrorChanged(Option<String>),
+    CompromisedKeys(BTreeSet<Fingerprint>),
     SavePolicy,
 }
 
@@ -38,6 +40,8 @@ pub struct AddVaultState {
     loading: bool,
     loaded: bool,
     selecting: bool,
+    /// Compromised keys of the descriptor (already shown to the user)
+    compromised_keys: BTreeSet<Fingerprint>,
     error: Option<String>,
 }
 
@@ -79,7 +83,10 @@ impl State for AddVaultState {
             match msg {
                 AddVaultMessage::NameChanged(name) => self.name = name,
                 AddVaultMessage::DescriptionChanged(desc) => self.description = desc,
-                AddVaultMessage::DescriptorChanged(desc) => self.descriptor = desc,
+                AddVaultMessage::DescriptorChanged(desc) => {
+                    self.descriptor = desc;
+                    self.compromised_keys.clear();
+                }
                 AddVaultMessage::Load(profile, contacts) => {
                     self.profile = Some(*profile);
                     self.contacts = contacts;
@@ -94,20 +101,36 @@ impl State for AddVaultState {
                     self.public_keys.remove(&public_key);
                 }
                 AddVaultMessage::ErrorChanged(error) => self.error = error,
+                AddVaultMessage::CompromisedKeys(fingerprints) => {
+                    self.compromised_keys = fingerprints
+                }
                 AddVaultMessage::SavePolicy => {
                     let client = ctx.client.clone();
                     let name = self.name.clone();
                     let description = self.description.clone();
                     let descriptor = self.descriptor.clone();
                     let public_keys: Vec<PublicKey> = self.public_keys.iter().copied().collect();
+                    // Ask for a confirmation if the descriptor includes compromised keys
+                    let confirmed: bool = !self.compromised_keys.is_empty();
                     return Command::perform(
                         async move {
+                            if !confirmed {
+                                let fingerprints =
+                                    client.compromised_keys_in_descriptor(&descriptor).await?;
+                                if !fingerprints.is_empty() {
+                                    return Ok(Some(fingerprints));
+                                }
+                            }
                             client
                                 .save_policy(name, description, descriptor, public_keys)
-                                .await
+                                .await?;
+                            Ok::<_, Box<dyn std::error::Error>>(None)
                         },
                         |res| match res {
-                            Ok(_) => Message::View(Stage::Vaults),
+                            Ok(Some(fingerprints)) => {
+                                AddVaultMessage::CompromisedKeys(fingerprints).into()
+                            }
+                            Ok(None) => Message::View(Stage::Vaults),
                             Err(e) => AddVaultMessage::ErrorChanged(Some(e.to_string())).into(),
                         },
                     );
@@ -179,8 +202,31 @@ impl State for AddVaultState {
             Row::new()
         };
 
+        let compromised_keys = if !self.compromised_keys.is_empty() {
+            let fingerprints: Vec<String> = self
+                .compromised_keys
+                .iter()
+                .map(|f| f.to_string())
+                .collect();
+            Row::new().push(
+                Text::new(format!(
+                    "The descriptor includes keys marked as compromised ({}). Save the vault anyway?",
+                    fingerprints.join(", ")
+                ))
+                .color(DARK_RED)
+                .bold()
+                .view(),
+            )
+        } else {
+            Row::new()
+        };
+
         let save_policy_btn = Button::new()
-            .text("Save vault")
+            .text(if self.compromised_keys.is_empty() {
+                "Save vault"
+            } else {
+                "Save anyway"
+            })
             .on_press(AddVaultMessage::SavePolicy.into())
             .width(Length::Fill);
 
@@ -193,7 +239,7 @@ impl State for AddVaultState {
         let policy_builder_btn = Button::new()
             .style(ButtonStyle::Bordered)
             .text("Vault builder")
-            .on_press(Message::View(Stage::VaultBuilder))
+            .on_press(Message::View(Stage::VaultBuilder(None)))
             .width(Length::Fill);
 
         let content = if self.selecting {
@@ -212,6 +258,7 @@ impl State for AddVaultState {
                 .push(description)
                 .push(descriptor)
                 .push(public_keys)
+                .push(compromised_keys)
                 .push(error)
                 .push(Space::with_height(Length::Fixed(15.0)))
                 .push(save_policy_btn.view())
@@ -4,8 +4,10 @@
 use iced::widget::{Column, Row, Space};
 use iced::{Alignment, Command, Element, Length};
 use smartvaults_sdk::core::miniscript::DescriptorPublicKey;
+use smartvaults_sdk::core::signer::SignerType;
 use smartvaults_sdk::core::PolicyTemplate;
 use smartvaults_sdk::nostr::{Profile, PublicKey};
+use smartvaults_sdk::protocol::v1::SignerRequest;
 use smartvaults_sdk::types::{GetAllSigners, GetSharedSigner, GetSigner};
 use smartvaults_sdk::util;
 
@@ -22,6 +24,9 @@ pub enum PolicyBuilderMessage {
     IncreaseThreshold,
     DecreaseThreshold,
     Load((GetAllSigners, Profile)),
+    LoadContact(Box<Profile>, Option<GetSharedSigner>),
+    RequestContactSigner,
+    ContactSignerRequested,
     AddSigner,
     EditSigner(usize, Box<Profile>, Box<DescriptorPublicKey>),
     RemoveSigner(usize),
@@ -41,12 +46,20 @@ pub struct PolicyBuilderState {
     loading: bool,
     loaded: bool,
     selecting_signer: Option<usize>,
+    /// Contact for the 2-of-2 vault shortcut
+    contact: Option<PublicKey>,
+    /// Waiting for the contact to share a signer
+    waiting_contact: Option<Profile>,
+    signer_requested: bool,
     error: Option<String>,
 }
 
 impl PolicyBuilderState {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(contact: Option<PublicKey>) -> Self {
+        Self {
+            contact,
+            ..Default::default()
+        }
     }
 
     fn is_already_selected(&self, descriptor: &DescriptorPublicKey) -> bool {
@@ -118,6 +131,83 @@ impl State for PolicyBuilderState {
                     self.profile = Some(profile);
                     self.loading = false;
                     self.loaded = true;
+
+                    if let Some(public_key) = self.contact {
+                        let client = ctx.client.clone();
+                        return Command::perform(
+                            async move {
+                                let metadata = client
+                                    .get_public_key_metadata(public_key)
+                                    .await
+                                    .unwrap_or_default();
+                                let signer = client.get_default_shared_signer(public_key).await;
+                                (Profile::new(public_key, metadata), signer)
+                            },
+                            |(contact, signer)| match signer {
+                                Ok(signer) => {
+                                    PolicyBuilderMessage::LoadContact(Box::new(contact), signer)
+                                        .into()
+                                }
+                                Err(e) => {
+                                    PolicyBuilderMessage::ErrorChanged(Some(e.to_string())).into()
+                                }
+                            },
+                        );
+                    }
+                }
+                PolicyBuilderMessage::RequestContactSigner => {
+                    if let Some(public_key) = self.contact {
+                        let client = ctx.client.clone();
+                        return Command::perform(
+                            async move {
+                                client
+                                    .request_signer(public_key, SignerRequest::taproot(None))
+                                    .await
+                            },
+                            |res| match res {
+                                Ok(_) => PolicyBuilderMessage::ContactSignerRequested.into(),
+                                Err(e) => {
+                                    PolicyBuilderMessage::ErrorChanged(Some(e.to_string())).into()
+                                }
+                            },
+                        );
+                    }
+                }
+                PolicyBuilderMessage::ContactSignerRequested => self.signer_requested = true,
+                PolicyBuilderMessage::LoadContact(contact, signer) => {
+                    // Pre-fill the 2-of-2 only once, to not override the user changes
+                    if self.policy.is_empty() {
+                        let my = self
+                            .signers
+                            .my
+                            .iter()
+                            .find(|s| s.signer_type() == SignerType::Seed)
+                            .or_else(|| self.signers.my.first());
+                        if let (Some(profile), Some(signer)) = (&self.profile, my) {
+                            if let Ok(desc) = signer.descriptor_public_key() {
+                                self.policy.push(Some((profile.clone(), desc)));
+                            }
+                        }
+                        self.policy.push(None);
+                        self.threshold = self.policy.len();
+                        if self.name.is_empty() {
+                            self.name = format!("2-of-2 with {}", contact.name());
+                        }
+                    }
+
+                    let contact_selected: bool = self.pk_is_already_selected(contact.public_key());
+                    match signer {
+                        Some(GetSharedSigner { shared_signer, .. }) if !contact_selected => {
+                            if let Ok(desc) = shared_signer.descriptor_public_key() {
+                                if let Some(slot) = self.policy.iter_mut().find(|v| v.is_none()) {
+                                    *slot = Some((*contact, desc));
+                                }
+                            }
+                            self.waiting_contact = None;
+                        }
+                        Some(..) => self.waiting_contact = None,
+                        None => self.waiting_contact = Some(*contact),
+                    }
                 }
                 PolicyBuilderMessage::AddSigner => {
                     self.policy.push(None);
@@ -305,6 +395,37 @@ impl State for PolicyBuilderState {
                 .width(Length::Fill)
                 .view();
 
+            let waiting = match &self.waiting_contact {
+                Some(contact) => {
+                    let mut request_btn = Button::new()
+                        .style(ButtonStyle::Bordered)
+                        .text(if self.signer_requested {
+                            "Signer requested"
+                        } else {
+                            "Request signer"
+                        })
+                        .width(Length::Fixed(180.0));
+                    if !self.signer_requested {
+                        request_btn =
+                            request_btn.on_press(PolicyBuilderMessage::RequestContactSigner.into());
+                    }
+                    Row::new()
+                        .push(
+                            Text::new(format!(
+                                "Waiting for {} to share a signer with you",
+                                contact.name()
+                            ))
+                            .extra_light()
+                            .width(Length::Fill)
+                            .view(),
+                        )
+                        .push(request_btn.view())
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                }
+                None => Row::new(),
+            };
+
             let error = if let Some(error) = &self.error {
                 Row::new().push(Text::new(error).color(DARK_RED).view())
             } else {
@@ -338,6 +459,7 @@ impl State for PolicyBuilderState {
                 .push(threshold)
                 .push(pks)
                 .push(add_new_pk_btn)
+                .push(waiting)
                 .push(error)
                 .push(Space::with_height(Length::Fixed(15.0)))
                 .push(save_policy_btn)
@@ -0,0 +1,309 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::HashMap;
+
+use iced::alignment::Horizontal;
+use iced::widget::{Column, Row};
+use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::nostr::{EventId, PublicKey};
+use smartvaults_sdk::types::GetVaultMember;
+use smartvaults_sdk::util;
+
+use crate::app::component::Dashboard;
+use crate::app::{Context, Message, Stage, State};
+use crate::component::{rule, Button, ButtonStyle, Text};
+use crate::theme::color::{DARK_RED, GREEN, RED, YELLOW};
+use crate::theme::icon::{RELOAD, SEND_PENDING};
+
+#[derive(Debug, Clone)]
+pub enum VaultMembersMessage {
+    LoadMembers(Vec<GetVaultMember>, HashMap<PublicKey, bool>),
+    ResendInvitation(PublicKey),
+    ErrorChanged(Option<String>),
+    Reload,
+}
+
+#[derive(Debug)]
+pub struct VaultMembersState {
+    loading: bool,
+    loaded: bool,
+    vault_id: EventId,
+    members: Vec<GetVaultMember>,
+    /// NIP-05 verification results (missing if the identifier couldn't be checked)
+    nip05: HashMap<PublicKey, bool>,
+    error: Option<String>,
+}
+
+impl VaultMembersState {
+    pub fn new(vault_id: EventId) -> Self {
+        Self {
+            loading: false,
+            loaded: false,
+            vault_id,
+            members: Vec::new(),
+            nip05: HashMap::new(),
+            error: None,
+        }
+    }
+}
+
+impl State for VaultMembersState {
+    fn title(&self) -> String {
+        format!("Members of vault #{}", util::cut_event_id(self.vault_id))
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.loading = true;
+        let client = ctx.client.clone();
+        let vault_id = self.vault_id;
+        Command::perform(
+            async move {
+                let members = client.get_vault_members(vault_id).await?;
+                let mut nip05 = HashMap::new();
+                for member in members.iter() {
+                    let public_key = member.user.public_key();
+                    if let Some(identifier) = member.user.metadata().nip05 {
+                        match client.verify_nip05(public_key, identifier).await {
+                            Ok(verified) => {
+                                nip05.insert(public_key, verified);
+                            }
+                            Err(e) => tracing::warn!("Impossible to verify NIP-05: {e}"),
+                        }
+                    }
+                }
+                Ok::<_, smartvaults_sdk::Error>((members, nip05))
+            },
+            |res| match res {
+                Ok((members, nip05)) => VaultMembersMessage::LoadMembers(members, nip05).into(),
+                Err(e) => VaultMembersMessage::ErrorChanged(Some(e.to_string())).into(),
+            },
+        )
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if !self.loaded && !self.loading {
+            return self.load(ctx);
+        }
+
+        if let Message::VaultMembers(msg) = message {
+            match msg {
+                VaultMembersMessage::LoadMembers(members, nip05) => {
+                    self.members = members;
+                    self.nip05 = nip05;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                VaultMembersMessage::ResendInvitation(public_key) => {
+                    self.loading = true;
+                    let client = ctx.client.clone();
+                    let vault_id = self.vault_id;
+                    return Command::perform(
+                        async move { client.resend_vault_invitation(vault_id, public_key).await },
+                        |res| match res {
+                            Ok(_) => VaultMembersMessage::Reload.into(),
+                            Err(e) => VaultMembersMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    );
+                }
+                VaultMembersMessage::ErrorChanged(e) => {
+                    self.error = e;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                VaultMembersMessage::Reload => return self.load(ctx),
+            }
+        }
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let mut content = Column::new().spacing(10).padding(20);
+
+        if self.loaded {
+            content = content
+                .push(
+                    Row::new()
+                        .push(Text::new("Member").bold().big().width(Length::Fill).view())
+                        .push(
+                            Text::new("NIP-05")
+                                .bold()
+                                .big()
+                                .width(Length::Fixed(250.0))
+                                .view(),
+                        )
+                        .push(
+                            Text::new("Role")
+                                .bold()
+                                .big()
+                                .horizontal_alignment(Horizontal::Center)
+                                .width(Length::Fixed(120.0))
+                                .view(),
+                        )
+                        .push(
+                            Text::new("Last activity")
+                                .bold()
+                                .big()
+                                .horizontal_alignment(Horizontal::Center)
+                                .width(Length::Fixed(200.0))
+                                .view(),
+                        )
+                        .push(
+                            Text::new("Status")
+                                .bold()
+                                .big()
+                                .horizontal_alignment(Horizontal::Center)
+                                .width(Length::Fixed(150.0))
+                                .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .icon(RELOAD)
+                                .style(ButtonStyle::Bordered)
+                                .on_press(VaultMembersMessage::Reload.into())
+                                .loading(self.loading)
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal_bold());
+
+            let my_public_key: PublicKey = ctx.client.keys().public_key();
+            for member in self.members.iter() {
+                let public_key: PublicKey = member.user.public_key();
+                let mut name: String = member.user.name();
+                if public_key == my_public_key {
+                    name.push_str(" (you)");
+                }
+
+                let nip05 = match member.user.metadata().nip05 {
+                    Some(identifier) => match self.nip05.get(&public_key) {
+                        Some(true) => Text::new(identifier).color(GREEN),
+                        Some(false) => Text::new(format!("{identifier} (invalid)")).color(RED),
+                        None => Text::new(format!("{identifier} (unverified)")),
+                    },
+                    None => Text::new("-"),
+                };
+
+                let role: String = if member.is_cosigner() {
+                    format!("Cosigner ({})", member.signers.len())
+                } else {
+                    String::from("Member")
+                };
+
+                let status = if !member.is_pending() {
+                    Text::new("Active").color(GREEN)
+                } else if member.shared_key_requested {
+                    Text::new("Key requested").color(YELLOW)
+                } else {
+                    Text::new("Pending").color(YELLOW)
+                };
+
+                let mut resend_btn = Button::new()
+                    .icon(SEND_PENDING)
+                    .loading(self.loading)
+                    .style(ButtonStyle::Bordered)
+                    .width(Length::Fixed(40.0));
+                if member.is_pending() && public_key != my_public_key {
+                    resend_btn = resend_btn
+                        .on_press(VaultMembersMessage::ResendInvitation(public_key).into());
+                }
+
+                let row = Row::new()
+                    .push(
+                        Column::new()
+                            .push(Text::new(name).view())
+                            .push(
+                                Text::new(util::cut_public_key(public_key))
+                                    .extra_light()
+                                    .view(),
+                            )
+                            .spacing(5)
+                            .width(Length::Fill),
+                    )
+                    .push(nip05.width(Length::Fixed(250.0)).view())
+                    .push(
+                        Text::new(role)
+                            .horizontal_alignment(Horizontal::Center)
+                            .width(Length::Fixed(120.0))
+                            .view(),
+                    )
+                    .push(
+                        Text::new(
+                            member
+                                .last_activity
+                                .map(|t| t.to_human_datetime())
+                                .unwrap_or_else(|| String::from("-")),
+                        )
+                        .horizontal_alignment(Horizontal::Center)
+                        .width(Length::Fixed(200.0))
+                        .view(),
+                    )
+                    .push(
+                        status
+                            .horizontal_alignment(Horizontal::Center)
+                            .width(Length::Fixed(150.0))
+                            .view(),
+                    )
+                    .push(resend_btn.view())
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .width(Length::Fill);
+                content = content.push(row).push(rule::horizontal());
+            }
+
+            content = content
+                .push(
+                    Text::new(
+                        "The members are part of the vault descriptor: to invite or remove a member, \
+                        create a new vault with the updated members and move the funds with a self transfer.",
+                    )
+                    .extra_light()
+                    .view(),
+                )
+                .push(
+                    Row::new()
+                        .push(
+                            Button::new()
+                                .text("New vault")
+                                .on_press(Message::View(Stage::VaultBuilder(None)))
+                                .width(Length::Fixed(250.0))
+                                .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .text("Self transfer")
+                                .style(ButtonStyle::Bordered)
+                                .on_press(Message::View(Stage::SelfTransfer))
+                                .width(Length::Fixed(250.0))
+                                .view(),
+                        )
+                        .spacing(10),
+                );
+
+            if let Some(error) = &self.error {
+                content = content.push(Text::new(error).color(DARK_RED).view());
+            }
+        }
+
+        Dashboard::new()
+            .loaded(self.loaded)
+            .view(ctx, content, true, false)
+    }
+}
+
+impl From<VaultMembersState> for Box<dyn State> {
+    fn from(s: VaultMembersState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<VaultMembersMessage> for Message {
+    fn from(msg: VaultMembersMessage) -> Self {
+        Self::VaultMembers(msg)
+    }
+}
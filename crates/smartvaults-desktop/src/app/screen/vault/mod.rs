@@ -6,35 +6,49 @@ use std::collections::BTreeSet;
 use iced::widget::{Column, Row, Space};
 use iced::{Alignment, Command, Element, Length};
 use rfd::FileDialog;
+use smartvaults_sdk::core::bitcoin::bip32::Fingerprint;
 use smartvaults_sdk::core::signer::Signer;
 use smartvaults_sdk::nostr::EventId;
-use smartvaults_sdk::types::{GetPolicy, GetProposal, GetTransaction};
+use smartvaults_sdk::types::{GetMemberContributions, GetPolicy, GetProposal, GetTransaction};
 use smartvaults_sdk::util;
 
 pub mod add;
 pub mod builder;
+pub mod members;
 pub mod restore;
 pub mod tree;
+pub mod utxos;
 pub mod vaults;
+pub mod whitelist;
 
 use crate::app::component::{Activity, Balances, Dashboard};
-use crate::app::{Context, Message, Stage, State};
-use crate::component::{rule, Button, ButtonStyle, Text};
+use crate::app::{print, Context, Message, Stage, State};
+use crate::component::{rule, Amount, Button, ButtonStyle, Text};
 use crate::theme::color::RED;
-use crate::theme::icon::{BINOCULARS, CLIPBOARD, GLOBE, PATCH_CHECK, SAVE, TRASH};
+use crate::theme::icon::{
+    BINOCULARS, BOX, CHECK_CIRCLE, CLIPBOARD, EXPORT, GLOBE, LIST, PATCH_CHECK, PEOPLE, PRINTER,
+    SAVE, TRASH,
+};
 
 #[derive(Debug, Clone)]
 pub enum VaultMessage {
     Send,
     Deposit,
     NewProofOfReserve,
+    BatchSpend,
     SavePolicyBackup,
+    ExportColdcardConfig,
+    ExportEvents,
+    PrintReport,
+    PrintRecoveryKit,
     Delete,
     LoadPolicy(
         GetPolicy,
         Vec<GetProposal>,
         Option<Signer>,
         BTreeSet<GetTransaction>,
+        Option<GetMemberContributions>,
+        BTreeSet<Fingerprint>,
     ),
     ErrorChanged(Option<String>),
     Reload,
@@ -50,6 +64,8 @@ pub struct VaultState {
     proposals: Vec<GetProposal>,
     signer: Option<Signer>,
     transactions: BTreeSet<GetTransaction>,
+    contributions: Option<GetMemberContributions>,
+    compromised_keys: BTreeSet<Fingerprint>,
     error: Option<String>,
 }
 
@@ -63,6 +79,8 @@ impl VaultState {
             proposals: Vec::new(),
             signer: None,
             transactions: BTreeSet::new(),
+            contributions: None,
+            compromised_keys: BTreeSet::new(),
             error: None,
         }
     }
@@ -90,11 +108,31 @@ impl State for VaultState {
                     .search_signer_by_descriptor(policy.policy.descriptor())
                     .await
                     .ok();
-                Some((policy, proposals, signer, list))
+                let contributions = client.get_member_contributions(policy_id).await.ok();
+                let compromised_keys = client
+                    .get_vault_compromised_keys(policy_id)
+                    .await
+                    .unwrap_or_default();
+                Some((
+                    policy,
+                    proposals,
+                    signer,
+                    list,
+                    contributions,
+                    compromised_keys,
+                ))
             },
             |res| match res {
-                Some((policy, proposals, signer, list)) => {
-                    VaultMessage::LoadPolicy(policy, proposals, signer, list).into()
+                Some((policy, proposals, signer, list, contributions, compromised_keys)) => {
+                    VaultMessage::LoadPolicy(
+                        policy,
+                        proposals,
+                        signer,
+                        list,
+                        contributions,
+                        compromised_keys,
+                    )
+                    .into()
                 }
                 None => Message::View(Stage::Vaults),
             },
@@ -129,6 +167,13 @@ impl State for VaultState {
                         None => Message::View(Stage::Vaults),
                     });
                 }
+                VaultMessage::BatchSpend => {
+                    let policy = self.policy.clone();
+                    return Command::perform(async {}, move |_| match policy {
+                        Some(policy) => Message::View(Stage::BatchSpend(Some(policy))),
+                        None => Message::View(Stage::Vaults),
+                    });
+                }
                 VaultMessage::SavePolicyBackup => {
                     let path = FileDialog::new()
                         .set_title("Export policy backup")
@@ -150,6 +195,77 @@ impl State for VaultState {
                         );
                     }
                 }
+                VaultMessage::ExportColdcardConfig => {
+                    if let Some(policy) = &self.policy {
+                        match policy.policy.coldcard_config() {
+                            Ok(config) => {
+                                let path = FileDialog::new()
+                                    .set_title("Export Coldcard config")
+                                    .set_file_name(format!(
+                                        "coldcard-{}.txt",
+                                        util::cut_event_id(self.policy_id)
+                                    ))
+                                    .save_file();
+
+                                if let Some(path) = path {
+                                    if let Err(e) = std::fs::write(path, config) {
+                                        self.error = Some(e.to_string());
+                                    }
+                                }
+                            }
+                            Err(e) => self.error = Some(e.to_string()),
+                        }
+                    }
+                }
+                VaultMessage::ExportEvents => {
+                    let path = FileDialog::new()
+                        .set_title("Export vault events")
+                        .set_file_name(format!(
+                            "events-{}.jsonl",
+                            util::cut_event_id(self.policy_id)
+                        ))
+                        .save_file();
+
+                    if let Some(path) = path {
+                        let policy_id = self.policy_id;
+                        let client = ctx.client.clone();
+                        return Command::perform(
+                            async move { client.export_vault_events(policy_id, path, None).await },
+                            move |res| match res {
+                                Ok(_) => VaultMessage::ErrorChanged(None).into(),
+                                Err(e) => VaultMessage::ErrorChanged(Some(e.to_string())).into(),
+                            },
+                        );
+                    }
+                }
+                VaultMessage::PrintReport => {
+                    if let Some(policy) = &self.policy {
+                        let body: String = print::vault_report(policy, &self.transactions);
+                        let name: String = format!("report-{}", util::cut_event_id(self.policy_id));
+                        if let Err(e) = print::open(&name, &policy.policy.name(), body) {
+                            self.error = Some(e.to_string());
+                        }
+                    }
+                }
+                VaultMessage::PrintRecoveryKit => {
+                    let client = ctx.client.clone();
+                    let policy_id = self.policy_id;
+                    return Command::perform(
+                        async move {
+                            let kit = client.recovery_kit(policy_id).await?;
+                            print::open(
+                                &format!("recovery-kit-{}", util::cut_event_id(policy_id)),
+                                &kit.name,
+                                print::recovery_kit(&kit),
+                            )?;
+                            Ok::<(), Box<dyn std::error::Error>>(())
+                        },
+                        |res| match res {
+                            Ok(_) => VaultMessage::ErrorChanged(None).into(),
+                            Err(e) => VaultMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    );
+                }
                 VaultMessage::Delete => {
                     let client = ctx.client.clone();
                     let policy_id = self.policy_id;
@@ -177,11 +293,20 @@ impl State for VaultState {
                         );
                     }
                 }
-                VaultMessage::LoadPolicy(policy, proposals, signer, list) => {
+                VaultMessage::LoadPolicy(
+                    policy,
+                    proposals,
+                    signer,
+                    list,
+                    contributions,
+                    compromised_keys,
+                ) => {
                     self.policy = Some(policy);
                     self.proposals = proposals;
                     self.signer = signer;
                     self.transactions = list;
+                    self.contributions = contributions;
+                    self.compromised_keys = compromised_keys;
                     self.loading = false;
                     self.loaded = true;
                 }
@@ -217,6 +342,23 @@ impl State for VaultState {
 
         if is_ready {
             if let Some(policy) = &self.policy {
+                if !self.compromised_keys.is_empty() {
+                    let fingerprints: Vec<String> = self
+                        .compromised_keys
+                        .iter()
+                        .map(|f| f.to_string())
+                        .collect();
+                    content = content.push(
+                        Text::new(format!(
+                            "Warning: this vault includes keys marked as compromised ({}). Move the funds to a new vault.",
+                            fingerprints.join(", ")
+                        ))
+                        .color(RED)
+                        .bold()
+                        .view(),
+                    );
+                }
+
                 content = content
                     .push(Space::with_height(Length::Fixed(20.0)))
                     .push(
@@ -233,6 +375,24 @@ impl State for VaultState {
                                         ))
                                         .view(),
                                     )
+                                    .push(
+                                        Text::new(format!(
+                                            "Fingerprint: {}",
+                                            policy
+                                                .policy
+                                                .descriptor_fingerprint()
+                                                .unwrap_or_else(|_| String::from("Unavailable"))
+                                        ))
+                                        .view(),
+                                    )
+                                    .push(
+                                        Text::new(
+                                            "Compare the fingerprint with your cosigners before depositing",
+                                        )
+                                        .extra_light()
+                                        .size(16)
+                                        .view(),
+                                    )
                                     .push(
                                         Text::new(format!(
                                             "Signer: {}",
@@ -265,6 +425,14 @@ impl State for VaultState {
                                                     .width(Length::Fixed(40.0))
                                                     .view(),
                                             )
+                                            .push(
+                                                Button::new()
+                                                    .style(ButtonStyle::Bordered)
+                                                    .icon(LIST)
+                                                    .on_press(VaultMessage::BatchSpend.into())
+                                                    .width(Length::Fixed(40.0))
+                                                    .view(),
+                                            )
                                             .push(
                                                 Button::new()
                                                     .style(ButtonStyle::Bordered)
@@ -273,6 +441,32 @@ impl State for VaultState {
                                                     .width(Length::Fixed(40.0))
                                                     .view(),
                                             )
+                                            .push(
+                                                Button::new()
+                                                    .style(ButtonStyle::Bordered)
+                                                    .icon(EXPORT)
+                                                    .on_press(
+                                                        VaultMessage::ExportColdcardConfig.into(),
+                                                    )
+                                                    .width(Length::Fixed(40.0))
+                                                    .view(),
+                                            )
+                                            .push(
+                                                Button::new()
+                                                    .style(ButtonStyle::Bordered)
+                                                    .icon(PRINTER)
+                                                    .on_press(VaultMessage::PrintReport.into())
+                                                    .width(Length::Fixed(40.0))
+                                                    .view(),
+                                            )
+                                            .push(
+                                                Button::new()
+                                                    .style(ButtonStyle::Bordered)
+                                                    .icon(BOX)
+                                                    .on_press(VaultMessage::ExportEvents.into())
+                                                    .width(Length::Fixed(40.0))
+                                                    .view(),
+                                            )
                                             .push(
                                                 Button::new()
                                                     .style(ButtonStyle::Bordered)
@@ -284,6 +478,39 @@ impl State for VaultState {
                                                     .loading(self.loading)
                                                     .view(),
                                             )
+                                            .push(
+                                                Button::new()
+                                                    .style(ButtonStyle::Bordered)
+                                                    .icon(PEOPLE)
+                                                    .width(Length::Fixed(40.0))
+                                                    .on_press(Message::View(Stage::VaultMembers(
+                                                        self.policy_id,
+                                                    )))
+                                                    .loading(self.loading)
+                                                    .view(),
+                                            )
+                                            .push(
+                                                Button::new()
+                                                    .style(ButtonStyle::Bordered)
+                                                    .icon(CHECK_CIRCLE)
+                                                    .width(Length::Fixed(40.0))
+                                                    .on_press(Message::View(Stage::VaultWhitelist(
+                                                        self.policy_id,
+                                                    )))
+                                                    .loading(self.loading)
+                                                    .view(),
+                                            )
+                                            .push(
+                                                Button::new()
+                                                    .style(ButtonStyle::Bordered)
+                                                    .icon(LIST)
+                                                    .width(Length::Fixed(40.0))
+                                                    .on_press(Message::View(Stage::VaultUtxos(
+                                                        self.policy_id,
+                                                    )))
+                                                    .loading(self.loading)
+                                                    .view(),
+                                            )
                                             .push(
                                                 Button::new()
                                                     .style(ButtonStyle::Bordered)
@@ -306,8 +533,17 @@ impl State for VaultState {
                                             )
                                             .spacing(10),
                                     )
+                                    .push(
+                                        Button::new()
+                                            .style(ButtonStyle::Bordered)
+                                            .icon(PRINTER)
+                                            .text("Print recovery kit")
+                                            .on_press(VaultMessage::PrintRecoveryKit.into())
+                                            .loading(self.loading)
+                                            .view(),
+                                    )
                                     .spacing(10)
-                                    .max_width(300),
+                                    .max_width(350),
                             )
                             .push(Space::with_width(Length::Fixed(10.0)))
                             .push(
@@ -334,6 +570,16 @@ impl State for VaultState {
                         Text::new("").view()
                     });
 
+                if let Some(contributions) = &self.contributions {
+                    if contributions.total() > 0 {
+                        content = content
+                            .push(Space::with_height(Length::Fixed(20.0)))
+                            .push(Text::new("Contributions").bold().big().view())
+                            .push(Space::with_height(Length::Fixed(5.0)))
+                            .push(contributions_view(contributions, ctx.hide_balances));
+                    }
+                }
+
                 content = content
                     .push(Space::with_height(Length::Fixed(20.0)))
                     .push(Text::new("Activity").bold().big().view())
@@ -352,6 +598,46 @@ impl State for VaultState {
     }
 }
 
+fn contributions_view(
+    contributions: &GetMemberContributions,
+    hide_balances: bool,
+) -> Element<'static, Message> {
+    let row = |name: String, deposits: usize, amount: u64| {
+        Row::new()
+            .push(Text::new(name).width(Length::Fill).view())
+            .push(
+                Text::new(format!("{deposits} deposits"))
+                    .small()
+                    .extra_light()
+                    .width(Length::Fixed(125.0))
+                    .view(),
+            )
+            .push(Amount::new(amount).hidden(hide_balances).view())
+            .spacing(10)
+            .align_items(Alignment::Center)
+    };
+
+    let mut list = Column::new().spacing(10);
+
+    for member in contributions.members.iter() {
+        list = list.push(row(
+            member.user.name(),
+            member.deposits.len(),
+            member.amount,
+        ));
+    }
+
+    if contributions.unattributed > 0 {
+        list = list.push(row(
+            String::from("Unattributed"),
+            contributions.unattributed_deposits.len(),
+            contributions.unattributed,
+        ));
+    }
+
+    list.into()
+}
+
 impl From<VaultState> for Box<dyn State> {
     fn from(s: VaultState) -> Box<dyn State> {
         Box::new(s)
@@ -0,0 +1,463 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::HashSet;
+use std::fmt;
+
+use iced::widget::{Checkbox, Column, PickList, Row, Space};
+use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::core::bdk::chain::ConfirmationTime;
+use smartvaults_sdk::core::bitcoin::{Address, Network, OutPoint};
+use smartvaults_sdk::nostr::EventId;
+use smartvaults_sdk::types::GetUtxo;
+use smartvaults_sdk::util::{self, format};
+
+use crate::app::component::Dashboard;
+use crate::app::{Context, Message, State};
+use crate::component::{rule, Button, ButtonStyle, Text, TextInput};
+use crate::theme::color::DARK_RED;
+use crate::theme::icon::{LOCK, RELOAD};
+
+const SORTS: [UtxoSort; 4] = [
+    UtxoSort::AmountDesc,
+    UtxoSort::AmountAsc,
+    UtxoSort::Oldest,
+    UtxoSort::Label,
+];
+
+const FILTERS: [FrozenFilter; 3] = [
+    FrozenFilter::All,
+    FrozenFilter::Frozen,
+    FrozenFilter::Unfrozen,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UtxoSort {
+    AmountDesc,
+    AmountAsc,
+    Oldest,
+    Label,
+}
+
+impl fmt::Display for UtxoSort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AmountDesc => write!(f, "Largest first"),
+            Self::AmountAsc => write!(f, "Smallest first"),
+            Self::Oldest => write!(f, "Oldest first"),
+            Self::Label => write!(f, "Label"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrozenFilter {
+    All,
+    Frozen,
+    Unfrozen,
+}
+
+impl fmt::Display for FrozenFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => write!(f, "All"),
+            Self::Frozen => write!(f, "Frozen"),
+            Self::Unfrozen => write!(f, "Not frozen"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum VaultUtxosMessage {
+    LoadUtxos(Vec<GetUtxo>),
+    SearchChanged(String),
+    SortSelected(UtxoSort),
+    FilterSelected(FrozenFilter),
+    ToggleUtxo(OutPoint, bool),
+    SelectAll(bool),
+    Freeze,
+    Unfreeze,
+    ErrorChanged(Option<String>),
+    Reload,
+}
+
+#[derive(Debug)]
+pub struct VaultUtxosState {
+    loading: bool,
+    loaded: bool,
+    vault_id: EventId,
+    utxos: Vec<GetUtxo>,
+    selected: HashSet<OutPoint>,
+    search: String,
+    sort: UtxoSort,
+    filter: FrozenFilter,
+    error: Option<String>,
+}
+
+impl VaultUtxosState {
+    pub fn new(vault_id: EventId) -> Self {
+        Self {
+            loading: false,
+            loaded: false,
+            vault_id,
+            utxos: Vec::new(),
+            selected: HashSet::new(),
+            search: String::new(),
+            sort: UtxoSort::AmountDesc,
+            filter: FrozenFilter::All,
+            error: None,
+        }
+    }
+
+    /// UTXOs matching the search and the filter, sorted
+    fn visible_utxos(&self, network: Network) -> Vec<(&GetUtxo, Option<Address>)> {
+        let search: String = self.search.trim().to_lowercase();
+        let mut utxos: Vec<(&GetUtxo, Option<Address>)> = self
+            .utxos
+            .iter()
+            .filter(|u| match self.filter {
+                FrozenFilter::All => true,
+                FrozenFilter::Frozen => u.frozen,
+                FrozenFilter::Unfrozen => !u.frozen,
+            })
+            .map(|u| {
+                let address = Address::from_script(&u.txout.script_pubkey, network).ok();
+                (u, address)
+            })
+            .filter(|(u, address)| {
+                search.is_empty()
+                    || u.outpoint.to_string().contains(&search)
+                    || u.label
+                        .as_ref()
+                        .map(|l| l.to_lowercase().contains(&search))
+                        .unwrap_or_default()
+                    || address
+                        .as_ref()
+                        .map(|a| a.to_string().to_lowercase().contains(&search))
+                        .unwrap_or_default()
+            })
+            .collect();
+
+        match self.sort {
+            UtxoSort::AmountDesc => {
+                utxos.sort_by(|(a, _), (b, _)| b.txout.value.cmp(&a.txout.value))
+            }
+            UtxoSort::AmountAsc => {
+                utxos.sort_by(|(a, _), (b, _)| a.txout.value.cmp(&b.txout.value))
+            }
+            UtxoSort::Oldest => utxos.sort_by_key(|(u, _)| match u.confirmation_time {
+                ConfirmationTime::Confirmed { height, .. } => height,
+                ConfirmationTime::Unconfirmed { .. } => u32::MAX,
+            }),
+            UtxoSort::Label => utxos.sort_by(|(a, _), (b, _)| match (&a.label, &b.label) {
+                (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }),
+        }
+
+        utxos
+    }
+}
+
+impl State for VaultUtxosState {
+    fn title(&self) -> String {
+        format!("UTXOs of vault #{}", util::cut_event_id(self.vault_id))
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.loading = true;
+        let client = ctx.client.clone();
+        let vault_id = self.vault_id;
+        Command::perform(
+            async move { client.get_utxos(vault_id).await },
+            |res| match res {
+                Ok(utxos) => VaultUtxosMessage::LoadUtxos(utxos).into(),
+                Err(e) => VaultUtxosMessage::ErrorChanged(Some(e.to_string())).into(),
+            },
+        )
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if !self.loaded && !self.loading {
+            return self.load(ctx);
+        }
+
+        if let Message::VaultUtxos(msg) = message {
+            let client = ctx.client.clone();
+            let vault_id = self.vault_id;
+            match msg {
+                VaultUtxosMessage::LoadUtxos(utxos) => {
+                    // Keep selected only the UTXOs still available
+                    self.selected
+                        .retain(|outpoint| utxos.iter().any(|u| u.outpoint == *outpoint));
+                    self.utxos = utxos;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                VaultUtxosMessage::SearchChanged(search) => self.search = search,
+                VaultUtxosMessage::SortSelected(sort) => self.sort = sort,
+                VaultUtxosMessage::FilterSelected(filter) => self.filter = filter,
+                VaultUtxosMessage::ToggleUtxo(outpoint, value) => {
+                    if value {
+                        self.selected.insert(outpoint);
+                    } else {
+                        self.selected.remove(&outpoint);
+                    }
+                }
+                VaultUtxosMessage::SelectAll(value) => {
+                    if value {
+                        let network = ctx.client.network();
+                        let visible: Vec<OutPoint> = self
+                            .visible_utxos(network)
+                            .into_iter()
+                            .map(|(u, _)| u.outpoint)
+                            .collect();
+                        self.selected.extend(visible);
+                    } else {
+                        self.selected.clear();
+                    }
+                }
+                VaultUtxosMessage::Freeze => {
+                    self.loading = true;
+                    let utxos = self.selected.clone();
+                    return Command::perform(
+                        async move { client.freeze_utxos(vault_id, utxos).await },
+                        |res| match res {
+                            Ok(_) => VaultUtxosMessage::Reload.into(),
+                            Err(e) => VaultUtxosMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    );
+                }
+                VaultUtxosMessage::Unfreeze => {
+                    self.loading = true;
+                    let utxos = self.selected.clone();
+                    return Command::perform(
+                        async move { client.unfreeze_utxos(vault_id, utxos).await },
+                        |res| match res {
+                            Ok(_) => VaultUtxosMessage::Reload.into(),
+                            Err(e) => VaultUtxosMessage::ErrorChanged(Some(e.to_string())).into(),
+                        },
+                    );
+                }
+                VaultUtxosMessage::ErrorChanged(e) => {
+                    self.error = e;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                VaultUtxosMessage::Reload => {
+                    self.error = None;
+                    return self.load(ctx);
+                }
+            }
+        }
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let mut content = Column::new().spacing(10).padding(20);
+
+        if self.loaded {
+            let network = ctx.client.network();
+            let block_height: u32 = ctx.client.block_height();
+            let utxos = self.visible_utxos(network);
+            let all_selected: bool = !utxos.is_empty()
+                && utxos
+                    .iter()
+                    .all(|(u, _)| self.selected.contains(&u.outpoint));
+
+            let mut freeze_btn = Button::new()
+                .icon(LOCK)
+                .text("Freeze selected")
+                .style(ButtonStyle::Bordered)
+                .loading(self.loading);
+            let mut unfreeze_btn = Button::new()
+                .text("Unfreeze selected")
+                .style(ButtonStyle::Bordered)
+                .loading(self.loading);
+            if !self.selected.is_empty() {
+                freeze_btn = freeze_btn.on_press(VaultUtxosMessage::Freeze.into());
+                unfreeze_btn = unfreeze_btn.on_press(VaultUtxosMessage::Unfreeze.into());
+            }
+
+            content = content
+                .push(
+                    Row::new()
+                        .push(
+                            TextInput::new(&self.search)
+                                .on_input(|s| VaultUtxosMessage::SearchChanged(s).into())
+                                .placeholder("Search by outpoint, label or address")
+                                .view(),
+                        )
+                        .push(
+                            PickList::new(&FILTERS[..], Some(self.filter), |filter| {
+                                VaultUtxosMessage::FilterSelected(filter).into()
+                            })
+                            .width(Length::Fixed(150.0))
+                            .padding(10),
+                        )
+                        .push(
+                            PickList::new(&SORTS[..], Some(self.sort), |sort| {
+                                VaultUtxosMessage::SortSelected(sort).into()
+                            })
+                            .width(Length::Fixed(180.0))
+                            .padding(10),
+                        )
+                        .push(
+                            Button::new()
+                                .icon(RELOAD)
+                                .style(ButtonStyle::Bordered)
+                                .on_press(VaultUtxosMessage::Reload.into())
+                                .loading(self.loading)
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(
+                    Row::new()
+                        .push(
+                            Text::new(format!("{} selected", self.selected.len()))
+                                .extra_light()
+                                .width(Length::Fill)
+                                .view(),
+                        )
+                        .push(freeze_btn.view())
+                        .push(unfreeze_btn.view())
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal_bold())
+                .push(
+                    Row::new()
+                        .push(
+                            Checkbox::new("", all_selected, |value| {
+                                VaultUtxosMessage::SelectAll(value).into()
+                            })
+                            .width(Length::Fixed(30.0)),
+                        )
+                        .push(
+                            Text::new("UTXO")
+                                .bold()
+                                .big()
+                                .width(Length::Fixed(180.0))
+                                .view(),
+                        )
+                        .push(Text::new("Amount").bold().big().width(Length::Fill).view())
+                        .push(Text::new("Age").bold().big().width(Length::Fill).view())
+                        .push(Text::new("Label").bold().big().width(Length::Fill).view())
+                        .push(
+                            Text::new("Address")
+                                .bold()
+                                .big()
+                                .width(Length::FillPortion(2))
+                                .view(),
+                        )
+                        .push(
+                            Text::new("Status")
+                                .bold()
+                                .big()
+                                .width(Length::Fixed(120.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal());
+
+            if utxos.is_empty() {
+                content = content.push(Text::new("No UTXOs").view());
+            }
+
+            for (utxo, address) in utxos.into_iter() {
+                let outpoint: OutPoint = utxo.outpoint;
+                let txid: String = outpoint.txid.to_string();
+                let age: String = match utxo.confirmation_time {
+                    ConfirmationTime::Confirmed { height, .. } => {
+                        let confirmations: u32 = block_height.saturating_sub(height) + 1;
+                        format!("{} blocks", format::number(confirmations as u64))
+                    }
+                    ConfirmationTime::Unconfirmed { .. } => String::from("Pending"),
+                };
+                let status: &str = if utxo.user_frozen {
+                    "Frozen"
+                } else if utxo.frozen {
+                    "In a proposal"
+                } else {
+                    "-"
+                };
+
+                let row = Row::new()
+                    .push(
+                        Checkbox::new("", self.selected.contains(&outpoint), move |value| {
+                            VaultUtxosMessage::ToggleUtxo(outpoint, value).into()
+                        })
+                        .width(Length::Fixed(30.0)),
+                    )
+                    .push(
+                        Text::new(format!(
+                            "{}..{}:{}",
+                            &txid[..8],
+                            &txid[txid.len() - 8..],
+                            outpoint.vout,
+                        ))
+                        .width(Length::Fixed(180.0))
+                        .view(),
+                    )
+                    .push(
+                        Text::new(format!("{} sat", format::number(utxo.txout.value)))
+                            .width(Length::Fill)
+                            .view(),
+                    )
+                    .push(Text::new(age).width(Length::Fill).view())
+                    .push(
+                        Text::new(utxo.label.as_deref().unwrap_or("-"))
+                            .width(Length::Fill)
+                            .view(),
+                    )
+                    .push(
+                        Text::new(
+                            address
+                                .map(|a| a.to_string())
+                                .unwrap_or_else(|| String::from("-")),
+                        )
+                        .width(Length::FillPortion(2))
+                        .view(),
+                    )
+                    .push(Text::new(status).width(Length::Fixed(120.0)).view())
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .width(Length::Fill);
+                content = content.push(row).push(rule::horizontal());
+            }
+
+            content = content.push(Space::with_height(Length::Fixed(10.0)));
+
+            if let Some(error) = &self.error {
+                content = content.push(Text::new(error).color(DARK_RED).view());
+            }
+        }
+
+        Dashboard::new()
+            .loaded(self.loaded)
+            .view(ctx, content, true, false)
+    }
+}
+
+impl From<VaultUtxosState> for Box<dyn State> {
+    fn from(s: VaultUtxosState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<VaultUtxosMessage> for Message {
+    fn from(msg: VaultUtxosMessage) -> Self {
+        Self::VaultUtxos(msg)
+    }
+}
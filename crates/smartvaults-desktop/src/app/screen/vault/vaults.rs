@@ -12,13 +12,15 @@ use smartvaults_sdk::util;
 
 use crate::app::component::Dashboard;
 use crate::app::{Context, Message, Stage, State};
-use crate::component::{rule, Amount, Button, ButtonStyle, SpinnerLinear, Text};
-use crate::theme::icon::{FULLSCREEN, PLUS, RELOAD, SAVE};
+use crate::component::{rule, Amount, Button, ButtonStyle, Icon, SpinnerLinear, Text};
+use crate::theme::color::ORANGE;
+use crate::theme::icon::{FULLSCREEN, PATCH_EXCLAMATION, PLUS, RELOAD, SAVE, STAR, STAR_FILL};
 
 #[derive(Debug, Clone)]
 pub enum PoliciesMessage {
     LoadPolicies(Vec<GetPolicy>),
     SavePolicyBackup(EventId),
+    TogglePin(EventId, bool),
     Reload,
 }
 
@@ -79,6 +81,19 @@ impl State for PoliciesState {
                     }
                     Command::none()
                 }
+                PoliciesMessage::TogglePin(policy_id, pinned) => {
+                    let client = ctx.client.clone();
+                    Command::perform(
+                        async move {
+                            if pinned {
+                                client.unpin_vault(policy_id).await
+                            } else {
+                                client.pin_vault(policy_id).await
+                            }
+                        },
+                        |_| PoliciesMessage::Reload.into(),
+                    )
+                }
                 PoliciesMessage::Reload => self.load(ctx),
             }
         } else {
@@ -120,6 +135,7 @@ impl State for PoliciesState {
                         Row::new()
                             .push(Text::new("ID").bold().width(Length::Fixed(115.0)).view())
                             .push(Text::new("Name").bold().width(Length::Fill).view())
+                            .push(Space::with_width(Length::Fixed(40.0)))
                             .push(Text::new("Balance").bold().width(Length::Fill).view())
                             .push(
                                 Button::new()
@@ -149,6 +165,9 @@ impl State for PoliciesState {
                     policy,
                     balance,
                     last_sync,
+                    stale,
+                    pinned,
+                    ..
                 } in self.policies.iter()
                 {
                     let balance = if *last_sync != Timestamp::from(0) {
@@ -166,6 +185,17 @@ impl State for PoliciesState {
                             .width(Length::Fill)
                     };
 
+                    // Synced in the past, but not recently
+                    let stale_icon: Element<Message> = if *stale && *last_sync != Timestamp::from(0)
+                    {
+                        Icon::new(PATCH_EXCLAMATION)
+                            .color(ORANGE)
+                            .width(Length::Fixed(40.0))
+                            .into()
+                    } else {
+                        Space::with_width(Length::Fixed(40.0)).into()
+                    };
+
                     let row = Row::new()
                         .push(
                             Text::new(util::cut_event_id(*policy_id))
@@ -173,7 +203,16 @@ impl State for PoliciesState {
                                 .view(),
                         )
                         .push(Text::new(&policy.name()).width(Length::Fill).view())
+                        .push(stale_icon)
                         .push(balance)
+                        .push(
+                            Button::new()
+                                .style(ButtonStyle::Bordered)
+                                .icon(if *pinned { STAR_FILL } else { STAR })
+                                .on_press(PoliciesMessage::TogglePin(*policy_id, *pinned).into())
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
                         .push(
                             Button::new()
                                 .style(ButtonStyle::Bordered)
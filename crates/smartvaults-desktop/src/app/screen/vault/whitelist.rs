@@ -0,0 +1,319 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use iced::widget::{Column, PickList, Row};
+use iced::{Alignment, Command, Element, Length};
+use smartvaults_sdk::nostr::EventId;
+use smartvaults_sdk::protocol::v1::{Whitelist, WhitelistMode};
+use smartvaults_sdk::util;
+
+use crate::app::component::Dashboard;
+use crate::app::{Context, Message, State};
+use crate::component::{rule, Button, ButtonStyle, Text, TextInput};
+use crate::theme::color::DARK_RED;
+use crate::theme::icon::{PLUS, RELOAD, TRASH};
+
+const MODES: [WhitelistMode; 3] = [
+    WhitelistMode::Disabled,
+    WhitelistMode::Confirm,
+    WhitelistMode::Enforce,
+];
+
+#[derive(Debug, Clone)]
+pub enum VaultWhitelistMessage {
+    LoadWhitelist(Whitelist),
+    DestinationChanged(String),
+    LabelChanged(String),
+    AddDestination,
+    RemoveDestination(String),
+    ModeSelected(WhitelistMode),
+    ErrorChanged(Option<String>),
+    Reload,
+}
+
+#[derive(Debug)]
+pub struct VaultWhitelistState {
+    loading: bool,
+    loaded: bool,
+    vault_id: EventId,
+    whitelist: Whitelist,
+    destination: String,
+    label: String,
+    error: Option<String>,
+}
+
+impl VaultWhitelistState {
+    pub fn new(vault_id: EventId) -> Self {
+        Self {
+            loading: false,
+            loaded: false,
+            vault_id,
+            whitelist: Whitelist::default(),
+            destination: String::new(),
+            label: String::new(),
+            error: None,
+        }
+    }
+}
+
+impl State for VaultWhitelistState {
+    fn title(&self) -> String {
+        format!("Whitelist of vault #{}", util::cut_event_id(self.vault_id))
+    }
+
+    fn load(&mut self, ctx: &Context) -> Command<Message> {
+        self.loading = true;
+        let client = ctx.client.clone();
+        let vault_id = self.vault_id;
+        Command::perform(
+            async move { client.get_vault_whitelist(vault_id).await },
+            |res| match res {
+                Ok(whitelist) => VaultWhitelistMessage::LoadWhitelist(whitelist).into(),
+                Err(e) => VaultWhitelistMessage::ErrorChanged(Some(e.to_string())).into(),
+            },
+        )
+    }
+
+    fn update(&mut self, ctx: &mut Context, message: Message) -> Command<Message> {
+        if !self.loaded && !self.loading {
+            return self.load(ctx);
+        }
+
+        if let Message::VaultWhitelist(msg) = message {
+            let client = ctx.client.clone();
+            let vault_id = self.vault_id;
+            match msg {
+                VaultWhitelistMessage::LoadWhitelist(whitelist) => {
+                    self.whitelist = whitelist;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                VaultWhitelistMessage::DestinationChanged(destination) => {
+                    self.destination = destination
+                }
+                VaultWhitelistMessage::LabelChanged(label) => self.label = label,
+                VaultWhitelistMessage::AddDestination => {
+                    self.loading = true;
+                    let destination = self.destination.clone();
+                    let label = if self.label.is_empty() {
+                        None
+                    } else {
+                        Some(self.label.clone())
+                    };
+                    return Command::perform(
+                        async move {
+                            client
+                                .add_whitelisted_destination(vault_id, destination, label)
+                                .await
+                        },
+                        |res| match res {
+                            Ok(_) => VaultWhitelistMessage::Reload.into(),
+                            Err(e) => {
+                                VaultWhitelistMessage::ErrorChanged(Some(e.to_string())).into()
+                            }
+                        },
+                    );
+                }
+                VaultWhitelistMessage::RemoveDestination(destination) => {
+                    self.loading = true;
+                    return Command::perform(
+                        async move {
+                            client
+                                .remove_whitelisted_destination(vault_id, &destination)
+                                .await
+                        },
+                        |res| match res {
+                            Ok(_) => VaultWhitelistMessage::Reload.into(),
+                            Err(e) => {
+                                VaultWhitelistMessage::ErrorChanged(Some(e.to_string())).into()
+                            }
+                        },
+                    );
+                }
+                VaultWhitelistMessage::ModeSelected(mode) => {
+                    self.loading = true;
+                    return Command::perform(
+                        async move { client.set_vault_whitelist_mode(vault_id, mode).await },
+                        |res| match res {
+                            Ok(_) => VaultWhitelistMessage::Reload.into(),
+                            Err(e) => {
+                                VaultWhitelistMessage::ErrorChanged(Some(e.to_string())).into()
+                            }
+                        },
+                    );
+                }
+                VaultWhitelistMessage::ErrorChanged(e) => {
+                    self.error = e;
+                    self.loading = false;
+                    self.loaded = true;
+                }
+                VaultWhitelistMessage::Reload => {
+                    self.destination.clear();
+                    self.label.clear();
+                    self.error = None;
+                    return self.load(ctx);
+                }
+            }
+        }
+
+        Command::none()
+    }
+
+    fn view(&self, ctx: &Context) -> Element<Message> {
+        let mut content = Column::new().spacing(10).padding(20);
+
+        if self.loaded {
+            let mode_description: &str = match self.whitelist.mode {
+                WhitelistMode::Disabled => "Any destination is allowed.",
+                WhitelistMode::Confirm => {
+                    "The approvers are asked for an extra confirmation of the proposals to destinations not in the whitelist."
+                }
+                WhitelistMode::Enforce => {
+                    "The proposals to destinations not in the whitelist are refused."
+                }
+            };
+
+            content = content
+                .push(
+                    Row::new()
+                        .push(Text::new("Mode").bold().big().view())
+                        .push(
+                            PickList::new(&MODES[..], Some(self.whitelist.mode), |mode| {
+                                VaultWhitelistMessage::ModeSelected(mode).into()
+                            })
+                            .width(Length::Fixed(200.0))
+                            .padding(10),
+                        )
+                        .push(
+                            Text::new(mode_description)
+                                .extra_light()
+                                .width(Length::Fill)
+                                .view(),
+                        )
+                        .push(
+                            Button::new()
+                                .icon(RELOAD)
+                                .style(ButtonStyle::Bordered)
+                                .on_press(VaultWhitelistMessage::Reload.into())
+                                .loading(self.loading)
+                                .width(Length::Fixed(40.0))
+                                .view(),
+                        )
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal_bold())
+                .push(
+                    Row::new()
+                        .push(
+                            Text::new("Address or descriptor")
+                                .bold()
+                                .big()
+                                .width(Length::Fill)
+                                .view(),
+                        )
+                        .push(
+                            Text::new("Label")
+                                .bold()
+                                .big()
+                                .width(Length::Fixed(250.0))
+                                .view(),
+                        )
+                        .push(Row::new().width(Length::Fixed(40.0)))
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .width(Length::Fill),
+                )
+                .push(rule::horizontal());
+
+            if self.whitelist.destinations.is_empty() {
+                content = content.push(Text::new("No whitelisted destinations").view());
+            }
+
+            for destination in self.whitelist.destinations.iter() {
+                let row = Row::new()
+                    .push(
+                        Text::new(destination.destination())
+                            .width(Length::Fill)
+                            .view(),
+                    )
+                    .push(
+                        Text::new(destination.label().unwrap_or("-"))
+                            .width(Length::Fixed(250.0))
+                            .view(),
+                    )
+                    .push(
+                        Button::new()
+                            .icon(TRASH)
+                            .style(ButtonStyle::BorderedDanger)
+                            .on_press(
+                                VaultWhitelistMessage::RemoveDestination(
+                                    destination.destination().to_string(),
+                                )
+                                .into(),
+                            )
+                            .loading(self.loading)
+                            .width(Length::Fixed(40.0))
+                            .view(),
+                    )
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .width(Length::Fill);
+                content = content.push(row).push(rule::horizontal());
+            }
+
+            let mut add_btn = Button::new()
+                .icon(PLUS)
+                .loading(self.loading)
+                .width(Length::Fixed(40.0));
+            if !self.destination.is_empty() {
+                add_btn = add_btn.on_press(VaultWhitelistMessage::AddDestination.into());
+            }
+
+            content = content.push(
+                Row::new()
+                    .push(
+                        TextInput::new(&self.destination)
+                            .on_input(|s| VaultWhitelistMessage::DestinationChanged(s).into())
+                            .placeholder("Address or descriptor")
+                            .view(),
+                    )
+                    .push(
+                        Column::new()
+                            .push(
+                                TextInput::new(&self.label)
+                                    .on_input(|s| VaultWhitelistMessage::LabelChanged(s).into())
+                                    .placeholder("Label (optional)")
+                                    .view(),
+                            )
+                            .width(Length::Fixed(250.0)),
+                    )
+                    .push(add_btn.view())
+                    .spacing(10)
+                    .align_items(Alignment::Center)
+                    .width(Length::Fill),
+            );
+
+            if let Some(error) = &self.error {
+                content = content.push(Text::new(error).color(DARK_RED).view());
+            }
+        }
+
+        Dashboard::new()
+            .loaded(self.loaded)
+            .view(ctx, content, true, false)
+    }
+}
+
+impl From<VaultWhitelistState> for Box<dyn State> {
+    fn from(s: VaultWhitelistState) -> Box<dyn State> {
+        Box::new(s)
+    }
+}
+
+impl From<VaultWhitelistMessage> for Message {
+    fn from(msg: VaultWhitelistMessage) -> Self {
+        Self::VaultWhitelist(msg)
+    }
+}
@@ -4,6 +4,7 @@
 pub const APP_NAME: &str = "Smart Vaults";
 pub const APP_LOGO: &[u8] = include_bytes!("../static/img/smartvaults.svg");
 pub const APP_DESCRIPTION: &str = env!("CARGO_PKG_DESCRIPTION");
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const BIGGER_FONT_SIZE: u16 = 19;
 pub const BIG_FONT_SIZE: u16 = 17;
@@ -6,7 +6,6 @@
 #![windows_subsystem = "windows"]
 
 use std::path::PathBuf;
-use std::str::FromStr;
 
 use constants::DEFAULT_FONT_SIZE;
 use iced::window::{Action as WindowAction, Event as WindowEvent};
@@ -46,10 +45,13 @@ fn base_path() -> Result<PathBuf> {
 static BASE_PATH: Lazy<PathBuf> = Lazy::new(|| base_path().expect("Impossible to get base path"));
 
 fn parse_network(args: Vec<String>) -> Network {
-    for (i, arg) in args.iter().enumerate() {
-        if arg.contains("--") {
-            let network = Network::from_str(args[i].trim_start_matches("--")).unwrap();
-            return network;
+    for arg in args.iter() {
+        match arg.as_str() {
+            "--bitcoin" | "--mainnet" => return Network::Bitcoin,
+            "--testnet" => return Network::Testnet,
+            "--signet" => return Network::Signet,
+            "--regtest" => return Network::Regtest,
+            _ => (),
         }
     }
     Network::Bitcoin
@@ -149,9 +151,17 @@ impl Application for SmartVaultsApp {
                 let (command, stage_to_move) = start.update(*msg);
                 if let Some(stage) = stage_to_move {
                     *self = stage;
-                    return Command::perform(async {}, |_| {
-                        Message::App(Box::new(app::Message::Tick))
-                    });
+                    let load_settings = match &self.state {
+                        State::App(app) => Command::batch(vec![
+                            app.load_app_settings().map(|m| m.into()),
+                            app.check_for_updates().map(|m| m.into()),
+                        ]),
+                        State::Start(..) => Command::none(),
+                    };
+                    return Command::batch(vec![
+                        Command::perform(async {}, |_| Message::App(Box::new(app::Message::Tick))),
+                        load_settings,
+                    ]);
                 }
                 command.map(|m| m.into())
             }
@@ -46,3 +46,6 @@ pub const BINOCULARS: char = '\u{F18F}';
 pub const LIST: char = '\u{F479}';
 pub const PEOPLE: char = '\u{F4D0}';
 pub const PENCIL: char = '\u{F4CB}';
+pub const STAR: char = '\u{F588}';
+pub const STAR_FILL: char = '\u{F586}';
+pub const PRINTER: char = '\u{F501}';
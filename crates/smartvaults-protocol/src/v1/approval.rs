@@ -0,0 +1,57 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Approvals
+//!
+//! The approval event carries a `txid` tag, signed by the approver, committing to the unsigned
+//! transaction of the proposal they approved: if the proposal is rebuilt, the approval is stale and
+//! must not be combined with the new PSBT.
+
+use core::str::FromStr;
+
+use nostr::{Event, Tag, TagKind};
+use smartvaults_core::bitcoin::Txid;
+
+const APPROVAL_TXID_TAG: &str = "txid";
+
+/// Build the tag committing the approval to the unsigned transaction of the proposal
+pub fn txid_tag(txid: Txid) -> Tag {
+    Tag::Generic(
+        TagKind::Custom(APPROVAL_TXID_TAG.to_string()),
+        vec![txid.to_string()],
+    )
+}
+
+/// Get the txid committed by the approval event (`None` for the approvals without the tag)
+pub fn extract_txid(event: &Event) -> Option<Txid> {
+    event.tags.iter().find_map(|tag| match tag {
+        Tag::Generic(TagKind::Custom(kind), values) if kind == APPROVAL_TXID_TAG => {
+            values.first().and_then(|txid| Txid::from_str(txid).ok())
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, Keys, Kind};
+
+    use super::*;
+
+    #[test]
+    fn test_txid_tag() {
+        let txid =
+            Txid::from_str("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b")
+                .unwrap();
+        let keys = Keys::generate();
+        let event = EventBuilder::new(Kind::Custom(9291), "", [txid_tag(txid)])
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(extract_txid(&event), Some(txid));
+
+        let event = EventBuilder::new(Kind::Custom(9291), "", [])
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(extract_txid(&event), None);
+    }
+}
@@ -4,19 +4,27 @@
 use std::collections::HashMap;
 
 use nostr::nips::nip04;
-use nostr::{Event, EventBuilder, EventId, Keys, PublicKey, Tag};
+use nostr::{Event, EventBuilder, EventId, Keys, Kind, PublicKey, Tag, Timestamp};
+use serde_json::Value;
 use smartvaults_core::bitcoin::Network;
-use smartvaults_core::{Policy, Proposal, Signer};
+use smartvaults_core::{CompletedProposal, Policy, Proposal, Signer};
 use thiserror::Error;
 
 use super::constants::{
+    APP_SETTINGS_IDENTIFIER, CAPABILITIES_KIND, COMPLETED_PROPOSAL_KIND, DEVICE_KIND,
     KEY_AGENT_SIGNALING, KEY_AGENT_SIGNER_OFFERING_KIND, KEY_AGENT_VERIFIED, LABELS_KIND,
-    POLICY_KIND, PROPOSAL_KIND, SHARED_KEY_KIND,
+    PAYEES_IDENTIFIER, PAYMENT_REQUEST_KIND, POLICY_KIND, PROPOSAL_COMMENT_KIND, PROPOSAL_KIND,
+    RECURRING_PAYMENT_KIND, SHARED_KEY_KIND, SIGNER_REQUEST_KIND, VAULT_METADATA_IDENTIFIER,
+    VAULT_METADATA_KIND,
 };
 use super::key_agent::signer::SignerOffering;
 use super::key_agent::verified::VerifiedKeyAgentData;
 use super::util::{Encryption, EncryptionError};
-use super::{Label, Serde};
+use super::{chunk, expiration, replacement};
+use super::{
+    AppSettings, Capabilities, Device, Label, Payees, PaymentRequest, ProposalComment,
+    RecurringPayment, Serde, SignerRequest, VaultMetadata,
+};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -30,6 +38,8 @@ pub enum Error {
     Encryption(#[from] EncryptionError),
     #[error(transparent)]
     Label(#[from] super::label::Error),
+    #[error(transparent)]
+    Chunk(#[from] chunk::Error),
 }
 
 pub trait SmartVaultsEventBuilder {
@@ -63,16 +73,78 @@ pub trait SmartVaultsEventBuilder {
         Ok(EventBuilder::new(POLICY_KIND, content, tags).to_event(shared_key)?)
     }
 
+    /// Build the proposal event (and the parts, if the content is too big)
+    ///
+    /// The proposal event is the last one.
     fn proposal(
         shared_key: &Keys,
         policy_id: EventId,
         proposal: &Proposal,
+        expires_at: Option<Timestamp>,
         nostr_pubkeys: &[PublicKey],
-    ) -> Result<Event, Error> {
+    ) -> Result<Vec<Event>, Error> {
         let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
         tags.push(Tag::event(policy_id));
+        tags.extend(expires_at.map(expiration::tag));
         let content: String = proposal.encrypt_with_keys(shared_key)?;
-        Ok(EventBuilder::new(PROPOSAL_KIND, content, tags).to_event(shared_key)?)
+        Ok(chunk::build(shared_key, PROPOSAL_KIND, content, tags)?)
+    }
+
+    /// Build the proposal event replacing the `replaces` proposal (see [`replacement`])
+    ///
+    /// The proposal event is the last one.
+    fn replacement_proposal(
+        shared_key: &Keys,
+        policy_id: EventId,
+        replaces: EventId,
+        proposal: &Proposal,
+        expires_at: Option<Timestamp>,
+        nostr_pubkeys: &[PublicKey],
+    ) -> Result<Vec<Event>, Error> {
+        let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
+        tags.push(Tag::event(policy_id));
+        tags.push(replacement::tag(replaces));
+        tags.extend(expires_at.map(expiration::tag));
+        let content: String = proposal.encrypt_with_keys(shared_key)?;
+        Ok(chunk::build(shared_key, PROPOSAL_KIND, content, tags)?)
+    }
+
+    /// Build the completed proposal event (and the parts, if the content is too big)
+    ///
+    /// The completed proposal event is the last one.
+    fn completed_proposal(
+        shared_key: &Keys,
+        proposal_id: EventId,
+        policy_id: EventId,
+        completed_proposal: &CompletedProposal,
+        nostr_pubkeys: &[PublicKey],
+    ) -> Result<Vec<Event>, Error> {
+        let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
+        tags.push(Tag::event(proposal_id));
+        tags.push(Tag::event(policy_id));
+        let content: String = completed_proposal.encrypt_with_keys(shared_key)?;
+        Ok(chunk::build(
+            shared_key,
+            COMPLETED_PROPOSAL_KIND,
+            content,
+            tags,
+        )?)
+    }
+
+    /// Comment on a proposal (signed by the member and encrypted with the shared key)
+    fn proposal_comment(
+        keys: &Keys,
+        shared_key: &Keys,
+        proposal_id: EventId,
+        policy_id: EventId,
+        comment: &ProposalComment,
+        nostr_pubkeys: &[PublicKey],
+    ) -> Result<Event, Error> {
+        let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
+        tags.push(Tag::event(proposal_id));
+        tags.push(Tag::event(policy_id));
+        let content: String = comment.encrypt_with_keys(shared_key)?;
+        Ok(EventBuilder::new(PROPOSAL_COMMENT_KIND, content, tags).to_event(keys)?)
     }
 
     fn label(
@@ -89,6 +161,19 @@ pub trait SmartVaultsEventBuilder {
         Ok(EventBuilder::new(LABELS_KIND, content, tags).to_event(shared_key)?)
     }
 
+    fn vault_metadata(
+        shared_key: &Keys,
+        policy_id: EventId,
+        metadata: &VaultMetadata,
+        nostr_pubkeys: &[PublicKey],
+    ) -> Result<Event, Error> {
+        let content: String = metadata.encrypt_with_keys(shared_key)?;
+        let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
+        tags.push(Tag::Identifier(VAULT_METADATA_IDENTIFIER.to_string()));
+        tags.push(Tag::event(policy_id));
+        Ok(EventBuilder::new(VAULT_METADATA_KIND, content, tags).to_event(shared_key)?)
+    }
+
     fn key_agent_signaling(keys: &Keys, network: Network) -> Result<Event, Error> {
         let identifier: String = network.magic().to_string();
         Ok(
@@ -123,6 +208,128 @@ pub trait SmartVaultsEventBuilder {
         tags.push(Tag::Identifier(identifier));
         Ok(EventBuilder::new(KEY_AGENT_VERIFIED, content, tags).to_event(keys)?)
     }
+
+    /// Device announcement (encrypted to self)
+    ///
+    /// The event is replaceable: the `created_at` of the latest one is the device last seen.
+    fn device<S>(keys: &Keys, device_id: S, device: &Device) -> Result<Event, Error>
+    where
+        S: Into<String>,
+    {
+        let content: String = device.encrypt_with_keys(keys)?;
+        Ok(
+            EventBuilder::new(DEVICE_KIND, content, [Tag::Identifier(device_id.into())])
+                .to_event(keys)?,
+        )
+    }
+
+    /// Signer request (NIP-04 encrypted to the receiver)
+    fn signer_request(
+        keys: &Keys,
+        receiver: &PublicKey,
+        request: &SignerRequest,
+    ) -> Result<Event, Error> {
+        let content: String = nip04::encrypt(keys.secret_key()?, receiver, request.as_json())?;
+        Ok(
+            EventBuilder::new(SIGNER_REQUEST_KIND, content, [Tag::public_key(*receiver)])
+                .to_event(keys)?,
+        )
+    }
+
+    /// Payment request to the vault (signed by the member and encrypted with the shared key)
+    fn payment_request(
+        keys: &Keys,
+        shared_key: &Keys,
+        policy_id: EventId,
+        request: &PaymentRequest,
+        nostr_pubkeys: &[PublicKey],
+    ) -> Result<Event, Error> {
+        let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
+        tags.push(Tag::event(policy_id));
+        let content: String = request.encrypt_with_keys(shared_key)?;
+        Ok(EventBuilder::new(PAYMENT_REQUEST_KIND, content, tags).to_event(keys)?)
+    }
+
+    /// Recurring payment of the vault (signed by the member and encrypted with the shared key)
+    ///
+    /// Replaceable by `identifier`, to update the state of the schedule.
+    fn recurring_payment<S>(
+        keys: &Keys,
+        shared_key: &Keys,
+        policy_id: EventId,
+        identifier: S,
+        payment: &RecurringPayment,
+        nostr_pubkeys: &[PublicKey],
+    ) -> Result<Event, Error>
+    where
+        S: Into<String>,
+    {
+        let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
+        tags.push(Tag::Identifier(identifier.into()));
+        tags.push(Tag::event(policy_id));
+        let content: String = payment.encrypt_with_keys(shared_key)?;
+        Ok(EventBuilder::new(RECURRING_PAYMENT_KIND, content, tags).to_event(keys)?)
+    }
+
+    /// Client capabilities of a vault member (signed by the member and encrypted with the shared key)
+    ///
+    /// The event is replaceable, with the vault ID as identifier.
+    fn capabilities(
+        keys: &Keys,
+        shared_key: &Keys,
+        policy_id: EventId,
+        capabilities: &Capabilities,
+        nostr_pubkeys: &[PublicKey],
+    ) -> Result<Event, Error> {
+        let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
+        tags.push(Tag::Identifier(policy_id.to_hex()));
+        tags.push(Tag::event(policy_id));
+        let content: String = capabilities.encrypt_with_keys(shared_key)?;
+        Ok(EventBuilder::new(CAPABILITIES_KIND, content, tags).to_event(keys)?)
+    }
+
+    /// Custom event of the vault (signed by the member and encrypted with the shared key)
+    ///
+    /// Used by the SDK plugins to extend the protocol with other kinds.
+    fn custom_vault_event(
+        keys: &Keys,
+        shared_key: &Keys,
+        policy_id: EventId,
+        kind: Kind,
+        identifier: Option<String>,
+        content: &Value,
+        nostr_pubkeys: &[PublicKey],
+    ) -> Result<Event, Error> {
+        let mut tags: Vec<Tag> = nostr_pubkeys.iter().copied().map(Tag::public_key).collect();
+        tags.push(Tag::event(policy_id));
+        if let Some(identifier) = identifier {
+            tags.push(Tag::Identifier(identifier));
+        }
+        let content: String = content.encrypt_with_keys(shared_key)?;
+        Ok(EventBuilder::new(kind, content, tags).to_event(keys)?)
+    }
+
+    /// Application settings (NIP-78, encrypted to self)
+    fn app_settings(keys: &Keys, settings: &AppSettings) -> Result<Event, Error> {
+        let content: String = settings.encrypt_with_keys(keys)?;
+        Ok(EventBuilder::new(
+            Kind::ApplicationSpecificData,
+            content,
+            [Tag::Identifier(APP_SETTINGS_IDENTIFIER.to_string())],
+        )
+        .to_event(keys)?)
+    }
+
+    /// Saved payees (NIP-78, encrypted to self)
+    fn payees(keys: &Keys, payees: &Payees) -> Result<Event, Error> {
+        let content: String = payees.encrypt_with_keys(keys)?;
+        Ok(EventBuilder::new(
+            Kind::ApplicationSpecificData,
+            content,
+            [Tag::Identifier(PAYEES_IDENTIFIER.to_string())],
+        )
+        .to_event(keys)?)
+    }
 }
 
 impl SmartVaultsEventBuilder for EventBuilder {}
@@ -0,0 +1,97 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Client capabilities
+//!
+//! Every member publish, for each vault, the protocol versions and the features supported by its client
+//! (signed by the member and encrypted with the shared key), so the others can detect incompatible cosigners.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::util::{Encryption, Serde};
+
+/// Current protocol version
+pub const PROTOCOL_VERSION: u8 = 1;
+/// Oldest protocol version this client is able to parse
+pub const MIN_PROTOCOL_VERSION: u8 = 1;
+
+/// Oversized events split across [`CHUNK_KIND`](super::constants::CHUNK_KIND) events
+pub const FEATURE_CHUNKED_EVENTS: &str = "chunked-events";
+/// Address and UTXO labels
+pub const FEATURE_LABELS: &str = "labels";
+/// Payment requests
+pub const FEATURE_PAYMENT_REQUESTS: &str = "payment-requests";
+
+/// Features supported by this client
+pub const SUPPORTED_FEATURES: [&str; 3] = [
+    FEATURE_CHUNKED_EVENTS,
+    FEATURE_LABELS,
+    FEATURE_PAYMENT_REQUESTS,
+];
+
+/// Protocol versions and features supported by a client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub protocol_versions: BTreeSet<u8>,
+    #[serde(default)]
+    pub features: BTreeSet<String>,
+    /// Client name and version (i.e. `smartvaults-desktop 0.4.0`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+}
+
+impl Capabilities {
+    /// Capabilities of this client
+    pub fn current(client: Option<String>) -> Self {
+        Self {
+            protocol_versions: (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).collect(),
+            features: SUPPORTED_FEATURES.into_iter().map(String::from).collect(),
+            client,
+        }
+    }
+
+    /// Check if the two clients share at least a protocol version
+    pub fn is_compatible_with(&self, other: &Self) -> bool {
+        !self.protocol_versions.is_disjoint(&other.protocol_versions)
+    }
+
+    /// Features supported by this client but not by `other`
+    pub fn missing_features<'a>(&'a self, other: &'a Self) -> BTreeSet<&'a str> {
+        self.features
+            .difference(&other.features)
+            .map(|f| f.as_str())
+            .collect()
+    }
+}
+
+impl Serde for Capabilities {}
+impl Encryption for Capabilities {}
+
+#[cfg(test)]
+mod tests {
+    use nostr::Keys;
+
+    use super::*;
+
+    #[test]
+    fn test_capabilities() {
+        let keys = Keys::generate();
+        let current = Capabilities::current(Some(String::from("smartvaults-cli 0.4.0")));
+        let content: String = current.encrypt_with_keys(&keys).unwrap();
+        assert_eq!(
+            Capabilities::decrypt_with_keys(&keys, content).unwrap(),
+            current
+        );
+
+        let old =
+            Capabilities::from_json(r#"{"protocol_versions":[0],"features":["labels"]}"#).unwrap();
+        assert!(!current.is_compatible_with(&old));
+        assert!(current
+            .missing_features(&old)
+            .contains(FEATURE_CHUNKED_EVENTS));
+        assert!(old.missing_features(&current).is_empty());
+        assert!(current.is_compatible_with(&current));
+    }
+}
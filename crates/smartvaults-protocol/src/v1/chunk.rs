@@ -0,0 +1,157 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Chunking of oversized payloads
+//!
+//! Some relays reject big events (i.e. proposals with PSBTs spending many inputs), so when the
+//! content exceeds [`MAX_CONTENT_SIZE`] it's split across [`CHUNK_KIND`] events. The main event
+//! is published with an empty content and references the ordered parts with `chunk` tags.
+
+use nostr::{Event, EventBuilder, EventId, Keys, Kind, Tag, TagKind};
+use thiserror::Error;
+
+use super::constants::CHUNK_KIND;
+
+/// Max content size of a single event (bytes)
+pub const MAX_CONTENT_SIZE: usize = 48 * 1024;
+
+const CHUNK_TAG: &str = "chunk";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Keys(#[from] nostr::key::Error),
+    #[error(transparent)]
+    EventBuilder(#[from] nostr::event::builder::Error),
+    #[error("chunk {0} not found")]
+    MissingChunk(EventId),
+    #[error("chunk {0} doesn't belong to the event")]
+    InvalidChunk(EventId),
+}
+
+/// Split the content in parts of max `size` bytes
+pub fn split<S>(content: S, size: usize) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    let content: &str = content.as_ref();
+    let mut parts: Vec<String> = Vec::new();
+    let mut part: String = String::new();
+    for c in content.chars() {
+        if part.len() + c.len_utf8() > size {
+            parts.push(part);
+            part = String::new();
+        }
+        part.push(c);
+    }
+    if !part.is_empty() || parts.is_empty() {
+        parts.push(part);
+    }
+    parts
+}
+
+/// Build the event, splitting the content across multiple events if needed
+///
+/// The parts are returned first: the main event is always the last one.
+pub fn build<S, I>(keys: &Keys, kind: Kind, content: S, tags: I) -> Result<Vec<Event>, Error>
+where
+    S: Into<String>,
+    I: IntoIterator<Item = Tag>,
+{
+    let content: String = content.into();
+    let mut tags: Vec<Tag> = tags.into_iter().collect();
+
+    if content.len() <= MAX_CONTENT_SIZE {
+        return Ok(vec![EventBuilder::new(kind, content, tags).to_event(keys)?]);
+    }
+
+    // Parts are sent to the same receivers of the main event
+    let receivers: Vec<Tag> = tags
+        .iter()
+        .filter(|t| matches!(t, Tag::PublicKey { .. }))
+        .cloned()
+        .collect();
+
+    let mut events: Vec<Event> = Vec::new();
+    for part in split(content, MAX_CONTENT_SIZE).into_iter() {
+        let event: Event = EventBuilder::new(CHUNK_KIND, part, receivers.clone()).to_event(keys)?;
+        tags.push(Tag::Generic(
+            TagKind::Custom(CHUNK_TAG.to_string()),
+            vec![event.id.to_hex()],
+        ));
+        events.push(event);
+    }
+
+    events.push(EventBuilder::new(kind, "", tags).to_event(keys)?);
+
+    Ok(events)
+}
+
+/// Get the ordered ids of the parts
+pub fn chunk_ids(event: &Event) -> Vec<EventId> {
+    event
+        .tags
+        .iter()
+        .filter_map(|tag| match tag {
+            Tag::Generic(TagKind::Custom(kind), values) if kind == CHUNK_TAG => {
+                values.first().and_then(|id| EventId::from_hex(id).ok())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check if the content of the event is split across multiple events
+pub fn is_chunked(event: &Event) -> bool {
+    !chunk_ids(event).is_empty()
+}
+
+/// Reassemble the content of a chunked event
+pub fn reassemble(event: &Event, chunks: &[Event]) -> Result<String, Error> {
+    let mut content: String = String::new();
+    for id in chunk_ids(event).into_iter() {
+        let chunk: &Event = chunks
+            .iter()
+            .find(|c| c.id == id)
+            .ok_or(Error::MissingChunk(id))?;
+        if chunk.kind != CHUNK_KIND || chunk.author() != event.author() {
+            return Err(Error::InvalidChunk(id));
+        }
+        content.push_str(&chunk.content);
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(split("abcdefg", 3), vec!["abc", "def", "g"]);
+        assert_eq!(split("", 3), vec![""]);
+        // Never split a char
+        assert_eq!(split("aé", 2), vec!["a", "é"]);
+    }
+
+    #[test]
+    fn test_chunked_event() {
+        let keys = Keys::generate();
+        let content: String = "a".repeat(MAX_CONTENT_SIZE * 2 + 10);
+        let events = build(&keys, Kind::Custom(9290), content.clone(), []).unwrap();
+        assert_eq!(events.len(), 4);
+
+        let (main, chunks) = events.split_last().unwrap();
+        assert!(is_chunked(main));
+        assert!(main.content.is_empty());
+        assert_eq!(reassemble(main, chunks).unwrap(), content);
+
+        // Missing chunk
+        assert!(reassemble(main, &chunks[1..]).is_err());
+
+        // Small content is not chunked
+        let events = build(&keys, Kind::Custom(9290), "content", []).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(!is_chunked(&events[0]));
+    }
+}
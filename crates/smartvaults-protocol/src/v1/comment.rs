@@ -0,0 +1,73 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use nostr::EventId;
+use serde::{Deserialize, Serialize};
+
+use super::util::{Encryption, Serde};
+
+/// Comment of a vault member on a proposal (i.e. to discuss the reason of a spending)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalComment {
+    message: String,
+    /// Approvals reported as stale by the comment (signed against an earlier PSBT of the proposal)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    stale_approvals: Vec<EventId>,
+}
+
+impl ProposalComment {
+    pub fn new<S>(message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            message: message.into(),
+            stale_approvals: Vec::new(),
+        }
+    }
+
+    /// Comment asking the approvers of the stale approvals to re-approve the proposal
+    pub fn stale_approvals<S>(message: S, approval_ids: Vec<EventId>) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            message: message.into(),
+            stale_approvals: approval_ids,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        self.message.clone()
+    }
+
+    /// Approvals reported as stale
+    pub fn reported_stale_approvals(&self) -> Vec<EventId> {
+        self.stale_approvals.clone()
+    }
+}
+
+impl Serde for ProposalComment {}
+impl Encryption for ProposalComment {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_serde() {
+        let comment = ProposalComment::new("hello");
+        assert_eq!(comment.as_json(), r#"{"message":"hello"}"#);
+        assert_eq!(
+            ProposalComment::from_json(comment.as_json()).unwrap(),
+            comment
+        );
+
+        let approval_id =
+            EventId::from_hex("2be17aa3031bdcb006f0fce80c146dea9c1c0268b0af2398bb673365c6444d45")
+                .unwrap();
+        let comment = ProposalComment::stale_approvals("re-approve", vec![approval_id]);
+        let deserialized = ProposalComment::from_json(comment.as_json()).unwrap();
+        assert_eq!(deserialized.reported_stale_approvals(), vec![approval_id]);
+    }
+}
@@ -16,6 +16,11 @@ pub static SMARTVAULTS_TESTNET_PUBLIC_KEY: Lazy<PublicKey> = Lazy::new(|| {
     PublicKey::from_str("2c2dcda12330dda3b9600237a419003c5d9bf3d757303e63ecee121b4aaa2fa0")
         .expect("Invalid public key")
 });
+/// Key that sign the release manifests, for every network
+pub static SMARTVAULTS_RELEASE_PUBLIC_KEY: Lazy<PublicKey> = Lazy::new(|| {
+    PublicKey::from_str("32c961f39afcff6df6abed251b346550329b2dbcabca0667530f0be5054fe7ae")
+        .expect("Invalid public key")
+});
 
 // Kinds
 pub const SHARED_KEY_KIND: Kind = Kind::Custom(9288);
@@ -25,10 +30,51 @@ pub const APPROVED_PROPOSAL_KIND: Kind = Kind::Custom(9291);
 pub const COMPLETED_PROPOSAL_KIND: Kind = Kind::Custom(9292);
 pub const SIGNERS_KIND: Kind = Kind::Custom(9294);
 pub const SHARED_SIGNERS_KIND: Kind = Kind::Custom(9295);
+pub const SHARED_KEY_REQUEST_KIND: Kind = Kind::Custom(9296);
+pub const SIGNER_REQUEST_KIND: Kind = Kind::Custom(9297);
+pub const PAYMENT_REQUEST_KIND: Kind = Kind::Custom(9298);
+pub const CHUNK_KIND: Kind = Kind::Custom(9299);
+pub const PROPOSAL_COMMENT_KIND: Kind = Kind::Custom(9300);
 pub const LABELS_KIND: Kind = Kind::ParameterizedReplaceable(32121);
 pub const KEY_AGENT_SIGNER_OFFERING_KIND: Kind = Kind::ParameterizedReplaceable(32122);
 pub const KEY_AGENT_VERIFIED: Kind = Kind::ParameterizedReplaceable(32123);
 pub const KEY_AGENT_SIGNALING: Kind = Kind::ParameterizedReplaceable(32124);
+pub const DEVICE_KIND: Kind = Kind::ParameterizedReplaceable(32125);
+pub const RELEASE_KIND: Kind = Kind::ParameterizedReplaceable(32126);
+pub const CAPABILITIES_KIND: Kind = Kind::ParameterizedReplaceable(32127);
+pub const VAULT_METADATA_KIND: Kind = Kind::ParameterizedReplaceable(32128);
+pub const RECURRING_PAYMENT_KIND: Kind = Kind::ParameterizedReplaceable(32129);
+
+/// Kinds used by the protocol (not available to the SDK plugins)
+pub const RESERVED_KINDS: [Kind; 21] = [
+    SHARED_KEY_KIND,
+    POLICY_KIND,
+    PROPOSAL_KIND,
+    APPROVED_PROPOSAL_KIND,
+    COMPLETED_PROPOSAL_KIND,
+    SIGNERS_KIND,
+    SHARED_SIGNERS_KIND,
+    SHARED_KEY_REQUEST_KIND,
+    SIGNER_REQUEST_KIND,
+    PAYMENT_REQUEST_KIND,
+    CHUNK_KIND,
+    PROPOSAL_COMMENT_KIND,
+    LABELS_KIND,
+    KEY_AGENT_SIGNER_OFFERING_KIND,
+    KEY_AGENT_VERIFIED,
+    KEY_AGENT_SIGNALING,
+    DEVICE_KIND,
+    RELEASE_KIND,
+    CAPABILITIES_KIND,
+    VAULT_METADATA_KIND,
+    RECURRING_PAYMENT_KIND,
+];
+
+// Identifiers
+pub const APP_SETTINGS_IDENTIFIER: &str = "smartvaults:settings";
+pub const PAYEES_IDENTIFIER: &str = "smartvaults:payees";
+pub const DESKTOP_RELEASE_IDENTIFIER: &str = "smartvaults-desktop";
+pub const VAULT_METADATA_IDENTIFIER: &str = "smartvaults:vault-metadata";
 
 // Expirations
 pub const APPROVED_PROPOSAL_EXPIRATION: Duration = Duration::from_secs(60 * 60 * 24 * 7);
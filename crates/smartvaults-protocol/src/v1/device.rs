@@ -0,0 +1,52 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use serde::{Deserialize, Serialize};
+
+use super::util::{Encryption, Serde};
+
+/// Device (or session) that opened the keychain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Device {
+    platform: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default)]
+    revoked: bool,
+}
+
+impl Device {
+    pub fn new<S>(platform: S, name: Option<String>) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            platform: platform.into(),
+            name,
+            revoked: false,
+        }
+    }
+
+    /// Mark the device as revoked
+    pub fn revoke(self) -> Self {
+        Self {
+            revoked: true,
+            ..self
+        }
+    }
+
+    pub fn platform(&self) -> String {
+        self.platform.clone()
+    }
+
+    pub fn name(&self) -> Option<String> {
+        self.name.clone()
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked
+    }
+}
+
+impl Serde for Device {}
+impl Encryption for Device {}
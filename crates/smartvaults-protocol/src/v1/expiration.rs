@@ -0,0 +1,44 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Proposal expiration
+//!
+//! A proposal may carry a NIP-40 `expiration` tag: once expired, it's no longer considered pending,
+//! its UTXOs are unfrozen and the proposal is deleted.
+
+use nostr::{Event, Tag, Timestamp};
+
+/// Build the tag of the expiration timestamp
+pub fn tag(expires_at: Timestamp) -> Tag {
+    Tag::Expiration(expires_at)
+}
+
+/// Get the expiration timestamp of the event, if any
+pub fn expiration(event: &Event) -> Option<Timestamp> {
+    event.tags.iter().find_map(|tag| match tag {
+        Tag::Expiration(timestamp) => Some(*timestamp),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, Keys, Kind};
+
+    use super::*;
+
+    #[test]
+    fn test_expiration() {
+        let keys = Keys::generate();
+        let expires_at = Timestamp::from(1_700_000_000);
+        let event = EventBuilder::new(Kind::Custom(9289), "", [tag(expires_at)])
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(expiration(&event), Some(expires_at));
+
+        let event = EventBuilder::new(Kind::Custom(9289), "", [])
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(expiration(&event), None);
+    }
+}
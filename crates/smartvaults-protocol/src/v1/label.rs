@@ -4,7 +4,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-use nostr::Keys;
+use nostr::{Keys, PublicKey};
 use serde::{Deserialize, Serialize};
 use smartvaults_core::bitcoin::address::NetworkUnchecked;
 use smartvaults_core::bitcoin::{Address, OutPoint, Txid};
@@ -100,6 +100,9 @@ impl LabelData {
 pub struct Label {
     data: LabelData,
     text: String,
+    /// Member that deposited the funds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    contributor: Option<PublicKey>,
 }
 
 impl Label {
@@ -110,6 +113,15 @@ impl Label {
         Self {
             data,
             text: text.into(),
+            contributor: None,
+        }
+    }
+
+    /// Attribute the labeled funds to a member (claimed deposit)
+    pub fn with_contributor(self, public_key: PublicKey) -> Self {
+        Self {
+            contributor: Some(public_key),
+            ..self
         }
     }
 
@@ -146,6 +158,10 @@ impl Label {
         self.text.clone()
     }
 
+    pub fn contributor(&self) -> Option<PublicKey> {
+        self.contributor
+    }
+
     pub fn generate_identifier(&self, shared_key: &Keys) -> Result<String, Error> {
         self.data.generate_identifier(shared_key)
     }
@@ -189,4 +205,31 @@ mod test {
             String::from("f225b2d56e21560d31ef180f5ff144c2")
         );
     }
+
+    #[test]
+    fn test_identifier_commits_to_shared_key() {
+        let txid =
+            Txid::from_str("3faa6bff53689b9763ed77fc693831a14030977f0ea79411b1132d27135eb1a9")
+                .unwrap();
+        let data = LabelData::Txid(txid);
+        let id1 = data.generate_identifier(&Keys::generate()).unwrap();
+        let id2 = data.generate_identifier(&Keys::generate()).unwrap();
+        assert_ne!(id1, id2);
+    }
+
+    #[test]
+    fn test_label_contributor_serde() {
+        let txid =
+            Txid::from_str("3faa6bff53689b9763ed77fc693831a14030977f0ea79411b1132d27135eb1a9")
+                .unwrap();
+        let label = Label::utxo(OutPoint::new(txid, 0), "Deposit");
+        let json = label.as_json();
+        assert!(!json.contains("contributor"));
+        assert_eq!(Label::from_json(json).unwrap().contributor(), None);
+
+        let public_key = Keys::generate().public_key();
+        let label = label.with_contributor(public_key);
+        let label = Label::from_json(label.as_json()).unwrap();
+        assert_eq!(label.contributor(), Some(public_key));
+    }
 }
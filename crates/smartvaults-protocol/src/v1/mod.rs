@@ -1,17 +1,45 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
+pub mod approval;
 pub mod builder;
+pub mod capabilities;
+pub mod chunk;
+pub mod comment;
 pub mod constants;
+pub mod device;
+pub mod expiration;
 pub mod key_agent;
 pub mod label;
 mod network;
+pub mod payee;
+pub mod payment_request;
+pub mod recurring_payment;
+pub mod release;
+pub mod replacement;
+pub mod settings;
+pub mod signer_request;
 pub mod util;
+pub mod vault_metadata;
+pub mod velocity;
+pub mod whitelist;
 
 pub use self::builder::{Error as SmartVaultsEventBuilderError, SmartVaultsEventBuilder};
+pub use self::capabilities::Capabilities;
+pub use self::comment::ProposalComment;
+pub use self::device::Device;
 pub use self::key_agent::{
-    BasisPoints, DeviceType, KeyAgentMetadata, Price, SignerOffering, Temperature,
+    BasisPoints, Currency, DeviceType, KeyAgentMetadata, Price, SignerOffering, Temperature,
     VerifiedKeyAgentData, VerifiedKeyAgents,
 };
 pub use self::label::{Label, LabelData, LabelKind};
+pub use self::payee::{Payee, Payees};
+pub use self::payment_request::PaymentRequest;
+pub use self::recurring_payment::RecurringPayment;
+pub use self::release::{Release, ReleaseAsset};
+pub use self::settings::AppSettings;
+pub use self::signer_request::SignerRequest;
 pub use self::util::{Encryption, EncryptionError, Serde, SerdeSer};
+pub use self::vault_metadata::VaultMetadata;
+pub use self::velocity::VelocityLimit;
+pub use self::whitelist::{Whitelist, WhitelistMode, WhitelistedDestination};
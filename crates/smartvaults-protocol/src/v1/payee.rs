@@ -0,0 +1,224 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Saved payees
+//!
+//! Recipients reused across the spending proposals, synced (encrypted to self) between the user
+//! devices. A payee is an address or a descriptor (or an xpub), from which a fresh address is
+//! derived for each payment.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::bip32::ExtendedPubKey;
+use smartvaults_core::bitcoin::{Address, Network};
+use smartvaults_core::miniscript::{Descriptor, DescriptorPublicKey};
+use thiserror::Error;
+
+use super::util::{Encryption, Serde};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid destination: expected an address, a descriptor or an xpub")]
+    InvalidDestination,
+    #[error("the address is not valid for {0}")]
+    WrongNetwork(Network),
+    #[error("impossible to derive the address: {0}")]
+    Derivation(String),
+    #[error("payee name can't be empty")]
+    EmptyName,
+}
+
+/// Saved recipient
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Payee {
+    name: String,
+    /// Address or descriptor
+    destination: String,
+    /// Derivation index of the next address (used only for the descriptors)
+    #[serde(default)]
+    index: u32,
+    /// Default description of the proposals to this payee
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+}
+
+impl Payee {
+    /// New payee
+    ///
+    /// An xpub is converted to the `wpkh(<xpub>/0/*)` descriptor.
+    pub fn new<S, D>(name: S, destination: D, memo: Option<String>) -> Result<Self, Error>
+    where
+        S: Into<String>,
+        D: AsRef<str>,
+    {
+        let name: String = name.into().trim().to_string();
+        if name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+
+        let destination: &str = destination.as_ref().trim();
+        let destination: String = if Address::<NetworkUnchecked>::from_str(destination).is_ok()
+            || Descriptor::<DescriptorPublicKey>::from_str(destination).is_ok()
+        {
+            destination.to_string()
+        } else if let Ok(xpub) = ExtendedPubKey::from_str(destination) {
+            format!("wpkh({xpub}/0/*)")
+        } else {
+            return Err(Error::InvalidDestination);
+        };
+
+        Ok(Self {
+            name,
+            destination,
+            index: 0,
+            memo: memo.filter(|m| !m.is_empty()),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    pub fn memo(&self) -> Option<&str> {
+        self.memo.as_deref()
+    }
+
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Check if a new address is derived for each payment
+    pub fn is_descriptor(&self) -> bool {
+        Address::<NetworkUnchecked>::from_str(&self.destination).is_err()
+    }
+
+    /// Get the address of the next payment
+    pub fn address(&self, network: Network) -> Result<Address, Error> {
+        if let Ok(address) = Address::<NetworkUnchecked>::from_str(&self.destination) {
+            return address
+                .require_network(network)
+                .map_err(|_| Error::WrongNetwork(network));
+        }
+
+        let descriptor = Descriptor::<DescriptorPublicKey>::from_str(&self.destination)
+            .map_err(|_| Error::InvalidDestination)?;
+        let descriptor = descriptor
+            .into_single_descriptors()
+            .map_err(|e| Error::Derivation(e.to_string()))?
+            .into_iter()
+            .next()
+            .ok_or(Error::InvalidDestination)?;
+        descriptor
+            .at_derivation_index(self.index)
+            .map_err(|e| Error::Derivation(e.to_string()))?
+            .address(network)
+            .map_err(|e| Error::Derivation(e.to_string()))
+    }
+
+    /// Move to the next address of the descriptor (no-op for an address)
+    pub fn advance(&mut self) {
+        if self.is_descriptor() {
+            self.index = self.index.saturating_add(1);
+        }
+    }
+}
+
+/// Saved payees of the user
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Payees {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub payees: Vec<Payee>,
+}
+
+impl Payees {
+    pub fn get(&self, name: &str) -> Option<&Payee> {
+        self.payees.iter().find(|p| p.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Payee> {
+        self.payees.iter_mut().find(|p| p.name == name)
+    }
+
+    /// Add the payee or replace the one with the same name
+    ///
+    /// The derivation index is kept if the destination is not changed, to not reuse addresses.
+    pub fn save(&mut self, mut payee: Payee) {
+        match self.get_mut(&payee.name) {
+            Some(p) => {
+                if p.destination == payee.destination {
+                    payee.index = p.index;
+                }
+                *p = payee;
+            }
+            None => {
+                self.payees.push(payee);
+                self.payees.sort();
+            }
+        }
+    }
+
+    /// Remove the payee (return `false` if not found)
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len: usize = self.payees.len();
+        self.payees.retain(|p| p.name != name);
+        self.payees.len() != len
+    }
+
+    /// Get the payees whose name starts with `prefix` (case insensitive), for the autocomplete
+    pub fn search(&self, prefix: &str) -> Vec<&Payee> {
+        let prefix: String = prefix.to_lowercase();
+        self.payees
+            .iter()
+            .filter(|p| p.name.to_lowercase().starts_with(&prefix))
+            .collect()
+    }
+}
+
+impl Serde for Payees {}
+impl Encryption for Payees {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XPUB: &str = "tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d";
+
+    #[test]
+    fn test_payee_address() {
+        let payee =
+            Payee::new("Alice", "tb1qe2tczyk2rw7u47kzxxee5g7ufkncdmlcta4z69", None).unwrap();
+        assert!(!payee.is_descriptor());
+        assert!(payee.address(Network::Testnet).is_ok());
+        assert!(payee.address(Network::Bitcoin).is_err());
+
+        let mut payee = Payee::new("Bob", XPUB, Some(String::from("Salary"))).unwrap();
+        assert!(payee.is_descriptor());
+        assert_eq!(payee.destination(), format!("wpkh({XPUB}/0/*)"));
+        let first = payee.address(Network::Testnet).unwrap();
+        payee.advance();
+        assert_eq!(payee.index(), 1);
+        assert_ne!(payee.address(Network::Testnet).unwrap(), first);
+
+        assert!(Payee::new("", XPUB, None).is_err());
+        assert!(Payee::new("Invalid", "invalid", None).is_err());
+    }
+
+    #[test]
+    fn test_payees() {
+        let mut payees = Payees::default();
+        payees.save(Payee::new("Bob", XPUB, None).unwrap());
+        payees.save(Payee::new("alice", XPUB, None).unwrap());
+        payees.save(Payee::new("Bob", XPUB, Some(String::from("Rent"))).unwrap());
+        assert_eq!(payees.payees.len(), 2);
+        assert_eq!(payees.get("Bob").unwrap().memo(), Some("Rent"));
+        assert_eq!(payees.search("b").len(), 1);
+        assert!(payees.remove("alice"));
+        assert!(!payees.remove("alice"));
+    }
+}
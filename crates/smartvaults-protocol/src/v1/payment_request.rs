@@ -0,0 +1,45 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use serde::{Deserialize, Serialize};
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::Address;
+
+use super::util::{Encryption, Serde};
+
+/// Request from a vault member to be paid by the vault (i.e. reimbursement)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRequest {
+    address: Address<NetworkUnchecked>,
+    /// Amount in SAT
+    amount: u64,
+    description: String,
+}
+
+impl PaymentRequest {
+    pub fn new<S>(address: Address<NetworkUnchecked>, amount: u64, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            address,
+            amount,
+            description: description.into(),
+        }
+    }
+
+    pub fn address(&self) -> Address<NetworkUnchecked> {
+        self.address.clone()
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+}
+
+impl Serde for PaymentRequest {}
+impl Encryption for PaymentRequest {}
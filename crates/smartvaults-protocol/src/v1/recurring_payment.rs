@@ -0,0 +1,141 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::time::Duration;
+
+use nostr::Timestamp;
+use serde::{Deserialize, Serialize};
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::Address;
+
+use super::util::{Encryption, Serde};
+
+/// Payment repeated at a fixed interval (i.e. salary)
+///
+/// The spending proposal of each due date is created by the member that defined the payment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurringPayment {
+    address: Address<NetworkUnchecked>,
+    /// Amount in SAT
+    amount: u64,
+    description: String,
+    /// Seconds between the payments
+    interval: u64,
+    /// First due date
+    start: Timestamp,
+    /// Due date of the last proposed payment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_due: Option<Timestamp>,
+}
+
+impl RecurringPayment {
+    pub fn new<S>(
+        address: Address<NetworkUnchecked>,
+        amount: u64,
+        description: S,
+        interval: Duration,
+        start: Timestamp,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            address,
+            amount,
+            description: description.into(),
+            interval: interval.as_secs(),
+            start,
+            last_due: None,
+        }
+    }
+
+    pub fn address(&self) -> Address<NetworkUnchecked> {
+        self.address.clone()
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    pub fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval)
+    }
+
+    pub fn start(&self) -> Timestamp {
+        self.start
+    }
+
+    pub fn last_due(&self) -> Option<Timestamp> {
+        self.last_due
+    }
+
+    /// Next due date
+    pub fn next_due(&self) -> Timestamp {
+        match self.last_due {
+            Some(last_due) => last_due + self.interval(),
+            None => self.start,
+        }
+    }
+
+    /// Get the most recent due date not proposed yet (`None` if not due)
+    ///
+    /// The due dates missed (i.e. the device was offline) are skipped, so only one payment is proposed.
+    pub fn due(&self, now: Timestamp) -> Option<Timestamp> {
+        let next_due: Timestamp = self.next_due();
+        if now < next_due {
+            return None;
+        }
+
+        let interval: u64 = self.interval.max(1);
+        let missed: u64 = (now.as_u64() - next_due.as_u64()) / interval;
+        Some(Timestamp::from(next_due.as_u64() + missed * interval))
+    }
+
+    /// Mark the payment of the `due` date as proposed
+    pub fn proposed(&mut self, due: Timestamp) {
+        self.last_due = Some(due);
+    }
+}
+
+impl Serde for RecurringPayment {}
+impl Encryption for RecurringPayment {}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const DAY: u64 = 60 * 60 * 24;
+
+    #[test]
+    fn test_recurring_payment_due() {
+        let address = Address::from_str("tb1qe2tczyk2rw7u47kzxxee5g7ufkncdmlcta4z69").unwrap();
+        let start = Timestamp::from(1_700_000_000);
+        let mut payment = RecurringPayment::new(
+            address,
+            10_000,
+            "Salary",
+            Duration::from_secs(30 * DAY),
+            start,
+        );
+
+        assert_eq!(payment.due(Timestamp::from(start.as_u64() - 1)), None);
+        assert_eq!(payment.due(start), Some(start));
+
+        payment.proposed(start);
+        assert_eq!(payment.next_due(), start + Duration::from_secs(30 * DAY));
+        assert_eq!(payment.due(Timestamp::from(start.as_u64() + DAY)), None);
+
+        // Missed due dates
+        let now = Timestamp::from(start.as_u64() + 95 * DAY);
+        assert_eq!(
+            payment.due(now),
+            Some(Timestamp::from(start.as_u64() + 90 * DAY))
+        );
+    }
+}
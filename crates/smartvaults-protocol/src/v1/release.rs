@@ -0,0 +1,109 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Release manifest
+//!
+//! Published by the Smart Vaults team as a signed parameterized replaceable event (the identifier
+//! is the app name, i.e. `smartvaults-desktop`). The same event JSON can be served over HTTPS.
+
+use core::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use super::util::Serde;
+
+/// Downloadable installer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseAsset {
+    /// Target platform (i.e. `linux-x86_64`, `macos-aarch64`, `windows-x86_64`)
+    pub platform: String,
+    pub url: String,
+    /// SHA256 of the installer
+    pub sha256: String,
+}
+
+/// Release manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Release {
+    /// Version (i.e. `0.5.0`)
+    pub version: String,
+    /// Changelog (markdown)
+    #[serde(default)]
+    pub changelog: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<ReleaseAsset>,
+    /// Versions older than this can't interoperate with the current protocol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_compatible_version: Option<String>,
+}
+
+impl Serde for Release {}
+
+/// Parse the numeric components of a version (i.e. `v0.4.0-rc.1` -> `[0, 4, 0]`)
+fn version_numbers(version: &str) -> Vec<u64> {
+    let version: &str = version.trim().trim_start_matches('v');
+    let version: &str = version.split(['-', '+']).next().unwrap_or(version);
+    version
+        .split('.')
+        .map(|n| n.parse::<u64>().unwrap_or(0))
+        .collect()
+}
+
+/// Compare two versions (missing components are considered `0`)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let a: Vec<u64> = version_numbers(a);
+    let b: Vec<u64> = version_numbers(b);
+    for i in 0..a.len().max(b.len()) {
+        let x: u64 = a.get(i).copied().unwrap_or(0);
+        let y: u64 = b.get(i).copied().unwrap_or(0);
+        match x.cmp(&y) {
+            Ordering::Equal => continue,
+            ordering => return ordering,
+        }
+    }
+    Ordering::Equal
+}
+
+impl Release {
+    /// Check if this release is newer than `version`
+    pub fn is_newer_than(&self, version: &str) -> bool {
+        compare_versions(&self.version, version) == Ordering::Greater
+    }
+
+    /// Check if `version` is too old to interoperate with this release
+    pub fn is_incompatible_with(&self, version: &str) -> bool {
+        match &self.min_compatible_version {
+            Some(min) => compare_versions(version, min) == Ordering::Less,
+            None => false,
+        }
+    }
+
+    /// Get the installer for the platform
+    pub fn asset(&self, platform: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|a| a.platform == platform)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("0.4.0", "0.4.0"), Ordering::Equal);
+        assert_eq!(compare_versions("v0.4", "0.4.0"), Ordering::Equal);
+        assert_eq!(compare_versions("0.10.0", "0.9.1"), Ordering::Greater);
+        assert_eq!(compare_versions("0.5.0-rc.1", "0.4.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0.0", "1.0.1"), Ordering::Less);
+
+        let release = Release::from_json(
+            r#"{"version":"0.5.0","min_compatible_version":"0.4.0","changelog":"- Fixes"}"#,
+        )
+        .unwrap();
+        assert!(release.is_newer_than("0.4.0"));
+        assert!(!release.is_newer_than("0.5.0"));
+        assert!(release.is_incompatible_with("0.3.2"));
+        assert!(!release.is_incompatible_with("0.4.0"));
+        assert!(release.asset("linux-x86_64").is_none());
+    }
+}
@@ -0,0 +1,53 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Proposal replacement
+//!
+//! A proposal deleted and recreated by the proposer (i.e. to change the amount or to bump the fee)
+//! references the proposal it replaces with a `replaces` tag, so the approvers can review only what changed.
+
+use nostr::{Event, EventId, Tag, TagKind};
+
+const REPLACES_TAG: &str = "replaces";
+
+/// Build the tag referencing the replaced proposal
+pub fn tag(proposal_id: EventId) -> Tag {
+    Tag::Generic(
+        TagKind::Custom(REPLACES_TAG.to_string()),
+        vec![proposal_id.to_hex()],
+    )
+}
+
+/// Get the ID of the proposal replaced by the event, if any
+pub fn replaced_proposal(event: &Event) -> Option<EventId> {
+    event.tags.iter().find_map(|tag| match tag {
+        Tag::Generic(TagKind::Custom(kind), values) if kind == REPLACES_TAG => {
+            values.first().and_then(|id| EventId::from_hex(id).ok())
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, Keys, Kind};
+
+    use super::*;
+
+    #[test]
+    fn test_replaced_proposal() {
+        let keys = Keys::generate();
+        let proposal_id =
+            EventId::from_hex("2be17aa3031bdcb006f0fce80c146dea9c1c0268b0af2398bb673365c6444d45")
+                .unwrap();
+        let event = EventBuilder::new(Kind::Custom(9290), "", [tag(proposal_id)])
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(replaced_proposal(&event), Some(proposal_id));
+
+        let event = EventBuilder::new(Kind::Custom(9290), "", [])
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(replaced_proposal(&event), None);
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr::EventId;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+
+use super::util::{Encryption, Serde};
+
+/// Non-sensitive application settings, synced across the user devices
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppSettings {
+    #[serde(default)]
+    pub hide_balances: bool,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub pinned_vaults: BTreeSet<EventId>,
+    /// Keys (master fingerprints) marked as compromised
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub compromised_keys: BTreeSet<Fingerprint>,
+    /// Other app-defined preferences (i.e. alert rules, templates)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, Value>,
+}
+
+impl Serde for AppSettings {}
+impl Encryption for AppSettings {}
+
+#[cfg(test)]
+mod tests {
+    use nostr::Keys;
+
+    use super::*;
+
+    #[test]
+    fn test_app_settings_encryption() {
+        let keys = Keys::generate();
+        let mut settings = AppSettings {
+            hide_balances: true,
+            ..Default::default()
+        };
+        settings
+            .extra
+            .insert(String::from("theme"), Value::String(String::from("dark")));
+
+        let content: String = settings.encrypt_with_keys(&keys).unwrap();
+        assert_eq!(
+            AppSettings::decrypt_with_keys(&keys, content).unwrap(),
+            settings
+        );
+
+        // Missing fields
+        assert_eq!(
+            AppSettings::from_json("{}").unwrap(),
+            AppSettings::default()
+        );
+    }
+}
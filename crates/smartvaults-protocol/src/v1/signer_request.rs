@@ -0,0 +1,89 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use serde::{Deserialize, Serialize};
+use smartvaults_core::bitcoin::bip32::{ChildNumber, DerivationPath};
+use smartvaults_core::miniscript::DescriptorPublicKey;
+
+use super::util::Serde;
+
+/// BIP-86 (taproot) purpose
+pub const TAPROOT_PURPOSE: u32 = 86;
+
+/// Request to a contact for a signer (xpub) with a specific purpose
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerRequest {
+    /// BIP-43 purpose (i.e. `86` for taproot)
+    purpose: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    account: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl SignerRequest {
+    pub fn new(purpose: u32, account: Option<u32>, message: Option<String>) -> Self {
+        Self {
+            purpose,
+            account,
+            message,
+        }
+    }
+
+    /// Request a taproot signer
+    pub fn taproot(message: Option<String>) -> Self {
+        Self::new(TAPROOT_PURPOSE, None, message)
+    }
+
+    pub fn purpose(&self) -> u32 {
+        self.purpose
+    }
+
+    pub fn account(&self) -> Option<u32> {
+        self.account
+    }
+
+    pub fn message(&self) -> Option<String> {
+        self.message.clone()
+    }
+
+    /// Check if the descriptor derivation path match the requested purpose (and account)
+    pub fn matches(&self, descriptor: &DescriptorPublicKey) -> bool {
+        let path: DerivationPath = match descriptor.full_derivation_path() {
+            Some(path) => path,
+            None => return false,
+        };
+        let mut path = path.into_iter();
+
+        if path.next()
+            != Some(&ChildNumber::Hardened {
+                index: self.purpose,
+            })
+        {
+            return false;
+        }
+
+        match self.account {
+            Some(account) => path.nth(1) == Some(&ChildNumber::Hardened { index: account }),
+            None => true,
+        }
+    }
+}
+
+impl Serde for SignerRequest {}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_signer_request_matches() {
+        let descriptor = DescriptorPublicKey::from_str("[7356e457/86'/1'/784923']tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d/0/*").unwrap();
+        assert!(SignerRequest::taproot(None).matches(&descriptor));
+        assert!(SignerRequest::new(86, Some(784923), None).matches(&descriptor));
+        assert!(!SignerRequest::new(86, Some(0), None).matches(&descriptor));
+        assert!(!SignerRequest::new(84, None, None).matches(&descriptor));
+    }
+}
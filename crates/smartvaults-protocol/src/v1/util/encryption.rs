@@ -2,6 +2,7 @@
 // Distributed under the MIT software license
 
 use nostr::{key, util, Keys};
+use serde_json::Value;
 use smartvaults_core::bdk::wallet::ChangeSet;
 use smartvaults_core::bitcoin::secp256k1::SecretKey;
 use smartvaults_core::crypto::aes;
@@ -77,3 +78,7 @@ impl Encryption for Signer {}
 
 impl Serde for SharedSigner {}
 impl Encryption for SharedSigner {}
+
+/// Custom content (i.e. events of SDK plugins)
+impl Serde for Value {}
+impl Encryption for Value {}
@@ -0,0 +1,56 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::BTreeSet;
+
+use nostr::Url;
+use serde::{Deserialize, Serialize};
+
+use super::util::{Encryption, Serde};
+use super::{VelocityLimit, Whitelist};
+
+/// Vault metadata, shared between the vault members
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultMetadata {
+    /// Relays where the vault events are always published to and fetched from,
+    /// in addition to the relays of each member (i.e. a private treasury relay)
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub relays: BTreeSet<Url>,
+    /// Spending velocity limits (see [`velocity`](super::velocity))
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub velocity_limits: Vec<VelocityLimit>,
+    /// Whitelist of the destinations (see [`whitelist`](super::whitelist))
+    #[serde(default, skip_serializing_if = "Whitelist::is_empty")]
+    pub whitelist: Whitelist,
+}
+
+impl Serde for VaultMetadata {}
+impl Encryption for VaultMetadata {}
+
+#[cfg(test)]
+mod tests {
+    use nostr::Keys;
+
+    use super::*;
+
+    #[test]
+    fn test_vault_metadata_encryption() {
+        let keys = Keys::generate();
+        let mut metadata = VaultMetadata::default();
+        metadata
+            .relays
+            .insert(Url::parse("wss://relay.example.com").unwrap());
+
+        let content: String = metadata.encrypt_with_keys(&keys).unwrap();
+        assert_eq!(
+            VaultMetadata::decrypt_with_keys(&keys, content).unwrap(),
+            metadata
+        );
+
+        // Missing fields
+        assert_eq!(
+            VaultMetadata::from_json("{}").unwrap(),
+            VaultMetadata::default()
+        );
+    }
+}
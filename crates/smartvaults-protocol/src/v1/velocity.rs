@@ -0,0 +1,127 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Spending velocity limits
+//!
+//! A vault may limit the amount spent in a rolling period (i.e. max 1 BTC per 7 days) or by a
+//! single proposal, see
+//! [`VaultMetadata`](super::VaultMetadata). The members refuse to approve a proposal exceeding a limit,
+//! unless they explicitly override it: the approval event carries a `velocity-override` tag and the
+//! proposal can be finalized only if all its approvals are overrides.
+
+use core::fmt;
+use std::time::Duration;
+
+use nostr::{Event, Tag, TagKind};
+use serde::{Deserialize, Serialize};
+
+const VELOCITY_OVERRIDE_TAG: &str = "velocity-override";
+
+/// Max amount spent in a rolling period (or by a single proposal, if the period is zero)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VelocityLimit {
+    /// Amount in SAT
+    amount: u64,
+    /// Period in seconds (`0` for a max amount per proposal)
+    period: u64,
+}
+
+impl fmt::Display for VelocityLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_per_proposal() {
+            return write!(f, "{} sat per proposal", self.amount);
+        }
+
+        let hours: u64 = self.period / (60 * 60);
+        if hours > 0 && hours % 24 == 0 {
+            write!(f, "{} sat per {} days", self.amount, hours / 24)
+        } else {
+            write!(f, "{} sat per {} hours", self.amount, hours)
+        }
+    }
+}
+
+impl VelocityLimit {
+    pub fn new(amount: u64, period: Duration) -> Self {
+        Self {
+            amount,
+            period: period.as_secs(),
+        }
+    }
+
+    /// Max amount spent by a single proposal
+    ///
+    /// The amount is the one leaving the vault according to the PSBT (fee included), not the
+    /// declared one.
+    pub fn per_proposal(amount: u64) -> Self {
+        Self { amount, period: 0 }
+    }
+
+    /// Check if the limit applies to each proposal, instead of to a rolling period
+    pub fn is_per_proposal(&self) -> bool {
+        self.period == 0
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    pub fn period(&self) -> Duration {
+        Duration::from_secs(self.period)
+    }
+
+    /// Check if spending `amount` exceeds the limit, given the amount already `spent` in the period
+    pub fn is_exceeded_by(&self, spent: u64, amount: u64) -> bool {
+        spent.saturating_add(amount) > self.amount
+    }
+}
+
+/// Build the tag of an approval overriding the velocity limits
+pub fn override_tag() -> Tag {
+    Tag::Generic(
+        TagKind::Custom(VELOCITY_OVERRIDE_TAG.to_string()),
+        Vec::new(),
+    )
+}
+
+/// Check if the approval event overrides the velocity limits
+pub fn is_override(event: &Event) -> bool {
+    event.tags.iter().any(|tag| {
+        matches!(tag, Tag::Generic(TagKind::Custom(kind), ..) if kind == VELOCITY_OVERRIDE_TAG)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, Keys, Kind};
+
+    use super::*;
+
+    #[test]
+    fn test_velocity_limit() {
+        let limit = VelocityLimit::new(100_000_000, Duration::from_secs(7 * 24 * 60 * 60));
+        assert_eq!(limit.to_string(), "100000000 sat per 7 days");
+        assert!(!limit.is_exceeded_by(60_000_000, 40_000_000));
+        assert!(limit.is_exceeded_by(60_000_000, 40_000_001));
+
+        let limit = VelocityLimit::per_proposal(10_000_000);
+        assert!(limit.is_per_proposal());
+        assert_eq!(limit.to_string(), "10000000 sat per proposal");
+        assert!(!limit.is_exceeded_by(0, 10_000_000));
+        assert!(limit.is_exceeded_by(0, 10_000_001));
+    }
+
+    #[test]
+    fn test_override_tag() {
+        let keys = Keys::generate();
+        let event = EventBuilder::new(Kind::Custom(9291), "", [override_tag()])
+            .to_event(&keys)
+            .unwrap();
+        assert!(is_override(&event));
+
+        let event = EventBuilder::new(Kind::Custom(9291), "", [])
+            .to_event(&keys)
+            .unwrap();
+        assert!(!is_override(&event));
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Whitelist of destinations
+//!
+//! A vault may restrict the destinations of the spending proposals to a list of addresses and
+//! descriptors (i.e. the deposit descriptor of an exchange), shared between the members in the
+//! [`VaultMetadata`](super::VaultMetadata).
+
+use core::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::{Address, ScriptBuf};
+use smartvaults_core::miniscript::{Descriptor, DescriptorPublicKey};
+use thiserror::Error;
+
+/// Addresses of a whitelisted descriptor checked against the recipients
+pub const DESCRIPTOR_LOOKAHEAD: u32 = 1000;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid destination: expected an address or a descriptor")]
+    InvalidDestination,
+}
+
+/// What to do with the proposals to destinations not in the whitelist
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhitelistMode {
+    /// Any destination is allowed
+    #[default]
+    Disabled,
+    /// The proposals are allowed but require an extra confirmation of the approvers
+    Confirm,
+    /// The proposals are refused
+    Enforce,
+}
+
+impl fmt::Display for WhitelistMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "disabled"),
+            Self::Confirm => write!(f, "confirm"),
+            Self::Enforce => write!(f, "enforce"),
+        }
+    }
+}
+
+impl FromStr for WhitelistMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disabled" => Ok(Self::Disabled),
+            "confirm" => Ok(Self::Confirm),
+            "enforce" => Ok(Self::Enforce),
+            _ => Err(format!("unknown whitelist mode: {s}")),
+        }
+    }
+}
+
+/// Whitelisted address or descriptor
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct WhitelistedDestination {
+    /// Address or descriptor
+    destination: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+impl WhitelistedDestination {
+    pub fn new<S>(destination: S, label: Option<String>) -> Result<Self, Error>
+    where
+        S: Into<String>,
+    {
+        let destination: String = destination.into().trim().to_string();
+        if Address::<NetworkUnchecked>::from_str(&destination).is_err()
+            && Descriptor::<DescriptorPublicKey>::from_str(&destination).is_err()
+        {
+            return Err(Error::InvalidDestination);
+        }
+        Ok(Self { destination, label })
+    }
+
+    pub fn destination(&self) -> &str {
+        &self.destination
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Get the address, if the destination is not a descriptor
+    pub fn address(&self) -> Option<Address<NetworkUnchecked>> {
+        Address::from_str(&self.destination).ok()
+    }
+
+    /// Check if the address is the whitelisted one or one of the first
+    /// [`DESCRIPTOR_LOOKAHEAD`] addresses of the whitelisted descriptor
+    pub fn matches(&self, address: &Address<NetworkUnchecked>) -> bool {
+        let script: ScriptBuf = address.payload.script_pubkey();
+
+        if let Some(whitelisted) = self.address() {
+            return whitelisted.payload.script_pubkey() == script;
+        }
+
+        let descriptor = match Descriptor::<DescriptorPublicKey>::from_str(&self.destination) {
+            Ok(descriptor) => descriptor,
+            Err(_) => return false,
+        };
+        let descriptors = match descriptor.into_single_descriptors() {
+            Ok(descriptors) => descriptors,
+            Err(_) => return false,
+        };
+        descriptors.into_iter().any(|descriptor| {
+            let last: u32 = if descriptor.has_wildcard() {
+                DESCRIPTOR_LOOKAHEAD
+            } else {
+                1
+            };
+            (0..last).any(|index| match descriptor.at_derivation_index(index) {
+                Ok(derived) => derived.script_pubkey() == script,
+                Err(_) => false,
+            })
+        })
+    }
+}
+
+/// Whitelist of the destinations of a vault
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Whitelist {
+    #[serde(default)]
+    pub mode: WhitelistMode,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub destinations: Vec<WhitelistedDestination>,
+}
+
+impl Whitelist {
+    pub fn is_empty(&self) -> bool {
+        self.mode == WhitelistMode::Disabled && self.destinations.is_empty()
+    }
+
+    /// Check if the address is whitelisted (always `true` if the whitelist is disabled)
+    pub fn allows(&self, address: &Address<NetworkUnchecked>) -> bool {
+        self.mode == WhitelistMode::Disabled || self.destinations.iter().any(|d| d.matches(address))
+    }
+
+    /// Get the addresses not whitelisted
+    pub fn not_whitelisted<'a, I>(&self, addresses: I) -> Vec<Address<NetworkUnchecked>>
+    where
+        I: IntoIterator<Item = &'a Address<NetworkUnchecked>>,
+    {
+        addresses
+            .into_iter()
+            .filter(|address| !self.allows(address))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smartvaults_core::bitcoin::Network;
+
+    use super::*;
+
+    const DESCRIPTOR: &str = "tr([7356e457/86'/1'/784923']tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d/0/*)";
+    const OTHER_DESCRIPTOR: &str = "tr([4eb5d5a1/86'/1'/784923']tpubDCLskGdzStPPo1auRQygJUfbmLMwujWr7fmekdUMD7gqSpwEcRso4CfiP5GkRqfXFYkfqTujyvuehb7inymMhBJFdbJqFyHsHVRuwLKCSe9/0/*)";
+
+    fn derive(descriptor: &str, index: u32) -> Address<NetworkUnchecked> {
+        let descriptor = Descriptor::<DescriptorPublicKey>::from_str(descriptor).unwrap();
+        let script = descriptor
+            .at_derivation_index(index)
+            .unwrap()
+            .script_pubkey();
+        let address = Address::from_script(&script, Network::Testnet).unwrap();
+        Address::new(Network::Testnet, address.payload)
+    }
+
+    #[test]
+    fn test_whitelist() {
+        let address = derive(DESCRIPTOR, 10);
+        let other = derive(OTHER_DESCRIPTOR, 0);
+
+        let mut whitelist = Whitelist::default();
+        assert!(whitelist.allows(&address));
+
+        whitelist.mode = WhitelistMode::Enforce;
+        assert!(!whitelist.allows(&address));
+
+        whitelist
+            .destinations
+            .push(WhitelistedDestination::new(DESCRIPTOR, None).unwrap());
+        assert!(whitelist.allows(&address));
+        assert_eq!(
+            whitelist.not_whitelisted([&address, &other]),
+            vec![other.clone()]
+        );
+
+        let destination: String = other.clone().assume_checked().to_string();
+        whitelist
+            .destinations
+            .push(WhitelistedDestination::new(destination, None).unwrap());
+        assert!(whitelist.allows(&other));
+
+        assert!(WhitelistedDestination::new("invalid", None).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+PRAGMA user_version = 4; -- Schema version
+
+-- Checkpoint saved on shutdown
+CREATE TABLE IF NOT EXISTS checkpoint (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    timestamp BIGINT NOT NULL,
+    clean BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+-- Wallets last sync
+CREATE TABLE IF NOT EXISTS timechain_sync (
+    descriptor_hash BLOB PRIMARY KEY NOT NULL,
+    last_sync BIGINT NOT NULL
+);
@@ -0,0 +1,7 @@
+PRAGMA user_version = 5; -- Schema version
+
+-- Pinned (favorite) vaults
+CREATE TABLE IF NOT EXISTS pinned_vaults (
+    policy_id BLOB PRIMARY KEY NOT NULL,
+    timestamp BIGINT NOT NULL
+);
@@ -0,0 +1,8 @@
+PRAGMA user_version = 6; -- Schema version
+
+-- Local device identifier
+CREATE TABLE IF NOT EXISTS device (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    device_id TEXT NOT NULL,
+    name TEXT
+);
@@ -0,0 +1,11 @@
+PRAGMA user_version = 7; -- Schema version
+
+-- Vaults (wallet policies) registered on hardware signers
+CREATE TABLE IF NOT EXISTS device_registration (
+    policy_id TEXT NOT NULL,
+    fingerprint TEXT NOT NULL,
+    device TEXT NOT NULL,
+    proof TEXT,
+    timestamp INTEGER NOT NULL,
+    PRIMARY KEY(policy_id, fingerprint)
+);
@@ -0,0 +1,14 @@
+PRAGMA user_version = 8; -- Schema version
+
+-- Nostr Connect permission profiles
+CREATE TABLE IF NOT EXISTS nostr_connect_profiles (
+    name TEXT PRIMARY KEY NOT NULL,
+    methods TEXT NOT NULL, -- Comma separated NIP46 methods
+    kinds TEXT NOT NULL -- Comma separated event kinds allowed for `sign_event` (empty for any kind)
+);
+
+INSERT OR IGNORE INTO nostr_connect_profiles (name, methods, kinds) VALUES ('social app', 'sign_event', '0,1,7');
+INSERT OR IGNORE INTO nostr_connect_profiles (name, methods, kinds) VALUES ('wallet', 'nip04_encrypt,nip04_decrypt', '');
+
+-- Profile assigned to the session (NULL for no restrictions)
+ALTER TABLE nostr_connect_sessions ADD COLUMN profile TEXT;
@@ -0,0 +1,19 @@
+PRAGMA user_version = 9; -- Schema version
+
+-- Time windows successfully synced, per relay and per filter scope (kinds/authors)
+CREATE TABLE IF NOT EXISTS sync_windows (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    relay_url TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    since BIGINT NOT NULL,
+    until BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS sync_windows_index ON sync_windows(relay_url, scope);
+
+-- Import the legacy `last_sync` of the relays (the contacts will be re-synced from scratch)
+INSERT INTO sync_windows (relay_url, scope, since, until) SELECT url, 'author', 0, last_sync FROM relays WHERE last_sync IS NOT NULL;
+INSERT INTO sync_windows (relay_url, scope, since, until) SELECT url, 'pubkey', 0, last_sync FROM relays WHERE last_sync IS NOT NULL;
+INSERT INTO sync_windows (relay_url, scope, since, until) SELECT url, 'nostr-connect', 0, last_sync FROM relays WHERE last_sync IS NOT NULL;
+INSERT INTO sync_windows (relay_url, scope, since, until) SELECT url, 'account', 0, last_sync FROM relays WHERE last_sync IS NOT NULL;
+INSERT INTO sync_windows (relay_url, scope, since, until) SELECT url, 'key-agents', 0, last_sync FROM relays WHERE last_sync IS NOT NULL;
@@ -0,0 +1,12 @@
+PRAGMA user_version = 10; -- Schema version
+
+-- Decrypted content (re-encrypted with the store key) of the custom event kinds handled by the SDK plugins
+CREATE TABLE IF NOT EXISTS plugin_data (
+    plugin TEXT NOT NULL,
+    vault_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    author TEXT NOT NULL,
+    value BLOB NOT NULL,
+    timestamp BIGINT NOT NULL,
+    PRIMARY KEY(plugin, vault_id, key)
+);
@@ -0,0 +1,4 @@
+PRAGMA user_version = 11; -- Schema version
+
+-- Wallets last full scan (the incremental syncs check only the revealed addresses)
+ALTER TABLE timechain_sync ADD COLUMN last_full_scan BIGINT NOT NULL DEFAULT 0;
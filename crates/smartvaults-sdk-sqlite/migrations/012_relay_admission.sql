@@ -0,0 +1,10 @@
+PRAGMA user_version = 12; -- Schema version
+
+-- Admission status of the relays requiring payment (or admission)
+CREATE TABLE IF NOT EXISTS relay_admission (
+    relay_url TEXT PRIMARY KEY NOT NULL,
+    status TEXT NOT NULL,
+    payments_url TEXT DEFAULT NULL,
+    message TEXT DEFAULT NULL,
+    timestamp BIGINT NOT NULL
+);
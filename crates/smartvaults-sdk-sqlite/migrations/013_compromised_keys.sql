@@ -0,0 +1,9 @@
+PRAGMA user_version = 13; -- Schema version
+
+-- Keys (master fingerprints) marked as compromised
+CREATE TABLE IF NOT EXISTS compromised_keys (
+    fingerprint TEXT PRIMARY KEY NOT NULL,
+    reason TEXT DEFAULT NULL,
+    shared BOOLEAN NOT NULL DEFAULT 0,
+    timestamp BIGINT NOT NULL
+);
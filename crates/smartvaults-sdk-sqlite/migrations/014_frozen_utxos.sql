@@ -0,0 +1,10 @@
+PRAGMA user_version = 14; -- Schema version
+
+-- UTXOs frozen by the user (excluded from the coin selection)
+CREATE TABLE IF NOT EXISTS frozen_utxos (
+    policy_id BLOB NOT NULL,
+    txid TEXT NOT NULL,
+    vout INTEGER NOT NULL,
+    timestamp BIGINT NOT NULL,
+    PRIMARY KEY(policy_id, txid, vout)
+);
@@ -0,0 +1,9 @@
+PRAGMA user_version = 15; -- Schema version
+
+-- Vaults watched for the maturity of a recovery (timelocked) spending path
+CREATE TABLE IF NOT EXISTS recovery_watch (
+    policy_id BLOB PRIMARY KEY NOT NULL,
+    sweep_address TEXT,
+    proposal_id BLOB,
+    timestamp BIGINT NOT NULL
+);
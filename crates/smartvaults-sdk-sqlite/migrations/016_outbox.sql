@@ -0,0 +1,7 @@
+PRAGMA user_version = 16; -- Schema version
+
+-- Own events not seen on any relay at shutdown, sent again on next open
+CREATE TABLE IF NOT EXISTS outbox (
+    event_id TEXT PRIMARY KEY NOT NULL,
+    event TEXT NOT NULL
+);
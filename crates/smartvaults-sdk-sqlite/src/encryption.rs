@@ -42,24 +42,42 @@ impl From<chacha20poly1305::Error> for Error {
     }
 }
 
+/// Encrypt raw data
+pub(crate) fn encrypt_bytes(cipher: &XChaCha20Poly1305, data: &[u8]) -> Result<Vec<u8>, Error> {
+    // Generate 192-bit nonce
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    // Encrypt
+    let ciphertext: Vec<u8> = cipher.encrypt(&nonce, data)?;
+
+    // Compose payload
+    let mut payload: Vec<u8> = Vec::new();
+    payload.extend_from_slice(nonce.as_slice());
+    payload.extend(ciphertext);
+
+    Ok(payload)
+}
+
+/// Decrypt raw data
+pub(crate) fn decrypt_bytes(cipher: &XChaCha20Poly1305, payload: &[u8]) -> Result<Vec<u8>, Error> {
+    // Get data from payload
+    let nonce: &[u8] = payload
+        .get(0..24)
+        .ok_or_else(|| Error::NotFound(String::from("nonce")))?;
+    let ciphertext: &[u8] = payload
+        .get(24..)
+        .ok_or_else(|| Error::NotFound(String::from("ciphertext")))?;
+
+    // Decrypt
+    Ok(cipher.decrypt(nonce.into(), ciphertext)?)
+}
+
 pub trait StoreEncryption: Serde {
     /// Encrypt
     fn encrypt(&self, cipher: &XChaCha20Poly1305) -> Result<Vec<u8>, Error> {
         // Serialize to JSON
         let json = self.as_json();
-
-        // Generate 192-bit nonce
-        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
-
-        // Encrypt
-        let ciphertext: Vec<u8> = cipher.encrypt(&nonce, json.as_bytes())?;
-
-        // Compose payload
-        let mut payload: Vec<u8> = Vec::new();
-        payload.extend_from_slice(nonce.as_slice());
-        payload.extend(ciphertext);
-
-        Ok(payload)
+        encrypt_bytes(cipher, json.as_bytes())
     }
 
     /// Decrypt
@@ -67,19 +85,7 @@ pub trait StoreEncryption: Serde {
     where
         T: AsRef<[u8]>,
     {
-        let payload: &[u8] = content.as_ref();
-
-        // Get data from payload
-        let nonce: &[u8] = payload
-            .get(0..24)
-            .ok_or_else(|| Error::NotFound(String::from("nonce")))?;
-        let ciphertext: &[u8] = payload
-            .get(24..)
-            .ok_or_else(|| Error::NotFound(String::from("ciphertext")))?;
-
-        // Decrypt
-        let data: Vec<u8> = cipher.decrypt(nonce.into(), ciphertext.as_ref())?;
-
+        let data: Vec<u8> = decrypt_bytes(cipher, content.as_ref())?;
         deserialize(data).map_err(|e| Error::Json(e.to_string()))
     }
 }
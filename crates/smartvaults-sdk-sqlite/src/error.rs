@@ -58,6 +58,9 @@ pub enum Error {
     /// Hash error
     #[error(transparent)]
     Hash(#[from] bitcoin::hashes::hex::Error),
+    /// Address error
+    #[error(transparent)]
+    Address(#[from] bitcoin::address::Error),
     /// Policy error
     #[error(transparent)]
     Policy(#[from] policy::Error),
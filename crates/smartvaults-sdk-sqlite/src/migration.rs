@@ -9,7 +9,7 @@ use rusqlite::Connection;
 use super::Error;
 
 /// Latest database version
-pub const DB_VERSION: usize = 3;
+pub const DB_VERSION: usize = 16;
 
 /// Startup DB Pragmas
 pub const STARTUP_SQL: &str = r##"
@@ -61,20 +61,60 @@ pub(crate) async fn run(conn: &Object) -> Result<(), Error> {
                     curr_version = mig_2_to_3(conn)?;
                 }
 
-                // if curr_version == 3 {
-                // curr_version = mig_3_to_4(conn)?;
-                // }
-                //
-                // if curr_version == 4 {
-                // curr_version = mig_4_to_5(conn)?;
-                // }
-                //
-                // if curr_version == 5 {
-                // curr_version = mig_5_to_6(conn)?;
-                // }
-                //
-                // if curr_version == 6 {
-                // curr_version = mig_6_to_7(conn)?;
+                if curr_version == 3 {
+                    curr_version = mig_3_to_4(conn)?;
+                }
+
+                if curr_version == 4 {
+                    curr_version = mig_4_to_5(conn)?;
+                }
+
+                if curr_version == 5 {
+                    curr_version = mig_5_to_6(conn)?;
+                }
+
+                if curr_version == 6 {
+                    curr_version = mig_6_to_7(conn)?;
+                }
+
+                if curr_version == 7 {
+                    curr_version = mig_7_to_8(conn)?;
+                }
+
+                if curr_version == 8 {
+                    curr_version = mig_8_to_9(conn)?;
+                }
+
+                if curr_version == 9 {
+                    curr_version = mig_9_to_10(conn)?;
+                }
+
+                if curr_version == 10 {
+                    curr_version = mig_10_to_11(conn)?;
+                }
+
+                if curr_version == 11 {
+                    curr_version = mig_11_to_12(conn)?;
+                }
+
+                if curr_version == 12 {
+                    curr_version = mig_12_to_13(conn)?;
+                }
+
+                if curr_version == 13 {
+                    curr_version = mig_13_to_14(conn)?;
+                }
+
+                if curr_version == 14 {
+                    curr_version = mig_14_to_15(conn)?;
+                }
+
+                if curr_version == 15 {
+                    curr_version = mig_15_to_16(conn)?;
+                }
+
+                // if curr_version == 16 {
+                // curr_version = mig_16_to_17(conn)?;
                 // }
 
                 if curr_version == DB_VERSION {
@@ -116,5 +156,83 @@ fn mig_1_to_2(conn: &mut Connection) -> Result<usize, Error> {
 fn mig_2_to_3(conn: &mut Connection) -> Result<usize, Error> {
     conn.execute_batch(include_str!("../migrations/003_drop_again.sql"))?;
     tracing::info!("database schema upgraded v2 -> v3");
-    Ok(2)
+    Ok(3)
+}
+
+fn mig_3_to_4(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/004_checkpoint.sql"))?;
+    tracing::info!("database schema upgraded v3 -> v4");
+    Ok(4)
+}
+
+fn mig_4_to_5(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/005_pinned_vaults.sql"))?;
+    tracing::info!("database schema upgraded v4 -> v5");
+    Ok(5)
+}
+
+fn mig_5_to_6(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/006_device.sql"))?;
+    tracing::info!("database schema upgraded v5 -> v6");
+    Ok(6)
+}
+
+fn mig_6_to_7(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/007_device_registration.sql"))?;
+    tracing::info!("database schema upgraded v6 -> v7");
+    Ok(7)
+}
+
+fn mig_7_to_8(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/008_nostr_connect_profiles.sql"))?;
+    tracing::info!("database schema upgraded v7 -> v8");
+    Ok(8)
+}
+
+fn mig_8_to_9(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/009_sync_windows.sql"))?;
+    tracing::info!("database schema upgraded v8 -> v9");
+    Ok(9)
+}
+
+fn mig_9_to_10(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/010_plugin_data.sql"))?;
+    tracing::info!("database schema upgraded v9 -> v10");
+    Ok(10)
+}
+
+fn mig_10_to_11(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/011_full_scan.sql"))?;
+    tracing::info!("database schema upgraded v10 -> v11");
+    Ok(11)
+}
+
+fn mig_11_to_12(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/012_relay_admission.sql"))?;
+    tracing::info!("database schema upgraded v11 -> v12");
+    Ok(12)
+}
+
+fn mig_12_to_13(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/013_compromised_keys.sql"))?;
+    tracing::info!("database schema upgraded v12 -> v13");
+    Ok(13)
+}
+
+fn mig_13_to_14(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/014_frozen_utxos.sql"))?;
+    tracing::info!("database schema upgraded v13 -> v14");
+    Ok(14)
+}
+
+fn mig_14_to_15(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/015_recovery_watch.sql"))?;
+    tracing::info!("database schema upgraded v14 -> v15");
+    Ok(15)
+}
+
+fn mig_15_to_16(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/016_outbox.sql"))?;
+    tracing::info!("database schema upgraded v15 -> v16");
+    Ok(16)
 }
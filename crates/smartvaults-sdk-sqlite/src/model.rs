@@ -1,7 +1,13 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use smartvaults_protocol::nostr::nips::nip46::Message;
+use std::collections::BTreeSet;
+use std::fmt;
+
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_core::bitcoin::Address;
+use smartvaults_protocol::nostr::nips::nip46::{Message, Request};
 use smartvaults_protocol::nostr::{EventId, PublicKey, Timestamp};
 
 #[derive(Debug, Clone)]
@@ -12,3 +18,216 @@ pub struct NostrConnectRequest {
     pub timestamp: Timestamp,
     pub approved: bool,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub timestamp: Timestamp,
+    /// `true` if the checkpoint was saved during a graceful shutdown
+    pub clean: bool,
+}
+
+/// Registration of a vault (wallet policy) on a hardware signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceRegistration {
+    pub policy_id: EventId,
+    pub fingerprint: Fingerprint,
+    /// Device model (i.e. `ledger`, `coldcard`)
+    pub device: String,
+    /// Registration proof returned by the device (i.e. Ledger wallet policy HMAC)
+    pub proof: Option<String>,
+    pub timestamp: Timestamp,
+}
+
+/// Key marked as compromised by the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompromisedKey {
+    /// Master fingerprint
+    pub fingerprint: Fingerprint,
+    pub reason: Option<String>,
+    /// Synced across the user devices
+    pub shared: bool,
+    pub timestamp: Timestamp,
+}
+
+/// Vault watched for the maturity of a recovery (timelocked) spending path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryWatch {
+    pub policy_id: EventId,
+    /// Sweep the funds to this address as soon as the path matures (automatic proposal)
+    pub sweep_address: Option<Address<NetworkUnchecked>>,
+    /// Last automatic sweep proposal
+    pub proposal_id: Option<EventId>,
+    pub timestamp: Timestamp,
+}
+
+/// Nostr Connect permission profile (assignable per session)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NostrConnectProfile {
+    pub name: String,
+    /// Allowed NIP46 methods (i.e. `sign_event`, `nip04_encrypt`)
+    pub methods: BTreeSet<String>,
+    /// Event kinds allowed for `sign_event` (empty for any kind)
+    pub kinds: BTreeSet<u64>,
+}
+
+impl NostrConnectProfile {
+    /// Check if the request is allowed by the profile
+    pub fn allows(&self, request: &Request) -> bool {
+        if !self.methods.contains(&request.method()) {
+            return false;
+        }
+
+        match request {
+            Request::SignEvent(unsigned) => {
+                self.kinds.is_empty() || self.kinds.contains(&unsigned.kind.as_u64())
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Data of a custom event kind handled by an SDK plugin
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginData {
+    pub plugin: String,
+    pub vault_id: EventId,
+    /// Identifier of the data (i.e. the `d` tag or the event ID)
+    pub key: String,
+    pub author: PublicKey,
+    /// Decrypted content
+    pub value: String,
+    pub timestamp: Timestamp,
+}
+
+/// Time window successfully synced from a relay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SyncWindow {
+    pub since: Timestamp,
+    pub until: Timestamp,
+}
+
+impl SyncWindow {
+    pub fn new(since: Timestamp, until: Timestamp) -> Self {
+        Self { since, until }
+    }
+
+    /// Merge the overlapping (or adjacent) windows
+    ///
+    /// The returned windows are sorted by `since`.
+    pub fn merge<I>(windows: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        let mut windows: Vec<Self> = windows.into_iter().filter(|w| w.since <= w.until).collect();
+        windows.sort();
+
+        let mut merged: Vec<Self> = Vec::with_capacity(windows.len());
+        for window in windows.into_iter() {
+            match merged.last_mut() {
+                Some(last) if window.since.as_u64() <= last.until.as_u64() + 1 => {
+                    last.until = last.until.max(window.until);
+                }
+                _ => merged.push(window),
+            }
+        }
+        merged
+    }
+
+    /// Get the missing windows: the ones between the synced windows and the one before the first
+    /// synced window (every scope is synced from the beginning)
+    pub fn gaps(windows: &[Self]) -> Vec<Self> {
+        let merged: Vec<Self> = Self::merge(windows.iter().copied());
+        let mut gaps: Vec<Self> = Vec::with_capacity(merged.len());
+        if let Some(first) = merged.first() {
+            if first.since > Timestamp::from(0) {
+                gaps.push(Self::new(Timestamp::from(0), first.since));
+            }
+        }
+        gaps.extend(merged.windows(2).map(|w| Self::new(w[0].until, w[1].since)));
+        gaps
+    }
+}
+
+/// Admission status of a relay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelayAdmissionStatus {
+    /// No payment or admission required
+    Open,
+    /// The relay requires a payment (or an admission) to accept the events
+    PaymentRequired,
+    /// Events accepted after the payment (or the admission)
+    Admitted,
+    /// Events rejected by the relay (i.e. `restricted:` or `blocked:` messages)
+    Rejected,
+}
+
+impl fmt::Display for RelayAdmissionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Open => write!(f, "open"),
+            Self::PaymentRequired => write!(f, "payment-required"),
+            Self::Admitted => write!(f, "admitted"),
+            Self::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+impl RelayAdmissionStatus {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "open" => Some(Self::Open),
+            "payment-required" => Some(Self::PaymentRequired),
+            "admitted" => Some(Self::Admitted),
+            "rejected" => Some(Self::Rejected),
+            _ => None,
+        }
+    }
+
+    /// Check if the relay is not accepting the events
+    pub fn is_blocked(&self) -> bool {
+        matches!(self, Self::PaymentRequired | Self::Rejected)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayAdmission {
+    pub status: RelayAdmissionStatus,
+    /// Where to pay (NIP-11 `payments_url`)
+    pub payments_url: Option<String>,
+    /// Last message from the relay (i.e. the rejection reason)
+    pub message: Option<String>,
+    pub timestamp: Timestamp,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(since: u64, until: u64) -> SyncWindow {
+        SyncWindow::new(Timestamp::from(since), Timestamp::from(until))
+    }
+
+    #[test]
+    fn test_merge_sync_windows() {
+        let merged =
+            SyncWindow::merge([window(50, 60), window(0, 10), window(5, 20), window(21, 30)]);
+        assert_eq!(merged, vec![window(0, 30), window(50, 60)]);
+
+        // Invalid windows are ignored
+        assert!(SyncWindow::merge([window(10, 5)]).is_empty());
+    }
+
+    #[test]
+    fn test_sync_windows_gaps() {
+        let windows = [window(0, 10), window(40, 50), window(20, 30)];
+        assert_eq!(
+            SyncWindow::gaps(&windows),
+            vec![window(10, 20), window(30, 40)]
+        );
+        assert!(SyncWindow::gaps(&[window(0, 10), window(5, 30)]).is_empty());
+
+        // Truncated first sync
+        assert_eq!(SyncWindow::gaps(&[window(20, 30)]), vec![window(0, 20)]);
+        assert!(SyncWindow::gaps(&[]).is_empty());
+    }
+}
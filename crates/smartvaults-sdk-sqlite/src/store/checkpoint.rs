@@ -0,0 +1,222 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use rusqlite::Connection;
+use smartvaults_core::hashes::sha256::Hash as Sha256Hash;
+use smartvaults_protocol::nostr::{Event, EventId, JsonUtil, Timestamp};
+
+use super::{Error, Store};
+use crate::model::Checkpoint;
+
+impl Store {
+    pub async fn save_checkpoint(&self, timestamp: Timestamp, clean: bool) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| save_checkpoint(conn, timestamp, clean))
+            .await?
+    }
+
+    pub async fn get_checkpoint(&self) -> Result<Checkpoint, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| get_checkpoint(conn)).await?
+    }
+
+    /// Mark the current checkpoint as not clean (i.e. the app is running)
+    pub async fn invalidate_checkpoint(&self) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| invalidate_checkpoint(conn))
+            .await?
+    }
+
+    /// Save the events to send again on next open
+    pub async fn save_outbox_events(&self, events: Vec<Event>) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| save_outbox_events(conn, events))
+            .await?
+    }
+
+    pub async fn get_outbox_events(&self) -> Result<Vec<Event>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| get_outbox_events(conn)).await?
+    }
+
+    pub async fn delete_outbox_event(&self, event_id: EventId) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "DELETE FROM outbox WHERE event_id = ?;",
+                [event_id.to_hex()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn save_wallet_last_sync(
+        &self,
+        descriptor_hash: Sha256Hash,
+        timestamp: Timestamp,
+    ) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let last_sync: u64 = timestamp.as_u64();
+            conn.execute(
+                "INSERT INTO timechain_sync (descriptor_hash, last_sync) VALUES (?, ?) ON CONFLICT(descriptor_hash) DO UPDATE SET last_sync = ?;",
+                (descriptor_hash.to_string(), last_sync, last_sync),
+            )?;
+            Ok(())
+        }).await?
+    }
+
+    pub async fn get_wallet_last_sync(
+        &self,
+        descriptor_hash: Sha256Hash,
+    ) -> Result<Timestamp, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT last_sync FROM timechain_sync WHERE descriptor_hash = ?;",
+            )?;
+            let mut rows = stmt.query([descriptor_hash.to_string()])?;
+            let row = rows
+                .next()?
+                .ok_or(Error::NotFound("wallet last sync".into()))?;
+            let last_sync: u64 = row.get(0)?;
+            Ok(Timestamp::from(last_sync))
+        })
+        .await?
+    }
+
+    pub async fn save_wallet_last_full_scan(
+        &self,
+        descriptor_hash: Sha256Hash,
+        timestamp: Timestamp,
+    ) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let last_full_scan: u64 = timestamp.as_u64();
+            conn.execute(
+                "INSERT INTO timechain_sync (descriptor_hash, last_sync, last_full_scan) VALUES (?, ?, ?) ON CONFLICT(descriptor_hash) DO UPDATE SET last_full_scan = ?;",
+                (descriptor_hash.to_string(), last_full_scan, last_full_scan, last_full_scan),
+            )?;
+            Ok(())
+        }).await?
+    }
+
+    pub async fn get_wallet_last_full_scan(
+        &self,
+        descriptor_hash: Sha256Hash,
+    ) -> Result<Timestamp, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT last_full_scan FROM timechain_sync WHERE descriptor_hash = ?;",
+            )?;
+            let mut rows = stmt.query([descriptor_hash.to_string()])?;
+            let row = rows
+                .next()?
+                .ok_or(Error::NotFound("wallet last full scan".into()))?;
+            let last_full_scan: u64 = row.get(0)?;
+            Ok(Timestamp::from(last_full_scan))
+        })
+        .await?
+    }
+}
+
+fn save_checkpoint(conn: &Connection, timestamp: Timestamp, clean: bool) -> Result<(), Error> {
+    let timestamp: u64 = timestamp.as_u64();
+    conn.execute(
+        "INSERT INTO checkpoint (id, timestamp, clean) VALUES (0, ?, ?) ON CONFLICT(id) DO UPDATE SET timestamp = ?, clean = ?;",
+        (timestamp, clean, timestamp, clean),
+    )?;
+    Ok(())
+}
+
+fn get_checkpoint(conn: &Connection) -> Result<Checkpoint, Error> {
+    let mut stmt = conn.prepare_cached("SELECT timestamp, clean FROM checkpoint WHERE id = 0;")?;
+    let mut rows = stmt.query([])?;
+    let row = rows.next()?.ok_or(Error::NotFound("checkpoint".into()))?;
+    let timestamp: u64 = row.get(0)?;
+    Ok(Checkpoint {
+        timestamp: Timestamp::from(timestamp),
+        clean: row.get(1)?,
+    })
+}
+
+fn invalidate_checkpoint(conn: &Connection) -> Result<(), Error> {
+    conn.execute("UPDATE checkpoint SET clean = ? WHERE id = 0;", [false])?;
+    Ok(())
+}
+
+fn save_outbox_events(conn: &Connection, events: Vec<Event>) -> Result<(), Error> {
+    let mut stmt =
+        conn.prepare_cached("INSERT OR IGNORE INTO outbox (event_id, event) VALUES (?, ?);")?;
+    for event in events.into_iter() {
+        stmt.execute((event.id.to_hex(), event.as_json()))?;
+    }
+    Ok(())
+}
+
+fn get_outbox_events(conn: &Connection) -> Result<Vec<Event>, Error> {
+    let mut stmt = conn.prepare_cached("SELECT event FROM outbox;")?;
+    let mut rows = stmt.query([])?;
+    let mut events: Vec<Event> = Vec::new();
+    while let Some(row) = rows.next()? {
+        let json: String = row.get(0)?;
+        events.push(Event::from_json(json)?);
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use smartvaults_protocol::nostr::{EventBuilder, Keys};
+
+    use super::*;
+
+    fn conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../migrations/004_checkpoint.sql"))
+            .unwrap();
+        conn.execute_batch(include_str!("../../migrations/016_outbox.sql"))
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let conn = conn();
+        assert!(matches!(get_checkpoint(&conn), Err(Error::NotFound(..))));
+
+        let timestamp = Timestamp::from(1_700_000_000);
+        save_checkpoint(&conn, timestamp, true).unwrap();
+        assert_eq!(
+            get_checkpoint(&conn).unwrap(),
+            Checkpoint {
+                timestamp,
+                clean: true
+            }
+        );
+
+        // Invalidated while running
+        invalidate_checkpoint(&conn).unwrap();
+        assert!(!get_checkpoint(&conn).unwrap().clean);
+
+        // Overwritten on next shutdown
+        let timestamp = Timestamp::from(1_700_000_100);
+        save_checkpoint(&conn, timestamp, true).unwrap();
+        assert_eq!(get_checkpoint(&conn).unwrap().timestamp, timestamp);
+    }
+
+    #[test]
+    fn test_outbox() {
+        let conn = conn();
+        assert!(get_outbox_events(&conn).unwrap().is_empty());
+
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("unsent", [])
+            .to_event(&keys)
+            .unwrap();
+        save_outbox_events(&conn, vec![event.clone(), event.clone()]).unwrap();
+        assert_eq!(get_outbox_events(&conn).unwrap(), vec![event]);
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_protocol::nostr::Timestamp;
+
+use super::{Error, Store};
+use crate::model::CompromisedKey;
+
+impl Store {
+    /// Mark a key as compromised (replacing the reason, if already marked)
+    pub async fn save_compromised_key(&self, key: CompromisedKey) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO compromised_keys (fingerprint, reason, shared, timestamp) VALUES (?, ?, ?, ?);",
+                (
+                    key.fingerprint.to_string(),
+                    key.reason,
+                    key.shared,
+                    key.timestamp.as_u64(),
+                ),
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn get_compromised_keys(&self) -> Result<Vec<CompromisedKey>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT fingerprint, reason, shared, timestamp FROM compromised_keys ORDER BY timestamp ASC;",
+            )?;
+            let mut rows = stmt.query([])?;
+            let mut keys: Vec<CompromisedKey> = Vec::new();
+            while let Ok(Some(row)) = rows.next() {
+                let fingerprint: String = row.get(0)?;
+                let timestamp: u64 = row.get(3)?;
+                keys.push(CompromisedKey {
+                    fingerprint: Fingerprint::from_str(&fingerprint)?,
+                    reason: row.get(1)?,
+                    shared: row.get(2)?,
+                    timestamp: Timestamp::from(timestamp),
+                });
+            }
+            Ok(keys)
+        })
+        .await?
+    }
+
+    pub async fn delete_compromised_key(&self, fingerprint: Fingerprint) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "DELETE FROM compromised_keys WHERE fingerprint = ?;",
+                [fingerprint.to_string()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+}
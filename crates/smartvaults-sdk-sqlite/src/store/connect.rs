@@ -1,14 +1,14 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::str::FromStr;
 
 use smartvaults_protocol::nostr::nips::nip46::{Message as NIP46Message, NostrConnectURI};
 use smartvaults_protocol::nostr::{EventId, JsonUtil, PublicKey, Timestamp, Url};
 
 use super::Store;
-use crate::model::NostrConnectRequest;
+use crate::model::{NostrConnectProfile, NostrConnectRequest};
 use crate::Error;
 
 impl Store {
@@ -258,4 +258,130 @@ impl Store {
         })
         .await?
     }
+
+    /// Save (or replace) a permission profile
+    pub async fn save_nostr_connect_profile(
+        &self,
+        profile: NostrConnectProfile,
+    ) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let methods: Vec<String> = profile.methods.into_iter().collect();
+            let kinds: Vec<String> = profile.kinds.into_iter().map(|k| k.to_string()).collect();
+            conn.execute(
+                "INSERT OR REPLACE INTO nostr_connect_profiles (name, methods, kinds) VALUES (?, ?, ?);",
+                (profile.name, methods.join(","), kinds.join(",")),
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn get_nostr_connect_profiles(&self) -> Result<Vec<NostrConnectProfile>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT name, methods, kinds FROM nostr_connect_profiles ORDER BY name;",
+            )?;
+            let mut rows = stmt.query([])?;
+            let mut profiles = Vec::new();
+            while let Ok(Some(row)) = rows.next() {
+                let methods: String = row.get(1)?;
+                let kinds: String = row.get(2)?;
+                profiles.push(profile_from_columns(row.get(0)?, &methods, &kinds));
+            }
+            Ok(profiles)
+        })
+        .await?
+    }
+
+    /// Delete a permission profile (the sessions that use it are left without restrictions)
+    pub async fn delete_nostr_connect_profile(&self, name: String) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "UPDATE nostr_connect_sessions SET profile = NULL WHERE profile = ?;",
+                [&name],
+            )?;
+            conn.execute(
+                "DELETE FROM nostr_connect_profiles WHERE name = ?;",
+                [&name],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Assign a permission profile to a session (`None` to remove restrictions)
+    pub async fn set_nostr_connect_session_profile(
+        &self,
+        app_public_key: PublicKey,
+        profile: Option<String>,
+    ) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "UPDATE nostr_connect_sessions SET profile = ? WHERE app_public_key = ?;",
+                (profile, app_public_key.to_string()),
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Get the permission profile assigned to a session
+    pub async fn get_nostr_connect_session_profile(
+        &self,
+        app_public_key: PublicKey,
+    ) -> Result<Option<NostrConnectProfile>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached("SELECT p.name, p.methods, p.kinds FROM nostr_connect_sessions s JOIN nostr_connect_profiles p ON s.profile = p.name WHERE s.app_public_key = ?;")?;
+            let mut rows = stmt.query([app_public_key.to_string()])?;
+            match rows.next()? {
+                Some(row) => {
+                    let methods: String = row.get(1)?;
+                    let kinds: String = row.get(2)?;
+                    Ok(Some(profile_from_columns(row.get(0)?, &methods, &kinds)))
+                }
+                None => Ok(None),
+            }
+        })
+        .await?
+    }
+
+    /// Get the name of the profiles assigned to the sessions
+    pub async fn get_nostr_connect_sessions_profiles(
+        &self,
+    ) -> Result<HashMap<PublicKey, String>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT app_public_key, profile FROM nostr_connect_sessions WHERE profile IS NOT NULL;",
+            )?;
+            let mut rows = stmt.query([])?;
+            let mut profiles = HashMap::new();
+            while let Ok(Some(row)) = rows.next() {
+                let app_public_key: String = row.get(0)?;
+                profiles.insert(PublicKey::from_str(&app_public_key)?, row.get(1)?);
+            }
+            Ok(profiles)
+        })
+        .await?
+    }
+}
+
+fn profile_from_columns(name: String, methods: &str, kinds: &str) -> NostrConnectProfile {
+    NostrConnectProfile {
+        name,
+        methods: methods
+            .split(',')
+            .filter(|m| !m.is_empty())
+            .map(String::from)
+            .collect(),
+        kinds: kinds
+            .split(',')
+            .filter_map(|k| k.parse::<u64>().ok())
+            .collect::<BTreeSet<u64>>(),
+    }
 }
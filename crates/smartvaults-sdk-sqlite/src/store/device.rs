@@ -0,0 +1,31 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use super::{Error, Store};
+
+impl Store {
+    /// Save the local device identifier (and optional name)
+    pub async fn save_device(&self, device_id: String, name: Option<String>) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT INTO device (id, device_id, name) VALUES (0, ?, ?) ON CONFLICT(id) DO UPDATE SET device_id = ?, name = ?;",
+                (&device_id, &name, &device_id, &name),
+            )?;
+            Ok(())
+        }).await?
+    }
+
+    /// Get the local device identifier and name
+    pub async fn get_device(&self) -> Result<(String, Option<String>), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt =
+                conn.prepare_cached("SELECT device_id, name FROM device WHERE id = 0;")?;
+            let mut rows = stmt.query([])?;
+            let row = rows.next()?.ok_or(Error::NotFound("device".into()))?;
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .await?
+    }
+}
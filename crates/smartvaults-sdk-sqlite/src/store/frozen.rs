@@ -0,0 +1,81 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use smartvaults_core::bitcoin::{OutPoint, Txid};
+use smartvaults_protocol::nostr::{EventId, Timestamp};
+
+use super::Store;
+use crate::Error;
+
+impl Store {
+    pub async fn freeze_utxos<I>(&self, policy_id: EventId, utxos: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = OutPoint>,
+    {
+        let utxos: Vec<OutPoint> = utxos.into_iter().collect();
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    "INSERT OR IGNORE INTO frozen_utxos (policy_id, txid, vout, timestamp) VALUES (?, ?, ?, ?);",
+                )?;
+                let timestamp: u64 = Timestamp::now().as_u64();
+                for utxo in utxos.into_iter() {
+                    stmt.execute((
+                        policy_id.to_hex(),
+                        utxo.txid.to_string(),
+                        utxo.vout,
+                        timestamp,
+                    ))?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn unfreeze_utxos<I>(&self, policy_id: EventId, utxos: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = OutPoint>,
+    {
+        let utxos: Vec<OutPoint> = utxos.into_iter().collect();
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached(
+                    "DELETE FROM frozen_utxos WHERE policy_id = ? AND txid = ? AND vout = ?;",
+                )?;
+                for utxo in utxos.into_iter() {
+                    stmt.execute((policy_id.to_hex(), utxo.txid.to_string(), utxo.vout))?;
+                }
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Get the UTXOs frozen by the user
+    pub async fn get_frozen_utxos(&self, policy_id: EventId) -> Result<HashSet<OutPoint>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt =
+                conn.prepare_cached("SELECT txid, vout FROM frozen_utxos WHERE policy_id = ?;")?;
+            let mut rows = stmt.query([policy_id.to_hex()])?;
+            let mut utxos: HashSet<OutPoint> = HashSet::new();
+            while let Ok(Some(row)) = rows.next() {
+                let txid: String = row.get(0)?;
+                let vout: u32 = row.get(1)?;
+                utxos.insert(OutPoint::new(Txid::from_str(&txid)?, vout));
+            }
+            Ok(utxos)
+        })
+        .await?
+    }
+}
@@ -17,7 +17,15 @@ use rusqlite::config::DbConfig;
 use smartvaults_protocol::nostr::{Keys, PublicKey, Timestamp};
 use tokio::sync::RwLock;
 
+mod checkpoint;
+mod compromised;
 mod connect;
+mod device;
+mod frozen;
+mod pinned;
+mod plugin;
+mod recovery;
+mod registration;
 mod relays;
 mod timechain;
 
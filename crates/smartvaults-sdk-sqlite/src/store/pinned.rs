@@ -0,0 +1,50 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::HashSet;
+
+use smartvaults_protocol::nostr::{EventId, Timestamp};
+
+use super::Store;
+use crate::Error;
+
+impl Store {
+    pub async fn pin_vault(&self, policy_id: EventId) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO pinned_vaults (policy_id, timestamp) VALUES (?, ?);",
+                (policy_id.to_hex(), Timestamp::now().as_u64()),
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn unpin_vault(&self, policy_id: EventId) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "DELETE FROM pinned_vaults WHERE policy_id = ?;",
+                [policy_id.to_hex()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn get_pinned_vaults(&self) -> Result<HashSet<EventId>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached("SELECT policy_id FROM pinned_vaults;")?;
+            let mut rows = stmt.query([])?;
+            let mut vaults: HashSet<EventId> = HashSet::new();
+            while let Ok(Some(row)) = rows.next() {
+                let policy_id: String = row.get(0)?;
+                vaults.insert(EventId::from_hex(policy_id)?);
+            }
+            Ok(vaults)
+        })
+        .await?
+    }
+}
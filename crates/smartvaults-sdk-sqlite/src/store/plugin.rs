@@ -0,0 +1,97 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+
+use smartvaults_protocol::nostr::{EventId, PublicKey, Timestamp};
+
+use super::{Error, Store};
+use crate::encryption::{self, Error as EncryptionError};
+use crate::model::PluginData;
+
+impl Store {
+    /// Save plugin data
+    ///
+    /// Data with the same key is replaced only if older.
+    pub async fn save_plugin_data(&self, data: PluginData) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        let cipher = self.cipher.clone();
+        conn.interact(move |conn| {
+            let value: Vec<u8> = encryption::encrypt_bytes(&cipher, data.value.as_bytes())?;
+            conn.execute(
+                "INSERT INTO plugin_data (plugin, vault_id, key, author, value, timestamp) VALUES (?, ?, ?, ?, ?, ?) ON CONFLICT(plugin, vault_id, key) DO UPDATE SET author = excluded.author, value = excluded.value, timestamp = excluded.timestamp WHERE excluded.timestamp >= plugin_data.timestamp;",
+                (
+                    data.plugin,
+                    data.vault_id.to_hex(),
+                    data.key,
+                    data.author.to_string(),
+                    value,
+                    data.timestamp.as_u64(),
+                ),
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Get the data saved by a plugin for a vault, newest first
+    pub async fn get_plugin_data<S>(
+        &self,
+        plugin: S,
+        vault_id: EventId,
+    ) -> Result<Vec<PluginData>, Error>
+    where
+        S: Into<String>,
+    {
+        let plugin: String = plugin.into();
+        let conn = self.acquire().await?;
+        let cipher = self.cipher.clone();
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT key, author, value, timestamp FROM plugin_data WHERE plugin = ? AND vault_id = ? ORDER BY timestamp DESC;",
+            )?;
+            let mut rows = stmt.query([plugin.clone(), vault_id.to_hex()])?;
+            let mut data: Vec<PluginData> = Vec::new();
+            while let Ok(Some(row)) = rows.next() {
+                let author: String = row.get(1)?;
+                let value: Vec<u8> = row.get(2)?;
+                let value: Vec<u8> = encryption::decrypt_bytes(&cipher, &value)?;
+                let timestamp: u64 = row.get(3)?;
+                data.push(PluginData {
+                    plugin: plugin.clone(),
+                    vault_id,
+                    key: row.get(0)?,
+                    author: PublicKey::from_str(&author)?,
+                    value: String::from_utf8(value)
+                        .map_err(|e| EncryptionError::Json(e.to_string()))?,
+                    timestamp: Timestamp::from(timestamp),
+                });
+            }
+            Ok(data)
+        })
+        .await?
+    }
+
+    pub async fn delete_plugin_data<S, K>(
+        &self,
+        plugin: S,
+        vault_id: EventId,
+        key: K,
+    ) -> Result<(), Error>
+    where
+        S: Into<String>,
+        K: Into<String>,
+    {
+        let plugin: String = plugin.into();
+        let key: String = key.into();
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "DELETE FROM plugin_data WHERE plugin = ? AND vault_id = ? AND key = ?;",
+                [plugin, vault_id.to_hex(), key],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+
+use smartvaults_core::bitcoin::Address;
+use smartvaults_protocol::nostr::{EventId, Timestamp};
+
+use super::{Error, Store};
+use crate::model::RecoveryWatch;
+
+impl Store {
+    /// Watch a vault for the maturity of a recovery path (replacing the sweep address, if already
+    /// watched)
+    pub async fn save_recovery_watch(&self, watch: RecoveryWatch) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO recovery_watch (policy_id, sweep_address, proposal_id, timestamp) VALUES (?, ?, ?, ?);",
+                (
+                    watch.policy_id.to_hex(),
+                    watch
+                        .sweep_address
+                        .map(|a| a.assume_checked().to_string()),
+                    watch.proposal_id.map(|id| id.to_hex()),
+                    watch.timestamp.as_u64(),
+                ),
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn get_recovery_watches(&self) -> Result<Vec<RecoveryWatch>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT policy_id, sweep_address, proposal_id, timestamp FROM recovery_watch;",
+            )?;
+            let mut rows = stmt.query([])?;
+            let mut watches: Vec<RecoveryWatch> = Vec::new();
+            while let Ok(Some(row)) = rows.next() {
+                let policy_id: String = row.get(0)?;
+                let sweep_address: Option<String> = row.get(1)?;
+                let proposal_id: Option<String> = row.get(2)?;
+                let timestamp: u64 = row.get(3)?;
+                watches.push(RecoveryWatch {
+                    policy_id: EventId::from_hex(policy_id)?,
+                    sweep_address: match sweep_address {
+                        Some(address) => Some(Address::from_str(&address)?),
+                        None => None,
+                    },
+                    proposal_id: match proposal_id {
+                        Some(id) => Some(EventId::from_hex(id)?),
+                        None => None,
+                    },
+                    timestamp: Timestamp::from(timestamp),
+                });
+            }
+            Ok(watches)
+        })
+        .await?
+    }
+
+    pub async fn delete_recovery_watch(&self, policy_id: EventId) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "DELETE FROM recovery_watch WHERE policy_id = ?;",
+                [policy_id.to_hex()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+}
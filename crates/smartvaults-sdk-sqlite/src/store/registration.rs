@@ -0,0 +1,77 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_protocol::nostr::{EventId, Timestamp};
+
+use super::{Error, Store};
+use crate::model::DeviceRegistration;
+
+impl Store {
+    /// Save (or replace) the registration of a vault on a hardware signer
+    pub async fn save_device_registration(
+        &self,
+        registration: DeviceRegistration,
+    ) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO device_registration (policy_id, fingerprint, device, proof, timestamp) VALUES (?, ?, ?, ?, ?);",
+                (
+                    registration.policy_id.to_hex(),
+                    registration.fingerprint.to_string(),
+                    registration.device,
+                    registration.proof,
+                    registration.timestamp.as_u64(),
+                ),
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn get_device_registrations(
+        &self,
+        policy_id: EventId,
+    ) -> Result<Vec<DeviceRegistration>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT fingerprint, device, proof, timestamp FROM device_registration WHERE policy_id = ?;",
+            )?;
+            let mut rows = stmt.query([policy_id.to_hex()])?;
+            let mut registrations: Vec<DeviceRegistration> = Vec::new();
+            while let Ok(Some(row)) = rows.next() {
+                let fingerprint: String = row.get(0)?;
+                let timestamp: u64 = row.get(3)?;
+                registrations.push(DeviceRegistration {
+                    policy_id,
+                    fingerprint: Fingerprint::from_str(&fingerprint)?,
+                    device: row.get(1)?,
+                    proof: row.get(2)?,
+                    timestamp: Timestamp::from(timestamp),
+                });
+            }
+            Ok(registrations)
+        })
+        .await?
+    }
+
+    pub async fn delete_device_registration(
+        &self,
+        policy_id: EventId,
+        fingerprint: Fingerprint,
+    ) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "DELETE FROM device_registration WHERE policy_id = ? AND fingerprint = ?;",
+                [policy_id.to_hex(), fingerprint.to_string()],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+}
@@ -3,34 +3,103 @@
 
 use std::net::SocketAddr;
 
+use std::collections::HashMap;
+
 use smartvaults_protocol::nostr::{Timestamp, Url};
 
+use crate::model::{RelayAdmission, RelayAdmissionStatus, SyncWindow};
 use crate::{Error, Store};
 
 impl Store {
-    pub async fn save_last_relay_sync(
+    /// Save a synced window for the relay and filter scope (merged with the existing ones)
+    pub async fn save_sync_window<S>(
         &self,
         relay_url: Url,
-        timestamp: Timestamp,
-    ) -> Result<(), Error> {
+        scope: S,
+        window: SyncWindow,
+    ) -> Result<(), Error>
+    where
+        S: Into<String>,
+    {
+        let scope: String = scope.into();
         let conn = self.acquire().await?;
         conn.interact(move |conn| {
-            let last_sync: u64 = timestamp.as_u64();
-            let mut stmt = conn.prepare_cached("INSERT INTO relays (url, enabled, last_sync) VALUES (?, ?, ?) ON CONFLICT(url) DO UPDATE SET last_sync = ?;")?;
-            stmt.execute((relay_url.as_str(), false, last_sync, last_sync))?;
+            let tx = conn.transaction()?;
+
+            let mut windows: Vec<SyncWindow> = vec![window];
+            {
+                let mut stmt = tx.prepare_cached(
+                    "SELECT since, until FROM sync_windows WHERE relay_url = ? AND scope = ?;",
+                )?;
+                let mut rows = stmt.query([relay_url.as_str(), scope.as_str()])?;
+                while let Some(row) = rows.next()? {
+                    let since: u64 = row.get(0)?;
+                    let until: u64 = row.get(1)?;
+                    windows.push(SyncWindow::new(Timestamp::from(since), Timestamp::from(until)));
+                }
+            }
+
+            tx.execute(
+                "DELETE FROM sync_windows WHERE relay_url = ? AND scope = ?;",
+                [relay_url.as_str(), scope.as_str()],
+            )?;
+
+            {
+                let mut stmt = tx.prepare_cached(
+                    "INSERT INTO sync_windows (relay_url, scope, since, until) VALUES (?, ?, ?, ?);",
+                )?;
+                for window in SyncWindow::merge(windows).into_iter() {
+                    stmt.execute((
+                        relay_url.as_str(),
+                        scope.as_str(),
+                        window.since.as_u64(),
+                        window.until.as_u64(),
+                    ))?;
+                }
+            }
+
+            tx.commit()?;
             Ok(())
-        }).await?
+        })
+        .await?
+    }
+
+    /// Get the synced windows of the relay for the filter scope, sorted by `since`
+    pub async fn get_sync_windows<S>(
+        &self,
+        relay_url: Url,
+        scope: S,
+    ) -> Result<Vec<SyncWindow>, Error>
+    where
+        S: Into<String>,
+    {
+        let scope: String = scope.into();
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT since, until FROM sync_windows WHERE relay_url = ? AND scope = ? ORDER BY since ASC;",
+            )?;
+            let mut rows = stmt.query([relay_url.as_str(), scope.as_str()])?;
+            let mut windows: Vec<SyncWindow> = Vec::new();
+            while let Some(row) = rows.next()? {
+                let since: u64 = row.get(0)?;
+                let until: u64 = row.get(1)?;
+                windows.push(SyncWindow::new(Timestamp::from(since), Timestamp::from(until)));
+            }
+            Ok(windows)
+        })
+        .await?
     }
 
-    pub async fn get_last_relay_sync(&self, relay_url: Url) -> Result<Timestamp, Error> {
+    /// Delete the synced windows that end in the future (i.e. saved with a wrong clock)
+    pub async fn delete_future_sync_windows(&self) -> Result<usize, Error> {
         let conn = self.acquire().await?;
         conn.interact(move |conn| {
-            let mut stmt = conn.prepare_cached("SELECT last_sync FROM relays WHERE url = ?")?;
-            let mut rows = stmt.query([relay_url.as_str()])?;
-            let row = rows.next()?.ok_or(Error::NotFound("relay".into()))?;
-            let last_sync: Option<u64> = row.get(0)?;
-            let last_sync: u64 = last_sync.unwrap_or_default();
-            Ok(Timestamp::from(last_sync))
+            let deleted: usize = conn.execute(
+                "DELETE FROM sync_windows WHERE until > ?;",
+                [Timestamp::now().as_u64()],
+            )?;
+            Ok(deleted)
         })
         .await?
     }
@@ -75,6 +144,14 @@ impl Store {
         let conn = self.acquire().await?;
         conn.interact(move |conn| {
             conn.execute("DELETE FROM relays WHERE url = ?;", [url.as_str()])?;
+            conn.execute(
+                "DELETE FROM relay_admission WHERE relay_url = ?;",
+                [url.as_str()],
+            )?;
+            conn.execute(
+                "DELETE FROM sync_windows WHERE relay_url = ?;",
+                [url.as_str()],
+            )?;
             Ok(())
         })
         .await?
@@ -103,4 +180,55 @@ impl Store {
         })
         .await?
     }
+
+    pub async fn save_relay_admission(
+        &self,
+        url: Url,
+        admission: RelayAdmission,
+    ) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO relay_admission (relay_url, status, payments_url, message, timestamp) VALUES (?, ?, ?, ?, ?);",
+                (
+                    url.as_str(),
+                    admission.status.to_string(),
+                    admission.payments_url,
+                    admission.message,
+                    admission.timestamp.as_u64(),
+                ),
+            )?;
+            Ok(())
+        })
+        .await?
+    }
+
+    pub async fn get_relays_admission(&self) -> Result<HashMap<Url, RelayAdmission>, Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT relay_url, status, payments_url, message, timestamp FROM relay_admission;",
+            )?;
+            let mut rows = stmt.query([])?;
+            let mut relays: HashMap<Url, RelayAdmission> = HashMap::new();
+            while let Ok(Some(row)) = rows.next() {
+                let url: String = row.get(0)?;
+                let status: String = row.get(1)?;
+                let timestamp: u64 = row.get(4)?;
+                if let Some(status) = RelayAdmissionStatus::parse(&status) {
+                    relays.insert(
+                        Url::parse(&url)?,
+                        RelayAdmission {
+                            status,
+                            payments_url: row.get(2)?,
+                            message: row.get(3)?,
+                            timestamp: Timestamp::from(timestamp),
+                        },
+                    );
+                }
+            }
+            Ok(relays)
+        })
+        .await?
+    }
 }
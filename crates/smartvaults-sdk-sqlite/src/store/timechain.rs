@@ -43,4 +43,23 @@ impl Store {
         })
         .await?
     }
+
+    /// Delete the wallet changeset and the last sync timestamp
+    #[tracing::instrument(skip_all, level = "trace")]
+    pub async fn delete_changeset(&self, descriptor_hash: Sha256Hash) -> Result<(), Error> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            let descriptor_hash: String = descriptor_hash.to_string();
+            conn.execute(
+                "DELETE FROM timechain WHERE descriptor_hash = ?;",
+                [&descriptor_hash],
+            )?;
+            conn.execute(
+                "DELETE FROM timechain_sync WHERE descriptor_hash = ?;",
+                [&descriptor_hash],
+            )?;
+            Ok(())
+        })
+        .await?
+    }
 }
@@ -59,7 +59,7 @@ async fn main() {
 
     // Approve a proposal
     client
-        .approve("password", proposal.proposal_id)
+        .approve("password", None, proposal.proposal_id)
         .await
         .unwrap();
     // other approvals ...
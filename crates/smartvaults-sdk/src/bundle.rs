@@ -0,0 +1,345 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Application state bundle
+//!
+//! Encrypted archive of the keychains (optional), databases and config of a network, to migrate
+//! to another device. The databases contain the vaults, proposals, labels and wallet checkpoints.
+//!
+//! Layout: `MAGIC || VERSION || salt || nonce || ciphertext`. The plaintext is the length of the
+//! manifest (u32 BE), the JSON manifest and then the content of the files, in the manifest order.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{AeadCore, XChaCha20Poly1305};
+use nostr_sdk::Timestamp;
+use serde::{Deserialize, Serialize};
+use smartvaults_core::bitcoin::hashes::sha256::Hash as Sha256Hash;
+use smartvaults_core::bitcoin::hashes::Hash;
+use smartvaults_core::bitcoin::Network;
+use smartvaults_core::secp256k1::rand::{self, RngCore};
+use thiserror::Error;
+
+use crate::util::dir;
+
+const MAGIC: &[u8; 8] = b"SVBUNDLE";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// Scrypt cost (2^15 iterations)
+const SCRYPT_LOG_N: u8 = 15;
+
+/// Directories (relative to the network directory) included in the bundle
+const DATABASES_DIRS: [&str; 2] = ["users", "nostr"];
+const KEYCHAINS_DIR: &str = "keychains";
+const CONFIG_FILE: &str = "config.json";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Dir(#[from] dir::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("key derivation: {0}")]
+    Kdf(String),
+    #[error("impossible to encrypt the bundle")]
+    Encryption,
+    #[error("wrong password or corrupted bundle")]
+    Decryption,
+    #[error("invalid bundle: {0}")]
+    InvalidBundle(String),
+    #[error("unsupported bundle version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("bundle created for {found} network, expected {expected}")]
+    NetworkMismatch { expected: Network, found: String },
+    #[error("integrity check failed for {0}")]
+    IntegrityCheckFailed(String),
+    #[error("{0} already exists")]
+    AlreadyExists(String),
+}
+
+/// File included in the bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleEntry {
+    /// Path relative to the network directory
+    pub path: String,
+    pub size: u64,
+    /// SHA256 of the content
+    pub sha256: String,
+}
+
+/// Bundle manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub network: String,
+    pub created_at: Timestamp,
+    /// `true` if the keychains are included
+    pub keychains: bool,
+    pub entries: Vec<BundleEntry>,
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], Error> {
+    let params =
+        scrypt::Params::new(SCRYPT_LOG_N, 8, 1, 32).map_err(|e| Error::Kdf(e.to_string()))?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| Error::Kdf(e.to_string()))?;
+    Ok(key)
+}
+
+/// Check that the path is relative and not escaping the network directory
+fn is_safe_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(..)))
+}
+
+/// Collect the files of a directory (not recursive), sorted by name
+fn collect_dir(network_path: &Path, dir: &str, files: &mut Vec<String>) -> Result<(), Error> {
+    let path: PathBuf = network_path.join(dir);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    names.sort();
+    files.extend(names.into_iter().map(|name| format!("{dir}/{name}")));
+    Ok(())
+}
+
+/// Export the application state of the network to an encrypted bundle
+///
+/// The databases are copied as they are: close the keychain before exporting.
+pub fn export_state_bundle<P, B, S>(
+    base_path: P,
+    network: Network,
+    path: B,
+    password: S,
+    include_keychains: bool,
+) -> Result<BundleManifest, Error>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+    S: AsRef<str>,
+{
+    let network_path: PathBuf = dir::network_path(base_path, network)?;
+
+    let mut files: Vec<String> = Vec::new();
+    if include_keychains {
+        collect_dir(&network_path, KEYCHAINS_DIR, &mut files)?;
+    }
+    for dir in DATABASES_DIRS.into_iter() {
+        collect_dir(&network_path, dir, &mut files)?;
+    }
+    if network_path.join(CONFIG_FILE).exists() {
+        files.push(CONFIG_FILE.to_string());
+    }
+
+    let mut entries: Vec<BundleEntry> = Vec::with_capacity(files.len());
+    let mut data: Vec<u8> = Vec::new();
+    for file in files.into_iter() {
+        let content: Vec<u8> = fs::read(network_path.join(&file))?;
+        entries.push(BundleEntry {
+            path: file,
+            size: content.len() as u64,
+            sha256: Sha256Hash::hash(&content).to_string(),
+        });
+        data.extend(content);
+    }
+
+    let manifest = BundleManifest {
+        network: network.to_string(),
+        created_at: Timestamp::now(),
+        keychains: include_keychains,
+        entries,
+    };
+    let json: Vec<u8> = serde_json::to_vec(&manifest)?;
+
+    let mut plaintext: Vec<u8> = Vec::with_capacity(4 + json.len() + data.len());
+    plaintext.extend_from_slice(&(json.len() as u32).to_be_bytes());
+    plaintext.extend(json);
+    plaintext.extend(data);
+
+    // Encrypt
+    let mut salt = [0u8; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let key: [u8; 32] = derive_key(password.as_ref(), &salt)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext: Vec<u8> = cipher
+        .encrypt(&nonce, plaintext.as_slice())
+        .map_err(|_| Error::Encryption)?;
+
+    let mut payload: Vec<u8> =
+        Vec::with_capacity(MAGIC.len() + 1 + SALT_LEN + NONCE_LEN + ciphertext.len());
+    payload.extend_from_slice(MAGIC);
+    payload.push(VERSION);
+    payload.extend_from_slice(&salt);
+    payload.extend_from_slice(nonce.as_slice());
+    payload.extend(ciphertext);
+    fs::write(path, payload)?;
+
+    Ok(manifest)
+}
+
+/// Decrypt the bundle and verify the integrity of every file
+fn open_bundle(payload: &[u8], password: &str) -> Result<(BundleManifest, Vec<Vec<u8>>), Error> {
+    let header_len: usize = MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+    if payload.len() < header_len || !payload.starts_with(MAGIC) {
+        return Err(Error::InvalidBundle(String::from("not a state bundle")));
+    }
+
+    let version: u8 = payload[MAGIC.len()];
+    if version != VERSION {
+        return Err(Error::UnsupportedVersion(version));
+    }
+
+    let salt: &[u8] = &payload[MAGIC.len() + 1..MAGIC.len() + 1 + SALT_LEN];
+    let nonce: &[u8] = &payload[MAGIC.len() + 1 + SALT_LEN..header_len];
+    let key: [u8; 32] = derive_key(password, salt)?;
+    let cipher = XChaCha20Poly1305::new(&key.into());
+    let plaintext: Vec<u8> = cipher
+        .decrypt(nonce.into(), &payload[header_len..])
+        .map_err(|_| Error::Decryption)?;
+
+    if plaintext.len() < 4 {
+        return Err(Error::InvalidBundle(String::from("missing manifest")));
+    }
+    let (len, rest) = plaintext.split_at(4);
+    let len: usize = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+    if rest.len() < len {
+        return Err(Error::InvalidBundle(String::from("truncated manifest")));
+    }
+    let (json, mut data) = rest.split_at(len);
+    let manifest: BundleManifest = serde_json::from_slice(json)?;
+
+    let mut contents: Vec<Vec<u8>> = Vec::with_capacity(manifest.entries.len());
+    for entry in manifest.entries.iter() {
+        if !is_safe_path(&entry.path) {
+            return Err(Error::InvalidBundle(format!("invalid path {}", entry.path)));
+        }
+
+        let size: usize = entry.size as usize;
+        if data.len() < size {
+            return Err(Error::IntegrityCheckFailed(entry.path.clone()));
+        }
+        let (content, rest) = data.split_at(size);
+        if Sha256Hash::hash(content).to_string() != entry.sha256 {
+            return Err(Error::IntegrityCheckFailed(entry.path.clone()));
+        }
+        contents.push(content.to_vec());
+        data = rest;
+    }
+
+    if !data.is_empty() {
+        return Err(Error::InvalidBundle(String::from(
+            "unexpected trailing data",
+        )));
+    }
+
+    Ok((manifest, contents))
+}
+
+/// Import an encrypted bundle created with [`export_state_bundle`]
+///
+/// All the files are verified before writing anything. Existing files are never overwritten.
+pub fn import_state_bundle<P, B, S>(
+    base_path: P,
+    network: Network,
+    path: B,
+    password: S,
+) -> Result<BundleManifest, Error>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+    S: AsRef<str>,
+{
+    let payload: Vec<u8> = fs::read(path)?;
+    let (manifest, contents) = open_bundle(&payload, password.as_ref())?;
+
+    if manifest.network != network.to_string() {
+        return Err(Error::NetworkMismatch {
+            expected: network,
+            found: manifest.network,
+        });
+    }
+
+    let network_path: PathBuf = dir::network_path(base_path, network)?;
+    for entry in manifest.entries.iter() {
+        if network_path.join(&entry.path).exists() {
+            return Err(Error::AlreadyExists(entry.path.clone()));
+        }
+    }
+
+    for (entry, content) in manifest.entries.iter().zip(contents.into_iter()) {
+        let file_path: PathBuf = network_path.join(&entry.path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file_path, content)?;
+    }
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_path() {
+        assert!(is_safe_path("users/abc.db"));
+        assert!(is_safe_path(CONFIG_FILE));
+        assert!(!is_safe_path("../config.json"));
+        assert!(!is_safe_path("/etc/passwd"));
+        assert!(!is_safe_path("users/../../x"));
+        assert!(!is_safe_path(""));
+    }
+
+    #[test]
+    fn test_export_import() {
+        let root = std::env::temp_dir().join(format!("smartvaults-bundle-{}", Timestamp::now()));
+        let from = root.join("from");
+        let to = root.join("to");
+        let bundle = root.join("state.bundle");
+        let network = Network::Testnet;
+
+        let network_path = dir::network_path(&from, network).unwrap();
+        fs::create_dir_all(network_path.join("users")).unwrap();
+        fs::write(network_path.join("users/a.db"), b"user db").unwrap();
+        fs::write(network_path.join(CONFIG_FILE), b"{}").unwrap();
+
+        let manifest = export_state_bundle(&from, network, &bundle, "password", false).unwrap();
+        assert_eq!(manifest.entries.len(), 2);
+
+        assert!(matches!(
+            import_state_bundle(&to, network, &bundle, "wrong"),
+            Err(Error::Decryption)
+        ));
+        assert!(matches!(
+            import_state_bundle(&to, Network::Signet, &bundle, "password"),
+            Err(Error::NetworkMismatch { .. })
+        ));
+
+        import_state_bundle(&to, network, &bundle, "password").unwrap();
+        let imported = dir::network_path(&to, network).unwrap();
+        assert_eq!(fs::read(imported.join("users/a.db")).unwrap(), b"user db");
+        assert!(matches!(
+            import_state_bundle(&to, network, &bundle, "password"),
+            Err(Error::AlreadyExists(..))
+        ));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Relays requiring a payment or an admission (NIP-11 `limitation`)
+//!
+//! The requirement is detected from the relay information document and from the `OK` messages
+//! of the rejected events, so the user can pay (or request the admission) instead of silently
+//! failing to publish.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use nostr_sdk::{Timestamp, Url};
+use reqwest::Client as HttpClient;
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+use super::{Error, Message, SmartVaults};
+use crate::types::{RelayAdmission, RelayAdmissionStatus, RelayFee, RelayTerms};
+
+/// Prefixes of the `OK` messages of events rejected by the relay policy (NIP-01)
+const REJECTED_PREFIXES: [&str; 2] = ["restricted:", "blocked:"];
+/// Words of the `OK` messages meaning that a payment is required
+const PAYMENT_KEYWORDS: [&str; 3] = ["pay", "admission", "subscri"];
+
+/// Admission status of the relays
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RelayAdmissions {
+    relays: Arc<RwLock<HashMap<Url, RelayAdmission>>>,
+}
+
+fn parse_fees(value: Option<&Value>) -> Vec<RelayFee> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|fees| {
+            fees.iter()
+                .filter_map(|fee| {
+                    Some(RelayFee {
+                        amount: fee.get("amount")?.as_u64()?,
+                        unit: fee.get("unit")?.as_str()?.to_string(),
+                        period: fee.get("period").and_then(|p| p.as_u64()),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn parse_terms(document: &Value) -> RelayTerms {
+    let limitation = document.get("limitation");
+    let flag = |name: &str| {
+        limitation
+            .and_then(|l| l.get(name))
+            .and_then(|v| v.as_bool())
+            .unwrap_or_default()
+    };
+    let string = |name: &str| {
+        document
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+    let fees = document.get("fees");
+    RelayTerms {
+        name: string("name"),
+        payment_required: flag("payment_required"),
+        auth_required: flag("auth_required"),
+        restricted_writes: flag("restricted_writes"),
+        payments_url: string("payments_url"),
+        admission_fees: parse_fees(fees.and_then(|f| f.get("admission"))),
+        subscription_fees: parse_fees(fees.and_then(|f| f.get("subscription"))),
+        posting_policy: string("posting_policy"),
+    }
+}
+
+/// Classify the message of a rejected event
+fn rejection_status(message: &str) -> Option<RelayAdmissionStatus> {
+    let message: String = message.to_lowercase();
+    if PAYMENT_KEYWORDS.iter().any(|k| message.contains(k)) {
+        Some(RelayAdmissionStatus::PaymentRequired)
+    } else if REJECTED_PREFIXES.iter().any(|p| message.starts_with(p)) {
+        Some(RelayAdmissionStatus::Rejected)
+    } else {
+        None
+    }
+}
+
+impl SmartVaults {
+    pub(crate) async fn load_relays_admission(&self) -> Result<(), Error> {
+        let admissions = self.db.get_relays_admission().await?;
+        let mut relays = self.relay_admissions.relays.write().await;
+        *relays = admissions;
+        Ok(())
+    }
+
+    async fn set_relay_admission(&self, url: Url, admission: RelayAdmission) -> Result<(), Error> {
+        let changed: bool = {
+            let mut relays = self.relay_admissions.relays.write().await;
+            let changed: bool = relays.get(&url).map(|a| a.status) != Some(admission.status);
+            relays.insert(url.clone(), admission.clone());
+            changed
+        };
+
+        self.db.save_relay_admission(url.clone(), admission).await?;
+
+        if changed {
+            let _ = self.sync_channel.send(Message::RelayAdmissionChanged(url));
+        }
+
+        Ok(())
+    }
+
+    /// Get the terms of the relay (NIP-11 information document)
+    pub async fn get_relay_terms(&self, url: Url) -> Result<RelayTerms, Error> {
+        let endpoint: String = url
+            .as_str()
+            .replacen("wss://", "https://", 1)
+            .replacen("ws://", "http://", 1);
+        let proxy = self.config.proxy().await.ok();
+        let client: HttpClient = self.http_client(proxy)?;
+        let text: String = client
+            .get(endpoint)
+            .header("Accept", "application/nostr+json")
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())?
+            .text()
+            .await?;
+        let document: Value = serde_json::from_str(&text)?;
+        Ok(parse_terms(&document))
+    }
+
+    /// Get the admission status of the relays (only the checked ones)
+    pub async fn get_relays_admission(&self) -> HashMap<Url, RelayAdmission> {
+        self.relay_admissions.relays.read().await.clone()
+    }
+
+    /// Check if the relay requires a payment (or an admission), using its terms
+    ///
+    /// A relay already admitted is not downgraded: the status changes only if an event is rejected.
+    pub async fn check_relay_admission(&self, url: Url) -> Result<RelayAdmission, Error> {
+        let terms: RelayTerms = self.get_relay_terms(url.clone()).await?;
+        let current: Option<RelayAdmission> =
+            self.relay_admissions.relays.read().await.get(&url).cloned();
+        let status: RelayAdmissionStatus = match current.as_ref().map(|a| a.status) {
+            Some(RelayAdmissionStatus::Admitted) => RelayAdmissionStatus::Admitted,
+            Some(RelayAdmissionStatus::Rejected) if terms.requires_admission() => {
+                RelayAdmissionStatus::Rejected
+            }
+            _ if terms.requires_admission() => RelayAdmissionStatus::PaymentRequired,
+            _ => RelayAdmissionStatus::Open,
+        };
+        let admission = RelayAdmission {
+            status,
+            payments_url: terms.payments_url,
+            message: current.and_then(|a| a.message),
+            timestamp: Timestamp::now(),
+        };
+        self.set_relay_admission(url, admission.clone()).await?;
+        Ok(admission)
+    }
+
+    /// Mark the relay as admitted (i.e. after paying the admission fee)
+    pub async fn mark_relay_admitted(&self, url: Url) -> Result<(), Error> {
+        let payments_url: Option<String> = self
+            .relay_admissions
+            .relays
+            .read()
+            .await
+            .get(&url)
+            .and_then(|a| a.payments_url.clone());
+        self.set_relay_admission(
+            url,
+            RelayAdmission {
+                status: RelayAdmissionStatus::Admitted,
+                payments_url,
+                message: None,
+                timestamp: Timestamp::now(),
+            },
+        )
+        .await
+    }
+
+    /// Track the admission status from the `OK` message of a published event
+    pub(crate) async fn handle_relay_ok(
+        &self,
+        url: Url,
+        accepted: bool,
+        message: &str,
+    ) -> Result<(), Error> {
+        let current: Option<RelayAdmission> =
+            self.relay_admissions.relays.read().await.get(&url).cloned();
+        let status: RelayAdmissionStatus = if accepted {
+            match current.as_ref().map(|a| a.status) {
+                Some(status) if status.is_blocked() => RelayAdmissionStatus::Admitted,
+                _ => return Ok(()),
+            }
+        } else {
+            match rejection_status(message) {
+                Some(status) => status,
+                None => return Ok(()),
+            }
+        };
+
+        if !accepted {
+            tracing::warn!("Event rejected by {url}: {message}");
+        }
+
+        self.set_relay_admission(
+            url,
+            RelayAdmission {
+                status,
+                payments_url: current.and_then(|a| a.payments_url),
+                message: if accepted {
+                    None
+                } else {
+                    Some(message.to_string())
+                },
+                timestamp: Timestamp::now(),
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_relay_terms() {
+        let document: Value = serde_json::from_str(
+            r#"{"name":"Paid relay","limitation":{"payment_required":true,"auth_required":false},"payments_url":"https://relay.example.com/pay","fees":{"admission":[{"amount":21000,"unit":"msats"}],"subscription":[{"amount":5000,"unit":"msats","period":2592000}]}}"#,
+        )
+        .unwrap();
+        let terms = parse_terms(&document);
+        assert!(terms.requires_admission());
+        assert_eq!(terms.name.as_deref(), Some("Paid relay"));
+        assert_eq!(
+            terms.payments_url.as_deref(),
+            Some("https://relay.example.com/pay")
+        );
+        assert_eq!(terms.admission_fees.len(), 1);
+        assert_eq!(terms.subscription_fees[0].period, Some(2592000));
+
+        let document: Value = serde_json::from_str(r#"{"name":"Free relay"}"#).unwrap();
+        assert!(!parse_terms(&document).requires_admission());
+    }
+
+    #[test]
+    fn test_rejection_status() {
+        assert_eq!(
+            rejection_status("restricted: payment required"),
+            Some(RelayAdmissionStatus::PaymentRequired)
+        );
+        assert_eq!(
+            rejection_status("blocked: pubkey not admitted"),
+            Some(RelayAdmissionStatus::PaymentRequired)
+        );
+        assert_eq!(
+            rejection_status("blocked: spam"),
+            Some(RelayAdmissionStatus::Rejected)
+        );
+        assert_eq!(rejection_status("duplicate: already have this event"), None);
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Guards applied before approving a proposal
+//!
+//! Every approval path (seed, signed PSBT and external signer) must go through
+//! [`SmartVaults::verify_approval`], so that a proposal refused with the seed can't be approved
+//! with a PSBT signed elsewhere.
+
+use nostr_sdk::EventId;
+use smartvaults_core::Proposal;
+
+use super::{Error, SmartVaults};
+use crate::types::{GetVelocityLimitStatus, ProposalIssue};
+
+/// How a proposal is approved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ApprovalPath {
+    /// Keychain seed, overriding the velocity limits if requested
+    Seed { velocity_override: bool },
+    /// PSBT signed elsewhere (i.e. by an external signer): the limits can't be overridden
+    SignedPsbt,
+}
+
+impl ApprovalPath {
+    pub(crate) fn velocity_override(&self) -> bool {
+        match self {
+            Self::Seed { velocity_override } => *velocity_override,
+            Self::SignedPsbt => false,
+        }
+    }
+}
+
+impl SmartVaults {
+    /// Refuse to approve a proposal with a PSBT not matching the declared recipients, exceeding
+    /// the velocity or spending limits of the vault (unless overridden) or to destinations not in
+    /// the enforced whitelist
+    pub(crate) async fn verify_approval(
+        &self,
+        vault_id: EventId,
+        proposal: &Proposal,
+        path: ApprovalPath,
+    ) -> Result<(), Error> {
+        let issues = self.validate_proposal(vault_id, proposal).await?;
+        verify_issues(issues)?;
+        let exceeded = self.exceeded_velocity_limits(vault_id, proposal).await?;
+        verify_limits(exceeded, path)?;
+        self.verify_whitelist(vault_id, proposal).await
+    }
+}
+
+/// Error if the PSBT doesn't match the declared recipients or amount
+fn verify_issues(issues: Vec<ProposalIssue>) -> Result<(), Error> {
+    match issues.into_iter().find(ProposalIssue::is_mismatch) {
+        Some(issue) => Err(Error::ProposalMismatch(issue)),
+        None => Ok(()),
+    }
+}
+
+/// Error if any limit is exceeded and not overridden
+fn verify_limits(exceeded: Vec<GetVelocityLimitStatus>, path: ApprovalPath) -> Result<(), Error> {
+    match exceeded.into_iter().next() {
+        Some(status) if !path.velocity_override() => {
+            Err(Error::VelocityLimitExceeded(status.limit))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use smartvaults_protocol::v1::VelocityLimit;
+
+    use super::*;
+
+    #[test]
+    fn test_verify_issues() {
+        assert!(verify_issues(Vec::new()).is_ok());
+        assert!(verify_issues(vec![ProposalIssue::AbsurdFee(10_000)]).is_ok());
+
+        let mismatch = ProposalIssue::AmountMismatch {
+            declared: 50_000,
+            actual: 60_000,
+        };
+        assert!(matches!(
+            verify_issues(vec![ProposalIssue::AbsurdFee(10_000), mismatch.clone()]),
+            Err(Error::ProposalMismatch(issue)) if issue == mismatch
+        ));
+    }
+
+    #[test]
+    fn test_verify_limits() {
+        let daily = GetVelocityLimitStatus {
+            limit: VelocityLimit::new(100_000, Duration::from_secs(24 * 60 * 60)),
+            spent: 90_000,
+        };
+        let per_proposal = GetVelocityLimitStatus {
+            limit: VelocityLimit::per_proposal(50_000),
+            spent: 0,
+        };
+
+        let seed = ApprovalPath::Seed {
+            velocity_override: false,
+        };
+        let seed_override = ApprovalPath::Seed {
+            velocity_override: true,
+        };
+
+        assert!(verify_limits(Vec::new(), seed).is_ok());
+        assert!(matches!(
+            verify_limits(vec![daily], seed),
+            Err(Error::VelocityLimitExceeded(limit)) if limit == daily.limit
+        ));
+        assert!(matches!(
+            verify_limits(vec![per_proposal], seed),
+            Err(Error::VelocityLimitExceeded(limit)) if limit == per_proposal.limit
+        ));
+        assert!(verify_limits(vec![daily, per_proposal], seed_override).is_ok());
+    }
+
+    #[test]
+    fn test_verify_limits_signed_psbt() {
+        let daily = GetVelocityLimitStatus {
+            limit: VelocityLimit::new(100_000, Duration::from_secs(24 * 60 * 60)),
+            spent: 90_000,
+        };
+
+        // The limits of a signed PSBT approval can't be overridden
+        assert!(verify_limits(Vec::new(), ApprovalPath::SignedPsbt).is_ok());
+        assert!(matches!(
+            verify_limits(vec![daily], ApprovalPath::SignedPsbt),
+            Err(Error::VelocityLimitExceeded(limit)) if limit == daily.limit
+        ));
+    }
+}
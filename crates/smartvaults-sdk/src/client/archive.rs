@@ -0,0 +1,202 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Offline export and import of events
+//!
+//! Archive the events of a vault independently of the relays retention (i.e. for compliance) and
+//! bootstrap a new device from the events exported by another member or dumped from a relay,
+//! when the relays have pruned the old events of the vaults.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{Event, EventId, Filter, JsonUtil, Keys, RelayMessage};
+use serde_json::{json, Value};
+use smartvaults_protocol::v1::constants::{POLICY_KIND, SHARED_KEY_KIND};
+use smartvaults_protocol::v1::Encryption;
+
+use super::{Error, SmartVaults};
+use crate::types::EventsImport;
+
+/// Parse an events archive
+///
+/// Supported formats:
+/// * a JSON array of events
+/// * an event per line (JSONL), also as relay message (`["EVENT", <subscription_id>, <event>]`),
+///   i.e. a relay dump
+fn parse_events(content: &str) -> Result<Vec<Event>, Error> {
+    let content: &str = content.trim();
+
+    if let Ok(events) = serde_json::from_str::<Vec<Event>>(content) {
+        return Ok(events);
+    }
+
+    content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .enumerate()
+        .map(|(index, line)| {
+            if let Ok(event) = Event::from_json(line) {
+                return Ok(event);
+            }
+            match RelayMessage::from_json(line) {
+                Ok(RelayMessage::Event { event, .. }) => Ok(*event),
+                _ => Err(Error::InvalidEventsArchive(format!(
+                    "line {}: not an event",
+                    index + 1
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Order in which the events are handled: shared keys, vaults and then the rest by creation time
+fn handling_order(event: &Event) -> u8 {
+    if event.kind == SHARED_KEY_KIND {
+        0
+    } else if event.kind == POLICY_KIND {
+        1
+    } else {
+        2
+    }
+}
+
+impl SmartVaults {
+    /// Get the events of a vault (the vault and the events referencing it), oldest first
+    pub(super) async fn vault_events(&self, vault_id: EventId) -> Result<Vec<Event>, Error> {
+        // Check if vault exists
+        self.storage.vault(&vault_id).await?;
+
+        let filters: Vec<Filter> = vec![Filter::new().id(vault_id), Filter::new().event(vault_id)];
+        Ok(self.client.database().query(filters, Order::Asc).await?)
+    }
+
+    /// Export the events of a vault, as signed (and encrypted), to an archive with an event per
+    /// line (importable with [`SmartVaults::import_events_from_file`])
+    ///
+    /// If `decrypted` is set, a JSON array with the decrypted content of the events is written
+    /// too. The content of the shared key events is never decrypted.
+    /// Return the number of exported events.
+    pub async fn export_vault_events<P>(
+        &self,
+        vault_id: EventId,
+        path: P,
+        decrypted: Option<P>,
+    ) -> Result<usize, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let events: Vec<Event> = self.vault_events(vault_id).await?;
+
+        let mut archive: String = String::new();
+        for event in events.iter() {
+            let _ = writeln!(archive, "{}", event.as_json());
+        }
+        fs::write(path, archive)?;
+
+        if let Some(decrypted) = decrypted {
+            let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+            let view: Vec<Value> = events
+                .iter()
+                .map(|event| {
+                    let content: Option<Value> = if event.kind == SHARED_KEY_KIND {
+                        None
+                    } else {
+                        Value::decrypt_with_keys(&shared_key, event.content()).ok()
+                    };
+                    json!({
+                        "id": event.id,
+                        "kind": event.kind.as_u64(),
+                        "author": event.author(),
+                        "created_at": event.created_at,
+                        "content": content,
+                    })
+                })
+                .collect();
+            fs::write(decrypted, serde_json::to_string_pretty(&view)?)?;
+        }
+
+        Ok(events.len())
+    }
+
+    /// Import the events and handle them as if received from a relay
+    ///
+    /// The events not handled (i.e. a proposal of a vault not imported yet) are retried by the
+    /// pending events handler.
+    pub async fn import_events<I>(&self, events: I) -> Result<EventsImport, Error>
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        let mut events: Vec<Event> = events.into_iter().collect();
+        events.sort_by_key(|e| (handling_order(e), e.created_at));
+
+        let mut summary = EventsImport::default();
+        for event in events.into_iter() {
+            if event.verify().is_err() {
+                summary.invalid += 1;
+                continue;
+            }
+
+            if !self.client.database().save_event(&event).await? {
+                summary.duplicated += 1;
+                continue;
+            }
+
+            let event_id = event.id;
+            match self.handle_event(event).await {
+                Ok(()) => summary.imported += 1,
+                Err(e) => {
+                    tracing::warn!("Impossible to handle imported event {event_id}: {e}");
+                    summary.failed += 1;
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Import the events of an archive (JSON array or JSONL, see [`SmartVaults::import_events`])
+    pub async fn import_events_from_file<P>(&self, path: P) -> Result<EventsImport, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let content: String = fs::read_to_string(path)?;
+        let events: Vec<Event> = parse_events(&content)?;
+        self.import_events(events).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr_sdk::{EventBuilder, Keys, SubscriptionId};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_events() {
+        let keys = Keys::generate();
+        let first = EventBuilder::text_note("first", [])
+            .to_event(&keys)
+            .unwrap();
+        let second = EventBuilder::text_note("second", [])
+            .to_event(&keys)
+            .unwrap();
+
+        // JSON array
+        let content = format!("[{}, {}]", first.as_json(), second.as_json());
+        assert_eq!(
+            parse_events(&content).unwrap(),
+            vec![first.clone(), second.clone()]
+        );
+
+        // JSONL (events and relay messages)
+        let message = RelayMessage::event(SubscriptionId::new("dump"), second.clone());
+        let content = format!("{}\n\n{}\n", first.as_json(), message.as_json());
+        assert_eq!(parse_events(&content).unwrap(), vec![first, second]);
+
+        assert!(parse_events("invalid").is_err());
+    }
+}
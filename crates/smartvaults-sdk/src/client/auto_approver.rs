@@ -0,0 +1,166 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Simulated cosigner for demos and testing
+//!
+//! Automatically approve the proposals of a vault matching the [`AutoApproverRules`], so a single
+//! developer can exercise the multi-signature flows running a second client as cosigner.
+//! Available only on testnet, signet and regtest.
+
+use std::collections::HashSet;
+
+use async_utility::thread;
+use futures_util::stream::AbortHandle;
+use nostr_sdk::EventId;
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::{Address, Network};
+use smartvaults_core::types::Seed;
+use smartvaults_core::Proposal;
+use tokio::sync::broadcast::error::RecvError;
+
+use super::{Error, EventHandled, Message, SmartVaults};
+use crate::types::GetProposal;
+
+/// Rules a proposal must match to be approved by the auto-approver
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AutoApproverRules {
+    /// Max amount (in SAT) sent to the recipients of a spending proposal
+    pub max_amount: Option<u64>,
+    /// Allowed recipients (any if empty)
+    pub addresses: HashSet<Address<NetworkUnchecked>>,
+    /// Approve also the proof of reserve proposals
+    pub proof_of_reserve: bool,
+}
+
+impl AutoApproverRules {
+    /// Check the proposal against the rules, returning the reason if not matching
+    pub fn check(&self, proposal: &GetProposal) -> Result<(), String> {
+        if proposal.is_suspicious() {
+            return Err(String::from("suspicious PSBT"));
+        }
+
+        match &proposal.proposal {
+            Proposal::Spending { .. } => {
+                let recipients = proposal.proposal.recipients();
+
+                if let Some(max_amount) = self.max_amount {
+                    let amount: u64 = recipients.iter().map(|r| r.amount).sum();
+                    if amount > max_amount {
+                        return Err(format!("amount {amount} sat above {max_amount} sat"));
+                    }
+                }
+
+                if !self.addresses.is_empty() {
+                    if let Some(recipient) = recipients
+                        .iter()
+                        .find(|r| !self.addresses.contains(&r.address))
+                    {
+                        return Err(format!(
+                            "address {} not allowed",
+                            recipient.address.clone().assume_checked()
+                        ));
+                    }
+                }
+
+                Ok(())
+            }
+            Proposal::ProofOfReserve { .. } if self.proof_of_reserve => Ok(()),
+            _ => Err(String::from("proposal type not allowed")),
+        }
+    }
+}
+
+impl SmartVaults {
+    async fn auto_approve_proposal(
+        &self,
+        seed: &Seed,
+        vault_id: EventId,
+        rules: &AutoApproverRules,
+        proposal_id: EventId,
+    ) -> Result<(), Error> {
+        let proposal: GetProposal = self.get_proposal_by_id(proposal_id).await?;
+        if proposal.policy_id != vault_id {
+            return Ok(());
+        }
+
+        // Skip if already approved
+        let status = self.get_proposal_status(proposal_id).await?;
+        if status.signed.contains(&self.fingerprint()) {
+            return Ok(());
+        }
+
+        match rules.check(&proposal) {
+            Ok(()) => {
+                let (approval_id, ..) = self.approve_with_seed(seed, proposal_id, false).await?;
+                tracing::info!("Auto-approved proposal {proposal_id}: {approval_id}");
+            }
+            Err(reason) => {
+                tracing::info!("Proposal {proposal_id} not auto-approved: {reason}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Start approving the proposals of the vault matching the `rules` (pending and new ones)
+    ///
+    /// The keychain is unlocked once and kept in memory until the returned handle is aborted.
+    /// Return an error on mainnet.
+    pub async fn auto_approve<T>(
+        &self,
+        vault_id: EventId,
+        password: T,
+        pin: Option<&str>,
+        rules: AutoApproverRules,
+    ) -> Result<AbortHandle, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        if self.network == Network::Bitcoin {
+            return Err(Error::AutoApproverNotAllowed(self.network));
+        }
+
+        self.verify_approval_pin(pin)?;
+        let seed: Seed = self.keechain.read().seed(password)?;
+
+        // Check that the vault exists
+        self.get_policy_by_id(vault_id).await?;
+
+        let this = self.clone();
+        let mut notifications = self.sync_notifications();
+        Ok(thread::abortable(async move {
+            // Pending proposals
+            match this.get_proposals_by_policy_id(vault_id).await {
+                Ok(proposals) => {
+                    for GetProposal { proposal_id, .. } in proposals.into_iter() {
+                        if let Err(e) = this
+                            .auto_approve_proposal(&seed, vault_id, &rules, proposal_id)
+                            .await
+                        {
+                            tracing::error!("Impossible to auto-approve {proposal_id}: {e}");
+                        }
+                    }
+                }
+                Err(e) => tracing::error!("Impossible to get proposals of {vault_id}: {e}"),
+            }
+
+            // New proposals
+            loop {
+                match notifications.recv().await {
+                    Ok(Message::EventHandled(EventHandled::Proposal(proposal_id))) => {
+                        if let Err(e) = this
+                            .auto_approve_proposal(&seed, vault_id, &rules, proposal_id)
+                            .await
+                        {
+                            tracing::error!("Impossible to auto-approve {proposal_id}: {e}");
+                        }
+                    }
+                    Ok(..) | Err(RecvError::Lagged(..)) => (),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+
+            tracing::debug!("Auto-approver of {vault_id} exited");
+        })?)
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::io::{BufReader, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use bdk_electrum::electrum_client::{
+    Client as ElectrumClient, Config as ElectrumConfig, ElectrumApi, Socks5Config,
+};
+use futures_util::future;
+use nostr_sdk::Url;
+use reqwest::{Client as HttpClient, Proxy};
+use smartvaults_core::bitcoin::consensus::encode::{serialize, serialize_hex, Decodable};
+use smartvaults_core::bitcoin::network::constants::ServiceFlags;
+use smartvaults_core::bitcoin::network::message::{NetworkMessage, RawNetworkMessage};
+use smartvaults_core::bitcoin::network::message_network::VersionMessage;
+use smartvaults_core::bitcoin::network::Address as PeerAddress;
+use smartvaults_core::bitcoin::{Network, Transaction};
+
+use super::{Error, SmartVaults};
+use crate::config::ElectrumEndpoint;
+use crate::constants::SEND_TIMEOUT;
+use crate::types::{BroadcastBackend, BroadcastResult, BroadcastStatus};
+
+const P2P_USER_AGENT: &str = concat!("/smartvaults:", env!("CARGO_PKG_VERSION"), "/");
+
+fn electrum_broadcast(
+    endpoint: &ElectrumEndpoint,
+    proxy: Option<SocketAddr>,
+    tx: &Transaction,
+) -> Result<(), String> {
+    let config = ElectrumConfig::builder()
+        .validate_domain(endpoint.validate_tls())
+        .socks5(proxy.map(Socks5Config::new))
+        .build();
+    let client = ElectrumClient::from_config(&endpoint.as_non_standard_format(), config)
+        .map_err(|e| e.to_string())?;
+    client
+        .transaction_broadcast(tx)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Send transaction to a Bitcoin P2P node
+///
+/// The `tx` message is followed by a `ping`: the `pong` confirms only that the node received the
+/// transaction, not that it was accepted (an invalid transaction is usually dropped without reply).
+fn peer_broadcast(network: Network, peer: SocketAddr, tx: Transaction) -> Result<(), String> {
+    let stream = TcpStream::connect_timeout(&peer, SEND_TIMEOUT).map_err(|e| e.to_string())?;
+    stream
+        .set_read_timeout(Some(SEND_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    stream
+        .set_write_timeout(Some(SEND_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut writer = stream.try_clone().map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream);
+
+    let send = |writer: &mut TcpStream, payload: NetworkMessage| -> Result<(), String> {
+        let msg = RawNetworkMessage {
+            magic: network.magic(),
+            payload,
+        };
+        writer
+            .write_all(&serialize(&msg))
+            .map_err(|e| e.to_string())
+    };
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let nonce: u64 = now.as_nanos() as u64;
+    let version = VersionMessage::new(
+        ServiceFlags::NONE,
+        now.as_secs() as i64,
+        PeerAddress::new(&peer, ServiceFlags::NONE),
+        PeerAddress::new(&SocketAddr::from(([0, 0, 0, 0], 0)), ServiceFlags::NONE),
+        nonce,
+        String::from(P2P_USER_AGENT),
+        0,
+    );
+    send(&mut writer, NetworkMessage::Version(version))?;
+
+    let deadline = Instant::now() + SEND_TIMEOUT;
+    let mut tx_sent: bool = false;
+    while Instant::now() < deadline {
+        let msg = RawNetworkMessage::consensus_decode(&mut reader).map_err(|e| e.to_string())?;
+        match msg.payload {
+            NetworkMessage::Version(_) => send(&mut writer, NetworkMessage::Verack)?,
+            NetworkMessage::Verack if !tx_sent => {
+                send(&mut writer, NetworkMessage::Tx(tx.clone()))?;
+                send(&mut writer, NetworkMessage::Ping(nonce))?;
+                tx_sent = true;
+            }
+            NetworkMessage::Ping(n) => send(&mut writer, NetworkMessage::Pong(n))?,
+            NetworkMessage::Pong(n) if tx_sent && n == nonce => return Ok(()),
+            NetworkMessage::Reject(reject) if tx_sent => return Err(reject.reason.to_string()),
+            _ => (),
+        }
+    }
+
+    Err(String::from("timeout"))
+}
+
+impl SmartVaults {
+    pub(super) fn http_client(
+        &self,
+        proxy: Option<SocketAddr>,
+    ) -> Result<HttpClient, reqwest::Error> {
+        let mut builder = HttpClient::builder().timeout(SEND_TIMEOUT);
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(Proxy::all(format!("socks5h://{proxy}"))?);
+        }
+        builder.build()
+    }
+
+    async fn esplora_broadcast(
+        &self,
+        client: &HttpClient,
+        url: &Url,
+        tx_hex: String,
+    ) -> Result<(), String> {
+        let endpoint = format!("{}/tx", url.as_str().trim_end_matches('/'));
+        let res = client
+            .post(endpoint)
+            .body(tx_hex)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            let status = res.status();
+            let msg: String = res.text().await.unwrap_or_default();
+            Err(format!("{status}: {msg}"))
+        }
+    }
+
+    /// Broadcast backends set in the config
+    async fn broadcast_backends(&self) -> Vec<BroadcastBackend> {
+        let mut backends: Vec<BroadcastBackend> = Vec::new();
+
+        // Electrum
+        if let Ok(endpoint) = self.config.electrum_endpoint().await {
+            backends.push(BroadcastBackend::Electrum(endpoint));
+        }
+        for endpoint in self.config.broadcast_electrum_endpoints().await.into_iter() {
+            let backend = BroadcastBackend::Electrum(endpoint);
+            if !backends.contains(&backend) {
+                backends.push(backend);
+            }
+        }
+
+        // Esplora
+        backends.extend(
+            self.config
+                .esplora_endpoints()
+                .await
+                .into_iter()
+                .map(BroadcastBackend::Esplora),
+        );
+
+        // P2P
+        if let Some(peer) = self.config.broadcast_peer().await {
+            backends.push(BroadcastBackend::Peer(peer));
+        }
+
+        backends
+    }
+
+    async fn broadcast_to(
+        &self,
+        backend: &BroadcastBackend,
+        proxy: Option<SocketAddr>,
+        tx: &Transaction,
+    ) -> BroadcastStatus {
+        let res: Result<BroadcastStatus, String> = match backend.clone() {
+            BroadcastBackend::Electrum(endpoint) => {
+                let tx: Transaction = tx.clone();
+                match tokio::task::spawn_blocking(move || electrum_broadcast(&endpoint, proxy, &tx))
+                    .await
+                {
+                    Ok(res) => res.map(|_| BroadcastStatus::Accepted),
+                    Err(e) => Err(e.to_string()),
+                }
+            }
+            BroadcastBackend::Esplora(url) => match self.http_client(proxy) {
+                Ok(client) => self
+                    .esplora_broadcast(&client, &url, serialize_hex(tx))
+                    .await
+                    .map(|_| BroadcastStatus::Accepted),
+                Err(e) => Err(e.to_string()),
+            },
+            // Connecting directly would bypass the proxy
+            BroadcastBackend::Peer(..) if proxy.is_some() => {
+                Err(String::from("P2P broadcast not available through proxy"))
+            }
+            BroadcastBackend::Peer(peer) => {
+                let network: Network = self.network;
+                let tx: Transaction = tx.clone();
+                match tokio::task::spawn_blocking(move || peer_broadcast(network, peer, tx)).await {
+                    Ok(res) => res.map(|_| BroadcastStatus::Sent),
+                    Err(e) => Err(e.to_string()),
+                }
+            }
+        };
+
+        match res {
+            Ok(status) => status,
+            Err(e) => {
+                tracing::warn!("Impossible to broadcast tx via {backend}: {e}");
+                BroadcastStatus::Failed(e)
+            }
+        }
+    }
+
+    /// Broadcast transaction through all the configured backends
+    ///
+    /// The transaction is sent to the electrum server, to the additional electrum servers, to the esplora endpoints
+    /// and to the P2P node, if set, so a single censoring or broken backend can't silently drop it.
+    ///
+    /// Return the result of every backend. Fails if the transaction was not accepted by any backend:
+    /// the P2P node doesn't report the acceptance, so sending to it alone is not enough.
+    pub async fn broadcast_tx(&self, tx: &Transaction) -> Result<Vec<BroadcastResult>, Error> {
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+        let backends: Vec<BroadcastBackend> = self.broadcast_backends().await;
+        let statuses = future::join_all(
+            backends
+                .iter()
+                .map(|backend| self.broadcast_to(backend, proxy, tx)),
+        )
+        .await;
+        let results: Vec<BroadcastResult> = backends
+            .into_iter()
+            .zip(statuses.into_iter())
+            .map(|(backend, status)| BroadcastResult { backend, status })
+            .collect();
+
+        if results.iter().any(|r| r.is_accepted()) {
+            Ok(results)
+        } else {
+            Err(Error::BroadcastFailed)
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeSet, HashMap};
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{Event, EventBuilder, EventId, Filter, Keys, PublicKey, Timestamp};
+use smartvaults_protocol::v1::capabilities::FEATURE_CHUNKED_EVENTS;
+use smartvaults_protocol::v1::constants::CAPABILITIES_KIND;
+use smartvaults_protocol::v1::{Capabilities, Encryption, SmartVaultsEventBuilder};
+
+use super::{Error, SmartVaults};
+use crate::storage::InternalPolicy;
+use crate::types::{CosignerCompatibility, GetCosignerCapabilities};
+
+fn current_capabilities() -> Capabilities {
+    Capabilities::current(Some(format!(
+        "smartvaults-sdk {}",
+        env!("CARGO_PKG_VERSION")
+    )))
+}
+
+impl SmartVaults {
+    /// Get the latest capabilities published by the members of the vault
+    async fn vault_capabilities(
+        &self,
+        vault_id: EventId,
+    ) -> Result<HashMap<PublicKey, (Capabilities, Timestamp)>, Error> {
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+
+        let filter: Filter = Filter::new()
+            .kind(CAPABILITIES_KIND)
+            .authors(public_keys)
+            .identifier(vault_id.to_hex());
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+
+        let mut capabilities: HashMap<PublicKey, (Capabilities, Timestamp)> = HashMap::new();
+        for event in events.into_iter() {
+            if capabilities.contains_key(&event.author()) {
+                continue;
+            }
+            match Capabilities::decrypt_with_keys(&shared_key, &event.content) {
+                Ok(c) => {
+                    capabilities.insert(event.author(), (c, event.created_at));
+                }
+                Err(e) => tracing::warn!("Impossible to decrypt capabilities {}: {e}", event.id),
+            }
+        }
+
+        Ok(capabilities)
+    }
+
+    /// Publish the capabilities of this client to the other members of the vault
+    pub async fn publish_capabilities(&self, vault_id: EventId) -> Result<EventId, Error> {
+        let keys: &Keys = self.keys();
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+        let event: Event = EventBuilder::capabilities(
+            keys,
+            &shared_key,
+            vault_id,
+            &current_capabilities(),
+            &public_keys,
+        )?;
+        Ok(self.client.send_event(event).await?)
+    }
+
+    /// Publish the capabilities of this client to the vault, if missing or outdated
+    pub(crate) async fn publish_capabilities_if_outdated(&self, vault_id: EventId) {
+        let public_key: PublicKey = self.keys().public_key();
+        let published: Option<Capabilities> = match self.vault_capabilities(vault_id).await {
+            Ok(mut capabilities) => capabilities.remove(&public_key).map(|(c, _)| c),
+            Err(e) => {
+                tracing::warn!("Impossible to get capabilities of vault {vault_id}: {e}");
+                return;
+            }
+        };
+
+        if published != Some(current_capabilities()) {
+            match self.publish_capabilities(vault_id).await {
+                Ok(_) => tracing::info!("Published capabilities for vault {vault_id}"),
+                Err(e) => {
+                    tracing::error!("Impossible to publish capabilities for vault {vault_id}: {e}")
+                }
+            }
+        }
+    }
+
+    /// Publish the capabilities of this client to the vaults where are missing or outdated
+    pub(crate) async fn publish_outdated_capabilities(&self) {
+        for vault_id in self.storage.vaults().await.into_keys() {
+            self.publish_capabilities_if_outdated(vault_id).await;
+        }
+    }
+
+    /// Get the capabilities of the other members of the vault and their compatibility with this client
+    pub async fn get_cosigners_capabilities(
+        &self,
+        vault_id: EventId,
+    ) -> Result<Vec<GetCosignerCapabilities>, Error> {
+        let public_key: PublicKey = self.keys().public_key();
+        let current: Capabilities = current_capabilities();
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+        let mut capabilities = self.vault_capabilities(vault_id).await?;
+
+        Ok(public_keys
+            .into_iter()
+            .filter(|p| *p != public_key)
+            .map(|p| match capabilities.remove(&p) {
+                Some((c, timestamp)) => {
+                    let compatibility = if !current.is_compatible_with(&c) {
+                        CosignerCompatibility::Incompatible
+                    } else {
+                        let missing: BTreeSet<String> = current
+                            .missing_features(&c)
+                            .into_iter()
+                            .map(String::from)
+                            .collect();
+                        if missing.is_empty() {
+                            CosignerCompatibility::Compatible
+                        } else {
+                            CosignerCompatibility::MissingFeatures(missing)
+                        }
+                    };
+                    GetCosignerCapabilities {
+                        public_key: p,
+                        capabilities: Some(c),
+                        compatibility,
+                        timestamp: Some(timestamp),
+                    }
+                }
+                None => GetCosignerCapabilities {
+                    public_key: p,
+                    capabilities: None,
+                    compatibility: CosignerCompatibility::Unknown,
+                    timestamp: None,
+                },
+            })
+            .collect())
+    }
+
+    /// Get the cosigners that could not be able to parse a new proposal of the vault
+    ///
+    /// Cosigners that never published their capabilities are not included.
+    pub async fn get_incompatible_cosigners(
+        &self,
+        vault_id: EventId,
+        chunked: bool,
+    ) -> Result<Vec<GetCosignerCapabilities>, Error> {
+        Ok(self
+            .get_cosigners_capabilities(vault_id)
+            .await?
+            .into_iter()
+            .filter(|c| match &c.compatibility {
+                CosignerCompatibility::Incompatible => true,
+                CosignerCompatibility::MissingFeatures(missing) => {
+                    chunked && missing.contains(FEATURE_CHUNKED_EVENTS)
+                }
+                CosignerCompatibility::Compatible | CosignerCompatibility::Unknown => false,
+            })
+            .collect())
+    }
+
+    /// Log a warning for every cosigner that could not be able to parse a new proposal
+    pub(crate) async fn warn_incompatible_cosigners(&self, vault_id: EventId, chunked: bool) {
+        match self.get_incompatible_cosigners(vault_id, chunked).await {
+            Ok(cosigners) => {
+                for c in cosigners.into_iter() {
+                    tracing::warn!(
+                        "Cosigner {} of vault {vault_id} may not be able to parse the proposal: {}",
+                        c.public_key,
+                        c.compatibility
+                    );
+                }
+            }
+            Err(e) => tracing::error!("Impossible to check cosigners compatibility: {e}"),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use nostr_sdk::database::NostrDatabaseExt;
+use nostr_sdk::{Event, EventBuilder, EventId, Keys};
+use smartvaults_protocol::v1::{ProposalComment, SmartVaultsEventBuilder};
+
+use super::{Error, SmartVaults};
+use crate::storage::{InternalComment, InternalPolicy, InternalProposal};
+use crate::types::GetProposalComment;
+
+impl SmartVaults {
+    /// Comment a pending proposal
+    ///
+    /// The comment is encrypted with the shared key, so it's visible only to the vault members.
+    pub async fn comment_proposal<S>(
+        &self,
+        proposal_id: EventId,
+        message: S,
+    ) -> Result<EventId, Error>
+    where
+        S: Into<String>,
+    {
+        let message: String = message.into();
+        if message.trim().is_empty() {
+            return Err(Error::EmptyComment);
+        }
+
+        self.publish_comment(proposal_id, ProposalComment::new(message.trim()))
+            .await
+    }
+
+    pub(super) async fn publish_comment(
+        &self,
+        proposal_id: EventId,
+        comment: ProposalComment,
+    ) -> Result<EventId, Error> {
+        let InternalProposal { policy_id, .. } = self.storage.proposal(&proposal_id).await?;
+        let shared_key: Keys = self.storage.shared_key(&policy_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&policy_id).await?;
+
+        let keys: &Keys = self.keys();
+        let event: Event = EventBuilder::proposal_comment(
+            keys,
+            &shared_key,
+            proposal_id,
+            policy_id,
+            &comment,
+            &public_keys,
+        )?;
+        let timestamp = event.created_at;
+        let comment_id = self.client.send_event(event).await?;
+
+        // Index comment
+        self.storage
+            .save_comment(
+                comment_id,
+                InternalComment {
+                    proposal_id,
+                    policy_id,
+                    public_key: keys.public_key(),
+                    comment,
+                    timestamp,
+                },
+            )
+            .await;
+
+        Ok(comment_id)
+    }
+
+    /// Get the comments of a proposal (oldest first)
+    pub async fn get_proposal_comments(
+        &self,
+        proposal_id: EventId,
+    ) -> Result<Vec<GetProposalComment>, Error> {
+        let comments = self.storage.comments_by_proposal_id(&proposal_id).await;
+        let mut list: Vec<GetProposalComment> = Vec::with_capacity(comments.len());
+        for (
+            comment_id,
+            InternalComment {
+                public_key,
+                comment,
+                timestamp,
+                ..
+            },
+        ) in comments.into_iter()
+        {
+            list.push(GetProposalComment {
+                comment_id,
+                proposal_id,
+                user: self.client.database().profile(public_key).await?,
+                message: comment.message(),
+                timestamp,
+            });
+        }
+        Ok(list)
+    }
+}
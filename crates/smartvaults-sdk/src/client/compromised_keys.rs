@@ -0,0 +1,143 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Keys marked as compromised
+//!
+//! The list is local, but the keys can be shared with the other devices of the user (through the
+//! app settings). The vaults and the shared signers including a compromised key are reported with
+//! a [`Message::CompromisedKeys`] (they are not refused).
+
+use std::collections::BTreeSet;
+
+use nostr_sdk::{EventId, Timestamp};
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_core::Policy;
+use smartvaults_sdk_sqlite::model::CompromisedKey;
+
+use super::{Error, Message, SmartVaults};
+use crate::storage::{InternalPolicy, InternalSharedSigner};
+
+impl SmartVaults {
+    /// Mark a key (master fingerprint) as compromised
+    ///
+    /// If `share` is `true`, the key is synced across the user devices.
+    pub async fn mark_key_compromised(
+        &self,
+        fingerprint: Fingerprint,
+        reason: Option<String>,
+        share: bool,
+    ) -> Result<(), Error> {
+        self.db
+            .save_compromised_key(CompromisedKey {
+                fingerprint,
+                reason,
+                shared: share,
+                timestamp: Timestamp::now(),
+            })
+            .await?;
+        if share {
+            self.sync_app_settings().await;
+        }
+        Ok(())
+    }
+
+    pub async fn unmark_key_compromised(&self, fingerprint: Fingerprint) -> Result<(), Error> {
+        let shared: bool = self
+            .db
+            .get_compromised_keys()
+            .await?
+            .into_iter()
+            .any(|key| key.fingerprint == fingerprint && key.shared);
+        self.db.delete_compromised_key(fingerprint).await?;
+        if shared {
+            self.sync_app_settings().await;
+        }
+        Ok(())
+    }
+
+    pub async fn get_compromised_keys(&self) -> Result<Vec<CompromisedKey>, Error> {
+        Ok(self.db.get_compromised_keys().await?)
+    }
+
+    /// Compromised keys synced across the user devices
+    pub(crate) async fn shared_compromised_keys(&self) -> Result<BTreeSet<Fingerprint>, Error> {
+        Ok(self
+            .db
+            .get_compromised_keys()
+            .await?
+            .into_iter()
+            .filter(|key| key.shared)
+            .map(|key| key.fingerprint)
+            .collect())
+    }
+
+    /// Get the compromised keys used by a descriptor or a policy (i.e. before creating a vault)
+    pub async fn compromised_keys_in_descriptor<S>(
+        &self,
+        descriptor: S,
+    ) -> Result<BTreeSet<Fingerprint>, Error>
+    where
+        S: AsRef<str>,
+    {
+        let policy = Policy::from_desc_or_policy("", "", descriptor, self.network)?;
+        self.policy_compromised_keys(&policy).await
+    }
+
+    /// Get the compromised keys used by the vault
+    pub async fn get_vault_compromised_keys(
+        &self,
+        vault_id: EventId,
+    ) -> Result<BTreeSet<Fingerprint>, Error> {
+        let InternalPolicy { policy, .. } = self.storage.vault(&vault_id).await?;
+        self.policy_compromised_keys(&policy).await
+    }
+
+    async fn policy_compromised_keys(
+        &self,
+        policy: &Policy,
+    ) -> Result<BTreeSet<Fingerprint>, Error> {
+        let mut fingerprints: BTreeSet<Fingerprint> = BTreeSet::new();
+        for key in self.db.get_compromised_keys().await?.into_iter() {
+            if policy.is_fingerprint_involved(&key.fingerprint)? {
+                fingerprints.insert(key.fingerprint);
+            }
+        }
+        Ok(fingerprints)
+    }
+
+    /// Report the vault if it includes compromised keys
+    pub(crate) async fn check_vault_compromised_keys(&self, vault_id: EventId, policy: &Policy) {
+        match self.policy_compromised_keys(policy).await {
+            Ok(fingerprints) => self.report_compromised_keys(vault_id, fingerprints),
+            Err(e) => tracing::error!("Impossible to check compromised keys of {vault_id}: {e}"),
+        }
+    }
+
+    /// Report the shared signer if its key is compromised
+    pub(crate) async fn check_shared_signer_compromised_key(&self, shared_signer_id: EventId) {
+        let fingerprint: Fingerprint =
+            match self.storage.shared_signers().await.get(&shared_signer_id) {
+                Some(InternalSharedSigner { shared_signer, .. }) => shared_signer.fingerprint(),
+                None => return,
+            };
+        match self.db.get_compromised_keys().await {
+            Ok(keys) => {
+                if keys.iter().any(|key| key.fingerprint == fingerprint) {
+                    self.report_compromised_keys(shared_signer_id, BTreeSet::from([fingerprint]));
+                }
+            }
+            Err(e) => tracing::error!("Impossible to get compromised keys: {e}"),
+        }
+    }
+
+    fn report_compromised_keys(&self, event_id: EventId, fingerprints: BTreeSet<Fingerprint>) {
+        if fingerprints.is_empty() {
+            return;
+        }
+        tracing::warn!("Event {event_id} includes compromised keys: {fingerprints:?}");
+        let _ = self.sync_channel.send(Message::CompromisedKeys {
+            event_id,
+            fingerprints,
+        });
+    }
+}
@@ -1,18 +1,19 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 
 use nostr_sdk::nips::nip46::{Message as NIP46Message, NostrConnectURI, Request as NIP46Request};
 use nostr_sdk::{
-    ClientMessage, EventBuilder, EventId, Keys, PublicKey, RelaySendOptions, SubscribeOptions,
-    SubscriptionId, Timestamp, Url,
+    ClientMessage, EventBuilder, EventId, JsonUtil, Keys, PublicKey, RelaySendOptions,
+    SubscribeOptions, SubscriptionId, Timestamp, Url,
 };
-use smartvaults_sdk_sqlite::model::NostrConnectRequest;
+use smartvaults_sdk_sqlite::model::{NostrConnectProfile, NostrConnectRequest};
 
 use super::{Error, SmartVaults};
 use crate::constants::NOSTR_CONNECT_SUBSCRIPTION_ID;
+use crate::types::NostrConnectRequestDetail;
 
 impl SmartVaults {
     pub async fn new_nostr_connect_session(&self, uri: NostrConnectURI) -> Result<(), Error> {
@@ -23,14 +24,7 @@ impl SmartVaults {
             let relay = self.client.relay(&relay_url).await?;
             relay.connect(Some(Duration::from_secs(30))).await;
 
-            let last_sync: Timestamp = match self.db.get_last_relay_sync(relay_url.clone()).await {
-                Ok(ts) => ts,
-                Err(e) => {
-                    tracing::error!("Impossible to get last relay sync: {e}");
-                    Timestamp::from(0)
-                }
-            };
-            let filters = self.sync_filters(last_sync).await;
+            let filters = self.relay_sync_filters(&relay_url).await;
             relay
                 .subscribe_with_id(
                     SubscriptionId::new(NOSTR_CONNECT_SUBSCRIPTION_ID),
@@ -101,6 +95,34 @@ impl SmartVaults {
         Ok(self.db.get_nostr_connect_requests(approved).await?)
     }
 
+    /// Get the decoded payload of a nostr connect request (i.e. the event to sign)
+    pub async fn get_nostr_connect_request_detail(
+        &self,
+        event_id: EventId,
+    ) -> Result<NostrConnectRequestDetail, Error> {
+        let NostrConnectRequest {
+            event_id,
+            app_public_key,
+            message,
+            timestamp,
+            approved,
+        } = self.db.get_nostr_connect_request(event_id).await?;
+        let request: NIP46Request = message.to_request()?;
+        Ok(NostrConnectRequestDetail {
+            event_id,
+            app_public_key,
+            method: request.method(),
+            params: request.params().iter().map(|p| p.to_string()).collect(),
+            unsigned_event: match request {
+                NIP46Request::SignEvent(unsigned) => Some(unsigned),
+                _ => None,
+            },
+            raw: message.as_json(),
+            timestamp,
+            approved,
+        })
+    }
+
     pub async fn approve_nostr_connect_request(&self, event_id: EventId) -> Result<(), Error> {
         let NostrConnectRequest {
             app_public_key,
@@ -109,6 +131,16 @@ impl SmartVaults {
             ..
         } = self.db.get_nostr_connect_request(event_id).await?;
         if !approved {
+            if let Some(profile) = self
+                .db
+                .get_nostr_connect_session_profile(app_public_key)
+                .await?
+            {
+                if !profile.allows(&message.to_request()?) {
+                    return Err(Error::NostrConnectRequestNotAllowed(profile.name));
+                }
+            }
+
             let uri = self.db.get_nostr_connect_session(app_public_key).await?;
             let keys: &Keys = self.keys();
             let msg = message
@@ -171,4 +203,58 @@ impl SmartVaults {
     pub async fn get_nostr_connect_pre_authorizations(&self) -> BTreeMap<PublicKey, Timestamp> {
         self.db.get_nostr_connect_pre_authorizations().await
     }
+
+    pub async fn get_nostr_connect_profiles(&self) -> Result<Vec<NostrConnectProfile>, Error> {
+        Ok(self.db.get_nostr_connect_profiles().await?)
+    }
+
+    /// Save (or replace) a permission profile
+    pub async fn save_nostr_connect_profile(
+        &self,
+        profile: NostrConnectProfile,
+    ) -> Result<(), Error> {
+        Ok(self.db.save_nostr_connect_profile(profile).await?)
+    }
+
+    /// Delete a permission profile (the sessions that use it are left without restrictions)
+    pub async fn delete_nostr_connect_profile<S>(&self, name: S) -> Result<(), Error>
+    where
+        S: Into<String>,
+    {
+        Ok(self.db.delete_nostr_connect_profile(name.into()).await?)
+    }
+
+    /// Assign a permission profile to a session (`None` to remove restrictions)
+    ///
+    /// Requests not allowed by the profile are automatically rejected.
+    pub async fn set_nostr_connect_session_profile(
+        &self,
+        app_public_key: PublicKey,
+        profile: Option<String>,
+    ) -> Result<(), Error> {
+        Ok(self
+            .db
+            .set_nostr_connect_session_profile(app_public_key, profile)
+            .await?)
+    }
+
+    /// Get the name of the profiles assigned to the sessions
+    pub async fn get_nostr_connect_sessions_profiles(
+        &self,
+    ) -> Result<HashMap<PublicKey, String>, Error> {
+        Ok(self.db.get_nostr_connect_sessions_profiles().await?)
+    }
+
+    /// Check if the request is allowed by the profile assigned to the session
+    pub(crate) async fn is_nostr_connect_request_allowed(
+        &self,
+        app_public_key: PublicKey,
+        request: &NIP46Request,
+    ) -> Result<bool, Error> {
+        Ok(self
+            .db
+            .get_nostr_connect_session_profile(app_public_key)
+            .await?
+            .map_or(true, |profile| profile.allows(request)))
+    }
 }
@@ -0,0 +1,198 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Import of contacts from other Nostr identities and clients
+//!
+//! The imported public keys are merged into the current contact list (NIP-02), without duplicates.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use nostr_sdk::database::NostrDatabaseExt;
+use nostr_sdk::{
+    Contact, Event, EventBuilder, Filter, JsonUtil, Kind, PublicKey, SubscribeAutoCloseOptions,
+};
+
+use super::{Error, SmartVaults};
+use crate::constants::CONTACT_LIST_FETCH_TIMEOUT;
+
+/// Extract the public keys of a NIP-02 contact list
+fn contact_list_public_keys(event: &Event) -> Result<Vec<PublicKey>, Error> {
+    if event.kind != Kind::ContactList {
+        return Err(Error::InvalidContactsFile(format!(
+            "unexpected event kind {}",
+            event.kind
+        )));
+    }
+    event
+        .verify()
+        .map_err(|e| Error::InvalidContactsFile(e.to_string()))?;
+    Ok(event.public_keys().copied().collect())
+}
+
+/// Parse an exported contact list
+///
+/// Supported formats:
+/// * a contact list event (kind 3), alone or in a JSON array of events (i.e. Damus or Amethyst
+///   export)
+/// * a JSON array of public keys
+/// * a public key (`npub` or hex) per line (empty lines and lines starting with `#` are skipped)
+fn parse_contacts(content: &str) -> Result<Vec<PublicKey>, Error> {
+    let content: &str = content.trim();
+
+    if let Ok(event) = Event::from_json(content) {
+        return contact_list_public_keys(&event);
+    }
+
+    if let Ok(events) = serde_json::from_str::<Vec<Event>>(content) {
+        // Most recent contact list
+        let event: &Event = events
+            .iter()
+            .filter(|e| e.kind == Kind::ContactList)
+            .max_by_key(|e| e.created_at())
+            .ok_or_else(|| Error::InvalidContactsFile(String::from("contact list not found")))?;
+        return contact_list_public_keys(event);
+    }
+
+    let list: Vec<String> = match serde_json::from_str::<Vec<String>>(content) {
+        Ok(list) => list,
+        Err(_) => content
+            .lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(String::from)
+            .collect(),
+    };
+
+    list.into_iter()
+        .map(|p| {
+            PublicKey::from_str(&p).map_err(|e| Error::InvalidContactsFile(format!("{p}: {e}")))
+        })
+        .collect()
+}
+
+impl SmartVaults {
+    /// Merge the public keys into the contact list
+    ///
+    /// Return the number of new contacts.
+    pub async fn import_contacts<I>(&self, public_keys: I) -> Result<usize, Error>
+    where
+        I: IntoIterator<Item = PublicKey>,
+    {
+        let own: PublicKey = self.keys().public_key();
+        let current: BTreeSet<PublicKey> = self
+            .client
+            .database()
+            .contacts_public_keys(own)
+            .await?
+            .into_iter()
+            .collect();
+        let new: BTreeSet<PublicKey> = public_keys
+            .into_iter()
+            .filter(|p| *p != own && !current.contains(p))
+            .collect();
+
+        if new.is_empty() {
+            return Ok(0);
+        }
+
+        let contacts = current
+            .iter()
+            .chain(new.iter())
+            .map(|p| Contact::new::<String>(*p, None, None));
+        let event = EventBuilder::contact_list(contacts);
+        self.client.send_event_builder(event).await?;
+
+        // Request contacts metadata
+        self.client
+            .subscribe(
+                vec![Filter::new()
+                    .authors(new.iter().copied())
+                    .kind(Kind::Metadata)],
+                Some(SubscribeAutoCloseOptions::default().timeout(Some(Duration::from_secs(10)))),
+            )
+            .await;
+
+        Ok(new.len())
+    }
+
+    /// Import the contact list (kind 3) of another Nostr identity
+    ///
+    /// Return the number of new contacts.
+    pub async fn import_contacts_from_public_key(
+        &self,
+        public_key: PublicKey,
+    ) -> Result<usize, Error> {
+        let filter: Filter = Filter::new()
+            .author(public_key)
+            .kind(Kind::ContactList)
+            .limit(1);
+        let event: Event = self
+            .client
+            .get_events_of(vec![filter], Some(CONTACT_LIST_FETCH_TIMEOUT))
+            .await?
+            .into_iter()
+            .filter(|e| e.author() == public_key)
+            .max_by_key(|e| e.created_at())
+            .ok_or(Error::ContactListNotFound)?;
+        let public_keys: Vec<PublicKey> = contact_list_public_keys(&event)?;
+        self.import_contacts(public_keys).await
+    }
+
+    /// Import the contacts exported by another Nostr client (see [`SmartVaults::import_contacts`])
+    ///
+    /// Supported formats: contact list event (kind 3), JSON array of events or public keys and
+    /// a public key (`npub` or hex) per line.
+    /// Return the number of new contacts.
+    pub async fn import_contacts_from_file<P>(&self, path: P) -> Result<usize, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let content: String = fs::read_to_string(path)?;
+        let public_keys: Vec<PublicKey> = parse_contacts(&content)?;
+        self.import_contacts(public_keys).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr_sdk::{Keys, Tag, ToBech32};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_contacts() {
+        let keys = Keys::generate();
+        let alice = Keys::generate().public_key();
+        let bob = Keys::generate().public_key();
+
+        // Contact list event
+        let event = EventBuilder::contact_list([
+            Contact::new::<String>(alice, None, None),
+            Contact::new::<String>(bob, None, None),
+        ])
+        .to_event(&keys)
+        .unwrap();
+        assert_eq!(parse_contacts(&event.as_json()).unwrap(), vec![alice, bob]);
+        assert_eq!(
+            parse_contacts(&format!("[{}]", event.as_json())).unwrap(),
+            vec![alice, bob]
+        );
+
+        // Not a contact list
+        let event = EventBuilder::new(Kind::TextNote, "", [Tag::public_key(alice)])
+            .to_event(&keys)
+            .unwrap();
+        assert!(parse_contacts(&event.as_json()).is_err());
+
+        // Public keys
+        let list = format!("[\"{alice}\", \"{}\"]", bob.to_bech32().unwrap());
+        assert_eq!(parse_contacts(&list).unwrap(), vec![alice, bob]);
+        let list = format!("# contacts\n{}\n\n{bob}\n", alice.to_bech32().unwrap());
+        assert_eq!(parse_contacts(&list).unwrap(), vec![alice, bob]);
+        assert!(parse_contacts("invalid").is_err());
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use bdk_electrum::electrum_client::{
+    Client as ElectrumClient, Config as ElectrumConfig, ElectrumApi, Socks5Config,
+};
+use nostr_sdk::{EventId, Url};
+use reqwest::Client as HttpClient;
+use serde_json::Value;
+use smartvaults_core::bdk::LocalOutput;
+use smartvaults_core::bitcoin::{Address, OutPoint, ScriptBuf, Txid};
+
+use super::{Error, Message, SmartVaults};
+use crate::config::{CrossCheckBackend, ElectrumEndpoint};
+use crate::types::BalanceCrossCheck;
+
+const ELECTRUM_BATCH_SIZE: usize = 50;
+
+impl SmartVaults {
+    fn electrum_unspents(
+        &self,
+        endpoint: &ElectrumEndpoint,
+        proxy: Option<SocketAddr>,
+        spks: &[ScriptBuf],
+    ) -> Result<BTreeMap<OutPoint, u64>, Error> {
+        let config = ElectrumConfig::builder()
+            .validate_domain(endpoint.validate_tls())
+            .socks5(proxy.map(Socks5Config::new))
+            .build();
+        let client = ElectrumClient::from_config(&endpoint.as_non_standard_format(), config)?;
+
+        let mut unspents: BTreeMap<OutPoint, u64> = BTreeMap::new();
+        for chunk in spks.chunks(ELECTRUM_BATCH_SIZE) {
+            let res = client.batch_script_list_unspent(chunk.iter().map(|spk| spk.as_script()))?;
+            for utxo in res.into_iter().flatten() {
+                unspents.insert(OutPoint::new(utxo.tx_hash, utxo.tx_pos as u32), utxo.value);
+            }
+        }
+        Ok(unspents)
+    }
+
+    async fn esplora_unspents(
+        &self,
+        url: &Url,
+        proxy: Option<SocketAddr>,
+        spks: &[ScriptBuf],
+    ) -> Result<BTreeMap<OutPoint, u64>, Error> {
+        let client: HttpClient = self.http_client(proxy)?;
+
+        let mut unspents: BTreeMap<OutPoint, u64> = BTreeMap::new();
+        for spk in spks.iter() {
+            let address: Address = Address::from_script(spk, self.network)?;
+            let endpoint = format!(
+                "{}/address/{address}/utxo",
+                url.as_str().trim_end_matches('/')
+            );
+            let text: String = client
+                .get(endpoint)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status())?
+                .text()
+                .await?;
+            let json: Value = serde_json::from_str(&text)?;
+            for utxo in json.as_array().into_iter().flatten() {
+                let txid: Txid = utxo
+                    .get("txid")
+                    .and_then(|v| v.as_str())
+                    .and_then(|v| Txid::from_str(v).ok())
+                    .ok_or_else(|| Error::InvalidBackendResponse(String::from("esplora utxo")))?;
+                let vout: u64 = utxo
+                    .get("vout")
+                    .and_then(|v| v.as_u64())
+                    .ok_or_else(|| Error::InvalidBackendResponse(String::from("esplora utxo")))?;
+                let value: u64 = utxo
+                    .get("value")
+                    .and_then(|v| v.as_u64())
+                    .ok_or_else(|| Error::InvalidBackendResponse(String::from("esplora utxo")))?;
+                unspents.insert(OutPoint::new(txid, vout as u32), value);
+            }
+        }
+        Ok(unspents)
+    }
+
+    /// Cross-check the balance of a vault against the backend set in the config
+    ///
+    /// Compare the local UTXOs (from the electrum server) with the ones reported by the second backend,
+    /// to detect a lying or broken electrum server. Sync the vault before: transactions broadcasted
+    /// in the meantime may cause temporary discrepancies.
+    ///
+    /// If the UTXOs don't match, a [`Message::BalanceMismatch`] notification is sent.
+    pub async fn cross_check_balance(&self, vault_id: EventId) -> Result<BalanceCrossCheck, Error> {
+        let backend: CrossCheckBackend = self.config.cross_check_backend().await?;
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+
+        let local: Vec<LocalOutput> = self.manager.get_utxos(vault_id).await?;
+        let spks: Vec<ScriptBuf> = self.manager.get_revealed_spks(vault_id).await?;
+
+        let remote: BTreeMap<OutPoint, u64> = match &backend {
+            CrossCheckBackend::Electrum { endpoint } => {
+                self.electrum_unspents(endpoint, proxy, &spks)?
+            }
+            CrossCheckBackend::Esplora { url } => self.esplora_unspents(url, proxy, &spks).await?,
+        };
+
+        let missing: Vec<OutPoint> = remote
+            .keys()
+            .filter(|outpoint| !local.iter().any(|utxo| utxo.outpoint == **outpoint))
+            .copied()
+            .collect();
+        let unknown: Vec<OutPoint> = local
+            .iter()
+            .filter(|utxo| !remote.contains_key(&utxo.outpoint))
+            .map(|utxo| utxo.outpoint)
+            .collect();
+
+        let check = BalanceCrossCheck {
+            backend,
+            local: local.iter().map(|utxo| utxo.txout.value).sum(),
+            remote: remote.values().sum(),
+            missing,
+            unknown,
+        };
+
+        if !check.is_consistent() {
+            tracing::warn!(
+                "Balance of vault {vault_id} not matching {}: local={} sat, remote={} sat",
+                check.backend,
+                check.local,
+                check.remote
+            );
+            let _ = self.sync_channel.send(Message::BalanceMismatch(vault_id));
+        }
+
+        Ok(check)
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use core::fmt;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use nostr_sdk::database::NostrDatabaseExt;
+use nostr_sdk::{Event, Filter, Keys, Kind, PublicKey, Timestamp, Url};
+use smartvaults_core::bitcoin::Network;
+use smartvaults_protocol::v1::constants::{
+    APPROVED_PROPOSAL_KIND, CAPABILITIES_KIND, CHUNK_KIND, COMPLETED_PROPOSAL_KIND, DEVICE_KIND,
+    KEY_AGENT_SIGNALING, KEY_AGENT_SIGNER_OFFERING_KIND, KEY_AGENT_VERIFIED, LABELS_KIND,
+    PAYMENT_REQUEST_KIND, POLICY_KIND, PROPOSAL_COMMENT_KIND, PROPOSAL_KIND,
+    RECURRING_PAYMENT_KIND, SHARED_KEY_KIND, SHARED_KEY_REQUEST_KIND, SHARED_SIGNERS_KIND,
+    SIGNERS_KIND, SIGNER_REQUEST_KIND, SMARTVAULTS_MAINNET_PUBLIC_KEY,
+    SMARTVAULTS_TESTNET_PUBLIC_KEY, VAULT_METADATA_KIND,
+};
+use smartvaults_sdk_sqlite::model::SyncWindow;
+use tokio::sync::RwLock;
+
+use super::{Error, SmartVaults};
+use crate::constants::{SYNC_CURSOR_MARGIN, SYNC_GAP_REFETCH_TIMEOUT, SYNC_RELAY_EVENTS_LIMIT};
+
+/// Kinds/authors covered by a sync filter
+///
+/// Every scope has its own sync windows, so a filter that changes (i.e. a new contact) doesn't
+/// inherit the cursor of the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum SyncScope {
+    /// Smart Vaults events authored by the user
+    Author,
+    /// Smart Vaults events that mention the user
+    Pubkey,
+    NostrConnect,
+    /// Metadata, contacts, relay list, app settings and devices of the user
+    Account,
+    KeyAgents,
+    Contact(PublicKey),
+}
+
+impl fmt::Display for SyncScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Author => write!(f, "author"),
+            Self::Pubkey => write!(f, "pubkey"),
+            Self::NostrConnect => write!(f, "nostr-connect"),
+            Self::Account => write!(f, "account"),
+            Self::KeyAgents => write!(f, "key-agents"),
+            Self::Contact(public_key) => write!(f, "contact:{public_key}"),
+        }
+    }
+}
+
+/// Relay subscription waiting for the EOSE
+#[derive(Debug, Clone)]
+struct SyncSubscription {
+    cursors: BTreeMap<SyncScope, Timestamp>,
+    /// When the subscription was sent: the newer events are not stored events
+    start: Timestamp,
+    /// Events received before the EOSE
+    received: usize,
+    /// Oldest event received before the EOSE
+    oldest: Option<Timestamp>,
+}
+
+impl SyncSubscription {
+    fn new(cursors: BTreeMap<SyncScope, Timestamp>) -> Self {
+        Self {
+            cursors,
+            start: Timestamp::now(),
+            received: 0,
+            oldest: None,
+        }
+    }
+
+    /// Get the window actually covered for a scope
+    ///
+    /// If the relay reached its limit of stored events, the older events could be missing, so only
+    /// the window from the oldest received event is covered.
+    fn window(&self, since: Timestamp) -> SyncWindow {
+        match self.oldest {
+            Some(oldest) if self.received >= SYNC_RELAY_EVENTS_LIMIT => {
+                SyncWindow::new(since.max(oldest), self.start)
+            }
+            _ => SyncWindow::new(since, self.start),
+        }
+    }
+}
+
+/// Cursors used by the current relay subscriptions
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SyncCursors {
+    subscriptions: Arc<RwLock<HashMap<Url, SyncSubscription>>>,
+}
+
+impl SmartVaults {
+    /// Sync filters (without `since`) grouped by scope
+    async fn scoped_sync_filters(&self) -> Vec<(SyncScope, Filter)> {
+        let base_filter = Filter::new().kinds([
+            POLICY_KIND,
+            PROPOSAL_KIND,
+            APPROVED_PROPOSAL_KIND,
+            PROPOSAL_COMMENT_KIND,
+            COMPLETED_PROPOSAL_KIND,
+            SHARED_KEY_KIND,
+            SHARED_KEY_REQUEST_KIND,
+            SIGNERS_KIND,
+            SHARED_SIGNERS_KIND,
+            SIGNER_REQUEST_KIND,
+            PAYMENT_REQUEST_KIND,
+            RECURRING_PAYMENT_KIND,
+            LABELS_KIND,
+            CHUNK_KIND,
+            CAPABILITIES_KIND,
+            VAULT_METADATA_KIND,
+            Kind::EventDeletion,
+        ]);
+
+        let keys: &Keys = self.keys();
+        let public_key: PublicKey = keys.public_key();
+        let contacts: Vec<PublicKey> = self
+            .client
+            .database()
+            .contacts_public_keys(public_key)
+            .await
+            .unwrap_or_default();
+
+        let mut filters: Vec<(SyncScope, Filter)> = vec![
+            (SyncScope::Author, base_filter.clone().author(public_key)),
+            (SyncScope::Pubkey, base_filter.pubkey(public_key)),
+            (
+                SyncScope::NostrConnect,
+                Filter::new().pubkey(public_key).kind(Kind::NostrConnect),
+            ),
+            (
+                SyncScope::Account,
+                Filter::new().author(public_key).kinds([
+                    Kind::Metadata,
+                    Kind::ContactList,
+                    Kind::RelayList,
+                    Kind::ApplicationSpecificData,
+                    DEVICE_KIND,
+                ]),
+            ),
+            (
+                SyncScope::KeyAgents,
+                Filter::new().kinds([KEY_AGENT_SIGNALING, KEY_AGENT_SIGNER_OFFERING_KIND]),
+            ),
+        ];
+
+        filters.extend(
+            contacts
+                .into_iter()
+                .map(|p| (SyncScope::Contact(p), Filter::new().author(p))),
+        );
+
+        filters
+    }
+
+    /// Verified key agents (always fully synced, few events)
+    fn key_agents_verified_filter(&self) -> Filter {
+        Filter::new()
+            .author(match self.network {
+                Network::Bitcoin => *SMARTVAULTS_MAINNET_PUBLIC_KEY,
+                _ => *SMARTVAULTS_TESTNET_PUBLIC_KEY,
+            })
+            .kind(KEY_AGENT_VERIFIED)
+    }
+
+    /// Sync filters starting from the same timestamp (used for reconciliation)
+    pub(crate) async fn sync_filters(&self, since: Timestamp) -> Vec<Filter> {
+        let mut filters: Vec<Filter> = Vec::new();
+        let mut contacts: Vec<PublicKey> = Vec::new();
+
+        for (scope, filter) in self.scoped_sync_filters().await.into_iter() {
+            match scope {
+                SyncScope::Contact(public_key) => contacts.push(public_key),
+                _ => filters.push(filter.since(since)),
+            }
+        }
+
+        filters.push(self.key_agents_verified_filter());
+
+        if !contacts.is_empty() {
+            filters.push(Filter::new().authors(contacts).since(since));
+        }
+
+        filters
+    }
+
+    /// Get the sync cursor of a scope: the end of the last synced window, minus a margin for the
+    /// clock skew between the relay and this machine
+    async fn sync_cursor(&self, relay_url: &Url, scope: SyncScope) -> Timestamp {
+        match self
+            .db
+            .get_sync_windows(relay_url.clone(), scope.to_string())
+            .await
+        {
+            Ok(windows) => match windows.last() {
+                Some(window) => Timestamp::from(
+                    window
+                        .until
+                        .as_u64()
+                        .saturating_sub(SYNC_CURSOR_MARGIN.as_secs()),
+                ),
+                None => Timestamp::from(0),
+            },
+            Err(e) => {
+                tracing::error!("Impossible to get sync windows for {scope} on {relay_url}: {e}");
+                Timestamp::from(0)
+            }
+        }
+    }
+
+    /// Build the subscription filters for a relay, starting from the sync cursor of each scope
+    ///
+    /// The used cursors are kept in memory until the EOSE, to save the synced windows. If the
+    /// subscription is closed before the EOSE, nothing is saved.
+    pub(crate) async fn relay_sync_filters(&self, relay_url: &Url) -> Vec<Filter> {
+        let mut cursors: BTreeMap<SyncScope, Timestamp> = BTreeMap::new();
+        let mut filters: Vec<Filter> = Vec::new();
+        let mut contacts: BTreeMap<Timestamp, Vec<PublicKey>> = BTreeMap::new();
+
+        for (scope, filter) in self.scoped_sync_filters().await.into_iter() {
+            let since: Timestamp = self.sync_cursor(relay_url, scope).await;
+            cursors.insert(scope, since);
+            match scope {
+                // Group the contacts with the same cursor, to not create a filter for each of them
+                SyncScope::Contact(public_key) => {
+                    contacts.entry(since).or_default().push(public_key)
+                }
+                _ => filters.push(filter.since(since)),
+            }
+        }
+
+        filters.push(self.key_agents_verified_filter());
+
+        for (since, public_keys) in contacts.into_iter() {
+            filters.push(Filter::new().authors(public_keys).since(since));
+        }
+
+        let mut subscriptions = self.sync_cursors.subscriptions.write().await;
+        subscriptions.insert(relay_url.clone(), SyncSubscription::new(cursors));
+
+        filters
+    }
+
+    /// Track an event received by the relay subscription
+    pub(crate) async fn track_relay_sync_event(&self, relay_url: &Url, event: &Event) {
+        let mut subscriptions = self.sync_cursors.subscriptions.write().await;
+        if let Some(subscription) = subscriptions.get_mut(relay_url) {
+            subscription.received += 1;
+            subscription.oldest = Some(match subscription.oldest {
+                Some(oldest) => oldest.min(event.created_at),
+                None => event.created_at,
+            });
+        }
+    }
+
+    /// Save the windows synced by the relay subscription (call it at EOSE)
+    ///
+    /// The windows end when the subscription was sent: the events received after it are not
+    /// recorded, since they are lost if the relay disconnects.
+    pub(crate) async fn save_relay_sync_windows(&self, relay_url: Url) {
+        let subscription: SyncSubscription = {
+            let mut subscriptions = self.sync_cursors.subscriptions.write().await;
+            match subscriptions.get_mut(&relay_url) {
+                Some(subscription) => {
+                    let current = subscription.clone();
+                    // The relay re-sends the stored events if the subscription is sent again
+                    subscription.received = 0;
+                    subscription.oldest = None;
+                    current
+                }
+                None => return,
+            }
+        };
+
+        for (scope, since) in subscription.cursors.iter() {
+            let window: SyncWindow = subscription.window(*since);
+            if window.since != *since {
+                tracing::warn!(
+                    "Sync of {scope} on {relay_url} truncated: missing events before {}",
+                    window.since
+                );
+            }
+            if let Err(e) = self
+                .db
+                .save_sync_window(relay_url.clone(), scope.to_string(), window)
+                .await
+            {
+                tracing::error!("Impossible to save sync window for {scope} on {relay_url}: {e}");
+            }
+        }
+    }
+
+    /// Discard the synced windows that end in the future
+    ///
+    /// They were saved with a wrong clock, so the events created in the meantime could be lost.
+    pub(crate) async fn discard_future_sync_windows(&self) {
+        match self.db.delete_future_sync_windows().await {
+            Ok(0) => (),
+            Ok(deleted) => tracing::warn!("Discarded {deleted} sync windows ending in the future"),
+            Err(e) => tracing::error!("Impossible to discard future sync windows: {e}"),
+        }
+    }
+
+    /// Re-fetch from the relay the windows missing between the synced ones
+    ///
+    /// Returns the fetched events, to handle them.
+    pub(crate) async fn refetch_sync_gaps(&self, relay_url: Url) -> Result<Vec<Event>, Error> {
+        let mut events: Vec<Event> = Vec::new();
+
+        for (scope, filter) in self.scoped_sync_filters().await.into_iter() {
+            let windows: Vec<SyncWindow> = self
+                .db
+                .get_sync_windows(relay_url.clone(), scope.to_string())
+                .await?;
+            for gap in SyncWindow::gaps(&windows).into_iter() {
+                tracing::warn!(
+                    "Found sync gap for {scope} on {relay_url}: {} - {}",
+                    gap.since,
+                    gap.until
+                );
+
+                // The relay sends the newest events first: fetch the gap backwards
+                let mut until: Timestamp = gap.until;
+                loop {
+                    let filter: Filter = filter.clone().since(gap.since).until(until);
+                    let fetched: Vec<Event> = self
+                        .client
+                        .get_events_from(
+                            [relay_url.clone()],
+                            vec![filter],
+                            Some(SYNC_GAP_REFETCH_TIMEOUT),
+                        )
+                        .await?;
+                    let oldest: Option<Timestamp> = fetched.iter().map(|e| e.created_at).min();
+                    let truncated: bool = fetched.len() >= SYNC_RELAY_EVENTS_LIMIT;
+                    tracing::info!(
+                        "Re-fetched {} events for {scope} from {relay_url}",
+                        fetched.len()
+                    );
+                    events.extend(fetched);
+
+                    match oldest {
+                        // Stop if the relay keeps sending the same second
+                        Some(oldest) if truncated && oldest > gap.since && oldest < until => {
+                            self.db
+                                .save_sync_window(
+                                    relay_url.clone(),
+                                    scope.to_string(),
+                                    SyncWindow::new(oldest, until),
+                                )
+                                .await?;
+                            until = oldest;
+                        }
+                        Some(..) if truncated => break,
+                        _ => {
+                            self.db
+                                .save_sync_window(
+                                    relay_url.clone(),
+                                    scope.to_string(),
+                                    SyncWindow::new(gap.since, until),
+                                )
+                                .await?;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(events)
+    }
+}
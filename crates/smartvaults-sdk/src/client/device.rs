@@ -0,0 +1,160 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::env::consts::{ARCH, OS};
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{Event, EventBuilder, EventId, Filter, Keys};
+use smartvaults_core::secp256k1::rand::{self, RngCore};
+use smartvaults_protocol::v1::constants::DEVICE_KIND;
+use smartvaults_protocol::v1::{Device, Encryption, SmartVaultsEventBuilder};
+
+use super::{Error, Message, SmartVaults};
+use crate::types::GetDevice;
+
+fn platform() -> String {
+    format!("{OS} ({ARCH})")
+}
+
+impl SmartVaults {
+    /// Get the identifier of this device (generated at first use)
+    pub async fn device_id(&self) -> Result<String, Error> {
+        match self.db.get_device().await {
+            Ok((device_id, ..)) => Ok(device_id),
+            Err(smartvaults_sdk_sqlite::Error::NotFound(..)) => {
+                let mut bytes = [0u8; 16];
+                rand::thread_rng().fill_bytes(&mut bytes);
+                let device_id: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                self.db.save_device(device_id.clone(), None).await?;
+                Ok(device_id)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get_device_event(&self, device_id: &str) -> Result<Option<Event>, Error> {
+        let filter = Filter::new()
+            .author(self.keys().public_key())
+            .kind(DEVICE_KIND)
+            .identifier(device_id)
+            .limit(1);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+        Ok(events.into_iter().next())
+    }
+
+    /// Announce this device (or refresh its last seen)
+    ///
+    /// Must be called after the reconciliation, to not override a revocation made by another device.
+    pub(crate) async fn announce_device(&self) -> Result<(), Error> {
+        let keys: &Keys = self.keys();
+        let device_id: String = self.device_id().await?;
+
+        if let Some(event) = self.get_device_event(&device_id).await? {
+            let device: Device = Device::decrypt_with_keys(keys, event.content())?;
+            if device.is_revoked() {
+                return self.handle_device_revoked().await;
+            }
+        }
+
+        let (_, name) = self.db.get_device().await?;
+        let device: Device = Device::new(platform(), name);
+        let event: Event = EventBuilder::device(keys, device_id, &device)?;
+        self.client.send_event(event).await?;
+        Ok(())
+    }
+
+    /// Set the name of this device
+    pub async fn set_device_name(&self, name: Option<String>) -> Result<(), Error> {
+        let device_id: String = self.device_id().await?;
+        self.db.save_device(device_id, name).await?;
+        self.announce_device().await
+    }
+
+    /// Get the devices (and sessions) that opened this keychain, sorted by last seen
+    pub async fn get_devices(&self) -> Result<Vec<GetDevice>, Error> {
+        let keys: &Keys = self.keys();
+        let current: String = self.device_id().await?;
+        let filter = Filter::new().author(keys.public_key()).kind(DEVICE_KIND);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+
+        let mut devices: Vec<GetDevice> = Vec::with_capacity(events.len());
+        for event in events.into_iter() {
+            let device_id: &str = match event.identifier() {
+                Some(id) => id,
+                None => continue,
+            };
+            match Device::decrypt_with_keys(keys, event.content()) {
+                Ok(device) => devices.push(GetDevice {
+                    device_id: device_id.to_string(),
+                    platform: device.platform(),
+                    name: device.name(),
+                    last_seen: event.created_at,
+                    revoked: device.is_revoked(),
+                    current: device_id == current,
+                }),
+                Err(e) => tracing::error!("Impossible to decrypt device {device_id}: {e}"),
+            }
+        }
+        devices.sort();
+        Ok(devices)
+    }
+
+    /// Revoke a device
+    ///
+    /// All the devices share the same keychain, so the revocation is cooperative: at the next sync the
+    /// revoked device disconnects its nostr connect sessions and stops. If the device was lost or
+    /// compromised, move the funds to a new keychain.
+    pub async fn revoke_device<S>(&self, device_id: S) -> Result<EventId, Error>
+    where
+        S: AsRef<str>,
+    {
+        let device_id: &str = device_id.as_ref();
+        if device_id == self.device_id().await? {
+            return Err(Error::CantRevokeCurrentDevice);
+        }
+
+        let keys: &Keys = self.keys();
+        let event: Event = self
+            .get_device_event(device_id)
+            .await?
+            .ok_or(Error::DeviceNotFound)?;
+        let device: Device = Device::decrypt_with_keys(keys, event.content())?.revoke();
+        let event: Event = EventBuilder::device(keys, device_id, &device)?;
+        Ok(self.client.send_event(event).await?)
+    }
+
+    pub(crate) async fn handle_device_event(&self, event: &Event) -> Result<(), Error> {
+        if event.identifier() == Some(self.device_id().await?.as_str()) {
+            let device: Device = Device::decrypt_with_keys(self.keys(), event.content())?;
+            if device.is_revoked() {
+                return self.handle_device_revoked().await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_device_revoked(&self) -> Result<(), Error> {
+        tracing::warn!("This device was revoked");
+
+        for (uri, ..) in self.db.get_nostr_connect_sessions().await? {
+            if let Err(e) = self
+                ._disconnect_nostr_connect_session(uri.public_key, false)
+                .await
+            {
+                tracing::error!("Impossible to disconnect nostr connect session: {e}");
+            }
+        }
+
+        let _ = self.sync_channel.send(Message::DeviceRevoked);
+        self.client.stop().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Connectivity diagnostics
+//!
+//! Check each relay of the pool (connection, NIP-42 auth requirement and publish/read round-trip
+//! of a test event) and the timechain backends (version, block height and latency), i.e. to
+//! troubleshoot the first run.
+
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+use bdk_electrum::electrum_client::{
+    Client as ElectrumClient, Config as ElectrumConfig, ElectrumApi, HeaderNotification,
+    Socks5Config,
+};
+use futures_util::future;
+use nostr_sdk::{Event, EventBuilder, Filter, Kind, Relay, RelayStatus, Tag, Timestamp, Url};
+use reqwest::Client as HttpClient;
+
+use super::{Error, SmartVaults};
+use crate::config::ElectrumEndpoint;
+use crate::constants::{DIAGNOSTICS_IDENTIFIER, DIAGNOSTICS_TIMEOUT};
+use crate::types::{BackendDiagnostic, DiagnosticsReport, RelayDiagnostic};
+
+/// Get the electrum server version, the block height and the latency (`server.ping`)
+fn electrum_status(
+    endpoint: &ElectrumEndpoint,
+    proxy: Option<SocketAddr>,
+) -> Result<(String, u32, Duration), Error> {
+    let config = ElectrumConfig::builder()
+        .validate_domain(endpoint.validate_tls())
+        .timeout(Some(DIAGNOSTICS_TIMEOUT.as_secs() as u8))
+        .socks5(proxy.map(Socks5Config::new))
+        .build();
+    let client = ElectrumClient::from_config(&endpoint.as_non_standard_format(), config)?;
+    let version: String = client.server_features()?.server_version;
+    let HeaderNotification { height, .. } = client.block_headers_subscribe()?;
+    let start = Instant::now();
+    client.ping()?;
+    Ok((version, height as u32, start.elapsed()))
+}
+
+impl SmartVaults {
+    /// Check the relays and the timechain backends
+    ///
+    /// A test event (NIP-78, replaceable) is published to each relay and read back.
+    pub async fn run_diagnostics(&self) -> Result<DiagnosticsReport, Error> {
+        let relays = self.relays().await;
+        let relays: Vec<RelayDiagnostic> = future::join_all(
+            relays
+                .into_iter()
+                .map(|(url, relay)| self.relay_diagnostic(url, relay)),
+        )
+        .await;
+
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+        let mut backends: Vec<BackendDiagnostic> = Vec::new();
+        backends.push(match self.config.electrum_endpoint().await {
+            Ok(endpoint) => self.electrum_diagnostic(&endpoint, proxy),
+            Err(e) => BackendDiagnostic {
+                backend: String::from("electrum"),
+                version: None,
+                block_height: None,
+                latency: None,
+                error: Some(e.to_string()),
+            },
+        });
+        for url in self.config.esplora_endpoints().await.into_iter() {
+            backends.push(self.esplora_diagnostic(&url, proxy).await);
+        }
+
+        Ok(DiagnosticsReport {
+            relays,
+            backends,
+            timestamp: Timestamp::now(),
+        })
+    }
+
+    async fn relay_diagnostic(&self, url: Url, relay: Relay) -> RelayDiagnostic {
+        if relay.status().await != RelayStatus::Connected {
+            relay.connect(Some(DIAGNOSTICS_TIMEOUT)).await;
+        }
+        let connected: bool = relay.status().await == RelayStatus::Connected;
+        let auth_required: Option<bool> = self
+            .get_relay_terms(url.clone())
+            .await
+            .ok()
+            .map(|terms| terms.auth_required);
+
+        let (round_trip, error) = if connected {
+            match self.relay_round_trip(&url).await {
+                Ok(round_trip) => (Some(round_trip), None),
+                Err(e) => (None, Some(e.to_string())),
+            }
+        } else {
+            (
+                None,
+                Some(format!("impossible to connect ({})", relay.status().await)),
+            )
+        };
+
+        RelayDiagnostic {
+            url,
+            connected,
+            auth_required,
+            round_trip,
+            error,
+        }
+    }
+
+    /// Publish the test event to the relay and read it back
+    async fn relay_round_trip(&self, url: &Url) -> Result<Duration, Error> {
+        let event: Event = EventBuilder::new(
+            Kind::ApplicationSpecificData,
+            Timestamp::now().to_string(),
+            [Tag::Identifier(DIAGNOSTICS_IDENTIFIER.to_string())],
+        )
+        .to_event(self.keys())?;
+
+        let start = Instant::now();
+        self.client
+            .send_event_to([url.clone()], event.clone())
+            .await?;
+        let filter: Filter = Filter::new()
+            .author(event.author())
+            .kind(Kind::ApplicationSpecificData)
+            .identifier(DIAGNOSTICS_IDENTIFIER);
+        let events: Vec<Event> = self
+            .client
+            .get_events_from([url.clone()], vec![filter], Some(DIAGNOSTICS_TIMEOUT))
+            .await?;
+
+        if events.iter().any(|e| e.id == event.id) {
+            Ok(start.elapsed())
+        } else {
+            Err(Error::EventNotReturned(url.clone()))
+        }
+    }
+
+    fn electrum_diagnostic(
+        &self,
+        endpoint: &ElectrumEndpoint,
+        proxy: Option<SocketAddr>,
+    ) -> BackendDiagnostic {
+        let backend: String = format!("electrum ({endpoint})");
+        match electrum_status(endpoint, proxy) {
+            Ok((version, block_height, latency)) => BackendDiagnostic {
+                backend,
+                version: Some(version),
+                block_height: Some(block_height),
+                latency: Some(latency),
+                error: None,
+            },
+            Err(e) => BackendDiagnostic {
+                backend,
+                version: None,
+                block_height: None,
+                latency: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Get the block height (`GET /blocks/tip/height`) of the esplora API
+    async fn esplora_diagnostic(&self, url: &Url, proxy: Option<SocketAddr>) -> BackendDiagnostic {
+        let backend: String = format!("esplora ({url})");
+        let endpoint: String = format!("{}/blocks/tip/height", url.as_str().trim_end_matches('/'));
+        let start = Instant::now();
+        let res: Result<u32, String> = async {
+            let client: HttpClient = self.http_client(proxy).map_err(|e| e.to_string())?;
+            let text: String = client
+                .get(endpoint)
+                .send()
+                .await
+                .and_then(|res| res.error_for_status())
+                .map_err(|e| e.to_string())?
+                .text()
+                .await
+                .map_err(|e| e.to_string())?;
+            text.trim().parse::<u32>().map_err(|e| e.to_string())
+        }
+        .await;
+        match res {
+            Ok(block_height) => BackendDiagnostic {
+                backend,
+                version: None,
+                block_height: Some(block_height),
+                latency: Some(start.elapsed()),
+                error: None,
+            },
+            Err(e) => BackendDiagnostic {
+                backend,
+                version: None,
+                block_height: None,
+                latency: None,
+                error: Some(e),
+            },
+        }
+    }
+}
@@ -1,11 +1,16 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use nostr_sdk::{Event, EventBuilder, EventId, Keys};
-use smartvaults_protocol::v1::{Label, SmartVaultsEventBuilder};
+use std::collections::{BTreeSet, HashMap};
+
+use nostr_sdk::{Event, EventBuilder, EventId, Keys, Profile, PublicKey, Timestamp};
+use smartvaults_core::bitcoin::{OutPoint, ScriptBuf, Transaction, Txid};
+use smartvaults_protocol::v1::{Label, LabelData, SmartVaultsEventBuilder};
 
 use super::{Error, SmartVaults};
+use crate::manager::{SmartVaultsWallet, TransactionDetails};
 use crate::storage::InternalPolicy;
+use crate::types::{GetMemberContribution, GetMemberContributions};
 
 impl SmartVaults {
     pub async fn save_label(&self, policy_id: EventId, label: Label) -> Result<EventId, Error> {
@@ -17,13 +22,146 @@ impl SmartVaults {
         // Compose event
         let event: Event = EventBuilder::label(&shared_key, policy_id, &label, &public_keys)?;
 
+        let timestamp: Timestamp = event.created_at;
+
         // Publish event
         let event_id: EventId = self.client.send_event(event).await?;
 
         // Save to db
         let identifier: String = label.generate_identifier(&shared_key)?;
-        self.storage.save_label(identifier, policy_id, label).await;
+        self.storage
+            .save_label(identifier, policy_id, label, timestamp)
+            .await;
 
         Ok(event_id)
     }
+
+    /// Label the transaction of a finalized proposal and its change outputs with the proposal
+    /// description, so the history and the exported labels don't need a manual relabeling
+    ///
+    /// The labels already set are not overwritten.
+    pub(super) async fn label_finalized_tx(
+        &self,
+        policy_id: EventId,
+        tx: &Transaction,
+        description: &str,
+    ) -> Result<(), Error> {
+        if description.is_empty() {
+            return Ok(());
+        }
+
+        let shared_key: Keys = self.storage.shared_key(&policy_id).await?;
+        let wallet: SmartVaultsWallet = self.manager.wallet(policy_id).await?;
+
+        let txid: Txid = tx.txid();
+        let mut labels: Vec<Label> = vec![Label::txid(txid, description)];
+        for (vout, txout) in tx.output.iter().enumerate() {
+            if wallet.is_mine(&txout.script_pubkey).await {
+                let utxo = OutPoint::new(txid, vout as u32);
+                labels.push(Label::utxo(utxo, description));
+            }
+        }
+
+        for label in labels.into_iter() {
+            let identifier: String = label.generate_identifier(&shared_key)?;
+            if self
+                .storage
+                .get_label_by_identifier(identifier)
+                .await
+                .is_err()
+            {
+                self.save_label(policy_id, label).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Claim a deposit (address or UTXO) as own contribution to the vault
+    ///
+    /// If `data` is an address, all the funds received by it will be attributed to the user.
+    pub async fn claim_deposit<S>(
+        &self,
+        policy_id: EventId,
+        data: LabelData,
+        text: S,
+    ) -> Result<EventId, Error>
+    where
+        S: Into<String>,
+    {
+        let public_key: PublicKey = self.keys().public_key();
+        let label: Label = Label::new(data, text).with_contributor(public_key);
+        self.save_label(policy_id, label).await
+    }
+
+    /// Get deposits breakdown by vault member
+    ///
+    /// Deposits are attributed using the contributor of the UTXO label or, as fallback, of the address label.
+    pub async fn get_member_contributions(
+        &self,
+        policy_id: EventId,
+    ) -> Result<GetMemberContributions, Error> {
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&policy_id).await?;
+
+        // Get labels
+        let script_labels: HashMap<ScriptBuf, Label> =
+            self.storage.get_addresses_labels(policy_id).await;
+        let utxo_labels: HashMap<OutPoint, Label> = self.storage.get_utxos_labels(policy_id).await;
+
+        let mut contributions: HashMap<PublicKey, (u64, Vec<OutPoint>)> = public_keys
+            .iter()
+            .map(|public_key| (*public_key, (0, Vec::new())))
+            .collect();
+        let mut unattributed: u64 = 0;
+        let mut unattributed_deposits: Vec<OutPoint> = Vec::new();
+
+        let wallet: SmartVaultsWallet = self.manager.wallet(policy_id).await?;
+        let txs: BTreeSet<TransactionDetails> = wallet.txs().await;
+        for tx in txs.into_iter().filter(|tx| tx.sent == 0) {
+            let txid = tx.txid();
+            for (vout, txout) in tx.output.iter().enumerate() {
+                if !wallet.is_mine(&txout.script_pubkey).await {
+                    continue;
+                }
+
+                let outpoint = OutPoint::new(txid, vout as u32);
+                let contributor: Option<PublicKey> = utxo_labels
+                    .get(&outpoint)
+                    .and_then(|l| l.contributor())
+                    .or_else(|| {
+                        script_labels
+                            .get(&txout.script_pubkey)
+                            .and_then(|l| l.contributor())
+                    });
+
+                match contributor.and_then(|public_key| contributions.get_mut(&public_key)) {
+                    Some((amount, deposits)) => {
+                        *amount = amount.saturating_add(txout.value);
+                        deposits.push(outpoint);
+                    }
+                    None => {
+                        unattributed = unattributed.saturating_add(txout.value);
+                        unattributed_deposits.push(outpoint);
+                    }
+                }
+            }
+        }
+
+        let mut members: Vec<GetMemberContribution> = Vec::with_capacity(contributions.len());
+        for (public_key, (amount, deposits)) in contributions.into_iter() {
+            let metadata = self.get_public_key_metadata(public_key).await?;
+            members.push(GetMemberContribution {
+                user: Profile::new(public_key, metadata),
+                amount,
+                deposits,
+            });
+        }
+        members.sort_by(|a, b| b.amount.cmp(&a.amount));
+
+        Ok(GetMemberContributions {
+            members,
+            unattributed,
+            unattributed_deposits,
+        })
+    }
 }
@@ -0,0 +1,135 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeSet, HashMap};
+use std::net::SocketAddr;
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{Event, EventId, Filter, Keys, Profile, PublicKey, Timestamp};
+use serde_json::Value;
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_core::Policy;
+use smartvaults_protocol::v1::constants::SHARED_KEY_REQUEST_KIND;
+
+use super::{Error, SmartVaults};
+use crate::storage::InternalPolicy;
+use crate::types::GetVaultMember;
+
+impl SmartVaults {
+    /// Fingerprints of the signers of `public_key` used in the policy
+    async fn member_signers(
+        &self,
+        policy: &Policy,
+        public_key: PublicKey,
+    ) -> BTreeSet<Fingerprint> {
+        if public_key == self.keys().public_key() {
+            let signers = self.storage.signers().await.into_values();
+            policy
+                .search_used_signers(signers)
+                .map(|s| s.fingerprint())
+                .collect()
+        } else {
+            self.storage
+                .get_shared_signers_by_public_key(public_key)
+                .await
+                .into_iter()
+                .map(|(_, s)| s.fingerprint())
+                .filter(|fingerprint| policy.is_fingerprint_involved(fingerprint).unwrap_or(false))
+                .collect()
+        }
+    }
+
+    /// Get the members of the vault, with their role and their latest activity
+    pub async fn get_vault_members(&self, vault_id: EventId) -> Result<Vec<GetVaultMember>, Error> {
+        let InternalPolicy {
+            policy,
+            public_keys,
+        } = self.storage.vault(&vault_id).await?;
+
+        let filter: Filter = Filter::new().authors(public_keys.clone()).event(vault_id);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+
+        let mut last_activity: HashMap<PublicKey, Timestamp> = HashMap::new();
+        let mut shared_key_requested: BTreeSet<PublicKey> = BTreeSet::new();
+        for event in events.into_iter() {
+            if event.kind == SHARED_KEY_REQUEST_KIND {
+                shared_key_requested.insert(event.author());
+            } else {
+                last_activity
+                    .entry(event.author())
+                    .and_modify(|t| *t = (*t).max(event.created_at))
+                    .or_insert(event.created_at);
+            }
+        }
+
+        let mut members: Vec<GetVaultMember> = Vec::with_capacity(public_keys.len());
+        for public_key in public_keys.into_iter() {
+            let metadata = self.get_public_key_metadata(public_key).await?;
+            members.push(GetVaultMember {
+                user: Profile::new(public_key, metadata),
+                signers: self.member_signers(&policy, public_key).await,
+                last_activity: last_activity.get(&public_key).copied(),
+                shared_key_requested: shared_key_requested.contains(&public_key),
+            });
+        }
+        Ok(members)
+    }
+
+    /// Verify the NIP-05 identifier (i.e. `name@example.com`) of a public key
+    ///
+    /// Return `false` if the identifier is malformed or doesn't point to the public key.
+    pub async fn verify_nip05<S>(&self, public_key: PublicKey, nip05: S) -> Result<bool, Error>
+    where
+        S: AsRef<str>,
+    {
+        let (name, domain) = match nip05.as_ref().split_once('@') {
+            Some((name, domain)) if !name.is_empty() && !domain.is_empty() => (name, domain),
+            _ => return Ok(false),
+        };
+
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+        let client = self
+            .http_client(proxy)
+            .map_err(|e| Error::Download(e.to_string()))?;
+        let res = client
+            .get(format!("https://{domain}/.well-known/nostr.json"))
+            .query(&[("name", name)])
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| Error::Download(e.to_string()))?;
+        let json: String = res
+            .text()
+            .await
+            .map_err(|e| Error::Download(e.to_string()))?;
+        let json: Value = match serde_json::from_str(&json) {
+            Ok(json) => json,
+            Err(_) => return Ok(false),
+        };
+
+        Ok(json
+            .get("names")
+            .and_then(|names| names.get(name))
+            .and_then(|p| p.as_str())
+            .map_or(false, |p| p == public_key.to_hex()))
+    }
+
+    /// Re-publish the shared key of the vault for a member that didn't receive it
+    pub async fn resend_vault_invitation(
+        &self,
+        vault_id: EventId,
+        public_key: PublicKey,
+    ) -> Result<EventId, Error> {
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+        if !public_keys.contains(&public_key) {
+            return Err(Error::PublicKeyNotFound);
+        }
+        self.publish_shared_key_for(vault_id, &shared_key, public_key)
+            .await
+    }
+}
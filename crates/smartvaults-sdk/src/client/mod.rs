@@ -10,17 +10,14 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_utility::thread;
-use bdk_electrum::electrum_client::{
-    Client as ElectrumClient, Config as ElectrumConfig, ElectrumApi, Socks5Config,
-};
 use nostr_sdk::database::{NostrDatabaseExt, Order};
 use nostr_sdk::nips::nip06::FromMnemonic;
 use nostr_sdk::pool::pool;
 use nostr_sdk::{
-    nips, Client, ClientBuilder, ClientMessage, Contact, Event, EventBuilder, EventId, Filter,
-    JsonUtil, Keys, Kind, Metadata, Options, Profile, PublicKey, Relay, RelayOptions,
-    RelayPoolNotification, RelaySendOptions, Result, SQLiteDatabase, SubscribeAutoCloseOptions,
-    SubscribeOptions, Tag, Timestamp, TryIntoUrl, UncheckedUrl, Url,
+    Client, ClientBuilder, ClientMessage, Contact, Event, EventBuilder, EventId, Filter, JsonUtil,
+    Keys, Kind, Metadata, Options, Profile, PublicKey, Relay, RelayOptions, RelayPoolNotification,
+    RelaySendOptions, Result, SQLiteDatabase, SubscribeAutoCloseOptions, SubscribeOptions, Tag,
+    Timestamp, TryIntoUrl, UncheckedUrl, Url,
 };
 use parking_lot::RwLock as ParkingLotRwLock;
 use smartvaults_core::bdk::chain::ConfirmationTime;
@@ -31,40 +28,98 @@ use smartvaults_core::bitcoin::address::NetworkUnchecked;
 use smartvaults_core::bitcoin::bip32::Fingerprint;
 use smartvaults_core::bitcoin::psbt::PartiallySignedTransaction;
 use smartvaults_core::bitcoin::{Address, Network, OutPoint, ScriptBuf, Txid};
+use smartvaults_core::descriptor::{self, DescriptorDiff};
 use smartvaults_core::miniscript::Descriptor;
+use smartvaults_core::reserves::ProofPackage;
 use smartvaults_core::signer::smartvaults_signer;
 use smartvaults_core::types::{KeeChain, Keychain, Seed, WordCount};
+use smartvaults_core::util::psbt_signers;
 use smartvaults_core::{
-    Amount, ApprovedProposal, CompletedProposal, FeeRate, Policy, PolicyTemplate, Proposal, Signer,
-    SECP256K1,
+    Amount, ApprovedProposal, CompletedProposal, FeeRate, Policy, PolicyTemplate, Priority,
+    Proposal, ProposalDiff, Recipient, Signer, TxOptions, SECP256K1,
 };
 use smartvaults_protocol::v1::constants::{
     APPROVED_PROPOSAL_EXPIRATION, APPROVED_PROPOSAL_KIND, COMPLETED_PROPOSAL_KIND, PROPOSAL_KIND,
-    SHARED_KEY_KIND,
 };
-use smartvaults_protocol::v1::{Encryption, Label, LabelData, SmartVaultsEventBuilder};
+use smartvaults_protocol::v1::{
+    approval, chunk, velocity, Encryption, Label, LabelData, SmartVaultsEventBuilder,
+};
 use smartvaults_sdk_sqlite::Store;
 use tokio::sync::broadcast::{self, Sender};
 
+mod admission;
+mod approval;
+mod archive;
+#[cfg(feature = "auto-approver")]
+mod auto_approver;
+mod broadcast;
+mod capabilities;
+mod comment;
+mod compromised_keys;
 mod connect;
+mod contacts;
+mod cross_check;
+mod cursor;
+mod device;
+mod diagnostics;
 mod key_agent;
 mod label;
+mod member;
+mod outbox;
+mod payees;
+mod payment_request;
+mod pin;
+mod plugin;
+mod price;
+mod quorum;
+mod recovery_kit;
+mod recovery_path;
+mod recurring_payment;
+mod registration;
+mod replacement;
+mod scheduler;
+mod settings;
+mod settlement;
+mod shared_key;
+mod signer_request;
 mod signers;
+mod split;
+mod stale_approval;
+mod stuck;
 mod sync;
-
+mod update;
+mod validation;
+mod vault_metadata;
+mod velocity;
+mod whitelist;
+
+use self::admission::RelayAdmissions;
+use self::approval::ApprovalPath;
+#[cfg(feature = "auto-approver")]
+pub use self::auto_approver::AutoApproverRules;
+use self::cursor::SyncCursors;
+use self::scheduler::SyncScheduler;
+pub use self::scheduler::{NetworkCondition, SyncSchedule};
 pub use self::sync::{EventHandled, Message};
-use crate::config::{Config, ElectrumEndpoint};
-use crate::constants::{MAINNET_RELAYS, SEND_TIMEOUT, TESTNET_RELAYS};
+use crate::bundle::{self, BundleManifest};
+use crate::config::{Config, ElectrumEndpoint, FeeProvider};
+use crate::constants::{
+    MAINNET_RELAYS, REGTEST_RELAYS, SEND_TIMEOUT, SIGNET_RELAYS, TESTNET_RELAYS,
+};
+use crate::fee::{self, ElectrumFeeEstimator, Error as FeeError, FeeEstimator};
 use crate::manager::{Manager, SmartVaultsWallet, TransactionDetails};
+use crate::plugin::Plugins;
 use crate::storage::{
-    InternalApproval, InternalCompletedProposal, InternalPolicy, InternalProposal,
-    SmartVaultsStorage,
+    CompletedProposalIndex, InternalApproval, InternalCompletedProposal, InternalPolicy,
+    InternalProposal, SmartVaultsStorage,
 };
 use crate::types::{
-    GetAddress, GetApproval, GetApprovedProposals, GetCompletedProposal, GetPolicy, GetProposal,
-    GetTransaction, GetUtxo, PolicyBackup,
+    BroadcastResult, BulkApprovalResult, ConsistencyReport, FeeSuggestion, GetAddress, GetApproval,
+    GetApprovedProposals, GetCompletedProposal, GetPolicy, GetProposal, GetProposalStatus,
+    GetTransaction, GetUtxo, GetVaultSummary, MempoolSnapshot, PolicyBackup, ProposalIssue,
+    SpendOptions, SpendPreview, SpendRecipient, StuckTransaction, VaultSyncStatus,
 };
-use crate::{util, Error};
+use crate::{util, Error, ErrorContext};
 
 /// Smart Vaults Client
 #[derive(Debug, Clone)]
@@ -79,6 +134,10 @@ pub struct SmartVaults {
     db: Store,
     syncing: Arc<AtomicBool>,
     sync_channel: Sender<Message>,
+    scheduler: SyncScheduler,
+    sync_cursors: SyncCursors,
+    relay_admissions: RelayAdmissions,
+    plugins: Plugins,
     default_signer: Signer,
 }
 
@@ -120,7 +179,9 @@ impl SmartVaults {
             .build();
 
         // Storage
-        let storage = SmartVaultsStorage::build(keys.clone(), client.database(), network).await?;
+        let storage = SmartVaultsStorage::build(keys.clone(), client.database(), network)
+            .await
+            .context("impossible to build storage")?;
 
         let (sender, _) = broadcast::channel::<Message>(4096);
 
@@ -130,11 +191,16 @@ impl SmartVaults {
             keys,
             client,
             manager: Manager::new(db.clone(), network),
-            config: Config::try_from_file(base_path, network)?,
+            config: Config::try_from_file(base_path, network)
+                .context("impossible to load config")?,
             storage,
             db,
             syncing: Arc::new(AtomicBool::new(false)),
             sync_channel: sender,
+            scheduler: SyncScheduler::default(),
+            sync_cursors: SyncCursors::default(),
+            relay_admissions: RelayAdmissions::default(),
+            plugins: Plugins::default(),
             default_signer: smartvaults_signer(seed, network)?,
         };
 
@@ -165,7 +231,8 @@ impl SmartVaults {
             || Ok(password.clone()),
             network,
             &SECP256K1,
-        )?;
+        )
+        .context("impossible to open keychain")?;
         let passphrase: Option<String> = keechain.keychain(&password)?.get_passphrase(0);
         keechain.apply_passphrase(&password, passphrase, &SECP256K1)?;
 
@@ -191,7 +258,7 @@ impl SmartVaults {
     {
         let base_path = base_path.as_ref();
 
-        let password: String = get_password().map_err(|e| Error::Generic(e.to_string()))?;
+        let password: String = get_password().map_err(|e| Error::GetPassword(e.to_string()))?;
 
         // Generate keychain
         let keychains_path: PathBuf = util::dir::keychains_path(base_path, network)?;
@@ -204,12 +271,13 @@ impl SmartVaults {
             || Ok(None),
             network,
             &SECP256K1,
-        )?;
+        )
+        .context("impossible to generate keychain")?;
         let passphrase: Option<String> =
-            get_passphrase().map_err(|e| Error::Generic(e.to_string()))?;
+            get_passphrase().map_err(|e| Error::GetPassphrase(e.to_string()))?;
         if let Some(passphrase) = passphrase {
             keechain.add_passphrase(&password, &passphrase)?;
-            keechain.save()?;
+            keechain.save().context("impossible to save keychain")?;
             keechain.apply_passphrase(&password, Some(passphrase), &SECP256K1)?;
         }
 
@@ -236,7 +304,7 @@ impl SmartVaults {
     {
         let base_path = base_path.as_ref();
 
-        let password: String = get_password().map_err(|e| Error::Generic(e.to_string()))?;
+        let password: String = get_password().map_err(|e| Error::GetPassword(e.to_string()))?;
 
         // Restore keychain
         let keychains_path: PathBuf = util::dir::keychains_path(base_path, network)?;
@@ -248,12 +316,13 @@ impl SmartVaults {
             get_mnemonic,
             network,
             &SECP256K1,
-        )?;
+        )
+        .context("impossible to restore keychain")?;
         let passphrase: Option<String> =
-            get_passphrase().map_err(|e| Error::Generic(e.to_string()))?;
+            get_passphrase().map_err(|e| Error::GetPassphrase(e.to_string()))?;
         if let Some(passphrase) = passphrase {
             keechain.add_passphrase(&password, &passphrase)?;
-            keechain.save()?;
+            keechain.save().context("impossible to save keychain")?;
             keechain.apply_passphrase(&password, Some(passphrase), &SECP256K1)?;
         }
 
@@ -268,15 +337,81 @@ impl SmartVaults {
         Ok(util::dir::get_keychains_list(base_path, network)?)
     }
 
+    /// Export keychains (optional), databases and config to an encrypted bundle
+    ///
+    /// See [`bundle::export_state_bundle`].
+    pub fn export_state_bundle<P, B, S>(
+        base_path: P,
+        network: Network,
+        path: B,
+        password: S,
+        include_keychains: bool,
+    ) -> Result<BundleManifest, Error>
+    where
+        P: AsRef<Path>,
+        B: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        Ok(bundle::export_state_bundle(
+            base_path,
+            network,
+            path,
+            password,
+            include_keychains,
+        )?)
+    }
+
+    /// Import a bundle created with [`SmartVaults::export_state_bundle`]
+    ///
+    /// See [`bundle::import_state_bundle`].
+    pub fn import_state_bundle<P, B, S>(
+        base_path: P,
+        network: Network,
+        path: B,
+        password: S,
+    ) -> Result<BundleManifest, Error>
+    where
+        P: AsRef<Path>,
+        B: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        Ok(bundle::import_state_bundle(
+            base_path, network, path, password,
+        )?)
+    }
+
     #[tracing::instrument(skip_all, level = "trace")]
     async fn init(&self) -> Result<(), Error> {
+        // Reconcile in-memory storage with the nostr database
+        match self.storage.check_consistency().await {
+            Ok(report) if !report.is_consistent() => tracing::warn!(
+                "Storage inconsistencies: {} replayed, {} failed, {} orphaned",
+                report.replayed.len(),
+                report.failed.len(),
+                report.orphaned.len()
+            ),
+            Ok(..) => tracing::debug!("Storage is consistent"),
+            Err(e) => tracing::error!("Impossible to check storage consistency: {e}"),
+        }
+
+        // Register policies: they'll be loaded on first access or by the background hydration
         for (policy_id, InternalPolicy { policy, .. }) in self.storage.vaults().await.into_iter() {
-            let manager = self.manager.clone();
-            thread::spawn(async move {
-                if let Err(e) = manager.load_policy(policy_id, policy).await {
-                    tracing::error!("Impossible to load policy {policy_id}: {e}");
-                }
-            })?;
+            self.manager.register_policy(policy_id, policy).await;
+        }
+
+        // Eagerly load pinned vaults
+        for policy_id in self.db.get_pinned_vaults().await?.into_iter() {
+            if let Err(e) = self.manager.set_pinned(policy_id, true).await {
+                tracing::error!("Impossible to load pinned policy {policy_id}: {e}");
+            }
+        }
+
+        let manager = self.manager.clone();
+        thread::spawn(async move {
+            manager.hydrate().await;
+        })?;
+        if let Err(e) = self.load_relays_admission().await {
+            tracing::error!("Impossible to load relays admission: {e}");
         }
         self.restore_relays().await?;
         self.client.connect().await;
@@ -284,17 +419,108 @@ impl SmartVaults {
         Ok(())
     }
 
-    async fn blockchain(&self) -> Result<ElectrumClient, Error> {
-        let endpoint = self.config.electrum_endpoint().await?;
+    /// Get the estimator of the fee provider set in the config
+    pub async fn fee_estimator(&self) -> Result<Box<dyn FeeEstimator>, Error> {
+        let provider: FeeProvider = self.config.fee_provider().await;
+        let endpoint: Option<ElectrumEndpoint> = self.config.electrum_endpoint().await.ok();
         let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
-        let config = ElectrumConfig::builder()
-            .validate_domain(endpoint.validate_tls())
-            .socks5(proxy.map(Socks5Config::new))
-            .build();
-        Ok(ElectrumClient::from_config(
-            &endpoint.as_non_standard_format(),
-            config,
-        )?)
+        Ok(fee::estimator(provider, endpoint, proxy)?)
+    }
+
+    /// Suggest a fee rate likely to confirm by the `deadline`
+    ///
+    /// Combine the recent block fee rates (from the mempool.space fee provider or, if not set, from the block explorer)
+    /// with the current estimation of the fee provider.
+    pub async fn suggest_fee(&self, deadline: Duration) -> Result<FeeSuggestion, Error> {
+        let target_blocks: u8 = (deadline.as_secs() / 600).clamp(1, 144) as u8;
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+
+        let url: Option<Url> = match self.config.fee_provider().await {
+            FeeProvider::MempoolSpace { url } => Some(url),
+            _ => self.config.block_explorer().await.ok(),
+        };
+        let historical: Option<BdkFeeRate> = match url {
+            Some(url) => match fee::recent_block_fee_rates(&url, proxy).await {
+                Ok(rates) => fee::fee_rate_for_target(rates, target_blocks)
+                    .map(|rate| BdkFeeRate::from_sat_per_vb(rate as f32)),
+                Err(e) => {
+                    tracing::warn!("Impossible to get recent block fee rates: {e}");
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let estimated: Option<BdkFeeRate> = match self.fee_estimator().await {
+            Ok(estimator) => match estimator.estimate_fee(target_blocks).await {
+                Ok(rate) => Some(rate),
+                Err(e) => {
+                    tracing::warn!("Impossible to estimate fee with {}: {e}", estimator.name());
+                    None
+                }
+            },
+            Err(e) => {
+                tracing::warn!("Impossible to get fee estimator: {e}");
+                None
+            }
+        };
+
+        let fee_rate: BdkFeeRate = match (historical, estimated) {
+            (Some(h), Some(e)) => {
+                if h.as_sat_per_vb() >= e.as_sat_per_vb() {
+                    h
+                } else {
+                    e
+                }
+            }
+            (Some(rate), None) | (None, Some(rate)) => rate,
+            (None, None) => return Err(FeeError::NotAvailable(target_blocks).into()),
+        };
+
+        Ok(FeeSuggestion {
+            target_blocks,
+            fee_rate,
+            historical,
+            estimated,
+        })
+    }
+
+    /// Get the fee histogram of the mempool and the fee rates needed to confirm within the
+    /// target blocks of the priorities
+    ///
+    /// The histogram is from the mempool.space fee provider or, if not set, from the electrum
+    /// server (`mempool.get_fee_histogram`) or the block explorer.
+    pub async fn get_mempool_snapshot(&self) -> Result<MempoolSnapshot, Error> {
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+        let fee_histogram: Vec<(f64, u64)> = match self.config.fee_provider().await {
+            FeeProvider::MempoolSpace { url } => {
+                fee::mempool_space_fee_histogram(&url, proxy).await?
+            }
+            _ => match self.config.electrum_endpoint().await {
+                Ok(endpoint) => ElectrumFeeEstimator::new(endpoint, proxy).fee_histogram()?,
+                Err(_) => {
+                    let url: Url = self.config.block_explorer().await?;
+                    fee::mempool_space_fee_histogram(&url, proxy).await?
+                }
+            },
+        };
+
+        let targets: BTreeMap<Priority, BdkFeeRate> =
+            [Priority::High, Priority::Medium, Priority::Low]
+                .into_iter()
+                .map(|priority| {
+                    let rate: f64 =
+                        fee::fee_rate_from_histogram(&fee_histogram, priority.target_blocks());
+                    (priority, BdkFeeRate::from_sat_per_vb(rate as f32))
+                })
+                .collect();
+
+        Ok(MempoolSnapshot {
+            vsize: fee_histogram.iter().map(|(_, vsize)| vsize).sum(),
+            fee_histogram,
+            targets,
+            timestamp: Timestamp::now(),
+        })
     }
 
     /// Get keychain name
@@ -367,12 +593,45 @@ impl SmartVaults {
     pub async fn force_full_timechain_sync(&self) -> Result<(), Error> {
         let endpoint = self.config.electrum_endpoint().await?;
         let proxy = self.config.proxy().await.ok();
+        let max_concurrent: usize = self.config.max_concurrent_syncs().await;
         self.manager
-            .full_sync_all(endpoint, proxy, true, None)
+            .full_sync_all(
+                endpoint,
+                proxy,
+                true,
+                max_concurrent,
+                Some(self.sync_channel.clone()),
+            )
             .await?;
         Ok(())
     }
 
+    /// Reconcile the in-memory storage with the nostr database and report the inconsistencies
+    ///
+    /// Less invasive than [`SmartVaults::clear_cache`]: nothing is re-downloaded.
+    pub async fn check_storage_consistency(&self) -> Result<ConsistencyReport, Error> {
+        self.storage.check_consistency().await
+    }
+
+    /// Reset the cache of a single vault
+    ///
+    /// Wallet, proposals, approvals and labels of the vault are removed and rebuilt from the events
+    /// already stored in the nostr database, without re-downloading the relays history.
+    /// The wallet is synced again with the timechain at the next sync.
+    pub async fn reset_vault_cache(&self, vault_id: EventId) -> Result<(), Error> {
+        let InternalPolicy { policy, .. } = self.storage.vault(&vault_id).await?;
+
+        let reprocessed: usize = self.storage.reset_vault(&vault_id).await?;
+        tracing::info!("Re-processed {reprocessed} events for vault {vault_id}");
+
+        self.manager.reset_policy(vault_id, policy).await?;
+        if self.db.get_pinned_vaults().await?.contains(&vault_id) {
+            self.manager.set_pinned(vault_id, true).await?;
+        }
+
+        Ok(())
+    }
+
     /// Clear cache
     pub async fn clear_cache(&self) -> Result<(), Error> {
         let mut notifications = self.client.notifications();
@@ -438,11 +697,7 @@ impl SmartVaults {
 
         if self.client.add_relay_with_opts(url.as_str(), opts).await? {
             let relay = self.client.relay(&url).await?;
-            let last_sync: Timestamp = match self.db.get_last_relay_sync(url.clone()).await {
-                Ok(ts) => ts,
-                Err(_) => Timestamp::from(0),
-            };
-            let filters: Vec<Filter> = self.sync_filters(last_sync).await;
+            let filters: Vec<Filter> = self.relay_sync_filters(&url).await;
             relay
                 .subscribe(
                     filters,
@@ -461,6 +716,15 @@ impl SmartVaults {
                 })?;
             }
 
+            // Check if the relay requires a payment (or an admission)
+            let this = self.clone();
+            let relay_url = url.clone();
+            thread::spawn(async move {
+                if let Err(e) = this.check_relay_admission(relay_url.clone()).await {
+                    tracing::warn!("Impossible to check admission of {relay_url}: {e}");
+                }
+            })?;
+
             if let Err(e) = self.rebroadcast_to(url.clone()).await {
                 tracing::error!("Impossible to rebroadcast events to {url}: {e}");
             }
@@ -470,10 +734,14 @@ impl SmartVaults {
     }
 
     /// Save relay list (NIP65)
+    ///
+    /// The relays pinned to vaults are not included.
     pub async fn save_relay_list(&self) -> Result<EventId, Error> {
         let relays = self.client.relays().await;
+        let pinned: HashSet<Url> = self.storage.pinned_relays().await;
         let list = relays
             .into_keys()
+            .filter(|url| !pinned.contains(url))
             .map(|url| (UncheckedUrl::from(url), None));
         let event = EventBuilder::relay_list(list);
         Ok(self.client.send_event_builder(event).await?)
@@ -483,6 +751,8 @@ impl SmartVaults {
     pub fn default_relays(&self) -> Vec<String> {
         match self.network {
             Network::Bitcoin => MAINNET_RELAYS.into_iter().map(|r| r.to_string()).collect(),
+            Network::Signet => SIGNET_RELAYS.into_iter().map(|r| r.to_string()).collect(),
+            Network::Regtest => REGTEST_RELAYS.into_iter().map(|r| r.to_string()).collect(),
             _ => TESTNET_RELAYS.into_iter().map(|r| r.to_string()).collect(),
         }
     }
@@ -514,6 +784,9 @@ impl SmartVaults {
         // Restore Nostr Connect Session relays
         self.load_nostr_connect_relays().await?;
 
+        // Restore vault pinned relays
+        self.load_vault_relays().await?;
+
         Ok(())
     }
 
@@ -574,7 +847,18 @@ impl SmartVaults {
         Ok(self.client.relay(url).await?)
     }
 
+    /// Save a checkpoint and shutdown the client
+    ///
+    /// The checkpoint allows to resume faster on next open, skipping the full reconciliation of already processed events.
+    /// The own events not yet seen on any relay are saved to the outbox and sent again on next open.
     pub async fn shutdown(self) -> Result<(), Error> {
+        if let Err(e) = self.manager.checkpoint().await {
+            tracing::error!("Impossible to save wallets checkpoint: {e}");
+        }
+        if let Err(e) = self.save_outbox().await {
+            tracing::error!("Impossible to save outbox: {e}");
+        }
+        self.db.save_checkpoint(Timestamp::now(), true).await?;
         self.manager.unload_policies().await;
         Ok(self.client.shutdown().await?)
     }
@@ -693,36 +977,105 @@ impl SmartVaults {
         Ok(())
     }
 
-    #[tracing::instrument(skip_all, level = "trace")]
-    pub async fn get_policy_by_id(&self, policy_id: EventId) -> Result<GetPolicy, Error> {
+    /// Timestamp of the last event of the vault (the vault itself or an event referencing it)
+    async fn vault_last_event(&self, vault_id: EventId) -> Result<Option<Timestamp>, Error> {
+        let filters: Vec<Filter> = vec![
+            Filter::new().id(vault_id),
+            Filter::new().event(vault_id).limit(1),
+        ];
+        Ok(self
+            .client
+            .database()
+            .query(filters, Order::Desc)
+            .await?
+            .into_iter()
+            .map(|event| event.created_at)
+            .max())
+    }
+
+    async fn internal_get_policy(
+        &self,
+        policy_id: EventId,
+        policy: Policy,
+        pinned: bool,
+    ) -> Result<GetPolicy, Error> {
+        let last_sync: Timestamp = self.manager.last_sync(policy_id).await?;
+        let last_event: Option<Timestamp> = self.vault_last_event(policy_id).await?;
         Ok(GetPolicy {
             policy_id,
-            policy: self.storage.vault(&policy_id).await?.policy,
+            policy,
             balance: self.manager.get_balance(policy_id).await?,
-            last_sync: self.manager.last_sync(policy_id).await?,
+            last_sync,
+            last_event,
+            stale: GetPolicy::is_stale(last_sync, last_event, Timestamp::now()),
+            pinned,
         })
     }
 
+    #[tracing::instrument(skip_all, level = "trace")]
+    pub async fn get_policy_by_id(&self, policy_id: EventId) -> Result<GetPolicy, Error> {
+        let pinned: HashSet<EventId> = self.db.get_pinned_vaults().await?;
+        let InternalPolicy { policy, .. } = self.storage.vault(&policy_id).await?;
+        self.internal_get_policy(policy_id, policy, pinned.contains(&policy_id))
+            .await
+    }
+
+    /// Pin vault
+    ///
+    /// Pinned vaults are listed first, eagerly loaded at startup and synced more frequently.
+    pub async fn pin_vault(&self, policy_id: EventId) -> Result<(), Error> {
+        self.storage.vault(&policy_id).await?;
+        self.db.pin_vault(policy_id).await?;
+        self.manager.set_pinned(policy_id, true).await?;
+        self.sync_app_settings().await;
+        Ok(())
+    }
+
+    /// Unpin vault
+    pub async fn unpin_vault(&self, policy_id: EventId) -> Result<(), Error> {
+        self.db.unpin_vault(policy_id).await?;
+        if let Err(e) = self.manager.set_pinned(policy_id, false).await {
+            tracing::warn!("Impossible to unpin wallet {policy_id}: {e}");
+        }
+        self.sync_app_settings().await;
+        Ok(())
+    }
+
+    /// Get pinned vaults IDs
+    pub async fn pinned_vaults(&self) -> Result<HashSet<EventId>, Error> {
+        Ok(self.db.get_pinned_vaults().await?)
+    }
+
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn get_proposal_by_id(&self, proposal_id: EventId) -> Result<GetProposal, Error> {
+        let internal: InternalProposal = self.storage.proposal(&proposal_id).await?;
+        let issues: Vec<ProposalIssue> = self.proposal_issues(proposal_id, &internal).await;
         let InternalProposal {
             policy_id,
             proposal,
             timestamp,
-        } = self.storage.proposal(&proposal_id).await?;
+            replaces,
+            diff,
+            expires_at,
+            ..
+        } = internal;
         let approvals = self
             .storage
             .approvals()
             .await
             .into_iter()
-            .filter(|(_, a)| a.proposal_id == proposal_id)
+            .filter(|(_, a)| a.proposal_id == proposal_id && a.commits_to(&proposal))
             .map(|(_, a)| a.approval);
         Ok(GetProposal {
             proposal_id,
             policy_id,
             signed: proposal.finalize(approvals, self.network).is_ok(),
+            issues,
             proposal,
             timestamp,
+            replaces,
+            diff,
+            expires_at,
         })
     }
 
@@ -774,6 +1127,7 @@ impl SmartVaults {
 
             // Unload policy
             self.manager.unload_policy(policy_id).await?;
+            self.db.unpin_vault(policy_id).await?;
 
             Ok(())
         } else {
@@ -809,6 +1163,14 @@ impl SmartVaults {
             // let events = self.client.get_events_of(vec![filter], timeout).await?;
 
             tags.push(Tag::event(proposal_id));
+
+            // Delete also the parts of the proposal
+            tags.extend(
+                chunk::chunk_ids(&proposal_event)
+                    .into_iter()
+                    .map(Tag::event),
+            );
+
             // let mut ids: Vec<EventId> = vec![proposal_id];
             //
             // for event in events.into_iter() {
@@ -828,6 +1190,37 @@ impl SmartVaults {
         }
     }
 
+    /// Delete the expired proposals (see [`SpendOptions::expires_at`])
+    ///
+    /// Any member can publish the deletion, since the proposals are signed with the shared key.
+    pub(crate) async fn delete_expired_proposals(&self) {
+        let expired: Vec<EventId> = self
+            .storage
+            .proposals()
+            .await
+            .into_iter()
+            .filter(|(_, p)| p.is_expired())
+            .map(|(proposal_id, _)| proposal_id)
+            .collect();
+
+        for proposal_id in expired.iter() {
+            tracing::info!("Proposal {proposal_id} expired");
+            if let Err(e) = self.delete_proposal_by_id(*proposal_id).await {
+                tracing::warn!(
+                    "Impossible to publish deletion of expired proposal {proposal_id}: {e}"
+                );
+                // Unfreeze the UTXOs anyway
+                self.storage.delete_proposal(proposal_id).await;
+            }
+        }
+
+        if !expired.is_empty() {
+            let _ = self
+                .sync_channel
+                .send(Message::EventHandled(EventHandled::EventDeletion));
+        }
+    }
+
     pub async fn delete_completed_proposal_by_id(
         &self,
         completed_proposal_id: EventId,
@@ -876,15 +1269,14 @@ impl SmartVaults {
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn get_policies(&self) -> Result<Vec<GetPolicy>, Error> {
         let items = self.storage.vaults().await;
+        let pinned: HashSet<EventId> = self.db.get_pinned_vaults().await?;
         let mut policies: Vec<GetPolicy> = Vec::with_capacity(items.len());
 
         for (id, internal) in items.into_iter() {
-            policies.push(GetPolicy {
-                policy_id: id,
-                policy: internal.policy,
-                balance: self.manager.get_balance(id).await?,
-                last_sync: self.manager.last_sync(id).await?,
-            });
+            policies.push(
+                self.internal_get_policy(id, internal.policy, pinned.contains(&id))
+                    .await?,
+            );
         }
 
         policies.sort();
@@ -892,24 +1284,102 @@ impl SmartVaults {
         Ok(policies)
     }
 
+    /// Get compact info of all vaults in a single call
+    ///
+    /// Wallets not loaded yet are not loaded by this method (balance will be empty).
+    #[tracing::instrument(skip_all, level = "trace")]
+    pub async fn get_vault_summaries(&self) -> Result<Vec<GetVaultSummary>, Error> {
+        let vaults = self.storage.vaults().await;
+        let pinned: HashSet<EventId> = self.db.get_pinned_vaults().await?;
+        let wallets: HashMap<EventId, SmartVaultsWallet> = self.manager.loaded_wallets().await;
+
+        let mut pending_proposals: HashMap<EventId, usize> = HashMap::new();
+        let mut last_activity: HashMap<EventId, Timestamp> = HashMap::new();
+
+        for InternalProposal {
+            policy_id,
+            timestamp,
+            ..
+        } in self
+            .storage
+            .proposals()
+            .await
+            .into_values()
+            .filter(|p| !p.is_expired())
+        {
+            *pending_proposals.entry(policy_id).or_default() += 1;
+            let last = last_activity.entry(policy_id).or_insert(timestamp);
+            *last = (*last).max(timestamp);
+        }
+
+        for CompletedProposalIndex {
+            policy_id,
+            timestamp,
+            ..
+        } in self.storage.completed_proposals_index().await.into_values()
+        {
+            let last = last_activity.entry(policy_id).or_insert(timestamp);
+            *last = (*last).max(timestamp);
+        }
+
+        let mut summaries: Vec<GetVaultSummary> = Vec::with_capacity(vaults.len());
+        for (policy_id, InternalPolicy { policy, .. }) in vaults.into_iter() {
+            let (balance, sync_status) = match wallets.get(&policy_id) {
+                Some(wallet) => {
+                    let last_sync: Timestamp = wallet.last_sync();
+                    let status = if wallet.is_syncing() {
+                        VaultSyncStatus::Syncing
+                    } else if last_sync == Timestamp::from(0) {
+                        VaultSyncStatus::NeverSynced
+                    } else {
+                        VaultSyncStatus::Synced(last_sync)
+                    };
+                    (wallet.get_balance().await, status)
+                }
+                None => (Balance::default(), VaultSyncStatus::NotLoaded),
+            };
+
+            summaries.push(GetVaultSummary {
+                policy_id,
+                name: policy.name(),
+                balance,
+                pending_proposals: pending_proposals
+                    .get(&policy_id)
+                    .copied()
+                    .unwrap_or_default(),
+                last_activity: last_activity.get(&policy_id).copied(),
+                sync_status,
+                pinned: pinned.contains(&policy_id),
+            });
+        }
+
+        summaries.sort();
+
+        Ok(summaries)
+    }
+
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn get_proposals(&self) -> Result<Vec<GetProposal>, Error> {
         let proposals = self.storage.proposals().await;
         let mut list = Vec::with_capacity(proposals.len());
-        for (proposal_id, p) in proposals.into_iter() {
+        for (proposal_id, p) in proposals.into_iter().filter(|(_, p)| !p.is_expired()) {
             let approvals = self
                 .storage
                 .approvals()
                 .await
                 .into_iter()
-                .filter(|(_, a)| a.proposal_id == proposal_id)
+                .filter(|(_, a)| a.proposal_id == proposal_id && a.commits_to(&p.proposal))
                 .map(|(_, a)| a.approval);
             list.push(GetProposal {
                 proposal_id,
                 policy_id: p.policy_id,
                 signed: p.proposal.finalize(approvals, self.network).is_ok(),
+                issues: self.proposal_issues(proposal_id, &p).await,
                 proposal: p.proposal,
                 timestamp: p.timestamp,
+                replaces: p.replaces,
+                diff: p.diff,
+                expires_at: p.expires_at,
             });
         }
         list.sort();
@@ -925,21 +1395,25 @@ impl SmartVaults {
         let mut list = Vec::with_capacity(proposals.len());
         for (proposal_id, p) in proposals
             .into_iter()
-            .filter(|(_, p)| p.policy_id == policy_id)
+            .filter(|(_, p)| p.policy_id == policy_id && !p.is_expired())
         {
             let approvals = self
                 .storage
                 .approvals()
                 .await
                 .into_iter()
-                .filter(|(_, a)| a.proposal_id == proposal_id)
+                .filter(|(_, a)| a.proposal_id == proposal_id && a.commits_to(&p.proposal))
                 .map(|(_, a)| a.approval);
             list.push(GetProposal {
                 proposal_id,
                 policy_id: p.policy_id,
                 signed: p.proposal.finalize(approvals, self.network).is_ok(),
+                issues: self.proposal_issues(proposal_id, &p).await,
                 proposal: p.proposal,
                 timestamp: p.timestamp,
+                replaces: p.replaces,
+                diff: p.diff,
+                expires_at: p.expires_at,
             });
         }
         list.sort();
@@ -951,31 +1425,84 @@ impl SmartVaults {
         &self,
         proposal_id: EventId,
     ) -> Result<Vec<GetApproval>, Error> {
+        let proposal: Option<Proposal> = self
+            .storage
+            .proposal(&proposal_id)
+            .await
+            .ok()
+            .map(|p| p.proposal);
+        let now: Timestamp = Timestamp::now();
+
         let mut list = Vec::new();
         let approvals = self.storage.approvals().await;
-        for (
-            approval_id,
-            InternalApproval {
+        for (approval_id, internal) in approvals
+            .into_iter()
+            .filter(|(_, a)| a.proposal_id == proposal_id)
+        {
+            let matching: bool = match &proposal {
+                Some(proposal) => internal.commits_to(proposal),
+                None => false,
+            };
+            let InternalApproval {
                 public_key,
                 approval,
                 timestamp,
+                velocity_override,
                 ..
-            },
-        ) in approvals
-            .into_iter()
-            .filter(|(_, a)| a.proposal_id == proposal_id)
-        {
+            } = internal;
+            let expired: bool = timestamp.add(APPROVED_PROPOSAL_EXPIRATION) < now;
             list.push(GetApproval {
                 approval_id,
                 user: self.client.database().profile(public_key).await?,
+                signers: approval.signers(),
+                valid: !expired && matching,
                 approved_proposal: approval,
                 timestamp,
+                velocity_override,
             });
         }
         list.sort();
         Ok(list)
     }
 
+    /// Get quorum progress of a pending proposal
+    pub async fn get_proposal_status(
+        &self,
+        proposal_id: EventId,
+    ) -> Result<GetProposalStatus, Error> {
+        let InternalProposal {
+            policy_id,
+            proposal,
+            ..
+        } = self.storage.proposal(&proposal_id).await?;
+
+        let approvals: Vec<ApprovedProposal> = self
+            .get_approvals_by_proposal_id(proposal_id)
+            .await?
+            .into_iter()
+            .filter(|a| a.valid)
+            .map(|a| a.approved_proposal)
+            .collect();
+
+        let signed: BTreeSet<Fingerprint> = approvals.iter().flat_map(|a| a.signers()).collect();
+        let missing: BTreeSet<Fingerprint> = proposal
+            .involved_signers()
+            .difference(&signed)
+            .copied()
+            .collect();
+        let ready: bool =
+            !approvals.is_empty() && proposal.finalize(approvals.clone(), self.network).is_ok();
+
+        Ok(GetProposalStatus {
+            proposal_id,
+            policy_id,
+            approvals: approvals.len(),
+            signed,
+            missing,
+            ready,
+        })
+    }
+
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn get_completed_proposals(&self) -> Result<Vec<GetCompletedProposal>, Error> {
         let mut list: Vec<GetCompletedProposal> = self
@@ -1019,6 +1546,11 @@ impl SmartVaults {
             return Err(Error::NotEnoughPublicKeys);
         }
 
+        // Detect tampered descriptors
+        if descriptor.as_ref().contains('#') {
+            descriptor::verify_checksum(descriptor.as_ref())?;
+        }
+
         // Generate a shared key
         let shared_key = Keys::generate();
         let policy = Policy::from_desc_or_policy(name, description, descriptor, self.network)?;
@@ -1054,12 +1586,30 @@ impl SmartVaults {
             )
             .await;
 
+        self.check_vault_compromised_keys(policy_id, &policy).await;
+
         // Load policy
         self.manager.load_policy(policy_id, policy).await?;
 
         Ok(policy_id)
     }
 
+    /// Compare a descriptor (i.e. from a backup) with the one of a vault
+    pub async fn compare_vault_descriptor<S>(
+        &self,
+        policy_id: EventId,
+        descriptor: S,
+    ) -> Result<DescriptorDiff, Error>
+    where
+        S: AsRef<str>,
+    {
+        let InternalPolicy { policy, .. } = self.storage.vault(&policy_id).await?;
+        Ok(descriptor::diff(
+            policy.as_descriptor().to_string(),
+            descriptor,
+        )?)
+    }
+
     pub async fn save_policy_from_template<S>(
         &self,
         name: S,
@@ -1091,7 +1641,7 @@ impl SmartVaults {
     ) -> Result<Option<usize>, Error> {
         let mut frozen_utxos: Option<Vec<OutPoint>> = None;
         if !skip_frozen_utxos {
-            let set: HashSet<OutPoint> = self.storage.get_frozen_utxos(&policy_id).await;
+            let set: HashSet<OutPoint> = self.get_frozen_utxos(policy_id).await?;
             frozen_utxos = Some(
                 self.manager
                     .get_utxos(policy_id)
@@ -1125,25 +1675,173 @@ impl SmartVaults {
         S: Into<String>,
     {
         let description: &str = &description.into();
+        let fee_rate: BdkFeeRate = self.calculate_fee_rate(fee_rate).await?;
+        let frozen_utxos: Option<Vec<OutPoint>> =
+            self.frozen_utxos(policy_id, skip_frozen_utxos).await?;
+
+        // Build spending proposal
+        let proposal: Proposal = self
+            .manager
+            .spend(
+                policy_id,
+                address,
+                amount,
+                description,
+                fee_rate,
+                utxos,
+                frozen_utxos,
+                policy_path,
+                TxOptions::default(),
+            )
+            .await?;
+
+        self.publish_spending_proposal(policy_id, proposal, None, None)
+            .await
+    }
+
+    /// Make a spending proposal paying multiple recipients in a single transaction
+    ///
+    /// The recipient labels are saved as address labels of the vault.
+    pub async fn spend_many<S>(
+        &self,
+        policy_id: EventId,
+        recipients: Vec<SpendRecipient>,
+        description: S,
+        fee_rate: FeeRate,
+        options: SpendOptions,
+    ) -> Result<GetProposal, Error>
+    where
+        S: Into<String>,
+    {
+        let fee_rate: BdkFeeRate = self.calculate_fee_rate(fee_rate).await?;
+        let frozen_utxos: Option<Vec<OutPoint>> =
+            self.unspendable_utxos(policy_id, &options).await?;
+
+        let labels: Vec<Label> = recipients
+            .iter()
+            .filter_map(|r| {
+                r.label
+                    .as_ref()
+                    .map(|text| Label::new(LabelData::Address(r.address.clone()), text))
+            })
+            .collect();
+
+        // Build spending proposal
+        let proposal: Proposal = self
+            .manager
+            .spend_many(
+                policy_id,
+                recipients.into_iter().map(Recipient::from).collect(),
+                description,
+                fee_rate,
+                options.utxos,
+                frozen_utxos,
+                options.policy_path,
+                options.op_return,
+                options.tx_options,
+            )
+            .await?
+            .with_memo(options.memo);
+
+        let proposal: GetProposal = self
+            .publish_spending_proposal(policy_id, proposal, None, options.expires_at)
+            .await?;
+
+        for label in labels.into_iter() {
+            if let Err(e) = self.save_label(policy_id, label).await {
+                tracing::error!("Impossible to save recipient label: {e}");
+            }
+        }
+
+        Ok(proposal)
+    }
+
+    /// Build a spending transaction without creating, persisting or publishing the proposal
+    ///
+    /// Useful to show a live preview while the user is editing the spending details.
+    pub async fn preview_spend(
+        &self,
+        vault_id: EventId,
+        address: Address<NetworkUnchecked>,
+        amount: Amount,
+        fee_rate: FeeRate,
+        options: SpendOptions,
+    ) -> Result<SpendPreview, Error> {
+        let fee_rate: BdkFeeRate = self.calculate_fee_rate(fee_rate).await?;
+        let frozen_utxos: Option<Vec<OutPoint>> =
+            self.unspendable_utxos(vault_id, &options).await?;
+        Ok(self
+            .manager
+            .preview_spend(
+                vault_id,
+                address,
+                amount,
+                fee_rate,
+                options.utxos,
+                frozen_utxos,
+                options.policy_path,
+            )
+            .await?)
+    }
 
-        // Check and calculate fee rate
+    /// Build a batch spending transaction without creating, persisting or publishing the proposal
+    pub async fn preview_spend_many(
+        &self,
+        vault_id: EventId,
+        recipients: Vec<SpendRecipient>,
+        fee_rate: FeeRate,
+        options: SpendOptions,
+    ) -> Result<SpendPreview, Error> {
+        let fee_rate: BdkFeeRate = self.calculate_fee_rate(fee_rate).await?;
+        let frozen_utxos: Option<Vec<OutPoint>> =
+            self.unspendable_utxos(vault_id, &options).await?;
+        Ok(self
+            .manager
+            .preview_spend_many(
+                vault_id,
+                recipients.into_iter().map(Recipient::from).collect(),
+                fee_rate,
+                options.utxos,
+                frozen_utxos,
+                options.policy_path,
+                options.op_return,
+                options.tx_options,
+            )
+            .await?)
+    }
+
+    /// Check and calculate fee rate
+    async fn calculate_fee_rate(&self, fee_rate: FeeRate) -> Result<BdkFeeRate, Error> {
         if !fee_rate.is_valid() {
             return Err(Error::InvalidFeeRate);
         }
 
-        let fee_rate: BdkFeeRate = match fee_rate {
+        Ok(match fee_rate {
             FeeRate::Priority(priority) => {
-                let blockchain = self.blockchain().await?;
-                let btc_per_kvb: f32 =
-                    blockchain.estimate_fee(priority.target_blocks() as usize)? as f32;
-                BdkFeeRate::from_btc_per_kvb(btc_per_kvb)
+                let estimator = self.fee_estimator().await?;
+                match estimator.estimate_fee(priority.target_blocks()).await {
+                    Ok(rate) => rate,
+                    // Fee estimation is usually not available on signet and regtest
+                    Err(e) if matches!(self.network, Network::Signet | Network::Regtest) => {
+                        tracing::warn!("Fee estimation not available, using min relay fee: {e}");
+                        BdkFeeRate::from_sat_per_vb(1.0)
+                    }
+                    Err(e) => return Err(e.into()),
+                }
             }
             FeeRate::Rate(rate) => BdkFeeRate::from_sat_per_vb(rate),
-        };
+        })
+    }
 
+    /// Get UTXOs frozen by other proposals or by the user (`None` if `skip_frozen_utxos` is `true`)
+    async fn frozen_utxos(
+        &self,
+        policy_id: EventId,
+        skip_frozen_utxos: bool,
+    ) -> Result<Option<Vec<OutPoint>>, Error> {
         let mut frozen_utxos: Option<Vec<OutPoint>> = None;
         if !skip_frozen_utxos {
-            let set: HashSet<OutPoint> = self.storage.get_frozen_utxos(&policy_id).await;
+            let set: HashSet<OutPoint> = self.get_frozen_utxos(policy_id).await?;
             frozen_utxos = Some(
                 self.manager
                     .get_utxos(policy_id)
@@ -1154,32 +1852,112 @@ impl SmartVaults {
                     .collect(),
             );
         }
+        Ok(frozen_utxos)
+    }
 
-        // Build spending proposal
-        let proposal: Proposal = self
-            .manager
-            .spend(
-                policy_id,
-                address,
-                amount,
-                description,
-                fee_rate,
-                utxos,
-                frozen_utxos,
-                policy_path,
-            )
+    /// Get the UTXOs that can't be spent with the `options`
+    ///
+    /// The UTXOs frozen by other proposals (see [`SmartVaults::frozen_utxos`]), the excluded ones
+    /// and, if `avoid_address_reuse` is set, the ones sharing the address with other UTXOs.
+    async fn unspendable_utxos(
+        &self,
+        policy_id: EventId,
+        options: &SpendOptions,
+    ) -> Result<Option<Vec<OutPoint>>, Error> {
+        let frozen_utxos: Option<Vec<OutPoint>> = self
+            .frozen_utxos(policy_id, options.skip_frozen_utxos)
             .await?;
+        if options.excluded_utxos.is_empty() && !options.avoid_address_reuse {
+            return Ok(frozen_utxos);
+        }
+
+        let mut unspendable: HashSet<OutPoint> =
+            frozen_utxos.unwrap_or_default().into_iter().collect();
+        unspendable.extend(options.excluded_utxos.iter().copied());
+
+        if options.avoid_address_reuse {
+            let utxos = self.manager.get_utxos(policy_id).await?;
+            let mut counter: HashMap<&ScriptBuf, usize> = HashMap::new();
+            for utxo in utxos.iter() {
+                *counter.entry(&utxo.txout.script_pubkey).or_default() += 1;
+            }
+            unspendable.extend(
+                utxos
+                    .iter()
+                    .filter(|utxo| {
+                        counter
+                            .get(&utxo.txout.script_pubkey)
+                            .copied()
+                            .unwrap_or_default()
+                            > 1
+                    })
+                    .map(|utxo| utxo.outpoint),
+            );
+        }
+
+        Ok(Some(unspendable.into_iter().collect()))
+    }
+
+    /// Send the parts (if any) and then the main event
+    ///
+    /// Return the ID of the main event (the last one).
+    async fn send_chunked_events(&self, events: Vec<Event>) -> Result<EventId, Error> {
+        let mut event_id: Option<EventId> = None;
+        for event in events.into_iter() {
+            event_id = Some(self.client.send_event(event).await?);
+        }
+        event_id.ok_or(Error::NotFound)
+    }
+
+    /// Publish a spending proposal (replacing the `replaces` one, if any)
+    ///
+    /// The proposal is automatically deleted after `expires_at`, if set.
+    async fn publish_spending_proposal(
+        &self,
+        policy_id: EventId,
+        proposal: Proposal,
+        replaces: Option<EventId>,
+        expires_at: Option<Timestamp>,
+    ) -> Result<GetProposal, Error> {
+        if let Some(expires_at) = expires_at {
+            if expires_at <= Timestamp::now() {
+                return Err(Error::InvalidProposalExpiration);
+            }
+        }
 
         if let Proposal::Spending { psbt, .. } = &proposal {
+            self.check_proposal_inputs(psbt).await?;
+            self.verify_whitelist(policy_id, &proposal).await?;
+
             // Get shared keys
             let shared_key: Keys = self.storage.shared_key(&policy_id).await?;
 
             // Compose the event
             let InternalPolicy { public_keys, .. } = self.storage.vault(&policy_id).await?;
-            let event: Event =
-                EventBuilder::proposal(&shared_key, policy_id, &proposal, &public_keys)?;
-            let timestamp = event.created_at;
-            let proposal_id = self.client.send_event(event).await?;
+            let events: Vec<Event> = match replaces {
+                Some(replaces) => EventBuilder::replacement_proposal(
+                    &shared_key,
+                    policy_id,
+                    replaces,
+                    &proposal,
+                    expires_at,
+                    &public_keys,
+                )?,
+                None => EventBuilder::proposal(
+                    &shared_key,
+                    policy_id,
+                    &proposal,
+                    expires_at,
+                    &public_keys,
+                )?,
+            };
+            self.warn_incompatible_cosigners(policy_id, events.len() > 1)
+                .await;
+            let timestamp = events
+                .last()
+                .map(|e| e.created_at)
+                .unwrap_or_else(Timestamp::now);
+            let proposal_id = self.send_chunked_events(events).await?;
 
             // Send DM msg
             // TODO: send withoud wait for OK
@@ -1197,16 +1975,20 @@ impl SmartVaults {
             } */
 
             // Index proposal
-            self.storage
-                .save_proposal(
-                    proposal_id,
-                    InternalProposal {
-                        policy_id,
-                        proposal: proposal.clone(),
-                        timestamp,
-                    },
-                )
+            let internal = InternalProposal {
+                policy_id,
+                proposal: proposal.clone(),
+                timestamp,
+                replaces,
+                diff: None,
+                expires_at,
+                issues: None,
+            };
+            let diff: Option<ProposalDiff> = self
+                .storage
+                .save_proposal(proposal_id, internal.clone())
                 .await;
+            let issues: Vec<ProposalIssue> = self.proposal_issues(proposal_id, &internal).await;
 
             // Froze UTXOs
             self.storage
@@ -1226,6 +2008,10 @@ impl SmartVaults {
                 proposal,
                 signed: false,
                 timestamp,
+                issues,
+                replaces,
+                diff,
+                expires_at,
             })
         } else {
             Err(Error::UnexpectedProposal)
@@ -1277,14 +2063,61 @@ impl SmartVaults {
         )
     } */
 
+    /// Approve a proposal with the keychain seed
+    ///
+    /// The `pin` is required if an approval PIN is set on this device (see [`SmartVaults::set_approval_pin`]).
     pub async fn approve<T>(
         &self,
         password: T,
+        pin: Option<&str>,
         proposal_id: EventId,
     ) -> Result<(EventId, ApprovedProposal), Error>
     where
         T: AsRef<[u8]>,
     {
+        self.verify_approval_pin(pin)?;
+        let seed: Seed = self.keechain.read().seed(password)?;
+        self.approve_with_seed(&seed, proposal_id, false).await
+    }
+
+    /// Approve a batch of proposals unlocking the keychain only once
+    ///
+    /// The proposals are signed sequentially: a failure doesn't stop the next approvals and is reported
+    /// in the [`BulkApprovalResult`] of the proposal. Return an error only if the password or the PIN are wrong.
+    pub async fn approve_many<T>(
+        &self,
+        proposal_ids: Vec<EventId>,
+        password: T,
+        pin: Option<&str>,
+    ) -> Result<Vec<BulkApprovalResult>, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.verify_approval_pin(pin)?;
+        let seed: Seed = self.keechain.read().seed(password)?;
+
+        let mut results: Vec<BulkApprovalResult> = Vec::with_capacity(proposal_ids.len());
+        for proposal_id in proposal_ids.into_iter() {
+            let result = self.approve_with_seed(&seed, proposal_id, false).await;
+            if let Err(e) = &result {
+                tracing::error!("Impossible to approve proposal {proposal_id}: {e}");
+            }
+            results.push(BulkApprovalResult {
+                proposal_id,
+                approval_id: result.as_ref().ok().map(|(approval_id, ..)| *approval_id),
+                error: result.err().map(|e| e.to_string()),
+            });
+        }
+        Ok(results)
+    }
+
+    /// Approve with the seed, refusing the proposals exceeding the velocity limits if not overridden
+    async fn approve_with_seed(
+        &self,
+        seed: &Seed,
+        proposal_id: EventId,
+        velocity_override: bool,
+    ) -> Result<(EventId, ApprovedProposal), Error> {
         // Get proposal and policy
         let GetProposal {
             policy_id,
@@ -1292,6 +2125,13 @@ impl SmartVaults {
             ..
         } = self.get_proposal_by_id(proposal_id).await?;
 
+        self.verify_approval(
+            policy_id,
+            &proposal,
+            ApprovalPath::Seed { velocity_override },
+        )
+        .await?;
+
         let keys: &Keys = self.keys();
 
         /* // Sign PSBT
@@ -1304,8 +2144,8 @@ impl SmartVaults {
                     .await?,
             },
         ); */
-        let seed: Seed = self.keechain.read().seed(password)?;
-        let approved_proposal = proposal.approve(&seed, Vec::new(), self.network)?;
+        let approved_proposal = proposal.approve(seed, Vec::new(), self.network)?;
+        let txid: Txid = proposal.psbt().unsigned_tx.txid();
 
         // Get shared keys
         let shared_key: Keys = self.storage.shared_key(&policy_id).await?;
@@ -1319,12 +2159,19 @@ impl SmartVaults {
         tags.push(Tag::Expiration(
             Timestamp::now().add(APPROVED_PROPOSAL_EXPIRATION),
         ));
+        tags.push(approval::txid_tag(txid));
+        if velocity_override {
+            tags.push(velocity::override_tag());
+        }
 
-        let event = EventBuilder::new(APPROVED_PROPOSAL_KIND, content, tags).to_event(keys)?;
-        let timestamp = event.created_at;
+        let events: Vec<Event> = chunk::build(keys, APPROVED_PROPOSAL_KIND, content, tags)?;
+        let timestamp = events
+            .last()
+            .map(|e| e.created_at)
+            .unwrap_or_else(Timestamp::now);
 
         // Publish the event
-        let event_id = self.client.send_event(event).await?;
+        let event_id = self.send_chunked_events(events).await?;
 
         // Index approved proposal
         self.storage
@@ -1334,8 +2181,10 @@ impl SmartVaults {
                     proposal_id,
                     policy_id,
                     public_key: keys.public_key(),
+                    txid: Some(txid),
                     approval: approved_proposal.clone(),
                     timestamp,
+                    velocity_override,
                 },
             )
             .await;
@@ -1343,11 +2192,17 @@ impl SmartVaults {
         Ok((event_id, approved_proposal))
     }
 
+    /// Approve with a signed PSBT of the proposal
+    ///
+    /// The `pin` is required if an approval PIN is set on this device (see [`SmartVaults::set_approval_pin`]).
     pub async fn approve_with_signed_psbt(
         &self,
         proposal_id: EventId,
         signed_psbt: PartiallySignedTransaction,
+        pin: Option<&str>,
     ) -> Result<(EventId, ApprovedProposal), Error> {
+        self.verify_approval_pin(pin)?;
+
         let keys: &Keys = self.keys();
 
         // Get proposal and policy
@@ -1357,7 +2212,11 @@ impl SmartVaults {
             ..
         } = self.get_proposal_by_id(proposal_id).await?;
 
+        self.verify_approval(policy_id, &proposal, ApprovalPath::SignedPsbt)
+            .await?;
+
         let approved_proposal = proposal.approve_with_signed_psbt(signed_psbt)?;
+        let txid: Txid = proposal.psbt().unsigned_tx.txid();
 
         // Get shared keys
         let shared_key: Keys = self.storage.shared_key(&policy_id).await?;
@@ -1371,12 +2230,16 @@ impl SmartVaults {
         tags.push(Tag::Expiration(
             Timestamp::now().add(APPROVED_PROPOSAL_EXPIRATION),
         ));
+        tags.push(approval::txid_tag(txid));
 
-        let event = EventBuilder::new(APPROVED_PROPOSAL_KIND, content, tags).to_event(keys)?;
-        let timestamp = event.created_at;
+        let events: Vec<Event> = chunk::build(keys, APPROVED_PROPOSAL_KIND, content, tags)?;
+        let timestamp = events
+            .last()
+            .map(|e| e.created_at)
+            .unwrap_or_else(Timestamp::now);
 
         // Publish the event
-        let event_id = self.client.send_event(event).await?;
+        let event_id = self.send_chunked_events(events).await?;
 
         // Index approved proposal
         self.storage
@@ -1386,8 +2249,10 @@ impl SmartVaults {
                     proposal_id,
                     policy_id,
                     public_key: keys.public_key(),
+                    txid: Some(txid),
                     approval: approved_proposal.clone(),
                     timestamp,
+                    velocity_override: false,
                 },
             )
             .await;
@@ -1395,6 +2260,36 @@ impl SmartVaults {
         Ok((event_id, approved_proposal))
     }
 
+    /// Approve with the PSBT signed by an external (i.e. air-gap) signer
+    ///
+    /// The signed PSBT must be of the proposal transaction and include the signer signatures.
+    /// The proposal is refused like with the seed (velocity and spending limits, whitelist), but
+    /// the limits can't be overridden. The `pin` is required if an approval PIN is set.
+    pub async fn approve_with_external_signer(
+        &self,
+        proposal_id: EventId,
+        signer_id: EventId,
+        signed_psbt: PartiallySignedTransaction,
+        pin: Option<&str>,
+    ) -> Result<(EventId, ApprovedProposal), Error> {
+        self.verify_approval_pin(pin)?;
+
+        let signer: Signer = self.get_signer_by_id(signer_id).await?;
+        let GetProposal { proposal, .. } = self.get_proposal_by_id(proposal_id).await?;
+
+        if signed_psbt.unsigned_tx.txid() != proposal.psbt().unsigned_tx.txid() {
+            return Err(Error::PsbtNotMatchingProposal);
+        }
+
+        let fingerprint: Fingerprint = signer.fingerprint();
+        if !psbt_signers(&signed_psbt).contains(&fingerprint) {
+            return Err(Error::PsbtNotSignedBySigner(fingerprint));
+        }
+
+        self.approve_with_signed_psbt(proposal_id, signed_psbt, pin)
+            .await
+    }
+
     // pub async fn approve_with_hwi_signer(
     // &self,
     // proposal_id: EventId,
@@ -1471,24 +2366,50 @@ impl SmartVaults {
 
     /// Finalize [`Proposal`]
     pub async fn finalize(&self, proposal_id: EventId) -> Result<CompletedProposal, Error> {
+        let (completed_proposal, ..) = self.finalize_with_opts(proposal_id, true).await?;
+        Ok(completed_proposal)
+    }
+
+    /// Finalize proposal
+    ///
+    /// If `broadcast` is `false`, the transaction is NOT broadcasted: the caller is responsible of broadcasting it (i.e. via own node).
+    /// Otherwise, the transaction is broadcasted through all the configured backends (see [`SmartVaults::broadcast_tx`]).
+    pub async fn finalize_with_opts(
+        &self,
+        proposal_id: EventId,
+        broadcast: bool,
+    ) -> Result<(CompletedProposal, Vec<BroadcastResult>), Error> {
         // Get PSBTs
         let GetApprovedProposals {
             policy_id,
             proposal,
             approved_proposals,
+            stale_approvals,
         } = self.storage.approvals_by_proposal_id(&proposal_id).await?;
 
+        if !stale_approvals.is_empty() {
+            if let Err(e) = self
+                .report_stale_approvals(proposal_id, stale_approvals)
+                .await
+            {
+                tracing::error!("Impossible to report stale approvals of {proposal_id}: {e}");
+            }
+        }
+
         let shared_key: Keys = self.storage.shared_key(&policy_id).await?;
         let InternalPolicy { public_keys, .. } = self.storage.vault(&policy_id).await?;
 
+        self.verify_velocity_limits(policy_id, proposal_id, &proposal)
+            .await?;
+
         // Finalize proposal
         let completed_proposal: CompletedProposal =
             proposal.finalize(approved_proposals, self.network)?;
 
         // Broadcast
-        if let CompletedProposal::Spending { tx, .. } = &completed_proposal {
-            let blockchain = self.blockchain().await?;
-            blockchain.transaction_broadcast(tx)?;
+        let mut broadcast_results: Vec<BroadcastResult> = Vec::new();
+        if let (CompletedProposal::Spending { tx, .. }, true) = (&completed_proposal, broadcast) {
+            broadcast_results = self.broadcast_tx(tx).await?;
 
             // Try insert transactions into wallet (without wait for the next sync)
             let txid: Txid = tx.txid();
@@ -1515,16 +2436,20 @@ impl SmartVaults {
         }
 
         // Compose the event
-        let content: String = completed_proposal.encrypt_with_keys(&shared_key)?;
-        let mut tags: Vec<Tag> = public_keys.iter().copied().map(Tag::public_key).collect();
-        tags.push(Tag::event(proposal_id));
-        tags.push(Tag::event(policy_id));
-        let event =
-            EventBuilder::new(COMPLETED_PROPOSAL_KIND, content, tags).to_event(&shared_key)?;
-        let timestamp = event.created_at;
+        let events: Vec<Event> = EventBuilder::completed_proposal(
+            &shared_key,
+            proposal_id,
+            policy_id,
+            &completed_proposal,
+            &public_keys,
+        )?;
+        let timestamp = events
+            .last()
+            .map(|e| e.created_at)
+            .unwrap_or_else(Timestamp::now);
 
         // Publish the event
-        let event_id = self.client.send_event(event).await?;
+        let event_id = self.send_chunked_events(events).await?;
 
         // Delete the proposal
         if let Err(e) = self.delete_proposal_by_id(proposal_id).await {
@@ -1543,7 +2468,20 @@ impl SmartVaults {
             )
             .await;
 
-        Ok(completed_proposal)
+        // Propagate the description to the tx and change labels
+        if let CompletedProposal::Spending {
+            tx, description, ..
+        }
+        | CompletedProposal::KeyAgentPayment {
+            tx, description, ..
+        } = &completed_proposal
+        {
+            if let Err(e) = self.label_finalized_tx(policy_id, tx, description).await {
+                tracing::error!("Impossible to label tx of proposal {proposal_id}: {e}");
+            }
+        }
+
+        Ok((completed_proposal, broadcast_results))
     }
 
     pub async fn new_proof_proposal<S>(
@@ -1564,13 +2502,14 @@ impl SmartVaults {
 
         // Compose the event
         let InternalPolicy { public_keys, .. } = self.storage.vault(&policy_id).await?;
-        let mut tags: Vec<Tag> = public_keys.iter().copied().map(Tag::public_key).collect();
-        tags.push(Tag::event(policy_id));
-        let content = proposal.encrypt_with_keys(&shared_key)?;
         // Publish proposal with `shared_key` so every owner can delete it
-        let event = EventBuilder::new(PROPOSAL_KIND, content, tags).to_event(&shared_key)?;
-        let timestamp = event.created_at;
-        let proposal_id = self.client.send_event(event).await?;
+        let events: Vec<Event> =
+            EventBuilder::proposal(&shared_key, policy_id, &proposal, None, &public_keys)?;
+        let timestamp = events
+            .last()
+            .map(|e| e.created_at)
+            .unwrap_or_else(Timestamp::now);
+        let proposal_id = self.send_chunked_events(events).await?;
 
         // Send DM msg
         // TODO: send withoud wait for OK
@@ -1591,6 +2530,10 @@ impl SmartVaults {
                     policy_id,
                     proposal: proposal.clone(),
                     timestamp,
+                    replaces: None,
+                    diff: None,
+                    expires_at: None,
+                    issues: None,
                 },
             )
             .await;
@@ -1613,6 +2556,33 @@ impl SmartVaults {
         }
     }
 
+    /// Export a completed proof of reserve as a self-contained package
+    ///
+    /// The package can be verified without a Smart Vaults wallet (see [`ProofPackage::verify`]).
+    pub async fn export_proof_by_id(
+        &self,
+        completed_proposal_id: EventId,
+    ) -> Result<ProofPackage, Error> {
+        let GetCompletedProposal { proposal, .. } = self
+            .get_completed_proposal_by_id(completed_proposal_id)
+            .await?;
+        if let CompletedProposal::ProofOfReserve {
+            descriptor,
+            message,
+            psbt,
+        } = proposal
+        {
+            Ok(ProofPackage::new(
+                self.network(),
+                message,
+                Some(descriptor.to_string()),
+                psbt,
+            ))
+        } else {
+            Err(Error::UnexpectedProposal)
+        }
+    }
+
     #[deprecated]
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn get_balance(&self, policy_id: EventId) -> Option<Balance> {
@@ -1629,6 +2599,7 @@ impl SmartVaults {
             self.storage.get_addresses_labels(policy_id).await;
 
         let block_explorer = self.config.block_explorer().await.ok();
+        let mempool_min_fee_rate: Option<BdkFeeRate> = self.mempool_min_fee_rate().await;
 
         let mut list: BTreeSet<GetTransaction> = BTreeSet::new();
 
@@ -1649,9 +2620,14 @@ impl SmartVaults {
                 descriptions.get(&txid).cloned()
             };
 
+            let is_confirmation_verified: bool = self.manager.is_confirmation_verified(&tx).await;
+            let stuck: Option<StuckTransaction> =
+                mempool_min_fee_rate.and_then(|rate| StuckTransaction::check(&tx, rate));
             list.insert(GetTransaction {
                 policy_id,
                 label,
+                is_confirmation_verified,
+                stuck,
                 tx,
                 block_explorer: block_explorer
                     .as_ref()
@@ -1693,10 +2669,19 @@ impl SmartVaults {
 
         let block_explorer = self.config.block_explorer().await.ok();
 
+        let is_confirmation_verified: bool = self.manager.is_confirmation_verified(&tx).await;
+
+        let stuck: Option<StuckTransaction> = match self.mempool_min_fee_rate().await {
+            Some(rate) => StuckTransaction::check(&tx, rate),
+            None => None,
+        };
+
         Ok(GetTransaction {
             policy_id,
             tx,
             label,
+            is_confirmation_verified,
+            stuck,
             block_explorer: block_explorer
                 .as_ref()
                 .map(|url| format!("{url}/tx/{txid}")),
@@ -1762,7 +2747,9 @@ impl SmartVaults {
         let script_labels: HashMap<ScriptBuf, Label> =
             self.storage.get_addresses_labels(policy_id).await;
         let utxo_labels: HashMap<OutPoint, Label> = self.storage.get_utxos_labels(policy_id).await;
-        let frozen_utxos: HashSet<OutPoint> = self.storage.get_frozen_utxos(&policy_id).await;
+        let mut frozen_utxos: HashSet<OutPoint> = self.storage.get_frozen_utxos(&policy_id).await;
+        let user_frozen_utxos: HashSet<OutPoint> = self.db.get_frozen_utxos(policy_id).await?;
+        frozen_utxos.extend(user_frozen_utxos.iter().copied());
 
         // Compose output
         Ok(self
@@ -1776,11 +2763,39 @@ impl SmartVaults {
                     .or_else(|| script_labels.get(&utxo.txout.script_pubkey))
                     .map(|l| l.text()),
                 frozen: frozen_utxos.contains(&utxo.outpoint),
+                user_frozen: user_frozen_utxos.contains(&utxo.outpoint),
                 utxo,
             })
             .collect())
     }
 
+    /// Get the UTXOs frozen by pending proposals or by the user
+    pub async fn get_frozen_utxos(&self, policy_id: EventId) -> Result<HashSet<OutPoint>, Error> {
+        let mut frozen: HashSet<OutPoint> = self.storage.get_frozen_utxos(&policy_id).await;
+        frozen.extend(self.db.get_frozen_utxos(policy_id).await?);
+        Ok(frozen)
+    }
+
+    /// Freeze UTXOs, excluding them from the coin selection until unfrozen
+    pub async fn freeze_utxos<I>(&self, policy_id: EventId, utxos: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = OutPoint>,
+    {
+        // Check if vault exists
+        self.storage.vault(&policy_id).await?;
+        Ok(self.db.freeze_utxos(policy_id, utxos).await?)
+    }
+
+    /// Unfreeze UTXOs frozen with [`SmartVaults::freeze_utxos`]
+    ///
+    /// The UTXOs used by pending proposals remain frozen.
+    pub async fn unfreeze_utxos<I>(&self, policy_id: EventId, utxos: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = OutPoint>,
+    {
+        Ok(self.db.unfreeze_utxos(policy_id, utxos).await?)
+    }
+
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn get_total_balance(&self) -> Result<Balance, Error> {
         let vaults: HashMap<EventId, InternalPolicy> = self.storage.vaults().await;
@@ -1829,6 +2844,23 @@ impl SmartVaults {
         Ok(())
     }
 
+    /// Rebroadcast the events of a vault (the vault and the events referencing it) to connected relays
+    ///
+    /// Return the number of rebroadcasted events.
+    pub async fn rebroadcast_vault_events(&self, vault_id: EventId) -> Result<usize, Error> {
+        let pool = self.client.pool();
+        let events: Vec<Event> = self.vault_events(vault_id).await?;
+        let count: usize = events.len();
+        for event in events.into_iter() {
+            pool.send_msg(
+                ClientMessage::event(event),
+                RelaySendOptions::new().skip_send_confirmation(true),
+            )
+            .await?;
+        }
+        Ok(count)
+    }
+
     pub async fn rebroadcast_to<S>(&self, url: S) -> Result<(), Error>
     where
         S: Into<String>,
@@ -1852,32 +2884,6 @@ impl SmartVaults {
         Ok(())
     }
 
-    pub async fn republish_shared_key_for_policy(&self, policy_id: EventId) -> Result<(), Error> {
-        let keys: &Keys = self.keys();
-        let shared_key: Keys = self.storage.shared_key(&policy_id).await?;
-        let InternalPolicy { public_keys, .. } = self.storage.vault(&policy_id).await?;
-        // Publish the shared key
-        for public_key in public_keys.into_iter() {
-            let encrypted_shared_key = nips::nip04::encrypt(
-                keys.secret_key()?,
-                &public_key,
-                shared_key.secret_key()?.display_secret().to_string(),
-            )?;
-            let event: Event = EventBuilder::new(
-                SHARED_KEY_KIND,
-                encrypted_shared_key,
-                [Tag::event(policy_id), Tag::public_key(public_key)],
-            )
-            .to_event(keys)?;
-            let event_id: EventId = event.id;
-
-            // TODO: use send_batch_event method from nostr-sdk
-            self.client.send_event(event).await?;
-            tracing::info!("Published shared key for {public_key} at event {event_id}");
-        }
-        Ok(())
-    }
-
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn export_policy_backup(&self, policy_id: EventId) -> Result<PolicyBackup, Error> {
         let InternalPolicy {
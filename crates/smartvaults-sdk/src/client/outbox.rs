@@ -0,0 +1,80 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Outbox
+//!
+//! The own events not seen on any relay at shutdown (i.e. sent while offline) are saved with the
+//! checkpoint and sent again on next open.
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{Event, Filter, Timestamp};
+
+use super::{Error, SmartVaults};
+use crate::constants::CHECKPOINT_RESUME_MARGIN;
+
+/// Reconcile the events since the checkpoint minus a margin, for the events created before the
+/// checkpoint but delivered to the relays after it
+pub(super) fn resume_since(checkpoint: Timestamp) -> Timestamp {
+    Timestamp::from(
+        checkpoint
+            .as_u64()
+            .saturating_sub(CHECKPOINT_RESUME_MARGIN.as_secs()),
+    )
+}
+
+impl SmartVaults {
+    /// Save to the outbox the own events, created since the last checkpoint, not seen on any relay
+    pub(super) async fn save_outbox(&self) -> Result<(), Error> {
+        let since: Timestamp = match self.db.get_checkpoint().await {
+            Ok(checkpoint) => resume_since(checkpoint.timestamp),
+            Err(_) => Timestamp::from(0),
+        };
+        let filter: Filter = Filter::new().author(self.keys.public_key()).since(since);
+
+        let database = self.client.database();
+        let mut unsent: Vec<Event> = Vec::new();
+        for event in database.query(vec![filter], Order::Asc).await?.into_iter() {
+            let seen: bool = match database.event_seen_on_relays(event.id).await? {
+                Some(relays) => !relays.is_empty(),
+                None => false,
+            };
+            if !seen {
+                unsent.push(event);
+            }
+        }
+
+        if !unsent.is_empty() {
+            tracing::info!("Saving {} unsent events to outbox", unsent.len());
+            self.db.save_outbox_events(unsent).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send the outbox events, removing the sent ones
+    pub(super) async fn send_outbox(&self) -> Result<(), Error> {
+        for event in self.db.get_outbox_events().await?.into_iter() {
+            let event_id = event.id;
+            match self.client.send_event(event).await {
+                Ok(..) => self.db.delete_outbox_event(event_id).await?,
+                Err(e) => tracing::warn!("Impossible to send outbox event {event_id}: {e}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_since() {
+        let margin = CHECKPOINT_RESUME_MARGIN.as_secs();
+        assert_eq!(
+            resume_since(Timestamp::from(margin + 100)),
+            Timestamp::from(100)
+        );
+        assert_eq!(resume_since(Timestamp::from(10)), Timestamp::from(0));
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Saved payees, synced across the user devices (NIP-78, encrypted to self)
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{Event, EventBuilder, EventId, Filter, Keys, Kind};
+use smartvaults_core::bitcoin::Address;
+use smartvaults_protocol::v1::constants::PAYEES_IDENTIFIER;
+use smartvaults_protocol::v1::{Encryption, Payee, Payees, SmartVaultsEventBuilder};
+
+use super::{Error, SmartVaults};
+
+impl SmartVaults {
+    /// Get the saved payees
+    pub async fn get_payees(&self) -> Result<Payees, Error> {
+        let keys: &Keys = self.keys();
+        let filter = Filter::new()
+            .author(keys.public_key())
+            .kind(Kind::ApplicationSpecificData)
+            .identifier(PAYEES_IDENTIFIER)
+            .limit(1);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+        match events.first() {
+            Some(event) => Ok(Payees::decrypt_with_keys(keys, event.content())?),
+            None => Ok(Payees::default()),
+        }
+    }
+
+    /// Get the payees whose name starts with `prefix` (i.e. to autocomplete the recipient)
+    pub async fn search_payees<S>(&self, prefix: S) -> Result<Vec<Payee>, Error>
+    where
+        S: AsRef<str>,
+    {
+        let payees: Payees = self.get_payees().await?;
+        Ok(payees
+            .search(prefix.as_ref())
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
+    async fn publish_payees(&self, payees: &Payees) -> Result<EventId, Error> {
+        let event: Event = EventBuilder::payees(self.keys(), payees)?;
+        Ok(self.client.send_event(event).await?)
+    }
+
+    /// Add a payee (or replace the one with the same name)
+    ///
+    /// The destination can be an address, a descriptor or an xpub.
+    pub async fn save_payee<S, D>(
+        &self,
+        name: S,
+        destination: D,
+        memo: Option<String>,
+    ) -> Result<EventId, Error>
+    where
+        S: Into<String>,
+        D: AsRef<str>,
+    {
+        let payee: Payee = Payee::new(name, destination, memo)?;
+        payee.address(self.network())?;
+        let mut payees: Payees = self.get_payees().await?;
+        payees.save(payee);
+        self.publish_payees(&payees).await
+    }
+
+    pub async fn remove_payee<S>(&self, name: S) -> Result<EventId, Error>
+    where
+        S: AsRef<str>,
+    {
+        let mut payees: Payees = self.get_payees().await?;
+        if !payees.remove(name.as_ref()) {
+            return Err(Error::PayeeNotFound);
+        }
+        self.publish_payees(&payees).await
+    }
+
+    /// Get the address of the next payment to the payee, without using it
+    pub async fn get_payee_address<S>(&self, name: S) -> Result<Address, Error>
+    where
+        S: AsRef<str>,
+    {
+        let payees: Payees = self.get_payees().await?;
+        let payee: &Payee = payees.get(name.as_ref()).ok_or(Error::PayeeNotFound)?;
+        Ok(payee.address(self.network())?)
+    }
+
+    /// Get the address of the next payment to the payee
+    ///
+    /// For the descriptors (and xpubs), the derivation index is moved forward, so the next
+    /// payment use a fresh address.
+    pub async fn use_payee_address<S>(&self, name: S) -> Result<Address, Error>
+    where
+        S: AsRef<str>,
+    {
+        let mut payees: Payees = self.get_payees().await?;
+        let payee: &mut Payee = payees.get_mut(name.as_ref()).ok_or(Error::PayeeNotFound)?;
+        let address: Address = payee.address(self.network())?;
+        if payee.is_descriptor() {
+            payee.advance();
+            self.publish_payees(&payees).await?;
+        }
+        Ok(address)
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use nostr_sdk::database::{NostrDatabaseExt, Order};
+use nostr_sdk::{Event, EventBuilder, EventId, Filter, Keys};
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::{Address, ScriptBuf};
+use smartvaults_core::{Amount, CompletedProposal, FeeRate};
+use smartvaults_protocol::v1::constants::PAYMENT_REQUEST_KIND;
+use smartvaults_protocol::v1::{Encryption, PaymentRequest, SmartVaultsEventBuilder};
+
+use super::{Error, SmartVaults};
+use crate::storage::{InternalCompletedProposal, InternalPolicy};
+use crate::types::{GetPaymentRequest, GetProposal};
+
+impl SmartVaults {
+    /// Ask the other members of the vault to be paid (i.e. reimbursement)
+    pub async fn create_payment_request<S>(
+        &self,
+        vault_id: EventId,
+        address: Address<NetworkUnchecked>,
+        amount: u64,
+        description: S,
+    ) -> Result<EventId, Error>
+    where
+        S: Into<String>,
+    {
+        // Check address network
+        address.clone().require_network(self.network)?;
+
+        let keys: &Keys = self.keys();
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+        let request = PaymentRequest::new(address, amount, description);
+        let event: Event =
+            EventBuilder::payment_request(keys, &shared_key, vault_id, &request, &public_keys)?;
+        Ok(self.client.send_event(event).await?)
+    }
+
+    /// Check if a proposal (pending or completed) of the vault pays the request
+    async fn is_payment_request_fulfilled(
+        &self,
+        vault_id: EventId,
+        request: &PaymentRequest,
+    ) -> bool {
+        let address = request.address();
+        let amount: u64 = request.amount();
+
+        let pending: bool = self.storage.proposals().await.into_values().any(|p| {
+            p.policy_id == vault_id
+                && p.proposal
+                    .recipients()
+                    .iter()
+                    .any(|r| r.address == address && r.amount == amount)
+        });
+        if pending {
+            return true;
+        }
+
+        let script: ScriptBuf = address.assume_checked().script_pubkey();
+        let ids = self
+            .storage
+            .completed_proposals_index()
+            .await
+            .into_iter()
+            .filter(|(_, i)| i.policy_id == vault_id && i.spending.is_some())
+            .map(|(id, ..)| id);
+        for id in ids {
+            if let Ok(InternalCompletedProposal {
+                proposal: CompletedProposal::Spending { tx, .. },
+                ..
+            }) = self.storage.completed_proposal(&id).await
+            {
+                if tx
+                    .output
+                    .iter()
+                    .any(|o| o.script_pubkey == script && o.value == amount)
+                {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    async fn internal_get_payment_request(
+        &self,
+        event: &Event,
+    ) -> Result<GetPaymentRequest, Error> {
+        let vault_id: EventId = *event.event_ids().next().ok_or(Error::PolicyNotFound)?;
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+
+        if !public_keys.contains(&event.author()) {
+            return Err(Error::PaymentRequestNotFound);
+        }
+
+        let request: PaymentRequest =
+            PaymentRequest::decrypt_with_keys(&shared_key, &event.content)?;
+        Ok(GetPaymentRequest {
+            request_id: event.id,
+            policy_id: vault_id,
+            user: self.client.database().profile(event.author()).await?,
+            fulfilled: self.is_payment_request_fulfilled(vault_id, &request).await,
+            request,
+            timestamp: event.created_at,
+        })
+    }
+
+    /// Get the payment requests published in the vaults, newest first
+    pub async fn get_payment_requests(&self) -> Result<Vec<GetPaymentRequest>, Error> {
+        let filter: Filter = Filter::new().kind(PAYMENT_REQUEST_KIND);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+
+        let mut requests: Vec<GetPaymentRequest> = Vec::with_capacity(events.len());
+        for event in events.into_iter() {
+            match self.internal_get_payment_request(&event).await {
+                Ok(request) => requests.push(request),
+                Err(e) => tracing::warn!("Impossible to get payment request {}: {e}", event.id),
+            }
+        }
+
+        Ok(requests)
+    }
+
+    /// Create a spending proposal that pays the request
+    pub async fn fulfill_payment_request(
+        &self,
+        request_id: EventId,
+        fee_rate: FeeRate,
+    ) -> Result<GetProposal, Error> {
+        let event: Event = self
+            .client
+            .database()
+            .event_by_id(request_id)
+            .await
+            .map_err(|_| Error::PaymentRequestNotFound)?;
+        if event.kind != PAYMENT_REQUEST_KIND {
+            return Err(Error::PaymentRequestNotFound);
+        }
+
+        let GetPaymentRequest {
+            policy_id,
+            request,
+            fulfilled,
+            ..
+        } = self.internal_get_payment_request(&event).await?;
+
+        if fulfilled {
+            return Err(Error::PaymentRequestAlreadyFulfilled);
+        }
+
+        self.spend(
+            policy_id,
+            request.address(),
+            Amount::Custom(request.amount()),
+            request.description(),
+            fee_rate,
+            None,
+            None,
+            false,
+        )
+        .await
+    }
+}
@@ -0,0 +1,46 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use super::{Error, SmartVaults};
+use crate::pin::ApprovalPin;
+
+impl SmartVaults {
+    fn approval_pin(&self) -> ApprovalPin {
+        ApprovalPin::new(self.network, self.keys().public_key())
+    }
+
+    /// Check if an approval PIN is set on this device (see [`crate::pin`])
+    pub fn has_approval_pin(&self) -> bool {
+        self.approval_pin().is_set()
+    }
+
+    /// Set (or change) the device-local approval PIN
+    ///
+    /// The keychain password is required. Setting the PIN also unlocks the approvals after too many wrong attempts.
+    pub fn set_approval_pin<T, S>(&self, password: T, pin: S) -> Result<(), Error>
+    where
+        T: AsRef<[u8]>,
+        S: AsRef<str>,
+    {
+        if !self.check_password(password) {
+            return Err(Error::PasswordNotMatch);
+        }
+        Ok(self.approval_pin().set(pin.as_ref())?)
+    }
+
+    /// Remove the approval PIN (the keychain password is required)
+    pub fn remove_approval_pin<T>(&self, password: T) -> Result<(), Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        if !self.check_password(password) {
+            return Err(Error::PasswordNotMatch);
+        }
+        Ok(self.approval_pin().remove()?)
+    }
+
+    /// Verify the approval PIN, if set
+    pub(crate) fn verify_approval_pin(&self, pin: Option<&str>) -> Result<(), Error> {
+        Ok(self.approval_pin().verify(pin)?)
+    }
+}
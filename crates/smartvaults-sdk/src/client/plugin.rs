@@ -0,0 +1,216 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::sync::Arc;
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{
+    Event, EventBuilder, EventId, Filter, Keys, Kind, SubscribeOptions, SubscriptionId,
+};
+use serde_json::Value;
+use smartvaults_protocol::v1::constants::RESERVED_KINDS;
+use smartvaults_protocol::v1::{Encryption, SmartVaultsEventBuilder};
+use smartvaults_sdk_sqlite::model::PluginData;
+
+use super::{Error, EventHandled, Message, SmartVaults};
+use crate::constants::PLUGINS_SUBSCRIPTION_ID;
+use crate::plugin::{VaultEvent, VaultPlugin};
+use crate::storage::InternalPolicy;
+
+impl SmartVaults {
+    /// Register a plugin to handle custom event kinds
+    ///
+    /// The events of the plugin kinds already in the database are dispatched to the plugin
+    /// and the kinds are subscribed on all the relays.
+    pub async fn register_plugin(&self, plugin: Arc<dyn VaultPlugin>) -> Result<(), Error> {
+        let name: String = plugin.name();
+        if self.plugins.is_registered(&name).await {
+            return Err(Error::PluginConflict(format!(
+                "plugin `{name}` already registered"
+            )));
+        }
+
+        let kinds: Vec<Kind> = plugin.kinds();
+        if let Some(kind) = kinds
+            .iter()
+            .find(|k| k.as_u64() < 1000 || RESERVED_KINDS.contains(k))
+        {
+            return Err(Error::PluginConflict(format!(
+                "kind {} is reserved",
+                kind.as_u64()
+            )));
+        }
+
+        self.plugins.register(plugin).await.map_err(|kind| {
+            Error::PluginConflict(format!(
+                "kind {} already handled by another plugin",
+                kind.as_u64()
+            ))
+        })?;
+        tracing::info!("Registered plugin `{name}`");
+
+        // Handle the events already in the database
+        let filter: Filter = Filter::new().kinds(kinds);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Asc)
+            .await?;
+        for event in events.into_iter() {
+            if let Err(e) = self.handle_plugin_event(&event).await {
+                tracing::warn!("Impossible to handle plugin event {}: {e}", event.id);
+            }
+        }
+
+        self.subscribe_plugins().await;
+
+        Ok(())
+    }
+
+    /// Unregister a plugin (the saved data is kept)
+    pub async fn unregister_plugin<S>(&self, name: S)
+    where
+        S: AsRef<str>,
+    {
+        self.plugins.unregister(name.as_ref()).await;
+        self.subscribe_plugins().await;
+    }
+
+    /// Subscribe to the kinds of the registered plugins
+    pub(crate) async fn subscribe_plugins(&self) {
+        let kinds: Vec<Kind> = self.plugins.kinds().await;
+        let id = SubscriptionId::new(PLUGINS_SUBSCRIPTION_ID);
+
+        if kinds.is_empty() {
+            self.client.unsubscribe(id).await;
+            return;
+        }
+
+        let public_key = self.keys().public_key();
+        let filters: Vec<Filter> = vec![
+            Filter::new().kinds(kinds.clone()).author(public_key),
+            Filter::new().kinds(kinds).pubkey(public_key),
+        ];
+        for (relay_url, relay) in self.client.relays().await {
+            if let Err(e) = relay
+                .subscribe_with_id(id.clone(), filters.clone(), SubscribeOptions::default())
+                .await
+            {
+                tracing::error!("Impossible to subscribe plugins to {relay_url}: {e}");
+            }
+        }
+    }
+
+    /// Decrypt, save and dispatch an event of a plugin kind
+    pub(crate) async fn handle_plugin_event(&self, event: &Event) -> Result<(), Error> {
+        let plugin = match self.plugins.get(&event.kind).await {
+            Some(plugin) => plugin,
+            None => return Ok(()),
+        };
+
+        let vault_id: EventId = *event.event_ids().next().ok_or(Error::PolicyNotFound)?;
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+
+        if !public_keys.contains(&event.author()) {
+            tracing::warn!(
+                "Plugin event {} not authored by a member of vault {vault_id}",
+                event.id
+            );
+            return Ok(());
+        }
+
+        let content: Value = Value::decrypt_with_keys(&shared_key, &event.content)?;
+        let vault_event = VaultEvent {
+            vault_id,
+            event_id: event.id,
+            author: event.author(),
+            kind: event.kind,
+            identifier: event.identifier().map(String::from),
+            content,
+            timestamp: event.created_at,
+        };
+
+        let name: String = plugin.name();
+        self.db
+            .save_plugin_data(PluginData {
+                plugin: name.clone(),
+                vault_id,
+                key: vault_event.key(),
+                author: vault_event.author,
+                value: vault_event.content.to_string(),
+                timestamp: vault_event.timestamp,
+            })
+            .await?;
+
+        if let Err(e) = plugin.handle(&vault_event) {
+            tracing::error!("Plugin `{name}` failed to handle event {}: {e}", event.id);
+        }
+
+        let _ = self
+            .sync_channel
+            .send(Message::EventHandled(EventHandled::Plugin {
+                kind: event.kind,
+                vault_id,
+            }));
+
+        Ok(())
+    }
+
+    /// Publish a custom event of a plugin to the other members of the vault
+    ///
+    /// Set the `identifier` for parameterized replaceable kinds.
+    pub async fn publish_plugin_event(
+        &self,
+        vault_id: EventId,
+        kind: Kind,
+        identifier: Option<String>,
+        content: Value,
+    ) -> Result<EventId, Error> {
+        if self.plugins.get(&kind).await.is_none() {
+            return Err(Error::PluginNotFound);
+        }
+
+        let keys: &Keys = self.keys();
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+        let event: Event = EventBuilder::custom_vault_event(
+            keys,
+            &shared_key,
+            vault_id,
+            kind,
+            identifier,
+            &content,
+            &public_keys,
+        )?;
+        let event_id: EventId = self.client.send_event(event.clone()).await?;
+        self.handle_plugin_event(&event).await?;
+        Ok(event_id)
+    }
+
+    /// Get the data saved by a plugin for a vault, newest first
+    pub async fn get_plugin_data<S>(
+        &self,
+        plugin: S,
+        vault_id: EventId,
+    ) -> Result<Vec<PluginData>, Error>
+    where
+        S: Into<String>,
+    {
+        Ok(self.db.get_plugin_data(plugin, vault_id).await?)
+    }
+
+    /// Delete data from the plugin storage (the events are not deleted from relays)
+    pub async fn delete_plugin_data<S, K>(
+        &self,
+        plugin: S,
+        vault_id: EventId,
+        key: K,
+    ) -> Result<(), Error>
+    where
+        S: Into<String>,
+        K: Into<String>,
+    {
+        Ok(self.db.delete_plugin_data(plugin, vault_id, key).await?)
+    }
+}
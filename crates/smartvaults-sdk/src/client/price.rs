@@ -0,0 +1,90 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+use nostr_sdk::{EventId, Timestamp};
+use serde_json::Value;
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::{Address, OutPoint};
+use smartvaults_core::{Amount, FeeRate};
+use smartvaults_protocol::v1::Currency;
+
+use super::{Error, SmartVaults};
+use crate::constants::PRICE_API_URL;
+use crate::types::{BtcPrice, GetProposal};
+
+impl SmartVaults {
+    /// Get the current BTC price in `currency`
+    pub async fn get_btc_price(&self, currency: Currency) -> Result<BtcPrice, Error> {
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+        let client = self
+            .http_client(proxy)
+            .map_err(|e| Error::PriceNotAvailable(e.to_string()))?;
+        let text: String = client
+            .get(PRICE_API_URL)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| Error::PriceNotAvailable(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| Error::PriceNotAvailable(e.to_string()))?;
+        let json: Value = serde_json::from_str(&text)?;
+        let price: f64 = json
+            .get(currency.to_string())
+            .and_then(|p| p.as_f64())
+            .filter(|p| *p > 0.0)
+            .ok_or_else(|| Error::PriceNotAvailable(format!("{currency} not supported")))?;
+        let timestamp: Timestamp = json
+            .get("time")
+            .and_then(|t| t.as_u64())
+            .map(Timestamp::from)
+            .unwrap_or_else(Timestamp::now);
+        Ok(BtcPrice {
+            currency,
+            price,
+            timestamp,
+        })
+    }
+
+    /// Make a spending proposal with the amount in fiat
+    ///
+    /// The amount is converted at the current price and frozen into the proposal:
+    /// the conversion rate is recorded in the description.
+    pub async fn spend_fiat<S>(
+        &self,
+        policy_id: EventId,
+        address: Address<NetworkUnchecked>,
+        fiat_amount: f64,
+        currency: Currency,
+        description: S,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        skip_frozen_utxos: bool,
+    ) -> Result<GetProposal, Error>
+    where
+        S: Into<String>,
+    {
+        let price: BtcPrice = self.get_btc_price(currency).await?;
+        let amount: u64 = price.to_sat(fiat_amount);
+        let description: String = format!(
+            "{} ({fiat_amount:.2} {currency} @ {:.2} {currency}/BTC)",
+            description.into(),
+            price.price
+        );
+        self.spend(
+            policy_id,
+            address,
+            Amount::Custom(amount),
+            description,
+            fee_rate,
+            utxos,
+            policy_path,
+            skip_frozen_utxos,
+        )
+        .await
+    }
+}
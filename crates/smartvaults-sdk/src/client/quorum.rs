@@ -0,0 +1,83 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr_sdk::database::NostrDatabaseExt;
+use nostr_sdk::{EventId, Profile, PublicKey};
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_core::QuorumPath;
+
+use super::{Error, SmartVaults};
+use crate::storage::{InternalPolicy, InternalProposal, InternalSharedSigner};
+use crate::types::{GetProposalStatus, GetQuorumStatus};
+
+impl SmartVaults {
+    /// Get the known owners of the signers (own signers and contacts' shared signers)
+    pub(crate) async fn signer_owners(
+        &self,
+        fingerprints: &BTreeSet<Fingerprint>,
+    ) -> Result<BTreeMap<Fingerprint, Profile>, Error> {
+        let mut owners: BTreeMap<Fingerprint, PublicKey> = BTreeMap::new();
+
+        let public_key: PublicKey = self.keys().public_key();
+        for signer in self.storage.signers().await.into_values() {
+            owners.insert(signer.fingerprint(), public_key);
+        }
+
+        for InternalSharedSigner {
+            owner_public_key,
+            shared_signer,
+        } in self.storage.shared_signers().await.into_values()
+        {
+            owners
+                .entry(shared_signer.fingerprint())
+                .or_insert(owner_public_key);
+        }
+
+        let mut profiles: BTreeMap<Fingerprint, Profile> = BTreeMap::new();
+        for (fingerprint, owner) in owners.into_iter() {
+            if fingerprints.contains(&fingerprint) {
+                let profile: Profile = self.client.database().profile(owner).await?;
+                profiles.insert(fingerprint, profile);
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// Get the signers still required by each spending path of a pending proposal
+    pub async fn get_quorum_status(&self, proposal_id: EventId) -> Result<GetQuorumStatus, Error> {
+        let GetProposalStatus {
+            policy_id,
+            approvals,
+            signed,
+            ready,
+            ..
+        } = self.get_proposal_status(proposal_id).await?;
+        let InternalProposal { proposal, .. } = self.storage.proposal(&proposal_id).await?;
+        let InternalPolicy { policy, .. } = self.storage.vault(&policy_id).await?;
+
+        // Only the paths the proposal can be finalized with
+        let involved: BTreeSet<Fingerprint> = proposal.involved_signers();
+        let paths: Vec<QuorumPath> = policy
+            .quorum_paths(&signed)?
+            .into_iter()
+            .filter(|p| involved.is_empty() || !p.signers.is_disjoint(&involved))
+            .collect();
+
+        let fingerprints: BTreeSet<Fingerprint> = paths
+            .iter()
+            .flat_map(|p| p.signers.iter().copied())
+            .collect();
+        let owners: BTreeMap<Fingerprint, Profile> = self.signer_owners(&fingerprints).await?;
+
+        Ok(GetQuorumStatus {
+            proposal_id,
+            policy_id,
+            approvals,
+            paths,
+            owners,
+            ready,
+        })
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::str::FromStr;
+
+use nostr_sdk::{EventId, Timestamp};
+use smartvaults_core::bdk::wallet::AddressIndex;
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_core::descriptor;
+use smartvaults_core::miniscript::{Descriptor, DescriptorPublicKey};
+
+use super::{Error, SmartVaults};
+use crate::storage::InternalPolicy;
+use crate::types::recovery_kit::{self, RecoveryKit, RecoveryKitKey};
+
+impl SmartVaults {
+    /// Get the disaster-recovery kit of a vault
+    pub async fn recovery_kit(&self, vault_id: EventId) -> Result<RecoveryKit, Error> {
+        let InternalPolicy { policy, .. } = self.storage.vault(&vault_id).await?;
+        let descriptor: String = policy.descriptor().to_string();
+        let parsed: Descriptor<DescriptorPublicKey> =
+            Descriptor::from_str(&descriptor).map_err(descriptor::Error::from)?;
+
+        let fingerprints: BTreeSet<Fingerprint> = policy
+            .quorum_paths(&BTreeSet::new())?
+            .into_iter()
+            .flat_map(|p| p.signers)
+            .collect();
+        let owners: BTreeMap<Fingerprint, String> = self
+            .signer_owners(&fingerprints)
+            .await?
+            .into_iter()
+            .map(|(fingerprint, owner)| (fingerprint, owner.name()))
+            .collect();
+
+        Ok(RecoveryKit {
+            name: policy.name(),
+            description: policy.description(),
+            network: self.network,
+            descriptor_fingerprint: policy.descriptor_fingerprint().ok(),
+            keys: RecoveryKitKey::from_descriptor(&parsed, &owners),
+            spending_paths: recovery_kit::spending_paths(policy.satisfiable_item()?, &owners),
+            first_address: self
+                .manager
+                .get_address(vault_id, AddressIndex::Peek(0))
+                .await?
+                .address,
+            generated_at: Timestamp::now(),
+            descriptor,
+        })
+    }
+
+    /// Save the printable disaster-recovery kit of a vault
+    ///
+    /// Descriptor, keys, spending conditions in plain language and instructions to recover the
+    /// funds with open-source tools, without Smart Vaults.
+    pub async fn generate_recovery_kit<P>(&self, vault_id: EventId, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let kit: RecoveryKit = self.recovery_kit(vault_id).await?;
+        kit.save(path)?;
+        Ok(())
+    }
+}
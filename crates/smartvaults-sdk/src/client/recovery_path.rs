@@ -0,0 +1,303 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Recovery paths
+//!
+//! Timelocked branches of a vault (i.e. decaying or social recovery templates) that a signer of
+//! the user can satisfy alone. The vaults can be watched (opt-in): when a path matures a
+//! [`Message::RecoveryPathMatured`] is sent and, if a sweep address is set, a proposal sweeping
+//! the funds with the path is made automatically. A new sweep is made only after the previous one
+//! is confirmed or dismissed (see [`SmartVaults::dismiss_recovery_sweep`]).
+
+use std::collections::{BTreeMap, HashSet};
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{EventId, Filter, Timestamp};
+use smartvaults_core::bdk::wallet::AddressIndex;
+use smartvaults_core::bdk::FeeRate as BdkFeeRate;
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::{Address, OutPoint};
+use smartvaults_core::{Amount, FeeRate, Priority, TxOptions};
+use smartvaults_protocol::v1::constants::COMPLETED_PROPOSAL_KIND;
+use smartvaults_sdk_sqlite::model::RecoveryWatch;
+
+use super::{Error, Message, SmartVaults};
+use crate::storage::{CompletedProposalIndex, InternalPolicy};
+use crate::types::{GetProposal, GetRecoveryPath};
+
+/// Status of the last automatic sweep of a watched vault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SweepStatus {
+    /// No sweep made (or dismissed)
+    None,
+    /// Proposal waiting for the approvals
+    Pending,
+    /// Proposal finalized, transaction not confirmed yet
+    Unconfirmed,
+    /// Proposal finalized and transaction confirmed
+    Confirmed,
+    /// Proposal deleted (or expired): waiting for an explicit dismissal
+    Deleted,
+}
+
+impl SweepStatus {
+    /// Check if a new sweep proposal can be made
+    fn can_sweep(&self) -> bool {
+        matches!(self, Self::None | Self::Confirmed)
+    }
+}
+
+impl SmartVaults {
+    /// Get the timelocked paths of a vault that a signer of the user can satisfy alone
+    ///
+    /// A path is matured if all the (not frozen) vault UTXOs can be spent with it.
+    pub async fn get_recovery_paths(
+        &self,
+        policy_id: EventId,
+    ) -> Result<Vec<GetRecoveryPath>, Error> {
+        let InternalPolicy { policy, .. } = self.storage.vault(&policy_id).await?;
+        if !policy.has_timelock() {
+            return Ok(Vec::new());
+        }
+
+        // Dry-run destination
+        let address = self
+            .manager
+            .get_address(policy_id, AddressIndex::Peek(0))
+            .await?
+            .address;
+        let address: Address<NetworkUnchecked> = Address::new(self.network, address.payload);
+        let frozen_utxos: Option<Vec<OutPoint>> = self.frozen_utxos(policy_id, true).await?;
+
+        let mut paths: Vec<GetRecoveryPath> = Vec::new();
+        for (signer_id, signer) in self.storage.signers().await.into_iter() {
+            let policy_path: BTreeMap<String, Vec<usize>> =
+                match policy.timelocked_policy_path(&signer)? {
+                    Some(policy_path) => policy_path,
+                    None => continue,
+                };
+
+            // The timelocks are checked building the transaction
+            let matured: bool = self
+                .manager
+                .spend(
+                    policy_id,
+                    address.clone(),
+                    Amount::Max,
+                    "",
+                    BdkFeeRate::from_sat_per_vb(1.0),
+                    None,
+                    frozen_utxos.clone(),
+                    Some(policy_path.clone()),
+                    TxOptions::default(),
+                )
+                .await
+                .is_ok();
+
+            paths.push(GetRecoveryPath {
+                policy_id,
+                signer_id,
+                policy_path,
+                matured,
+            });
+        }
+
+        Ok(paths)
+    }
+
+    /// Make a proposal sweeping the vault funds to `address` with the recovery path of the signer
+    pub async fn sweep_with_recovery_path(
+        &self,
+        policy_id: EventId,
+        signer_id: EventId,
+        address: Address<NetworkUnchecked>,
+        fee_rate: FeeRate,
+    ) -> Result<GetProposal, Error> {
+        let InternalPolicy { policy, .. } = self.storage.vault(&policy_id).await?;
+        let signer = self.storage.signer(&signer_id).await?;
+        let policy_path: BTreeMap<String, Vec<usize>> = policy
+            .timelocked_policy_path(&signer)?
+            .ok_or(Error::RecoveryPathNotFound)?;
+        self.spend(
+            policy_id,
+            address,
+            Amount::Max,
+            format!("Recovery sweep with {}", signer.name()),
+            fee_rate,
+            None,
+            Some(policy_path),
+            true,
+        )
+        .await
+    }
+
+    /// Watch a vault for the maturity of its recovery paths
+    ///
+    /// If `sweep_address` is set, a proposal sweeping the funds to it is made automatically as
+    /// soon as a path matures. Otherwise, only a [`Message::RecoveryPathMatured`] is sent.
+    pub async fn watch_recovery_paths(
+        &self,
+        policy_id: EventId,
+        sweep_address: Option<Address<NetworkUnchecked>>,
+    ) -> Result<(), Error> {
+        // Check if vault exists
+        self.storage.vault(&policy_id).await?;
+
+        if let Some(address) = &sweep_address {
+            address.clone().require_network(self.network)?;
+        }
+
+        Ok(self
+            .db
+            .save_recovery_watch(RecoveryWatch {
+                policy_id,
+                sweep_address,
+                proposal_id: None,
+                timestamp: Timestamp::now(),
+            })
+            .await?)
+    }
+
+    pub async fn unwatch_recovery_paths(&self, policy_id: EventId) -> Result<(), Error> {
+        Ok(self.db.delete_recovery_watch(policy_id).await?)
+    }
+
+    pub async fn get_recovery_watches(&self) -> Result<Vec<RecoveryWatch>, Error> {
+        Ok(self.db.get_recovery_watches().await?)
+    }
+
+    /// Dismiss the last automatic sweep of a watched vault (i.e. deleted on purpose), allowing a
+    /// new sweep proposal
+    pub async fn dismiss_recovery_sweep(&self, policy_id: EventId) -> Result<(), Error> {
+        let mut watch: RecoveryWatch = self
+            .db
+            .get_recovery_watches()
+            .await?
+            .into_iter()
+            .find(|w| w.policy_id == policy_id)
+            .ok_or(Error::NotFound)?;
+        watch.proposal_id = None;
+        Ok(self.db.save_recovery_watch(watch).await?)
+    }
+
+    async fn sweep_status(&self, watch: &RecoveryWatch) -> Result<SweepStatus, Error> {
+        let proposal_id: EventId = match watch.proposal_id {
+            Some(proposal_id) => proposal_id,
+            None => return Ok(SweepStatus::None),
+        };
+
+        if self.storage.proposal(&proposal_id).await.is_ok() {
+            return Ok(SweepStatus::Pending);
+        }
+
+        // Completed proposal event referencing the sweep
+        let filter: Filter = Filter::new()
+            .kind(COMPLETED_PROPOSAL_KIND)
+            .event(proposal_id);
+        let completed_ids = self
+            .client
+            .database()
+            .event_ids_by_filters(vec![filter], Order::Desc)
+            .await?;
+        let index = self.storage.completed_proposals_index().await;
+        match completed_ids.iter().find_map(|id| index.get(id)) {
+            Some(CompletedProposalIndex {
+                spending: Some((txid, ..)),
+                ..
+            }) => match self.manager.get_tx(watch.policy_id, *txid).await {
+                Ok(tx) if tx.confirmation_time.is_confirmed() => Ok(SweepStatus::Confirmed),
+                _ => Ok(SweepStatus::Unconfirmed),
+            },
+            Some(..) => Ok(SweepStatus::Confirmed),
+            None => Ok(SweepStatus::Deleted),
+        }
+    }
+
+    /// Check the recovery paths of the watched vaults
+    ///
+    /// A sweep proposal is made only if the previous one is confirmed or dismissed.
+    pub(super) async fn process_recovery_watches(
+        &self,
+        notified: &mut HashSet<(EventId, EventId)>,
+    ) -> Result<(), Error> {
+        for mut watch in self.db.get_recovery_watches().await?.into_iter() {
+            let policy_id: EventId = watch.policy_id;
+            let paths: Vec<GetRecoveryPath> = match self.get_recovery_paths(policy_id).await {
+                Ok(paths) => paths,
+                Err(e) => {
+                    tracing::warn!("Impossible to get recovery paths of vault {policy_id}: {e}");
+                    continue;
+                }
+            };
+
+            for GetRecoveryPath { signer_id, .. } in paths.into_iter().filter(|p| p.matured) {
+                let mut proposal_id: Option<EventId> = None;
+
+                if let Some(address) = watch.sweep_address.clone() {
+                    let status: SweepStatus = match self.sweep_status(&watch).await {
+                        Ok(status) => status,
+                        Err(e) => {
+                            tracing::warn!(
+                                "Impossible to get sweep status of vault {policy_id}: {e}"
+                            );
+                            continue;
+                        }
+                    };
+                    if status.can_sweep() {
+                        match self
+                            .sweep_with_recovery_path(
+                                policy_id,
+                                signer_id,
+                                address,
+                                FeeRate::Priority(Priority::Medium),
+                            )
+                            .await
+                        {
+                            Ok(GetProposal {
+                                proposal_id: id, ..
+                            }) => {
+                                watch.proposal_id = Some(id);
+                                self.db.save_recovery_watch(watch.clone()).await?;
+                                proposal_id = Some(id);
+                            }
+                            Err(e) => {
+                                tracing::error!("Impossible to sweep vault {policy_id}: {e}")
+                            }
+                        }
+                    }
+                }
+
+                if notified.insert((policy_id, signer_id)) || proposal_id.is_some() {
+                    let _ = self.sync_channel.send(Message::RecoveryPathMatured {
+                        policy_id,
+                        signer_id,
+                        proposal_id,
+                    });
+                }
+
+                // A single sweep proposal per vault
+                if proposal_id.is_some() {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_status() {
+        assert!(SweepStatus::None.can_sweep());
+        assert!(SweepStatus::Confirmed.can_sweep());
+
+        // No duplicated sweep while the previous one is in progress or deleted but not dismissed
+        assert!(!SweepStatus::Pending.can_sweep());
+        assert!(!SweepStatus::Unconfirmed.can_sweep());
+        assert!(!SweepStatus::Deleted.can_sweep());
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::time::Duration;
+
+use nostr_sdk::database::{NostrDatabaseExt, Order};
+use nostr_sdk::nips::nip01::Coordinate;
+use nostr_sdk::{Event, EventBuilder, EventId, Filter, Keys, PublicKey, Timestamp};
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::Address;
+use smartvaults_core::secp256k1::rand::{self, RngCore};
+use smartvaults_core::{Amount, FeeRate};
+use smartvaults_protocol::v1::constants::RECURRING_PAYMENT_KIND;
+use smartvaults_protocol::v1::{Encryption, RecurringPayment, SmartVaultsEventBuilder};
+
+use super::{Error, SmartVaults};
+use crate::constants::RECURRING_PAYMENT_MIN_INTERVAL;
+use crate::storage::InternalPolicy;
+use crate::types::{GetProposal, GetRecurringPayment};
+
+impl SmartVaults {
+    async fn publish_recurring_payment(
+        &self,
+        vault_id: EventId,
+        identifier: &str,
+        payment: &RecurringPayment,
+    ) -> Result<EventId, Error> {
+        let keys: &Keys = self.keys();
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+        let event: Event = EventBuilder::recurring_payment(
+            keys,
+            &shared_key,
+            vault_id,
+            identifier,
+            payment,
+            &public_keys,
+        )?;
+        Ok(self.client.send_event(event).await?)
+    }
+
+    /// Schedule a payment repeated every `interval`, starting from `start` (default: now)
+    ///
+    /// At each due date, a spending proposal is created by this device and sent to the other members.
+    /// Return the ID of the recurring payment.
+    pub async fn create_recurring_payment<S>(
+        &self,
+        vault_id: EventId,
+        address: Address<NetworkUnchecked>,
+        amount: u64,
+        description: S,
+        interval: Duration,
+        start: Option<Timestamp>,
+    ) -> Result<String, Error>
+    where
+        S: Into<String>,
+    {
+        // Check address network
+        address.clone().require_network(self.network)?;
+
+        if interval < RECURRING_PAYMENT_MIN_INTERVAL {
+            return Err(Error::InvalidRecurringPaymentInterval);
+        }
+
+        let mut id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut id);
+        let identifier: String = id.iter().map(|b| format!("{b:02x}")).collect();
+
+        let payment = RecurringPayment::new(
+            address,
+            amount,
+            description,
+            interval,
+            start.unwrap_or_else(Timestamp::now),
+        );
+        self.publish_recurring_payment(vault_id, &identifier, &payment)
+            .await?;
+        Ok(identifier)
+    }
+
+    async fn internal_get_recurring_payment(
+        &self,
+        event: &Event,
+    ) -> Result<GetRecurringPayment, Error> {
+        let vault_id: EventId = *event.event_ids().next().ok_or(Error::PolicyNotFound)?;
+        let identifier: String = event
+            .identifier()
+            .ok_or(Error::RecurringPaymentNotFound)?
+            .to_string();
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+
+        if !public_keys.contains(&event.author()) {
+            return Err(Error::RecurringPaymentNotFound);
+        }
+
+        let payment: RecurringPayment =
+            RecurringPayment::decrypt_with_keys(&shared_key, &event.content)?;
+        Ok(GetRecurringPayment {
+            payment_id: identifier,
+            policy_id: vault_id,
+            owner: event.author(),
+            next_due: payment.next_due(),
+            payment,
+        })
+    }
+
+    /// Get the recurring payments of the vaults
+    pub async fn get_recurring_payments(&self) -> Result<Vec<GetRecurringPayment>, Error> {
+        let filter: Filter = Filter::new().kind(RECURRING_PAYMENT_KIND);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+
+        let mut payments: Vec<GetRecurringPayment> = Vec::with_capacity(events.len());
+        for event in events.into_iter() {
+            match self.internal_get_recurring_payment(&event).await {
+                Ok(payment) => payments.push(payment),
+                Err(e) => tracing::warn!("Impossible to get recurring payment {}: {e}", event.id),
+            }
+        }
+
+        payments.sort_by_key(|p| p.next_due);
+        Ok(payments)
+    }
+
+    /// Get the recurring payments of a vault
+    pub async fn get_recurring_payments_by_vault_id(
+        &self,
+        vault_id: EventId,
+    ) -> Result<Vec<GetRecurringPayment>, Error> {
+        Ok(self
+            .get_recurring_payments()
+            .await?
+            .into_iter()
+            .filter(|p| p.policy_id == vault_id)
+            .collect())
+    }
+
+    /// Cancel a recurring payment (only the member that created it can cancel it)
+    pub async fn delete_recurring_payment<S>(&self, payment_id: S) -> Result<(), Error>
+    where
+        S: AsRef<str>,
+    {
+        let payment_id: &str = payment_id.as_ref();
+        let public_key: PublicKey = self.keys().public_key();
+        let payment: GetRecurringPayment = self
+            .get_recurring_payments()
+            .await?
+            .into_iter()
+            .find(|p| p.payment_id == payment_id && p.owner == public_key)
+            .ok_or(Error::RecurringPaymentNotFound)?;
+
+        let coordinate: Coordinate =
+            Coordinate::new(RECURRING_PAYMENT_KIND, public_key).identifier(payment.payment_id);
+        let event: EventBuilder = EventBuilder::delete([coordinate]);
+        self.client.send_event_builder(event).await?;
+        Ok(())
+    }
+
+    /// Create the spending proposals of the due recurring payments created by this device's keys
+    ///
+    /// Return the created proposals.
+    pub(crate) async fn process_recurring_payments(&self) -> Result<Vec<GetProposal>, Error> {
+        let public_key: PublicKey = self.keys().public_key();
+        let now: Timestamp = Timestamp::now();
+
+        let mut proposals: Vec<GetProposal> = Vec::new();
+        for GetRecurringPayment {
+            payment_id,
+            policy_id,
+            owner,
+            mut payment,
+            ..
+        } in self.get_recurring_payments().await?.into_iter()
+        {
+            if owner != public_key {
+                continue;
+            }
+
+            if let Some(due) = payment.due(now) {
+                // Retried at the next check if it fails (i.e. not enough funds)
+                let proposal: GetProposal = match self
+                    .spend(
+                        policy_id,
+                        payment.address(),
+                        Amount::Custom(payment.amount()),
+                        payment.description(),
+                        FeeRate::default(),
+                        None,
+                        None,
+                        false,
+                    )
+                    .await
+                {
+                    Ok(proposal) => proposal,
+                    Err(e) => {
+                        tracing::error!(
+                            "Impossible to propose recurring payment {payment_id}: {e}"
+                        );
+                        continue;
+                    }
+                };
+                tracing::info!(
+                    "Recurring payment {payment_id} of {due} proposed: {}",
+                    proposal.proposal_id
+                );
+
+                payment.proposed(due);
+                self.publish_recurring_payment(policy_id, &payment_id, &payment)
+                    .await?;
+                proposals.push(proposal);
+            }
+        }
+
+        Ok(proposals)
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::HashSet;
+
+use nostr_sdk::{EventId, Timestamp};
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_core::signer::{Signer, SignerType};
+use smartvaults_sdk_sqlite::model::DeviceRegistration;
+
+use super::{Error, SmartVaults};
+use crate::storage::InternalPolicy;
+use crate::types::GetSigner;
+
+impl SmartVaults {
+    /// Record that the vault was registered on a hardware signer
+    ///
+    /// `proof` is the registration proof returned by the device, if any (i.e. Ledger wallet policy HMAC).
+    pub async fn save_device_registration<S>(
+        &self,
+        vault_id: EventId,
+        fingerprint: Fingerprint,
+        device: S,
+        proof: Option<String>,
+    ) -> Result<(), Error>
+    where
+        S: Into<String>,
+    {
+        // Check if vault exists
+        self.storage.vault(&vault_id).await?;
+
+        let registration = DeviceRegistration {
+            policy_id: vault_id,
+            fingerprint,
+            device: device.into(),
+            proof,
+            timestamp: Timestamp::now(),
+        };
+        Ok(self.db.save_device_registration(registration).await?)
+    }
+
+    /// Get the hardware signers on which the vault was registered
+    pub async fn get_device_registrations(
+        &self,
+        vault_id: EventId,
+    ) -> Result<Vec<DeviceRegistration>, Error> {
+        Ok(self.db.get_device_registrations(vault_id).await?)
+    }
+
+    pub async fn delete_device_registration(
+        &self,
+        vault_id: EventId,
+        fingerprint: Fingerprint,
+    ) -> Result<(), Error> {
+        Ok(self
+            .db
+            .delete_device_registration(vault_id, fingerprint)
+            .await?)
+    }
+
+    /// Get my hardware and air-gap signers used by the vault that still need to register it
+    pub async fn get_unregistered_signers(
+        &self,
+        vault_id: EventId,
+    ) -> Result<Vec<GetSigner>, Error> {
+        let InternalPolicy { policy, .. } = self.storage.vault(&vault_id).await?;
+        let registered: HashSet<Fingerprint> = self
+            .db
+            .get_device_registrations(vault_id)
+            .await?
+            .into_iter()
+            .map(|r| r.fingerprint)
+            .collect();
+
+        let signers = self
+            .storage
+            .signers()
+            .await
+            .into_iter()
+            .filter(|(_, signer)| {
+                matches!(
+                    signer.signer_type(),
+                    SignerType::Hardware | SignerType::AirGap
+                )
+            })
+            .filter(|(_, signer)| !registered.contains(&signer.fingerprint()));
+
+        let mut list: Vec<GetSigner> = Vec::new();
+        for (signer_id, signer) in signers {
+            let used: bool = policy
+                .search_used_signers(std::iter::once(signer.clone()))
+                .next()
+                .is_some();
+            if used {
+                list.push(GetSigner::from((signer_id, signer)));
+            }
+        }
+        list.sort();
+        Ok(list)
+    }
+
+    /// Check if the signer must register the vault before approving
+    pub async fn is_device_registration_required(
+        &self,
+        vault_id: EventId,
+        signer: &Signer,
+    ) -> Result<bool, Error> {
+        match signer.signer_type() {
+            SignerType::Hardware | SignerType::AirGap => {
+                let fingerprint: Fingerprint = signer.fingerprint();
+                Ok(!self
+                    .db
+                    .get_device_registrations(vault_id)
+                    .await?
+                    .into_iter()
+                    .any(|r| r.fingerprint == fingerprint))
+            }
+            SignerType::Seed => Ok(false),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeMap, HashSet};
+
+use nostr_sdk::EventId;
+use smartvaults_core::bdk::FeeRate as BdkFeeRate;
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::psbt::PartiallySignedTransaction;
+use smartvaults_core::bitcoin::script::Instruction;
+use smartvaults_core::bitcoin::{Address, OutPoint, Sequence};
+use smartvaults_core::{Amount, FeeRate, Proposal, Recipient, TxOptions};
+
+use super::{Error, SmartVaults};
+use crate::storage::InternalProposal;
+use crate::types::GetProposal;
+
+impl SmartVaults {
+    /// Build the spending proposal replacing `proposal_id` (the UTXOs of the replaced proposal can be reused)
+    ///
+    /// The `OP_RETURN` data of the replaced transaction is kept, as its nLockTime/nSequence if the
+    /// policy path doesn't change (i.e. to still satisfy the timelocks of a recovery path).
+    async fn build_replacement(
+        &self,
+        proposal_id: EventId,
+        recipients: Vec<Recipient>,
+        description: &str,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        skip_frozen_utxos: bool,
+    ) -> Result<(EventId, Proposal, Proposal), Error> {
+        let InternalProposal {
+            policy_id,
+            proposal: replaced,
+            ..
+        } = self.storage.proposal(&proposal_id).await?;
+        let replaced_policy_path: Option<BTreeMap<String, Vec<usize>>> = match &replaced {
+            Proposal::Spending { policy_path, .. } => policy_path.clone(),
+            _ => return Err(Error::UnexpectedProposal),
+        };
+
+        let fee_rate: BdkFeeRate = self.calculate_fee_rate(fee_rate).await?;
+        let replaced_utxos: HashSet<OutPoint> = replaced
+            .psbt()
+            .unsigned_tx
+            .input
+            .iter()
+            .map(|txin| txin.previous_output)
+            .collect();
+        let frozen_utxos: Option<Vec<OutPoint>> = self
+            .frozen_utxos(policy_id, skip_frozen_utxos)
+            .await?
+            .map(|frozen| {
+                frozen
+                    .into_iter()
+                    .filter(|utxo| !replaced_utxos.contains(utxo))
+                    .collect()
+            });
+
+        // The timelocks to satisfy depend on the policy path
+        let psbt: PartiallySignedTransaction = replaced.psbt();
+        let tx_options: TxOptions = if policy_path == replaced_policy_path {
+            tx_options(&psbt)
+        } else {
+            TxOptions::default()
+        };
+        let proposal: Proposal = self
+            .manager
+            .spend_many(
+                policy_id,
+                recipients,
+                description,
+                fee_rate,
+                utxos,
+                frozen_utxos,
+                policy_path,
+                data(&psbt),
+                tx_options,
+            )
+            .await?;
+
+        Ok((policy_id, proposal, replaced))
+    }
+
+    /// Publish the replacement and delete the replaced proposal
+    async fn publish_replacement(
+        &self,
+        proposal_id: EventId,
+        policy_id: EventId,
+        proposal: Proposal,
+    ) -> Result<GetProposal, Error> {
+        // Keep the expiration and the payment records of the replaced proposal
+        let InternalProposal {
+            expires_at,
+            proposal: replaced,
+            ..
+        } = self.storage.proposal(&proposal_id).await?;
+        let proposal: Proposal = proposal.with_memo(replaced.memo());
+        let replacement: GetProposal = self
+            .publish_spending_proposal(policy_id, proposal, Some(proposal_id), expires_at)
+            .await?;
+        self.delete_proposal_by_id(proposal_id).await?;
+        Ok(replacement)
+    }
+
+    /// Replace a pending spending proposal (i.e. to change the amount or the destination)
+    ///
+    /// The new proposal references the replaced one, that is deleted, so the approvers can review
+    /// only what changed (see [`GetProposal::diff`]).
+    /// The amount must be [`Amount::Custom`]. The replacement has a single recipient, also if the
+    /// replaced proposal is a batch.
+    pub async fn replace_proposal<S>(
+        &self,
+        proposal_id: EventId,
+        address: Address<NetworkUnchecked>,
+        amount: Amount,
+        description: S,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        skip_frozen_utxos: bool,
+    ) -> Result<GetProposal, Error>
+    where
+        S: Into<String>,
+    {
+        let description: &str = &description.into();
+        let recipient = Recipient {
+            address,
+            amount: custom_amount(amount)?,
+        };
+        let (policy_id, proposal, _) = self
+            .build_replacement(
+                proposal_id,
+                vec![recipient],
+                description,
+                fee_rate,
+                utxos,
+                policy_path,
+                skip_frozen_utxos,
+            )
+            .await?;
+        self.publish_replacement(proposal_id, policy_id, proposal)
+            .await
+    }
+
+    /// Amend a pending spending proposal, changing the amount, the description and the fee rate
+    ///
+    /// The recipients and the policy path are kept. The amended proposal supersedes the original
+    /// one (see [`GetProposal::is_amended`]): the approvals already collected must be given again.
+    /// The amount, if changed, must be [`Amount::Custom`]. The amount of a batch (multiple
+    /// recipients) can't be changed: [`Error::UnsupportedReplacement`] is returned.
+    pub async fn amend_proposal(
+        &self,
+        proposal_id: EventId,
+        amount: Option<Amount>,
+        description: Option<String>,
+        fee_rate: FeeRate,
+    ) -> Result<GetProposal, Error> {
+        let InternalProposal { proposal, .. } = self.storage.proposal(&proposal_id).await?;
+        let mut recipients: Vec<Recipient> = proposal.recipients();
+        let (old_description, policy_path) = match proposal {
+            Proposal::Spending {
+                description,
+                policy_path,
+                ..
+            } => (description, policy_path),
+            _ => return Err(Error::UnexpectedProposal),
+        };
+
+        if let Some(amount) = amount {
+            match recipients.as_mut_slice() {
+                [recipient] => recipient.amount = custom_amount(amount)?,
+                _ => {
+                    return Err(Error::UnsupportedReplacement(String::from(
+                        "the amount of a batch can't be amended",
+                    )))
+                }
+            }
+        }
+
+        let (policy_id, proposal, _) = self
+            .build_replacement(
+                proposal_id,
+                recipients,
+                &description.unwrap_or(old_description),
+                fee_rate,
+                None,
+                policy_path,
+                true,
+            )
+            .await?;
+        self.publish_replacement(proposal_id, policy_id, proposal)
+            .await
+    }
+
+    /// Replace a pending spending proposal with one spending the same UTXOs to the same recipients at a higher fee rate
+    ///
+    /// Return [`Error::InvalidFeeRate`] if the fee doesn't increase.
+    pub async fn bump_proposal_fee(
+        &self,
+        proposal_id: EventId,
+        fee_rate: FeeRate,
+    ) -> Result<GetProposal, Error> {
+        let InternalProposal { proposal, .. } = self.storage.proposal(&proposal_id).await?;
+        let utxos: Vec<OutPoint> = proposal
+            .psbt()
+            .unsigned_tx
+            .input
+            .iter()
+            .map(|txin| txin.previous_output)
+            .collect();
+        let recipients: Vec<Recipient> = proposal.recipients();
+        let (description, policy_path) = match proposal {
+            Proposal::Spending {
+                description,
+                policy_path,
+                ..
+            } => (description, policy_path),
+            _ => return Err(Error::UnexpectedProposal),
+        };
+
+        let (policy_id, proposal, replaced) = self
+            .build_replacement(
+                proposal_id,
+                recipients,
+                &description,
+                fee_rate,
+                Some(utxos),
+                policy_path,
+                true,
+            )
+            .await?;
+
+        if proposal.diff(&replaced).fee.unwrap_or_default() <= 0 {
+            return Err(Error::InvalidFeeRate);
+        }
+
+        self.publish_replacement(proposal_id, policy_id, proposal)
+            .await
+    }
+}
+
+fn custom_amount(amount: Amount) -> Result<u64, Error> {
+    match amount {
+        Amount::Custom(amount) => Ok(amount),
+        Amount::Max | Amount::MaxWithReserve(..) => Err(Error::UnsupportedReplacement(
+            String::from("the amount must be explicit"),
+        )),
+    }
+}
+
+/// Data of the `OP_RETURN` output of the PSBT, if any
+fn data(psbt: &PartiallySignedTransaction) -> Option<Vec<u8>> {
+    let txout = psbt
+        .unsigned_tx
+        .output
+        .iter()
+        .find(|txout| txout.script_pubkey.is_op_return())?;
+    Some(
+        txout
+            .script_pubkey
+            .instructions()
+            .filter_map(|i| match i {
+                Ok(Instruction::PushBytes(bytes)) => Some(bytes.as_bytes().to_vec()),
+                _ => None,
+            })
+            .flatten()
+            .collect(),
+    )
+}
+
+/// Options reproducing the nLockTime and the nSequence of the PSBT
+fn tx_options(psbt: &PartiallySignedTransaction) -> TxOptions {
+    let sequence: Option<Sequence> = psbt
+        .unsigned_tx
+        .input
+        .first()
+        .map(|txin| txin.sequence)
+        .filter(|sequence| sequence.is_rbf());
+    TxOptions {
+        anti_fee_sniping: false,
+        rbf: sequence.is_some(),
+        locktime: Some(psbt.unsigned_tx.lock_time),
+        sequence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smartvaults_core::bitcoin::absolute::{Height, LockTime};
+    use smartvaults_core::bitcoin::script::PushBytesBuf;
+    use smartvaults_core::bitcoin::{ScriptBuf, Transaction, TxIn, TxOut};
+
+    use super::*;
+
+    fn psbt(
+        lock_time: LockTime,
+        sequence: Sequence,
+        output: Vec<TxOut>,
+    ) -> PartiallySignedTransaction {
+        let tx = Transaction {
+            version: 2,
+            lock_time,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                sequence,
+                ..Default::default()
+            }],
+            output,
+        };
+        PartiallySignedTransaction::from_unsigned_tx(tx).unwrap()
+    }
+
+    #[test]
+    fn test_data() {
+        let op_return = TxOut {
+            value: 0,
+            script_pubkey: ScriptBuf::new_op_return(
+                &PushBytesBuf::try_from(b"invoice-42".to_vec()).unwrap(),
+            ),
+        };
+        let psbt_with_data = psbt(
+            LockTime::ZERO,
+            Sequence::ENABLE_RBF_NO_LOCKTIME,
+            vec![op_return],
+        );
+        assert_eq!(data(&psbt_with_data), Some(b"invoice-42".to_vec()));
+
+        let psbt_without_data = psbt(LockTime::ZERO, Sequence::ENABLE_RBF_NO_LOCKTIME, Vec::new());
+        assert_eq!(data(&psbt_without_data), None);
+    }
+
+    #[test]
+    fn test_tx_options() {
+        // Recovery path with `older(144)` and anti-fee-sniping
+        let lock_time = LockTime::from(Height::from_consensus(800_000).unwrap());
+        let options = tx_options(&psbt(lock_time, Sequence::from_height(144), Vec::new()));
+        assert_eq!(options.locktime, Some(lock_time));
+        assert_eq!(options.sequence, Some(Sequence::from_height(144)));
+        assert!(options.rbf);
+
+        // Not replaceable
+        let options = tx_options(&psbt(LockTime::ZERO, Sequence::MAX, Vec::new()));
+        assert_eq!(options.locktime, Some(LockTime::ZERO));
+        assert_eq!(options.sequence, None);
+        assert!(!options.rbf);
+    }
+}
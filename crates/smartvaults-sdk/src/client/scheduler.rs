@@ -0,0 +1,140 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_utility::thread;
+use futures::future::{self, Either};
+use nostr_sdk::EventId;
+use tokio::sync::{Notify, RwLock};
+
+use super::{Error, SmartVaults};
+
+/// Network condition reported by the platform
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetworkCondition {
+    /// i.e. Wi-Fi or ethernet
+    #[default]
+    Unmetered,
+    /// i.e. cellular
+    Metered,
+    Offline,
+}
+
+/// Background sync schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncSchedule {
+    /// Interval between sync rounds on unmetered networks
+    pub unmetered_interval: Duration,
+    /// Interval between sync rounds on metered networks
+    pub metered_interval: Duration,
+    /// Pause the background sync while the battery saver is enabled
+    pub pause_on_battery_saver: bool,
+}
+
+impl Default for SyncSchedule {
+    fn default() -> Self {
+        Self {
+            unmetered_interval: Duration::from_secs(10),
+            metered_interval: Duration::from_secs(300),
+            pause_on_battery_saver: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct PlatformHints {
+    network: NetworkCondition,
+    battery_saver: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SyncScheduler {
+    schedule: Arc<RwLock<SyncSchedule>>,
+    hints: Arc<RwLock<PlatformHints>>,
+    notify: Arc<Notify>,
+}
+
+impl SyncScheduler {
+    pub async fn is_paused(&self) -> bool {
+        let hints = *self.hints.read().await;
+        let schedule = *self.schedule.read().await;
+        hints.network == NetworkCondition::Offline
+            || (hints.battery_saver && schedule.pause_on_battery_saver)
+    }
+
+    async fn interval(&self) -> Duration {
+        let schedule = self.schedule.read().await;
+        match self.hints.read().await.network {
+            NetworkCondition::Metered => schedule.metered_interval,
+            _ => schedule.unmetered_interval,
+        }
+    }
+
+    /// Wait for the next sync round (or for a manual trigger)
+    pub async fn wait(&self) {
+        let interval: Duration = self.interval().await;
+        let notified = Box::pin(self.notify.notified());
+        let sleep = Box::pin(thread::sleep(interval));
+        if let Either::Left(..) = future::select(notified, sleep).await {
+            tracing::debug!("Sync round triggered");
+        }
+    }
+
+    /// Wake up the syncers
+    fn wake_up(&self) {
+        self.notify.notify_waiters();
+    }
+}
+
+impl SmartVaults {
+    pub async fn sync_schedule(&self) -> SyncSchedule {
+        *self.scheduler.schedule.read().await
+    }
+
+    pub async fn set_sync_schedule(&self, schedule: SyncSchedule) {
+        *self.scheduler.schedule.write().await = schedule;
+        self.scheduler.wake_up();
+    }
+
+    /// Update the platform hints used by the background sync scheduling
+    pub async fn set_platform_hints(&self, network: NetworkCondition, battery_saver: bool) {
+        *self.scheduler.hints.write().await = PlatformHints {
+            network,
+            battery_saver,
+        };
+        self.scheduler.wake_up();
+    }
+
+    /// Check if the background sync is paused by the platform hints
+    pub async fn is_sync_paused(&self) -> bool {
+        self.scheduler.is_paused().await
+    }
+
+    /// Sync the vaults now, also if the background sync is paused
+    pub async fn sync_now(&self) -> Result<(), Error> {
+        let endpoint = self.config.electrum_endpoint().await?;
+        let proxy = self.config.proxy().await.ok();
+        let max_concurrent: usize = self.config.max_concurrent_syncs().await;
+        self.manager
+            .sync_all(
+                endpoint,
+                proxy,
+                true,
+                max_concurrent,
+                Some(self.sync_channel.clone()),
+            )
+            .await?;
+        self.scheduler.wake_up();
+        Ok(())
+    }
+
+    /// Sync a single vault now, also if the background sync is paused
+    pub async fn sync_vault(&self, vault_id: EventId) -> Result<(), Error> {
+        let endpoint = self.config.electrum_endpoint().await?;
+        let proxy = self.config.proxy().await.ok();
+        self.manager.sync(vault_id, endpoint, proxy, true).await?;
+        Ok(())
+    }
+}
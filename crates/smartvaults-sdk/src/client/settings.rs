@@ -0,0 +1,107 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeSet, HashSet};
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{Event, EventBuilder, EventId, Filter, Keys, Kind};
+use smartvaults_protocol::v1::constants::APP_SETTINGS_IDENTIFIER;
+use smartvaults_protocol::v1::{AppSettings, Encryption, SmartVaultsEventBuilder};
+use smartvaults_sdk_sqlite::model::CompromisedKey;
+
+use super::{Error, SmartVaults};
+
+impl SmartVaults {
+    /// Get the application settings synced across the user devices
+    ///
+    /// Pinned vaults are always taken from the local database.
+    pub async fn get_app_settings(&self) -> Result<AppSettings, Error> {
+        let keys: &Keys = self.keys();
+        let filter = Filter::new()
+            .author(keys.public_key())
+            .kind(Kind::ApplicationSpecificData)
+            .identifier(APP_SETTINGS_IDENTIFIER)
+            .limit(1);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+        let mut settings: AppSettings = match events.first() {
+            Some(event) => AppSettings::decrypt_with_keys(keys, event.content())?,
+            None => AppSettings::default(),
+        };
+        settings.pinned_vaults = self.db.get_pinned_vaults().await?.into_iter().collect();
+        settings.compromised_keys = self.shared_compromised_keys().await?;
+        Ok(settings)
+    }
+
+    /// Publish the application settings (encrypted NIP-78 event)
+    ///
+    /// The compromised keys are always taken from the local database.
+    pub async fn save_app_settings(&self, mut settings: AppSettings) -> Result<EventId, Error> {
+        settings.compromised_keys = self.shared_compromised_keys().await?;
+        let keys: &Keys = self.keys();
+        let event: Event = EventBuilder::app_settings(keys, &settings)?;
+        Ok(self.client.send_event(event).await?)
+    }
+
+    /// Re-publish the application settings with the current pinned vaults
+    pub(crate) async fn sync_app_settings(&self) {
+        match self.get_app_settings().await {
+            Ok(settings) => {
+                if let Err(e) = self.save_app_settings(settings).await {
+                    tracing::error!("Impossible to publish app settings: {e}");
+                }
+            }
+            Err(e) => tracing::error!("Impossible to get app settings: {e}"),
+        }
+    }
+
+    /// Apply the settings received from another device
+    pub(crate) async fn handle_app_settings_event(&self, event: &Event) -> Result<(), Error> {
+        if event.identifier() != Some(APP_SETTINGS_IDENTIFIER) {
+            return Ok(());
+        }
+
+        let settings: AppSettings = AppSettings::decrypt_with_keys(self.keys(), event.content())?;
+        let pinned: HashSet<EventId> = self.db.get_pinned_vaults().await?;
+        let remote: BTreeSet<EventId> = settings.pinned_vaults;
+
+        for policy_id in remote.iter().filter(|id| !pinned.contains(id)) {
+            self.db.pin_vault(*policy_id).await?;
+            if let Err(e) = self.manager.set_pinned(*policy_id, true).await {
+                tracing::warn!("Impossible to pin wallet {policy_id}: {e}");
+            }
+        }
+
+        for policy_id in pinned.iter().filter(|id| !remote.contains(id)) {
+            self.db.unpin_vault(*policy_id).await?;
+            if let Err(e) = self.manager.set_pinned(*policy_id, false).await {
+                tracing::warn!("Impossible to unpin wallet {policy_id}: {e}");
+            }
+        }
+
+        let local: Vec<CompromisedKey> = self.db.get_compromised_keys().await?;
+        for fingerprint in settings.compromised_keys.iter() {
+            if !local.iter().any(|key| key.fingerprint == *fingerprint) {
+                self.db
+                    .save_compromised_key(CompromisedKey {
+                        fingerprint: *fingerprint,
+                        reason: None,
+                        shared: true,
+                        timestamp: event.created_at,
+                    })
+                    .await?;
+            }
+        }
+        for key in local
+            .into_iter()
+            .filter(|key| key.shared && !settings.compromised_keys.contains(&key.fingerprint))
+        {
+            self.db.delete_compromised_key(key.fingerprint).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeMap, HashMap};
+
+use nostr_sdk::{EventId, PublicKey};
+use smartvaults_core::bdk::FeeRate as BdkFeeRate;
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::{Address, OutPoint};
+use smartvaults_core::{FeeRate, Proposal, Recipient, TxOptions};
+
+use super::{Error, SmartVaults};
+use crate::types::{GetMemberContributions, GetProposal};
+
+/// Split `amount` proportionally to the contributions
+///
+/// The rounding remainder goes to the biggest contributor. Members with a zero share are skipped.
+fn split_proportionally(contributions: &[(PublicKey, u64)], amount: u64) -> Vec<(PublicKey, u64)> {
+    let total: u128 = contributions.iter().map(|(_, c)| *c as u128).sum();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let mut shares: Vec<(PublicKey, u64)> = contributions
+        .iter()
+        .map(|(public_key, contribution)| {
+            let share: u128 = amount as u128 * *contribution as u128 / total;
+            (*public_key, share as u64)
+        })
+        .collect();
+
+    let distributed: u64 = shares.iter().map(|(_, s)| *s).sum();
+    let remainder: u64 = amount.saturating_sub(distributed);
+    if let Some((_, share)) = contributions
+        .iter()
+        .zip(shares.iter_mut())
+        .max_by_key(|((_, contribution), _)| *contribution)
+        .map(|(_, share)| share)
+    {
+        *share = share.saturating_add(remainder);
+    }
+
+    shares.retain(|(_, share)| *share > 0);
+    shares
+}
+
+impl SmartVaults {
+    /// Compute what each member is owed when settling `amount` (SAT)
+    ///
+    /// The amount is split proportionally to the member contributions (see [`SmartVaults::get_member_contributions`]).
+    pub async fn compute_settlement(
+        &self,
+        policy_id: EventId,
+        amount: u64,
+    ) -> Result<BTreeMap<PublicKey, u64>, Error> {
+        let GetMemberContributions { members, .. } =
+            self.get_member_contributions(policy_id).await?;
+        let contributions: Vec<(PublicKey, u64)> = members
+            .into_iter()
+            .map(|m| (m.user.public_key(), m.amount))
+            .collect();
+        let shares = split_proportionally(&contributions, amount);
+        if shares.is_empty() {
+            return Err(Error::NoContributions);
+        }
+        Ok(shares.into_iter().collect())
+    }
+
+    /// Create a spending proposal that pays every member their share of `amount` (SAT)
+    ///
+    /// `payouts` must contain the payout address of every member owed something.
+    pub async fn settle_up<S>(
+        &self,
+        policy_id: EventId,
+        amount: u64,
+        payouts: HashMap<PublicKey, Address<NetworkUnchecked>>,
+        description: S,
+        fee_rate: FeeRate,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+    ) -> Result<GetProposal, Error>
+    where
+        S: Into<String>,
+    {
+        let shares: BTreeMap<PublicKey, u64> = self.compute_settlement(policy_id, amount).await?;
+
+        let mut recipients: Vec<Recipient> = Vec::with_capacity(shares.len());
+        for (public_key, amount) in shares.into_iter() {
+            let address = payouts
+                .get(&public_key)
+                .cloned()
+                .ok_or(Error::PayoutAddressNotFound(public_key))?;
+            recipients.push(Recipient { address, amount });
+        }
+
+        let fee_rate: BdkFeeRate = self.calculate_fee_rate(fee_rate).await?;
+        let frozen_utxos: Option<Vec<OutPoint>> = self.frozen_utxos(policy_id, false).await?;
+
+        // Build spending proposal
+        let proposal: Proposal = self
+            .manager
+            .spend_many(
+                policy_id,
+                recipients,
+                description,
+                fee_rate,
+                None,
+                frozen_utxos,
+                policy_path,
+                None,
+                TxOptions::default(),
+            )
+            .await?;
+
+        self.publish_spending_proposal(policy_id, proposal, None, None)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr_sdk::Keys;
+
+    use super::*;
+
+    #[test]
+    fn test_split_proportionally() {
+        let a = Keys::generate().public_key();
+        let b = Keys::generate().public_key();
+        let c = Keys::generate().public_key();
+
+        let shares = split_proportionally(&[(a, 3_000), (b, 1_000), (c, 0)], 10_001);
+        assert_eq!(shares, vec![(a, 7_501), (b, 2_500)]);
+
+        assert!(split_proportionally(&[(a, 0), (b, 0)], 10_000).is_empty());
+        assert!(split_proportionally(&[], 10_000).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::HashMap;
+
+use nostr_sdk::{nips, Event, EventBuilder, EventId, Filter, Keys, PublicKey, Tag};
+use smartvaults_protocol::v1::constants::{SHARED_KEY_KIND, SHARED_KEY_REQUEST_KIND};
+
+use super::{Error, SmartVaults};
+use crate::storage::InternalPolicy;
+
+impl SmartVaults {
+    pub(super) async fn publish_shared_key_for(
+        &self,
+        policy_id: EventId,
+        shared_key: &Keys,
+        public_key: PublicKey,
+    ) -> Result<EventId, Error> {
+        let keys: &Keys = self.keys();
+        let encrypted_shared_key = nips::nip04::encrypt(
+            keys.secret_key()?,
+            &public_key,
+            shared_key.secret_key()?.display_secret().to_string(),
+        )?;
+        let event: Event = EventBuilder::new(
+            SHARED_KEY_KIND,
+            encrypted_shared_key,
+            [Tag::event(policy_id), Tag::public_key(public_key)],
+        )
+        .to_event(keys)?;
+        let event_id: EventId = self.client.send_event(event).await?;
+        tracing::info!("Published shared key for {public_key} at event {event_id}");
+        Ok(event_id)
+    }
+
+    pub async fn republish_shared_key_for_policy(&self, policy_id: EventId) -> Result<(), Error> {
+        let shared_key: Keys = self.storage.shared_key(&policy_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&policy_id).await?;
+        // Publish the shared key
+        for public_key in public_keys.into_iter() {
+            // TODO: use send_batch_event method from nostr-sdk
+            self.publish_shared_key_for(policy_id, &shared_key, public_key)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Get the vaults received for which the shared key is still missing
+    pub async fn get_vaults_without_shared_key(&self) -> Vec<EventId> {
+        self.storage
+            .vaults_without_shared_key()
+            .await
+            .into_keys()
+            .collect()
+    }
+
+    /// Ask the other members of the vault to re-publish the shared key
+    ///
+    /// Any member that holds the shared key will automatically answer to the request.
+    pub async fn request_shared_key(&self, policy_id: EventId) -> Result<EventId, Error> {
+        let keys: &Keys = self.keys();
+        let mut vaults: HashMap<EventId, Vec<PublicKey>> =
+            self.storage.vaults_without_shared_key().await;
+        let public_keys: Vec<PublicKey> = vaults.remove(&policy_id).ok_or(Error::PolicyNotFound)?;
+
+        let mut tags: Vec<Tag> = vec![Tag::event(policy_id)];
+        tags.extend(
+            public_keys
+                .into_iter()
+                .filter(|p| p != &keys.public_key())
+                .map(Tag::public_key),
+        );
+        let event: Event = EventBuilder::new(SHARED_KEY_REQUEST_KIND, "", tags).to_event(keys)?;
+        Ok(self.client.send_event(event).await?)
+    }
+
+    /// Re-publish the shared key for a vault member that requested it
+    ///
+    /// The request is ignored if the author isn't a member of the vault or if the shared key
+    /// was already published for them after the request.
+    pub(crate) async fn handle_shared_key_request(&self, event: &Event) -> Result<(), Error> {
+        let policy_id: EventId = match event.event_ids().next() {
+            Some(id) => *id,
+            None => return Ok(()),
+        };
+        let requester: PublicKey = event.author();
+
+        let shared_key: Keys = match self.storage.shared_key(&policy_id).await {
+            Ok(shared_key) => shared_key,
+            Err(_) => return Ok(()),
+        };
+        let InternalPolicy { public_keys, .. } = match self.storage.vault(&policy_id).await {
+            Ok(vault) => vault,
+            Err(_) => return Ok(()),
+        };
+
+        if !public_keys.contains(&requester) {
+            tracing::warn!("Shared key request for {policy_id} from non-member {requester}");
+            return Ok(());
+        }
+
+        // Check if the shared key was already published (by any member) after the request
+        let filter: Filter = Filter::new()
+            .kind(SHARED_KEY_KIND)
+            .event(policy_id)
+            .pubkey(requester)
+            .since(event.created_at);
+        let count: usize = self.client.database().count(vec![filter]).await?;
+        if count > 0 {
+            tracing::debug!("Shared key for {policy_id} already re-published for {requester}");
+            return Ok(());
+        }
+
+        self.publish_shared_key_for(policy_id, &shared_key, requester)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use nostr_sdk::database::{NostrDatabaseExt, Order};
+use nostr_sdk::nips::nip04;
+use nostr_sdk::{Event, EventBuilder, EventId, Filter, Keys, PublicKey};
+use smartvaults_protocol::v1::constants::{SHARED_SIGNERS_KIND, SIGNER_REQUEST_KIND};
+use smartvaults_protocol::v1::{Serde, SignerRequest, SmartVaultsEventBuilder};
+
+use super::{Error, SmartVaults};
+use crate::types::{GetSigner, GetSignerRequest};
+
+impl SmartVaults {
+    /// Ask a contact to share a signer matching the request
+    pub async fn request_signer(
+        &self,
+        public_key: PublicKey,
+        request: SignerRequest,
+    ) -> Result<EventId, Error> {
+        let keys: &Keys = self.keys();
+        let event: Event = EventBuilder::signer_request(keys, &public_key, &request)?;
+        Ok(self.client.send_event(event).await?)
+    }
+
+    fn decrypt_signer_request(&self, event: &Event) -> Result<SignerRequest, Error> {
+        let content: String = nip04::decrypt(
+            self.keys().secret_key()?,
+            event.author_ref(),
+            &event.content,
+        )?;
+        Ok(SignerRequest::from_json(content)?)
+    }
+
+    /// Check if a signer was already shared with the requester after the request
+    async fn signer_request_answered(&self, event: &Event) -> Result<bool, Error> {
+        let filter: Filter = Filter::new()
+            .author(self.keys().public_key())
+            .kind(SHARED_SIGNERS_KIND)
+            .pubkey(event.author())
+            .since(event.created_at);
+        let count: usize = self.client.database().count(vec![filter]).await?;
+        Ok(count > 0)
+    }
+
+    /// Get the signer requests received from other users and not answered yet
+    pub async fn get_signer_requests(&self) -> Result<Vec<GetSignerRequest>, Error> {
+        let public_key: PublicKey = self.keys().public_key();
+        let filter: Filter = Filter::new().kind(SIGNER_REQUEST_KIND).pubkey(public_key);
+        let events: Vec<Event> = self
+            .client
+            .database()
+            .query(vec![filter], Order::Desc)
+            .await?;
+
+        let signers: Vec<GetSigner> = self.get_signers().await;
+        let mut requests: Vec<GetSignerRequest> = Vec::new();
+        for event in events.into_iter() {
+            if event.author() == public_key || self.signer_request_answered(&event).await? {
+                continue;
+            }
+
+            match self.decrypt_signer_request(&event) {
+                Ok(request) => {
+                    let matching: Vec<GetSigner> = signers
+                        .iter()
+                        .filter(|s| {
+                            s.descriptor_public_key()
+                                .map(|d| request.matches(&d))
+                                .unwrap_or_default()
+                        })
+                        .cloned()
+                        .collect();
+                    requests.push(GetSignerRequest {
+                        request_id: event.id,
+                        user: self.client.database().profile(event.author()).await?,
+                        request,
+                        matching,
+                        timestamp: event.created_at,
+                    });
+                }
+                Err(e) => tracing::error!("Impossible to decrypt signer request {}: {e}", event.id),
+            }
+        }
+
+        Ok(requests)
+    }
+
+    /// Share a signer with the author of the request
+    pub async fn answer_signer_request(
+        &self,
+        request_id: EventId,
+        signer_id: EventId,
+    ) -> Result<EventId, Error> {
+        let event: Event = self
+            .client
+            .database()
+            .event_by_id(request_id)
+            .await
+            .map_err(|_| Error::SignerRequestNotFound)?;
+        if event.kind != SIGNER_REQUEST_KIND {
+            return Err(Error::SignerRequestNotFound);
+        }
+
+        let request: SignerRequest = self.decrypt_signer_request(&event)?;
+        let signer = self.get_signer_by_id(signer_id).await?;
+        if !request.matches(&signer.descriptor_public_key()?) {
+            return Err(Error::SignerNotMatchingRequest);
+        }
+
+        self.share_signer(signer_id, event.author()).await
+    }
+}
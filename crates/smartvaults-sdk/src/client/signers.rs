@@ -1,7 +1,8 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::iter;
 
 use nostr_sdk::database::NostrDatabaseExt;
 use nostr_sdk::nips::nip04;
@@ -9,14 +10,17 @@ use nostr_sdk::{
     ClientMessage, Event, EventBuilder, EventId, Keys, Kind, Profile, PublicKey, RelaySendOptions,
     Tag,
 };
+use smartvaults_core::bitcoin::bip32::Fingerprint;
 use smartvaults_core::miniscript::Descriptor;
 use smartvaults_core::signer::{SharedSigner, Signer};
 use smartvaults_protocol::v1::constants::{SHARED_SIGNERS_KIND, SIGNERS_KIND};
 use smartvaults_protocol::v1::util::{Encryption, Serde};
 
 use super::{Error, SmartVaults};
-use crate::storage::InternalSharedSigner;
-use crate::types::{GetAllSigners, GetSharedSigner, GetSigner};
+use crate::storage::{InternalPolicy, InternalSharedSigner};
+use crate::types::{
+    GetAllSigners, GetSharedSigner, GetSigner, GetSignerDescriptors, GetSignerUsage,
+};
 
 impl SmartVaults {
     #[tracing::instrument(skip_all, level = "trace")]
@@ -24,7 +28,75 @@ impl SmartVaults {
         self.storage.signer(&signer_id).await
     }
 
-    pub async fn delete_signer_by_id(&self, signer_id: EventId) -> Result<(), Error> {
+    /// Get the vaults that reference the signer keys and the approvals made with it
+    pub async fn get_signer_usage(&self, signer_id: EventId) -> Result<GetSignerUsage, Error> {
+        let signer: Signer = self.storage.signer(&signer_id).await?;
+        let fingerprint: Fingerprint = signer.fingerprint();
+
+        let vaults: BTreeSet<EventId> = self
+            .storage
+            .vaults()
+            .await
+            .into_iter()
+            .filter(|(_, InternalPolicy { policy, .. })| {
+                policy
+                    .search_used_signers(iter::once(signer.clone()))
+                    .next()
+                    .is_some()
+            })
+            .map(|(policy_id, ..)| policy_id)
+            .collect();
+
+        let public_key: PublicKey = self.keys().public_key();
+        let approvals: BTreeMap<EventId, EventId> = self
+            .storage
+            .approvals()
+            .await
+            .into_iter()
+            .filter(|(_, a)| {
+                a.public_key == public_key && a.approval.signers().contains(&fingerprint)
+            })
+            .map(|(approval_id, a)| (approval_id, a.proposal_id))
+            .collect();
+
+        Ok(GetSignerUsage {
+            signer_id,
+            vaults,
+            approvals,
+        })
+    }
+
+    /// Get the account extended public key and the descriptors of the signer
+    pub async fn get_signer_descriptors(
+        &self,
+        signer_id: EventId,
+    ) -> Result<GetSignerDescriptors, Error> {
+        let signer: Signer = self.storage.signer(&signer_id).await?;
+        Ok(GetSignerDescriptors {
+            signer_id,
+            purpose: signer.purpose(),
+            xpub: signer.xpub()?,
+            external: signer.descriptor(),
+            internal: signer.change_descriptor().ok(),
+        })
+    }
+
+    /// Delete signer
+    ///
+    /// Refuse to delete a signer used by a vault (returning the affected vaults), unless `force` is `true`.
+    pub async fn delete_signer_by_id(&self, signer_id: EventId, force: bool) -> Result<(), Error> {
+        let usage: GetSignerUsage = self.get_signer_usage(signer_id).await?;
+        if usage.is_used() {
+            if !force {
+                return Err(Error::SignerInUse(usage.vaults.into_iter().collect()));
+            }
+
+            tracing::warn!(
+                "Deleting signer {signer_id} used by {} vaults",
+                usage.vaults.len()
+            );
+        }
+
         let my_shared_signers = self
             .storage
             .get_my_shared_signers_by_signer_id(&signer_id)
@@ -302,4 +374,15 @@ impl SmartVaults {
             })
             .collect())
     }
+
+    /// Get the default shared signer of a contact (`None` if the contact hasn't shared any signer yet)
+    pub async fn get_default_shared_signer(
+        &self,
+        public_key: PublicKey,
+    ) -> Result<Option<GetSharedSigner>, Error> {
+        let mut signers: Vec<GetSharedSigner> =
+            self.get_shared_signers_by_public_key(public_key).await?;
+        signers.sort();
+        Ok(signers.into_iter().next())
+    }
 }
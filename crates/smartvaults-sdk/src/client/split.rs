@@ -0,0 +1,130 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::{BTreeMap, HashSet};
+
+use nostr_sdk::EventId;
+use smartvaults_core::bdk::FeeRate as BdkFeeRate;
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::psbt::PartiallySignedTransaction;
+use smartvaults_core::bitcoin::{Address, OutPoint, Txid};
+use smartvaults_core::{Amount, FeeRate, Proposal, TxOptions};
+
+use super::{Error, SmartVaults};
+use crate::types::{GetProposal, GetSplitSpendProgress};
+
+impl SmartVaults {
+    /// Check that the PSBT not exceed the max number of inputs set in the config
+    pub(crate) async fn check_proposal_inputs(
+        &self,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<(), Error> {
+        let max: usize = self.config.max_proposal_inputs().await;
+        let inputs: usize = psbt.unsigned_tx.input.len();
+        if inputs > max {
+            return Err(Error::TooManyInputs {
+                inputs,
+                max,
+                size: psbt.serialize().len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Send all funds splitting the spending in multiple sequential proposals
+    ///
+    /// Every proposal spends at most the max number of inputs set in the config (biggest UTXOs first)
+    /// and has the part number appended to the description (i.e. `Consolidation (2/5)`).
+    /// UTXOs frozen by other proposals are skipped.
+    pub async fn spend_split<S>(
+        &self,
+        policy_id: EventId,
+        address: Address<NetworkUnchecked>,
+        description: S,
+        fee_rate: FeeRate,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+    ) -> Result<Vec<GetProposal>, Error>
+    where
+        S: Into<String>,
+    {
+        let description: String = description.into();
+        let max: usize = self.config.max_proposal_inputs().await;
+        let fee_rate: BdkFeeRate = self.calculate_fee_rate(fee_rate).await?;
+
+        let frozen: HashSet<OutPoint> = self.get_frozen_utxos(policy_id).await?;
+        let mut utxos = self.manager.get_utxos(policy_id).await?;
+        utxos.retain(|utxo| !frozen.contains(&utxo.outpoint));
+        utxos.sort_by(|a, b| b.txout.value.cmp(&a.txout.value));
+        let utxos: Vec<OutPoint> = utxos.into_iter().map(|utxo| utxo.outpoint).collect();
+
+        let batches: Vec<&[OutPoint]> = utxos.chunks(max).collect();
+        let total: usize = batches.len();
+        let mut proposals: Vec<GetProposal> = Vec::with_capacity(total);
+
+        for (index, batch) in batches.into_iter().enumerate() {
+            let proposal: Proposal = self
+                .manager
+                .spend(
+                    policy_id,
+                    address.clone(),
+                    Amount::Max,
+                    format!("{description} ({}/{total})", index + 1),
+                    fee_rate,
+                    Some(batch.to_vec()),
+                    None,
+                    policy_path.clone(),
+                    TxOptions::default(),
+                )
+                .await?;
+            proposals.push(
+                self.publish_spending_proposal(policy_id, proposal, None, None)
+                    .await?,
+            );
+        }
+
+        Ok(proposals)
+    }
+
+    /// Get the combined progress of the proposals created by [`SmartVaults::spend_split`]
+    pub async fn get_split_spend_progress(
+        &self,
+        proposals: &[GetProposal],
+    ) -> Result<GetSplitSpendProgress, Error> {
+        let completed_txs: Vec<(EventId, Txid)> = self
+            .storage
+            .completed_proposals()
+            .await
+            .into_iter()
+            .filter_map(|(id, p)| p.proposal.tx().map(|tx| (id, tx.txid())))
+            .collect();
+
+        let mut progress = GetSplitSpendProgress {
+            total: proposals.len(),
+            completed: Vec::new(),
+            pending: Vec::new(),
+            deleted: Vec::new(),
+        };
+
+        for GetProposal {
+            proposal_id,
+            proposal,
+            ..
+        } in proposals.iter()
+        {
+            if self.storage.proposal(proposal_id).await.is_ok() {
+                progress
+                    .pending
+                    .push(self.get_proposal_status(*proposal_id).await?);
+                continue;
+            }
+
+            let txid: Txid = proposal.psbt().unsigned_tx.txid();
+            match completed_txs.iter().find(|(_, t)| *t == txid) {
+                Some((completed_proposal_id, _)) => progress.completed.push(*completed_proposal_id),
+                None => progress.deleted.push(*proposal_id),
+            }
+        }
+
+        Ok(progress)
+    }
+}
@@ -0,0 +1,102 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Stale approvals
+//!
+//! The approvals signed against an earlier PSBT of a rebuilt proposal are not deleted: they are
+//! filtered out at read time (see `InternalApproval::commits_to`). Their approvers are asked,
+//! once, to re-approve the proposal with a comment listing the stale approvals.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use nostr_sdk::database::NostrDatabaseExt;
+use nostr_sdk::{EventId, PublicKey};
+use smartvaults_protocol::v1::ProposalComment;
+
+use super::{Error, SmartVaults};
+
+/// Stale approvals not reported yet by a comment
+fn unreported<'a, I>(
+    stale_approvals: BTreeMap<EventId, PublicKey>,
+    comments: I,
+) -> BTreeMap<EventId, PublicKey>
+where
+    I: IntoIterator<Item = &'a ProposalComment>,
+{
+    let reported: HashSet<EventId> = comments
+        .into_iter()
+        .flat_map(|comment| comment.reported_stale_approvals())
+        .collect();
+    stale_approvals
+        .into_iter()
+        .filter(|(approval_id, _)| !reported.contains(approval_id))
+        .collect()
+}
+
+impl SmartVaults {
+    /// Ask the approvers of the stale approvals to re-approve the proposal
+    ///
+    /// The approvals already reported (by any member) are skipped, so a comment is published only
+    /// for new stale approvals.
+    pub(super) async fn report_stale_approvals(
+        &self,
+        proposal_id: EventId,
+        stale_approvals: BTreeMap<EventId, PublicKey>,
+    ) -> Result<(), Error> {
+        let comments = self.storage.comments_by_proposal_id(&proposal_id).await;
+        let stale_approvals = unreported(stale_approvals, comments.iter().map(|(_, c)| &c.comment));
+        if stale_approvals.is_empty() {
+            return Ok(());
+        }
+
+        let approvers: BTreeSet<PublicKey> = stale_approvals.values().copied().collect();
+        let mut names: Vec<String> = Vec::with_capacity(approvers.len());
+        for public_key in approvers.into_iter() {
+            names.push(self.client.database().profile(public_key).await?.name());
+        }
+        let message: String = format!(
+            "The approvals of {} were signed against an earlier version of the transaction and \
+             are no longer valid: please re-approve the proposal",
+            names.join(", ")
+        );
+        let comment =
+            ProposalComment::stale_approvals(message, stale_approvals.into_keys().collect());
+        self.publish_comment(proposal_id, comment).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr_sdk::Keys;
+
+    use super::*;
+
+    #[test]
+    fn test_unreported() {
+        let alice = Keys::generate().public_key();
+        let bob = Keys::generate().public_key();
+        let first = EventId::all_zeros();
+        let second = EventId::from_slice(&[1; 32]).unwrap();
+        let stale: BTreeMap<EventId, PublicKey> = [(first, alice), (second, bob)].into();
+
+        // Nothing reported yet
+        let comments: Vec<ProposalComment> = vec![ProposalComment::new("why?")];
+        assert_eq!(unreported(stale.clone(), &comments), stale);
+
+        // Already reported approvals are skipped (no comment on next finalize attempt)
+        let comments = vec![
+            ProposalComment::new("why?"),
+            ProposalComment::stale_approvals("re-approve", vec![first]),
+        ];
+        let expected: BTreeMap<EventId, PublicKey> = [(second, bob)].into();
+        assert_eq!(unreported(stale.clone(), &comments), expected);
+
+        let comments = vec![ProposalComment::stale_approvals(
+            "re-approve",
+            vec![first, second],
+        )];
+        assert!(unreported(stale, &comments).is_empty());
+    }
+}
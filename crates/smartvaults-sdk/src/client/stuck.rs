@@ -0,0 +1,43 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Stuck transactions
+//!
+//! A broadcast transaction is considered stuck while it's unconfirmed and its fee rate is below
+//! the last low priority mempool fee estimate. The stuck transactions of the loaded vaults are
+//! reported with a [`Message::StuckTx`](super::Message::StuckTx), with the suggested actions
+//! (RBF and/or CPFP) to speed up the confirmation.
+
+use nostr_sdk::EventId;
+use smartvaults_core::bdk::FeeRate;
+use smartvaults_core::bitcoin::Txid;
+use smartvaults_core::Priority;
+
+use super::SmartVaults;
+use crate::types::StuckTransaction;
+
+impl SmartVaults {
+    /// Low priority mempool fee rate (`None` until the mempool fees are synced)
+    pub(super) async fn mempool_min_fee_rate(&self) -> Option<FeeRate> {
+        self.manager
+            .mempool_fees()
+            .await
+            .get(&Priority::Low)
+            .copied()
+    }
+
+    /// Get the stuck transactions of the loaded vaults
+    pub(super) async fn stuck_txs(&self) -> Vec<(EventId, Txid, StuckTransaction)> {
+        let mut list = Vec::new();
+        if let Some(mempool_min_fee_rate) = self.mempool_min_fee_rate().await {
+            for (policy_id, wallet) in self.manager.loaded_wallets().await.into_iter() {
+                for tx in wallet.txs().await.into_iter() {
+                    if let Some(stuck) = StuckTransaction::check(&tx, mempool_min_fee_rate) {
+                        list.push((policy_id, tx.txid(), stuck));
+                    }
+                }
+            }
+        }
+        list
+    }
+}
@@ -1,14 +1,13 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::ops::Add;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use async_utility::thread;
 use futures_util::stream::AbortHandle;
-use nostr_sdk::database::NostrDatabaseExt;
 use nostr_sdk::nips::nip46::{Message as NIP46Message, Request as NIP46Request};
 use nostr_sdk::nips::{nip04, nip65};
 use nostr_sdk::{
@@ -18,30 +17,44 @@ use nostr_sdk::{
 };
 use smartvaults_core::bdk::chain::ConfirmationTime;
 use smartvaults_core::bdk::FeeRate;
-use smartvaults_core::bitcoin::Network;
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_core::bitcoin::Txid;
 use smartvaults_core::{CompletedProposal, Priority};
 use smartvaults_protocol::v1::constants::{
-    APPROVED_PROPOSAL_KIND, COMPLETED_PROPOSAL_KIND, KEY_AGENT_SIGNALING,
-    KEY_AGENT_SIGNER_OFFERING_KIND, KEY_AGENT_VERIFIED, LABELS_KIND, POLICY_KIND, PROPOSAL_KIND,
-    SHARED_KEY_KIND, SHARED_SIGNERS_KIND, SIGNERS_KIND, SMARTVAULTS_MAINNET_PUBLIC_KEY,
-    SMARTVAULTS_TESTNET_PUBLIC_KEY,
+    CAPABILITIES_KIND, DEVICE_KIND, PAYEES_IDENTIFIER, PAYMENT_REQUEST_KIND,
+    RECURRING_PAYMENT_KIND, SHARED_KEY_REQUEST_KIND, SIGNER_REQUEST_KIND,
 };
 use tokio::sync::broadcast::Receiver;
 
-use super::{Error, SmartVaults};
-use crate::constants::DEFAULT_SUBSCRIPTION_ID;
+use super::{outbox, Error, SmartVaults};
+use crate::constants::{
+    DEFAULT_SUBSCRIPTION_ID, EXPIRED_PROPOSALS_CHECK_INTERVAL, MEMPOOL_WATCHER_INTERVAL,
+    RECOVERY_PATHS_CHECK_INTERVAL, RECURRING_PAYMENTS_CHECK_INTERVAL, STUCK_TXS_CHECK_INTERVAL,
+};
+use crate::manager::mempool::MempoolWatcher;
 use crate::storage::{InternalCompletedProposal, InternalPolicy};
+use crate::types::StuckTransaction;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum EventHandled {
     SharedKey(EventId),
+    SharedKeyRequest(EventId),
     Policy(EventId),
     Proposal(EventId),
-    Approval { proposal_id: EventId },
+    Approval {
+        proposal_id: EventId,
+    },
+    ProposalComment {
+        proposal_id: EventId,
+    },
     CompletedProposal(EventId),
     Signer(EventId),
     MySharedSigner(EventId),
     SharedSigner(EventId),
+    SignerRequest(EventId),
+    PaymentRequest(EventId),
+    /// Recurring payment created or updated (vault ID)
+    RecurringPayment(EventId),
     Contacts,
     Metadata(PublicKey),
     NostrConnectRequest(EventId),
@@ -50,14 +63,59 @@ pub enum EventHandled {
     RelayList,
     KeyAgentSignerOffering,
     VerifiedKeyAgents,
+    Device,
+    AppSettings,
+    /// Saved payees updated by another device
+    Payees,
+    Capabilities(PublicKey),
+    Plugin {
+        kind: Kind,
+        vault_id: EventId,
+    },
+    VaultMetadata(EventId),
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     EventHandled(EventHandled),
     WalletSyncCompleted(EventId),
+    /// The timechain sync of a wallet failed (the other wallets are not affected)
+    WalletSyncFailed {
+        policy_id: EventId,
+        error: String,
+    },
     BlockHeightUpdated,
     MempoolFeesUpdated(BTreeMap<Priority, FeeRate>),
+    /// This device was revoked by another one
+    DeviceRevoked,
+    /// The vault UTXOs don't match the ones reported by the cross-check backend
+    BalanceMismatch(EventId),
+    /// Unconfirmed transaction detected by the mempool watcher (before the next wallet sync)
+    UnconfirmedTx {
+        policy_id: EventId,
+        txid: Txid,
+    },
+    /// The admission status of a relay changed (i.e. a payment is required to publish)
+    RelayAdmissionChanged(Url),
+    /// A vault or a shared signer includes keys marked as compromised
+    CompromisedKeys {
+        /// Vault or shared signer ID
+        event_id: EventId,
+        fingerprints: BTreeSet<Fingerprint>,
+    },
+    /// A recovery path of a watched vault matured
+    RecoveryPathMatured {
+        policy_id: EventId,
+        signer_id: EventId,
+        /// Sweep proposal made automatically
+        proposal_id: Option<EventId>,
+    },
+    /// Broadcast transaction with a fee rate fallen below the current mempool minimum
+    StuckTx {
+        policy_id: EventId,
+        txid: Txid,
+        stuck: StuckTransaction,
+    },
 }
 
 impl SmartVaults {
@@ -65,20 +123,29 @@ impl SmartVaults {
         let this = self.clone();
         Ok(thread::abortable(async move {
             loop {
-                match this.config.electrum_endpoint().await {
-                    Ok(endpoint) => {
-                        let proxy = this.config.proxy().await.ok();
-                        match this.manager.sync_block_height(endpoint, proxy).await {
-                            Ok(_) => {
-                                let _ = this.sync_channel.send(Message::BlockHeightUpdated);
+                if !this.scheduler.is_paused().await {
+                    match this.config.electrum_endpoint().await {
+                        Ok(endpoint) => {
+                            let proxy = this.config.proxy().await.ok();
+                            match this
+                                .manager
+                                .sync_block_height(endpoint.clone(), proxy)
+                                .await
+                            {
+                                Ok(_) => {
+                                    let _ = this.sync_channel.send(Message::BlockHeightUpdated);
+                                }
+                                Err(e) => tracing::error!("Impossible to sync block height: {e}"),
+                            }
+                            if let Err(e) = this.manager.sync_headers(endpoint, proxy).await {
+                                tracing::error!("Impossible to sync header chain: {e}");
                             }
-                            Err(e) => tracing::error!("Impossible to sync block height: {e}"),
                         }
+                        Err(e) => tracing::error!("Impossible to sync wallets: {e}"),
                     }
-                    Err(e) => tracing::error!("Impossible to sync wallets: {e}"),
                 }
 
-                thread::sleep(Duration::from_secs(10)).await;
+                this.scheduler.wait().await;
             }
         })?)
     }
@@ -87,21 +154,23 @@ impl SmartVaults {
         let this = self.clone();
         Ok(thread::abortable(async move {
             loop {
-                match this.config.electrum_endpoint().await {
-                    Ok(endpoint) => {
-                        let proxy = this.config.proxy().await.ok();
-                        match this.manager.sync_mempool_fees(endpoint, proxy).await {
-                            Ok(Some(fees)) => {
-                                let _ = this.sync_channel.send(Message::MempoolFeesUpdated(fees));
+                if !this.scheduler.is_paused().await {
+                    match this.fee_estimator().await {
+                        Ok(estimator) => {
+                            match this.manager.sync_mempool_fees(estimator.as_ref()).await {
+                                Ok(Some(fees)) => {
+                                    let _ =
+                                        this.sync_channel.send(Message::MempoolFeesUpdated(fees));
+                                }
+                                Ok(None) => (),
+                                Err(e) => tracing::error!("Impossible to get mempool fees: {e}"),
                             }
-                            Ok(None) => (),
-                            Err(e) => tracing::error!("Impossible to get mempool fees: {e}"),
                         }
+                        Err(e) => tracing::error!("Impossible to get mempool fees: {e}"),
                     }
-                    Err(e) => tracing::error!("Impossible to get mempool fees: {e}"),
                 }
 
-                thread::sleep(Duration::from_secs(10)).await;
+                this.scheduler.wait().await;
             }
         })?)
     }
@@ -110,21 +179,137 @@ impl SmartVaults {
         let this = self.clone();
         Ok(thread::abortable(async move {
             loop {
-                match this.config.electrum_endpoint().await {
-                    Ok(endpoint) => {
-                        let proxy = this.config.proxy().await.ok();
-                        if let Err(e) = this
-                            .manager
-                            .sync_all(endpoint, proxy, Some(this.sync_channel.clone()))
-                            .await
-                        {
-                            tracing::error!("Impossible to sync all wallets: {e}");
+                if !this.scheduler.is_paused().await {
+                    match this.config.electrum_endpoint().await {
+                        Ok(endpoint) => {
+                            let proxy = this.config.proxy().await.ok();
+                            let max_concurrent: usize = this.config.max_concurrent_syncs().await;
+                            if let Err(e) = this
+                                .manager
+                                .sync_all(
+                                    endpoint,
+                                    proxy,
+                                    false,
+                                    max_concurrent,
+                                    Some(this.sync_channel.clone()),
+                                )
+                                .await
+                            {
+                                tracing::error!("Impossible to sync all wallets: {e}");
+                            }
                         }
+                        Err(e) => tracing::error!("Impossible to sync wallets: {e}"),
                     }
-                    Err(e) => tracing::error!("Impossible to sync wallets: {e}"),
                 }
 
-                thread::sleep(Duration::from_secs(10)).await;
+                this.scheduler.wait().await;
+            }
+        })?)
+    }
+
+    fn mempool_watcher(&self) -> Result<AbortHandle, Error> {
+        let this = self.clone();
+        Ok(thread::abortable(async move {
+            let mut watcher: Option<MempoolWatcher> = None;
+            loop {
+                if !this.scheduler.is_paused().await {
+                    match this.config.electrum_endpoint().await {
+                        Ok(endpoint) => {
+                            // (Re)connect if not connected or if the endpoint changed
+                            if watcher.as_ref().map_or(true, |w| *w.endpoint() != endpoint) {
+                                let proxy = this.config.proxy().await.ok();
+                                watcher = match MempoolWatcher::new(endpoint, proxy) {
+                                    Ok(w) => Some(w),
+                                    Err(e) => {
+                                        tracing::error!("Impossible to start mempool watcher: {e}");
+                                        None
+                                    }
+                                };
+                            }
+
+                            if let Some(w) = watcher.as_mut() {
+                                match this.manager.watch_mempool(w).await {
+                                    Ok(txs) => {
+                                        for (policy_id, txid) in txs.into_iter() {
+                                            let _ = this
+                                                .sync_channel
+                                                .send(Message::UnconfirmedTx { policy_id, txid });
+                                        }
+                                    }
+                                    Err(e) => {
+                                        tracing::error!("Mempool watcher error: {e}");
+                                        watcher = None;
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => tracing::error!("Impossible to watch mempool: {e}"),
+                    }
+                }
+
+                thread::sleep(MEMPOOL_WATCHER_INTERVAL).await;
+            }
+        })?)
+    }
+
+    fn recurring_payments_scheduler(&self) -> Result<AbortHandle, Error> {
+        let this = self.clone();
+        Ok(thread::abortable(async move {
+            loop {
+                if !this.scheduler.is_paused().await {
+                    if let Err(e) = this.process_recurring_payments().await {
+                        tracing::error!("Impossible to process recurring payments: {e}");
+                    }
+                }
+                thread::sleep(RECURRING_PAYMENTS_CHECK_INTERVAL).await;
+            }
+        })?)
+    }
+
+    fn expired_proposals_cleaner(&self) -> Result<AbortHandle, Error> {
+        let this = self.clone();
+        Ok(thread::abortable(async move {
+            loop {
+                this.delete_expired_proposals().await;
+                thread::sleep(EXPIRED_PROPOSALS_CHECK_INTERVAL).await;
+            }
+        })?)
+    }
+
+    fn recovery_paths_monitor(&self) -> Result<AbortHandle, Error> {
+        let this = self.clone();
+        Ok(thread::abortable(async move {
+            // Notify a matured path only once per session
+            let mut notified: HashSet<(EventId, EventId)> = HashSet::new();
+            loop {
+                if !this.scheduler.is_paused().await {
+                    if let Err(e) = this.process_recovery_watches(&mut notified).await {
+                        tracing::error!("Impossible to check recovery paths: {e}");
+                    }
+                }
+                thread::sleep(RECOVERY_PATHS_CHECK_INTERVAL).await;
+            }
+        })?)
+    }
+
+    fn stuck_txs_monitor(&self) -> Result<AbortHandle, Error> {
+        let this = self.clone();
+        Ok(thread::abortable(async move {
+            // Notify a stuck transaction only once per session
+            let mut notified: HashSet<Txid> = HashSet::new();
+            loop {
+                if !this.scheduler.is_paused().await {
+                    for (policy_id, txid, stuck) in this.stuck_txs().await.into_iter() {
+                        if notified.insert(txid) {
+                            let _ = this.sync_channel.send(Message::StuckTx {
+                                policy_id,
+                                txid,
+                                stuck,
+                            });
+                        }
+                    }
+                }
+                thread::sleep(STUCK_TXS_CHECK_INTERVAL).await;
             }
         })?)
     }
@@ -148,64 +333,6 @@ impl SmartVaults {
         self.sync_channel.subscribe()
     }
 
-    pub(crate) async fn sync_filters(&self, since: Timestamp) -> Vec<Filter> {
-        let base_filter = Filter::new().kinds([
-            POLICY_KIND,
-            PROPOSAL_KIND,
-            APPROVED_PROPOSAL_KIND,
-            COMPLETED_PROPOSAL_KIND,
-            SHARED_KEY_KIND,
-            SIGNERS_KIND,
-            SHARED_SIGNERS_KIND,
-            LABELS_KIND,
-            Kind::EventDeletion,
-        ]);
-
-        let keys: &Keys = self.keys();
-        let public_key: PublicKey = keys.public_key();
-        let contacts: Vec<PublicKey> = self
-            .client
-            .database()
-            .contacts_public_keys(public_key)
-            .await
-            .unwrap_or_default();
-
-        let author_filter: Filter = base_filter.clone().author(public_key).since(since);
-        let pubkey_filter: Filter = base_filter.pubkey(public_key).since(since);
-        let nostr_connect_filter = Filter::new()
-            .pubkey(public_key)
-            .kind(Kind::NostrConnect)
-            .since(since);
-        let other_filters: Filter = Filter::new()
-            .author(public_key)
-            .kinds([Kind::Metadata, Kind::ContactList, Kind::RelayList])
-            .since(since);
-        let key_agents: Filter = Filter::new()
-            .kinds([KEY_AGENT_SIGNALING, KEY_AGENT_SIGNER_OFFERING_KIND])
-            .since(since);
-        let smartvaults: Filter = Filter::new()
-            .author(match self.network {
-                Network::Bitcoin => *SMARTVAULTS_MAINNET_PUBLIC_KEY,
-                _ => *SMARTVAULTS_TESTNET_PUBLIC_KEY,
-            })
-            .kind(KEY_AGENT_VERIFIED);
-
-        let mut filters = vec![
-            author_filter,
-            pubkey_filter,
-            nostr_connect_filter,
-            other_filters,
-            key_agents,
-            smartvaults,
-        ];
-
-        if !contacts.is_empty() {
-            filters.push(Filter::new().authors(contacts).since(since));
-        }
-
-        filters
-    }
-
     pub(crate) fn sync(&self) -> Result<(), Error> {
         if self.syncing.load(Ordering::SeqCst) {
             tracing::warn!("Syncing threads are already running");
@@ -219,20 +346,20 @@ impl SmartVaults {
                 let block_height_syncer: AbortHandle = this.block_height_syncer()?;
                 let mempool_fees_syncer: AbortHandle = this.mempool_fees_syncer()?;
                 let policies_syncer: AbortHandle = this.policies_syncer()?;
+                let mempool_watcher: AbortHandle = this.mempool_watcher()?;
+                let recurring_payments_scheduler: AbortHandle =
+                    this.recurring_payments_scheduler()?;
+                let expired_proposals_cleaner: AbortHandle = this.expired_proposals_cleaner()?;
+                let stuck_txs_monitor: AbortHandle = this.stuck_txs_monitor()?;
+                let recovery_paths_monitor: AbortHandle = this.recovery_paths_monitor()?;
 
                 // Pending events handler
                 let pending_event_handler = this.handle_pending_events()?;
 
+                this.discard_future_sync_windows().await;
+
                 for (relay_url, relay) in this.client.relays().await {
-                    let last_sync: Timestamp =
-                        match this.db.get_last_relay_sync(relay_url.clone()).await {
-                            Ok(ts) => ts,
-                            Err(e) => {
-                                tracing::error!("Impossible to get last relay sync: {e}");
-                                Timestamp::from(0)
-                            }
-                        };
-                    let filters: Vec<Filter> = this.sync_filters(last_sync).await;
+                    let filters: Vec<Filter> = this.relay_sync_filters(&relay_url).await;
                     if let Err(e) = relay
                         .subscribe_with_id(
                             SubscriptionId::new(DEFAULT_SUBSCRIPTION_ID),
@@ -245,11 +372,36 @@ impl SmartVaults {
                     }
                 }
 
+                this.subscribe_plugins().await;
+
+                // Publish the capabilities of this client
+                let capabilities = this.clone();
+                thread::spawn(async move {
+                    capabilities.publish_outdated_capabilities().await;
+                })?;
+
+                // Check the admission of the relays not checked yet
+                let admission = this.clone();
+                thread::spawn(async move {
+                    let checked = admission.get_relays_admission().await;
+                    for relay_url in admission.client.relays().await.into_keys() {
+                        if !checked.contains_key(&relay_url) {
+                            if let Err(e) = admission.check_relay_admission(relay_url.clone()).await
+                            {
+                                tracing::warn!("Impossible to check admission of {relay_url}: {e}");
+                            }
+                        }
+                    }
+                })?;
+
                 let _ = this
                     .client
                     .handle_notifications(|notification| async {
                         match notification {
-                            RelayPoolNotification::Event { event, ..} => {
+                            RelayPoolNotification::Event { relay_url, subscription_id, event } => {
+                                if subscription_id == SubscriptionId::new(DEFAULT_SUBSCRIPTION_ID) {
+                                    this.track_relay_sync_event(&relay_url, &event).await;
+                                }
                                 let event_id = event.id;
                                 if event.is_expired() {
                                     tracing::warn!("Event {event_id} expired");
@@ -257,25 +409,50 @@ impl SmartVaults {
                                     tracing::error!("Impossible to handle event {event_id}: {e}");
                                 }
                             }
-                            RelayPoolNotification::Message { relay_url, message } => {
-                                if let RelayMessage::EndOfStoredEvents(subscription_id) = message {
+                            RelayPoolNotification::Message { relay_url, message } => match message {
+                                RelayMessage::EndOfStoredEvents(subscription_id) => {
                                     tracing::debug!("Received new EOSE for {relay_url} with subid {subscription_id}");
-                                        if subscription_id == SubscriptionId::new(DEFAULT_SUBSCRIPTION_ID) {
-                                            if let Err(e) = this
-                                                .db
-                                                .save_last_relay_sync(relay_url, Timestamp::now()).await
-                                            {
-                                                tracing::error!("Impossible to save last relay sync: {e}");
+                                    if subscription_id == SubscriptionId::new(DEFAULT_SUBSCRIPTION_ID) {
+                                        this.save_relay_sync_windows(relay_url.clone()).await;
+
+                                        // Re-fetch the windows missed by the previous syncs
+                                        let gaps = this.clone();
+                                        let spawned = thread::spawn(async move {
+                                            match gaps.refetch_sync_gaps(relay_url.clone()).await {
+                                                Ok(events) => {
+                                                    for event in events.into_iter() {
+                                                        let event_id = event.id;
+                                                        if let Err(e) = gaps.handle_event(event).await {
+                                                            tracing::error!("Impossible to handle event {event_id}: {e}");
+                                                        }
+                                                    }
+                                                }
+                                                Err(e) => tracing::error!("Impossible to re-fetch sync gaps from {relay_url}: {e}"),
                                             }
+                                        });
+                                        if let Err(e) = spawned {
+                                            tracing::error!("Impossible to spawn sync gaps re-fetch: {e}");
                                         }
+                                    }
                                 }
-                            }
+                                RelayMessage::Ok { status, message, .. } => {
+                                    if let Err(e) = this.handle_relay_ok(relay_url, status, &message).await {
+                                        tracing::error!("Impossible to update relay admission: {e}");
+                                    }
+                                }
+                                _ => (),
+                            },
                             RelayPoolNotification::RelayStatus { .. } => (),
                             RelayPoolNotification::Stop | RelayPoolNotification::Shutdown => {
                                 tracing::debug!("Received stop/shutdown msg");
                                 block_height_syncer.abort();
                                 mempool_fees_syncer.abort();
                                 policies_syncer.abort();
+                                mempool_watcher.abort();
+                                recurring_payments_scheduler.abort();
+                                expired_proposals_cleaner.abort();
+                                stuck_txs_monitor.abort();
+                                recovery_paths_monitor.abort();
                                 pending_event_handler.abort();
                                 let _ = this.syncing.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(false));
                             }
@@ -292,9 +469,35 @@ impl SmartVaults {
             // Negentropy reconciliation
             let this = self.clone();
             thread::spawn(async move {
+                // If previous session was closed gracefully, reconcile only events newer than the checkpoint
+                let since: Timestamp = match this.db.get_checkpoint().await {
+                    Ok(checkpoint) if checkpoint.clean => {
+                        tracing::info!("Resuming from checkpoint {}", checkpoint.timestamp);
+                        outbox::resume_since(checkpoint.timestamp)
+                    }
+                    _ => Timestamp::from(0),
+                };
+
+                // Invalidate checkpoint until next graceful shutdown
+                if let Err(e) = this.db.invalidate_checkpoint().await {
+                    tracing::error!("Impossible to invalidate checkpoint: {e}");
+                }
+
                 let opts = NegentropyOptions::new().direction(NegentropyDirection::Both);
-                for filter in this.sync_filters(Timestamp::from(0)).await.into_iter() {
-                    this.client.reconcile(filter, opts).await.unwrap();
+                for filter in this.sync_filters(since).await.into_iter() {
+                    if let Err(e) = this.client.reconcile(filter, opts).await {
+                        tracing::error!("Impossible to reconcile events: {e}");
+                    }
+                }
+
+                // Send the events not delivered before the last shutdown
+                if let Err(e) = this.send_outbox().await {
+                    tracing::error!("Impossible to send outbox events: {e}");
+                }
+
+                // Announce device after reconciliation, to not override a revocation
+                if let Err(e) = this.announce_device().await {
+                    tracing::error!("Impossible to announce device: {e}");
                 }
             })?;
         }
@@ -302,7 +505,7 @@ impl SmartVaults {
         Ok(())
     }
 
-    async fn handle_event(&self, event: Event) -> Result<()> {
+    pub(super) async fn handle_event(&self, event: Event) -> Result<()> {
         if event.kind == Kind::ContactList {
             let pubkeys = event.public_keys().copied();
             let filter: Filter = Filter::new().authors(pubkeys).kind(Kind::Metadata);
@@ -383,7 +586,29 @@ impl SmartVaults {
                             .await?;
                     }
                     _ => {
-                        if self
+                        if !self
+                            .is_nostr_connect_request_allowed(event.author(), &request)
+                            .await?
+                        {
+                            let uri = self.db.get_nostr_connect_session(event.author()).await?;
+                            let msg = msg.generate_error_response("Request not allowed")?;
+                            let nip46_event =
+                                EventBuilder::nostr_connect(keys, uri.public_key, msg)?
+                                    .to_event(keys)?;
+                            self.client
+                                .pool()
+                                .send_msg_to(
+                                    [uri.relay_url],
+                                    ClientMessage::event(nip46_event),
+                                    RelaySendOptions::new().skip_send_confirmation(true),
+                                )
+                                .await?;
+                            tracing::info!(
+                                "Rejected nostr connect request {} for app {}: not allowed by profile",
+                                event.id,
+                                event.author()
+                            );
+                        } else if self
                             .db
                             .is_nostr_connect_session_pre_authorized(event.author())
                             .await
@@ -436,11 +661,67 @@ impl SmartVaults {
                     EventHandled::NostrConnectRequest(event.id),
                 ))?;
             }
+        } else if event.kind == Kind::ApplicationSpecificData {
+            if event.author() == self.keys().public_key() {
+                if event.identifier() == Some(PAYEES_IDENTIFIER) {
+                    self.sync_channel
+                        .send(Message::EventHandled(EventHandled::Payees))?;
+                } else {
+                    self.handle_app_settings_event(&event).await?;
+                    self.sync_channel
+                        .send(Message::EventHandled(EventHandled::AppSettings))?;
+                }
+            }
+        } else if event.kind == DEVICE_KIND {
+            if event.author() == self.keys().public_key() {
+                self.handle_device_event(&event).await?;
+                self.sync_channel
+                    .send(Message::EventHandled(EventHandled::Device))?;
+            }
+        } else if event.kind == CAPABILITIES_KIND {
+            if event.author() != self.keys().public_key() {
+                if let Some(vault_id) = event.event_ids().next() {
+                    self.warn_incompatible_cosigners(*vault_id, false).await;
+                }
+                self.sync_channel
+                    .send(Message::EventHandled(EventHandled::Capabilities(
+                        event.author(),
+                    )))?;
+            }
+        } else if event.kind == SIGNER_REQUEST_KIND {
+            if event.author() != self.keys().public_key() {
+                self.sync_channel
+                    .send(Message::EventHandled(EventHandled::SignerRequest(event.id)))?;
+            }
+        } else if event.kind == PAYMENT_REQUEST_KIND {
+            self.sync_channel
+                .send(Message::EventHandled(EventHandled::PaymentRequest(
+                    event.id,
+                )))?;
+        } else if event.kind == RECURRING_PAYMENT_KIND {
+            if let Some(vault_id) = event.event_ids().next() {
+                self.sync_channel
+                    .send(Message::EventHandled(EventHandled::RecurringPayment(
+                        *vault_id,
+                    )))?;
+            }
+        } else if event.kind == SHARED_KEY_REQUEST_KIND {
+            if event.author() != self.keys().public_key() {
+                self.handle_shared_key_request(&event).await?;
+                self.sync_channel
+                    .send(Message::EventHandled(EventHandled::SharedKeyRequest(
+                        event.id,
+                    )))?;
+            }
+        } else if self.plugins.get(&event.kind).await.is_some() {
+            self.handle_plugin_event(&event).await?;
         } else if let Some(h) = self.storage.handle_event(&event).await? {
             match h {
                 EventHandled::Policy(vault_id) => {
                     let InternalPolicy { policy, .. } = self.storage.vault(&vault_id).await?;
+                    self.check_vault_compromised_keys(vault_id, &policy).await;
                     self.manager.load_policy(event.id, policy).await?;
+                    self.publish_capabilities_if_outdated(vault_id).await;
                 }
                 EventHandled::CompletedProposal(completed_proposal_id) => {
                     let InternalCompletedProposal {
@@ -486,6 +767,29 @@ impl SmartVaults {
                         }
                     }
                 }
+                EventHandled::VaultMetadata(vault_id) => {
+                    if let Err(e) = self.connect_vault_relays(vault_id).await {
+                        tracing::error!("Impossible to connect to relays of vault {vault_id}: {e}");
+                    }
+                }
+                EventHandled::Proposal(proposal_id) => {
+                    match self.storage.proposal(&proposal_id).await {
+                        Ok(internal) => {
+                            for issue in self
+                                .proposal_issues(proposal_id, &internal)
+                                .await
+                                .into_iter()
+                            {
+                                tracing::warn!("Suspicious proposal {proposal_id}: {issue}");
+                            }
+                        }
+                        Err(e) => tracing::error!("Impossible to get proposal {proposal_id}: {e}"),
+                    }
+                }
+                EventHandled::SharedSigner(shared_signer_id) => {
+                    self.check_shared_signer_compromised_key(shared_signer_id)
+                        .await;
+                }
                 _ => (),
             };
             self.sync_channel.send(Message::EventHandled(h))?;
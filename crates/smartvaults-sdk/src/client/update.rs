@@ -0,0 +1,136 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use nostr_sdk::{Event, Filter, JsonUtil, Url};
+use smartvaults_core::bitcoin::hashes::sha256::Hash as Sha256Hash;
+use smartvaults_core::bitcoin::hashes::Hash;
+use smartvaults_protocol::v1::constants::{RELEASE_KIND, SMARTVAULTS_RELEASE_PUBLIC_KEY};
+use smartvaults_protocol::v1::{Release, ReleaseAsset, Serde};
+
+use super::{Error, SmartVaults};
+use crate::constants::{RELEASE_DOWNLOAD_TIMEOUT, RELEASE_FETCH_TIMEOUT};
+
+impl SmartVaults {
+    /// Verify the signature and the author of a release event and parse the manifest
+    fn verify_release_event(&self, app: &str, event: &Event) -> Result<Release, Error> {
+        event
+            .verify()
+            .map_err(|e| Error::InvalidRelease(e.to_string()))?;
+        if event.author() != *SMARTVAULTS_RELEASE_PUBLIC_KEY {
+            return Err(Error::InvalidRelease(String::from("unknown author")));
+        }
+        if event.kind() != RELEASE_KIND || event.identifier() != Some(app) {
+            return Err(Error::InvalidRelease(format!("not a `{app}` release")));
+        }
+        Release::from_json(event.content()).map_err(|e| Error::InvalidRelease(e.to_string()))
+    }
+
+    async fn fetch_release_event_from_url(&self, url: Url) -> Result<Event, Error> {
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+        let client = self
+            .http_client(proxy)
+            .map_err(|e| Error::Download(e.to_string()))?;
+        let res = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| Error::Download(e.to_string()))?;
+        let json: String = res
+            .text()
+            .await
+            .map_err(|e| Error::Download(e.to_string()))?;
+        Event::from_json(json).map_err(|e| Error::InvalidRelease(e.to_string()))
+    }
+
+    /// Get the latest signed release manifest of `app` (i.e. `smartvaults-desktop`)
+    ///
+    /// The manifest is fetched from `manifest_url` (a JSON serialized release event), if set,
+    /// otherwise from the relays. In both cases the event must be signed by the Smart Vaults key.
+    pub async fn get_latest_release(
+        &self,
+        app: &str,
+        manifest_url: Option<Url>,
+    ) -> Result<Option<Release>, Error> {
+        let event: Option<Event> = match manifest_url {
+            Some(url) => Some(self.fetch_release_event_from_url(url).await?),
+            None => {
+                let filter = Filter::new()
+                    .author(*SMARTVAULTS_RELEASE_PUBLIC_KEY)
+                    .kind(RELEASE_KIND)
+                    .identifier(app)
+                    .limit(1);
+                self.client
+                    .get_events_of(vec![filter], Some(RELEASE_FETCH_TIMEOUT))
+                    .await?
+                    .into_iter()
+                    .max_by_key(|e| e.created_at())
+            }
+        };
+
+        match event {
+            Some(event) => Ok(Some(self.verify_release_event(app, &event)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Check if a release of `app` newer than `current_version` is available
+    pub async fn check_for_updates(
+        &self,
+        app: &str,
+        current_version: &str,
+        manifest_url: Option<Url>,
+    ) -> Result<Option<Release>, Error> {
+        let release: Option<Release> = self.get_latest_release(app, manifest_url).await?;
+        Ok(release.filter(|r| r.is_newer_than(current_version)))
+    }
+
+    /// Download a release installer in `dir` and verify its checksum
+    ///
+    /// Return the path of the downloaded file.
+    pub async fn download_release_asset<P>(
+        &self,
+        asset: &ReleaseAsset,
+        dir: P,
+    ) -> Result<PathBuf, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let url: Url = Url::parse(&asset.url)?;
+        let filename: String = url
+            .path_segments()
+            .and_then(|mut s| s.next_back())
+            .filter(|s| !s.is_empty() && *s != "." && *s != "..")
+            .map(String::from)
+            .unwrap_or_else(|| format!("smartvaults-{}", asset.platform));
+
+        let proxy: Option<SocketAddr> = self.config.proxy().await.ok();
+        let client = self
+            .http_client(proxy)
+            .map_err(|e| Error::Download(e.to_string()))?;
+        let bytes = client
+            .get(url)
+            .timeout(RELEASE_DOWNLOAD_TIMEOUT)
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(|e| Error::Download(e.to_string()))?
+            .bytes()
+            .await
+            .map_err(|e| Error::Download(e.to_string()))?;
+
+        if !Sha256Hash::hash(&bytes)
+            .to_string()
+            .eq_ignore_ascii_case(asset.sha256.trim())
+        {
+            return Err(Error::ChecksumMismatch);
+        }
+
+        let path: PathBuf = dir.as_ref().join(filename);
+        std::fs::write(&path, bytes)?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,555 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::HashSet;
+
+use nostr_sdk::EventId;
+use smartvaults_core::bitcoin::psbt::{Input, PartiallySignedTransaction};
+use smartvaults_core::bitcoin::script::Instruction;
+use smartvaults_core::bitcoin::{Address, Network, Script, ScriptBuf, TxIn, TxOut};
+use smartvaults_core::constants::MAX_OP_RETURN_SIZE;
+use smartvaults_core::{Proposal, Recipient};
+
+use super::{Error, SmartVaults};
+use crate::manager::SmartVaultsWallet;
+use crate::storage::InternalProposal;
+use crate::types::ProposalIssue;
+
+impl SmartVaults {
+    /// Check that the PSBT of a proposal matches what the proposal declares
+    ///
+    /// Verify that the inputs belong to the vault, that the amount sent and the outputs match the
+    /// declared recipients and that the fee is sane.
+    /// Proof of reserve proposals are not validated.
+    pub async fn validate_proposal(
+        &self,
+        policy_id: EventId,
+        proposal: &Proposal,
+    ) -> Result<Vec<ProposalIssue>, Error> {
+        let declared_amount: u64 = match proposal {
+            Proposal::Spending { amount, .. } | Proposal::KeyAgentPayment { amount, .. } => *amount,
+            Proposal::ProofOfReserve { .. } => return Ok(Vec::new()),
+        };
+
+        let psbt: PartiallySignedTransaction = proposal.psbt();
+        let mine: HashSet<ScriptBuf> = self.vault_scripts(policy_id, &psbt).await?;
+        Ok(check_psbt(
+            &psbt,
+            declared_amount,
+            proposal.recipients(),
+            self.network,
+            |script| mine.contains(script),
+        ))
+    }
+
+    /// Get the funds sent outside of the vault by a proposal
+    ///
+    /// The destinations are taken from the PSBT, not from the declared recipients: the outputs
+    /// owned by the vault (i.e. change) and the `OP_RETURN` (data) ones are excluded.
+    /// Empty for proof of reserve proposals, since they don't spend the vault funds.
+    pub(crate) async fn proposal_outgoing(
+        &self,
+        policy_id: EventId,
+        proposal: &Proposal,
+    ) -> Result<Outgoing, Error> {
+        if let Proposal::ProofOfReserve { .. } = proposal {
+            return Ok(Outgoing::default());
+        }
+        let psbt: PartiallySignedTransaction = proposal.psbt();
+        let mine: HashSet<ScriptBuf> = self.vault_scripts(policy_id, &psbt).await?;
+        Ok(outgoing(&psbt, |script| mine.contains(script)))
+    }
+
+    /// Scripts of the inputs and outputs of the PSBT owned by the vault
+    async fn vault_scripts(
+        &self,
+        policy_id: EventId,
+        psbt: &PartiallySignedTransaction,
+    ) -> Result<HashSet<ScriptBuf>, Error> {
+        let wallet: SmartVaultsWallet = self.manager.wallet(policy_id).await?;
+        let mut mine: HashSet<ScriptBuf> = HashSet::new();
+        let scripts = psbt
+            .unsigned_tx
+            .input
+            .iter()
+            .zip(psbt.inputs.iter())
+            .filter_map(|(txin, input)| prevout(txin, input))
+            .chain(psbt.unsigned_tx.output.iter())
+            .map(|txout| &txout.script_pubkey);
+        for script in scripts {
+            if !mine.contains(script) && wallet.is_mine(script).await {
+                mine.insert(script.clone());
+            }
+        }
+        Ok(mine)
+    }
+
+    /// Get the issues of an indexed proposal
+    ///
+    /// The proposal is validated only the first time, then the issues are stored with it.
+    /// The errors are logged (instead of propagated) and the validation retried at the next call.
+    pub(crate) async fn proposal_issues(
+        &self,
+        proposal_id: EventId,
+        internal: &InternalProposal,
+    ) -> Vec<ProposalIssue> {
+        if let Some(issues) = &internal.issues {
+            return issues.clone();
+        }
+
+        match self
+            .validate_proposal(internal.policy_id, &internal.proposal)
+            .await
+        {
+            Ok(issues) => {
+                self.storage
+                    .save_proposal_issues(&proposal_id, issues.clone())
+                    .await;
+                issues
+            }
+            Err(e) => {
+                tracing::error!("Impossible to validate proposal {proposal_id}: {e}");
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Funds sent outside of the vault
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Outgoing {
+    /// Outputs not owned by the vault, excluding the `OP_RETURN` (data) ones
+    pub outputs: Vec<TxOut>,
+    /// Amount (SAT) leaving the vault: outputs, amount burned in `OP_RETURN` outputs and fee
+    ///
+    /// The fee is not included if the previous output of some input is missing
+    /// (see [`ProposalIssue::MissingInputUtxo`]).
+    pub amount: u64,
+}
+
+/// Compare the PSBT with what the proposal declares
+///
+/// The outputs are matched with the declared recipients first, also if owned by the vault
+/// (i.e. self-transfers and consolidations): the amount sent is the amount of the matched outputs
+/// plus the one of the undeclared outputs not owned by the vault.
+/// The `OP_RETURN` (data) outputs are not recipients: a single standard one, not burning any
+/// amount, is expected.
+fn check_psbt<F>(
+    psbt: &PartiallySignedTransaction,
+    declared_amount: u64,
+    mut declared: Vec<Recipient>,
+    network: Network,
+    is_mine: F,
+) -> Vec<ProposalIssue>
+where
+    F: Fn(&Script) -> bool,
+{
+    let mut issues: Vec<ProposalIssue> = Vec::new();
+
+    // Inputs
+    let mut total_in: u64 = 0;
+    let mut complete_inputs: bool = true;
+    for (txin, input) in psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()) {
+        let outpoint = txin.previous_output;
+        match prevout(txin, input) {
+            Some(txout) => {
+                if !is_mine(txout.script_pubkey.as_script()) {
+                    issues.push(ProposalIssue::ForeignInput(outpoint));
+                }
+                total_in += txout.value;
+            }
+            None => {
+                complete_inputs = false;
+                issues.push(ProposalIssue::MissingInputUtxo(outpoint));
+            }
+        }
+    }
+
+    // Outputs
+    let check_recipients: bool = !declared.is_empty();
+    let mut total_out: u64 = 0;
+    let mut sent: u64 = 0;
+    let mut data_outputs: usize = 0;
+    for txout in psbt.unsigned_tx.output.iter() {
+        total_out += txout.value;
+
+        if txout.script_pubkey.is_op_return() {
+            data_outputs += 1;
+            if data_outputs > 1 || data_size(&txout.script_pubkey) > MAX_OP_RETURN_SIZE {
+                issues.push(ProposalIssue::NonStandardDataOutput);
+            }
+            if txout.value > 0 {
+                issues.push(ProposalIssue::BurnedAmount(txout.value));
+            }
+            continue;
+        }
+
+        // Declared recipient
+        let pos: Option<usize> = declared.iter().position(|r| {
+            r.amount == txout.value
+                && r.address.clone().assume_checked().script_pubkey() == txout.script_pubkey
+        });
+        if let Some(pos) = pos {
+            declared.remove(pos);
+            sent += txout.value;
+            continue;
+        }
+
+        // Change
+        if is_mine(txout.script_pubkey.as_script()) {
+            continue;
+        }
+
+        sent += txout.value;
+
+        if check_recipients {
+            issues.push(ProposalIssue::UndeclaredOutput {
+                destination: destination(&txout.script_pubkey, network),
+                amount: txout.value,
+            });
+        }
+    }
+
+    for recipient in declared.into_iter() {
+        issues.push(ProposalIssue::MissingRecipient(recipient.address));
+    }
+
+    if sent != declared_amount {
+        issues.push(ProposalIssue::AmountMismatch {
+            declared: declared_amount,
+            actual: sent,
+        });
+    }
+
+    // Fee
+    if complete_inputs {
+        match total_in.checked_sub(total_out) {
+            Some(fee) => {
+                if fee > sent {
+                    issues.push(ProposalIssue::AbsurdFee(fee));
+                }
+            }
+            None => issues.push(ProposalIssue::NegativeFee),
+        }
+    }
+
+    issues
+}
+
+/// Funds sent outside of the vault by the PSBT
+fn outgoing<F>(psbt: &PartiallySignedTransaction, is_mine: F) -> Outgoing
+where
+    F: Fn(&Script) -> bool,
+{
+    let mut outgoing = Outgoing::default();
+
+    let mut total_in: Option<u64> = Some(0);
+    for (txin, input) in psbt.unsigned_tx.input.iter().zip(psbt.inputs.iter()) {
+        total_in = match (total_in, prevout(txin, input)) {
+            (Some(total), Some(txout)) => Some(total + txout.value),
+            _ => None,
+        };
+    }
+
+    let mut total_out: u64 = 0;
+    for txout in psbt.unsigned_tx.output.iter() {
+        total_out += txout.value;
+        if txout.script_pubkey.is_op_return() {
+            outgoing.amount += txout.value;
+        } else if !is_mine(txout.script_pubkey.as_script()) {
+            outgoing.amount += txout.value;
+            outgoing.outputs.push(txout.clone());
+        }
+    }
+
+    // Fee
+    if let Some(total_in) = total_in {
+        outgoing.amount += total_in.saturating_sub(total_out);
+    }
+
+    outgoing
+}
+
+fn prevout<'a>(txin: &TxIn, input: &'a Input) -> Option<&'a TxOut> {
+    match (&input.witness_utxo, &input.non_witness_utxo) {
+        (Some(txout), _) => Some(txout),
+        (None, Some(tx)) => tx.output.get(txin.previous_output.vout as usize),
+        (None, None) => None,
+    }
+}
+
+/// Size of the data pushed by an `OP_RETURN` script
+fn data_size(script: &Script) -> usize {
+    script
+        .instructions()
+        .filter_map(|i| match i {
+            Ok(Instruction::PushBytes(bytes)) => Some(bytes.len()),
+            _ => None,
+        })
+        .sum()
+}
+
+fn destination(script: &ScriptBuf, network: Network) -> String {
+    match Address::from_script(script, network) {
+        Ok(address) => address.to_string(),
+        Err(_) => format!("{script:x}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use smartvaults_core::bitcoin::absolute::LockTime;
+    use smartvaults_core::bitcoin::hashes::Hash;
+    use smartvaults_core::bitcoin::script::PushBytesBuf;
+    use smartvaults_core::bitcoin::{OutPoint, Transaction, WPubkeyHash};
+    use smartvaults_protocol::v1::VelocityLimit;
+
+    use super::*;
+
+    const NETWORK: Network = Network::Testnet;
+
+    fn script(seed: &[u8]) -> ScriptBuf {
+        ScriptBuf::new_v0_p2wpkh(&WPubkeyHash::hash(seed))
+    }
+
+    fn recipient(script: &ScriptBuf, amount: u64) -> Recipient {
+        Recipient {
+            address: Address::from_script(script, NETWORK)
+                .unwrap()
+                .as_unchecked()
+                .clone(),
+            amount,
+        }
+    }
+
+    /// PSBT spending a 100_000 SAT vault UTXO
+    fn vault_psbt(vault: &ScriptBuf, outputs: Vec<TxOut>) -> PartiallySignedTransaction {
+        let tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                ..Default::default()
+            }],
+            output: outputs,
+        };
+        let mut psbt = PartiallySignedTransaction::from_unsigned_tx(tx).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: 100_000,
+            script_pubkey: vault.clone(),
+        });
+        psbt
+    }
+
+    #[test]
+    fn test_check_psbt_spend() {
+        let vault = script(b"vault");
+        let change = script(b"change");
+        let external = script(b"external");
+        let is_mine = |s: &Script| s == vault.as_script() || s == change.as_script();
+
+        let psbt = vault_psbt(
+            &vault,
+            vec![
+                TxOut {
+                    value: 60_000,
+                    script_pubkey: external.clone(),
+                },
+                TxOut {
+                    value: 39_000,
+                    script_pubkey: change.clone(),
+                },
+            ],
+        );
+
+        let recipients = vec![recipient(&external, 60_000)];
+        assert!(check_psbt(&psbt, 60_000, recipients, NETWORK, is_mine).is_empty());
+
+        // Different amount declared
+        let recipients = vec![recipient(&external, 50_000)];
+        let issues = check_psbt(&psbt, 50_000, recipients, NETWORK, is_mine);
+        assert!(issues.contains(&ProposalIssue::AmountMismatch {
+            declared: 50_000,
+            actual: 60_000
+        }));
+        assert!(issues
+            .iter()
+            .any(|i| matches!(i, ProposalIssue::UndeclaredOutput { amount: 60_000, .. })));
+        assert!(issues
+            .iter()
+            .any(|i| matches!(i, ProposalIssue::MissingRecipient(..))));
+    }
+
+    #[test]
+    fn test_check_psbt_self_send() {
+        let vault = script(b"vault");
+        let is_mine = |s: &Script| s == vault.as_script();
+
+        // Consolidation to a vault address
+        let psbt = vault_psbt(
+            &vault,
+            vec![TxOut {
+                value: 99_000,
+                script_pubkey: vault.clone(),
+            }],
+        );
+        let recipients = vec![recipient(&vault, 99_000)];
+        assert!(check_psbt(&psbt, 99_000, recipients, NETWORK, is_mine).is_empty());
+    }
+
+    #[test]
+    fn test_check_psbt_op_return() {
+        let vault = script(b"vault");
+        let external = script(b"external");
+        let is_mine = |s: &Script| s == vault.as_script();
+
+        let data = PushBytesBuf::try_from(b"invoice-42".to_vec()).unwrap();
+        let psbt = vault_psbt(
+            &vault,
+            vec![
+                TxOut {
+                    value: 99_000,
+                    script_pubkey: external.clone(),
+                },
+                TxOut {
+                    value: 0,
+                    script_pubkey: ScriptBuf::new_op_return(&data),
+                },
+            ],
+        );
+        let recipients = vec![recipient(&external, 99_000)];
+        assert!(check_psbt(&psbt, 99_000, recipients, NETWORK, is_mine).is_empty());
+    }
+
+    #[test]
+    fn test_check_psbt_non_standard_op_return() {
+        let vault = script(b"vault");
+        let external = script(b"external");
+        let is_mine = |s: &Script| s == vault.as_script();
+        let op_return = |data: Vec<u8>, value: u64| TxOut {
+            value,
+            script_pubkey: ScriptBuf::new_op_return(&PushBytesBuf::try_from(data).unwrap()),
+        };
+        let spend = TxOut {
+            value: 98_000,
+            script_pubkey: external.clone(),
+        };
+        let recipients = vec![recipient(&external, 98_000)];
+
+        // Burning an amount
+        let psbt = vault_psbt(&vault, vec![spend.clone(), op_return(vec![1; 10], 1_000)]);
+        assert_eq!(
+            check_psbt(&psbt, 98_000, recipients.clone(), NETWORK, is_mine),
+            vec![ProposalIssue::BurnedAmount(1_000)]
+        );
+
+        // Too much data
+        let psbt = vault_psbt(
+            &vault,
+            vec![spend.clone(), op_return(vec![1; MAX_OP_RETURN_SIZE + 1], 0)],
+        );
+        assert_eq!(
+            check_psbt(&psbt, 98_000, recipients.clone(), NETWORK, is_mine),
+            vec![ProposalIssue::NonStandardDataOutput]
+        );
+
+        // Multiple data outputs
+        let psbt = vault_psbt(
+            &vault,
+            vec![spend, op_return(vec![1; 10], 0), op_return(vec![2; 10], 0)],
+        );
+        assert_eq!(
+            check_psbt(&psbt, 98_000, recipients, NETWORK, is_mine),
+            vec![ProposalIssue::NonStandardDataOutput]
+        );
+    }
+
+    #[test]
+    fn test_outgoing() {
+        let vault = script(b"vault");
+        let change = script(b"change");
+        let external = script(b"external");
+        let undeclared = script(b"undeclared");
+        let is_mine = |s: &Script| s == vault.as_script() || s == change.as_script();
+
+        let data = PushBytesBuf::try_from(b"invoice-42".to_vec()).unwrap();
+        let spend = TxOut {
+            value: 50_000,
+            script_pubkey: external.clone(),
+        };
+        let hidden = TxOut {
+            value: 20_000,
+            script_pubkey: undeclared.clone(),
+        };
+        let psbt = vault_psbt(
+            &vault,
+            vec![
+                spend.clone(),
+                TxOut {
+                    value: 0,
+                    script_pubkey: ScriptBuf::new_op_return(&data),
+                },
+                hidden.clone(),
+                TxOut {
+                    value: 29_000,
+                    script_pubkey: change.clone(),
+                },
+            ],
+        );
+        assert_eq!(
+            outgoing(&psbt, is_mine),
+            Outgoing {
+                outputs: vec![spend, hidden],
+                amount: 71_000,
+            }
+        );
+
+        // Consolidation: only the fee leaves the vault
+        let mut psbt = vault_psbt(
+            &vault,
+            vec![TxOut {
+                value: 99_000,
+                script_pubkey: vault.clone(),
+            }],
+        );
+        assert_eq!(
+            outgoing(&psbt, is_mine),
+            Outgoing {
+                outputs: Vec::new(),
+                amount: 1_000,
+            }
+        );
+
+        // Unknown fee
+        psbt.inputs[0].witness_utxo = None;
+        assert_eq!(outgoing(&psbt, is_mine), Outgoing::default());
+    }
+
+    #[test]
+    fn test_outgoing_per_proposal_limit() {
+        let vault = script(b"vault");
+        let external = script(b"external");
+        let undeclared = script(b"undeclared");
+        let is_mine = |s: &Script| s == vault.as_script();
+        let limit = VelocityLimit::per_proposal(50_000);
+
+        // Declared amount within the limit, but an undeclared output exceeding it
+        let recipients = vec![recipient(&external, 40_000)];
+        let psbt = vault_psbt(
+            &vault,
+            vec![
+                TxOut {
+                    value: 40_000,
+                    script_pubkey: external.clone(),
+                },
+                TxOut {
+                    value: 59_000,
+                    script_pubkey: undeclared.clone(),
+                },
+            ],
+        );
+        let declared: u64 = recipients.iter().map(|r| r.amount).sum();
+        assert!(!limit.is_exceeded_by(0, declared));
+        assert!(limit.is_exceeded_by(0, outgoing(&psbt, is_mine).amount));
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use std::collections::BTreeSet;
+
+use nostr_sdk::database::Order;
+use nostr_sdk::{
+    ClientMessage, Event, EventBuilder, EventId, Filter, Keys, RelaySendOptions, SubscribeOptions,
+    Url,
+};
+use smartvaults_protocol::v1::{SmartVaultsEventBuilder, VaultMetadata, VelocityLimit};
+
+use super::{Error, SmartVaults};
+use crate::storage::InternalPolicy;
+
+impl SmartVaults {
+    /// Get the vault metadata
+    pub async fn get_vault_metadata(&self, vault_id: EventId) -> Result<VaultMetadata, Error> {
+        // Check if vault exists
+        self.storage.vault(&vault_id).await?;
+        Ok(self.storage.vault_metadata(&vault_id).await)
+    }
+
+    /// Get the relays pinned to the vault
+    pub async fn get_vault_relays(&self, vault_id: EventId) -> Result<BTreeSet<Url>, Error> {
+        Ok(self.get_vault_metadata(vault_id).await?.relays)
+    }
+
+    /// Pin relays to the vault (replacing the current ones)
+    ///
+    /// The vault events are always published to and fetched from the pinned relays, regardless of
+    /// the relay list of each member. The pinned relays are not added to the user relay list (NIP65).
+    pub async fn set_vault_relays<I, S>(
+        &self,
+        vault_id: EventId,
+        relays: I,
+    ) -> Result<EventId, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut metadata: VaultMetadata = self.storage.vault_metadata(&vault_id).await;
+        metadata.relays = relays
+            .into_iter()
+            .map(|url| Url::parse(url.as_ref()))
+            .collect::<Result<_, _>>()?;
+        self.publish_vault_metadata(vault_id, metadata).await
+    }
+
+    /// Get the spending velocity limits of the vault
+    pub async fn get_vault_velocity_limits(
+        &self,
+        vault_id: EventId,
+    ) -> Result<Vec<VelocityLimit>, Error> {
+        Ok(self.get_vault_metadata(vault_id).await?.velocity_limits)
+    }
+
+    /// Set the spending velocity limits of the vault (replacing the current ones)
+    ///
+    /// An empty list removes the limits.
+    pub async fn set_vault_velocity_limits(
+        &self,
+        vault_id: EventId,
+        limits: Vec<VelocityLimit>,
+    ) -> Result<EventId, Error> {
+        let mut metadata: VaultMetadata = self.storage.vault_metadata(&vault_id).await;
+        metadata.velocity_limits = limits;
+        self.publish_vault_metadata(vault_id, metadata).await
+    }
+
+    pub(crate) async fn publish_vault_metadata(
+        &self,
+        vault_id: EventId,
+        metadata: VaultMetadata,
+    ) -> Result<EventId, Error> {
+        let shared_key: Keys = self.storage.shared_key(&vault_id).await?;
+        let InternalPolicy { public_keys, .. } = self.storage.vault(&vault_id).await?;
+
+        // Compose event
+        let event: Event =
+            EventBuilder::vault_metadata(&shared_key, vault_id, &metadata, &public_keys)?;
+        let timestamp = event.created_at;
+
+        // Connect to the pinned relays before publishing, so the event is sent to them too
+        self.storage
+            .save_vault_metadata(vault_id, metadata, timestamp)
+            .await;
+        self.connect_vault_relays(vault_id).await?;
+
+        Ok(self.client.send_event(event).await?)
+    }
+
+    /// Add the pinned relays of the vault to the pool (if not already added)
+    ///
+    /// Newly added relays are subscribed and receive the past events of the vault.
+    pub(crate) async fn connect_vault_relays(&self, vault_id: EventId) -> Result<(), Error> {
+        let metadata: VaultMetadata = self.storage.vault_metadata(&vault_id).await;
+        for url in metadata.relays.into_iter() {
+            if self.client.add_relay(&url).await? {
+                let relay = self.client.relay(&url).await?;
+                let filters: Vec<Filter> = self.relay_sync_filters(&url).await;
+                relay
+                    .subscribe(
+                        filters,
+                        SubscribeOptions::default()
+                            .send_opts(RelaySendOptions::new().skip_send_confirmation(true)),
+                    )
+                    .await?;
+                relay.connect(None).await;
+
+                if let Err(e) = self.rebroadcast_vault_events_to(vault_id, &url).await {
+                    tracing::error!("Impossible to rebroadcast vault events to {url}: {e}");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Add the pinned relays of all the vaults to the pool
+    pub(crate) async fn load_vault_relays(&self) -> Result<(), Error> {
+        let relays = self.storage.pinned_relays().await;
+        self.client.add_relays(relays).await?;
+        Ok(())
+    }
+
+    async fn rebroadcast_vault_events_to(&self, vault_id: EventId, url: &Url) -> Result<(), Error> {
+        let pool = self.client.pool();
+        let filters: Vec<Filter> = vec![Filter::new().id(vault_id), Filter::new().event(vault_id)];
+        let events: Vec<Event> = self.client.database().query(filters, Order::Asc).await?;
+        for event in events.into_iter() {
+            pool.send_msg_to(
+                [url.as_str()],
+                ClientMessage::event(event),
+                RelaySendOptions::new().skip_send_confirmation(true),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+use nostr_sdk::{EventId, Timestamp};
+use smartvaults_core::bdk::chain::ConfirmationTime;
+use smartvaults_core::types::Seed;
+use smartvaults_core::{ApprovedProposal, Proposal};
+
+use super::validation::Outgoing;
+use super::{Error, SmartVaults};
+use crate::types::{GetProposal, GetVelocityLimitStatus};
+
+impl SmartVaults {
+    /// Amount (SAT) sent by the vault since `since`, fees included
+    async fn outflows_since(&self, vault_id: EventId, since: Timestamp) -> Result<u64, Error> {
+        Ok(self
+            .manager
+            .get_txs(vault_id)
+            .await?
+            .into_iter()
+            .filter(|tx| {
+                let time: u64 = match tx.confirmation_time {
+                    ConfirmationTime::Confirmed { time, .. } => time,
+                    ConfirmationTime::Unconfirmed { last_seen } => last_seen,
+                };
+                time >= since.as_u64()
+            })
+            .filter_map(|tx| match tx.total() {
+                total if total < 0 => Some(total.unsigned_abs()),
+                _ => None,
+            })
+            .sum())
+    }
+
+    /// Get the spending velocity limits of the vault with the amount spent in the current period
+    ///
+    /// The amount spent is always zero for the limits per proposal.
+    pub async fn get_velocity_limits_status(
+        &self,
+        vault_id: EventId,
+    ) -> Result<Vec<GetVelocityLimitStatus>, Error> {
+        let now: Timestamp = Timestamp::now();
+        let limits = self.get_vault_velocity_limits(vault_id).await?;
+        let mut list: Vec<GetVelocityLimitStatus> = Vec::with_capacity(limits.len());
+        for limit in limits.into_iter() {
+            let spent: u64 = if limit.is_per_proposal() {
+                0
+            } else {
+                let since = Timestamp::from(now.as_u64().saturating_sub(limit.period().as_secs()));
+                self.outflows_since(vault_id, since).await?
+            };
+            list.push(GetVelocityLimitStatus { limit, spent });
+        }
+        Ok(list)
+    }
+
+    /// Get the velocity limits of the vault exceeded by spending `amount` (SAT)
+    pub async fn check_velocity_limits(
+        &self,
+        vault_id: EventId,
+        amount: u64,
+    ) -> Result<Vec<GetVelocityLimitStatus>, Error> {
+        Ok(self
+            .get_velocity_limits_status(vault_id)
+            .await?
+            .into_iter()
+            .filter(|status| status.limit.is_exceeded_by(status.spent, amount))
+            .collect())
+    }
+
+    /// Get the velocity limits exceeded by a pending proposal
+    pub async fn get_proposal_exceeded_velocity_limits(
+        &self,
+        proposal_id: EventId,
+    ) -> Result<Vec<GetVelocityLimitStatus>, Error> {
+        let GetProposal {
+            policy_id,
+            proposal,
+            ..
+        } = self.get_proposal_by_id(proposal_id).await?;
+        self.exceeded_velocity_limits(policy_id, &proposal).await
+    }
+
+    /// Get the velocity limits exceeded by the funds leaving the vault with the proposal
+    ///
+    /// The amount is computed from the PSBT (see [`SmartVaults::proposal_outgoing`]), fee
+    /// included, as for the past outflows.
+    pub(crate) async fn exceeded_velocity_limits(
+        &self,
+        vault_id: EventId,
+        proposal: &Proposal,
+    ) -> Result<Vec<GetVelocityLimitStatus>, Error> {
+        let outgoing: Outgoing = self.proposal_outgoing(vault_id, proposal).await?;
+        if outgoing.amount == 0 {
+            return Ok(Vec::new());
+        }
+        self.check_velocity_limits(vault_id, outgoing.amount).await
+    }
+
+    /// Approve a proposal exceeding the spending velocity limits of the vault
+    ///
+    /// The proposal can be finalized only if all its approvals are overrides.
+    pub async fn approve_with_velocity_override<T>(
+        &self,
+        password: T,
+        pin: Option<&str>,
+        proposal_id: EventId,
+    ) -> Result<(EventId, ApprovedProposal), Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        self.verify_approval_pin(pin)?;
+        let seed: Seed = self.keechain.read().seed(password)?;
+        self.approve_with_seed(&seed, proposal_id, true).await
+    }
+
+    /// Refuse to finalize a proposal exceeding the velocity limits, unless all approvals are overrides
+    pub(crate) async fn verify_velocity_limits(
+        &self,
+        vault_id: EventId,
+        proposal_id: EventId,
+        proposal: &Proposal,
+    ) -> Result<(), Error> {
+        if let Some(status) = self
+            .exceeded_velocity_limits(vault_id, proposal)
+            .await?
+            .into_iter()
+            .next()
+        {
+            let approvals = self.storage.approvals().await;
+            let mut approvals = approvals
+                .values()
+                .filter(|a| a.proposal_id == proposal_id)
+                .peekable();
+            let overridden: bool =
+                approvals.peek().is_some() && approvals.all(|a| a.velocity_override);
+            if !overridden {
+                return Err(Error::VelocityLimitExceeded(status.limit));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Whitelist of the destinations of the spending proposals
+//!
+//! With the [`WhitelistMode::Enforce`] mode the proposals to destinations not in the whitelist are
+//! refused, while with [`WhitelistMode::Confirm`] they are allowed but the approvers must be
+//! asked for an extra confirmation (see [`SmartVaults::get_proposal_not_whitelisted_recipients`]).
+
+use nostr_sdk::EventId;
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::Address;
+use smartvaults_core::Proposal;
+use smartvaults_protocol::v1::{VaultMetadata, Whitelist, WhitelistMode, WhitelistedDestination};
+
+use super::validation::Outgoing;
+use super::{Error, SmartVaults};
+use crate::types::GetProposal;
+
+impl SmartVaults {
+    /// Get the whitelist of the vault
+    pub async fn get_vault_whitelist(&self, vault_id: EventId) -> Result<Whitelist, Error> {
+        Ok(self.get_vault_metadata(vault_id).await?.whitelist)
+    }
+
+    /// Set what to do with the proposals to destinations not in the whitelist
+    pub async fn set_vault_whitelist_mode(
+        &self,
+        vault_id: EventId,
+        mode: WhitelistMode,
+    ) -> Result<EventId, Error> {
+        let mut metadata: VaultMetadata = self.storage.vault_metadata(&vault_id).await;
+        metadata.whitelist.mode = mode;
+        self.publish_vault_metadata(vault_id, metadata).await
+    }
+
+    /// Add an address or a descriptor to the whitelist of the vault
+    ///
+    /// If already whitelisted, the label is updated.
+    pub async fn add_whitelisted_destination<S>(
+        &self,
+        vault_id: EventId,
+        destination: S,
+        label: Option<String>,
+    ) -> Result<EventId, Error>
+    where
+        S: Into<String>,
+    {
+        let destination = WhitelistedDestination::new(destination, label)?;
+        if let Some(address) = destination.address() {
+            address.require_network(self.network)?;
+        }
+
+        let mut metadata: VaultMetadata = self.storage.vault_metadata(&vault_id).await;
+        let destinations: &mut Vec<WhitelistedDestination> = &mut metadata.whitelist.destinations;
+        destinations.retain(|d| d.destination() != destination.destination());
+        destinations.push(destination);
+        self.publish_vault_metadata(vault_id, metadata).await
+    }
+
+    /// Remove an address or a descriptor from the whitelist of the vault
+    pub async fn remove_whitelisted_destination(
+        &self,
+        vault_id: EventId,
+        destination: &str,
+    ) -> Result<EventId, Error> {
+        let mut metadata: VaultMetadata = self.storage.vault_metadata(&vault_id).await;
+        let destinations: &mut Vec<WhitelistedDestination> = &mut metadata.whitelist.destinations;
+        let len: usize = destinations.len();
+        destinations.retain(|d| d.destination() != destination.trim());
+        if destinations.len() == len {
+            return Err(Error::NotFound);
+        }
+        self.publish_vault_metadata(vault_id, metadata).await
+    }
+
+    /// Get the destinations of a pending proposal not in the whitelist of the vault
+    ///
+    /// Always empty if the whitelist is disabled.
+    pub async fn get_proposal_not_whitelisted_recipients(
+        &self,
+        proposal_id: EventId,
+    ) -> Result<Vec<Address<NetworkUnchecked>>, Error> {
+        let GetProposal {
+            policy_id,
+            proposal,
+            ..
+        } = self.get_proposal_by_id(proposal_id).await?;
+        self.not_whitelisted_recipients(policy_id, &proposal).await
+    }
+
+    /// Get the destinations of the outputs sending funds outside of the vault not in the whitelist
+    ///
+    /// The outputs are taken from the PSBT (see [`SmartVaults::proposal_outgoing`]), so a
+    /// destination not declared in the proposal recipients can't skip the whitelist.
+    /// The outputs without a standard address can't be whitelisted.
+    pub(crate) async fn not_whitelisted_recipients(
+        &self,
+        vault_id: EventId,
+        proposal: &Proposal,
+    ) -> Result<Vec<Address<NetworkUnchecked>>, Error> {
+        let whitelist: Whitelist = self.get_vault_whitelist(vault_id).await?;
+        if whitelist.mode == WhitelistMode::Disabled {
+            return Ok(Vec::new());
+        }
+        let mut addresses: Vec<Address<NetworkUnchecked>> = Vec::new();
+        let outgoing: Outgoing = self.proposal_outgoing(vault_id, proposal).await?;
+        for txout in outgoing.outputs.into_iter() {
+            match Address::from_script(&txout.script_pubkey, self.network) {
+                Ok(address) => addresses.push(address.as_unchecked().clone()),
+                Err(_) => {
+                    return Err(Error::DestinationNotWhitelisted(format!(
+                        "{:x}",
+                        txout.script_pubkey
+                    )))
+                }
+            }
+        }
+        Ok(whitelist.not_whitelisted(addresses.iter()))
+    }
+
+    /// Refuse the proposals to destinations not in the whitelist, if enforced
+    pub(crate) async fn verify_whitelist(
+        &self,
+        vault_id: EventId,
+        proposal: &Proposal,
+    ) -> Result<(), Error> {
+        if self.get_vault_whitelist(vault_id).await?.mode != WhitelistMode::Enforce {
+            return Ok(());
+        }
+        match self
+            .not_whitelisted_recipients(vault_id, proposal)
+            .await?
+            .into_iter()
+            .next()
+        {
+            Some(address) => Err(Error::DestinationNotWhitelisted(
+                address.assume_checked().to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
@@ -12,15 +12,61 @@ pub const TESTNET_RELAYS: [&str; 2] = [
     "wss://relay-test1.hashed.systems",
     "wss://relay-test2.hashed.systems",
 ];
+pub const SIGNET_RELAYS: [&str; 2] = [
+    "wss://relay-signet1.hashed.systems",
+    "wss://relay-signet2.hashed.systems",
+];
+pub const REGTEST_RELAYS: [&str; 1] = ["ws://localhost:7777"];
 
 // Sync intervals
 pub const BLOCK_HEIGHT_SYNC_INTERVAL: Duration = Duration::from_secs(60);
 pub const MEMPOOL_TX_FEES_SYNC_INTERVAL: Duration = Duration::from_secs(60);
 pub const WALLET_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+pub const PINNED_WALLET_SYNC_INTERVAL: Duration = Duration::from_secs(20);
+pub const MEMPOOL_WATCHER_INTERVAL: Duration = Duration::from_secs(5);
+pub const WALLET_FULL_SCAN_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
 pub const METADATA_SYNC_INTERVAL: Duration = Duration::from_secs(3600);
+pub const DEFAULT_MAX_CONCURRENT_SYNCS: usize = 4;
+
+// Vault staleness
+/// Max age of the last timechain sync of a vault
+pub const VAULT_STALE_SYNC_THRESHOLD: Duration = Duration::from_secs(60 * 60);
+/// Max age of the last event of a vault received from relays
+pub const VAULT_STALE_EVENT_THRESHOLD: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+// Checkpoint
+/// Events reconciled before the checkpoint on resume (events delivered to the relays late)
+pub(crate) const CHECKPOINT_RESUME_MARGIN: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Sync windows
+pub(crate) const SYNC_CURSOR_MARGIN: Duration = Duration::from_secs(600);
+pub(crate) const SYNC_GAP_REFETCH_TIMEOUT: Duration = Duration::from_secs(60);
+/// Max stored events sent by most of the relays for a request: a sync reaching it can be truncated
+pub(crate) const SYNC_RELAY_EVENTS_LIMIT: usize = 500;
+
+// Proposals
+pub const DEFAULT_MAX_PROPOSAL_INPUTS: usize = 250;
+
+// Recurring payments
+pub const RECURRING_PAYMENT_MIN_INTERVAL: Duration = Duration::from_secs(60 * 60);
+pub(crate) const RECURRING_PAYMENTS_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+pub(crate) const EXPIRED_PROPOSALS_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+pub(crate) const STUCK_TXS_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+pub(crate) const RECOVERY_PATHS_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// Price
+pub const PRICE_API_URL: &str = "https://mempool.space/api/v1/prices";
+pub const SUPPORTED_FIAT_CURRENCIES: [&str; 7] = ["USD", "EUR", "GBP", "CAD", "CHF", "AUD", "JPY"];
 
 // Timeout
 pub(crate) const SEND_TIMEOUT: Duration = Duration::from_secs(20);
+pub(crate) const RELEASE_FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+pub(crate) const CONTACT_LIST_FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+pub(crate) const DIAGNOSTICS_TIMEOUT: Duration = Duration::from_secs(15);
+pub(crate) const RELEASE_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
 
 pub(crate) const DEFAULT_SUBSCRIPTION_ID: &str = "smartvaults";
 pub(crate) const NOSTR_CONNECT_SUBSCRIPTION_ID: &str = "ncs";
+pub(crate) const PLUGINS_SUBSCRIPTION_ID: &str = "plugins";
+/// Identifier of the test event published by the diagnostics (NIP-78, replaced on each run)
+pub(crate) const DIAGNOSTICS_IDENTIFIER: &str = "smartvaults:diagnostics";
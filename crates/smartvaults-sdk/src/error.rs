@@ -1,12 +1,15 @@
 // Copyright (c) 2022-2024 Smart Vaults
 // Distributed under the MIT software license
 
+use std::io;
+
 use nostr_sdk::database::DatabaseError;
 use nostr_sdk::SQLiteError;
 use smartvaults_protocol::v1::util::EncryptionError;
 use smartvaults_protocol::v1::SmartVaultsEventBuilderError;
 use thiserror::Error;
 
+use crate::fee::Error as FeeError;
 use crate::manager::{Error as ManagerError, WalletError};
 use crate::util;
 
@@ -27,6 +30,8 @@ pub enum Error {
     #[error(transparent)]
     Electrum(#[from] bdk_electrum::electrum_client::Error),
     #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
     Url(#[from] nostr_sdk::types::url::ParseError),
     #[error(transparent)]
     Client(#[from] nostr_sdk::client::Error),
@@ -45,16 +50,22 @@ pub enum Error {
     #[error(transparent)]
     SmartVaultsEventBuilder(#[from] SmartVaultsEventBuilderError),
     #[error(transparent)]
+    Chunk(#[from] smartvaults_protocol::v1::chunk::Error),
+    #[error(transparent)]
     Relay(#[from] nostr_sdk::pool::relay::Error),
     #[error(transparent)]
     Policy(#[from] smartvaults_core::policy::Error),
     #[error(transparent)]
+    Descriptor(#[from] smartvaults_core::descriptor::Error),
+    #[error(transparent)]
     Proposal(#[from] smartvaults_core::proposal::Error),
     #[error(transparent)]
     Secp256k1(#[from] smartvaults_core::bitcoin::secp256k1::Error),
     #[error(transparent)]
     Address(#[from] smartvaults_core::bitcoin::address::Error),
     #[error(transparent)]
+    Fee(#[from] FeeError),
+    #[error(transparent)]
     Encryption(#[from] EncryptionError),
     #[error(transparent)]
     NIP04(#[from] nostr_sdk::nips::nip04::Error),
@@ -73,13 +84,25 @@ pub enum Error {
     #[error(transparent)]
     Config(#[from] crate::config::Error),
     #[error(transparent)]
+    Bundle(#[from] crate::bundle::Error),
+    #[error(transparent)]
+    Pin(#[from] crate::pin::Error),
+    #[error(transparent)]
     Store(#[from] smartvaults_sdk_sqlite::Error),
     #[error(transparent)]
     Label(#[from] smartvaults_protocol::v1::label::Error),
     #[error(transparent)]
     KeyAgentVerified(#[from] smartvaults_protocol::v1::key_agent::verified::Error),
+    #[error(transparent)]
+    Whitelist(#[from] smartvaults_protocol::v1::whitelist::Error),
+    #[error(transparent)]
+    Payee(#[from] smartvaults_protocol::v1::payee::Error),
     #[error("password not match")]
     PasswordNotMatch,
+    #[error("impossible to get password: {0}")]
+    GetPassword(String),
+    #[error("impossible to get passphrase: {0}")]
+    GetPassphrase(String),
     #[error("not enough public keys")]
     NotEnoughPublicKeys,
     #[error("shared keys not found")]
@@ -102,16 +125,335 @@ pub enum Error {
     SignerAlreadyShared,
     #[error("signer descriptor already exists")]
     SignerDescriptorAlreadyExists,
+    #[error("signer used by {} vault/s", .0.len())]
+    SignerInUse(Vec<nostr_sdk::EventId>),
+    #[error("signer request not found")]
+    SignerRequestNotFound,
+    #[error("signer not matching the request")]
+    SignerNotMatchingRequest,
+    #[error("PSBT not matching the proposal transaction")]
+    PsbtNotMatchingProposal,
+    #[error("PSBT not signed by signer {0}")]
+    PsbtNotSignedBySigner(smartvaults_core::bitcoin::bip32::Fingerprint),
+    #[error("shared key conflict for vault {0}")]
+    SharedKeyConflict(nostr_sdk::EventId),
+    #[error("identifier collision: {0}")]
+    IdentifierCollision(String),
+    #[error("identifier not matching the content of event {0}")]
+    IdentifierMismatch(nostr_sdk::EventId),
+    #[error("payment request not found")]
+    PaymentRequestNotFound,
+    #[error("payment request already fulfilled")]
+    PaymentRequestAlreadyFulfilled,
+    #[error("recurring payment not found")]
+    RecurringPaymentNotFound,
+    #[error("payee not found")]
+    PayeeNotFound,
+    #[error("recurring payment interval too short")]
+    InvalidRecurringPaymentInterval,
+    #[error("spending velocity limit exceeded ({0})")]
+    VelocityLimitExceeded(smartvaults_protocol::v1::VelocityLimit),
+    #[error("destination not whitelisted: {0}")]
+    DestinationNotWhitelisted(String),
+    #[error("replacement not supported: {0}")]
+    UnsupportedReplacement(String),
+    #[error("proposal not matching its PSBT: {0}")]
+    ProposalMismatch(crate::types::ProposalIssue),
+    #[error("proposal expiration must be in the future")]
+    InvalidProposalExpiration,
+    #[error("empty comment")]
+    EmptyComment,
+    #[error("auto-approver not allowed on {0}")]
+    AutoApproverNotAllowed(smartvaults_core::bitcoin::Network),
     #[error("nostr connect request already approved")]
     NostrConnectRequestAlreadyApproved,
+    #[error("nostr connect request not allowed by the `{0}` profile")]
+    NostrConnectRequestNotAllowed(String),
     #[error("impossible to generate nostr connect response")]
     CantGenerateNostrConnectResponse,
     #[error("invalid fee rate")]
     InvalidFeeRate,
+    #[error("broadcast failed on all backends")]
+    BroadcastFailed,
+    #[error("too many inputs: {inputs} (max {max}, PSBT of ~{size} bytes), split the proposal")]
+    TooManyInputs {
+        inputs: usize,
+        max: usize,
+        /// Estimated PSBT size (bytes)
+        size: usize,
+    },
+    #[error("invalid release manifest: {0}")]
+    InvalidRelease(String),
+    #[error("checksum mismatch")]
+    ChecksumMismatch,
+    #[error("download failed: {0}")]
+    Download(String),
+    #[error("invalid backend response: {0}")]
+    InvalidBackendResponse(String),
+    #[error("price not available: {0}")]
+    PriceNotAvailable(String),
+    #[error("plugin conflict: {0}")]
+    PluginConflict(String),
+    #[error("plugin not found")]
+    PluginNotFound,
+    #[error("no contributions attributed to members")]
+    NoContributions,
+    #[error("payout address not found for {0}")]
+    PayoutAddressNotFound(nostr_sdk::PublicKey),
+    #[error("the signer can't satisfy a timelocked path of the vault alone")]
+    RecoveryPathNotFound,
+    #[error("contact list not found")]
+    ContactListNotFound,
+    #[error("invalid contacts file: {0}")]
+    InvalidContactsFile(String),
+    #[error("invalid events archive: {0}")]
+    InvalidEventsArchive(String),
+    #[error("the published event was not returned by {0}")]
+    EventNotReturned(nostr_sdk::Url),
+    #[error("device not found")]
+    DeviceNotFound,
+    #[error("impossible to revoke the current device")]
+    CantRevokeCurrentDevice,
     #[error("impossible to delete a not owned event")]
     TryingToDeleteNotOwnedEvent,
     #[error("not found")]
     NotFound,
+    #[error("{context}: {source}")]
+    Context {
+        /// Description of the failed operation
+        context: String,
+        source: Box<Error>,
+    },
     #[error("{0}")]
     Generic(String),
 }
+
+/// Add context to the error of a [`Result`]
+pub trait ErrorContext<T> {
+    /// Wrap the error with a description of the failed operation
+    fn context<S>(self, context: S) -> Result<T, Error>
+    where
+        S: Into<String>;
+}
+
+impl<T, E> ErrorContext<T> for Result<T, E>
+where
+    E: Into<Error>,
+{
+    fn context<S>(self, context: S) -> Result<T, Error>
+    where
+        S: Into<String>,
+    {
+        self.map_err(|e| e.into().context(context))
+    }
+}
+
+/// Error kind
+///
+/// Coarse classification of [`Error`], useful to branch on failure reasons without matching every variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Keychain, keys or password related error
+    Keychain,
+    /// Relay or nostr client error
+    Relay,
+    /// HTTP request error
+    Network,
+    /// Local storage, database or filesystem error
+    Storage,
+    /// Wallet, electrum or transaction error
+    Wallet,
+    /// Protocol event building, parsing or encryption error
+    Protocol,
+    /// Policy, proposal or signer error
+    Policy,
+    /// Unclassified error
+    Other,
+}
+
+impl Error {
+    /// Wrap the error with a description of the failed operation
+    pub fn context<S>(self, context: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Get the [`ErrorKind`]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Context { source, .. } => source.kind(),
+            Self::Keechain(..)
+            | Self::Keychain(..)
+            | Self::Keys(..)
+            | Self::Secp256k1(..)
+            | Self::NIP06(..)
+            | Self::BIP32(..)
+            | Self::PasswordNotMatch
+            | Self::Pin(..)
+            | Self::GetPassword(..)
+            | Self::GetPassphrase(..) => ErrorKind::Keychain,
+            Self::Url(..)
+            | Self::Client(..)
+            | Self::RelayPool(..)
+            | Self::Relay(..)
+            | Self::EventNotReturned(..) => ErrorKind::Relay,
+            Self::IO(..)
+            | Self::Dir(..)
+            | Self::NostrDatabase(..)
+            | Self::NostrDatabaseSQLite(..)
+            | Self::Config(..)
+            | Self::Bundle(..)
+            | Self::Store(..)
+            | Self::NotFound => ErrorKind::Storage,
+            Self::Electrum(..)
+            | Self::Fee(..)
+            | Self::Address(..)
+            | Self::Manager(..)
+            | Self::Wallet(..)
+            | Self::InvalidFeeRate
+            | Self::BroadcastFailed
+            | Self::InvalidBackendResponse(..)
+            | Self::TooManyInputs { .. } => ErrorKind::Wallet,
+            Self::JSON(..)
+            | Self::EventId(..)
+            | Self::EventBuilder(..)
+            | Self::SmartVaultsEventBuilder(..)
+            | Self::Chunk(..)
+            | Self::Encryption(..)
+            | Self::NIP04(..)
+            | Self::NIP46(..)
+            | Self::Label(..)
+            | Self::KeyAgentVerified(..)
+            | Self::Whitelist(..)
+            | Self::Payee(..)
+            | Self::SharedKeysNotFound
+            | Self::SharedKeyConflict(..)
+            | Self::IdentifierCollision(..)
+            | Self::IdentifierMismatch(..)
+            | Self::PublicKeyNotFound
+            | Self::NostrConnectRequestAlreadyApproved
+            | Self::NostrConnectRequestNotAllowed(..)
+            | Self::CantGenerateNostrConnectResponse
+            | Self::TryingToDeleteNotOwnedEvent
+            | Self::ContactListNotFound
+            | Self::InvalidContactsFile(..)
+            | Self::InvalidEventsArchive(..)
+            | Self::InvalidRelease(..)
+            | Self::ChecksumMismatch => ErrorKind::Protocol,
+            Self::Policy(..)
+            | Self::Descriptor(..)
+            | Self::Proposal(..)
+            | Self::Signer(..)
+            | Self::NotEnoughPublicKeys
+            | Self::PolicyNotFound
+            | Self::ProposalNotFound
+            | Self::UnexpectedProposal
+            | Self::ApprovedProposalNotFound
+            | Self::SignerNotFound
+            | Self::SignerIdNotFound
+            | Self::SignerAlreadyShared
+            | Self::SignerDescriptorAlreadyExists
+            | Self::SignerInUse(..)
+            | Self::SignerRequestNotFound
+            | Self::SignerNotMatchingRequest
+            | Self::PsbtNotMatchingProposal
+            | Self::PsbtNotSignedBySigner(..)
+            | Self::PaymentRequestNotFound
+            | Self::PaymentRequestAlreadyFulfilled
+            | Self::RecurringPaymentNotFound
+            | Self::PayeeNotFound
+            | Self::InvalidRecurringPaymentInterval
+            | Self::AutoApproverNotAllowed(..)
+            | Self::InvalidProposalExpiration
+            | Self::EmptyComment
+            | Self::VelocityLimitExceeded(..)
+            | Self::DestinationNotWhitelisted(..)
+            | Self::ProposalMismatch(..)
+            | Self::UnsupportedReplacement(..)
+            | Self::NoContributions
+            | Self::PayoutAddressNotFound(..)
+            | Self::RecoveryPathNotFound => ErrorKind::Policy,
+            Self::Http(..) | Self::Download(..) => ErrorKind::Network,
+            Self::Thread(..)
+            | Self::DeviceNotFound
+            | Self::CantRevokeCurrentDevice
+            | Self::PriceNotAvailable(..)
+            | Self::PluginConflict(..)
+            | Self::PluginNotFound
+            | Self::Generic(..) => ErrorKind::Other,
+        }
+    }
+
+    /// Check if the failed operation may succeed if retried later (i.e. network errors)
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Context { source, .. } => source.is_retryable(),
+            Self::IO(e) => matches!(
+                e.kind(),
+                io::ErrorKind::Interrupted
+                    | io::ErrorKind::TimedOut
+                    | io::ErrorKind::WouldBlock
+                    | io::ErrorKind::ConnectionReset
+            ),
+            Self::Electrum(..)
+            | Self::Client(..)
+            | Self::RelayPool(..)
+            | Self::Relay(..)
+            | Self::EventNotReturned(..)
+            | Self::BroadcastFailed
+            | Self::Http(..)
+            | Self::Download(..)
+            | Self::PriceNotAvailable(..) => true,
+            Self::Fee(e) => matches!(e, FeeError::Electrum(..) | FeeError::Http(..)),
+            Self::Manager(e) => {
+                matches!(e, ManagerError::Electrum(..) | ManagerError::NotLoaded(..))
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context() {
+        let res: Result<(), Error> = Err(Error::BroadcastFailed);
+        let e = res.context("impossible to finalize proposal").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "impossible to finalize proposal: broadcast failed on all backends"
+        );
+        assert_eq!(e.kind(), ErrorKind::Wallet);
+        assert!(e.is_retryable());
+
+        let e = Error::PolicyNotFound.context("impossible to approve");
+        assert_eq!(e.kind(), ErrorKind::Policy);
+        assert!(!e.is_retryable());
+    }
+
+    #[test]
+    fn test_kind() {
+        let e = Error::Download(String::from("connection refused"));
+        assert_eq!(e.kind(), ErrorKind::Network);
+        assert!(e.is_retryable());
+
+        assert_eq!(Error::DeviceNotFound.kind(), ErrorKind::Other);
+        assert_eq!(Error::CantRevokeCurrentDevice.kind(), ErrorKind::Other);
+    }
+
+    #[test]
+    fn test_io_retryable() {
+        let e = Error::IO(io::Error::from(io::ErrorKind::TimedOut));
+        assert_eq!(e.kind(), ErrorKind::Storage);
+        assert!(e.is_retryable());
+        assert!(!Error::IO(io::Error::from(io::ErrorKind::NotFound)).is_retryable());
+        assert!(!Error::IO(io::Error::from(io::ErrorKind::PermissionDenied)).is_retryable());
+    }
+}
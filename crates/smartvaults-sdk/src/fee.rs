@@ -0,0 +1,408 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Fee estimation
+//!
+//! The provider used by the SDK is selected with [`FeeProvider`] in the [`Config`](crate::config::Config).
+
+use std::fmt::Debug;
+use std::net::SocketAddr;
+
+use async_trait::async_trait;
+use bdk_electrum::electrum_client::{
+    self, Client as ElectrumClient, Config as ElectrumConfig, ElectrumApi, Param, Socks5Config,
+};
+use nostr_sdk::Url;
+use reqwest::{Client as HttpClient, Proxy};
+use serde_json::{json, Value};
+use smartvaults_core::bdk::FeeRate;
+use thiserror::Error;
+
+use crate::config::{ElectrumEndpoint, FeeProvider};
+use crate::constants::SEND_TIMEOUT;
+
+/// Target probability to confirm by the deadline of the fee suggestions
+const CONFIRMATION_PROBABILITY: f64 = 0.9;
+/// Max size of a block (vBytes)
+const BLOCK_VSIZE: u64 = 1_000_000;
+/// Min relay fee rate (sat/vByte)
+const MIN_RELAY_FEE_RATE: f64 = 1.0;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Electrum(#[from] electrum_client::Error),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error("invalid fee estimation response: {0}")]
+    InvalidResponse(String),
+    #[error("fee estimation not available for {0} blocks")]
+    NotAvailable(u8),
+    #[error("electrum endpoint not set")]
+    ElectrumEndpointNotSet,
+}
+
+/// Fee rate estimator
+#[async_trait]
+pub trait FeeEstimator: Debug + Send + Sync {
+    /// Name of the provider
+    fn name(&self) -> String;
+
+    /// Estimate the fee rate needed to confirm within `target_blocks`
+    async fn estimate_fee(&self, target_blocks: u8) -> Result<FeeRate, Error>;
+}
+
+/// Electrum `blockchain.estimatefee`
+#[derive(Debug, Clone)]
+pub struct ElectrumFeeEstimator {
+    endpoint: ElectrumEndpoint,
+    proxy: Option<SocketAddr>,
+}
+
+impl ElectrumFeeEstimator {
+    pub fn new(endpoint: ElectrumEndpoint, proxy: Option<SocketAddr>) -> Self {
+        Self { endpoint, proxy }
+    }
+
+    fn client(&self) -> Result<ElectrumClient, Error> {
+        let config = ElectrumConfig::builder()
+            .validate_domain(self.endpoint.validate_tls())
+            .socks5(self.proxy.map(Socks5Config::new))
+            .build();
+        Ok(ElectrumClient::from_config(
+            &self.endpoint.as_non_standard_format(),
+            config,
+        )?)
+    }
+
+    /// Fee histogram of the mempool (`mempool.get_fee_histogram`, see [`parse_fee_histogram`])
+    pub fn fee_histogram(&self) -> Result<Vec<(f64, u64)>, Error> {
+        let client = self.client()?;
+        let json: Value = client.raw_call("mempool.get_fee_histogram", Vec::<Param>::new())?;
+        parse_fee_histogram(&json)
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for ElectrumFeeEstimator {
+    fn name(&self) -> String {
+        format!("electrum ({})", self.endpoint)
+    }
+
+    async fn estimate_fee(&self, target_blocks: u8) -> Result<FeeRate, Error> {
+        let client = self.client()?;
+        let btc_per_kvb: f64 = client.estimate_fee(target_blocks as usize)?;
+        if btc_per_kvb <= 0.0 {
+            return Err(Error::NotAvailable(target_blocks));
+        }
+        Ok(FeeRate::from_btc_per_kvb(btc_per_kvb as f32))
+    }
+}
+
+/// mempool.space recommended fees API (`/api/v1/fees/recommended`)
+#[derive(Debug, Clone)]
+pub struct MempoolSpaceFeeEstimator {
+    client: HttpClient,
+    url: Url,
+}
+
+impl MempoolSpaceFeeEstimator {
+    /// New estimator for the mempool.space instance at `url` (i.e. `https://mempool.space`)
+    pub fn new(url: Url, proxy: Option<SocketAddr>) -> Result<Self, Error> {
+        Ok(Self {
+            client: http_client(proxy)?,
+            url,
+        })
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for MempoolSpaceFeeEstimator {
+    fn name(&self) -> String {
+        format!("mempool.space ({})", self.url)
+    }
+
+    async fn estimate_fee(&self, target_blocks: u8) -> Result<FeeRate, Error> {
+        let endpoint = format!(
+            "{}/api/v1/fees/recommended",
+            self.url.as_str().trim_end_matches('/')
+        );
+        let res = self.client.get(endpoint).send().await?.error_for_status()?;
+        let json: Value = parse_json(&res.text().await?)?;
+
+        let field: &str = match target_blocks {
+            0 | 1 => "fastestFee",
+            2 | 3 => "halfHourFee",
+            4..=6 => "hourFee",
+            _ => "economyFee",
+        };
+        let sat_per_vb: f64 = json
+            .get(field)
+            .and_then(|v| v.as_f64())
+            .ok_or_else(|| Error::InvalidResponse(format!("missing `{field}`")))?;
+        Ok(FeeRate::from_sat_per_vb(sat_per_vb as f32))
+    }
+}
+
+/// Bitcoin Core `estimatesmartfee` RPC
+#[derive(Debug, Clone)]
+pub struct BitcoinCoreFeeEstimator {
+    client: HttpClient,
+    url: Url,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl BitcoinCoreFeeEstimator {
+    pub fn new(
+        url: Url,
+        username: Option<String>,
+        password: Option<String>,
+        proxy: Option<SocketAddr>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            client: http_client(proxy)?,
+            url,
+            username,
+            password,
+        })
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for BitcoinCoreFeeEstimator {
+    fn name(&self) -> String {
+        format!("bitcoin core ({})", self.url)
+    }
+
+    async fn estimate_fee(&self, target_blocks: u8) -> Result<FeeRate, Error> {
+        let body = json!({
+            "jsonrpc": "1.0",
+            "id": "smartvaults",
+            "method": "estimatesmartfee",
+            "params": [target_blocks],
+        });
+        let mut req = self.client.post(self.url.clone()).body(body.to_string());
+        if let Some(username) = &self.username {
+            req = req.basic_auth(username, self.password.as_ref());
+        }
+        let res = req.send().await?.error_for_status()?;
+        let json: Value = parse_json(&res.text().await?)?;
+
+        if let Some(error) = json.get("error").filter(|e| !e.is_null()) {
+            return Err(Error::InvalidResponse(error.to_string()));
+        }
+        let btc_per_kvb: f64 = json
+            .get("result")
+            .and_then(|r| r.get("feerate"))
+            .and_then(|f| f.as_f64())
+            .ok_or(Error::NotAvailable(target_blocks))?;
+        Ok(FeeRate::from_btc_per_kvb(btc_per_kvb as f32))
+    }
+}
+
+/// Manually configured fee rates (sat/vByte)
+#[derive(Debug, Clone, Copy)]
+pub struct StaticFeeEstimator {
+    pub high: f32,
+    pub medium: f32,
+    pub low: f32,
+}
+
+#[async_trait]
+impl FeeEstimator for StaticFeeEstimator {
+    fn name(&self) -> String {
+        String::from("static")
+    }
+
+    async fn estimate_fee(&self, target_blocks: u8) -> Result<FeeRate, Error> {
+        let sat_per_vb: f32 = match target_blocks {
+            0 | 1 => self.high,
+            2..=6 => self.medium,
+            _ => self.low,
+        };
+        Ok(FeeRate::from_sat_per_vb(sat_per_vb))
+    }
+}
+
+fn parse_json(text: &str) -> Result<Value, Error> {
+    serde_json::from_str(text).map_err(|e| Error::InvalidResponse(e.to_string()))
+}
+
+fn http_client(proxy: Option<SocketAddr>) -> Result<HttpClient, Error> {
+    let mut builder = HttpClient::builder().timeout(SEND_TIMEOUT);
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(Proxy::all(format!("socks5h://{proxy}"))?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Lower fee rates (sat/vByte) of the blocks of the last 24h
+///
+/// Use the fee-rate percentiles of the mempool.space API (`/api/v1/mining/blocks/fee-rates/24h`):
+/// for every block, the 10th percentile of the included transactions.
+pub async fn recent_block_fee_rates(
+    url: &Url,
+    proxy: Option<SocketAddr>,
+) -> Result<Vec<f64>, Error> {
+    let client: HttpClient = http_client(proxy)?;
+    let endpoint = format!(
+        "{}/api/v1/mining/blocks/fee-rates/24h",
+        url.as_str().trim_end_matches('/')
+    );
+    let res = client.get(endpoint).send().await?.error_for_status()?;
+    let json: Value = parse_json(&res.text().await?)?;
+    let blocks = json
+        .as_array()
+        .ok_or_else(|| Error::InvalidResponse(String::from("expected an array")))?;
+    Ok(blocks
+        .iter()
+        .filter_map(|b| b.get("avgFee_10").and_then(|f| f.as_f64()))
+        .collect())
+}
+
+/// Fee histogram of the mempool from the mempool.space API (`/api/mempool`)
+///
+/// See [`parse_fee_histogram`].
+pub async fn mempool_space_fee_histogram(
+    url: &Url,
+    proxy: Option<SocketAddr>,
+) -> Result<Vec<(f64, u64)>, Error> {
+    let client: HttpClient = http_client(proxy)?;
+    let endpoint = format!("{}/api/mempool", url.as_str().trim_end_matches('/'));
+    let res = client.get(endpoint).send().await?.error_for_status()?;
+    let json: Value = parse_json(&res.text().await?)?;
+    let histogram = json
+        .get("fee_histogram")
+        .ok_or_else(|| Error::InvalidResponse(String::from("missing `fee_histogram`")))?;
+    parse_fee_histogram(histogram)
+}
+
+/// Parse a fee histogram: an array of `[fee_rate, vsize]` pairs
+///
+/// Every pair is the size (vBytes) of the mempool transactions paying at least `fee_rate`
+/// (sat/vByte) and less than the fee rate of the previous pair. The pairs are returned highest
+/// fee rate first.
+pub fn parse_fee_histogram(json: &Value) -> Result<Vec<(f64, u64)>, Error> {
+    let pairs = json
+        .as_array()
+        .ok_or_else(|| Error::InvalidResponse(String::from("expected an array")))?;
+    let mut histogram: Vec<(f64, u64)> = pairs
+        .iter()
+        .map(|pair| match pair.as_array().map(|p| p.as_slice()) {
+            Some([rate, vsize]) => match (rate.as_f64(), vsize.as_f64()) {
+                (Some(rate), Some(vsize)) => Ok((rate, vsize as u64)),
+                _ => Err(Error::InvalidResponse(format!("invalid pair {pair}"))),
+            },
+            _ => Err(Error::InvalidResponse(format!("invalid pair {pair}"))),
+        })
+        .collect::<Result<_, Error>>()?;
+    histogram.sort_by(|a, b| b.0.total_cmp(&a.0));
+    Ok(histogram)
+}
+
+/// Fee rate (sat/vByte) needed to be included within the next `target_blocks` blocks, given the
+/// fee histogram of the mempool (highest fee rate first)
+///
+/// The blocks are filled with the mempool transactions by fee rate: if the mempool doesn't fill
+/// them, the min relay fee rate is returned.
+pub fn fee_rate_from_histogram(histogram: &[(f64, u64)], target_blocks: u8) -> f64 {
+    let limit: u64 = target_blocks.max(1) as u64 * BLOCK_VSIZE;
+    let mut size: u64 = 0;
+    for (rate, vsize) in histogram.iter() {
+        size += vsize;
+        if size >= limit {
+            return rate.max(MIN_RELAY_FEE_RATE);
+        }
+    }
+    MIN_RELAY_FEE_RATE
+}
+
+/// Fee rate likely to confirm within `target_blocks`, given the lower fee rates of the recent blocks
+///
+/// The transaction confirms if its fee rate is above the lower fee rate of at least one of the next `target_blocks` blocks:
+/// assuming independent blocks, the rate is chosen so that this happens with a 90% probability.
+pub fn fee_rate_for_target(mut rates: Vec<f64>, target_blocks: u8) -> Option<f64> {
+    if rates.is_empty() {
+        return None;
+    }
+    rates.sort_by(|a, b| a.total_cmp(b));
+
+    // Max fraction of blocks with a lower fee rate above the suggested one
+    let target_blocks: i32 = target_blocks.max(1) as i32;
+    let above: f64 = (1.0 - CONFIRMATION_PROBABILITY).powf(1.0 / target_blocks as f64);
+
+    let index: usize = ((1.0 - above) * (rates.len() - 1) as f64).ceil() as usize;
+    rates.get(index.min(rates.len() - 1)).copied()
+}
+
+/// Build the estimator of the [`FeeProvider`]
+///
+/// The electrum endpoint is required only by [`FeeProvider::Electrum`].
+pub fn estimator(
+    provider: FeeProvider,
+    electrum_endpoint: Option<ElectrumEndpoint>,
+    proxy: Option<SocketAddr>,
+) -> Result<Box<dyn FeeEstimator>, Error> {
+    Ok(match provider {
+        FeeProvider::Electrum => match electrum_endpoint {
+            Some(endpoint) => Box::new(ElectrumFeeEstimator::new(endpoint, proxy)),
+            None => return Err(Error::ElectrumEndpointNotSet),
+        },
+        FeeProvider::MempoolSpace { url } => Box::new(MempoolSpaceFeeEstimator::new(url, proxy)?),
+        FeeProvider::BitcoinCore {
+            url,
+            username,
+            password,
+        } => Box::new(BitcoinCoreFeeEstimator::new(
+            url, username, password, proxy,
+        )?),
+        FeeProvider::Static { high, medium, low } => {
+            Box::new(StaticFeeEstimator { high, medium, low })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_rate_for_target() {
+        assert_eq!(fee_rate_for_target(Vec::new(), 1), None);
+
+        let rates: Vec<f64> = (1..=101).map(|r| r as f64).rev().collect();
+        assert_eq!(fee_rate_for_target(rates.clone(), 1), Some(91.0));
+        assert_eq!(fee_rate_for_target(rates.clone(), 0), Some(91.0));
+
+        let six: f64 = fee_rate_for_target(rates.clone(), 6).unwrap();
+        let day: f64 = fee_rate_for_target(rates, 144).unwrap();
+        assert!(six < 91.0);
+        assert!(day < six);
+        assert!(day >= 1.0);
+    }
+
+    #[test]
+    fn test_fee_histogram() {
+        let json: Value = json!([[2.5, 800000], [40, 300000], [10.1, 500000], [1, 2000000]]);
+        let histogram = parse_fee_histogram(&json).unwrap();
+        assert_eq!(
+            histogram,
+            vec![
+                (40.0, 300000),
+                (10.1, 500000),
+                (2.5, 800000),
+                (1.0, 2000000)
+            ]
+        );
+        assert!(parse_fee_histogram(&json!([[1]])).is_err());
+        assert!(parse_fee_histogram(&json!({})).is_err());
+
+        assert_eq!(fee_rate_from_histogram(&histogram, 1), 2.5);
+        assert_eq!(fee_rate_from_histogram(&histogram, 0), 2.5);
+        assert_eq!(fee_rate_from_histogram(&histogram, 3), 1.0);
+        assert_eq!(fee_rate_from_histogram(&histogram, 6), 1.0);
+        assert_eq!(fee_rate_from_histogram(&histogram[..1], 1), 1.0);
+        assert_eq!(fee_rate_from_histogram(&[], 1), 1.0);
+    }
+}
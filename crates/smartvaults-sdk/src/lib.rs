@@ -8,19 +8,23 @@
 
 pub use {nostr_sdk as nostr, smartvaults_core as core, smartvaults_protocol as protocol};
 
+pub mod bundle;
 pub mod client;
 pub mod config;
 pub mod constants;
 mod error;
+pub mod fee;
 pub mod logger;
 pub mod manager;
+pub mod pin;
+pub mod plugin;
 pub mod prelude;
 mod storage;
 pub mod types;
 pub mod util;
 
 pub use self::client::{EventHandled, Message, SmartVaults};
-pub use self::error::Error;
+pub use self::error::{Error, ErrorContext, ErrorKind};
 pub use self::types::PolicyBackup;
 
 pub fn git_hash_version() -> Option<String> {
@@ -0,0 +1,251 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! SPV header chain
+//!
+//! Lightweight verification of the confirmations reported by the electrum server: the headers are validated
+//! (chain linking, proof of work and, on mainnet and signet, difficulty constant within a retarget period)
+//! and the merkle proof of every confirmed transaction is checked against the header at its height.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bdk_electrum::electrum_client::{
+    self, utils, Client as ElectrumClient, ElectrumApi, GetMerkleRes, HeaderNotification,
+};
+use smartvaults_core::bitcoin::block::Header;
+use smartvaults_core::bitcoin::{BlockHash, Network, Txid};
+use thiserror::Error;
+use tokio::sync::RwLock;
+
+/// Blocks of a difficulty period
+const RETARGET_INTERVAL: u32 = 2016;
+/// Max number of headers requested to the electrum server at once
+const HEADERS_CHUNK: u32 = 2016;
+/// Min number of headers kept
+const MIN_HEADERS: u32 = RETARGET_INTERVAL;
+/// Max number of headers kept (~1 year): older confirmations are not verified
+pub const MAX_HEADERS: u32 = RETARGET_INTERVAL * 26;
+/// Headers dropped when the new ones don't connect to the chain
+const REORG_DEPTH: usize = 10;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Electrum(#[from] electrum_client::Error),
+    #[error("header at height {0} doesn't connect to the previous one")]
+    Disconnected(u32),
+    #[error("invalid proof of work at height {0}")]
+    InvalidPow(u32),
+    #[error("unexpected difficulty change at height {0}")]
+    UnexpectedDifficulty(u32),
+}
+
+#[derive(Debug, Default)]
+struct InnerHeaderChain {
+    /// Height of the first header
+    start: u32,
+    headers: Vec<Header>,
+    /// Confirmations with a valid merkle proof (height and block hash)
+    verified: HashMap<Txid, (u32, BlockHash)>,
+}
+
+impl InnerHeaderChain {
+    fn header(&self, height: u32) -> Option<&Header> {
+        let index: u32 = height.checked_sub(self.start)?;
+        self.headers.get(index as usize)
+    }
+
+    /// Height of the next header
+    fn next_height(&self) -> u32 {
+        self.start + self.headers.len() as u32
+    }
+
+    fn clear(&mut self) {
+        self.start = 0;
+        self.headers.clear();
+        self.verified.clear();
+    }
+}
+
+/// Validated header chain, from at most [`MAX_HEADERS`] blocks ago to the tip
+#[derive(Debug, Clone)]
+pub struct HeaderChain {
+    network: Network,
+    inner: Arc<RwLock<InnerHeaderChain>>,
+}
+
+impl HeaderChain {
+    pub fn new(network: Network) -> Self {
+        Self {
+            network,
+            inner: Arc::new(RwLock::new(InnerHeaderChain::default())),
+        }
+    }
+
+    fn validate(&self, height: u32, prev: Option<&Header>, header: &Header) -> Result<(), Error> {
+        if let Some(prev) = prev {
+            if header.prev_blockhash != prev.block_hash() {
+                return Err(Error::Disconnected(height));
+            }
+
+            // Testnet allows min difficulty blocks
+            if matches!(self.network, Network::Bitcoin | Network::Signet)
+                && height % RETARGET_INTERVAL != 0
+                && header.bits != prev.bits
+            {
+                return Err(Error::UnexpectedDifficulty(height));
+            }
+        }
+
+        header
+            .validate_pow(header.target())
+            .map_err(|_| Error::InvalidPow(height))?;
+
+        Ok(())
+    }
+
+    fn validate_all(
+        &self,
+        start: u32,
+        mut prev: Option<&Header>,
+        headers: &[Header],
+    ) -> Result<(), Error> {
+        for (index, header) in headers.iter().enumerate() {
+            self.validate(start + index as u32, prev, header)?;
+            prev = Some(header);
+        }
+        Ok(())
+    }
+
+    /// Fetch the headers in `[from, to)`
+    fn fetch(client: &ElectrumClient, from: u32, to: u32) -> Result<Vec<Header>, Error> {
+        let mut headers: Vec<Header> = Vec::new();
+        let mut height: u32 = from;
+        while height < to {
+            let count: u32 = (to - height).min(HEADERS_CHUNK);
+            let res = client.block_headers(height as usize, count as usize)?;
+            if res.headers.is_empty() {
+                break;
+            }
+            height += res.headers.len() as u32;
+            headers.extend(res.headers);
+        }
+        Ok(headers)
+    }
+
+    /// Sync the headers up to the tip
+    ///
+    /// The headers from `from_height` are kept, if within [`MAX_HEADERS`] blocks from the tip.
+    pub async fn sync(
+        &self,
+        client: &ElectrumClient,
+        from_height: Option<u32>,
+    ) -> Result<(), Error> {
+        let HeaderNotification { height: tip, .. } = client.block_headers_subscribe()?;
+        let tip: u32 = tip as u32;
+        let min_start: u32 = tip.saturating_sub(MAX_HEADERS - 1);
+        let start: u32 = from_height
+            .unwrap_or(tip)
+            .min(tip.saturating_sub(MIN_HEADERS - 1))
+            .max(min_start);
+
+        let mut chain = self.inner.write().await;
+
+        if chain.headers.is_empty() || chain.next_height() < start {
+            let headers: Vec<Header> = Self::fetch(client, start, tip + 1)?;
+            self.validate_all(start, None, &headers)?;
+            chain.clear();
+            chain.start = start;
+            chain.headers = headers;
+            tracing::info!("Header chain initialized from height {start}");
+            return Ok(());
+        }
+
+        // Extend backward
+        if start < chain.start {
+            let older: Vec<Header> = Self::fetch(client, start, chain.start)?;
+            self.validate_all(start, None, &older)?;
+            self.validate(chain.start, older.last(), &chain.headers[0])?;
+            chain.start = start;
+            chain.headers.splice(0..0, older);
+        }
+
+        // Extend forward (dropping the last headers if reorged)
+        let mut attempts: u8 = 0;
+        loop {
+            let next: u32 = chain.next_height();
+            if next > tip + 1 {
+                let len: usize = (tip + 1 - chain.start) as usize;
+                chain.headers.truncate(len);
+                continue;
+            }
+
+            let new: Vec<Header> = Self::fetch(client, next, tip + 1)?;
+            match self.validate_all(next, chain.headers.last(), &new) {
+                Ok(()) => {
+                    chain.headers.extend(new);
+                    break;
+                }
+                Err(Error::Disconnected(height)) if height == next && attempts < 1 => {
+                    tracing::warn!("Header chain reorg at height {height}");
+                    let len: usize = chain.headers.len().saturating_sub(REORG_DEPTH);
+                    chain.headers.truncate(len);
+                    attempts += 1;
+                }
+                Err(e) => {
+                    chain.clear();
+                    return Err(e);
+                }
+            }
+
+            if chain.headers.is_empty() {
+                chain.clear();
+                return Err(Error::Disconnected(next));
+            }
+        }
+
+        // Trim
+        if chain.start < min_start {
+            let len: usize = (min_start - chain.start) as usize;
+            chain.headers.drain(..len);
+            chain.start = min_start;
+        }
+
+        Ok(())
+    }
+
+    /// Verify the merkle proof of a confirmed transaction against the header at `height`
+    pub async fn verify_tx(
+        &self,
+        client: &ElectrumClient,
+        txid: Txid,
+        height: u32,
+    ) -> Result<bool, Error> {
+        let header: Header = match self.inner.read().await.header(height) {
+            Some(header) => *header,
+            None => return Ok(false),
+        };
+
+        let res: GetMerkleRes = client.transaction_get_merkle(&txid, height as usize)?;
+        if res.block_height != height as usize
+            || !utils::validate_merkle_proof(&txid, &header.merkle_root, &res)
+        {
+            tracing::warn!("Invalid merkle proof for tx {txid} at height {height}");
+            return Ok(false);
+        }
+
+        let mut chain = self.inner.write().await;
+        chain.verified.insert(txid, (height, header.block_hash()));
+        Ok(true)
+    }
+
+    /// Check if the confirmation at `height` was verified (and the block is still in the chain)
+    pub async fn is_verified(&self, txid: &Txid, height: u32) -> bool {
+        let chain = self.inner.read().await;
+        match (chain.verified.get(txid), chain.header(height)) {
+            (Some((h, hash)), Some(header)) => *h == height && *hash == header.block_hash(),
+            _ => false,
+        }
+    }
+}
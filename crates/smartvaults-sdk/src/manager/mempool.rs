@@ -0,0 +1,93 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Mempool watcher
+//!
+//! Subscribe to the scripthash notifications of the revealed scripts of the loaded wallets, so the
+//! unconfirmed transactions (i.e. incoming deposits) are detected without waiting for the next wallet sync.
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+
+use bdk_electrum::electrum_client::{
+    self, Client as ElectrumClient, Config as ElectrumConfig, ElectrumApi, GetHistoryRes,
+    Socks5Config,
+};
+use nostr_sdk::EventId;
+use smartvaults_core::bitcoin::{ScriptBuf, Transaction, Txid};
+
+use crate::config::ElectrumEndpoint;
+
+/// Scripthash subscriptions on a dedicated electrum connection
+pub struct MempoolWatcher {
+    endpoint: ElectrumEndpoint,
+    client: ElectrumClient,
+    /// Subscribed scripts and the wallet they belong to
+    scripts: HashMap<ScriptBuf, EventId>,
+    /// Transactions already notified
+    seen: HashSet<Txid>,
+}
+
+impl MempoolWatcher {
+    pub fn new(
+        endpoint: ElectrumEndpoint,
+        proxy: Option<SocketAddr>,
+    ) -> Result<Self, electrum_client::Error> {
+        let config = ElectrumConfig::builder()
+            .validate_domain(endpoint.validate_tls())
+            .socks5(proxy.map(Socks5Config::new))
+            .build();
+        let client = ElectrumClient::from_config(&endpoint.as_non_standard_format(), config)?;
+        Ok(Self {
+            endpoint,
+            client,
+            scripts: HashMap::new(),
+            seen: HashSet::new(),
+        })
+    }
+
+    pub fn endpoint(&self) -> &ElectrumEndpoint {
+        &self.endpoint
+    }
+
+    /// Subscribe to the scripts not subscribed yet
+    pub fn subscribe<I>(
+        &mut self,
+        policy_id: EventId,
+        scripts: I,
+    ) -> Result<(), electrum_client::Error>
+    where
+        I: IntoIterator<Item = ScriptBuf>,
+    {
+        for script in scripts.into_iter() {
+            if !self.scripts.contains_key(&script) {
+                self.client.script_subscribe(&script)?;
+                self.scripts.insert(script, policy_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the new unconfirmed transactions of the scripts with a status update
+    pub fn poll(&mut self) -> Result<Vec<(EventId, Transaction)>, electrum_client::Error> {
+        // Read the pending notifications
+        self.client.ping()?;
+
+        let mut txs: Vec<(EventId, Transaction)> = Vec::new();
+        for (script, policy_id) in self.scripts.iter() {
+            if self.client.script_pop(script)?.is_none() {
+                continue;
+            }
+
+            let history: Vec<GetHistoryRes> = self.client.script_get_history(script)?;
+            for item in history.into_iter() {
+                // Height is `0` or `-1` for mempool transactions
+                if item.height <= 0 && self.seen.insert(item.tx_hash) {
+                    let tx: Transaction = self.client.transaction_get(&item.tx_hash)?;
+                    txs.push((*policy_id, tx));
+                }
+            }
+        }
+        Ok(txs)
+    }
+}
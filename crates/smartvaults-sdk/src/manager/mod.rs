@@ -22,20 +22,25 @@ use smartvaults_core::bdk::{FeeRate, LocalOutput, Wallet};
 use smartvaults_core::bitcoin::address::NetworkUnchecked;
 use smartvaults_core::bitcoin::psbt::PartiallySignedTransaction;
 use smartvaults_core::bitcoin::{Address, Network, OutPoint, ScriptBuf, Transaction, Txid};
-use smartvaults_core::{Amount, Policy, Priority, Proposal};
+use smartvaults_core::{Amount, Policy, Priority, Proposal, Recipient, TxOptions};
 use smartvaults_sdk_sqlite::Store;
 use thiserror::Error;
 use tokio::sync::broadcast::Sender;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 
+pub mod headers;
+pub mod mempool;
 pub mod wallet;
 
+use self::headers::{Error as HeadersError, HeaderChain};
+use self::mempool::MempoolWatcher;
 pub use self::wallet::{
-    Error as WalletError, SmartVaultsWallet, SmartVaultsWalletStorage, StorageError,
-    TransactionDetails,
+    Error as WalletError, SmartVaultsWallet, SmartVaultsWalletStorage, SpendPreview,
+    SpendPreviewOutput, StorageError, TransactionDetails,
 };
 use crate::config::ElectrumEndpoint;
 use crate::constants::{BLOCK_HEIGHT_SYNC_INTERVAL, MEMPOOL_TX_FEES_SYNC_INTERVAL};
+use crate::fee::{Error as FeeError, FeeEstimator};
 use crate::Message;
 
 const TARGET_BLOCKS: [Priority; 3] = [Priority::High, Priority::Medium, Priority::Low];
@@ -52,10 +57,16 @@ pub enum Error {
     Wallet(#[from] WalletError),
     #[error(transparent)]
     Join(#[from] tokio::task::JoinError),
+    #[error(transparent)]
+    Store(#[from] smartvaults_sdk_sqlite::Error),
     #[error("policy {0} already loaded")]
     AlreadyLoaded(EventId),
     #[error("policy {0} not loaded")]
     NotLoaded(EventId),
+    #[error(transparent)]
+    Fee(#[from] FeeError),
+    #[error(transparent)]
+    Headers(#[from] HeadersError),
 }
 
 #[derive(Debug, Clone, Default)]
@@ -65,6 +76,20 @@ pub struct BlockHeight {
 }
 
 impl BlockHeight {
+    /// Unload the policy, delete its wallet state and load it again from scratch
+    #[tracing::instrument(skip_all, level = "trace")]
+    pub async fn reset_policy(&self, policy_id: EventId, policy: Policy) -> Result<(), Error> {
+        match self.unload_policy(policy_id).await {
+            Ok(()) | Err(Error::NotLoaded(..)) => (),
+            Err(e) => return Err(e),
+        }
+        let descriptor_hash = Sha256Hash::hash(policy.as_descriptor().to_string().as_bytes());
+        self.db.delete_changeset(descriptor_hash).await?;
+        self.load_policy(policy_id, policy).await?;
+        tracing::info!("Policy {policy_id} reset");
+        Ok(())
+    }
+
     pub fn block_height(&self) -> u32 {
         self.height.load(Ordering::SeqCst)
     }
@@ -120,7 +145,10 @@ pub struct Manager {
     db: Store,
     network: Network,
     wallets: Arc<RwLock<HashMap<EventId, SmartVaultsWallet>>>,
+    /// Policies registered but not loaded yet (lazy loading)
+    unloaded: Arc<RwLock<HashMap<EventId, Policy>>>,
     block_height: BlockHeight,
+    headers: HeaderChain,
     mempool_fees: EstimatedMempoolFees,
 }
 
@@ -130,19 +158,55 @@ impl Manager {
             db,
             network,
             wallets: Arc::new(RwLock::new(HashMap::new())),
+            unloaded: Arc::new(RwLock::new(HashMap::new())),
             block_height: BlockHeight::default(),
+            headers: HeaderChain::new(network),
             mempool_fees: EstimatedMempoolFees::default(),
         }
     }
 
+    /// Register policy without loading it
+    ///
+    /// The policy will be loaded on first access or during [`Manager::hydrate`].
+    #[tracing::instrument(skip_all, level = "trace")]
+    pub async fn register_policy(&self, policy_id: EventId, policy: Policy) {
+        let wallets = self.wallets.read().await;
+        if !wallets.contains_key(&policy_id) {
+            let mut unloaded = self.unloaded.write().await;
+            unloaded.insert(policy_id, policy);
+        }
+    }
+
+    /// Load all the registered policies not loaded yet
+    #[tracing::instrument(skip_all, level = "trace")]
+    pub async fn hydrate(&self) {
+        let policy_ids: Vec<EventId> = self.unloaded.read().await.keys().copied().collect();
+        for policy_id in policy_ids.into_iter() {
+            if let Err(e) = self.load_registered_policy(policy_id).await {
+                tracing::error!("Impossible to load policy {policy_id}: {e}");
+            }
+        }
+        tracing::info!("All policies loaded");
+    }
+
+    async fn load_registered_policy(&self, policy_id: EventId) -> Result<bool, Error> {
+        let policy: Option<Policy> = self.unloaded.write().await.remove(&policy_id);
+        match policy {
+            Some(policy) => match self.load_policy(policy_id, policy).await {
+                Ok(()) | Err(Error::AlreadyLoaded(..)) => Ok(true),
+                Err(e) => Err(e),
+            },
+            None => Ok(false),
+        }
+    }
+
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn load_policy(&self, policy_id: EventId, policy: Policy) -> Result<(), Error> {
         let this = self.clone();
         let mut wallets = self.wallets.write().await;
         if let Entry::Vacant(e) = wallets.entry(policy_id) {
+            let descriptor_hash = Sha256Hash::hash(policy.as_descriptor().to_string().as_bytes());
             let wallet: SmartVaultsWallet = tokio::task::spawn_blocking(move || {
-                let descriptor_hash =
-                    Sha256Hash::hash(policy.as_descriptor().to_string().as_bytes());
                 let db: SmartVaultsWalletStorage =
                     SmartVaultsWalletStorage::new(descriptor_hash, this.db.clone());
                 let wallet: Wallet<SmartVaultsWalletStorage> = Wallet::new_or_load(
@@ -154,6 +218,15 @@ impl Manager {
                 Ok::<SmartVaultsWallet, Error>(SmartVaultsWallet::new(policy_id, policy, wallet))
             })
             .await??;
+
+            // Restore last sync from checkpoint
+            if let Ok(last_sync) = self.db.get_wallet_last_sync(descriptor_hash).await {
+                wallet.set_last_sync(last_sync);
+            }
+            if let Ok(last_full_scan) = self.db.get_wallet_last_full_scan(descriptor_hash).await {
+                wallet.set_last_full_scan(last_full_scan);
+            }
+
             e.insert(wallet);
             tracing::info!("Loaded policy {policy_id}");
             Ok(())
@@ -162,19 +235,45 @@ impl Manager {
         }
     }
 
+    /// Persist wallets state and last sync timestamps
+    #[tracing::instrument(skip_all, level = "trace")]
+    pub async fn checkpoint(&self) -> Result<(), Error> {
+        let wallets = self.wallets.read().await;
+        for (id, wallet) in wallets.iter() {
+            wallet.commit().await?;
+            let last_sync: Timestamp = wallet.last_sync();
+            if last_sync > Timestamp::from(0) {
+                self.db
+                    .save_wallet_last_sync(wallet.descriptor_hash(), last_sync)
+                    .await?;
+            }
+            let last_full_scan: Timestamp = wallet.last_full_scan();
+            if last_full_scan > Timestamp::from(0) {
+                self.db
+                    .save_wallet_last_full_scan(wallet.descriptor_hash(), last_full_scan)
+                    .await?;
+            }
+            tracing::debug!("Checkpoint saved for policy {id}");
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn unload_policies(&self) {
         let mut wallets = self.wallets.write().await;
         wallets.clear();
+        let mut unloaded = self.unloaded.write().await;
+        unloaded.clear();
         tracing::info!("All policies unloaded.")
     }
 
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn unload_policy(&self, policy_id: EventId) -> Result<(), Error> {
         let mut wallets = self.wallets.write().await;
-        match wallets.remove(&policy_id) {
-            Some(_) => Ok(()),
-            None => Err(Error::NotLoaded(policy_id)),
+        let mut unloaded = self.unloaded.write().await;
+        match (wallets.remove(&policy_id), unloaded.remove(&policy_id)) {
+            (None, None) => Err(Error::NotLoaded(policy_id)),
+            _ => Ok(()),
         }
     }
 
@@ -210,42 +309,120 @@ impl Manager {
         Ok(())
     }
 
-    pub async fn sync_mempool_fees(
+    /// Sync the SPV header chain and verify the confirmations of the loaded wallets
+    pub async fn sync_headers(
         &self,
         endpoint: ElectrumEndpoint,
         proxy: Option<SocketAddr>,
-    ) -> Result<Option<BTreeMap<Priority, FeeRate>>, Error> {
-        if !self.mempool_fees.is_synced().await {
-            tracing::info!("Initializing electrum client: endpoint={endpoint}, proxy={proxy:?}");
-            let proxy: Option<Socks5Config> = proxy.map(Socks5Config::new);
-            let config = ElectrumConfig::builder()
-                .validate_domain(endpoint.validate_tls())
-                .socks5(proxy)
-                .build();
-            let client = ElectrumClient::from_config(&endpoint.as_non_standard_format(), config)?;
+    ) -> Result<(), Error> {
+        let mut confirmed: Vec<(Txid, u32)> = Vec::new();
+        for wallet in self.loaded_wallets().await.into_values() {
+            for tx in wallet.txs().await.into_iter() {
+                if let ConfirmationTime::Confirmed { height, .. } = tx.confirmation_time {
+                    confirmed.push((tx.txid(), height));
+                }
+            }
+        }
+
+        let proxy: Option<Socks5Config> = proxy.map(Socks5Config::new);
+        let config = ElectrumConfig::builder()
+            .validate_domain(endpoint.validate_tls())
+            .socks5(proxy)
+            .build();
+        let client = ElectrumClient::from_config(&endpoint.as_non_standard_format(), config)?;
+
+        let from_height: Option<u32> = confirmed.iter().map(|(_, height)| *height).min();
+        self.headers.sync(&client, from_height).await?;
 
-            let fees: Vec<f64> = client
-                .batch_estimate_fee(TARGET_BLOCKS.iter().map(|p| p.target_blocks() as usize))?;
-            if TARGET_BLOCKS.len() == fees.len() {
-                let mut estimated_fees = BTreeMap::new();
-                for (priority, btc_per_kvb) in TARGET_BLOCKS.into_iter().zip(fees) {
-                    let rate = FeeRate::from_btc_per_kvb(btc_per_kvb as f32);
-                    estimated_fees.insert(priority, rate);
+        for (txid, height) in confirmed.into_iter() {
+            if !self.headers.is_verified(&txid, height).await {
+                if let Err(e) = self.headers.verify_tx(&client, txid, height).await {
+                    tracing::warn!("Impossible to verify confirmation of tx {txid}: {e}");
                 }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Subscribe the revealed scripts of the loaded wallets and insert the new unconfirmed transactions
+    ///
+    /// Return the inserted transactions.
+    pub async fn watch_mempool(
+        &self,
+        watcher: &mut MempoolWatcher,
+    ) -> Result<Vec<(EventId, Txid)>, Error> {
+        for (policy_id, wallet) in self.loaded_wallets().await.into_iter() {
+            watcher.subscribe(policy_id, wallet.revealed_spks().await.into_keys())?;
+        }
 
-                // Save
-                self.mempool_fees.set_fees(estimated_fees.clone()).await;
-                self.mempool_fees.just_synced().await;
-                tracing::info!("Mempool fees synced");
+        let mut inserted: Vec<(EventId, Txid)> = Vec::new();
+        for (policy_id, tx) in watcher.poll()?.into_iter() {
+            let txid: Txid = tx.txid();
+            let position = ConfirmationTime::Unconfirmed {
+                last_seen: Timestamp::now().as_u64(),
+            };
+            if self.insert_tx(policy_id, tx, position).await? {
+                tracing::info!("New unconfirmed tx {txid} for policy {policy_id}");
+                inserted.push((policy_id, txid));
+            }
+        }
+        Ok(inserted)
+    }
 
-                return Ok(Some(estimated_fees));
+    /// Check if the confirmation of the transaction was verified against the SPV header chain
+    pub async fn is_confirmation_verified(&self, tx: &TransactionDetails) -> bool {
+        match tx.confirmation_time {
+            ConfirmationTime::Confirmed { height, .. } => {
+                self.headers.is_verified(&tx.txid(), height).await
             }
+            ConfirmationTime::Unconfirmed { .. } => false,
+        }
+    }
+
+    pub async fn sync_mempool_fees(
+        &self,
+        estimator: &dyn FeeEstimator,
+    ) -> Result<Option<BTreeMap<Priority, FeeRate>>, Error> {
+        if !self.mempool_fees.is_synced().await {
+            tracing::info!("Estimating mempool fees with {}", estimator.name());
+
+            let mut estimated_fees = BTreeMap::new();
+            for priority in TARGET_BLOCKS.into_iter() {
+                let rate: FeeRate = estimator.estimate_fee(priority.target_blocks()).await?;
+                estimated_fees.insert(priority, rate);
+            }
+
+            // Save
+            self.mempool_fees.set_fees(estimated_fees.clone()).await;
+            self.mempool_fees.just_synced().await;
+            tracing::info!("Mempool fees synced");
+
+            return Ok(Some(estimated_fees));
         }
 
         Ok(None)
     }
 
+    /// Get the last estimated mempool fees (empty if never synced)
+    pub async fn mempool_fees(&self) -> BTreeMap<Priority, FeeRate> {
+        self.mempool_fees.get().await
+    }
+
+    /// Get loaded wallets (without triggering the lazy loading)
+    pub async fn loaded_wallets(&self) -> HashMap<EventId, SmartVaultsWallet> {
+        self.wallets.read().await.clone()
+    }
+
+    /// Get wallet, loading it if registered but not loaded yet
     pub async fn wallet(&self, policy_id: EventId) -> Result<SmartVaultsWallet, Error> {
+        if let Some(wallet) = self.wallets.read().await.get(&policy_id) {
+            return Ok(wallet.clone());
+        }
+
+        // Lazy load
+        self.load_registered_policy(policy_id).await?;
+
         let wallets = self.wallets.read().await;
         Ok(wallets
             .get(&policy_id)
@@ -253,6 +430,12 @@ impl Manager {
             .clone())
     }
 
+    /// Pin/unpin wallet (loading it, if not loaded yet)
+    pub async fn set_pinned(&self, policy_id: EventId, pinned: bool) -> Result<(), Error> {
+        self.wallet(policy_id).await?.set_pinned(pinned);
+        Ok(())
+    }
+
     pub async fn insert_tx(
         &self,
         policy_id: EventId,
@@ -312,19 +495,52 @@ impl Manager {
         Ok(self.wallet(policy_id).await?.get_utxos().await)
     }
 
-    /// Sync all policies with the timechain
-    pub async fn sync_all(
+    /// Get the spks of the revealed addresses, plus a stop gap
+    pub async fn get_revealed_spks(&self, policy_id: EventId) -> Result<Vec<ScriptBuf>, Error> {
+        Ok(self
+            .wallet(policy_id)
+            .await?
+            .revealed_spks()
+            .await
+            .into_keys()
+            .collect())
+    }
+
+    /// Sync the wallets concurrently, at most `max_concurrent` at a time
+    ///
+    /// The errors are isolated per wallet: the result is sent on the `sync_channel`
+    /// ([`Message::WalletSyncCompleted`] or [`Message::WalletSyncFailed`]).
+    async fn sync_wallets(
         &self,
         endpoint: ElectrumEndpoint,
         proxy: Option<SocketAddr>,
+        full: bool,
+        force: bool,
+        max_concurrent: usize,
         sync_channel: Option<Sender<Message>>,
     ) -> Result<(), Error> {
         let wallets = self.wallets.read().await;
+        let semaphore = Arc::new(Semaphore::new(max_concurrent.max(1)));
         for (id, wallet) in wallets.clone().into_iter() {
             let endpoint = endpoint.clone();
             let sync_channel = sync_channel.clone();
+            let semaphore = semaphore.clone();
             thread::spawn(async move {
-                match wallet.full_sync(endpoint, proxy, false).await {
+                let _permit = match semaphore.acquire_owned().await {
+                    Ok(permit) => permit,
+                    Err(e) => {
+                        tracing::error!("Impossible to sync policy {id}: {e}");
+                        return;
+                    }
+                };
+
+                let res = if full {
+                    wallet.full_sync(endpoint, proxy, force).await
+                } else {
+                    wallet.sync(endpoint, proxy, force).await
+                };
+
+                match res {
                     Ok(_) => {
                         if let Some(sync_channel) = sync_channel {
                             let _ = sync_channel.send(Message::WalletSyncCompleted(id));
@@ -334,24 +550,52 @@ impl Manager {
                     Err(WalletError::AlreadySyncing) => {
                         tracing::warn!("Policy {id} is already syncing");
                     }
-                    Err(e) => tracing::error!("Impossible to sync policy {id}: {e}"),
+                    Err(e) => {
+                        tracing::error!("Impossible to sync policy {id}: {e}");
+                        if let Some(sync_channel) = sync_channel {
+                            let _ = sync_channel.send(Message::WalletSyncFailed {
+                                policy_id: id,
+                                error: e.to_string(),
+                            });
+                        }
+                    }
                 }
             })?;
         }
         Ok(())
     }
 
-    /* /// Execute a timechain sync
+    /// Sync all policies with the timechain
+    ///
+    /// Incremental sync: a full sync is executed only if needed (see [`SmartVaultsWallet::sync`])
+    pub async fn sync_all(
+        &self,
+        endpoint: ElectrumEndpoint,
+        proxy: Option<SocketAddr>,
+        force: bool,
+        max_concurrent: usize,
+        sync_channel: Option<Sender<Message>>,
+    ) -> Result<(), Error> {
+        self.sync_wallets(endpoint, proxy, false, force, max_concurrent, sync_channel)
+            .await
+    }
+
+    /// Execute a timechain sync
     ///
-    /// If the local chain is empty, execute a full sync.
+    /// If the local chain is empty or the last full scan is too old, execute a full sync.
     pub async fn sync(
         &self,
         policy_id: EventId,
         endpoint: ElectrumEndpoint,
         proxy: Option<SocketAddr>,
+        force: bool,
     ) -> Result<(), Error> {
-        Ok(self.wallet(policy_id).await?.sync(endpoint, proxy).await?)
-    } */
+        Ok(self
+            .wallet(policy_id)
+            .await?
+            .sync(endpoint, proxy, force)
+            .await?)
+    }
 
     /// Full sync all policies with the timechain
     pub async fn full_sync_all(
@@ -359,28 +603,11 @@ impl Manager {
         endpoint: ElectrumEndpoint,
         proxy: Option<SocketAddr>,
         force: bool,
+        max_concurrent: usize,
         sync_channel: Option<Sender<Message>>,
     ) -> Result<(), Error> {
-        let wallets = self.wallets.read().await;
-        for (id, wallet) in wallets.clone().into_iter() {
-            let endpoint = endpoint.clone();
-            let sync_channel = sync_channel.clone();
-            thread::spawn(async move {
-                match wallet.full_sync(endpoint, proxy, force).await {
-                    Ok(_) => {
-                        if let Some(sync_channel) = sync_channel {
-                            let _ = sync_channel.send(Message::WalletSyncCompleted(id));
-                        }
-                    }
-                    Err(WalletError::AlreadySynced) => {}
-                    Err(WalletError::AlreadySyncing) => {
-                        tracing::warn!("Policy {id} is already syncing");
-                    }
-                    Err(e) => tracing::error!("Impossible to sync policy {id}: {e}"),
-                }
-            })?;
-        }
-        Ok(())
+        self.sync_wallets(endpoint, proxy, true, force, max_concurrent, sync_channel)
+            .await
     }
 
     /// Execute a **full** timechain sync.
@@ -424,6 +651,7 @@ impl Manager {
         utxos: Option<Vec<OutPoint>>,
         frozen_utxos: Option<Vec<OutPoint>>,
         policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        tx_options: TxOptions,
     ) -> Result<Proposal, Error>
     where
         S: Into<String>,
@@ -439,6 +667,81 @@ impl Manager {
                 utxos,
                 frozen_utxos,
                 policy_path,
+                tx_options,
+            )
+            .await?)
+    }
+
+    pub async fn preview_spend(
+        &self,
+        policy_id: EventId,
+        address: Address<NetworkUnchecked>,
+        amount: Amount,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        frozen_utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+    ) -> Result<SpendPreview, Error> {
+        Ok(self
+            .wallet(policy_id)
+            .await?
+            .preview_spend(address, amount, fee_rate, utxos, frozen_utxos, policy_path)
+            .await?)
+    }
+
+    pub async fn preview_spend_many(
+        &self,
+        policy_id: EventId,
+        recipients: Vec<Recipient>,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        frozen_utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        data: Option<Vec<u8>>,
+        tx_options: TxOptions,
+    ) -> Result<SpendPreview, Error> {
+        Ok(self
+            .wallet(policy_id)
+            .await?
+            .preview_spend_many(
+                recipients,
+                fee_rate,
+                utxos,
+                frozen_utxos,
+                policy_path,
+                data,
+                tx_options,
+            )
+            .await?)
+    }
+
+    pub async fn spend_many<S>(
+        &self,
+        policy_id: EventId,
+        recipients: Vec<Recipient>,
+        description: S,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        frozen_utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        data: Option<Vec<u8>>,
+        tx_options: TxOptions,
+    ) -> Result<Proposal, Error>
+    where
+        S: Into<String>,
+    {
+        Ok(self
+            .wallet(policy_id)
+            .await?
+            .spend_many(
+                recipients,
+                description,
+                fee_rate,
+                utxos,
+                frozen_utxos,
+                policy_path,
+                data,
+                tx_options,
             )
             .await?)
     }
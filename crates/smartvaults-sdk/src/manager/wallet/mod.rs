@@ -2,7 +2,7 @@
 // Distributed under the MIT software license
 
 use std::cmp::Ordering;
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::net::SocketAddr;
 use std::ops::Deref;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
@@ -12,6 +12,8 @@ use bdk_electrum::electrum_client::{
     Client as ElectrumClient, Config as ElectrumConfig, Socks5Config,
 };
 use bdk_electrum::{ElectrumExt, ElectrumUpdate};
+use nostr_sdk::hashes::sha256::Hash as Sha256Hash;
+use nostr_sdk::hashes::Hash;
 use nostr_sdk::{EventId, Timestamp};
 use smartvaults_core::bdk::chain::keychain::KeychainTxOutIndex;
 use smartvaults_core::bdk::chain::local_chain::{CannotConnectError, CheckPoint, LocalChain};
@@ -25,7 +27,7 @@ use smartvaults_core::bitcoin::address::NetworkUnchecked;
 use smartvaults_core::bitcoin::psbt::PartiallySignedTransaction;
 use smartvaults_core::bitcoin::{Address, OutPoint, Script, ScriptBuf, Transaction, Txid};
 use smartvaults_core::reserves::ProofOfReserves;
-use smartvaults_core::{Amount, Policy, Proposal};
+use smartvaults_core::{Amount, Policy, Proposal, Recipient, TxOptions};
 use thiserror::Error;
 use tokio::sync::RwLock;
 
@@ -33,7 +35,9 @@ mod storage;
 
 pub use self::storage::{Error as StorageError, SmartVaultsWalletStorage};
 use crate::config::ElectrumEndpoint;
-use crate::constants::WALLET_SYNC_INTERVAL;
+use crate::constants::{
+    PINNED_WALLET_SYNC_INTERVAL, WALLET_FULL_SCAN_INTERVAL, WALLET_SYNC_INTERVAL,
+};
 
 const STOP_GAP: usize = 50;
 const BATCH_SIZE: usize = 5;
@@ -80,6 +84,59 @@ impl PartialEq for Fee {
 
 impl Eq for Fee {}
 
+/// Output of a [`SpendPreview`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendPreviewOutput {
+    pub script_pubkey: ScriptBuf,
+    /// Amount in SAT
+    pub amount: u64,
+    /// Output paying back to the vault
+    pub is_change: bool,
+}
+
+/// Spending transaction built without persisting or publishing anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendPreview {
+    /// Selected UTXOs, with their amount in SAT
+    pub inputs: Vec<(OutPoint, u64)>,
+    pub outputs: Vec<SpendPreviewOutput>,
+    /// Fee in SAT
+    pub fee: u64,
+    /// Change in SAT (`0` if there isn't a change output)
+    pub change: u64,
+    /// Virtual size of the unsigned transaction
+    pub vsize: usize,
+    /// Estimated virtual size of the signed transaction (through the most expensive spending
+    /// path)
+    pub estimated_vsize: usize,
+    /// Policy path used to build the transaction (`None` if the policy has a single spending path)
+    pub policy_path: Option<BTreeMap<String, Vec<usize>>>,
+}
+
+impl SpendPreview {
+    /// Amount paid to the recipients in SAT (i.e. the drained amount when sending all the funds)
+    pub fn amount(&self) -> u64 {
+        self.outputs
+            .iter()
+            .filter(|o| !o.is_change)
+            .map(|o| o.amount)
+            .sum()
+    }
+
+    /// Sum of the selected UTXOs in SAT
+    pub fn total_input(&self) -> u64 {
+        self.inputs.iter().map(|(_, amount)| amount).sum()
+    }
+
+    /// Fee rate (sat/vB) of the signed transaction, based on the estimated vsize
+    pub fn effective_fee_rate(&self) -> f64 {
+        if self.estimated_vsize == 0 {
+            return 0.0;
+        }
+        self.fee as f64 / self.estimated_vsize as f64
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TransactionDetails {
     pub transaction: Transaction,
@@ -137,7 +194,9 @@ pub struct SmartVaultsWallet {
     policy: Policy,
     wallet: Arc<RwLock<Wallet<SmartVaultsWalletStorage>>>,
     syncing: Arc<AtomicBool>,
+    pinned: Arc<AtomicBool>,
     last_sync: Arc<AtomicU64>,
+    last_full_scan: Arc<AtomicU64>,
 }
 
 impl SmartVaultsWallet {
@@ -151,11 +210,13 @@ impl SmartVaultsWallet {
             policy,
             wallet: Arc::new(RwLock::new(wallet)),
             syncing: Arc::new(AtomicBool::new(false)),
+            pinned: Arc::new(AtomicBool::new(false)),
             last_sync: Arc::new(AtomicU64::new(0)),
+            last_full_scan: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    fn is_syncing(&self) -> bool {
+    pub fn is_syncing(&self) -> bool {
         self.syncing.load(AtomicOrdering::SeqCst)
     }
 
@@ -167,10 +228,32 @@ impl SmartVaultsWallet {
             });
     }
 
+    pub fn is_pinned(&self) -> bool {
+        self.pinned.load(AtomicOrdering::SeqCst)
+    }
+
+    /// Pinned wallets are synced more frequently
+    pub fn set_pinned(&self, pinned: bool) {
+        let _ = self
+            .pinned
+            .fetch_update(AtomicOrdering::SeqCst, AtomicOrdering::SeqCst, |_| {
+                Some(pinned)
+            });
+    }
+
     pub fn last_sync(&self) -> Timestamp {
         Timestamp::from(self.last_sync.load(AtomicOrdering::SeqCst))
     }
 
+    /// Restore last sync timestamp (i.e. from a checkpoint)
+    pub fn set_last_sync(&self, timestamp: Timestamp) {
+        let _ = self
+            .last_sync
+            .fetch_update(AtomicOrdering::SeqCst, AtomicOrdering::SeqCst, |_| {
+                Some(timestamp.as_u64())
+            });
+    }
+
     fn update_last_sync(&self) {
         let _ = self
             .last_sync
@@ -179,6 +262,30 @@ impl SmartVaultsWallet {
             });
     }
 
+    pub fn last_full_scan(&self) -> Timestamp {
+        Timestamp::from(self.last_full_scan.load(AtomicOrdering::SeqCst))
+    }
+
+    /// Restore last full scan timestamp (i.e. from a checkpoint)
+    pub fn set_last_full_scan(&self, timestamp: Timestamp) {
+        let _ = self.last_full_scan.fetch_update(
+            AtomicOrdering::SeqCst,
+            AtomicOrdering::SeqCst,
+            |_| Some(timestamp.as_u64()),
+        );
+    }
+
+    pub fn descriptor_hash(&self) -> Sha256Hash {
+        Sha256Hash::hash(self.policy.as_descriptor().to_string().as_bytes())
+    }
+
+    /// Persist staged changes
+    pub async fn commit(&self) -> Result<(), Error> {
+        let mut wallet = self.wallet.write().await;
+        wallet.commit()?;
+        Ok(())
+    }
+
     pub async fn latest_checkpoint(&self) -> CheckPoint {
         self.wallet.read().await.latest_checkpoint()
     }
@@ -203,6 +310,22 @@ impl SmartVaultsWallet {
         self.wallet.read().await.all_unbounded_spk_iters()
     }
 
+    /// Get the spks of the revealed addresses, plus a stop gap (to detect the addresses revealed by the other members)
+    pub async fn revealed_spks(&self) -> HashMap<ScriptBuf, (KeychainKind, u32)> {
+        let spk_index: KeychainTxOutIndex<KeychainKind> = self.spk_index().await;
+        let mut spks: HashMap<ScriptBuf, (KeychainKind, u32)> = HashMap::new();
+        for (keychain, iter) in self.spks().await.into_iter() {
+            let limit: u32 = spk_index
+                .last_revealed_index(&keychain)
+                .map_or(0, |index| index + 1)
+                .saturating_add(STOP_GAP as u32);
+            for (index, spk) in iter.take_while(|(index, _)| *index < limit) {
+                spks.insert(spk, (keychain, index));
+            }
+        }
+        spks
+    }
+
     pub async fn spk_index(&self) -> KeychainTxOutIndex<KeychainKind> {
         let wallet = self.wallet.read().await;
         wallet.spk_index().clone()
@@ -330,15 +453,10 @@ impl SmartVaultsWallet {
         wallet.list_unspent().collect()
     }
 
-    async fn internal_full_sync(
-        &self,
+    fn electrum_client(
         endpoint: ElectrumEndpoint,
         proxy: Option<SocketAddr>,
-        prev_tip: CheckPoint,
-        graph: TxGraph<ConfirmationTimeHeightAnchor>,
-    ) -> Result<(), Error> {
-        let keychain_spks = self.spks().await;
-
+    ) -> Result<ElectrumClient, Error> {
         tracing::info!("Initializing electrum client: endpoint={endpoint}, proxy={proxy:?}");
         let proxy: Option<Socks5Config> = proxy.map(Socks5Config::new);
         let config: ElectrumConfig = ElectrumConfig::builder()
@@ -347,8 +465,21 @@ impl SmartVaultsWallet {
             .retry(3)
             .socks5(proxy)
             .build();
-        let client: ElectrumClient =
-            ElectrumClient::from_config(&endpoint.as_non_standard_format(), config)?;
+        Ok(ElectrumClient::from_config(
+            &endpoint.as_non_standard_format(),
+            config,
+        )?)
+    }
+
+    async fn internal_full_sync(
+        &self,
+        endpoint: ElectrumEndpoint,
+        proxy: Option<SocketAddr>,
+        prev_tip: CheckPoint,
+        graph: TxGraph<ConfirmationTimeHeightAnchor>,
+    ) -> Result<(), Error> {
+        let keychain_spks = self.spks().await;
+        let client: ElectrumClient = Self::electrum_client(endpoint, proxy)?;
 
         let (
             ElectrumUpdate {
@@ -381,7 +512,12 @@ impl SmartVaultsWallet {
     ) -> Result<(), Error> {
         if !force {
             let last_sync: Timestamp = self.last_sync();
-            if last_sync + WALLET_SYNC_INTERVAL > Timestamp::now() {
+            let interval = if self.is_pinned() {
+                PINNED_WALLET_SYNC_INTERVAL
+            } else {
+                WALLET_SYNC_INTERVAL
+            };
+            if last_sync + interval > Timestamp::now() {
                 return Err(Error::AlreadySynced);
             }
 
@@ -411,12 +547,15 @@ impl SmartVaultsWallet {
         };
 
         // Sync
-        self.internal_full_sync(endpoint, proxy, prev_tip, graph)
-            .await?;
+        let res = self
+            .internal_full_sync(endpoint, proxy, prev_tip, graph)
+            .await;
+        self.set_syncing(false);
+        res?;
 
-        // Update sync timestamp and status
+        // Update sync timestamps
         self.update_last_sync();
-        self.set_syncing(false);
+        self.set_last_full_scan(Timestamp::now());
 
         if force {
             tracing::info!("Policy {} synced [full-force]", self.id);
@@ -427,37 +566,19 @@ impl SmartVaultsWallet {
         Ok(())
     }
 
-    /* pub async fn sync(
+    async fn internal_sync(
         &self,
         endpoint: ElectrumEndpoint,
         proxy: Option<SocketAddr>,
     ) -> Result<(), Error> {
-        let last_sync: Timestamp = self.last_sync();
-        if last_sync + WALLET_SYNC_INTERVAL > Timestamp::now() {
-            return Err(Error::AlreadySynced);
-        }
-
-        if self.is_syncing() {
-            return Err(Error::AlreadySyncing);
-        }
-
-        if self.is_chain_empty().await {
-            tracing::warn!("Local chain is empty: executing a full sync");
-            return self.full_sync(endpoint, proxy, false).await;
-        }
-
-        self.set_syncing(true);
-
-        tracing::debug!("Syncing policy {}", self.id);
-
         let prev_tip: CheckPoint = self.latest_checkpoint().await;
-        let chain = self.chain().await;
+        let chain: LocalChain = self.chain().await;
         let graph: TxGraph<ConfirmationTimeHeightAnchor> = self.graph().await;
-        let spk_index = self.spk_index().await;
+        let spk_index: KeychainTxOutIndex<KeychainKind> = self.spk_index().await;
         let chain_tip: BlockId = chain.tip().block_id();
-        let all_spks = spk_index
-            .revealed_keychain_spks(&KeychainKind::External)
-            .map(|(_, s)| s.to_owned());
+
+        let spks: HashMap<ScriptBuf, (KeychainKind, u32)> = self.revealed_spks().await;
+
         let unconfirmed_txids = graph
             .list_chain_txs(&chain, chain_tip)
             .filter(|canonical_tx| !canonical_tx.chain_position.is_confirmed())
@@ -467,39 +588,86 @@ impl SmartVaultsWallet {
             .filter_chain_unspents(&chain, chain_tip, init_outpoints)
             .map(|(_, utxo)| utxo.outpoint);
 
-        tracing::info!("Initializing electrum client: endpoint={endpoint}, proxy={proxy:?}");
-        let proxy: Option<Socks5Config> = proxy.map(Socks5Config::new);
-        let config: ElectrumConfig = ElectrumConfig::builder()
-            .validate_domain(endpoint.validate_tls())
-            .timeout(Some(120))
-            .retry(3)
-            .socks5(proxy)
-            .build();
-        let client: ElectrumClient =
-            ElectrumClient::from_config(&endpoint.as_non_standard_format(), config)?;
-
+        let client: ElectrumClient = Self::electrum_client(endpoint, proxy)?;
         let ElectrumUpdate {
             chain_update,
             relevant_txids,
-        } = client.sync(prev_tip, all_spks, unconfirmed_txids, outpoints, BATCH_SIZE)?;
+        } = client.sync(
+            prev_tip,
+            spks.keys().cloned(),
+            unconfirmed_txids,
+            outpoints,
+            BATCH_SIZE,
+        )?;
         let missing: Vec<Txid> = relevant_txids.missing_full_txs(&graph);
         let graph_update =
             relevant_txids.into_confirmation_time_tx_graph(&client, None, missing)?;
 
+        // Reveal the spks used by the new transactions
+        let mut last_active_indices: BTreeMap<KeychainKind, u32> = BTreeMap::new();
+        for tx in graph_update.full_txs() {
+            for txout in tx.tx.output.iter() {
+                if let Some((keychain, index)) = spks.get(&txout.script_pubkey) {
+                    let last = last_active_indices.entry(*keychain).or_insert(*index);
+                    *last = (*last).max(*index);
+                }
+            }
+        }
+
         let update = Update {
-            last_active_indices: BTreeMap::new(),
+            last_active_indices,
             graph: graph_update,
             chain: Some(chain_update),
         };
 
-        self.apply_update(update).await?;
-        self.update_last_sync();
+        self.apply_update(update).await
+    }
+
+    /// Execute an incremental timechain sync
+    ///
+    /// Only the revealed addresses (plus a stop gap), the unconfirmed transactions and the UTXOs are checked.
+    /// Fallback to a full sync if the local chain is empty or the last full scan is older than
+    /// [`WALLET_FULL_SCAN_INTERVAL`].
+    pub async fn sync(
+        &self,
+        endpoint: ElectrumEndpoint,
+        proxy: Option<SocketAddr>,
+        force: bool,
+    ) -> Result<(), Error> {
+        let last_sync: Timestamp = self.last_sync();
+        let interval = if self.is_pinned() {
+            PINNED_WALLET_SYNC_INTERVAL
+        } else {
+            WALLET_SYNC_INTERVAL
+        };
+        if !force && last_sync + interval > Timestamp::now() {
+            return Err(Error::AlreadySynced);
+        }
+
+        if self.is_syncing() {
+            return Err(Error::AlreadySyncing);
+        }
+
+        if self.is_chain_empty().await
+            || self.last_full_scan() + WALLET_FULL_SCAN_INTERVAL <= Timestamp::now()
+        {
+            return self.full_sync(endpoint, proxy, force).await;
+        }
+
+        self.set_syncing(true);
+
+        tracing::debug!("Syncing policy {}", self.id);
+
+        let res = self.internal_sync(endpoint, proxy).await;
         self.set_syncing(false);
+        res?;
+
+        self.update_last_sync();
 
         tracing::info!("Policy {} synced", self.id);
 
         Ok(())
-    } */
+    }
 
     #[tracing::instrument(skip_all, level = "trace")]
     pub async fn apply_update(&self, update: Update) -> Result<(), Error> {
@@ -537,6 +705,7 @@ impl SmartVaultsWallet {
         utxos: Option<Vec<OutPoint>>,
         frozen_utxos: Option<Vec<OutPoint>>,
         policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        tx_options: TxOptions,
     ) -> Result<Proposal, Error>
     where
         S: Into<String>,
@@ -551,6 +720,102 @@ impl SmartVaultsWallet {
             utxos,
             frozen_utxos,
             policy_path,
+            tx_options,
+        )?;
+        Ok(proposal)
+    }
+
+    /// Build a spending transaction without creating the proposal
+    pub async fn preview_spend(
+        &self,
+        address: Address<NetworkUnchecked>,
+        amount: Amount,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        frozen_utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+    ) -> Result<SpendPreview, Error> {
+        let mut wallet = self.wallet.write().await;
+        let destinations: HashSet<ScriptBuf> = HashSet::from([address.payload.script_pubkey()]);
+        let proposal: Proposal = self.policy.spend(
+            &mut wallet,
+            address,
+            amount,
+            "",
+            fee_rate,
+            utxos,
+            frozen_utxos,
+            policy_path.clone(),
+            TxOptions::default(),
+        )?;
+        Ok(build_preview(
+            &wallet,
+            &proposal,
+            &destinations,
+            policy_path,
+        ))
+    }
+
+    /// Build a batch spending transaction without creating the proposal
+    pub async fn preview_spend_many(
+        &self,
+        recipients: Vec<Recipient>,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        frozen_utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        data: Option<Vec<u8>>,
+        tx_options: TxOptions,
+    ) -> Result<SpendPreview, Error> {
+        let mut wallet = self.wallet.write().await;
+        let destinations: HashSet<ScriptBuf> = recipients
+            .iter()
+            .map(|r| r.address.payload.script_pubkey())
+            .collect();
+        let proposal: Proposal = self.policy.spend_many(
+            &mut wallet,
+            recipients,
+            "",
+            fee_rate,
+            utxos,
+            frozen_utxos,
+            policy_path.clone(),
+            data,
+            tx_options,
+        )?;
+        Ok(build_preview(
+            &wallet,
+            &proposal,
+            &destinations,
+            policy_path,
+        ))
+    }
+
+    pub async fn spend_many<S>(
+        &self,
+        recipients: Vec<Recipient>,
+        description: S,
+        fee_rate: FeeRate,
+        utxos: Option<Vec<OutPoint>>,
+        frozen_utxos: Option<Vec<OutPoint>>,
+        policy_path: Option<BTreeMap<String, Vec<usize>>>,
+        data: Option<Vec<u8>>,
+        tx_options: TxOptions,
+    ) -> Result<Proposal, Error>
+    where
+        S: Into<String>,
+    {
+        let mut wallet = self.wallet.write().await;
+        let proposal = self.policy.spend_many(
+            &mut wallet,
+            recipients,
+            description,
+            fee_rate,
+            utxos,
+            frozen_utxos,
+            policy_path,
+            data,
+            tx_options,
         )?;
         Ok(proposal)
     }
@@ -575,3 +840,62 @@ impl SmartVaultsWallet {
         Ok(self.wallet.read().await.verify_proof(psbt, message, None)?)
     }
 }
+
+/// Build the [`SpendPreview`] of a spending proposal
+///
+/// The outputs not paying the `destinations` and owned by the wallet are the change.
+fn build_preview(
+    wallet: &Wallet<SmartVaultsWalletStorage>,
+    proposal: &Proposal,
+    destinations: &HashSet<ScriptBuf>,
+    policy_path: Option<BTreeMap<String, Vec<usize>>>,
+) -> SpendPreview {
+    let tx: Transaction = proposal.psbt().unsigned_tx;
+
+    let inputs: Vec<(OutPoint, u64)> = tx
+        .input
+        .iter()
+        .map(|txin| {
+            let amount: u64 = wallet
+                .get_utxo(txin.previous_output)
+                .map(|utxo| utxo.txout.value)
+                .unwrap_or_default();
+            (txin.previous_output, amount)
+        })
+        .collect();
+    let outputs: Vec<SpendPreviewOutput> = tx
+        .output
+        .iter()
+        .map(|txout| SpendPreviewOutput {
+            script_pubkey: txout.script_pubkey.clone(),
+            amount: txout.value,
+            is_change: !destinations.contains(&txout.script_pubkey)
+                && wallet.is_mine(&txout.script_pubkey),
+        })
+        .collect();
+
+    let total_in: u64 = inputs.iter().map(|(_, amount)| amount).sum();
+    let total_out: u64 = outputs.iter().map(|o| o.amount).sum();
+    let change: u64 = outputs
+        .iter()
+        .filter(|o| o.is_change)
+        .map(|o| o.amount)
+        .sum();
+
+    // Witness of each input (plus segwit marker and flag)
+    let satisfaction_weight: usize = wallet
+        .public_descriptor(KeychainKind::External)
+        .and_then(|descriptor| descriptor.max_weight_to_satisfy().ok())
+        .unwrap_or_default();
+    let weight: usize = tx.weight().to_wu() as usize + 2 + tx.input.len() * satisfaction_weight;
+
+    SpendPreview {
+        inputs,
+        outputs,
+        fee: total_in.saturating_sub(total_out),
+        change,
+        vsize: tx.vsize(),
+        estimated_vsize: (weight + 3) / 4,
+        policy_path,
+    }
+}
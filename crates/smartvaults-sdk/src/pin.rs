@@ -0,0 +1,239 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Device-local approval PIN
+//!
+//! Optional short PIN required to approve proposals on this device, in addition to the keychain password,
+//! so a desktop can stay unlocked for viewing while signing remains gated. A scrypt hash of the PIN
+//! is saved in the OS keyring (requires the `keyring` feature): it's never synced or included in the bundles.
+//!
+//! After [`MAX_PIN_ATTEMPTS`] wrong attempts, approvals are locked until the PIN is reset with the keychain password.
+
+use nostr_sdk::PublicKey;
+use serde::{Deserialize, Serialize};
+use smartvaults_core::bitcoin::Network;
+#[cfg(feature = "keyring")]
+use smartvaults_core::secp256k1::rand::{self, RngCore};
+use thiserror::Error;
+
+#[cfg(feature = "keyring")]
+const SERVICE: &str = "smartvaults-approval-pin";
+pub const MIN_PIN_LEN: usize = 4;
+pub const MAX_PIN_LEN: usize = 12;
+/// Wrong attempts before locking the approvals
+pub const MAX_PIN_ATTEMPTS: u8 = 5;
+#[cfg(feature = "keyring")]
+const SALT_LEN: usize = 16;
+/// Scrypt cost (2^14 iterations)
+const SCRYPT_LOG_N: u8 = 14;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[cfg(feature = "keyring")]
+    #[error(transparent)]
+    Keyring(#[from] keyring::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("key derivation: {0}")]
+    Kdf(String),
+    #[error("OS keyring not supported")]
+    NotSupported,
+    #[error("the PIN must be {MIN_PIN_LEN} to {MAX_PIN_LEN} digits")]
+    InvalidFormat,
+    #[error("approval PIN required")]
+    Required,
+    #[error("wrong approval PIN ({0} attempts left)")]
+    Wrong(u8),
+    #[error("too many wrong attempts: reset the approval PIN with the keychain password")]
+    Locked,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PinRecord {
+    salt: Vec<u8>,
+    hash: Vec<u8>,
+    #[serde(default)]
+    failed_attempts: u8,
+}
+
+fn hash_pin(pin: &str, salt: &[u8]) -> Result<Vec<u8>, Error> {
+    let params =
+        scrypt::Params::new(SCRYPT_LOG_N, 8, 1, 32).map_err(|e| Error::Kdf(e.to_string()))?;
+    let mut hash = vec![0u8; 32];
+    scrypt::scrypt(pin.as_bytes(), salt, &params, &mut hash)
+        .map_err(|e| Error::Kdf(e.to_string()))?;
+    Ok(hash)
+}
+
+/// Compare two hashes in constant time (no early exit at the first different byte)
+///
+/// The length is not secret (the hashes are always 32 bytes).
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+/// Check the PIN format (only digits)
+pub fn is_valid_pin(pin: &str) -> bool {
+    (MIN_PIN_LEN..=MAX_PIN_LEN).contains(&pin.len()) && pin.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Approval PIN of a keychain on this device
+#[derive(Debug, Clone)]
+pub struct ApprovalPin {
+    /// Keyring account
+    id: String,
+}
+
+impl ApprovalPin {
+    pub fn new(network: Network, public_key: PublicKey) -> Self {
+        Self {
+            id: format!("{network}-{public_key}"),
+        }
+    }
+
+    #[cfg(feature = "keyring")]
+    fn entry(&self) -> Result<keyring::Entry, Error> {
+        Ok(keyring::Entry::new(SERVICE, &self.id)?)
+    }
+
+    #[cfg(feature = "keyring")]
+    fn load(&self) -> Result<Option<PinRecord>, Error> {
+        match self.entry()?.get_password() {
+            Ok(record) => Ok(Some(serde_json::from_str(&record)?)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    fn load(&self) -> Result<Option<PinRecord>, Error> {
+        Ok(None)
+    }
+
+    #[cfg(feature = "keyring")]
+    fn save(&self, record: &PinRecord) -> Result<(), Error> {
+        Ok(self
+            .entry()?
+            .set_password(&serde_json::to_string(record)?)?)
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    fn save(&self, _record: &PinRecord) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Check if the PIN is set
+    ///
+    /// Return `false` also if the keyring is not available.
+    pub fn is_set(&self) -> bool {
+        matches!(self.load(), Ok(Some(..)))
+    }
+
+    /// Set (or replace) the PIN, resetting the wrong attempts
+    #[cfg(feature = "keyring")]
+    pub fn set(&self, pin: &str) -> Result<(), Error> {
+        if !is_valid_pin(pin) {
+            return Err(Error::InvalidFormat);
+        }
+
+        let mut salt = vec![0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let hash: Vec<u8> = hash_pin(pin, &salt)?;
+        self.save(&PinRecord {
+            salt,
+            hash,
+            failed_attempts: 0,
+        })
+    }
+
+    /// Set (or replace) the PIN, resetting the wrong attempts
+    #[cfg(not(feature = "keyring"))]
+    pub fn set(&self, _pin: &str) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// Remove the PIN
+    #[cfg(feature = "keyring")]
+    pub fn remove(&self) -> Result<(), Error> {
+        match self.entry()?.delete_password() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Remove the PIN
+    #[cfg(not(feature = "keyring"))]
+    pub fn remove(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// Verify the PIN (always succeed if not set)
+    pub fn verify(&self, pin: Option<&str>) -> Result<(), Error> {
+        let mut record: PinRecord = match self.load()? {
+            Some(record) => record,
+            None => return Ok(()),
+        };
+
+        if record.failed_attempts >= MAX_PIN_ATTEMPTS {
+            return Err(Error::Locked);
+        }
+
+        let pin: &str = pin.ok_or(Error::Required)?;
+        if constant_time_eq(&hash_pin(pin, &record.salt)?, &record.hash) {
+            if record.failed_attempts > 0 {
+                record.failed_attempts = 0;
+                self.save(&record)?;
+            }
+            Ok(())
+        } else {
+            record.failed_attempts += 1;
+            self.save(&record)?;
+            match MAX_PIN_ATTEMPTS - record.failed_attempts {
+                0 => Err(Error::Locked),
+                left => Err(Error::Wrong(left)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_format() {
+        assert!(is_valid_pin("1234"));
+        assert!(is_valid_pin("123456789012"));
+        assert!(!is_valid_pin("123"));
+        assert!(!is_valid_pin("1234567890123"));
+        assert!(!is_valid_pin("12a4"));
+    }
+
+    #[test]
+    fn test_hash_pin() {
+        let salt = [1u8; 16];
+        assert_eq!(
+            hash_pin("1234", &salt).unwrap(),
+            hash_pin("1234", &salt).unwrap()
+        );
+        assert_ne!(
+            hash_pin("1234", &salt).unwrap(),
+            hash_pin("1235", &salt).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+        assert!(!constant_time_eq(&[0, 2, 3], &[1, 2, 3]));
+        assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
+        assert!(constant_time_eq(&[], &[]));
+    }
+}
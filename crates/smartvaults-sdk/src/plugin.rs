@@ -0,0 +1,98 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Plugins
+//!
+//! Let embedders extend the protocol with custom event kinds, exchanged between the members of a vault
+//! (signed by the member and encrypted with the shared key of the vault).
+//!
+//! The decrypted content of the received events is saved in the plugin storage (see
+//! [`SmartVaults::get_plugin_data`](crate::SmartVaults::get_plugin_data)) and dispatched to the registered plugin.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use nostr_sdk::{EventId, Kind, PublicKey, Timestamp};
+use serde_json::Value;
+use tokio::sync::RwLock;
+
+/// Custom event of a vault, decrypted with the shared key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultEvent {
+    pub vault_id: EventId,
+    pub event_id: EventId,
+    pub author: PublicKey,
+    pub kind: Kind,
+    /// Identifier (`d` tag) of the parameterized replaceable events
+    pub identifier: Option<String>,
+    pub content: Value,
+    pub timestamp: Timestamp,
+}
+
+impl VaultEvent {
+    /// Key used in the plugin storage: the identifier, if any, otherwise the event ID
+    pub fn key(&self) -> String {
+        match &self.identifier {
+            Some(identifier) => format!("{}:{identifier}", self.kind.as_u64()),
+            None => self.event_id.to_hex(),
+        }
+    }
+}
+
+/// Handler of custom event kinds
+pub trait VaultPlugin: Debug + Send + Sync {
+    /// Unique name of the plugin (used as namespace of the plugin storage)
+    fn name(&self) -> String;
+
+    /// Event kinds handled by the plugin
+    fn kinds(&self) -> Vec<Kind>;
+
+    /// Handle an event (already saved in the plugin storage)
+    ///
+    /// Called for the events received from relays and, at registration, for the ones already in the database.
+    fn handle(&self, event: &VaultEvent) -> Result<(), String>;
+}
+
+/// Registered plugins, by kind
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Plugins {
+    kinds: Arc<RwLock<HashMap<Kind, Arc<dyn VaultPlugin>>>>,
+}
+
+impl Plugins {
+    pub async fn get(&self, kind: &Kind) -> Option<Arc<dyn VaultPlugin>> {
+        let kinds = self.kinds.read().await;
+        kinds.get(kind).cloned()
+    }
+
+    pub async fn kinds(&self) -> Vec<Kind> {
+        let kinds = self.kinds.read().await;
+        kinds.keys().copied().collect()
+    }
+
+    pub async fn is_registered(&self, name: &str) -> bool {
+        let kinds = self.kinds.read().await;
+        kinds.values().any(|p| p.name() == name)
+    }
+
+    /// Register the plugin for its kinds
+    ///
+    /// Return the kind already registered by another plugin, if any.
+    pub async fn register(&self, plugin: Arc<dyn VaultPlugin>) -> Result<(), Kind> {
+        let mut kinds = self.kinds.write().await;
+        let plugin_kinds: Vec<Kind> = plugin.kinds();
+        if let Some(kind) = plugin_kinds.iter().find(|k| kinds.contains_key(k)) {
+            return Err(*kind);
+        }
+        for kind in plugin_kinds.into_iter() {
+            kinds.insert(kind, plugin.clone());
+        }
+        Ok(())
+    }
+
+    pub async fn unregister(&self, name: &str) {
+        let mut kinds = self.kinds.write().await;
+        kinds.retain(|_, p| p.name() != name);
+    }
+}
@@ -11,23 +11,27 @@ use nostr_sdk::prelude::*;
 use smartvaults_core::bitcoin::{Network, OutPoint, ScriptBuf, Txid};
 use smartvaults_core::miniscript::{Descriptor, DescriptorPublicKey};
 use smartvaults_core::{
-    ApprovedProposal, CompletedProposal, Policy, Proposal, SharedSigner, Signer,
+    ApprovedProposal, CompletedProposal, Policy, Proposal, ProposalDiff, SharedSigner, Signer,
 };
 use smartvaults_protocol::v1::constants::{
-    APPROVED_PROPOSAL_KIND, COMPLETED_PROPOSAL_KIND, KEY_AGENT_VERIFIED, LABELS_KIND, POLICY_KIND,
-    PROPOSAL_KIND, SHARED_KEY_KIND, SHARED_SIGNERS_KIND, SIGNERS_KIND,
-    SMARTVAULTS_MAINNET_PUBLIC_KEY, SMARTVAULTS_TESTNET_PUBLIC_KEY,
+    APPROVED_PROPOSAL_KIND, CHUNK_KIND, COMPLETED_PROPOSAL_KIND, KEY_AGENT_VERIFIED, LABELS_KIND,
+    POLICY_KIND, PROPOSAL_COMMENT_KIND, PROPOSAL_KIND, SHARED_KEY_KIND, SHARED_SIGNERS_KIND,
+    SIGNERS_KIND, SMARTVAULTS_MAINNET_PUBLIC_KEY, SMARTVAULTS_TESTNET_PUBLIC_KEY,
+    VAULT_METADATA_KIND,
+};
+use smartvaults_protocol::v1::{
+    approval, chunk, expiration, replacement, velocity, Encryption, Label, LabelData, LabelKind,
+    ProposalComment, Serde, VaultMetadata, VerifiedKeyAgents,
 };
-use smartvaults_protocol::v1::{Encryption, Label, LabelData, LabelKind, Serde, VerifiedKeyAgents};
 use tokio::sync::RwLock;
 
 mod model;
 
 pub(crate) use self::model::{
-    InternalApproval, InternalCompletedProposal, InternalLabel, InternalPolicy, InternalProposal,
-    InternalSharedSigner,
+    CompletedProposalIndex, InternalApproval, InternalComment, InternalCompletedProposal,
+    InternalLabel, InternalPolicy, InternalProposal, InternalSharedSigner,
 };
-use crate::types::GetApprovedProposals;
+use crate::types::{ConsistencyReport, GetApprovedProposals, ProposalIssue};
 use crate::{Error, EventHandled};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -55,16 +59,19 @@ impl Ord for WrappedEvent {
 #[derive(Debug, Clone)]
 pub(crate) struct SmartVaultsStorage {
     keys: Keys,
+    network: Network,
     database: Arc<DynNostrDatabase>,
     shared_keys: Arc<RwLock<HashMap<EventId, Keys>>>,
     vaults: Arc<RwLock<HashMap<EventId, InternalPolicy>>>,
     proposals: Arc<RwLock<HashMap<EventId, InternalProposal>>>,
     approvals: Arc<RwLock<HashMap<EventId, InternalApproval>>>,
-    completed_proposals: Arc<RwLock<HashMap<EventId, InternalCompletedProposal>>>,
+    comments: Arc<RwLock<HashMap<EventId, InternalComment>>>,
+    completed_proposals: Arc<RwLock<HashMap<EventId, CompletedProposalIndex>>>,
     signers: Arc<RwLock<HashMap<EventId, Signer>>>,
     my_shared_signers: Arc<RwLock<HashMap<EventId, (EventId, PublicKey)>>>, /* Signer ID, Shared Signer ID, pubkey */
     shared_signers: Arc<RwLock<HashMap<EventId, InternalSharedSigner>>>,
     labels: Arc<RwLock<HashMap<String, InternalLabel>>>,
+    vault_metadata: Arc<RwLock<HashMap<EventId, (VaultMetadata, Timestamp)>>>,
     frozed_utxos: Arc<RwLock<HashMap<EventId, HashSet<OutPoint>>>>,
     verified_key_agents: Arc<RwLock<VerifiedKeyAgents>>,
     pending: Arc<RwLock<BTreeSet<Event>>>,
@@ -80,70 +87,282 @@ impl SmartVaultsStorage {
     ) -> Result<Self, Error> {
         let this: Self = Self {
             keys,
+            network,
             database,
             shared_keys: Arc::new(RwLock::new(HashMap::new())),
             vaults: Arc::new(RwLock::new(HashMap::new())),
             proposals: Arc::new(RwLock::new(HashMap::new())),
             approvals: Arc::new(RwLock::new(HashMap::new())),
+            comments: Arc::new(RwLock::new(HashMap::new())),
             completed_proposals: Arc::new(RwLock::new(HashMap::new())),
             signers: Arc::new(RwLock::new(HashMap::new())),
             my_shared_signers: Arc::new(RwLock::new(HashMap::new())),
             shared_signers: Arc::new(RwLock::new(HashMap::new())),
             labels: Arc::new(RwLock::new(HashMap::new())),
+            vault_metadata: Arc::new(RwLock::new(HashMap::new())),
             frozed_utxos: Arc::new(RwLock::new(HashMap::new())),
             verified_key_agents: Arc::new(RwLock::new(VerifiedKeyAgents::empty(network))),
             pending: Arc::new(RwLock::new(BTreeSet::new())),
         };
 
-        let author_filter: Filter = Filter::new().author(this.keys.public_key()).kinds([
+        let mut pending = this.pending.write().await;
+        for event in this
+            .database
+            .query(this.filters(), Order::Asc)
+            .await?
+            .into_iter()
+        {
+            if let Err(e) = this.internal_handle_event(&mut pending, &event).await {
+                tracing::error!("Impossible to handle event: {e}");
+            }
+        }
+
+        // Clone to avoid lock in handle event
+        for event in pending.clone().into_iter() {
+            if let Err(e) = this.internal_handle_event(&mut pending, &event).await {
+                tracing::error!("Impossible to handle event: {e}");
+            }
+        }
+
+        drop(pending);
+
+        Ok(this)
+    }
+
+    /// Filters of the events handled by the storage
+    fn filters(&self) -> Vec<Filter> {
+        let author_filter: Filter = Filter::new().author(self.keys.public_key()).kinds([
             SHARED_KEY_KIND,
             POLICY_KIND,
             PROPOSAL_KIND,
             APPROVED_PROPOSAL_KIND,
+            PROPOSAL_COMMENT_KIND,
             COMPLETED_PROPOSAL_KIND,
             SIGNERS_KIND,
             SHARED_SIGNERS_KIND,
             LABELS_KIND,
+            VAULT_METADATA_KIND,
         ]);
-        let pubkey_filter: Filter = Filter::new().pubkey(this.keys.public_key()).kinds([
+        let pubkey_filter: Filter = Filter::new().pubkey(self.keys.public_key()).kinds([
             SHARED_KEY_KIND,
             POLICY_KIND,
             PROPOSAL_KIND,
             APPROVED_PROPOSAL_KIND,
+            PROPOSAL_COMMENT_KIND,
             COMPLETED_PROPOSAL_KIND,
             SIGNERS_KIND,
             SHARED_SIGNERS_KIND,
             LABELS_KIND,
+            VAULT_METADATA_KIND,
         ]);
         let smartvaults: Filter = Filter::new()
-            .author(match network {
+            .author(match self.network {
                 Network::Bitcoin => *SMARTVAULTS_MAINNET_PUBLIC_KEY,
                 _ => *SMARTVAULTS_TESTNET_PUBLIC_KEY,
             })
             .kind(KEY_AGENT_VERIFIED);
+        vec![author_filter, pubkey_filter, smartvaults]
+    }
 
-        let mut pending = this.pending.write().await;
-        for event in this
-            .database
-            .query(vec![author_filter, pubkey_filter, smartvaults], Order::Asc)
-            .await?
-            .into_iter()
-        {
-            if let Err(e) = this.internal_handle_event(&mut pending, &event).await {
-                tracing::error!("Impossible to handle event: {e}");
+    /// Check if the event was processed (or is waiting for the shared key)
+    async fn is_processed(&self, event: &Event, completed: &HashSet<EventId>) -> bool {
+        if self.pending.read().await.contains(event) {
+            return true;
+        }
+
+        if event.kind == SHARED_KEY_KIND {
+            match event.event_ids().next() {
+                Some(policy_id) => self.shared_keys.read().await.contains_key(policy_id),
+                None => true,
+            }
+        } else if event.kind == POLICY_KIND {
+            self.vaults.read().await.contains_key(&event.id)
+        } else if event.kind == PROPOSAL_KIND {
+            // Proposals are removed when completed
+            completed.contains(&event.id) || self.proposals.read().await.contains_key(&event.id)
+        } else if event.kind == APPROVED_PROPOSAL_KIND {
+            self.approvals.read().await.contains_key(&event.id)
+        } else if event.kind == PROPOSAL_COMMENT_KIND {
+            self.comments.read().await.contains_key(&event.id)
+        } else if event.kind == COMPLETED_PROPOSAL_KIND {
+            self.completed_proposals
+                .read()
+                .await
+                .contains_key(&event.id)
+        } else if event.kind == SIGNERS_KIND {
+            self.signers.read().await.contains_key(&event.id)
+        } else if event.kind == SHARED_SIGNERS_KIND {
+            if event.author() == self.keys.public_key() {
+                self.my_shared_signers
+                    .read()
+                    .await
+                    .values()
+                    .any(|(id, ..)| id == &event.id)
+            } else {
+                self.shared_signers.read().await.contains_key(&event.id)
+            }
+        } else if event.kind == LABELS_KIND {
+            match event.identifier() {
+                Some(identifier) => self
+                    .labels
+                    .read()
+                    .await
+                    .get(identifier)
+                    .map(|l| l.timestamp >= event.created_at)
+                    .unwrap_or_default(),
+                None => true,
+            }
+        } else if event.kind == VAULT_METADATA_KIND {
+            match event.event_ids().next() {
+                Some(vault_id) => self
+                    .vault_metadata
+                    .read()
+                    .await
+                    .get(vault_id)
+                    .map(|(_, timestamp)| *timestamp >= event.created_at)
+                    .unwrap_or_default(),
+                None => true,
             }
+        } else {
+            true
         }
+    }
 
-        // Clone to avoid lock in handle event
-        for event in pending.clone().into_iter() {
-            if let Err(e) = this.internal_handle_event(&mut pending, &event).await {
-                tracing::error!("Impossible to handle event: {e}");
+    /// Reconcile the in-memory storage with the nostr database
+    ///
+    /// Not processed events are replayed, while items without a backing event are removed.
+    pub async fn check_consistency(&self) -> Result<ConsistencyReport, Error> {
+        let events: Vec<Event> = self.database.query(self.filters(), Order::Asc).await?;
+        let ids: HashSet<EventId> = events.iter().map(|e| e.id).collect();
+        let completed: HashSet<EventId> = events
+            .iter()
+            .filter(|e| e.kind == COMPLETED_PROPOSAL_KIND)
+            .filter_map(|e| e.event_ids().next().copied())
+            .collect();
+
+        let mut report = ConsistencyReport {
+            checked: events.len(),
+            ..Default::default()
+        };
+
+        // Replay not processed events
+        let mut missing: Vec<Event> = Vec::new();
+        for event in events.into_iter() {
+            if !self.is_processed(&event, &completed).await {
+                missing.push(event);
             }
         }
 
+        let mut pending = self.pending.write().await;
+        for event in missing.into_iter() {
+            match self.internal_handle_event(&mut pending, &event).await {
+                Ok(Some(..)) => report.replayed.push(event.id),
+                Ok(None) => {
+                    if !pending.contains(&event) {
+                        report.failed.push(event.id);
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Impossible to replay event {}: {e}", event.id);
+                    report.failed.push(event.id);
+                }
+            }
+        }
+        report.pending = pending.len();
         drop(pending);
 
-        Ok(this)
+        // Remove items without a backing event
+        let mut stored: Vec<EventId> = Vec::new();
+        stored.extend(self.vaults.read().await.keys().copied());
+        stored.extend(self.proposals.read().await.keys().copied());
+        stored.extend(self.approvals.read().await.keys().copied());
+        stored.extend(self.comments.read().await.keys().copied());
+        stored.extend(self.completed_proposals.read().await.keys().copied());
+        stored.extend(self.signers.read().await.keys().copied());
+        stored.extend(self.shared_signers.read().await.keys().copied());
+        for event_id in stored.into_iter().filter(|id| !ids.contains(id)) {
+            self.delete_event(&event_id).await;
+            report.orphaned.push(event_id);
+        }
+
+        Ok(report)
+    }
+
+    /// Remove the derived state of a vault (proposals, approvals, comments, completed proposals,
+    /// labels and frozen UTXOs) and re-process its events from the nostr database
+    ///
+    /// Return the number of re-processed events.
+    pub async fn reset_vault(&self, vault_id: &EventId) -> Result<usize, Error> {
+        if !self.vaults.read().await.contains_key(vault_id) {
+            return Err(Error::NotFound);
+        }
+
+        self.proposals
+            .write()
+            .await
+            .retain(|_, p| p.policy_id != *vault_id);
+        self.approvals
+            .write()
+            .await
+            .retain(|_, a| a.policy_id != *vault_id);
+        self.comments
+            .write()
+            .await
+            .retain(|_, c| c.policy_id != *vault_id);
+        self.completed_proposals
+            .write()
+            .await
+            .retain(|_, c| c.policy_id != *vault_id);
+        self.labels
+            .write()
+            .await
+            .retain(|_, l| l.policy_id != *vault_id);
+        self.frozed_utxos.write().await.remove(vault_id);
+
+        let filter: Filter = Filter::new().event(*vault_id).kinds([
+            PROPOSAL_KIND,
+            APPROVED_PROPOSAL_KIND,
+            PROPOSAL_COMMENT_KIND,
+            COMPLETED_PROPOSAL_KIND,
+            LABELS_KIND,
+        ]);
+        let events: Vec<Event> = self.database.query(vec![filter], Order::Asc).await?;
+
+        let mut count: usize = 0;
+        let mut pending = self.pending.write().await;
+        for event in events.into_iter() {
+            match self.internal_handle_event(&mut pending, &event).await {
+                Ok(Some(..)) => count += 1,
+                Ok(None) => (),
+                Err(e) => tracing::error!("Impossible to handle event {}: {e}", event.id),
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Get the content of the event, reassembling it if split across multiple events
+    ///
+    /// Return `None` if some parts are not received yet.
+    async fn event_content(&self, event: &Event) -> Result<Option<String>, Error> {
+        let ids: Vec<EventId> = chunk::chunk_ids(event);
+        if ids.is_empty() {
+            return Ok(Some(event.content.clone()));
+        }
+
+        let filter: Filter = Filter::new().ids(ids.clone()).kind(CHUNK_KIND);
+        let chunks: Vec<Event> = self.database.query(vec![filter], Order::Asc).await?;
+        if chunks.len() < ids.len() {
+            tracing::debug!(
+                "Received {}/{} parts of event {}",
+                chunks.len(),
+                ids.len(),
+                event.id
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(chunk::reassemble(event, &chunks)?))
     }
 
     pub(crate) async fn handle_event(&self, event: &Event) -> Result<Option<EventHandled>, Error> {
@@ -167,20 +386,28 @@ impl SmartVaultsStorage {
                 .copied()
                 .ok_or(Error::PolicyNotFound)?;
             let mut shared_keys = self.shared_keys.write().await;
-            if let HashMapEntry::Vacant(e) = shared_keys.entry(policy_id) {
-                let content =
-                    nip04::decrypt(self.keys.secret_key()?, event.author_ref(), &event.content)?;
-                let sk = SecretKey::from_str(&content)?;
-                let shared_key = Keys::new(sk);
-                e.insert(shared_key);
-                return Ok(Some(EventHandled::SharedKey(event.id)));
+            let content =
+                nip04::decrypt(self.keys.secret_key()?, event.author_ref(), &event.content)?;
+            let sk = SecretKey::from_str(&content)?;
+            match shared_keys.entry(policy_id) {
+                HashMapEntry::Vacant(e) => {
+                    e.insert(Keys::new(sk));
+                    return Ok(Some(EventHandled::SharedKey(event.id)));
+                }
+                HashMapEntry::Occupied(e) => {
+                    // Never replace the shared key of a vault
+                    if e.get().secret_key()? != &sk {
+                        return Err(Error::SharedKeyConflict(policy_id));
+                    }
+                }
             }
         } else if event.kind == POLICY_KIND {
             let shared_keys = self.shared_keys.read().await;
             let mut vaults = self.vaults.write().await;
             if let HashMapEntry::Vacant(e) = vaults.entry(event.id) {
                 if let Some(shared_key) = shared_keys.get(&event.id) {
-                    let policy = Policy::decrypt_with_keys(shared_key, &event.content)?;
+                    let policy = Policy::decrypt_with_keys(shared_key, &event.content)?
+                        .with_network(self.network)?;
                     let mut nostr_pubkeys: Vec<PublicKey> = Vec::new();
                     for tag in event.tags.iter() {
                         if let Tag::PublicKey { public_key, .. } = tag {
@@ -203,12 +430,19 @@ impl SmartVaultsStorage {
         } else if event.kind == PROPOSAL_KIND {
             let shared_keys = self.shared_keys.read().await;
             let mut proposals = self.proposals.write().await;
-            if let HashMapEntry::Vacant(e) = proposals.entry(event.id) {
+            if !proposals.contains_key(&event.id) {
                 if let Some(policy_id) = event.event_ids().next() {
                     if let Some(shared_key) = shared_keys.get(policy_id) {
+                        let content: String = match self.event_content(event).await? {
+                            Some(content) => content,
+                            None => {
+                                pending.insert(event.clone());
+                                return Ok(None);
+                            }
+                        };
+
                         // Decrypt proposal
-                        let proposal: Proposal =
-                            Proposal::decrypt_with_keys(shared_key, &event.content)?;
+                        let proposal: Proposal = Proposal::decrypt_with_keys(shared_key, content)?;
 
                         // Froze UTXOs
                         let psbt = proposal.psbt();
@@ -222,11 +456,17 @@ impl SmartVaultsStorage {
                         .await;
 
                         // Insert proposal
-                        e.insert(InternalProposal {
+                        let mut internal = InternalProposal {
                             policy_id: *policy_id,
                             proposal,
                             timestamp: event.created_at,
-                        });
+                            replaces: replacement::replaced_proposal(event),
+                            diff: None,
+                            expires_at: expiration::expiration(event),
+                            issues: None,
+                        };
+                        internal.diff = replacement_diff(&proposals, &internal);
+                        proposals.insert(event.id, internal);
 
                         return Ok(Some(EventHandled::Proposal(event.id)));
                     } else {
@@ -244,14 +484,23 @@ impl SmartVaultsStorage {
                 if let Some(proposal_id) = ids.next().copied() {
                     if let Some(policy_id) = ids.next() {
                         if let Some(shared_key) = shared_keys.get(policy_id) {
+                            let content: String = match self.event_content(event).await? {
+                                Some(content) => content,
+                                None => {
+                                    pending.insert(event.clone());
+                                    return Ok(None);
+                                }
+                            };
                             let approved_proposal =
-                                ApprovedProposal::decrypt_with_keys(shared_key, &event.content)?;
+                                ApprovedProposal::decrypt_with_keys(shared_key, content)?;
                             e.insert(InternalApproval {
                                 proposal_id,
                                 policy_id: *policy_id,
                                 public_key: event.author(),
+                                txid: approval::extract_txid(event),
                                 approval: approved_proposal,
                                 timestamp: event.created_at,
+                                velocity_override: velocity::is_override(event),
                             });
                             return Ok(Some(EventHandled::Approval { proposal_id }));
                         } else {
@@ -267,6 +516,46 @@ impl SmartVaultsStorage {
                     );
                 }
             }
+        } else if event.kind == PROPOSAL_COMMENT_KIND {
+            let shared_keys = self.shared_keys.read().await;
+            let vaults = self.vaults.read().await;
+            let mut comments = self.comments.write().await;
+            if let HashMapEntry::Vacant(e) = comments.entry(event.id) {
+                let mut ids = event.event_ids();
+                if let (Some(proposal_id), Some(policy_id)) = (ids.next().copied(), ids.next()) {
+                    match (shared_keys.get(policy_id), vaults.get(policy_id)) {
+                        (Some(shared_key), Some(vault)) => {
+                            // Only the vault members can comment
+                            if !vault.public_keys.contains(&event.author()) {
+                                tracing::warn!(
+                                    "Comment {} not sent by a member of the vault",
+                                    event.id
+                                );
+                                return Ok(None);
+                            }
+
+                            let comment =
+                                ProposalComment::decrypt_with_keys(shared_key, &event.content)?;
+                            e.insert(InternalComment {
+                                proposal_id,
+                                policy_id: *policy_id,
+                                public_key: event.author(),
+                                comment,
+                                timestamp: event.created_at,
+                            });
+                            return Ok(Some(EventHandled::ProposalComment { proposal_id }));
+                        }
+                        _ => {
+                            pending.insert(event.clone());
+                        }
+                    }
+                } else {
+                    tracing::error!(
+                        "Impossible to find proposal and policy id in comment {}",
+                        event.id
+                    );
+                }
+            }
         } else if event.kind == COMPLETED_PROPOSAL_KIND {
             let shared_keys = self.shared_keys.read().await;
             let mut completed_proposals = self.completed_proposals.write().await;
@@ -276,13 +565,20 @@ impl SmartVaultsStorage {
                     self.delete_proposal(proposal_id).await;
                     if let Some(policy_id) = ids.next() {
                         if let Some(shared_key) = shared_keys.get(policy_id) {
+                            let content: String = match self.event_content(event).await? {
+                                Some(content) => content,
+                                None => {
+                                    pending.insert(event.clone());
+                                    return Ok(None);
+                                }
+                            };
                             let completed_proposal =
-                                CompletedProposal::decrypt_with_keys(shared_key, &event.content)?;
-                            e.insert(InternalCompletedProposal {
+                                CompletedProposal::decrypt_with_keys(shared_key, content)?;
+                            e.insert(CompletedProposalIndex::from(&InternalCompletedProposal {
                                 policy_id: *policy_id,
                                 proposal: completed_proposal,
                                 timestamp: event.created_at,
-                            });
+                            }));
                             return Ok(Some(EventHandled::CompletedProposal(event.id)));
                         } else {
                             pending.insert(event.clone());
@@ -339,13 +635,34 @@ impl SmartVaultsStorage {
                 if let Some(identifier) = event.identifier() {
                     if let Some(shared_key) = shared_keys.get(policy_id) {
                         let label = Label::decrypt_with_keys(shared_key, &event.content)?;
-                        labels.insert(
-                            identifier.to_string(),
-                            InternalLabel {
-                                policy_id: *policy_id,
-                                label,
-                            },
-                        );
+
+                        // The identifier commits to the shared key and to the label data
+                        if label.generate_identifier(shared_key)? != identifier {
+                            return Err(Error::IdentifierMismatch(event.id));
+                        }
+
+                        let internal = InternalLabel {
+                            policy_id: *policy_id,
+                            label,
+                            timestamp: event.created_at,
+                        };
+                        match labels.entry(identifier.to_string()) {
+                            HashMapEntry::Vacant(e) => {
+                                e.insert(internal);
+                            }
+                            HashMapEntry::Occupied(mut e) => {
+                                if e.get().policy_id != *policy_id {
+                                    return Err(Error::IdentifierCollision(identifier.to_string()));
+                                }
+
+                                // Ignore replays of older labels
+                                if e.get().timestamp > event.created_at {
+                                    return Ok(None);
+                                }
+
+                                e.insert(internal);
+                            }
+                        }
                         return Ok(Some(EventHandled::Label));
                     } else {
                         pending.insert(event.clone());
@@ -384,6 +701,36 @@ impl SmartVaultsStorage {
                     return Ok(Some(EventHandled::EventDeletion));
                 }
             }
+        } else if event.kind == VAULT_METADATA_KIND {
+            let shared_keys = self.shared_keys.read().await;
+            let mut vault_metadata = self.vault_metadata.write().await;
+            if let Some(policy_id) = event.event_ids().next() {
+                if let Some(shared_key) = shared_keys.get(policy_id) {
+                    // Must be signed with the shared key of the vault
+                    if event.author() != shared_key.public_key() {
+                        tracing::error!("Vault metadata {} not signed by shared key", event.id);
+                        return Ok(None);
+                    }
+
+                    // Ignore replays of older metadata
+                    if let Some((_, timestamp)) = vault_metadata.get(policy_id) {
+                        if *timestamp > event.created_at {
+                            return Ok(None);
+                        }
+                    }
+
+                    let metadata = VaultMetadata::decrypt_with_keys(shared_key, &event.content)?;
+                    vault_metadata.insert(*policy_id, (metadata, event.created_at));
+                    return Ok(Some(EventHandled::VaultMetadata(*policy_id)));
+                } else {
+                    pending.insert(event.clone());
+                }
+            } else {
+                tracing::error!(
+                    "Impossible to find policy id in vault metadata {}",
+                    event.id
+                );
+            }
         } else if event.kind == KEY_AGENT_VERIFIED {
             let new_verified_agents: VerifiedKeyAgents = VerifiedKeyAgents::from_event(event)?;
             let mut verified_key_agents = self.verified_key_agents.write().await;
@@ -398,6 +745,20 @@ impl SmartVaultsStorage {
         self.pending.read().await.clone()
     }
 
+    /// Get vaults that can't be decrypted due to missing shared key
+    ///
+    /// Return the vault IDs with the public keys of the members
+    pub async fn vaults_without_shared_key(&self) -> HashMap<EventId, Vec<PublicKey>> {
+        let shared_keys = self.shared_keys.read().await;
+        self.pending
+            .read()
+            .await
+            .iter()
+            .filter(|e| e.kind == POLICY_KIND && !shared_keys.contains_key(&e.id))
+            .map(|e| (e.id, e.public_keys().copied().collect()))
+            .collect()
+    }
+
     /// Delete event without know the kind
     pub async fn delete_event(&self, event_id: &EventId) {
         if self.delete_vault(event_id).await {
@@ -412,6 +773,10 @@ impl SmartVaultsStorage {
             return;
         }
 
+        if self.delete_comment(event_id).await {
+            return;
+        }
+
         if self.delete_completed_proposal(event_id).await {
             return;
         }
@@ -441,7 +806,12 @@ impl SmartVaultsStorage {
 
     pub async fn delete_vault(&self, vault_id: &EventId) -> bool {
         let mut vaults = self.vaults.write().await;
-        vaults.remove(vault_id).is_some()
+        if vaults.remove(vault_id).is_some() {
+            self.vault_metadata.write().await.remove(vault_id);
+            true
+        } else {
+            false
+        }
     }
 
     /// Get vaults
@@ -460,9 +830,55 @@ impl SmartVaultsStorage {
         vaults.get(vault_id).cloned().ok_or(Error::NotFound)
     }
 
-    pub async fn save_proposal(&self, proposal_id: EventId, internal: InternalProposal) {
+    pub async fn save_vault_metadata(
+        &self,
+        vault_id: EventId,
+        metadata: VaultMetadata,
+        timestamp: Timestamp,
+    ) {
+        let mut vault_metadata = self.vault_metadata.write().await;
+        vault_metadata.insert(vault_id, (metadata, timestamp));
+    }
+
+    /// Get vault metadata (default if not set)
+    pub async fn vault_metadata(&self, vault_id: &EventId) -> VaultMetadata {
+        let vault_metadata = self.vault_metadata.read().await;
+        vault_metadata
+            .get(vault_id)
+            .map(|(metadata, _)| metadata.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the relays pinned by all the vaults
+    pub async fn pinned_relays(&self) -> HashSet<Url> {
+        let vault_metadata = self.vault_metadata.read().await;
+        vault_metadata
+            .values()
+            .flat_map(|(metadata, _)| metadata.relays.iter().cloned())
+            .collect()
+    }
+
+    /// Save proposal
+    ///
+    /// Return the changes from the replaced proposal, if any.
+    pub async fn save_proposal(
+        &self,
+        proposal_id: EventId,
+        mut internal: InternalProposal,
+    ) -> Option<ProposalDiff> {
         let mut proposals = self.proposals.write().await;
+        internal.diff = replacement_diff(&proposals, &internal);
+        let diff: Option<ProposalDiff> = internal.diff.clone();
         proposals.insert(proposal_id, internal);
+        diff
+    }
+
+    /// Save the issues of the PSBT of a proposal, once validated
+    pub async fn save_proposal_issues(&self, proposal_id: &EventId, issues: Vec<ProposalIssue>) {
+        let mut proposals = self.proposals.write().await;
+        if let Some(internal) = proposals.get_mut(proposal_id) {
+            internal.issues = Some(issues);
+        }
     }
 
     /// Delete proposal and unfreeze UTXOs
@@ -470,17 +886,23 @@ impl SmartVaultsStorage {
         let mut proposals = self.proposals.write().await;
         match proposals.remove(proposal_id) {
             Some(p) => {
-                // Unfreeze UTXOs
-                self.unfreeze_utxos(
-                    p.policy_id,
-                    p.proposal
-                        .psbt()
-                        .unsigned_tx
-                        .input
-                        .iter()
-                        .map(|txin| txin.previous_output),
-                )
-                .await;
+                // Unfreeze UTXOs (except the ones spent by other proposals, i.e. by the replacement)
+                let used: HashSet<OutPoint> = proposals
+                    .values()
+                    .filter(|other| other.policy_id == p.policy_id)
+                    .flat_map(|other| other.proposal.psbt().unsigned_tx.input)
+                    .map(|txin| txin.previous_output)
+                    .collect();
+                let utxos: Vec<OutPoint> = p
+                    .proposal
+                    .psbt()
+                    .unsigned_tx
+                    .input
+                    .iter()
+                    .map(|txin| txin.previous_output)
+                    .filter(|utxo| !used.contains(utxo))
+                    .collect();
+                self.unfreeze_utxos(p.policy_id, utxos).await;
 
                 true
             }
@@ -529,6 +951,8 @@ impl SmartVaultsStorage {
     }
 
     /// Approvals by proposal ID
+    ///
+    /// The approvals not signed against the current PSBT of the proposal are returned apart.
     pub async fn approvals_by_proposal_id(
         &self,
         proposal_id: &EventId,
@@ -538,27 +962,64 @@ impl SmartVaultsStorage {
             proposal,
             ..
         } = self.proposal(proposal_id).await?;
+        let mut approved_proposals = Vec::new();
+        let mut stale_approvals = BTreeMap::new();
+        let approvals = self.approvals.read().await;
+        for (approval_id, internal) in approvals
+            .iter()
+            .filter(|(_, internal)| internal.proposal_id == *proposal_id)
+        {
+            if internal.commits_to(&proposal) {
+                approved_proposals.push(internal.approval.clone());
+            } else {
+                stale_approvals.insert(*approval_id, internal.public_key);
+            }
+        }
         Ok(GetApprovedProposals {
             policy_id,
             proposal,
-            approved_proposals: self
-                .approvals
-                .read()
-                .await
-                .values()
-                .filter(|internal| internal.proposal_id == *proposal_id)
-                .map(|internal| internal.approval.clone())
-                .collect(),
+            approved_proposals,
+            stale_approvals,
         })
     }
 
+    pub async fn save_comment(&self, comment_id: EventId, internal: InternalComment) {
+        let mut comments = self.comments.write().await;
+        comments.insert(comment_id, internal);
+    }
+
+    pub async fn delete_comment(&self, comment_id: &EventId) -> bool {
+        let mut comments = self.comments.write().await;
+        comments.remove(comment_id).is_some()
+    }
+
+    /// Comments by proposal ID (oldest first)
+    pub async fn comments_by_proposal_id(
+        &self,
+        proposal_id: &EventId,
+    ) -> Vec<(EventId, InternalComment)> {
+        let mut list: Vec<(EventId, InternalComment)> = self
+            .comments
+            .read()
+            .await
+            .iter()
+            .filter(|(_, c)| c.proposal_id == *proposal_id)
+            .map(|(id, c)| (*id, c.clone()))
+            .collect();
+        list.sort_by_key(|(id, c)| (c.timestamp, *id));
+        list
+    }
+
     pub async fn save_completed_proposal(
         &self,
         completed_proposal_id: EventId,
         internal: InternalCompletedProposal,
     ) {
         let mut completed_proposals = self.completed_proposals.write().await;
-        completed_proposals.insert(completed_proposal_id, internal);
+        completed_proposals.insert(
+            completed_proposal_id,
+            CompletedProposalIndex::from(&internal),
+        );
     }
 
     pub async fn delete_completed_proposal(&self, completed_proposal_id: &EventId) -> bool {
@@ -566,59 +1027,82 @@ impl SmartVaultsStorage {
         completed_proposals.remove(completed_proposal_id).is_some()
     }
 
-    /// Get completed_proposals
+    /// Get the resident index of the completed proposals
+    pub async fn completed_proposals_index(&self) -> HashMap<EventId, CompletedProposalIndex> {
+        self.completed_proposals.read().await.clone()
+    }
+
+    /// Get completed proposals
+    ///
+    /// The proposals are loaded from the database: use [`SmartVaultsStorage::completed_proposals_index`]
+    /// when the full proposals aren't needed.
     pub async fn completed_proposals(&self) -> HashMap<EventId, InternalCompletedProposal> {
-        self.completed_proposals
+        let ids: Vec<EventId> = self
+            .completed_proposals
             .read()
             .await
-            .iter()
-            .map(|(id, internal)| (*id, internal.clone()))
-            .collect()
+            .keys()
+            .copied()
+            .collect();
+        let mut completed_proposals = HashMap::with_capacity(ids.len());
+        for id in ids.into_iter() {
+            match self.completed_proposal(&id).await {
+                Ok(internal) => {
+                    completed_proposals.insert(id, internal);
+                }
+                Err(e) => tracing::error!("Impossible to load completed proposal {id}: {e}"),
+            }
+        }
+        completed_proposals
     }
 
+    /// Load completed proposal from the database
     pub async fn completed_proposal(
         &self,
         completed_proposal_id: &EventId,
     ) -> Result<InternalCompletedProposal, Error> {
-        let completed_proposals = self.completed_proposals.read().await;
-        completed_proposals
+        let CompletedProposalIndex {
+            policy_id,
+            timestamp,
+            ..
+        } = self
+            .completed_proposals
+            .read()
+            .await
             .get(completed_proposal_id)
             .cloned()
-            .ok_or(Error::NotFound)
+            .ok_or(Error::NotFound)?;
+        let shared_key: Keys = self.shared_key(&policy_id).await?;
+        let event: Event = self.database.event_by_id(*completed_proposal_id).await?;
+        let content: String = self.event_content(&event).await?.ok_or(Error::NotFound)?;
+        let proposal = CompletedProposal::decrypt_with_keys(&shared_key, content)?;
+        Ok(InternalCompletedProposal {
+            policy_id,
+            proposal,
+            timestamp,
+        })
     }
 
     pub async fn description_by_txid(&self, policy_id: EventId, txid: Txid) -> Option<String> {
         let completed_proposals = self.completed_proposals.read().await;
-        for InternalCompletedProposal { proposal, .. } in completed_proposals
+        completed_proposals
             .values()
             .filter(|i| i.policy_id == policy_id)
-        {
-            if let CompletedProposal::Spending {
-                tx, description, ..
-            } = proposal
-            {
-                if tx.txid() == txid {
-                    return Some(description.clone());
-                }
-            }
-        }
-        None
+            .filter_map(|i| i.spending.as_ref())
+            .find(|(t, ..)| t == &txid)
+            .map(|(_, description)| description.clone())
     }
 
     pub async fn txs_descriptions(&self, policy_id: EventId) -> HashMap<Txid, String> {
         let mut map = HashMap::new();
         let completed_proposals = self.completed_proposals.read().await;
-        for InternalCompletedProposal { proposal, .. } in completed_proposals
+        for (txid, description) in completed_proposals
             .values()
             .filter(|i| i.policy_id == policy_id)
+            .filter_map(|i| i.spending.as_ref())
         {
-            if let CompletedProposal::Spending {
-                tx, description, ..
-            } = proposal
-            {
-                if let HashMapEntry::Vacant(e) = map.entry(tx.txid()) {
-                    e.insert(description.clone());
-                }
+            if let HashMapEntry::Vacant(e) = map.entry(*txid) {
+                e.insert(description.clone());
             }
         }
         map
@@ -767,12 +1251,24 @@ impl SmartVaultsStorage {
             .collect()
     }
 
-    pub async fn save_label<S>(&self, identifier: S, policy_id: EventId, label: Label)
-    where
+    pub async fn save_label<S>(
+        &self,
+        identifier: S,
+        policy_id: EventId,
+        label: Label,
+        timestamp: Timestamp,
+    ) where
         S: Into<String>,
     {
         let mut labels = self.labels.write().await;
-        labels.insert(identifier.into(), InternalLabel { policy_id, label });
+        labels.insert(
+            identifier.into(),
+            InternalLabel {
+                policy_id,
+                label,
+                timestamp,
+            },
+        );
     }
 
     pub async fn get_addresses_labels(&self, policy_id: EventId) -> HashMap<ScriptBuf, Label> {
@@ -858,3 +1354,15 @@ impl SmartVaultsStorage {
         self.verified_key_agents.read().await.clone()
     }
 }
+
+/// Compute the changes from the replaced proposal, if known
+fn replacement_diff(
+    proposals: &HashMap<EventId, InternalProposal>,
+    internal: &InternalProposal,
+) -> Option<ProposalDiff> {
+    let replaced: &InternalProposal = proposals.get(internal.replaces.as_ref()?)?;
+    if replaced.policy_id != internal.policy_id {
+        return None;
+    }
+    Some(internal.proposal.diff(&replaced.proposal))
+}
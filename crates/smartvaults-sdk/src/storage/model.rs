@@ -4,8 +4,13 @@
 use std::cmp::Ordering;
 
 use nostr_sdk::{EventId, PublicKey, Timestamp};
-use smartvaults_core::{ApprovedProposal, CompletedProposal, Policy, Proposal, SharedSigner};
-use smartvaults_protocol::v1::Label;
+use smartvaults_core::bitcoin::Txid;
+use smartvaults_core::{
+    ApprovedProposal, CompletedProposal, Policy, Proposal, ProposalDiff, SharedSigner,
+};
+use smartvaults_protocol::v1::{Label, ProposalComment};
+
+use crate::types::ProposalIssue;
 
 #[derive(Debug, Clone)]
 pub(crate) struct InternalPolicy {
@@ -18,6 +23,19 @@ pub(crate) struct InternalProposal {
     pub policy_id: EventId,
     pub proposal: Proposal,
     pub timestamp: Timestamp,
+    /// Proposal replaced by this one
+    pub replaces: Option<EventId>,
+    /// Changes from the replaced proposal (if known when this one was received)
+    pub diff: Option<ProposalDiff>,
+    pub expires_at: Option<Timestamp>,
+    /// Issues of the PSBT, `None` until validated
+    pub issues: Option<Vec<ProposalIssue>>,
+}
+
+impl InternalProposal {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map_or(false, |t| t <= Timestamp::now())
+    }
 }
 
 impl PartialOrd for InternalProposal {
@@ -42,7 +60,28 @@ pub(crate) struct InternalApproval {
     pub policy_id: EventId,
     pub public_key: PublicKey,
     pub approval: ApprovedProposal,
+    /// Txid of the proposal transaction committed by the approval event (`txid` tag), `None` for
+    /// the approvals published without the tag
+    pub txid: Option<Txid>,
     pub timestamp: Timestamp,
+    /// The approval overrides the spending velocity limits
+    pub velocity_override: bool,
+}
+
+impl InternalApproval {
+    /// Check if the approval was signed against the current PSBT of the proposal
+    ///
+    /// The approvals of a rebuilt proposal are stale and can't be combined with the new PSBT.
+    pub fn commits_to(&self, proposal: &Proposal) -> bool {
+        let unsigned_tx = proposal.psbt().unsigned_tx;
+        let committed: bool = match self.txid {
+            Some(txid) => txid == unsigned_tx.txid(),
+            None => true,
+        };
+        committed
+            && self.approval.get_type() == proposal.get_type()
+            && self.approval.psbt().unsigned_tx == unsigned_tx
+    }
 }
 
 impl PartialOrd for InternalApproval {
@@ -61,6 +100,41 @@ impl Ord for InternalApproval {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InternalComment {
+    pub proposal_id: EventId,
+    pub policy_id: EventId,
+    pub public_key: PublicKey,
+    pub comment: ProposalComment,
+    pub timestamp: Timestamp,
+}
+
+/// Resident index of a completed proposal
+///
+/// The full proposal (i.e. the transaction) is loaded on demand from the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CompletedProposalIndex {
+    pub policy_id: EventId,
+    /// TXID and description of spending proposals
+    pub spending: Option<(Txid, String)>,
+    pub timestamp: Timestamp,
+}
+
+impl From<&InternalCompletedProposal> for CompletedProposalIndex {
+    fn from(internal: &InternalCompletedProposal) -> Self {
+        Self {
+            policy_id: internal.policy_id,
+            spending: match &internal.proposal {
+                CompletedProposal::Spending {
+                    tx, description, ..
+                } => Some((tx.txid(), description.clone())),
+                _ => None,
+            },
+            timestamp: internal.timestamp,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct InternalCompletedProposal {
     pub policy_id: EventId,
@@ -94,4 +168,69 @@ pub(crate) struct InternalSharedSigner {
 pub(crate) struct InternalLabel {
     pub policy_id: EventId,
     pub label: Label,
+    pub timestamp: Timestamp,
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use nostr_sdk::Keys;
+    use smartvaults_core::bitcoin::absolute::LockTime;
+    use smartvaults_core::bitcoin::psbt::PartiallySignedTransaction;
+    use smartvaults_core::bitcoin::{OutPoint, ScriptBuf, Transaction, TxIn, TxOut};
+    use smartvaults_core::miniscript::Descriptor;
+
+    use super::*;
+
+    const DESCRIPTOR: &str = "tr([7356e457/86'/1'/784923']tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d/0/*)";
+
+    fn psbt(value: u64) -> PartiallySignedTransaction {
+        let tx = Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                ..Default::default()
+            }],
+            output: vec![TxOut {
+                value,
+                script_pubkey: ScriptBuf::new(),
+            }],
+        };
+        PartiallySignedTransaction::from_unsigned_tx(tx).unwrap()
+    }
+
+    fn approval(psbt: PartiallySignedTransaction, txid: Option<Txid>) -> InternalApproval {
+        InternalApproval {
+            proposal_id: EventId::all_zeros(),
+            policy_id: EventId::all_zeros(),
+            public_key: Keys::generate().public_key(),
+            approval: ApprovedProposal::proof_of_reserve(psbt),
+            txid,
+            timestamp: Timestamp::now(),
+            velocity_override: false,
+        }
+    }
+
+    #[test]
+    fn test_approval_commits_to() {
+        let descriptor = Descriptor::from_str(DESCRIPTOR).unwrap();
+        let current = psbt(1_000);
+        let earlier = psbt(2_000);
+        let txid: Txid = current.unsigned_tx.txid();
+        let proposal = Proposal::proof_of_reserve(descriptor, "message", current.clone());
+
+        assert!(approval(current.clone(), Some(txid)).commits_to(&proposal));
+        // Approval published without the txid tag
+        assert!(approval(current.clone(), None).commits_to(&proposal));
+
+        // Approval of the proposal before being rebuilt
+        let earlier_txid: Txid = earlier.unsigned_tx.txid();
+        assert!(!approval(earlier.clone(), Some(earlier_txid)).commits_to(&proposal));
+        assert!(!approval(earlier, None).commits_to(&proposal));
+
+        // The committed txid doesn't match the proposal
+        assert!(!approval(current, Some(earlier_txid)).commits_to(&proposal));
+    }
 }
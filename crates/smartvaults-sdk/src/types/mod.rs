@@ -2,31 +2,64 @@
 // Distributed under the MIT software license
 
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt;
+use std::net::SocketAddr;
 use std::ops::Deref;
+use std::time::Duration;
 
-use nostr_sdk::{EventId, Profile, Timestamp};
+use nostr_sdk::{EventId, Kind, Profile, PublicKey, Timestamp, UnsignedEvent, Url};
 use smartvaults_core::bdk::wallet::Balance;
-use smartvaults_core::bdk::LocalOutput;
+use smartvaults_core::bdk::{FeeRate as BdkFeeRate, LocalOutput};
 use smartvaults_core::bitcoin::address::NetworkUnchecked;
-use smartvaults_core::bitcoin::Address;
+use smartvaults_core::bitcoin::bip32::Fingerprint;
+use smartvaults_core::bitcoin::{Address, OutPoint};
+use smartvaults_core::miniscript::{Descriptor, DescriptorPublicKey};
 use smartvaults_core::{
-    ApprovedProposal, CompletedProposal, Policy, Proposal, SharedSigner, Signer,
+    ApprovedProposal, CompletedProposal, PaymentMemo, Policy, Priority, Proposal, ProposalDiff,
+    Purpose, QuorumPath, Recipient, SharedSigner, Signer, TxOptions,
+};
+use smartvaults_protocol::v1::{
+    Capabilities, Currency, PaymentRequest, RecurringPayment, SignerOffering, SignerRequest,
+    VelocityLimit,
 };
-use smartvaults_protocol::v1::SignerOffering;
 pub use smartvaults_sdk_sqlite::model::*;
 
 pub mod backup;
+pub mod recovery_kit;
 
 pub use self::backup::PolicyBackup;
+pub use self::recovery_kit::{RecoveryKit, RecoveryKitKey};
+use crate::config::{CrossCheckBackend, ElectrumEndpoint};
+use crate::constants::{VAULT_STALE_EVENT_THRESHOLD, VAULT_STALE_SYNC_THRESHOLD};
 use crate::manager::TransactionDetails;
+pub use crate::manager::{SpendPreview, SpendPreviewOutput};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GetPolicy {
     pub policy_id: EventId,
     pub policy: Policy,
     pub balance: Balance,
+    /// Last timechain sync (`0` if never synced)
     pub last_sync: Timestamp,
+    /// Last event of the vault received from relays
+    pub last_event: Option<Timestamp>,
+    /// Timechain sync or relay events older than the thresholds (see [`GetPolicy::is_stale`])
+    pub stale: bool,
+    pub pinned: bool,
+}
+
+impl GetPolicy {
+    /// Check if the vault is stale: never synced, or the last timechain sync or the last relay event
+    /// are older than [`VAULT_STALE_SYNC_THRESHOLD`] and [`VAULT_STALE_EVENT_THRESHOLD`]
+    pub fn is_stale(last_sync: Timestamp, last_event: Option<Timestamp>, now: Timestamp) -> bool {
+        let older_than = |timestamp: Timestamp, threshold: Duration| {
+            now.as_u64().saturating_sub(timestamp.as_u64()) > threshold.as_secs()
+        };
+        last_sync == Timestamp::from(0)
+            || older_than(last_sync, VAULT_STALE_SYNC_THRESHOLD)
+            || last_event.map_or(true, |t| older_than(t, VAULT_STALE_EVENT_THRESHOLD))
+    }
 }
 
 impl PartialOrd for GetPolicy {
@@ -37,7 +70,12 @@ impl PartialOrd for GetPolicy {
 
 impl Ord for GetPolicy {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.policy.cmp(&other.policy)
+        if self.pinned != other.pinned {
+            // Pinned first
+            self.pinned.cmp(&other.pinned).reverse()
+        } else {
+            self.policy.cmp(&other.policy)
+        }
     }
 }
 
@@ -49,6 +87,311 @@ impl Deref for GetPolicy {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultSyncStatus {
+    /// Wallet not loaded yet
+    NotLoaded,
+    /// Wallet loaded but never synced
+    NeverSynced,
+    Syncing,
+    Synced(Timestamp),
+}
+
+/// Compact vault info
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetVaultSummary {
+    pub policy_id: EventId,
+    pub name: String,
+    pub balance: Balance,
+    pub pending_proposals: usize,
+    pub last_activity: Option<Timestamp>,
+    pub sync_status: VaultSyncStatus,
+    pub pinned: bool,
+}
+
+impl PartialOrd for GetVaultSummary {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GetVaultSummary {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.pinned != other.pinned {
+            // Pinned first
+            self.pinned.cmp(&other.pinned).reverse()
+        } else if self.name != other.name {
+            self.name.cmp(&other.name)
+        } else {
+            self.policy_id.cmp(&other.policy_id)
+        }
+    }
+}
+
+/// Backend used to broadcast a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BroadcastBackend {
+    Electrum(ElectrumEndpoint),
+    /// Esplora API (`POST /tx`)
+    Esplora(Url),
+    /// Bitcoin P2P node
+    Peer(SocketAddr),
+}
+
+impl fmt::Display for BroadcastBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Electrum(endpoint) => write!(f, "electrum ({endpoint})"),
+            Self::Esplora(url) => write!(f, "esplora ({url})"),
+            Self::Peer(addr) => write!(f, "peer ({addr})"),
+        }
+    }
+}
+
+/// Broadcast status for a single backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BroadcastStatus {
+    /// Transaction accepted by the backend
+    Accepted,
+    /// Transaction sent, but the backend doesn't report if it was accepted (P2P node)
+    Sent,
+    Failed(String),
+}
+
+impl fmt::Display for BroadcastStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Accepted => write!(f, "accepted"),
+            Self::Sent => write!(f, "sent"),
+            Self::Failed(e) => write!(f, "failed ({e})"),
+        }
+    }
+}
+
+/// Broadcast result for a single backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastResult {
+    pub backend: BroadcastBackend,
+    pub status: BroadcastStatus,
+}
+
+impl BroadcastResult {
+    /// Check if the transaction was accepted by the backend
+    pub fn is_accepted(&self) -> bool {
+        self.status == BroadcastStatus::Accepted
+    }
+
+    /// Check if the transaction was delivered to the backend (accepted or sent)
+    pub fn is_success(&self) -> bool {
+        !matches!(self.status, BroadcastStatus::Failed(..))
+    }
+}
+
+/// Fee rate suggested to confirm by a deadline (see [`SmartVaults::suggest_fee`])
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FeeSuggestion {
+    pub target_blocks: u8,
+    /// Suggested fee rate (the higher of `historical` and `estimated`)
+    pub fee_rate: BdkFeeRate,
+    /// Based on the fee rates of the blocks of the last 24h
+    pub historical: Option<BdkFeeRate>,
+    /// Current estimation of the fee provider
+    pub estimated: Option<BdkFeeRate>,
+}
+
+/// Mempool fee histogram (see [`SmartVaults::get_mempool_snapshot`])
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolSnapshot {
+    /// `(fee_rate, vsize)` pairs, highest fee rate first: size (vBytes) of the transactions paying
+    /// at least `fee_rate` (sat/vByte) and less than the fee rate of the previous pair
+    pub fee_histogram: Vec<(f64, u64)>,
+    /// Total size of the mempool (vBytes)
+    pub vsize: u64,
+    /// Fee rate needed to be included within the target blocks of the priorities
+    pub targets: BTreeMap<Priority, BdkFeeRate>,
+    pub timestamp: Timestamp,
+}
+
+/// BTC price in fiat
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BtcPrice {
+    pub currency: Currency,
+    /// Price of 1 BTC
+    pub price: f64,
+    pub timestamp: Timestamp,
+}
+
+impl BtcPrice {
+    /// Convert a fiat amount to SAT
+    pub fn to_sat(&self, fiat_amount: f64) -> u64 {
+        (fiat_amount / self.price * 100_000_000.0).round() as u64
+    }
+
+    /// Convert SAT to fiat
+    pub fn to_fiat(&self, sat: u64) -> f64 {
+        sat as f64 / 100_000_000.0 * self.price
+    }
+}
+
+/// Options of [`SmartVaults::spend_many`](crate::SmartVaults::spend_many) and of the previews
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpendOptions {
+    /// Spend only these UTXOs
+    pub utxos: Option<Vec<OutPoint>>,
+    /// Never spend these UTXOs (i.e. doxxed coins), without freezing them
+    pub excluded_utxos: Vec<OutPoint>,
+    /// Don't spend the UTXOs sharing the address with other UTXOs
+    pub avoid_address_reuse: bool,
+    pub policy_path: Option<BTreeMap<String, Vec<usize>>>,
+    /// Allow to spend the UTXOs frozen by other proposals
+    pub skip_frozen_utxos: bool,
+    /// Delete the proposal if not finalized by this time (ignored by the previews)
+    pub expires_at: Option<Timestamp>,
+    /// Payment records encrypted with the proposal (ignored by the previews)
+    pub memo: Option<PaymentMemo>,
+    /// Data embedded in an `OP_RETURN` output (i.e. an invoice hash, max 80 bytes)
+    ///
+    /// Ignored by [`SmartVaults::preview_spend`](crate::SmartVaults::preview_spend).
+    pub op_return: Option<Vec<u8>>,
+    /// nLockTime (anti-fee-sniping by default), RBF signaling and nSequence of the transaction
+    ///
+    /// Ignored by [`SmartVaults::preview_spend`](crate::SmartVaults::preview_spend).
+    pub tx_options: TxOptions,
+}
+
+/// Recipient of a batched spending proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpendRecipient {
+    pub address: Address<NetworkUnchecked>,
+    /// Amount in SAT
+    pub amount: u64,
+    /// Label of the address (i.e. the invoice reference), shared with the vault members
+    pub label: Option<String>,
+}
+
+impl From<SpendRecipient> for Recipient {
+    fn from(recipient: SpendRecipient) -> Self {
+        Self {
+            address: recipient.address,
+            amount: recipient.amount,
+        }
+    }
+}
+
+/// Relay fee (NIP-11)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayFee {
+    pub amount: u64,
+    /// Unit of the amount (i.e. `msats`)
+    pub unit: String,
+    /// Subscription period (seconds)
+    pub period: Option<u64>,
+}
+
+/// Terms of a relay (NIP-11 information document)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelayTerms {
+    pub name: Option<String>,
+    pub payment_required: bool,
+    pub auth_required: bool,
+    /// Some events may be rejected even if the other requirements are met
+    pub restricted_writes: bool,
+    pub payments_url: Option<String>,
+    pub admission_fees: Vec<RelayFee>,
+    pub subscription_fees: Vec<RelayFee>,
+    /// Url of the posting policy
+    pub posting_policy: Option<String>,
+}
+
+impl RelayTerms {
+    /// Check if the relay requires a payment (or an admission) to accept the events
+    pub fn requires_admission(&self) -> bool {
+        self.payment_required || !self.admission_fees.is_empty() || self.restricted_writes
+    }
+}
+
+/// Summary of the import of an events archive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventsImport {
+    /// New events, handled as if received from a relay
+    pub imported: usize,
+    /// Events already in the database
+    pub duplicated: usize,
+    /// Events with an invalid ID or signature
+    pub invalid: usize,
+    /// Events not handled (i.e. the shared key of the vault is missing)
+    pub failed: usize,
+}
+
+/// Cross-check of a vault balance against a second backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceCrossCheck {
+    pub backend: CrossCheckBackend,
+    /// Local balance, confirmed and unconfirmed (sat)
+    pub local: u64,
+    /// Balance reported by the backend (sat)
+    pub remote: u64,
+    /// UTXOs reported by the backend but missing in the local wallet
+    pub missing: Vec<OutPoint>,
+    /// Local UTXOs not reported by the backend
+    pub unknown: Vec<OutPoint>,
+}
+
+impl BalanceCrossCheck {
+    pub fn is_consistent(&self) -> bool {
+        self.missing.is_empty() && self.unknown.is_empty()
+    }
+}
+
+/// Connectivity check of a relay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayDiagnostic {
+    pub url: Url,
+    pub connected: bool,
+    /// NIP-42 authentication required (`None` if the relay information document is not available)
+    pub auth_required: Option<bool>,
+    /// Time to publish a test event and read it back
+    pub round_trip: Option<Duration>,
+    pub error: Option<String>,
+}
+
+impl RelayDiagnostic {
+    pub fn is_ok(&self) -> bool {
+        self.connected && self.round_trip.is_some()
+    }
+}
+
+/// Connectivity check of a timechain backend (electrum server or esplora API)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendDiagnostic {
+    pub backend: String,
+    /// Server version (not available for esplora)
+    pub version: Option<String>,
+    pub block_height: Option<u32>,
+    pub latency: Option<Duration>,
+    pub error: Option<String>,
+}
+
+impl BackendDiagnostic {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Connectivity diagnostics of the relays and of the timechain backends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticsReport {
+    pub relays: Vec<RelayDiagnostic>,
+    pub backends: Vec<BackendDiagnostic>,
+    pub timestamp: Timestamp,
+}
+
+impl DiagnosticsReport {
+    pub fn is_ok(&self) -> bool {
+        self.relays.iter().all(|r| r.is_ok()) && self.backends.iter().all(|b| b.is_ok())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GetProposal {
     pub proposal_id: EventId,
@@ -56,6 +399,26 @@ pub struct GetProposal {
     pub proposal: Proposal,
     pub signed: bool,
     pub timestamp: Timestamp,
+    /// Inconsistencies found validating the PSBT (see [`ProposalIssue`])
+    pub issues: Vec<ProposalIssue>,
+    /// Proposal replaced by this one
+    pub replaces: Option<EventId>,
+    /// Changes from the replaced proposal (missing if it was deleted before being received)
+    pub diff: Option<ProposalDiff>,
+    /// The proposal is deleted if not finalized by this time
+    pub expires_at: Option<Timestamp>,
+}
+
+impl GetProposal {
+    /// Check if the PSBT doesn't match what the proposal declares
+    pub fn is_suspicious(&self) -> bool {
+        !self.issues.is_empty()
+    }
+
+    /// Check if the proposal supersedes an amended or replaced one
+    pub fn is_amended(&self) -> bool {
+        self.replaces.is_some()
+    }
 }
 
 impl PartialOrd for GetProposal {
@@ -74,12 +437,160 @@ impl Ord for GetProposal {
     }
 }
 
+/// Inconsistency between the PSBT of a proposal and what the proposal declares
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProposalIssue {
+    /// Input not owned by the vault
+    ForeignInput(OutPoint),
+    /// Input without previous output (impossible to verify ownership and amount)
+    MissingInputUtxo(OutPoint),
+    /// Output not declared in the proposal recipients
+    UndeclaredOutput {
+        /// Address (or script, if not a standard address)
+        destination: String,
+        amount: u64,
+    },
+    /// Declared recipient not found in the outputs
+    MissingRecipient(Address<NetworkUnchecked>),
+    /// Amount sent (to the declared recipients and outside of the vault) not matching the declared
+    /// amount
+    AmountMismatch { declared: u64, actual: u64 },
+    /// Fee higher than the amount sent
+    AbsurdFee(u64),
+    /// Outputs exceeding the inputs
+    NegativeFee,
+    /// More than one `OP_RETURN` output or data larger than the standardness limit
+    NonStandardDataOutput,
+    /// Amount sent to an `OP_RETURN` output (unspendable)
+    BurnedAmount(u64),
+}
+
+impl ProposalIssue {
+    /// Check if the outputs of the PSBT don't match the declared recipients or amount
+    pub fn is_mismatch(&self) -> bool {
+        matches!(
+            self,
+            Self::UndeclaredOutput { .. }
+                | Self::MissingRecipient(..)
+                | Self::AmountMismatch { .. }
+        )
+    }
+}
+
+impl fmt::Display for ProposalIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ForeignInput(outpoint) => write!(f, "input {outpoint} not owned by the vault"),
+            Self::MissingInputUtxo(outpoint) => {
+                write!(f, "input {outpoint} without previous output")
+            }
+            Self::UndeclaredOutput {
+                destination,
+                amount,
+            } => write!(f, "undeclared output of {amount} sat to {destination}"),
+            Self::MissingRecipient(address) => write!(
+                f,
+                "recipient {} not found in outputs",
+                address.clone().assume_checked()
+            ),
+            Self::AmountMismatch { declared, actual } => {
+                write!(f, "declared amount {declared} sat but {actual} sat sent")
+            }
+            Self::AbsurdFee(fee) => write!(f, "fee of {fee} sat higher than the amount sent"),
+            Self::NegativeFee => write!(f, "outputs exceed inputs"),
+            Self::NonStandardDataOutput => write!(f, "non-standard OP_RETURN data output"),
+            Self::BurnedAmount(amount) => write!(f, "{amount} sat burned in OP_RETURN output"),
+        }
+    }
+}
+
+/// Quorum progress of a proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetProposalStatus {
+    pub proposal_id: EventId,
+    pub policy_id: EventId,
+    /// Number of valid approvals
+    pub approvals: usize,
+    /// Fingerprints of the signers that approved
+    pub signed: BTreeSet<Fingerprint>,
+    /// Fingerprints of the involved signers that not approved yet
+    pub missing: BTreeSet<Fingerprint>,
+    /// `true` if the collected approvals are enough to finalize the proposal
+    pub ready: bool,
+}
+
+/// Approvals still required by each spending path of a pending proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetQuorumStatus {
+    pub proposal_id: EventId,
+    pub policy_id: EventId,
+    /// Number of valid approvals
+    pub approvals: usize,
+    /// Spending paths the proposal can be finalized with
+    pub paths: Vec<QuorumPath>,
+    /// Known owners of the signers involved in the paths
+    pub owners: BTreeMap<Fingerprint, Profile>,
+    /// `true` if the collected approvals are enough to finalize the proposal
+    pub ready: bool,
+}
+
+/// Spending velocity limit of a vault and the amount spent in the current period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GetVelocityLimitStatus {
+    pub limit: VelocityLimit,
+    /// Amount (SAT) sent in the last period, fees included
+    pub spent: u64,
+}
+
+impl GetVelocityLimitStatus {
+    /// Amount (SAT) that can still be spent in the current period
+    pub fn remaining(&self) -> u64 {
+        self.limit.amount().saturating_sub(self.spent)
+    }
+}
+
+/// Progress of a spending split in multiple proposals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetSplitSpendProgress {
+    /// Number of proposals
+    pub total: usize,
+    /// Completed proposal IDs
+    pub completed: Vec<EventId>,
+    /// Quorum progress of the pending proposals
+    pub pending: Vec<GetProposalStatus>,
+    /// Proposals deleted without being completed
+    pub deleted: Vec<EventId>,
+}
+
+impl GetSplitSpendProgress {
+    /// Check if all the proposals are completed
+    pub fn is_completed(&self) -> bool {
+        self.completed.len() == self.total
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GetApproval {
     pub approval_id: EventId,
     pub user: Profile,
     pub approved_proposal: ApprovedProposal,
     pub timestamp: Timestamp,
+    /// Fingerprints of the signers used to approve
+    pub signers: BTreeSet<Fingerprint>,
+    /// `false` if expired or not matching the proposal transaction
+    pub valid: bool,
+    /// The approver explicitly overrode the spending velocity limits
+    pub velocity_override: bool,
+}
+
+/// Comment of a vault member on a proposal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetProposalComment {
+    pub comment_id: EventId,
+    pub proposal_id: EventId,
+    pub user: Profile,
+    pub message: String,
+    pub timestamp: Timestamp,
 }
 
 impl PartialOrd for GetApproval {
@@ -102,6 +613,23 @@ pub struct GetApprovedProposals {
     pub policy_id: EventId,
     pub proposal: Proposal,
     pub approved_proposals: Vec<ApprovedProposal>,
+    /// Approvals signed against an earlier PSBT of the proposal (approval ID and approver)
+    pub stale_approvals: BTreeMap<EventId, PublicKey>,
+}
+
+/// Approval result for a single proposal of a batch (see [`SmartVaults::approve_many`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkApprovalResult {
+    pub proposal_id: EventId,
+    /// Approval event, if approved
+    pub approval_id: Option<EventId>,
+    pub error: Option<String>,
+}
+
+impl BulkApprovalResult {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -160,6 +688,71 @@ impl Deref for GetSigner {
     }
 }
 
+/// Vaults and approvals that depend on a signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetSignerUsage {
+    pub signer_id: EventId,
+    /// Vaults whose descriptor contains the signer keys
+    pub vaults: BTreeSet<EventId>,
+    /// Approvals signed with the signer (approval ID -> proposal ID)
+    pub approvals: BTreeMap<EventId, EventId>,
+}
+
+impl GetSignerUsage {
+    /// Check if the signer is used by at least one vault
+    pub fn is_used(&self) -> bool {
+        !self.vaults.is_empty()
+    }
+}
+
+/// Extended public key and descriptors of a signer
+#[derive(Debug, Clone)]
+pub struct GetSignerDescriptors {
+    pub signer_id: EventId,
+    /// Purpose of the key derivation path, if known
+    pub purpose: Option<Purpose>,
+    /// Account extended public key, with key origin
+    pub xpub: String,
+    pub external: Descriptor<DescriptorPublicKey>,
+    /// Change descriptor (not available for signers with a non standard derivation)
+    pub internal: Option<Descriptor<DescriptorPublicKey>>,
+}
+
+/// Signer request received from another user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetSignerRequest {
+    pub request_id: EventId,
+    pub user: Profile,
+    pub request: SignerRequest,
+    /// Own signers matching the request
+    pub matching: Vec<GetSigner>,
+    pub timestamp: Timestamp,
+}
+
+/// Recurring payment of a vault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetRecurringPayment {
+    /// Identifier of the recurring payment (stable across the updates of the schedule)
+    pub payment_id: String,
+    pub policy_id: EventId,
+    /// Member that created the payment (the one proposing the payments)
+    pub owner: PublicKey,
+    pub payment: RecurringPayment,
+    pub next_due: Timestamp,
+}
+
+/// Payment request published by a vault member
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetPaymentRequest {
+    pub request_id: EventId,
+    pub policy_id: EventId,
+    pub user: Profile,
+    pub request: PaymentRequest,
+    /// A proposal of the vault already pays the request
+    pub fulfilled: bool,
+    pub timestamp: Timestamp,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct GetSharedSigner {
     pub shared_signer_id: EventId,
@@ -183,7 +776,10 @@ impl Ord for GetSharedSigner {
 pub struct GetUtxo {
     pub utxo: LocalOutput,
     pub label: Option<String>,
+    /// Frozen by a pending proposal or by the user
     pub frozen: bool,
+    /// Frozen by the user (see [`SmartVaults::freeze_utxos`](crate::SmartVaults::freeze_utxos))
+    pub user_frozen: bool,
 }
 
 impl Deref for GetUtxo {
@@ -200,6 +796,10 @@ pub struct GetTransaction {
     pub tx: TransactionDetails,
     pub label: Option<String>,
     pub block_explorer: Option<String>,
+    /// The confirmation was verified against the SPV header chain
+    pub is_confirmation_verified: bool,
+    /// Unconfirmed transaction paying less than the current mempool minimum
+    pub stuck: Option<StuckTransaction>,
 }
 
 impl PartialOrd for GetTransaction {
@@ -222,6 +822,75 @@ impl Deref for GetTransaction {
     }
 }
 
+/// Timelocked spending path of a vault that a signer of the user can satisfy alone (i.e. the
+/// recovery branch of a decaying vault)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetRecoveryPath {
+    pub policy_id: EventId,
+    pub signer_id: EventId,
+    pub policy_path: BTreeMap<String, Vec<usize>>,
+    /// The timelock is matured: the vault funds can be swept with this path
+    pub matured: bool,
+}
+
+/// Action suggested to speed up the confirmation of a stuck transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StuckTxAction {
+    /// Replace the transaction with one paying a higher fee (the transaction signals RBF)
+    Rbf,
+    /// Spend an output received by the vault with a child transaction paying for both
+    Cpfp,
+}
+
+impl fmt::Display for StuckTxAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rbf => write!(f, "RBF"),
+            Self::Cpfp => write!(f, "CPFP"),
+        }
+    }
+}
+
+/// Broadcast transaction with a fee rate below the current mempool minimum
+#[derive(Debug, Clone, PartialEq)]
+pub struct StuckTransaction {
+    pub fee_rate: BdkFeeRate,
+    /// Low priority mempool fee rate
+    pub mempool_min_fee_rate: BdkFeeRate,
+    /// Suggested actions (empty if the transaction can't be accelerated by the vault)
+    pub actions: Vec<StuckTxAction>,
+}
+
+impl Eq for StuckTransaction {}
+
+impl StuckTransaction {
+    /// Check if an unconfirmed transaction is stuck
+    pub(crate) fn check(tx: &TransactionDetails, mempool_min_fee_rate: BdkFeeRate) -> Option<Self> {
+        if tx.confirmation_time.is_confirmed() {
+            return None;
+        }
+
+        let fee_rate: BdkFeeRate = tx.fee.rate?;
+        if fee_rate.as_sat_per_vb() >= mempool_min_fee_rate.as_sat_per_vb() {
+            return None;
+        }
+
+        let mut actions: Vec<StuckTxAction> = Vec::new();
+        if tx.sent > 0 && tx.transaction.is_explicitly_rbf() {
+            actions.push(StuckTxAction::Rbf);
+        }
+        if tx.received > 0 {
+            actions.push(StuckTxAction::Cpfp);
+        }
+
+        Some(Self {
+            fee_rate,
+            mempool_min_fee_rate,
+            actions,
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GetAddress {
     pub address: Address<NetworkUnchecked>,
@@ -236,6 +905,37 @@ impl Deref for GetAddress {
     }
 }
 
+/// Funds deposited by a vault member
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetMemberContribution {
+    pub user: Profile,
+    /// Total deposited amount (SAT)
+    pub amount: u64,
+    /// Deposited outputs
+    pub deposits: Vec<OutPoint>,
+}
+
+/// Per-member breakdown of the vault deposits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetMemberContributions {
+    /// Sorted by amount (descending)
+    pub members: Vec<GetMemberContribution>,
+    /// Deposited amount not attributed to any member (SAT)
+    pub unattributed: u64,
+    pub unattributed_deposits: Vec<OutPoint>,
+}
+
+impl GetMemberContributions {
+    /// Total deposited amount (SAT)
+    pub fn total(&self) -> u64 {
+        self.members
+            .iter()
+            .map(|m| m.amount)
+            .sum::<u64>()
+            .saturating_add(self.unattributed)
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct GetAllSigners {
     pub my: Vec<GetSigner>,
@@ -276,3 +976,220 @@ pub struct GetSignerOffering {
     pub signer: GetSigner,
     pub offering: SignerOffering,
 }
+
+/// Result of the consistency check between the in-memory storage and the nostr database
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Number of checked events
+    pub checked: usize,
+    /// Not processed events that were replayed
+    pub replayed: Vec<EventId>,
+    /// Events that can't be processed
+    pub failed: Vec<EventId>,
+    /// Items without a backing event (removed)
+    pub orphaned: Vec<EventId>,
+    /// Events waiting for a shared key
+    pub pending: usize,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.replayed.is_empty() && self.failed.is_empty() && self.orphaned.is_empty()
+    }
+}
+
+/// Device (or session) that opened the keychain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetDevice {
+    pub device_id: String,
+    pub platform: String,
+    pub name: Option<String>,
+    pub last_seen: Timestamp,
+    pub revoked: bool,
+    /// This device
+    pub current: bool,
+}
+
+impl PartialOrd for GetDevice {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for GetDevice {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.last_seen != other.last_seen {
+            self.last_seen.cmp(&other.last_seen).reverse()
+        } else {
+            self.device_id.cmp(&other.device_id)
+        }
+    }
+}
+
+/// Compatibility of a cosigner client with this one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CosignerCompatibility {
+    Compatible,
+    /// Same protocol version but some features are not supported
+    MissingFeatures(BTreeSet<String>),
+    /// No protocol version in common: the cosigner can't parse the events of this client
+    Incompatible,
+    /// Capabilities never published (old client or not synced yet)
+    Unknown,
+}
+
+impl CosignerCompatibility {
+    pub fn is_compatible(&self) -> bool {
+        matches!(self, Self::Compatible)
+    }
+}
+
+impl fmt::Display for CosignerCompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Compatible => write!(f, "compatible"),
+            Self::MissingFeatures(features) => write!(
+                f,
+                "missing features: {}",
+                features.iter().cloned().collect::<Vec<_>>().join(", ")
+            ),
+            Self::Incompatible => write!(f, "incompatible protocol version"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetCosignerCapabilities {
+    pub public_key: PublicKey,
+    pub capabilities: Option<Capabilities>,
+    pub compatibility: CosignerCompatibility,
+    pub timestamp: Option<Timestamp>,
+}
+
+/// Member of a vault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetVaultMember {
+    pub user: Profile,
+    /// Fingerprints of the member signers used in the vault descriptor
+    ///
+    /// Only the own signers and the ones shared by the member can be detected.
+    pub signers: BTreeSet<Fingerprint>,
+    /// Latest event of the vault published by the member
+    pub last_activity: Option<Timestamp>,
+    /// The member asked for the shared key of the vault
+    pub shared_key_requested: bool,
+}
+
+impl GetVaultMember {
+    /// Check if the member is able to sign for the vault
+    pub fn is_cosigner(&self) -> bool {
+        !self.signers.is_empty()
+    }
+
+    /// Check if the member never published an event for the vault (invitation not accepted yet)
+    pub fn is_pending(&self) -> bool {
+        self.last_activity.is_none()
+    }
+}
+
+/// Decoded nostr connect request, to inspect before the approval
+#[derive(Debug, Clone)]
+pub struct NostrConnectRequestDetail {
+    pub event_id: EventId,
+    pub app_public_key: PublicKey,
+    pub method: String,
+    /// Request params (JSON)
+    pub params: Vec<String>,
+    /// Event to sign, for `sign_event` requests
+    pub unsigned_event: Option<UnsignedEvent>,
+    /// Raw NIP46 message (JSON)
+    pub raw: String,
+    pub timestamp: Timestamp,
+    pub approved: bool,
+}
+
+impl NostrConnectRequestDetail {
+    /// Kind of the event to sign
+    pub fn kind(&self) -> Option<Kind> {
+        self.unsigned_event.as_ref().map(|e| e.kind)
+    }
+
+    /// Content of the event to sign, truncated to `max_chars`
+    pub fn content_preview(&self, max_chars: usize) -> Option<String> {
+        let content: &str = &self.unsigned_event.as_ref()?.content;
+        if content.chars().count() > max_chars {
+            let mut preview: String = content.chars().take(max_chars).collect();
+            preview.push('…');
+            Some(preview)
+        } else {
+            Some(content.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vault_staleness() {
+        let now = Timestamp::from(10_000_000);
+        let recent = Timestamp::from(now.as_u64() - 60);
+        assert!(!GetPolicy::is_stale(recent, Some(recent), now));
+        assert!(GetPolicy::is_stale(Timestamp::from(0), Some(recent), now));
+        assert!(GetPolicy::is_stale(recent, None, now));
+
+        let old_sync = Timestamp::from(now.as_u64() - VAULT_STALE_SYNC_THRESHOLD.as_secs() - 1);
+        assert!(GetPolicy::is_stale(old_sync, Some(recent), now));
+
+        let old_event = Timestamp::from(now.as_u64() - VAULT_STALE_EVENT_THRESHOLD.as_secs() - 1);
+        assert!(GetPolicy::is_stale(recent, Some(old_event), now));
+    }
+
+    #[test]
+    fn test_stuck_transaction() {
+        use smartvaults_core::bdk::chain::ConfirmationTime;
+        use smartvaults_core::bitcoin::absolute::LockTime;
+        use smartvaults_core::bitcoin::{Sequence, Transaction, TxIn};
+
+        use crate::manager::wallet::Fee;
+
+        let mut tx = TransactionDetails {
+            transaction: Transaction {
+                version: 2,
+                lock_time: LockTime::ZERO,
+                input: vec![TxIn {
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    ..Default::default()
+                }],
+                output: Vec::new(),
+            },
+            received: 0,
+            sent: 10_000,
+            fee: Fee {
+                amount: Some(141),
+                rate: Some(BdkFeeRate::from_sat_per_vb(1.0)),
+            },
+            confirmation_time: ConfirmationTime::Unconfirmed { last_seen: 0 },
+        };
+
+        // Fee rate above the mempool minimum
+        assert!(StuckTransaction::check(&tx, BdkFeeRate::from_sat_per_vb(1.0)).is_none());
+
+        let min = BdkFeeRate::from_sat_per_vb(5.0);
+        let stuck = StuckTransaction::check(&tx, min).unwrap();
+        assert_eq!(stuck.actions, vec![StuckTxAction::Rbf]);
+
+        // Change output received by the vault
+        tx.received = 5_000;
+        let stuck = StuckTransaction::check(&tx, min).unwrap();
+        assert_eq!(stuck.actions, vec![StuckTxAction::Rbf, StuckTxAction::Cpfp]);
+
+        tx.confirmation_time = ConfirmationTime::Confirmed {
+            height: 800_000,
+            time: 0,
+        };
+        assert!(StuckTransaction::check(&tx, min).is_none());
+    }
+}
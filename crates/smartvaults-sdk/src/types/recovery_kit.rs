@@ -0,0 +1,349 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Disaster-recovery kit
+//!
+//! Printable document with everything needed to recover the funds of a vault with open-source
+//! tools (i.e. Sparrow Wallet or Bitcoin Core), without Smart Vaults.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Error;
+use std::path::Path;
+
+use nostr_sdk::Timestamp;
+use smartvaults_core::bdk::descriptor::policy::{PkOrF, SatisfiableItem};
+use smartvaults_core::bitcoin::absolute::LockTime;
+use smartvaults_core::bitcoin::bip32::{DerivationPath, Fingerprint};
+use smartvaults_core::bitcoin::{relative, Address, Network};
+use smartvaults_core::miniscript::{Descriptor, DescriptorPublicKey, ForEachKey};
+
+/// Key of the vault descriptor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryKitKey {
+    /// Master key fingerprint (missing for keys without origin)
+    pub fingerprint: Option<Fingerprint>,
+    /// Derivation path from the master key
+    pub derivation_path: Option<DerivationPath>,
+    /// Public key, as written in the descriptor
+    pub key: String,
+    /// Name of the owner, if known
+    pub owner: Option<String>,
+}
+
+impl RecoveryKitKey {
+    /// Extract the keys of the descriptor
+    pub fn from_descriptor(
+        descriptor: &Descriptor<DescriptorPublicKey>,
+        owners: &BTreeMap<Fingerprint, String>,
+    ) -> Vec<Self> {
+        let mut keys: Vec<Self> = Vec::new();
+        descriptor.for_each_key(|key| {
+            let origin = match key {
+                DescriptorPublicKey::Single(k) => k.origin.clone(),
+                DescriptorPublicKey::XPub(k) => k.origin.clone(),
+                DescriptorPublicKey::MultiXPub(k) => k.origin.clone(),
+            };
+            let (fingerprint, derivation_path) = match origin {
+                Some((fingerprint, path)) => (Some(fingerprint), Some(path)),
+                None => (None, None),
+            };
+            keys.push(Self {
+                owner: fingerprint.and_then(|f| owners.get(&f).cloned()),
+                fingerprint,
+                derivation_path,
+                key: key.to_string(),
+            });
+            true
+        });
+        keys
+    }
+}
+
+/// Disaster-recovery kit of a vault (see [`RecoveryKit::to_string`] for the printable document)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoveryKit {
+    pub name: String,
+    pub description: String,
+    pub network: Network,
+    pub descriptor: String,
+    /// Human-comparable fingerprint of the descriptor
+    pub descriptor_fingerprint: Option<String>,
+    pub keys: Vec<RecoveryKitKey>,
+    /// Spending conditions, in plain language
+    pub spending_paths: Vec<String>,
+    /// First receiving address, to check that the descriptor was imported correctly
+    pub first_address: Address,
+    pub generated_at: Timestamp,
+}
+
+impl RecoveryKit {
+    /// Save the printable document
+    pub fn save<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for RecoveryKit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "DISASTER-RECOVERY KIT - {}", self.name)?;
+        writeln!(f, "Generated on {}", self.generated_at.to_human_datetime())?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Keep this document in a safe place. It doesn't contain private keys:"
+        )?;
+        writeln!(
+            f,
+            "it allows to watch the vault, but the funds can be spent only with the"
+        )?;
+        writeln!(f, "signatures of the keys listed below.")?;
+        writeln!(f)?;
+
+        writeln!(f, "1. VAULT")?;
+        writeln!(f)?;
+        writeln!(f, "Name: {}", self.name)?;
+        if !self.description.is_empty() {
+            writeln!(f, "Description: {}", self.description)?;
+        }
+        writeln!(f, "Network: {}", self.network)?;
+        if let Some(fingerprint) = &self.descriptor_fingerprint {
+            writeln!(f, "Descriptor fingerprint: {fingerprint}")?;
+        }
+        writeln!(f, "First address: {}", self.first_address)?;
+        writeln!(f)?;
+        writeln!(f, "Output descriptor:")?;
+        writeln!(f, "{}", self.descriptor)?;
+        writeln!(f)?;
+
+        writeln!(f, "2. KEYS")?;
+        writeln!(f)?;
+        for (index, key) in self.keys.iter().enumerate() {
+            match key.fingerprint {
+                Some(fingerprint) => writeln!(f, "Key #{}: fingerprint {fingerprint}", index + 1)?,
+                None => writeln!(
+                    f,
+                    "Key #{}: no origin (i.e. unspendable taproot internal key)",
+                    index + 1
+                )?,
+            }
+            if let Some(owner) = &key.owner {
+                writeln!(f, "  Owner: {owner}")?;
+            }
+            if let Some(path) = &key.derivation_path {
+                writeln!(f, "  Derivation path: {path}")?;
+            }
+            writeln!(f, "  Public key: {}", key.key)?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "3. SPENDING CONDITIONS")?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "The funds can be spent if any of the following conditions is met:"
+        )?;
+        for (index, path) in self.spending_paths.iter().enumerate() {
+            writeln!(f, "  {}) {path}", index + 1)?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "4. RECOVERY INSTRUCTIONS")?;
+        writeln!(f)?;
+        writeln!(f, "With Sparrow Wallet (https://sparrowwallet.com):")?;
+        writeln!(
+            f,
+            "  a) File > New Wallet, then choose \"Edit\" in the descriptor settings"
+        )?;
+        writeln!(f, "     and paste the output descriptor of section 1.")?;
+        writeln!(
+            f,
+            "  b) Check that the first receiving address matches the one above."
+        )?;
+        writeln!(
+            f,
+            "  c) Connect to a Bitcoin node or a public Electrum server and wait the"
+        )?;
+        writeln!(f, "     sync of the transactions.")?;
+        writeln!(
+            f,
+            "  d) Create the transaction from the Send tab and save the PSBT."
+        )?;
+        writeln!(
+            f,
+            "  e) Sign the PSBT with the keys needed by one of the spending"
+        )?;
+        writeln!(
+            f,
+            "     conditions (hardware wallets, air-gapped devices or the seeds"
+        )?;
+        writeln!(
+            f,
+            "     restored in another Sparrow Wallet), one after the other."
+        )?;
+        writeln!(f, "  f) Broadcast the fully signed transaction.")?;
+        writeln!(f)?;
+        writeln!(f, "With Bitcoin Core (https://bitcoincore.org):")?;
+        writeln!(
+            f,
+            "  a) bitcoin-cli createwallet \"recovery\" true true \"\" false true"
+        )?;
+        writeln!(
+            f,
+            "  b) bitcoin-cli -rpcwallet=recovery importdescriptors \\"
+        )?;
+        writeln!(
+            f,
+            "     '[{{\"desc\": \"<descriptor>\", \"timestamp\": 0, \"active\": true}}]'"
+        )?;
+        writeln!(
+            f,
+            "  c) bitcoin-cli -rpcwallet=recovery getaddressinfo <first address>"
+        )?;
+        writeln!(f, "     (must report \"ismine\": true)")?;
+        writeln!(
+            f,
+            "  d) bitcoin-cli -rpcwallet=recovery walletcreatefundedpsbt ..."
+        )?;
+        writeln!(
+            f,
+            "  e) Sign the PSBT with each needed key, merge the signatures with"
+        )?;
+        writeln!(
+            f,
+            "     `combinepsbt`, then `finalizepsbt` and `sendrawtransaction`."
+        )?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "Conditions with a timelock can be used only after the timelock expired:"
+        )?;
+        writeln!(
+            f,
+            "the transaction is rejected by the network before that time."
+        )?;
+
+        Ok(())
+    }
+}
+
+fn display_key(key: &PkOrF, owners: &BTreeMap<Fingerprint, String>) -> String {
+    match key {
+        PkOrF::Fingerprint(f) => match owners.get(f) {
+            Some(owner) => format!("key {f} ({owner})"),
+            None => format!("key {f}"),
+        },
+        PkOrF::Pubkey(pk) => format!("key {pk}"),
+        PkOrF::XOnlyPubkey(pk) => format!("key {pk}"),
+    }
+}
+
+/// Describe the [`SatisfiableItem`] in plain language
+fn explain(item: &SatisfiableItem, owners: &BTreeMap<Fingerprint, String>) -> String {
+    match item {
+        SatisfiableItem::EcdsaSignature(key) | SatisfiableItem::SchnorrSignature(key) => {
+            format!("a signature of {}", display_key(key, owners))
+        }
+        SatisfiableItem::Sha256Preimage { hash } => format!("the SHA256 preimage of {hash}"),
+        SatisfiableItem::Hash256Preimage { hash } => {
+            format!("the double-SHA256 preimage of {hash}")
+        }
+        SatisfiableItem::Ripemd160Preimage { hash } => {
+            format!("the RIPEMD160 preimage of {hash}")
+        }
+        SatisfiableItem::Hash160Preimage { hash } => format!("the HASH160 preimage of {hash}"),
+        SatisfiableItem::AbsoluteTimelock { value } => match value {
+            LockTime::Blocks(height) => format!("block height {height} reached"),
+            LockTime::Seconds(time) => format!(
+                "date {} reached",
+                Timestamp::from(time.to_consensus_u32() as u64).to_human_datetime()
+            ),
+        },
+        SatisfiableItem::RelativeTimelock { value } => match value.to_relative_lock_time() {
+            Some(relative::LockTime::Blocks(height)) => match height.value() {
+                blocks if blocks >= 144 => format!(
+                    "{blocks} blocks (about {} days) passed since the funds were received",
+                    blocks / 144
+                ),
+                blocks => format!("{blocks} blocks passed since the funds were received"),
+            },
+            Some(relative::LockTime::Time(time)) => format!(
+                "{} seconds passed since the funds were received",
+                time.value() as u32 * 512
+            ),
+            None => format!("relative timelock {value}"),
+        },
+        SatisfiableItem::Multisig { keys, threshold } => format!(
+            "{threshold} signatures out of {}: {}",
+            keys.len(),
+            keys.iter()
+                .map(|k| display_key(k, owners))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        SatisfiableItem::Thresh { items, threshold } => {
+            let items: Vec<String> = items.iter().map(|i| explain(&i.item, owners)).collect();
+            if *threshold == items.len() {
+                format!("all of [{}]", items.join("; "))
+            } else if *threshold == 1 {
+                format!("any of [{}]", items.join("; "))
+            } else {
+                format!(
+                    "{threshold} out of {} of [{}]",
+                    items.len(),
+                    items.join("; ")
+                )
+            }
+        }
+    }
+}
+
+/// Get the alternative spending paths of the policy, in plain language
+pub(crate) fn spending_paths(
+    item: &SatisfiableItem,
+    owners: &BTreeMap<Fingerprint, String>,
+) -> Vec<String> {
+    match item {
+        SatisfiableItem::Thresh { items, threshold } if *threshold == 1 => {
+            items.iter().map(|i| explain(&i.item, owners)).collect()
+        }
+        _ => vec![explain(item, owners)],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use smartvaults_core::Policy;
+
+    use super::*;
+
+    const DESCRIPTOR: &str = "tr([7356e457/86'/1'/784923']tpubDCvLwbJPseNux9EtPbrbA2tgDayzptK4HNkky14Cw6msjHuqyZCE88miedZD86TZUb29Rof3sgtREU4wtzofte7QDSWDiw8ZU6ZYHmAxY9d/0/*,and_v(v:pk([4eb5d5a1/86'/1'/784923']tpubDCLskGdzStPPo1auRQygJUfbmLMwujWr7fmekdUMD7gqSpwEcRso4CfiP5GkRqfXFYkfqTujyvuehb7inymMhBJFdbJqFyHsHVRuwLKCSe9/0/*),older(6)))";
+
+    #[test]
+    fn test_spending_paths() {
+        let policy = Policy::from_descriptor("", "", DESCRIPTOR, Network::Testnet).unwrap();
+        let owners = BTreeMap::from([(
+            Fingerprint::from_str("4eb5d5a1").unwrap(),
+            String::from("Alice"),
+        )]);
+        let paths = spending_paths(policy.satisfiable_item().unwrap(), &owners);
+        assert_eq!(
+            paths,
+            vec![
+                String::from("a signature of key 7356e457"),
+                String::from("all of [a signature of key 4eb5d5a1 (Alice); 6 blocks passed since the funds were received]"),
+            ]
+        );
+
+        let descriptor: Descriptor<DescriptorPublicKey> = Descriptor::from_str(DESCRIPTOR).unwrap();
+        let keys = RecoveryKitKey::from_descriptor(&descriptor, &owners);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[1].owner.as_deref(), Some("Alice"));
+    }
+}
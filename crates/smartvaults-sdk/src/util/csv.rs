@@ -0,0 +1,166 @@
+// Copyright (c) 2022-2024 Smart Vaults
+// Distributed under the MIT software license
+
+//! Import the recipients of a batch spending proposal from CSV
+//!
+//! Each row is `address,amount[,label]`: an integer amount is in SAT, a decimal amount in BTC.
+//! Empty lines, lines starting with `#` and an `address` header row are skipped.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use smartvaults_core::bitcoin::address::NetworkUnchecked;
+use smartvaults_core::bitcoin::{Address, Amount, Denomination, Network};
+use thiserror::Error;
+
+use crate::types::SpendRecipient;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum Error {
+    #[error("line {0}: expected address,amount[,label]")]
+    InvalidRow(usize),
+    #[error("line {line}: invalid address: {error}")]
+    InvalidAddress { line: usize, error: String },
+    #[error("line {0}: address not valid for the current network")]
+    WrongNetwork(usize),
+    #[error("line {line}: invalid amount: {error}")]
+    InvalidAmount { line: usize, error: String },
+    #[error("line {0}: address already used at line {1}")]
+    DuplicatedAddress(usize, usize),
+    #[error("no recipients")]
+    Empty,
+}
+
+/// Split a CSV row, supporting quoted fields (i.e. labels containing commas)
+fn split_row(row: &str) -> Vec<String> {
+    let mut fields: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut quoted: bool = false;
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+fn parse_amount(amount: &str) -> Result<u64, String> {
+    let amount: Amount = if amount.contains('.') {
+        Amount::from_str_in(amount, Denomination::Bitcoin).map_err(|e| e.to_string())?
+    } else {
+        Amount::from_str_in(amount, Denomination::Satoshi).map_err(|e| e.to_string())?
+    };
+    match amount.to_sat() {
+        0 => Err(String::from("amount must be greater than zero")),
+        sat => Ok(sat),
+    }
+}
+
+/// Parse and validate the recipients against the `network`
+pub fn parse_recipients(content: &str, network: Network) -> Result<Vec<SpendRecipient>, Error> {
+    let mut recipients: Vec<SpendRecipient> = Vec::new();
+    let mut lines: HashMap<Address<NetworkUnchecked>, usize> = HashMap::new();
+
+    for (index, row) in content.lines().enumerate() {
+        let line: usize = index + 1;
+        let row: &str = row.trim();
+        if row.is_empty() || row.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<String> = split_row(row);
+        if recipients.is_empty() && fields[0].eq_ignore_ascii_case("address") {
+            continue;
+        }
+
+        let (address, amount, label) = match fields.as_slice() {
+            [address, amount] => (address, amount, None),
+            [address, amount, label] => (address, amount, Some(label)),
+            _ => return Err(Error::InvalidRow(line)),
+        };
+
+        let address = Address::from_str(address).map_err(|e| Error::InvalidAddress {
+            line,
+            error: e.to_string(),
+        })?;
+        if !address.is_valid_for_network(network) {
+            return Err(Error::WrongNetwork(line));
+        }
+
+        if let Some(first) = lines.insert(address.clone(), line) {
+            return Err(Error::DuplicatedAddress(line, first));
+        }
+
+        recipients.push(SpendRecipient {
+            address,
+            amount: parse_amount(amount).map_err(|error| Error::InvalidAmount { line, error })?,
+            label: label.filter(|l| !l.is_empty()).cloned(),
+        });
+    }
+
+    if recipients.is_empty() {
+        return Err(Error::Empty);
+    }
+
+    Ok(recipients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS_1: &str = "tb1qe2tczyk2rw7u47kzxxee5g7ufkncdmlcta4z69";
+    const ADDRESS_2: &str = "tb1q8c37s9sq89v55vuffajkfcd3xj9m67sqlw8pc5";
+
+    #[test]
+    fn test_parse_recipients() {
+        let content = format!(
+            "address,amount,label
+            {ADDRESS_1},1500,\"Salary, March\"
+
+            # Bonus
+            {ADDRESS_2},0.001"
+        );
+        let recipients = parse_recipients(&content, Network::Testnet).unwrap();
+        assert_eq!(recipients.len(), 2);
+        assert_eq!(recipients[0].amount, 1500);
+        assert_eq!(recipients[0].label.as_deref(), Some("Salary, March"));
+        assert_eq!(recipients[1].amount, 100_000);
+        assert_eq!(recipients[1].label, None);
+    }
+
+    #[test]
+    fn test_invalid_recipients() {
+        assert_eq!(
+            parse_recipients(&format!("{ADDRESS_1},1500"), Network::Bitcoin),
+            Err(Error::WrongNetwork(1))
+        );
+        assert_eq!(
+            parse_recipients(
+                &format!("{ADDRESS_1},1500\n{ADDRESS_1},10"),
+                Network::Testnet
+            ),
+            Err(Error::DuplicatedAddress(2, 1))
+        );
+        assert!(matches!(
+            parse_recipients(&format!("{ADDRESS_1},0"), Network::Testnet),
+            Err(Error::InvalidAmount { line: 1, .. })
+        ));
+        assert_eq!(
+            parse_recipients(ADDRESS_1, Network::Testnet),
+            Err(Error::InvalidRow(1))
+        );
+        assert_eq!(
+            parse_recipients("address,amount\n", Network::Testnet),
+            Err(Error::Empty)
+        );
+    }
+}
@@ -8,7 +8,7 @@ use smartvaults_core::bitcoin::Network;
 use smartvaults_core::util::dir;
 pub use smartvaults_core::util::dir::Error;
 
-fn network_path<P>(base_path: P, network: Network) -> Result<PathBuf, Error>
+pub(crate) fn network_path<P>(base_path: P, network: Network) -> Result<PathBuf, Error>
 where
     P: AsRef<Path>,
 {
@@ -43,6 +43,17 @@ pub fn big_number(num: u64) -> String {
     number
 }
 
+/// Format the data of an `OP_RETURN` output: hex, followed by the text if printable UTF-8
+pub fn data(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.is_empty() && !text.chars().any(char::is_control) => {
+            format!("{hex} (\"{text}\")")
+        }
+        _ => hex,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -66,4 +77,10 @@ mod test {
         assert_eq!(big_number(1_000_000), "1M".to_string());
         assert_eq!(big_number(1_000_000_000), "1Bn".to_string());
     }
+
+    #[test]
+    fn format_data() {
+        assert_eq!(data(b"inv"), "696e76 (\"inv\")".to_string());
+        assert_eq!(data(&[0x00, 0xff]), "00ff".to_string());
+    }
 }
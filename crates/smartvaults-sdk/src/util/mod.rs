@@ -2,8 +2,9 @@
 // Distributed under the MIT software license
 
 use nostr_sdk::{EventId, PublicKey};
-use smartvaults_core::bitcoin::Txid;
+use smartvaults_core::bitcoin::{Address, Amount, Denomination, Txid};
 
+pub mod csv;
 pub(crate) mod dir;
 pub mod format;
 
@@ -22,3 +23,35 @@ pub fn cut_public_key(pk: PublicKey) -> String {
 pub fn cut_txid(txid: Txid) -> String {
     txid.to_string()[..8].to_string()
 }
+
+/// Build a BIP21 URI (i.e. `bitcoin:<address>?amount=<btc>&label=<label>`)
+pub fn bip21_uri(address: &Address, amount: Option<u64>, label: Option<&str>) -> String {
+    let mut params: Vec<String> = Vec::new();
+    if let Some(amount) = amount {
+        let amount = Amount::from_sat(amount).to_string_in(Denomination::Bitcoin);
+        params.push(format!("amount={amount}"));
+    }
+    if let Some(label) = label.filter(|l| !l.is_empty()) {
+        params.push(format!("label={}", percent_encode(label)));
+    }
+
+    let mut uri: String = format!("bitcoin:{address}");
+    if !params.is_empty() {
+        uri.push('?');
+        uri.push_str(&params.join("&"));
+    }
+    uri
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}